@@ -0,0 +1,29 @@
+// Scene lighting for dark themes (see src/lighting.rs).
+//
+// Draws the darkness over the whole screen, fading it out around each light.
+// Each light is (x, y, radius, intensity) in world coordinates.
+
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+// Must match MAX_LIGHTS in src/lighting.rs
+const MAX_LIGHTS: u32 = 16u;
+
+struct Lighting {
+    darkness: vec4<f32>,
+    lights: array<vec4<f32>, 16>,
+    count: u32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> lighting: Lighting;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    var light = 0.0;
+    for (var i = 0u; i < min(lighting.count, MAX_LIGHTS); i++) {
+        let source = lighting.lights[i];
+        let offset = distance(in.world_position.xy, source.xy);
+        light += source.w * (1.0 - smoothstep(0.0, source.z, offset));
+    }
+    let alpha = lighting.darkness.a * (1.0 - clamp(light, 0.0, 1.0));
+    return vec4<f32>(lighting.darkness.rgb, alpha);
+}
@@ -0,0 +1,21 @@
+// Vignette darkening the corners of the screen (see src/vignette.rs).
+//
+// The quad covers the view, so the distance from the center is measured in
+// its UVs: 1.0 at the middle of each edge, about 1.41 in the corners.
+
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct Vignette {
+    color: vec4<f32>,
+    inner: f32,
+    outer: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> vignette: Vignette;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let offset = length((in.uv - vec2<f32>(0.5)) * 2.0);
+    let alpha = vignette.color.a * smoothstep(vignette.inner, vignette.outer, offset);
+    return vec4<f32>(vignette.color.rgb, alpha);
+}
@@ -0,0 +1,25 @@
+//! Build script embedding build information.
+//!
+//! Exposes the short git hash and the build profile to the crate as the
+//! `FLAPPY_GIT_HASH` and `FLAPPY_BUILD_PROFILE` environment variables (see
+//! `build_info`). Builds outside a git checkout report the hash as "unknown".
+
+use std::process::Command;
+
+fn main() {
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    let profile = std::env::var("PROFILE").unwrap_or_else(|_| "unknown".to_string());
+
+    println!("cargo:rustc-env=FLAPPY_GIT_HASH={git_hash}");
+    println!("cargo:rustc-env=FLAPPY_BUILD_PROFILE={profile}");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+}
@@ -0,0 +1,111 @@
+//! Achievement definitions.
+//!
+//! This module lists every achievement and the condition that unlocks it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::DeathCause;
+
+/// An unlockable achievement.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Achievement {
+    /// Pass the first pipe.
+    FirstPipe,
+    /// Reach a score of 10.
+    Score10,
+    /// Reach a score of 25.
+    Score25,
+    /// Reach a score of 50.
+    Score50,
+    /// Reach a score of 100.
+    Score100,
+    /// Fly into the ceiling.
+    Icarus,
+    /// Collect every letter of FLAPPY in one run.
+    Flappy,
+}
+
+impl Achievement {
+    /// Achievements unlocked by reaching a score, paired with the required score.
+    pub const SCORE_MILESTONES: [(u32, Achievement); 5] = [
+        (1, Achievement::FirstPipe),
+        (10, Achievement::Score10),
+        (25, Achievement::Score25),
+        (50, Achievement::Score50),
+        (100, Achievement::Score100),
+    ];
+
+    /// Returns the achievements unlocked by reaching `score`.
+    pub fn for_score(score: u32) -> impl Iterator<Item = Achievement> {
+        Self::SCORE_MILESTONES
+            .into_iter()
+            .filter(move |(required, _)| score >= *required)
+            .map(|(_, achievement)| achievement)
+    }
+
+    /// Returns the achievement unlocked by dying this way, if any.
+    pub fn for_death(cause: DeathCause) -> Option<Achievement> {
+        match cause {
+            DeathCause::Ceiling => Some(Achievement::Icarus),
+            DeathCause::Ground
+            | DeathCause::Pipe { .. }
+            | DeathCause::Obstacle { .. }
+            | DeathCause::GaveUp => None,
+        }
+    }
+
+    /// Stable identifier used by platform integrations (e.g. Steam).
+    #[cfg_attr(not(feature = "steam"), allow(dead_code))]
+    pub fn api_name(&self) -> &'static str {
+        match self {
+            Self::FirstPipe => "ACH_FIRST_PIPE",
+            Self::Score10 => "ACH_SCORE_10",
+            Self::Score25 => "ACH_SCORE_25",
+            Self::Score50 => "ACH_SCORE_50",
+            Self::Score100 => "ACH_SCORE_100",
+            Self::Icarus => "ACH_ICARUS",
+            Self::Flappy => "ACH_FLAPPY",
+        }
+    }
+
+    /// Human-readable title.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::FirstPipe => "First Flight",
+            Self::Score10 => "Getting the Hang of It",
+            Self::Score25 => "Pipe Dream",
+            Self::Score50 => "Half Century",
+            Self::Score100 => "Centurion",
+            Self::Icarus => "Icarus",
+            Self::Flappy => "Spelling Bee",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn for_score_includes_all_reached_milestones() {
+        let unlocked: Vec<_> = Achievement::for_score(25).collect();
+        assert_eq!(
+            unlocked,
+            vec![
+                Achievement::FirstPipe,
+                Achievement::Score10,
+                Achievement::Score25
+            ]
+        );
+        assert_eq!(Achievement::for_score(0).count(), 0);
+    }
+
+    #[test]
+    fn only_ceiling_deaths_unlock_icarus() {
+        assert_eq!(
+            Achievement::for_death(DeathCause::Ceiling),
+            Some(Achievement::Icarus)
+        );
+        assert_eq!(Achievement::for_death(DeathCause::Ground), None);
+    }
+}
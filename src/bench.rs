@@ -0,0 +1,152 @@
+//! Stress-test benchmark.
+//!
+//! `--bench` flies an invincible autopilot run at high speed with pipes
+//! spawning many times faster than usual and particle counts forced up,
+//! records every frame time, and prints percentiles when it exits. Comparing
+//! reports between builds catches performance regressions in the effects and
+//! pipe systems.
+
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::constants::{BENCH_DURATION, BENCH_PARTICLE_SCALE, BENCH_PIPE_SPAWN_DISTANCE};
+
+/// Resource present while benchmarking, collecting frame times.
+#[derive(Resource, Debug)]
+pub struct Bench {
+    /// Real seconds to run before reporting and exiting
+    pub duration: f32,
+    /// Real seconds measured so far, after the warm-up
+    pub elapsed: f32,
+    /// Real duration of every frame so far, in milliseconds
+    pub frame_times: Vec<f32>,
+    /// Pipe entities spawned so far
+    pub pipes_spawned: usize,
+    /// Most pipe entities alive at once
+    pub peak_pipes: usize,
+    /// Most particles alive at once
+    pub peak_particles: usize,
+}
+
+impl Default for Bench {
+    fn default() -> Self {
+        Self {
+            duration: BENCH_DURATION,
+            elapsed: 0.0,
+            frame_times: Vec::new(),
+            pipes_spawned: 0,
+            peak_pipes: 0,
+            peak_particles: 0,
+        }
+    }
+}
+
+impl Bench {
+    /// Overrides `config` to stress the game: pipes spawn far more often and
+    /// every flap and crash spawns the most particles, scaled up.
+    pub fn apply(config: &mut GameConfig) {
+        config.pipes.spawn_distance = BENCH_PIPE_SPAWN_DISTANCE;
+        let effects = &mut config.effects;
+        effects.flap_particle_count_max *= BENCH_PARTICLE_SCALE;
+        effects.flap_particle_count_min = effects.flap_particle_count_max;
+        effects.death_particle_count *= BENCH_PARTICLE_SCALE;
+    }
+
+    /// Summarizes the recorded frame times.
+    pub fn report(&self) -> FrameTimeReport {
+        FrameTimeReport::from_frame_times(&self.frame_times)
+    }
+}
+
+/// Frame-time percentiles of a benchmark, in milliseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameTimeReport {
+    /// Number of frames measured
+    pub frames: usize,
+    /// Mean frame time
+    pub mean: f32,
+    /// Median frame time
+    pub p50: f32,
+    /// 90th percentile frame time
+    pub p90: f32,
+    /// 99th percentile frame time
+    pub p99: f32,
+    /// Slowest frame
+    pub max: f32,
+}
+
+impl FrameTimeReport {
+    /// Computes the report from frame times in milliseconds.
+    pub fn from_frame_times(frame_times: &[f32]) -> Self {
+        if frame_times.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = frame_times.to_vec();
+        sorted.sort_by(f32::total_cmp);
+        // Nearest-rank percentile
+        let percentile = |p: f32| {
+            let rank = (p / 100.0 * sorted.len() as f32).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+        Self {
+            frames: sorted.len(),
+            mean: sorted.iter().sum::<f32>() / sorted.len() as f32,
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+impl fmt::Display for FrameTimeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} frames, mean {:.2} ms ({:.0} FPS), p50 {:.2} ms, p90 {:.2} ms, p99 {:.2} ms, max {:.2} ms",
+            self.frames,
+            self.mean,
+            1000.0 / self.mean.max(f32::EPSILON),
+            self.p50,
+            self.p90,
+            self.p99,
+            self.max
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_uses_nearest_rank_percentiles() {
+        let frame_times: Vec<f32> = (1..=100).rev().map(|ms| ms as f32).collect();
+        let report = FrameTimeReport::from_frame_times(&frame_times);
+        assert_eq!(report.frames, 100);
+        assert_eq!(report.p50, 50.0);
+        assert_eq!(report.p90, 90.0);
+        assert_eq!(report.p99, 99.0);
+        assert_eq!(report.max, 100.0);
+        assert_eq!(report.mean, 50.5);
+    }
+
+    #[test]
+    fn empty_report_is_zero() {
+        assert_eq!(
+            FrameTimeReport::from_frame_times(&[]),
+            FrameTimeReport::default()
+        );
+    }
+
+    #[test]
+    fn bench_config_spawns_more() {
+        let mut config = GameConfig::default();
+        Bench::apply(&mut config);
+        let defaults = GameConfig::default();
+        assert!(config.pipes.spawn_distance < defaults.pipes.spawn_distance);
+        assert!(config.effects.flap_particle_count_min > defaults.effects.flap_particle_count_max);
+    }
+}
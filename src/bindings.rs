@@ -0,0 +1,631 @@
+//! Rebindable game controls.
+//!
+//! Every game action has a keyboard key and a gamepad button, chosen on the
+//! controls screen and persisted in `bindings.ron` inside the save directory.
+//! Keys are stored by name, so the file stays readable and a key this
+//! version doesn't know falls back to the default binding. The menu's back
+//! inputs (Escape and the east gamepad button) cancel a rebind, so they can't
+//! be bound; neither can the function keys and the console key.
+//!
+//! Which devices flap the bird is part of the bindings too, so presets on the
+//! controls screen can set up a whole control scheme (keyboard only, mouse
+//! only, touch only, gamepad only, or the one-switch assist) in one step.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::save::{Versioned, load_versioned, store_versioned};
+use crate::settings::Settings;
+
+/// Name of the bindings file inside the save directory.
+const BINDINGS_FILE_NAME: &str = "bindings.ron";
+
+/// Keys that can be bound, by name.
+const BINDABLE_KEYS: [(KeyCode, &str); 60] = [
+    (KeyCode::KeyA, "KeyA"),
+    (KeyCode::KeyB, "KeyB"),
+    (KeyCode::KeyC, "KeyC"),
+    (KeyCode::KeyD, "KeyD"),
+    (KeyCode::KeyE, "KeyE"),
+    (KeyCode::KeyF, "KeyF"),
+    (KeyCode::KeyG, "KeyG"),
+    (KeyCode::KeyH, "KeyH"),
+    (KeyCode::KeyI, "KeyI"),
+    (KeyCode::KeyJ, "KeyJ"),
+    (KeyCode::KeyK, "KeyK"),
+    (KeyCode::KeyL, "KeyL"),
+    (KeyCode::KeyM, "KeyM"),
+    (KeyCode::KeyN, "KeyN"),
+    (KeyCode::KeyO, "KeyO"),
+    (KeyCode::KeyP, "KeyP"),
+    (KeyCode::KeyQ, "KeyQ"),
+    (KeyCode::KeyR, "KeyR"),
+    (KeyCode::KeyS, "KeyS"),
+    (KeyCode::KeyT, "KeyT"),
+    (KeyCode::KeyU, "KeyU"),
+    (KeyCode::KeyV, "KeyV"),
+    (KeyCode::KeyW, "KeyW"),
+    (KeyCode::KeyX, "KeyX"),
+    (KeyCode::KeyY, "KeyY"),
+    (KeyCode::KeyZ, "KeyZ"),
+    (KeyCode::Digit0, "Digit0"),
+    (KeyCode::Digit1, "Digit1"),
+    (KeyCode::Digit2, "Digit2"),
+    (KeyCode::Digit3, "Digit3"),
+    (KeyCode::Digit4, "Digit4"),
+    (KeyCode::Digit5, "Digit5"),
+    (KeyCode::Digit6, "Digit6"),
+    (KeyCode::Digit7, "Digit7"),
+    (KeyCode::Digit8, "Digit8"),
+    (KeyCode::Digit9, "Digit9"),
+    (KeyCode::Space, "Space"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::NumpadEnter, "NumpadEnter"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::ShiftLeft, "ShiftLeft"),
+    (KeyCode::ShiftRight, "ShiftRight"),
+    (KeyCode::ControlLeft, "ControlLeft"),
+    (KeyCode::ControlRight, "ControlRight"),
+    (KeyCode::AltLeft, "AltLeft"),
+    (KeyCode::AltRight, "AltRight"),
+    (KeyCode::ArrowUp, "ArrowUp"),
+    (KeyCode::ArrowDown, "ArrowDown"),
+    (KeyCode::ArrowLeft, "ArrowLeft"),
+    (KeyCode::ArrowRight, "ArrowRight"),
+    (KeyCode::Comma, "Comma"),
+    (KeyCode::Period, "Period"),
+    (KeyCode::Slash, "Slash"),
+    (KeyCode::Semicolon, "Semicolon"),
+    (KeyCode::Quote, "Quote"),
+    (KeyCode::BracketLeft, "BracketLeft"),
+    (KeyCode::BracketRight, "BracketRight"),
+    (KeyCode::Minus, "Minus"),
+    (KeyCode::Equal, "Equal"),
+];
+
+/// Gamepad buttons that can be bound, by name.
+const BINDABLE_BUTTONS: [(GamepadButton, &str); 11] = [
+    (GamepadButton::South, "South"),
+    (GamepadButton::North, "North"),
+    (GamepadButton::West, "West"),
+    (GamepadButton::LeftTrigger, "LeftTrigger"),
+    (GamepadButton::LeftTrigger2, "LeftTrigger2"),
+    (GamepadButton::RightTrigger, "RightTrigger"),
+    (GamepadButton::RightTrigger2, "RightTrigger2"),
+    (GamepadButton::Select, "Select"),
+    (GamepadButton::Start, "Start"),
+    (GamepadButton::LeftThumb, "LeftThumb"),
+    (GamepadButton::RightThumb, "RightThumb"),
+];
+
+/// A rebindable game action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameAction {
+    /// Flap, and start or restart a run
+    Flap,
+    /// Rewind on the game over screen
+    Rewind,
+    /// Accept the revive offer
+    Revive,
+    /// Open the settings from the menu
+    OpenSettings,
+}
+
+impl GameAction {
+    /// All actions, in display order.
+    pub const ALL: [GameAction; 4] = [
+        GameAction::Flap,
+        GameAction::Rewind,
+        GameAction::Revive,
+        GameAction::OpenSettings,
+    ];
+
+    /// Returns the action's display name.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Flap => "Flap",
+            Self::Rewind => "Rewind",
+            Self::Revive => "Revive",
+            Self::OpenSettings => "Settings",
+        }
+    }
+}
+
+/// A kind of input device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputDevice {
+    /// The keyboard
+    Keyboard,
+    /// Any gamepad
+    Gamepad,
+}
+
+/// A bindable keyboard key, stored by name.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct BoundKey(pub KeyCode);
+
+impl BoundKey {
+    /// Returns the key if it can be bound.
+    pub fn new(key: KeyCode) -> Option<Self> {
+        BINDABLE_KEYS
+            .iter()
+            .any(|(bindable, _)| *bindable == key)
+            .then_some(Self(key))
+    }
+
+    /// Returns the text shown for this key, e.g. "R" or "Space".
+    pub fn label(&self) -> String {
+        let name = String::from(*self);
+        name.strip_prefix("Key")
+            .or_else(|| name.strip_prefix("Digit"))
+            .unwrap_or(&name)
+            .to_string()
+    }
+}
+
+impl TryFrom<String> for BoundKey {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, String> {
+        BINDABLE_KEYS
+            .iter()
+            .find(|(_, other)| *other == name)
+            .map(|(key, _)| Self(*key))
+            .ok_or_else(|| format!("unknown key {name}"))
+    }
+}
+
+impl From<BoundKey> for String {
+    fn from(key: BoundKey) -> Self {
+        BINDABLE_KEYS
+            .iter()
+            .find(|(other, _)| *other == key.0)
+            .map_or("Unknown", |(_, name)| name)
+            .to_string()
+    }
+}
+
+/// A bindable gamepad button, stored by name.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct BoundButton(pub GamepadButton);
+
+impl BoundButton {
+    /// Returns the button if it can be bound.
+    pub fn new(button: GamepadButton) -> Option<Self> {
+        BINDABLE_BUTTONS
+            .iter()
+            .any(|(bindable, _)| *bindable == button)
+            .then_some(Self(button))
+    }
+
+    /// Returns the text shown for this button.
+    pub fn label(&self) -> String {
+        String::from(*self)
+    }
+}
+
+impl TryFrom<String> for BoundButton {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, String> {
+        BINDABLE_BUTTONS
+            .iter()
+            .find(|(_, other)| *other == name)
+            .map(|(button, _)| Self(*button))
+            .ok_or_else(|| format!("unknown gamepad button {name}"))
+    }
+}
+
+impl From<BoundButton> for String {
+    fn from(button: BoundButton) -> Self {
+        BINDABLE_BUTTONS
+            .iter()
+            .find(|(other, _)| *other == button.0)
+            .map_or("Unknown", |(_, name)| name)
+            .to_string()
+    }
+}
+
+/// The key and gamepad button of an action.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Binding {
+    /// Keyboard key
+    pub key: BoundKey,
+    /// Gamepad button
+    pub button: BoundButton,
+}
+
+impl Binding {
+    /// Returns a binding of a key and a gamepad button.
+    const fn new(key: KeyCode, button: GamepadButton) -> Self {
+        Self {
+            key: BoundKey(key),
+            button: BoundButton(button),
+        }
+    }
+}
+
+/// An input captured on the controls screen, to bind to an action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundInput {
+    /// A keyboard key
+    Key(BoundKey),
+    /// A gamepad button
+    Button(BoundButton),
+}
+
+impl BoundInput {
+    /// Returns the text shown for this input.
+    pub fn label(&self) -> String {
+        match self {
+            Self::Key(key) => key.label(),
+            Self::Button(button) => button.label(),
+        }
+    }
+}
+
+/// Resource holding the player's control bindings.
+///
+/// Missing or unreadable entries fall back to their defaults so older files
+/// keep loading.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct InputBindings {
+    /// Flap, and start or restart a run
+    pub flap: Binding,
+    /// Rewind on the game over screen
+    pub rewind: Binding,
+    /// Accept the revive offer
+    pub revive: Binding,
+    /// Open the settings from the menu
+    pub open_settings: Binding,
+    /// Devices that flap the bird
+    pub flap_sources: FlapSources,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            flap: Binding::new(KeyCode::Space, GamepadButton::South),
+            rewind: Binding::new(KeyCode::KeyR, GamepadButton::West),
+            revive: Binding::new(KeyCode::Enter, GamepadButton::North),
+            open_settings: Binding::new(KeyCode::KeyS, GamepadButton::Select),
+            flap_sources: FlapSources::default(),
+        }
+    }
+}
+
+/// Devices that flap the bird (and start runs with the flap binding).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct FlapSources {
+    /// The flap key
+    pub keyboard: bool,
+    /// The flap gamepad button
+    pub gamepad: bool,
+    /// The left mouse button
+    pub mouse: bool,
+    /// Touching the screen anywhere
+    pub touch: bool,
+}
+
+impl Default for FlapSources {
+    fn default() -> Self {
+        Self {
+            keyboard: true,
+            gamepad: true,
+            mouse: true,
+            touch: true,
+        }
+    }
+}
+
+impl FlapSources {
+    /// Returns sources with only the chosen devices enabled.
+    const fn only(keyboard: bool, gamepad: bool, mouse: bool, touch: bool) -> Self {
+        Self {
+            keyboard,
+            gamepad,
+            mouse,
+            touch,
+        }
+    }
+}
+
+/// A control scheme set up in one step from the controls screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlPreset {
+    /// Flap with Space only
+    Classic,
+    /// Flap with the left mouse button only
+    Mouse,
+    /// Flap by tapping anywhere on the screen
+    Touch,
+    /// Flap with the gamepad's south (A) button only
+    Gamepad,
+    /// The bird flaps on its own and any input skips a flap
+    OneSwitch,
+}
+
+impl ControlPreset {
+    /// All presets, in display order.
+    pub const ALL: [ControlPreset; 5] = [
+        ControlPreset::Classic,
+        ControlPreset::Mouse,
+        ControlPreset::Touch,
+        ControlPreset::Gamepad,
+        ControlPreset::OneSwitch,
+    ];
+
+    /// Returns the preset's display name.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Classic => "Classic (Space)",
+            Self::Mouse => "Mouse only",
+            Self::Touch => "Tap anywhere",
+            Self::Gamepad => "Gamepad (A)",
+            Self::OneSwitch => "One-switch assist",
+        }
+    }
+
+    /// Returns the devices that flap with this preset.
+    pub fn flap_sources(&self) -> FlapSources {
+        match self {
+            Self::Classic => FlapSources::only(true, false, false, false),
+            Self::Mouse => FlapSources::only(false, false, true, false),
+            Self::Touch => FlapSources::only(false, false, false, true),
+            Self::Gamepad => FlapSources::only(false, true, false, false),
+            Self::OneSwitch => FlapSources::default(),
+        }
+    }
+
+    /// Returns the preset the current controls match, if any.
+    pub fn matching(bindings: &InputBindings, settings: &Settings) -> Option<ControlPreset> {
+        let default_flap = InputBindings::default().flap;
+        Self::ALL.into_iter().find(|preset| {
+            let sources = preset.flap_sources();
+            bindings.flap_sources == sources
+                && settings.one_switch == (*preset == Self::OneSwitch)
+                && (!sources.keyboard || bindings.flap.key == default_flap.key)
+                && (!sources.gamepad || bindings.flap.button == default_flap.button)
+        })
+    }
+
+    /// Returns the preset after the current one, starting over after the last.
+    pub fn next(current: Option<ControlPreset>) -> ControlPreset {
+        let index = current
+            .and_then(|current| Self::ALL.iter().position(|preset| *preset == current))
+            .map_or(0, |index| (index + 1) % Self::ALL.len());
+        Self::ALL[index]
+    }
+
+    /// Sets up the controls for this preset: its flap devices, the one-switch
+    /// setting, and the default flap key and button.
+    ///
+    /// If another action holds a default flap input, returns that action and
+    /// keeps the current flap binding for it.
+    pub fn apply(
+        &self,
+        bindings: &mut InputBindings,
+        settings: &mut Settings,
+    ) -> Result<(), GameAction> {
+        bindings.flap_sources = self.flap_sources();
+        settings.one_switch = *self == Self::OneSwitch;
+
+        let default_flap = InputBindings::default().flap;
+        let key = bindings.rebind(GameAction::Flap, BoundInput::Key(default_flap.key));
+        let button = bindings.rebind(GameAction::Flap, BoundInput::Button(default_flap.button));
+        key.and(button)
+    }
+}
+
+impl Versioned for InputBindings {
+    const VERSION: u32 = 1;
+}
+
+impl InputBindings {
+    /// Loads the bindings file, falling back to defaults if it is missing or invalid.
+    pub fn load() -> Self {
+        load_versioned(BINDINGS_FILE_NAME)
+    }
+
+    /// Writes the bindings file, logging (not panicking) on failure.
+    pub fn store(&self) {
+        store_versioned(BINDINGS_FILE_NAME, self);
+    }
+
+    /// Returns the binding of an action.
+    pub fn get(&self, action: GameAction) -> &Binding {
+        match action {
+            GameAction::Flap => &self.flap,
+            GameAction::Rewind => &self.rewind,
+            GameAction::Revive => &self.revive,
+            GameAction::OpenSettings => &self.open_settings,
+        }
+    }
+
+    /// Returns the binding of an action, for changing it.
+    fn get_mut(&mut self, action: GameAction) -> &mut Binding {
+        match action {
+            GameAction::Flap => &mut self.flap,
+            GameAction::Rewind => &mut self.rewind,
+            GameAction::Revive => &mut self.revive,
+            GameAction::OpenSettings => &mut self.open_settings,
+        }
+    }
+
+    /// Returns the action bound to `input`, if any.
+    pub fn action_for(&self, input: BoundInput) -> Option<GameAction> {
+        GameAction::ALL.into_iter().find(|action| {
+            let binding = self.get(*action);
+            match input {
+                BoundInput::Key(key) => binding.key == key,
+                BoundInput::Button(button) => binding.button == button,
+            }
+        })
+    }
+
+    /// Binds `input` to `action`, unless another action already uses it.
+    ///
+    /// On a conflict, returns the action holding the input and changes
+    /// nothing.
+    pub fn rebind(&mut self, action: GameAction, input: BoundInput) -> Result<(), GameAction> {
+        if let Some(other) = self.action_for(input)
+            && other != action
+        {
+            return Err(other);
+        }
+        let binding = self.get_mut(action);
+        match input {
+            BoundInput::Key(key) => binding.key = key,
+            BoundInput::Button(button) => binding.button = button,
+        }
+        Ok(())
+    }
+}
+
+/// A row of the controls screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlsRow {
+    /// Switches to the next control preset
+    Preset,
+    /// The key or gamepad button of an action
+    Binding(GameAction, InputDevice),
+    /// Restores every default binding
+    Reset,
+    /// Returns to the settings screen
+    Back,
+}
+
+impl ControlsRow {
+    /// All rows, in display order.
+    pub const ALL: [ControlsRow; 11] = [
+        ControlsRow::Preset,
+        ControlsRow::Binding(GameAction::Flap, InputDevice::Keyboard),
+        ControlsRow::Binding(GameAction::Flap, InputDevice::Gamepad),
+        ControlsRow::Binding(GameAction::Rewind, InputDevice::Keyboard),
+        ControlsRow::Binding(GameAction::Rewind, InputDevice::Gamepad),
+        ControlsRow::Binding(GameAction::Revive, InputDevice::Keyboard),
+        ControlsRow::Binding(GameAction::Revive, InputDevice::Gamepad),
+        ControlsRow::Binding(GameAction::OpenSettings, InputDevice::Keyboard),
+        ControlsRow::Binding(GameAction::OpenSettings, InputDevice::Gamepad),
+        ControlsRow::Reset,
+        ControlsRow::Back,
+    ];
+
+    /// Returns the text shown for this row; `listening` marks the row
+    /// waiting for an input.
+    pub fn label(&self, bindings: &InputBindings, settings: &Settings, listening: bool) -> String {
+        match self {
+            Self::Preset => {
+                let preset = ControlPreset::matching(bindings, settings);
+                format!(
+                    "Preset: < {} >",
+                    preset.map_or("Custom", |preset| preset.label())
+                )
+            }
+            Self::Binding(action, device) => {
+                let (device_name, current) = match device {
+                    InputDevice::Keyboard => ("key", bindings.get(*action).key.label()),
+                    InputDevice::Gamepad => ("gamepad", bindings.get(*action).button.label()),
+                };
+                if listening {
+                    format!("{} ({device_name}): press a new one...", action.label())
+                } else {
+                    format!("{} ({device_name}): {current}", action.label())
+                }
+            }
+            Self::Reset => "Reset to defaults".to_string(),
+            Self::Back => "Back".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_round_trip_by_name() {
+        let bindings = InputBindings {
+            flap: Binding::new(KeyCode::KeyW, GamepadButton::RightTrigger),
+            ..default()
+        };
+        let contents = ron::to_string(&bindings).expect("serializable");
+        assert!(contents.contains("\"KeyW\""));
+        assert_eq!(ron::from_str::<InputBindings>(&contents), Ok(bindings));
+    }
+
+    #[test]
+    fn missing_bindings_use_defaults() {
+        assert_eq!(
+            ron::from_str::<InputBindings>("()").ok(),
+            Some(InputBindings::default())
+        );
+    }
+
+    #[test]
+    fn rebinding_refuses_conflicts() {
+        let mut bindings = InputBindings::default();
+        let r = BoundInput::Key(BoundKey(KeyCode::KeyR));
+        assert_eq!(
+            bindings.rebind(GameAction::Flap, r),
+            Err(GameAction::Rewind)
+        );
+        assert_eq!(bindings, InputBindings::default());
+
+        let w = BoundInput::Key(BoundKey(KeyCode::KeyW));
+        assert_eq!(bindings.rebind(GameAction::Flap, w), Ok(()));
+        assert_eq!(bindings.action_for(w), Some(GameAction::Flap));
+        // Rebinding an action to its own input is not a conflict
+        assert_eq!(bindings.rebind(GameAction::Flap, w), Ok(()));
+    }
+
+    #[test]
+    fn presets_set_up_the_flap_inputs() {
+        let mut bindings = InputBindings::default();
+        let mut settings = Settings::default();
+        assert_eq!(ControlPreset::matching(&bindings, &settings), None);
+
+        for preset in ControlPreset::ALL {
+            assert_eq!(preset.apply(&mut bindings, &mut settings), Ok(()));
+            assert_eq!(ControlPreset::matching(&bindings, &settings), Some(preset));
+        }
+        assert!(settings.one_switch);
+        assert_eq!(
+            ControlPreset::next(Some(ControlPreset::OneSwitch)),
+            ControlPreset::Classic
+        );
+        assert_eq!(ControlPreset::next(None), ControlPreset::Classic);
+    }
+
+    #[test]
+    fn presets_keep_flap_inputs_held_by_other_actions() {
+        let mut bindings = InputBindings::default();
+        let w = BoundInput::Key(BoundKey(KeyCode::KeyW));
+        let space = BoundInput::Key(BoundKey(KeyCode::Space));
+        assert_eq!(bindings.rebind(GameAction::Flap, w), Ok(()));
+        assert_eq!(bindings.rebind(GameAction::Rewind, space), Ok(()));
+
+        let mut settings = Settings::default();
+        assert_eq!(
+            ControlPreset::Classic.apply(&mut bindings, &mut settings),
+            Err(GameAction::Rewind)
+        );
+        assert_eq!(bindings.action_for(w), Some(GameAction::Flap));
+        assert_eq!(ControlPreset::matching(&bindings, &settings), None);
+    }
+
+    #[test]
+    fn menu_back_inputs_cannot_be_bound() {
+        assert_eq!(BoundKey::new(KeyCode::Escape), None);
+        assert_eq!(BoundKey::new(KeyCode::Backquote), None);
+        assert_eq!(BoundButton::new(GamepadButton::East), None);
+        assert!(BoundKey::new(KeyCode::KeyQ).is_some());
+        assert_eq!(BoundKey(KeyCode::KeyQ).label(), "Q");
+        assert_eq!(BoundKey(KeyCode::Space).label(), "Space");
+    }
+}
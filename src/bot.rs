@@ -0,0 +1,42 @@
+//! Autopilot that flies the bird through gaps.
+//!
+//! The bot flaps whenever the bird sinks too far below the height it aims
+//! for, which keeps it bouncing around the center of the next gap.
+
+use crate::config::PhysicsConfig;
+
+/// Returns true if a bird at `y` moving at `velocity` should flap to stay
+/// around `target_y`.
+///
+/// A flap lifts the bird by `rise` before it falls again, so flapping half
+/// that distance below the target centers the bounce on it.
+pub fn should_flap(y: f32, velocity: f32, target_y: f32, physics: &PhysicsConfig) -> bool {
+    let rise = physics.flap_strength.powi(2) / (2.0 * -physics.gravity).max(f32::EPSILON);
+    velocity <= 0.0 && y < target_y - rise / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bot_stays_around_target() {
+        let physics = PhysicsConfig::default();
+        let (mut y, mut velocity) = (100.0, 0.0);
+        let target_y = -40.0;
+        let delta = 1.0 / 60.0;
+
+        for frame in 0..600 {
+            if should_flap(y, velocity, target_y, &physics) {
+                velocity = physics.flap_strength;
+            }
+            velocity += physics.gravity * delta;
+            y += velocity * delta;
+
+            // Give the bird a second to reach the target first
+            if frame > 60 {
+                assert!((y - target_y).abs() < 60.0, "bird strayed to {y}");
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Build information embedded at compile time.
+//!
+//! The git hash and build profile come from the build script; the version is
+//! the crate's own. They are shown in a corner of the menu and in the debug
+//! overlay, so bug reports and screenshots say which build they came from.
+
+/// Crate version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Short hash of the commit the game was built from, or "unknown".
+pub const GIT_HASH: &str = env!("FLAPPY_GIT_HASH");
+
+/// Cargo profile the game was built with (release builds, including the iOS
+/// profile, report "release").
+pub const BUILD_PROFILE: &str = env!("FLAPPY_BUILD_PROFILE");
+
+/// Returns a one-line description of the build, e.g. "v0.1.0 (1a2b3c4, release)".
+pub fn build_label() -> String {
+    format!("v{VERSION} ({GIT_HASH}, {BUILD_PROFILE})")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_label_names_version_hash_and_profile() {
+        let label = build_label();
+        assert!(label.starts_with(&format!("v{VERSION} (")));
+        assert!(label.contains(GIT_HASH));
+        assert!(label.ends_with(&format!("{BUILD_PROFILE})")));
+    }
+}
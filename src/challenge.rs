@@ -0,0 +1,408 @@
+//! Daily and weekly challenges.
+//!
+//! Everyone playing the daily (or weekly) challenge flies through the same
+//! pipes: the run's seed is derived from the current UTC day (or week,
+//! starting on Monday). By default the system clock is used. Like the other
+//! online features, syncing is opt-in: so that players with a skewed system
+//! clock still agree on the day, `FLAPPY_TIME_SERVER` can name a server the
+//! time is fetched from once at startup, falling back to the system clock
+//! when it can't be reached.
+//!
+//! `FLAPPY_TIME_SERVER` picks the source: an NTP `host[:port]` (`on` for
+//! `pool.ntp.org`), an `http://` URL whose `Date` header is used instead, or
+//! `off` (the same as leaving it unset) to only use the system clock.
+
+use std::io;
+use std::net::UdpSocket;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+
+use crate::net;
+use crate::rng::{RngStream, SEED_CODE_BITS};
+
+/// Environment variable that overrides the time server.
+pub const TIME_SERVER_ENV_VAR: &str = "FLAPPY_TIME_SERVER";
+
+/// NTP server used for `FLAPPY_TIME_SERVER=on`.
+const DEFAULT_TIME_SERVER: &str = "pool.ntp.org";
+
+/// Port NTP servers listen on.
+const NTP_PORT: u16 = 123;
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970).
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// Timeout for the NTP reply.
+const NTP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Seconds in a day.
+const DAY_SECONDS: u64 = 24 * 60 * 60;
+
+/// The Unix epoch was a Thursday: weeks start three days later, on Monday.
+const WEEK_START_OFFSET: u64 = 3 * DAY_SECONDS;
+
+/// A challenge, with a new seed every period.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengeKind {
+    /// New pipes every UTC day
+    Daily,
+    /// New pipes every UTC week, starting on Monday
+    Weekly,
+}
+
+impl ChallengeKind {
+    /// Every challenge, in menu order.
+    pub const ALL: [ChallengeKind; 2] = [ChallengeKind::Daily, ChallengeKind::Weekly];
+
+    /// Returns the name shown in the menu.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Daily => "Daily challenge",
+            Self::Weekly => "Weekly challenge",
+        }
+    }
+
+    /// Returns the length of a period, in seconds.
+    fn period(self) -> u64 {
+        match self {
+            Self::Daily => DAY_SECONDS,
+            Self::Weekly => 7 * DAY_SECONDS,
+        }
+    }
+
+    /// Returns how far into a period the Unix epoch falls, in seconds.
+    fn epoch_offset(self) -> u64 {
+        match self {
+            Self::Daily => 0,
+            Self::Weekly => WEEK_START_OFFSET,
+        }
+    }
+
+    /// Returns the number of the period containing `now` (Unix seconds).
+    pub fn period_index(self, now: u64) -> u64 {
+        (now + self.epoch_offset()) / self.period()
+    }
+
+    /// Returns the seed of the challenge at `now` (Unix seconds).
+    ///
+    /// Seeds are as short as those of regular runs, so challenge runs share
+    /// their codes the same way.
+    pub fn seed(self, now: u64) -> u64 {
+        let stream_seed = self.period_index(now) ^ ((self as u64 + 1) << 56);
+        RngStream::from_seed(stream_seed).next_u64() >> (64 - SEED_CODE_BITS)
+    }
+
+    /// Returns the seconds left before the challenge at `now` rolls over.
+    pub fn rolls_over_in(self, now: u64) -> u64 {
+        self.period() - (now + self.epoch_offset()) % self.period()
+    }
+}
+
+/// Where the challenge clock got its time from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeSource {
+    /// The system clock, unchecked
+    System,
+    /// An NTP server
+    Ntp,
+    /// The `Date` header of an HTTP server
+    Http,
+}
+
+/// A configured time server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TimeServer {
+    /// An NTP server, as `host:port`
+    Ntp(String),
+    /// An HTTP server's URL
+    Http(String),
+}
+
+impl TimeServer {
+    /// Reads the server from the environment variable's value, or `None`
+    /// when syncing is off (as it is unless the player opts in).
+    fn from_setting(value: Option<&str>) -> Option<Self> {
+        match value.map(str::trim) {
+            None | Some("") => None,
+            Some(value) if value.eq_ignore_ascii_case("off") => None,
+            Some(value) if value.eq_ignore_ascii_case("on") => {
+                Some(Self::Ntp(format!("{DEFAULT_TIME_SERVER}:{NTP_PORT}")))
+            }
+            Some(value) if value.starts_with("http://") => Some(Self::Http(value.to_string())),
+            Some(value) if value.contains(':') => Some(Self::Ntp(value.to_string())),
+            Some(value) => Some(Self::Ntp(format!("{value}:{NTP_PORT}"))),
+        }
+    }
+
+    /// Returns the source this server provides.
+    fn source(&self) -> TimeSource {
+        match self {
+            Self::Ntp(_) => TimeSource::Ntp,
+            Self::Http(_) => TimeSource::Http,
+        }
+    }
+
+    /// Asks the server for the time, in Unix seconds.
+    fn fetch(&self) -> io::Result<f64> {
+        match self {
+            Self::Ntp(address) => {
+                let socket = UdpSocket::bind("0.0.0.0:0")?;
+                socket.set_read_timeout(Some(NTP_TIMEOUT))?;
+                socket.connect(address)?;
+
+                // Leap indicator 0, version 3, client mode
+                let mut packet = [0u8; 48];
+                packet[0] = 0x1b;
+                socket.send(&packet)?;
+                let length = socket.recv(&mut packet)?;
+                parse_ntp_reply(&packet[..length])
+            }
+            Self::Http(url) => {
+                let response = net::request("HEAD", url, &[], &[])?;
+                response
+                    .header("date")
+                    .and_then(parse_http_date)
+                    .map(|seconds| seconds as f64)
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, "no Date header in the response")
+                    })
+            }
+        }
+    }
+
+    /// Measures how far ahead of the system clock the server is, in seconds.
+    fn measure_offset(&self) -> io::Result<f64> {
+        let sent = system_time();
+        let time = self.fetch()?;
+        let received = system_time();
+        // The server answered halfway through the round trip
+        Ok(time - (sent + received) / 2.0)
+    }
+}
+
+/// Resource holding the UTC time used for challenges.
+///
+/// The server is queried on a background thread, so a slow or unreachable
+/// server never stalls a frame; until it answers, the system clock is used.
+#[derive(Resource)]
+pub struct ChallengeClock {
+    /// Seconds to add to the system clock
+    offset: f64,
+    /// Where the time comes from
+    source: TimeSource,
+    /// Pending reply from the time server
+    reply: Option<Mutex<Receiver<(TimeSource, f64)>>>,
+}
+
+impl ChallengeClock {
+    /// Creates the clock and starts syncing with the configured server, if
+    /// any.
+    pub fn from_env() -> Self {
+        let setting = std::env::var(TIME_SERVER_ENV_VAR).ok();
+        let Some(server) = TimeServer::from_setting(setting.as_deref()) else {
+            return Self::system();
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || match server.measure_offset() {
+            Ok(offset) => {
+                let _ = sender.send((server.source(), offset));
+            }
+            Err(error) => {
+                warn!("Couldn't get the time from {server:?}, using the system clock: {error}")
+            }
+        });
+
+        Self {
+            reply: Some(Mutex::new(receiver)),
+            ..Self::system()
+        }
+    }
+
+    /// Creates a clock that only uses the system clock.
+    pub fn system() -> Self {
+        Self {
+            offset: 0.0,
+            source: TimeSource::System,
+            reply: None,
+        }
+    }
+
+    /// Applies the time server's reply once it arrives, returning whether it did.
+    pub fn poll(&mut self) -> bool {
+        let reply = self
+            .reply
+            .as_ref()
+            .and_then(|reply| reply.lock().ok()?.try_recv().ok());
+        let Some((source, offset)) = reply else {
+            return false;
+        };
+        self.source = source;
+        self.offset = offset;
+        self.reply = None;
+        true
+    }
+
+    /// Returns where the time comes from.
+    pub fn source(&self) -> TimeSource {
+        self.source
+    }
+
+    /// Returns how far ahead of the system clock the time is, in seconds.
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+
+    /// Returns the current UTC time, in Unix seconds.
+    pub fn now(&self) -> u64 {
+        (system_time() + self.offset).max(0.0) as u64
+    }
+}
+
+/// Returns the system clock's time, in Unix seconds.
+fn system_time() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// Reads the transmit time of an NTP reply, in Unix seconds.
+fn parse_ntp_reply(reply: &[u8]) -> io::Result<f64> {
+    let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let timestamp: [u8; 8] = reply
+        .get(40..48)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid("NTP reply too short"))?;
+    let seconds = u64::from(u32::from_be_bytes([
+        timestamp[0],
+        timestamp[1],
+        timestamp[2],
+        timestamp[3],
+    ]));
+    let fraction = u32::from_be_bytes([timestamp[4], timestamp[5], timestamp[6], timestamp[7]]);
+    // Servers turning the request down leave the time empty
+    if seconds < NTP_UNIX_OFFSET {
+        return Err(invalid("NTP reply without a time"));
+    }
+
+    Ok((seconds - NTP_UNIX_OFFSET) as f64 + f64::from(fraction) / f64::from(u32::MAX))
+}
+
+/// Parses an HTTP date (`Sun, 06 Nov 1994 08:49:37 GMT`) into Unix seconds.
+fn parse_http_date(date: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let mut words = date.split_whitespace().skip(1);
+    let day: u64 = words.next()?.parse().ok()?;
+    let month = words.next()?;
+    let month = MONTHS.iter().position(|name| *name == month)? as u64 + 1;
+    let year: u64 = words.next()?.parse().ok()?;
+    let mut time = words
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+    if year < 1970 || !(1..=31).contains(&day) || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    // Days since the epoch of a March-based year (Hinnant's algorithm)
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    Some(days * DAY_SECONDS + hours * 3600 + minutes * 60 + seconds)
+}
+
+/// Formats the time left before a challenge rolls over (`5:07:42`, or
+/// `3d 05h` past a day).
+pub fn format_countdown(seconds: u64) -> String {
+    let (days, hours) = (seconds / DAY_SECONDS, seconds % DAY_SECONDS / 3600);
+    if days > 0 {
+        format!("{days}d {hours:02}h")
+    } else {
+        format!("{hours}:{:02}:{:02}", seconds % 3600 / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Monday, 2 March 2026, 00:00 UTC.
+    const MONDAY: u64 = 1_772_409_600;
+
+    #[test]
+    fn challenges_share_a_seed_until_they_roll_over() {
+        let daily = ChallengeKind::Daily;
+        assert_eq!(daily.seed(MONDAY), daily.seed(MONDAY + DAY_SECONDS - 1));
+        assert_ne!(daily.seed(MONDAY), daily.seed(MONDAY + DAY_SECONDS));
+        assert_eq!(daily.rolls_over_in(MONDAY + 3600), DAY_SECONDS - 3600);
+
+        let weekly = ChallengeKind::Weekly;
+        assert_ne!(weekly.seed(MONDAY - 1), weekly.seed(MONDAY));
+        assert_eq!(
+            weekly.seed(MONDAY),
+            weekly.seed(MONDAY + 7 * DAY_SECONDS - 1)
+        );
+        assert_eq!(weekly.rolls_over_in(MONDAY), 7 * DAY_SECONDS);
+        assert_ne!(weekly.seed(MONDAY), daily.seed(MONDAY));
+        assert!(weekly.seed(MONDAY) < 1 << SEED_CODE_BITS);
+    }
+
+    #[test]
+    fn time_server_setting_picks_the_protocol() {
+        assert_eq!(TimeServer::from_setting(None), None);
+        assert_eq!(
+            TimeServer::from_setting(Some("on")),
+            Some(TimeServer::Ntp("pool.ntp.org:123".to_string()))
+        );
+        assert_eq!(
+            TimeServer::from_setting(Some("time.local")),
+            Some(TimeServer::Ntp("time.local:123".to_string()))
+        );
+        assert_eq!(
+            TimeServer::from_setting(Some("http://time.local/")),
+            Some(TimeServer::Http("http://time.local/".to_string()))
+        );
+        assert_eq!(TimeServer::from_setting(Some("off")), None);
+    }
+
+    #[test]
+    fn ntp_replies_and_http_dates_read_as_unix_time() {
+        let mut reply = [0u8; 48];
+        reply[40..44].copy_from_slice(&((MONDAY + NTP_UNIX_OFFSET) as u32).to_be_bytes());
+        reply[44..48].copy_from_slice(&(u32::MAX / 2).to_be_bytes());
+        let time = parse_ntp_reply(&reply).ok();
+        assert!(time.is_some_and(|time| (time - (MONDAY as f64 + 0.5)).abs() < 1e-3));
+        assert!(parse_ntp_reply(&[0u8; 48]).is_err());
+        assert!(parse_ntp_reply(&reply[..40]).is_err());
+
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784_111_777)
+        );
+        assert_eq!(
+            parse_http_date("Mon, 02 Mar 2026 00:00:00 GMT"),
+            Some(MONDAY)
+        );
+        assert_eq!(parse_http_date("yesterday"), None);
+    }
+
+    #[test]
+    fn countdowns_show_days_past_a_day() {
+        assert_eq!(format_countdown(5 * 3600 + 7 * 60 + 42), "5:07:42");
+        assert_eq!(format_countdown(3 * DAY_SECONDS + 5 * 3600 + 59), "3d 05h");
+    }
+}
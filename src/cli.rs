@@ -0,0 +1,236 @@
+//! Command-line arguments.
+//!
+//! `CliArgs` is parsed from the command line in `main` and turned into
+//! startup resources by `run_with_args`, so automation, tests, and power
+//! users can configure a session without editing files. Every flag is
+//! optional; `CliArgs::default()` is a normal run.
+
+use std::path::PathBuf;
+
+use crate::config::Difficulty;
+use crate::mutators::{Mutator, Mutators};
+
+/// Help text printed for `--help` and after invalid arguments.
+pub const USAGE: &str = "\
+Usage: flappy-rust [OPTIONS]
+
+Options:
+  --seed <N>              Master random seed, for reproducible runs
+  --headless              Run without a window (for automation)
+  --windowed <WxH>        Open a window of the given size, e.g. 480x720
+  --difficulty <LEVEL>    easy, normal or hard
+  --mutator <NAME>        Turn a mutator on (repeatable): big-bird,
+                          double-speed, invisible-bird or inverted-controls
+  --mute                  Silence all audio
+  --replay <FILE>         Race a replay file (.replay.ron) as a ghost
+  --record <FILE>         Write each finished run to FILE (e.g. run.flap), to
+                          attach to bug reports
+  --play <FILE>           Play a recorded run back, with the seed and inputs
+                          from the file
+  --bench                 Run the stress-test benchmark and print frame times
+  --verify                Re-simulate the --replay file headlessly and check
+                          its score (exits with 1 if it doesn't hold up)
+  --log <FILTER>          Log verbosity, e.g. debug or flappy_rust=trace
+                          (RUST_LOG takes precedence)
+  -h, --help              Print this help";
+
+/// Options given on the command line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CliArgs {
+    /// Master seed of the `GameRng`
+    pub seed: Option<u64>,
+    /// Whether to run without a window
+    pub headless: bool,
+    /// Window size in logical pixels
+    pub window_size: Option<(u32, u32)>,
+    /// Difficulty preset applied to the configuration
+    pub difficulty: Difficulty,
+    /// Mutators applied to the configuration
+    pub mutators: Mutators,
+    /// Whether to silence all audio
+    pub mute: bool,
+    /// Replay file to race as a ghost
+    pub replay: Option<PathBuf>,
+    /// File to write each finished run to
+    pub record: Option<PathBuf>,
+    /// Recorded run to play back
+    pub play: Option<PathBuf>,
+    /// Whether to run the stress-test benchmark
+    pub bench: bool,
+    /// Whether to verify the replay file instead of playing
+    pub verify: bool,
+    /// Extra log filter directives
+    pub log_filter: Option<String>,
+    /// Whether help was requested
+    pub help: bool,
+}
+
+impl CliArgs {
+    /// Parses the arguments following the program name.
+    ///
+    /// Values can follow their flag as a separate argument or after `=`
+    /// (`--seed 42` or `--seed=42`).
+    pub fn parse<I>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{flag} needs a value"))
+            };
+
+            match flag.as_str() {
+                "--seed" => {
+                    let seed = value()?;
+                    parsed.seed = Some(
+                        seed.parse()
+                            .map_err(|_| format!("invalid seed \"{seed}\""))?,
+                    );
+                }
+                "--headless" => parsed.headless = true,
+                "--windowed" => {
+                    let size = value()?;
+                    parsed.window_size = Some(
+                        parse_window_size(&size)
+                            .ok_or_else(|| format!("invalid window size \"{size}\""))?,
+                    );
+                }
+                "--difficulty" => {
+                    let name = value()?;
+                    parsed.difficulty = Difficulty::from_name(&name)
+                        .ok_or_else(|| format!("unknown difficulty \"{name}\""))?;
+                }
+                "--mutator" => {
+                    let name = value()?;
+                    let mutator = Mutator::from_tag(&name)
+                        .ok_or_else(|| format!("unknown mutator \"{name}\""))?;
+                    parsed.mutators.0.insert(mutator);
+                }
+                "--mute" => parsed.mute = true,
+                "--replay" => parsed.replay = Some(PathBuf::from(value()?)),
+                "--record" => parsed.record = Some(PathBuf::from(value()?)),
+                "--play" => parsed.play = Some(PathBuf::from(value()?)),
+                "--bench" => parsed.bench = true,
+                "--verify" => parsed.verify = true,
+                "--log" => parsed.log_filter = Some(value()?),
+                "-h" | "--help" => parsed.help = true,
+                _ => return Err(format!("unknown argument \"{flag}\"")),
+            }
+        }
+
+        if parsed.headless && parsed.window_size.is_some() {
+            return Err("--headless and --windowed can't be combined".to_string());
+        }
+        if parsed.verify && parsed.replay.is_none() {
+            return Err("--verify needs a --replay file".to_string());
+        }
+        if parsed.play.is_some() && (parsed.verify || parsed.bench) {
+            return Err("--play can't be combined with --verify or --bench".to_string());
+        }
+        Ok(parsed)
+    }
+}
+
+/// Parses a window size written as `WIDTHxHEIGHT`.
+fn parse_window_size(size: &str) -> Option<(u32, u32)> {
+    let (width, height) = size.split_once(['x', 'X'])?;
+    let size = (width.trim().parse().ok()?, height.trim().parse().ok()?);
+    (size.0 > 0 && size.1 > 0).then_some(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        CliArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_is_a_normal_run() {
+        assert_eq!(parse(&[]), Ok(CliArgs::default()));
+    }
+
+    #[test]
+    fn parses_every_flag() {
+        let args = parse(&[
+            "--seed",
+            "42",
+            "--windowed=480x720",
+            "--difficulty",
+            "Hard",
+            "--mutator",
+            "big-bird",
+            "--mutator=double-speed",
+            "--mute",
+            "--replay",
+            "runs/best.replay.ron",
+            "--record",
+            "bug.flap",
+            "--bench",
+            "--log",
+            "flappy_rust=debug",
+        ]);
+        let expected = CliArgs {
+            seed: Some(42),
+            window_size: Some((480, 720)),
+            difficulty: Difficulty::Hard,
+            mutators: Mutators([Mutator::BigBird, Mutator::DoubleSpeed].into()),
+            mute: true,
+            replay: Some(PathBuf::from("runs/best.replay.ron")),
+            record: Some(PathBuf::from("bug.flap")),
+            bench: true,
+            log_filter: Some("flappy_rust=debug".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(args, Ok(expected));
+    }
+
+    #[test]
+    fn verify_checks_the_replay_file() {
+        let args = parse(&["--verify", "--replay", "run.replay.ron"]);
+        let expected = CliArgs {
+            replay: Some(PathBuf::from("run.replay.ron")),
+            verify: true,
+            ..Default::default()
+        };
+        assert_eq!(args, Ok(expected));
+    }
+
+    #[test]
+    fn play_takes_a_recorded_run() {
+        let args = parse(&["--play=bug.flap", "--windowed", "480x720"]);
+        let expected = CliArgs {
+            play: Some(PathBuf::from("bug.flap")),
+            window_size: Some((480, 720)),
+            ..Default::default()
+        };
+        assert_eq!(args, Ok(expected));
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--windowed", "480"]).is_err());
+        assert!(parse(&["--windowed", "0x720"]).is_err());
+        assert!(parse(&["--difficulty", "nightmare"]).is_err());
+        assert!(parse(&["--mutator", "tiny-bird"]).is_err());
+        assert!(parse(&["--log"]).is_err());
+        assert!(parse(&["--fullscreen"]).is_err());
+        assert!(parse(&["--headless", "--windowed", "480x720"]).is_err());
+        assert!(parse(&["--verify"]).is_err());
+        assert!(parse(&["--play"]).is_err());
+        assert!(parse(&["--play", "bug.flap", "--bench"]).is_err());
+    }
+}
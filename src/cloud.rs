@@ -0,0 +1,442 @@
+//! Optional cloud save sync.
+//!
+//! When `FLAPPY_CLOUD_SAVE` is set to an `http://` URL, the save data and
+//! settings are mirrored to that file on a WebDAV server or an S3-compatible
+//! bucket (anything that serves `GET` and accepts `PUT` on the URL).
+//!
+//! `FLAPPY_CLOUD_AUTH` holds the credentials, if the remote needs any:
+//! - `s3:REGION:ACCESS_KEY_ID:SECRET_ACCESS_KEY` signs each request with AWS
+//!   Signature Version 4 (see `sigv4`). Only signatures are sent, never the
+//!   secret key, so this works with any host.
+//! - `user:password` for basic authentication, or a complete `Authorization`
+//!   value such as `Bearer <token>`, for WebDAV. Plain http would send them
+//!   in the clear, so these are only accepted for a URL on this machine, such
+//!   as a local TLS-terminating proxy in front of the real server.
+//!
+//! Copies are merged rather than overwritten: the higher best score and
+//! playtime win (the remote's best scores only if they match the signature
+//! uploaded with them, as for portable saves), unlocked achievements and bought trails are combined, and
+//! the coin balance and settings with the newer timestamp are kept.
+
+use std::io;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::net;
+use crate::save::{SaveData, Versioned, now_ms};
+use crate::settings::Settings;
+use crate::sigv4;
+
+/// Environment variable that enables sync and holds the remote file URL.
+pub const CLOUD_SAVE_ENV_VAR: &str = "FLAPPY_CLOUD_SAVE";
+
+/// Environment variable holding optional credentials for the remote.
+pub const CLOUD_AUTH_ENV_VAR: &str = "FLAPPY_CLOUD_AUTH";
+
+/// Everything that is synced, stored remotely as a single RON file.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CloudSave {
+    /// Player progress
+    pub save: SaveData,
+    /// Player settings
+    pub settings: Settings,
+}
+
+impl CloudSave {
+    /// Combines two copies, keeping the best of each.
+    pub fn merge(&self, other: &CloudSave) -> CloudSave {
+        let mut save = self.save.clone();
+        save.best_score = save.best_score.max(other.save.best_score);
+        save.flicker_best_score = save.flicker_best_score.max(other.save.flicker_best_score);
+        save.glide_best_score = save.glide_best_score.max(other.save.glide_best_score);
+        for (tag, best) in &other.save.mutator_best_scores {
+            save.submit_mutator_score(*best, tag);
+        }
+        save.playtime = save.playtime.max(other.save.playtime);
+        save.tutorial_done |= other.save.tutorial_done;
+        save.achievements
+            .extend(other.save.achievements.iter().copied());
+        save.trails.extend(other.save.trails.iter().cloned());
+        if other.save.coins_updated_at > save.coins_updated_at {
+            save.coins = other.save.coins;
+            save.coins_updated_at = other.save.coins_updated_at;
+        }
+
+        let settings = if other.settings.updated_at > self.settings.updated_at {
+            other.settings.clone()
+        } else {
+            self.settings.clone()
+        };
+
+        CloudSave { save, settings }
+    }
+}
+
+/// Contents of the remote file: a `CloudSave` and the signature of its best
+/// scores.
+#[derive(Serialize, Deserialize)]
+struct CloudFile {
+    /// Player progress
+    #[serde(default)]
+    save: SaveData,
+    /// Signature of the best scores in `save`
+    #[serde(default)]
+    signature: Option<u64>,
+    /// Player settings
+    #[serde(default)]
+    settings: Settings,
+}
+
+impl CloudFile {
+    /// Wraps a copy for upload, signing its best scores.
+    fn signed(cloud_save: &CloudSave) -> Self {
+        Self {
+            save: cloud_save.save.clone(),
+            signature: cloud_save.save.sign(),
+            settings: cloud_save.settings.clone(),
+        }
+    }
+
+    /// Unwraps a downloaded copy, resetting best scores that don't match
+    /// their signature.
+    fn verified(self) -> CloudSave {
+        let mut save = self.save.verify(self.signature);
+        // The rejection is about the remote copy, not the local save file
+        save.scores_rejected = false;
+        CloudSave {
+            save,
+            settings: self.settings,
+        }
+    }
+}
+
+/// How requests to the remote are authenticated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloudAuth {
+    /// A fixed `Authorization` header value (basic or bearer), for WebDAV
+    Header(String),
+    /// Requests signed for an S3-compatible bucket
+    S3(sigv4::Credentials),
+}
+
+/// A remote file holding a `CloudSave`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloudRemote {
+    /// URL of the remote file
+    pub url: String,
+    /// Credentials sent with each request, if any
+    pub auth: Option<CloudAuth>,
+}
+
+impl CloudRemote {
+    /// Creates a remote, reading `s3:` credentials as S3 keys and turning
+    /// `user:password` credentials into basic auth.
+    ///
+    /// Header credentials are refused unless the URL is on this machine,
+    /// since plain http would send them across the network in the clear.
+    pub fn new(url: &str, credentials: Option<&str>) -> Result<Self, String> {
+        let auth = match credentials {
+            None => None,
+            Some(credentials) if credentials.starts_with("s3:") => {
+                let keys = sigv4::Credentials::parse(&credentials["s3:".len()..]).ok_or(
+                    "S3 credentials must look like s3:REGION:ACCESS_KEY_ID:SECRET_ACCESS_KEY",
+                )?;
+                Some(CloudAuth::S3(keys))
+            }
+            Some(_) if !net::is_loopback(url) => {
+                return Err(
+                    "credentials are only sent to a local proxy (localhost), never over plain http"
+                        .to_string(),
+                );
+            }
+            Some(credentials) if credentials.contains(' ') => {
+                Some(CloudAuth::Header(credentials.to_string()))
+            }
+            Some(credentials) => Some(CloudAuth::Header(net::basic_auth(credentials))),
+        };
+
+        Ok(Self {
+            url: url.to_string(),
+            auth,
+        })
+    }
+
+    /// Sends a request with the remote's credentials.
+    fn request(
+        &self,
+        method: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> io::Result<net::Response> {
+        let signed = match &self.auth {
+            Some(CloudAuth::S3(credentials)) => {
+                let (host, path) = net::host_and_path(&self.url)?;
+                credentials.sign(method, &host, &path, &[], body, now_ms())
+            }
+            Some(CloudAuth::Header(authorization)) => {
+                vec![("Authorization".to_string(), authorization.clone())]
+            }
+            None => Vec::new(),
+        };
+        let mut headers = headers.to_vec();
+        headers.extend(
+            signed
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        net::request(method, &self.url, &headers, body)
+    }
+
+    /// Downloads the remote copy, or `None` if there is none yet.
+    fn fetch(&self) -> io::Result<Option<CloudSave>> {
+        let response = self.request("GET", &[], &[])?;
+        if response.status == 404 {
+            return Ok(None);
+        }
+        if !response.is_success() {
+            return Err(io::Error::other(format!(
+                "GET returned {}",
+                response.status
+            )));
+        }
+
+        let text = String::from_utf8_lossy(&response.body);
+        ron::from_str::<CloudFile>(&text)
+            .map(|file| Some(file.verified()))
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))
+    }
+
+    /// Replaces the remote copy.
+    fn upload(&self, cloud_save: &CloudSave) -> io::Result<()> {
+        let file = CloudFile::signed(cloud_save);
+        let text = ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        let response = self.request("PUT", &[("Content-Type", "text/plain")], text.as_bytes())?;
+        if !response.is_success() {
+            return Err(io::Error::other(format!(
+                "PUT returned {}",
+                response.status
+            )));
+        }
+        Ok(())
+    }
+
+    /// Merges a local copy with the remote one, uploading the result if the
+    /// remote was missing something. Returns the merged copy.
+    fn sync(&self, local: &CloudSave) -> io::Result<CloudSave> {
+        let remote = self.fetch()?;
+        let merged = match &remote {
+            Some(remote) => local.merge(remote),
+            None => local.clone(),
+        };
+
+        if remote.as_ref() != Some(&merged) {
+            self.upload(&merged)?;
+        }
+        Ok(merged)
+    }
+}
+
+/// Resource that syncs local progress and settings with the remote.
+///
+/// Syncing happens on a background thread so network I/O never stalls a frame.
+#[derive(Resource)]
+pub struct CloudSync {
+    /// Channel of local copies to sync
+    sender: Option<Sender<CloudSave>>,
+    /// Merged copies that differ from what was sent
+    results: Mutex<Receiver<CloudSave>>,
+    /// Sync thread handle, joined on drop to finish a pending upload
+    worker: Option<JoinHandle<()>>,
+}
+
+impl CloudSync {
+    /// Creates the sync from the environment, or `None` if not configured.
+    pub fn from_env() -> Option<Self> {
+        let credentials = std::env::var(CLOUD_AUTH_ENV_VAR).ok();
+        net::target_from_env(CLOUD_SAVE_ENV_VAR, "Cloud save", |url| {
+            CloudRemote::new(url, credentials.as_deref())
+        })
+        .map(Self::new)
+    }
+
+    /// Creates a sync against the given remote.
+    pub fn new(remote: CloudRemote) -> Self {
+        let (sender, receiver) = mpsc::channel::<CloudSave>();
+        let (result_sender, results) = mpsc::channel();
+        let worker = thread::spawn(move || {
+            while let Ok(local) = receiver.recv() {
+                // Only the most recent local copy matters
+                let local = receiver.try_iter().last().unwrap_or(local);
+                match remote.sync(&local) {
+                    Ok(merged) if merged != local => {
+                        let _ = result_sender.send(merged);
+                    }
+                    Ok(_) => {}
+                    Err(error) => warn!("Cloud save sync with {} failed: {error}", remote.url),
+                }
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            results: Mutex::new(results),
+            worker: Some(worker),
+        }
+    }
+
+    /// Queues a local copy to be merged with the remote.
+    pub fn request(&self, local: CloudSave) {
+        if let Some(sender) = &self.sender {
+            // The worker only stops once the sender is dropped
+            let _ = sender.send(local);
+        }
+    }
+
+    /// Returns the latest merged copy received from the remote, if any.
+    pub fn take_result(&self) -> Option<CloudSave> {
+        self.results
+            .lock()
+            .ok()
+            .and_then(|results| results.try_iter().last())
+    }
+}
+
+impl Drop for CloudSync {
+    fn drop(&mut self) {
+        // Close the channel and wait for a pending sync to finish
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::achievements::Achievement;
+
+    fn cloud_save(
+        best_score: u32,
+        achievement: Achievement,
+        theme: &str,
+        updated_at: u64,
+    ) -> CloudSave {
+        let mut cloud_save = CloudSave::default();
+        cloud_save.save.best_score = best_score;
+        cloud_save.save.achievements.insert(achievement);
+        cloud_save.settings.theme = theme.to_string();
+        cloud_save.settings.updated_at = updated_at;
+        cloud_save
+    }
+
+    #[test]
+    fn merge_keeps_higher_score_and_playtime_and_all_achievements() {
+        let local = cloud_save(12, Achievement::Score10, "Night", 0);
+        let mut remote = cloud_save(30, Achievement::Icarus, "Night", 0);
+        remote.save.playtime = 90.0;
+
+        let merged = local.merge(&remote);
+        assert_eq!(merged.save.best_score, 30);
+        assert_eq!(merged.save.playtime, 90.0);
+        assert_eq!(
+            merged.save.achievements.into_iter().collect::<Vec<_>>(),
+            vec![Achievement::Score10, Achievement::Icarus]
+        );
+    }
+
+    #[test]
+    fn merge_combines_trails_and_keeps_newer_coins() {
+        let mut local = cloud_save(1, Achievement::FirstPipe, "Night", 0);
+        local.save.trails.insert("Bubbles".to_string());
+        local.save.equipped_trail = Some("Bubbles".to_string());
+        local.save.coins = 80;
+        local.save.coins_updated_at = 100;
+        let mut remote = cloud_save(1, Achievement::FirstPipe, "Night", 0);
+        remote.save.trails.insert("Flames".to_string());
+        remote.save.coins = 5;
+        remote.save.coins_updated_at = 200;
+
+        let merged = local.merge(&remote);
+        assert_eq!(
+            merged.save.trails.into_iter().collect::<Vec<_>>(),
+            vec!["Bubbles".to_string(), "Flames".to_string()]
+        );
+        assert_eq!(merged.save.equipped_trail.as_deref(), Some("Bubbles"));
+        // The remote spent coins more recently
+        assert_eq!(merged.save.coins, 5);
+        assert_eq!(remote.merge(&local).save.coins, 5);
+    }
+
+    #[test]
+    fn edited_remote_scores_are_not_merged() {
+        let local = cloud_save(12, Achievement::Score10, "Night", 0);
+        let remote = cloud_save(30, Achievement::Icarus, "Night", 0);
+        let uploaded = ron::to_string(&CloudFile::signed(&remote)).unwrap();
+
+        let signed = ron::from_str::<CloudFile>(&uploaded).unwrap().verified();
+        assert_eq!(local.merge(&signed).save.best_score, 30);
+
+        let mut edited = ron::from_str::<CloudFile>(&uploaded).unwrap();
+        edited.save.best_score = 9999;
+        let merged = local.merge(&edited.verified());
+        assert_eq!(merged.save.best_score, 12);
+        assert!(merged.save.achievements.contains(&Achievement::Icarus));
+
+        // Files without a signature don't carry their scores over either
+        let unsigned = ron::to_string(&remote).unwrap();
+        let unsigned = ron::from_str::<CloudFile>(&unsigned).unwrap().verified();
+        assert_eq!(local.merge(&unsigned).save.best_score, 12);
+    }
+
+    #[test]
+    fn merge_keeps_newer_settings() {
+        let local = cloud_save(1, Achievement::FirstPipe, "Night", 200);
+        let remote = cloud_save(1, Achievement::FirstPipe, "Sunset", 100);
+
+        assert_eq!(local.merge(&remote).settings.theme, "Night");
+        assert_eq!(remote.merge(&local).settings.theme, "Night");
+    }
+
+    #[test]
+    fn credentials_become_authorization_header() {
+        let basic = CloudRemote::new("http://localhost:8080/save.ron", Some("me:secret"));
+        assert_eq!(
+            basic.map(|remote| remote.auth),
+            Ok(Some(CloudAuth::Header("Basic bWU6c2VjcmV0".to_string())))
+        );
+
+        let bearer = CloudRemote::new("http://127.0.0.1:8080/save.ron", Some("Bearer abc"));
+        assert_eq!(
+            bearer.map(|remote| remote.auth),
+            Ok(Some(CloudAuth::Header("Bearer abc".to_string())))
+        );
+    }
+
+    #[test]
+    fn credentials_are_not_sent_over_the_network() {
+        assert!(CloudRemote::new("http://dav.local/save.ron", Some("me:secret")).is_err());
+        assert!(CloudRemote::new("http://dav.local/save.ron", None).is_ok());
+    }
+
+    #[test]
+    fn s3_credentials_sign_requests_to_any_host() {
+        let url = "http://bucket.s3.amazonaws.com/flappy/save.ron";
+        let remote = CloudRemote::new(url, Some("s3:eu-west-3:AKIDEXAMPLE:secret"));
+        assert_eq!(
+            remote.map(|remote| remote.auth),
+            Ok(Some(CloudAuth::S3(sigv4::Credentials {
+                region: "eu-west-3".to_string(),
+                access_key: "AKIDEXAMPLE".to_string(),
+                secret_key: "secret".to_string(),
+            })))
+        );
+        assert!(CloudRemote::new(url, Some("s3:eu-west-3:AKIDEXAMPLE")).is_err());
+    }
+}
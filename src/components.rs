@@ -4,6 +4,14 @@
 
 use bevy::prelude::*;
 
+use crate::bindings::ControlsRow;
+use crate::challenge::ChallengeKind;
+use crate::hazards::HazardKind;
+use crate::music::{JukeboxRow, JukeboxTrack, MusicLayer};
+use crate::mutators::MutatorRow;
+use crate::settings::SettingsRow;
+use crate::theme::Decoration;
+
 /// Component for the bird/player entity.
 ///
 /// Tracks the vertical velocity for physics simulation.
@@ -11,23 +19,367 @@ use bevy::prelude::*;
 pub struct Bird {
     /// Current vertical velocity in pixels per second.
     pub velocity: f32,
+    /// Direction gravity pulls in: 1.0 normally, -1.0 inside a gravity-flip zone.
+    pub gravity_direction: f32,
 }
 
 impl Default for Bird {
     fn default() -> Self {
-        Self { velocity: 0.0 }
+        Self {
+            velocity: 0.0,
+            gravity_direction: 1.0,
+        }
     }
 }
 
+/// Marker for the prompt of the current tutorial step.
+#[derive(Component)]
+pub struct TutorialText;
+
+/// Marker for the wing recharge bar shown below the bird during a flap
+/// cooldown.
+#[derive(Component)]
+pub struct WingRecharge;
+
+/// Component for the cosmetic companion trailing the bird.
+#[derive(Component, Default)]
+pub struct Companion {
+    /// Current velocity in pixels per second
+    pub velocity: Vec2,
+}
+
+/// Component for a ghost bird re-flying a recorded run.
+#[derive(Component)]
+pub struct Ghost {
+    /// Seconds from the start of the run to each flap
+    pub flaps: Vec<f32>,
+    /// Index of the next flap to replay
+    pub next_flap: usize,
+    /// Seconds from the start of the run to the crash
+    pub duration: f32,
+    /// Elapsed app time when the run started
+    pub started_at: f32,
+    /// Current vertical velocity in pixels per second
+    pub velocity: f32,
+}
+
+/// Component for the bird of another player in an online race.
+#[derive(Component)]
+pub struct RaceOpponent {
+    /// The player's race id
+    pub id: u8,
+}
+
+/// Marker component for the text announcing a race winner.
+#[derive(Component)]
+pub struct RaceBanner;
+
+/// Marker component for entities of the attract-mode demo.
+#[derive(Component)]
+pub struct AttractDemo;
+
+/// Idle animation of the bird on the menu: a gentle bob and an occasional
+/// blink.
+#[derive(Component)]
+pub struct IdleAnimation {
+    /// Height the bird bobs around
+    pub base_y: f32,
+    /// Seconds since the animation started
+    pub elapsed: f32,
+}
+
+/// Marker for the bird's eye, shown while the bird idles on the menu.
+#[derive(Component)]
+pub struct BirdEye;
+
+/// Component for the autopilot bird of the attract-mode demo.
+#[derive(Component, Default)]
+pub struct DemoBird {
+    /// Current vertical velocity in pixels per second
+    pub velocity: f32,
+}
+
+/// Component for the lower pipe of an attract-mode demo pair.
+#[derive(Component)]
+pub struct DemoPipe {
+    /// Center of the pair's gap
+    pub gap_y: f32,
+}
+
+/// Component for the audio player of a music layer.
+#[derive(Component)]
+pub struct Music {
+    /// The layer it plays
+    pub layer: MusicLayer,
+}
+
 /// Marker component for pipe entities.
+///
+/// Pipes are children of a `PipePair` and positioned relative to it.
 #[derive(Component)]
 pub struct Pipe;
 
-/// Component to track if a pipe has been scored.
+/// Parent entity of the pipes of one column.
+///
+/// The pair moves, scores, and despawns as a whole, taking its pipes along.
+#[derive(Component, Clone, Copy)]
+pub struct PipePair {
+    /// World Y coordinate of the gap center (the lowest one, for columns
+    /// with several gaps)
+    pub gap_y: f32,
+    /// Width of the pipes
+    pub width: f32,
+    /// Whether the bird has passed the pair
+    pub scored: bool,
+}
+
+/// Invisible sensor filling a gap of a pipe pair.
+///
+/// Sensors are children of the `PipePair`; the pair scores when a bird
+/// overlaps one of them.
+#[derive(Component, Clone, Copy)]
+pub struct GapSensor {
+    /// Width and height of the gap
+    pub size: Vec2,
+}
+
+impl GapSensor {
+    /// Returns true if a bird whose hitbox spans `bird_size` (width and
+    /// height) at `bird_pos` overlaps the sensor centered on `center`.
+    ///
+    /// With `whole_column`, only the horizontal extent counts, for birds that
+    /// fly through the pipes around the gap.
+    pub fn overlaps(
+        &self,
+        center: Vec2,
+        bird_pos: Vec2,
+        bird_size: Vec2,
+        whole_column: bool,
+    ) -> bool {
+        let reach = (self.size + bird_size) / 2.0;
+        let offset = (bird_pos - center).abs();
+        offset.x < reach.x && (whole_column || offset.y < reach.y)
+    }
+}
+
+/// Marker for the arrow pointing to a gap outside the visible area.
+#[derive(Component)]
+pub struct GapIndicator;
+
+/// Marker for the developer console panel.
+#[derive(Component)]
+pub struct ConsoleUi;
+
+/// Marker for the developer console's log and command line.
+#[derive(Component)]
+pub struct ConsoleText;
+
+/// Marker for the strip previewing the gaps of the next pipe pairs.
+#[derive(Component)]
+pub struct PipePreview;
+
+/// Component for the notch marking a gap on the pipe preview strip.
+#[derive(Component)]
+pub struct PreviewNotch {
+    /// Position on the strip (0 = the next pair)
+    pub slot: usize,
+}
+
+/// Marker component for obstacles other than pipes.
+///
+/// Obstacles scroll and despawn along with the pipes.
+#[derive(Component)]
+pub struct Obstacle;
+
+/// Collision shape of an obstacle, centered on its transform.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub enum CollisionShape {
+    /// Circle with the given radius
+    Circle(f32),
+    /// Axis-aligned box with the given width and height
+    Box(Vec2),
+}
+
+/// Score tier a pipe was spawned in, which picks its color.
+#[derive(Component, Clone, Copy)]
+pub struct PipeTier(pub usize);
+
+/// Marker for a gravity-flip zone between pipes; its sprite is the trigger volume.
+#[derive(Component)]
+pub struct GravityZone;
+
+/// Component for a blade spinning inside a pipe gap.
+#[derive(Component)]
+pub struct SpinningBlade {
+    /// Rotation speed in radians per second
+    pub speed: f32,
+}
+
+/// Component for one door of a gate that closes a pipe gap.
+///
+/// Doors slide out of the pipes towards the gap center and back.
+#[derive(Component)]
+pub struct GateDoor {
+    /// Y coordinate when retracted into the pipe
+    pub open_y: f32,
+    /// Y coordinate when fully closed
+    pub closed_y: f32,
+    /// Speed of the open/close cycle in radians per second
+    pub speed: f32,
+    /// Seconds since the door was spawned
+    pub elapsed: f32,
+}
+
+/// Component for a block bobbing up and down between pipe pairs.
+#[derive(Component)]
+pub struct FloatingBlock {
+    /// Y coordinate at the middle of the bob
+    pub base_y: f32,
+    /// Distance from the middle to the top of the bob
+    pub amplitude: f32,
+    /// Bob speed in radians per second
+    pub speed: f32,
+    /// Seconds since the block was spawned
+    pub elapsed: f32,
+}
+
+/// Component for the warning icon announcing a hazard.
+///
+/// The icon blinks where the hazard will come from, and the hazard starts
+/// when it runs out.
+#[derive(Component)]
+pub struct HazardWarning {
+    /// The hazard announced
+    pub kind: HazardKind,
+    /// Seconds until the hazard starts
+    pub remaining: f32,
+}
+
+/// Component for a bird of a hazard flock, flying against the scroll.
+///
+/// Hazard birds are obstacles, so they also scroll with the pipes.
+#[derive(Component)]
+pub struct HazardBird {
+    /// Flight speed on top of the scroll, in pixels per second
+    pub speed: f32,
+    /// Y coordinate at the middle of the bob
+    pub base_y: f32,
+    /// Seconds since the bird was spawned
+    pub elapsed: f32,
+}
+
+/// Component for a crate falling onto the bird's path.
+///
+/// Crates are obstacles, so they also scroll with the pipes, and break
+/// when they reach the ground.
+#[derive(Component)]
+pub struct FallingCrate {
+    /// Downward speed in pixels per second
+    pub velocity: f32,
+}
+
+/// Component for the chaser enemy that closes in on a hovering bird.
+///
+/// The chaser isn't an obstacle (it doesn't scroll with the pipes), but it
+/// registers itself in `NearbyObstacles` once level with the bird so
+/// `check_collisions` tests it.
+#[derive(Component, Default)]
+pub struct Chaser {
+    /// How close it is, from 0 (off-screen) to 1 (on the bird's column)
+    pub closeness: f32,
+    /// Height the bird has been hovering around
+    pub hover_y: f32,
+    /// Seconds the bird has stayed within the hover band
+    pub hover_time: f32,
+    /// Current velocity in pixels per second
+    pub velocity: Vec2,
+}
+
+/// Component for the pipe pair of the boss wall.
 ///
-/// Only attached to bottom pipes to avoid double-counting.
+/// The pair moves its segments up and down to keep a hole at a height that
+/// oscillates around `base_y`.
 #[derive(Component)]
-pub struct Scored(pub bool);
+pub struct BossWall {
+    /// Y coordinate of the hole center at the middle of its path
+    pub base_y: f32,
+    /// Distance from the middle to the top of the hole's path
+    pub travel: f32,
+    /// Speed of the hole in radians per second
+    pub speed: f32,
+    /// Seconds since the wall was spawned
+    pub elapsed: f32,
+}
+
+/// Background layer the clouds are parented to.
+#[derive(Component, Default)]
+pub struct CloudLayer {
+    /// Seconds until the next cloud spawns
+    pub spawn_timer: f32,
+}
+
+/// Component for a background cloud drifting to the left.
+#[derive(Component)]
+pub struct Cloud {
+    /// Drift speed in pixels per second
+    pub speed: f32,
+}
+
+/// Background layer the bird flocks are parented to.
+#[derive(Component, Default)]
+pub struct FlockLayer {
+    /// Seconds until the next flock spawns
+    pub spawn_timer: f32,
+}
+
+/// Component for a flock of background birds flying to the left.
+#[derive(Component)]
+pub struct Flock {
+    /// Flight speed in pixels per second
+    pub speed: f32,
+    /// Seconds since the flock spawned
+    pub elapsed: f32,
+    /// Distance from the leader to the farthest slot, in pixels
+    pub extent: f32,
+}
+
+/// Component for a background bird, positioned relative to its flock.
+#[derive(Component)]
+pub struct FlockBird {
+    /// Place in the formation, relative to the leader
+    pub slot: Vec2,
+    /// Velocity relative to the flock, in pixels per second
+    pub velocity: Vec2,
+    /// Offset of this bird's bobbing, in radians
+    pub phase: f32,
+}
+
+/// Component for a strip of the fog ahead of the bird.
+#[derive(Component)]
+pub struct FogStrip {
+    /// Strip index (0 = nearest the bird); the last strip is solid and covers
+    /// the rest of the screen
+    pub index: usize,
+}
+
+/// Layer the seasonal decorations of the current theme are parented to.
+#[derive(Component, Default)]
+pub struct DecorationLayer {
+    /// Seconds until the next decoration spawns
+    pub spawn_timer: f32,
+}
+
+/// Component for a snowflake or bat drifting across the sky.
+#[derive(Component)]
+pub struct SeasonalDecoration {
+    /// Kind of decoration
+    pub kind: Decoration,
+    /// Velocity in pixels per second
+    pub velocity: Vec2,
+    /// Seconds since the decoration spawned, offset at random so they don't
+    /// sway in step
+    pub elapsed: f32,
+}
 
 /// Marker component for the ground entity.
 #[derive(Component)]
@@ -37,14 +389,309 @@ pub struct Ground;
 #[derive(Component)]
 pub struct ScoreText;
 
+/// Marker component for the best score text UI element.
+#[derive(Component)]
+pub struct BestScoreText;
+
 /// Marker component for instruction/message text UI element.
 #[derive(Component)]
 pub struct InstructionText;
 
+/// Marker for the full-screen overlay used by state transitions.
+#[derive(Component)]
+pub struct TransitionOverlay;
+
+/// Marker for the game over screen's rewind button.
+#[derive(Component, Clone, Copy)]
+pub struct RewindButton;
+
+/// Marker for a star pickup, which makes the bird invincible.
+#[derive(Component)]
+pub struct StarPickup;
+
+/// Component for a floating letter of the letter bonus.
+///
+/// Letters scroll with the pipes (see `pipe_movement`).
+#[derive(Component)]
+pub struct LetterPickup {
+    /// The letter shown
+    pub letter: char,
+    /// World Y coordinate it bobs around
+    pub base_y: f32,
+    /// Seconds since it spawned
+    pub elapsed: f32,
+}
+
+/// Component for a HUD icon of the letter bonus word, by index in the word.
+#[derive(Component)]
+pub struct LetterIcon(pub usize);
+
+/// Marker for a pipe the star-powered bird broke through; it no longer collides.
+#[derive(Component)]
+pub struct Shattered;
+
+/// Marker for the revive prompt's accept button.
+#[derive(Component, Clone, Copy)]
+pub struct ReviveButton;
+
+/// Marker for entities of the revive prompt.
+#[derive(Component)]
+pub struct ReviveUi;
+
+/// Marker for entities of the pause screen.
+#[derive(Component)]
+pub struct PauseUi;
+
+/// Component for a button of the pause screen.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum PauseButton {
+    /// Resume the run
+    Resume,
+    /// End the run now
+    GiveUp,
+}
+
+/// Marker component for the run statistics shown on the game over screen.
+#[derive(Component)]
+pub struct RunStatsText;
+
+/// Marker component for the speedrun timer in the top left corner.
+#[derive(Component)]
+pub struct SplitTimerText;
+
+/// Marker component for the menu's settings button.
+#[derive(Component, Clone, Copy)]
+pub struct SettingsButton;
+
+/// Marker component for the menu's play button.
+#[derive(Component, Clone, Copy)]
+pub struct PlayButton;
+
+/// Marker component for the menu's button opening the mutator screen.
+#[derive(Component, Clone, Copy)]
+pub struct MutatorsButton;
+
+/// Component for a menu button starting a daily or weekly challenge.
+#[derive(Component, Clone, Copy)]
+pub struct ChallengeButton(pub ChallengeKind);
+
+/// Marker component for the main menu's buttons, hidden with the menu.
+#[derive(Component)]
+pub struct MenuButton;
+
+/// Component for a menu item reachable with the keyboard or a gamepad.
+#[derive(Component, Default)]
+pub struct Focusable {
+    /// Position in the focus order
+    pub order: u32,
+    /// Whether the item has the focus
+    pub focused: bool,
+}
+
+impl Focusable {
+    /// Creates an item at `order` in the focus order, focused if first.
+    pub fn new(order: u32) -> Self {
+        Self {
+            order,
+            focused: order == 0,
+        }
+    }
+}
+
+/// Marker component for the build information in the corner of the menu.
+#[derive(Component)]
+pub struct VersionText;
+
+/// Marker component for the debug overlay text (frame rate and build
+/// information), toggled with F3.
+#[derive(Component)]
+pub struct DebugOverlay;
+
+/// Marker component for every entity of the settings screen.
+#[derive(Component)]
+pub struct SettingsUi;
+
+/// Component for a row of the settings screen.
+#[derive(Component, Clone, Copy)]
+pub struct SettingsRowText(pub SettingsRow);
+
+/// Marker component for every entity of the sound test screen.
+#[derive(Component)]
+pub struct JukeboxUi;
+
+/// Component for a row of the sound test screen.
+#[derive(Component, Clone, Copy)]
+pub struct JukeboxRowText(pub JukeboxRow);
+
+/// Marker component for every entity of the controls screen.
+#[derive(Component)]
+pub struct ControlsUi;
+
+/// Component for a row of the controls screen.
+#[derive(Component, Clone, Copy)]
+pub struct ControlsRowText(pub ControlsRow);
+
+/// Marker component for the status line of the controls screen.
+#[derive(Component)]
+pub struct ControlsStatusText;
+
+/// Marker component for every entity of the seed entry screen.
+#[derive(Component)]
+pub struct SeedEntryUi;
+
+/// Marker component for the code typed on the seed entry screen.
+#[derive(Component)]
+pub struct SeedInputText;
+
+/// Marker component for the status line of the seed entry screen.
+#[derive(Component)]
+pub struct SeedStatusText;
+
+/// Marker component for every entity of the mutator screen.
+#[derive(Component)]
+pub struct MutatorsUi;
+
+/// Component for a row of the mutator screen.
+#[derive(Component, Clone, Copy)]
+pub struct MutatorRowText(pub MutatorRow);
+
+/// Component for the audio player of the track previewed on the sound test
+/// screen.
+#[derive(Component)]
+pub struct JukeboxSound(pub JukeboxTrack);
+
+/// Marker component for the export/import status line of the settings screen.
+#[derive(Component)]
+pub struct SettingsStatusText;
+
+/// Component for the buttons scrolling the settings rows by a page
+/// (-1 up, 1 down).
+#[derive(Component, Clone, Copy)]
+pub struct SettingsScrollButton(pub i32);
+
+/// Pressable area of a 2D UI element, centered on its transform.
+#[derive(Component)]
+pub struct HitArea {
+    /// Width and height of the area
+    pub size: Vec2,
+}
+
+impl HitArea {
+    /// Returns true if a world position lies inside the area.
+    pub fn contains(&self, transform: &GlobalTransform, point: Vec2) -> bool {
+        let offset = point - transform.translation().truncate();
+        offset.x.abs() <= self.size.x / 2.0 && offset.y.abs() <= self.size.y / 2.0
+    }
+}
+
+/// Visual state of a `WorldButton`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ButtonState {
+    /// Nothing is happening
+    #[default]
+    Idle,
+    /// The pointer is over the button
+    Hovered,
+    /// A press that started on the button is held over it
+    Pressed,
+    /// The button ignores the pointer
+    Disabled,
+}
+
+/// Component for a 2D UI element pressed with the mouse or a touch, within
+/// its `HitArea`.
+///
+/// The button is clicked when a press that started on it is released over
+/// it, which sends a `ButtonClicked` message carrying its action component.
+#[derive(Component, Clone, Copy, PartialEq)]
+pub struct WorldButton {
+    /// Text color while idle
+    pub color: Color,
+    /// Whether the button ignores the pointer
+    pub disabled: bool,
+    /// Current visual state
+    pub state: ButtonState,
+    /// Whether a press that started on the button is still held
+    pub armed: bool,
+    /// Whether the button was clicked this frame
+    pub clicked: bool,
+}
+
+impl WorldButton {
+    /// Creates an enabled button drawn in `color` while idle.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            disabled: false,
+            state: ButtonState::Idle,
+            armed: false,
+            clicked: false,
+        }
+    }
+}
+
+impl Default for WorldButton {
+    fn default() -> Self {
+        Self::new(Color::WHITE)
+    }
+}
+
 /// Marker component for the main camera.
 #[derive(Component)]
 pub struct MainCamera;
 
+/// Layers of camera motion on the main camera.
+///
+/// Each effect writes only its own layer; `apply_camera_rig` composes them
+/// into the camera's position and projection scale every frame, so effects
+/// add up instead of overwriting each other.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct CameraRig {
+    /// Offset of the camera zoom, keeping its focus point in place
+    pub zoom_offset: Vec2,
+    /// Offset of the screen shake
+    pub shake_offset: Vec2,
+    /// Projection scale (below 1.0 zooms in)
+    pub scale: f32,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        Self {
+            zoom_offset: Vec2::ZERO,
+            shake_offset: Vec2::ZERO,
+            scale: 1.0,
+        }
+    }
+}
+
+impl CameraRig {
+    /// Returns the camera position with every layer added up.
+    pub fn translation(&self) -> Vec2 {
+        self.zoom_offset + self.shake_offset
+    }
+}
+
+/// Component remembering the nominal font size of world text, which is
+/// rendered at the window's resolution.
+#[derive(Component)]
+pub struct WorldTextSize(pub f32);
+
+/// Marker component for world text wrapped to fit the screen, because the
+/// text size setting made it too wide.
+#[derive(Component)]
+pub struct WrappedWorldText;
+
+/// Component remembering the nominal font size of HUD text, scaled by the
+/// text size setting.
+#[derive(Component)]
+pub struct HudTextSize(pub f32);
+
+/// Marker component for the camera drawing the HUD, which stays still while
+/// the main camera shakes.
+#[derive(Component)]
+pub struct HudCamera;
+
 /// Component for screen flash overlay.
 #[derive(Component)]
 pub struct ScreenFlash;
@@ -58,6 +705,46 @@ pub enum EdgeType {
     Right,
 }
 
+/// Component making an entity glow under bloom, by pushing its sprite or
+/// text color past white (see `apply_emissive`).
+///
+/// Systems keep setting the plain color; the glow is added on top just
+/// before rendering, and the last color written is kept to tell a new plain
+/// color apart from the glowing one.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Emissive {
+    /// Brightness multiplier while bloom is on
+    pub strength: f32,
+    /// Plain color, as last set by the game
+    pub base: Color,
+    /// Color last written by `apply_emissive`
+    pub applied: Option<Color>,
+}
+
+impl Emissive {
+    /// Creates a glow of the given strength.
+    pub fn new(strength: f32) -> Self {
+        Self {
+            strength,
+            base: Color::WHITE,
+            applied: None,
+        }
+    }
+}
+
+/// Marker for HUD text drawn with the text outline setting (the score and
+/// the instructions).
+#[derive(Component)]
+pub struct OutlinedText;
+
+/// Component for a dark copy of outlined text, offset from it; together the
+/// copies draw the outline (see `sync_text_outlines`).
+#[derive(Component)]
+pub struct OutlineGlyph {
+    /// The text this copy follows
+    pub source: Entity,
+}
+
 /// Component for edge flash overlays (score effect).
 /// The alpha_multiplier controls the gradient fade (1.0 at edge, 0.0 at center).
 #[derive(Component)]
@@ -91,6 +778,20 @@ pub struct FlapParticle;
 #[derive(Component)]
 pub struct DeathParticle;
 
+/// Component for bird trail particles.
+#[derive(Component)]
+pub struct TrailParticle {
+    /// Downward acceleration in pixels per second squared
+    pub gravity: f32,
+}
+
+/// Component for the confetti of a new record celebration.
+#[derive(Component)]
+pub struct Confetti {
+    /// Downward acceleration in pixels per second squared
+    pub gravity: f32,
+}
+
 /// Component for animating score text pop effect.
 #[derive(Component)]
 pub struct ScorePopAnimation {
@@ -100,6 +801,22 @@ pub struct ScorePopAnimation {
     pub duration: f32,
 }
 
+/// Component for the floating "+1" text shown at a scored gap.
+#[derive(Component)]
+pub struct ScorePopup {
+    /// Current animation time
+    pub timer: f32,
+    /// Total animation duration
+    pub duration: f32,
+}
+
+/// Component for toast notifications (achievements, playtime reminders).
+#[derive(Component)]
+pub struct Toast {
+    /// Time the toast has been visible
+    pub timer: f32,
+}
+
 /// Component for bird squash/stretch animation.
 #[derive(Component)]
 pub struct BirdSquashStretch {
@@ -112,6 +829,23 @@ pub struct BirdSquashStretch {
     pub is_squash: bool,
 }
 
+/// Component for a milestone banner sliding across the top of the screen.
+#[derive(Component)]
+pub struct MilestoneBanner {
+    /// Seconds since the banner appeared
+    pub elapsed: f32,
+    /// Seconds the banner takes to cross the screen
+    pub duration: f32,
+}
+
+/// Marker for the full-screen overlay drawing dark themes' lighting.
+#[derive(Component)]
+pub struct LightingOverlay;
+
+/// Marker for the overlay darkening the corners of the screen.
+#[derive(Component)]
+pub struct VignetteOverlay;
+
 /// Marker component for entities that should fill the entire screen.
 #[derive(Component)]
 pub struct FillScreen;
@@ -0,0 +1,609 @@
+//! Tunable gameplay configuration.
+//!
+//! `GameConfig` holds the values used to balance the game (physics, pipe
+//! difficulty curve, obstacles, hazards, boss segments, effect timings, music, fog). It is loaded from a RON asset and
+//! re-applied whenever that file changes, so balancing doesn't require a
+//! recompile. The defaults mirror `constants.rs` and are used until the
+//! asset has loaded (or if it fails to load).
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    BIRD_SCALE, BLADE_CHANCE, BLADE_SIZE, BLADE_SPIN_SPEED, BLOCK_BOB_HEIGHT, BLOCK_BOB_SPEED,
+    BLOCK_CHANCE, BLOCK_SIZE, BOSS_HOLE_SIZE, BOSS_HOLE_SPEED, BOSS_HOLE_TRAVEL, BOSS_INTERVAL,
+    BOSS_WALL_WIDTH, CRATE_HAZARD_GRAVITY, CRATE_HAZARD_SIZE, CRATE_HAZARD_WEIGHT,
+    DEATH_FLASH_ALPHA, DEATH_FLASH_DURATION, DEATH_PARTICLE_COUNT, DEATH_PARTICLE_LIFETIME,
+    DEATH_PARTICLE_SPEED, DEATH_ZOOM, DEATH_ZOOM_DURATION, DIFFICULTY_EASY, DIFFICULTY_HARD,
+    FLAP_PARTICLE_COUNT_MAX, FLAP_PARTICLE_COUNT_MIN, FLAP_PARTICLE_LIFETIME, FLAP_SQUASH_DURATION,
+    FLAP_SQUASH_SCALE, FLAP_STRENGTH, FLAP_STRETCH_SCALE, FLOCK_HAZARD_BIRD_SIZE,
+    FLOCK_HAZARD_SIZE, FLOCK_HAZARD_SPEED, FLOCK_HAZARD_WEIGHT, FOG_DISTANCE, FOG_OPACITY,
+    GATE_CHANCE, GATE_MIN_OPENING, GATE_SPEED, GRAVITY, HAZARD_MAX_INTERVAL, HAZARD_MIN_INTERVAL,
+    HAZARD_MIN_SCORE, HAZARD_WARNING_TIME, INVERTED_GRAVITY, MAX_TILT_DOWN, MAX_TILT_UP,
+    MILESTONE_BANNER_DURATION, MILESTONE_SCORES, MUSIC_FADE_TIME, MUSIC_LEAD_SCORE,
+    MUSIC_PERCUSSION_SCORE, MUSIC_VOLUME, NEAR_MISS_DISTANCE, OBSTACLE_MIN_SCORE, PIPE_GAP_END,
+    PIPE_GAP_SCALE_SCORE, PIPE_GAP_START_MAX, PIPE_GAP_START_MIN, PIPE_SPAWN_DISTANCE,
+    RANDOM_PAIR_WEIGHT, ROTATED_HITBOX, SCORE_FLASH_ALPHA, SCORE_FLASH_DURATION,
+    SCORE_POP_DURATION, SCORE_POP_SCALE, SCORE_POPUP_DURATION, SCORE_POPUP_RISE, SCORE_ZOOM,
+    SCORE_ZOOM_DURATION, SCREEN_SHAKE_DURATION, SCREEN_SHAKE_FREQUENCY, SCREEN_SHAKE_INTENSITY,
+    TILT_SPEED, TUNNEL_LENGTH, TUNNEL_MIN_SCORE, TUNNEL_SPACING, TUNNEL_WEIGHT, VIGNETTE_DEATH,
+    VIGNETTE_INTENSITY, VIGNETTE_NEAR_MISS, VIGNETTE_PULSE_DURATION, WIND_HAZARD_DURATION,
+    WIND_HAZARD_STRENGTH, WIND_HAZARD_WEIGHT, WORLD_SCROLL_SPEED,
+};
+
+/// Asset path of the game configuration file.
+pub const GAME_CONFIG_PATH: &str = "config/game.config.ron";
+
+/// Asset path of the configuration saved from the tuning panel, loaded
+/// instead of `GAME_CONFIG_PATH` when it exists (with the `tuning` feature).
+pub const TUNED_CONFIG_PATH: &str = "config/tuned.config.ron";
+
+/// Gameplay configuration, available both as an asset and as a resource.
+///
+/// Systems read the `GameConfig` resource; the asset is copied into it each
+/// time it is (re)loaded. Missing fields fall back to their defaults.
+#[derive(Asset, Resource, TypePath, Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Bird physics
+    pub physics: PhysicsConfig,
+    /// Pipe spawning and difficulty curve
+    pub pipes: PipeConfig,
+    /// Obstacles spawned alongside random pipe pairs
+    pub obstacles: ObstacleConfig,
+    /// Hazard events interrupting the pipes
+    pub hazards: HazardConfig,
+    /// Boss segments
+    pub boss: BossConfig,
+    /// Visual effect timings and intensities
+    pub effects: EffectsConfig,
+    /// Adaptive music layers
+    pub music: MusicConfig,
+    /// Milestone banners
+    pub milestones: MilestoneConfig,
+    /// Fog hiding the screen ahead of the bird
+    pub fog: FogConfig,
+}
+
+impl GameConfig {
+    /// Formats the configuration as a RON file, in the layout of the shipped
+    /// one (without its comments).
+    pub fn to_ron(&self) -> Result<String, String> {
+        let pretty = ron::ser::PrettyConfig::default().struct_names(false);
+        ron::ser::to_string_pretty(self, pretty).map_err(|error| error.to_string())
+    }
+}
+
+/// Bird physics settings.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PhysicsConfig {
+    /// Downward acceleration (negative is down)
+    pub gravity: f32,
+    /// Upward velocity set by a flap
+    pub flap_strength: f32,
+    /// Maximum upward tilt in radians
+    pub max_tilt_up: f32,
+    /// Maximum downward tilt in radians (negative)
+    pub max_tilt_down: f32,
+    /// How quickly the bird rotates towards its target tilt
+    pub tilt_speed: f32,
+    /// Whether the hitbox turns with the bird's tilt (otherwise it stays an
+    /// upright square)
+    pub rotated_hitbox: bool,
+    /// Size of the bird (sprite and hitbox), relative to `BIRD_SIZE`
+    pub bird_scale: f32,
+    /// Whether gravity pulls the bird up and flaps push it down, outside
+    /// gravity-flip zones
+    pub inverted_gravity: bool,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            gravity: GRAVITY,
+            flap_strength: FLAP_STRENGTH,
+            max_tilt_up: MAX_TILT_UP,
+            max_tilt_down: MAX_TILT_DOWN,
+            tilt_speed: TILT_SPEED,
+            rotated_hitbox: ROTATED_HITBOX,
+            bird_scale: BIRD_SCALE,
+            inverted_gravity: INVERTED_GRAVITY,
+        }
+    }
+}
+
+/// Pipe spawning and difficulty settings.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PipeConfig {
+    /// World units scrolled between pipe spawns
+    pub spawn_distance: f32,
+    /// Smallest gap at score 0
+    pub gap_start_min: f32,
+    /// Largest gap at score 0
+    pub gap_start_max: f32,
+    /// Gap size once maximum difficulty is reached
+    pub gap_end: f32,
+    /// Score at which maximum difficulty is reached
+    pub gap_scale_score: u32,
+    /// How fast pipes (and the world) scroll left
+    pub scroll_speed: f32,
+    /// Selection weight of a random pipe pair versus authored patterns
+    pub random_pair_weight: f32,
+    /// Selection weight of a generated tunnel (0 disables tunnels)
+    pub tunnel_weight: f32,
+    /// Score from which tunnels can appear
+    pub tunnel_min_score: u32,
+    /// Number of columns in a tunnel
+    pub tunnel_length: u32,
+    /// Distance between tunnel columns, in multiples of `spawn_distance`
+    pub tunnel_spacing: f32,
+}
+
+impl Default for PipeConfig {
+    fn default() -> Self {
+        Self {
+            spawn_distance: PIPE_SPAWN_DISTANCE,
+            gap_start_min: PIPE_GAP_START_MIN,
+            gap_start_max: PIPE_GAP_START_MAX,
+            gap_end: PIPE_GAP_END,
+            gap_scale_score: PIPE_GAP_SCALE_SCORE,
+            scroll_speed: WORLD_SCROLL_SPEED,
+            random_pair_weight: RANDOM_PAIR_WEIGHT,
+            tunnel_weight: TUNNEL_WEIGHT,
+            tunnel_min_score: TUNNEL_MIN_SCORE,
+            tunnel_length: TUNNEL_LENGTH,
+            tunnel_spacing: TUNNEL_SPACING,
+        }
+    }
+}
+
+impl PipeConfig {
+    /// Returns the seconds between pipe spawns at the current scroll speed.
+    pub fn spawn_interval(&self) -> f32 {
+        self.spawn_distance / self.scroll_speed.max(f32::EPSILON)
+    }
+
+    /// Returns the (min, max) gap size range for a given score.
+    ///
+    /// At score 0 the gap ranges from `gap_start_min` to `gap_start_max`;
+    /// both ends shrink linearly to `gap_end` at `gap_scale_score`.
+    pub fn gap_range(&self, score: u32) -> (f32, f32) {
+        let difficulty = (score as f32 / self.gap_scale_score.max(1) as f32).min(1.0);
+        let gap_min = self.gap_start_min + (self.gap_end - self.gap_start_min) * difficulty;
+        let gap_max = self.gap_start_max + (self.gap_end - self.gap_start_max) * difficulty;
+        (gap_min, gap_max)
+    }
+}
+
+/// Obstacle settings.
+///
+/// Each random pipe pair rolls for at most one obstacle: a blade spinning in
+/// the gap, a gate closing the gap, or a block floating before the next pair.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ObstacleConfig {
+    /// Score required before obstacles appear
+    pub min_score: u32,
+    // Spinning blade in the gap
+    pub blade_chance: f32,
+    pub blade_size: f32,
+    pub blade_spin_speed: f32,
+    // Gate doors closing the gap from above and below
+    pub gate_chance: f32,
+    pub gate_min_opening: f32,
+    pub gate_speed: f32,
+    // Block bobbing between two pipe pairs
+    pub block_chance: f32,
+    pub block_size: f32,
+    pub block_bob_height: f32,
+    pub block_bob_speed: f32,
+}
+
+impl Default for ObstacleConfig {
+    fn default() -> Self {
+        Self {
+            min_score: OBSTACLE_MIN_SCORE,
+            blade_chance: BLADE_CHANCE,
+            blade_size: BLADE_SIZE,
+            blade_spin_speed: BLADE_SPIN_SPEED,
+            gate_chance: GATE_CHANCE,
+            gate_min_opening: GATE_MIN_OPENING,
+            gate_speed: GATE_SPEED,
+            block_chance: BLOCK_CHANCE,
+            block_size: BLOCK_SIZE,
+            block_bob_height: BLOCK_BOB_HEIGHT,
+            block_bob_speed: BLOCK_BOB_SPEED,
+        }
+    }
+}
+
+/// Hazard event settings.
+///
+/// From `min_score` on, a flock, falling crate or wind gust (picked by
+/// weight) is announced every `min_interval` to `max_interval` seconds.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct HazardConfig {
+    /// Score required before hazards appear
+    pub min_score: u32,
+    /// Shortest time between two hazards, in seconds
+    pub min_interval: f32,
+    /// Longest time between two hazards, in seconds
+    pub max_interval: f32,
+    /// Time the warning shows before the hazard starts, in seconds
+    pub warning_time: f32,
+    // Birds flying against the scroll
+    pub flock_weight: f32,
+    pub flock_size: u32,
+    pub flock_speed: f32,
+    pub flock_bird_size: f32,
+    // Crate dropping onto the bird's path
+    pub crate_weight: f32,
+    pub crate_size: f32,
+    pub crate_gravity: f32,
+    // Gust pushing the bird up or down
+    pub wind_weight: f32,
+    pub wind_strength: f32,
+    pub wind_duration: f32,
+}
+
+impl Default for HazardConfig {
+    fn default() -> Self {
+        Self {
+            min_score: HAZARD_MIN_SCORE,
+            min_interval: HAZARD_MIN_INTERVAL,
+            max_interval: HAZARD_MAX_INTERVAL,
+            warning_time: HAZARD_WARNING_TIME,
+            flock_weight: FLOCK_HAZARD_WEIGHT,
+            flock_size: FLOCK_HAZARD_SIZE,
+            flock_speed: FLOCK_HAZARD_SPEED,
+            flock_bird_size: FLOCK_HAZARD_BIRD_SIZE,
+            crate_weight: CRATE_HAZARD_WEIGHT,
+            crate_size: CRATE_HAZARD_SIZE,
+            crate_gravity: CRATE_HAZARD_GRAVITY,
+            wind_weight: WIND_HAZARD_WEIGHT,
+            wind_strength: WIND_HAZARD_STRENGTH,
+            wind_duration: WIND_HAZARD_DURATION,
+        }
+    }
+}
+
+/// Boss segment settings.
+///
+/// Every `interval` points, regular spawning pauses for a wide pipe wall
+/// whose hole moves up and down.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct BossConfig {
+    /// Points between boss segments (0 disables them)
+    pub interval: u32,
+    /// Width of the pipe wall
+    pub wall_width: f32,
+    /// Height of the hole in the wall
+    pub hole_size: f32,
+    /// Distance from the middle to the top of the hole's path
+    pub hole_travel: f32,
+    /// Speed of the hole in radians per second
+    pub hole_speed: f32,
+}
+
+impl Default for BossConfig {
+    fn default() -> Self {
+        Self {
+            interval: BOSS_INTERVAL,
+            wall_width: BOSS_WALL_WIDTH,
+            hole_size: BOSS_HOLE_SIZE,
+            hole_travel: BOSS_HOLE_TRAVEL,
+            hole_speed: BOSS_HOLE_SPEED,
+        }
+    }
+}
+
+impl BossConfig {
+    /// Returns true if reaching `score` starts a boss segment.
+    pub fn starts_at(&self, score: u32) -> bool {
+        self.interval > 0 && score > 0 && score.is_multiple_of(self.interval)
+    }
+}
+
+/// Visual effect settings.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct EffectsConfig {
+    // Screen shake on death
+    pub screen_shake_duration: f32,
+    pub screen_shake_intensity: f32,
+    pub screen_shake_frequency: f32,
+    // Camera zoom on score and death
+    pub score_zoom: f32,
+    pub score_zoom_duration: f32,
+    pub death_zoom: f32,
+    pub death_zoom_duration: f32,
+    // Red flash on death
+    pub death_flash_duration: f32,
+    pub death_flash_alpha: f32,
+    // Gold edge flash on score
+    pub score_flash_duration: f32,
+    pub score_flash_alpha: f32,
+    // Bird squash/stretch on flap
+    pub flap_squash_duration: f32,
+    pub flap_squash_scale: f32,
+    pub flap_stretch_scale: f32,
+    // Score text pop
+    pub score_pop_duration: f32,
+    pub score_pop_scale: f32,
+    // Floating "+1" popup
+    pub score_popup_duration: f32,
+    pub score_popup_rise: f32,
+    // Flap cloud puff particles
+    pub flap_particle_count_min: u32,
+    pub flap_particle_count_max: u32,
+    pub flap_particle_lifetime: f32,
+    // Death burst particles
+    pub death_particle_count: u32,
+    pub death_particle_speed: f32,
+    pub death_particle_lifetime: f32,
+    // Vignette, pulsing darker on near misses and death
+    pub vignette_intensity: f32,
+    pub vignette_near_miss: f32,
+    pub vignette_death: f32,
+    pub vignette_pulse_duration: f32,
+    pub near_miss_distance: f32,
+    // Bird hidden during runs, and the trail following it when none is
+    // equipped
+    pub hide_bird: bool,
+    pub bird_trail: Option<String>,
+}
+
+impl Default for EffectsConfig {
+    fn default() -> Self {
+        Self {
+            screen_shake_duration: SCREEN_SHAKE_DURATION,
+            screen_shake_intensity: SCREEN_SHAKE_INTENSITY,
+            screen_shake_frequency: SCREEN_SHAKE_FREQUENCY,
+            score_zoom: SCORE_ZOOM,
+            score_zoom_duration: SCORE_ZOOM_DURATION,
+            death_zoom: DEATH_ZOOM,
+            death_zoom_duration: DEATH_ZOOM_DURATION,
+            death_flash_duration: DEATH_FLASH_DURATION,
+            death_flash_alpha: DEATH_FLASH_ALPHA,
+            score_flash_duration: SCORE_FLASH_DURATION,
+            score_flash_alpha: SCORE_FLASH_ALPHA,
+            flap_squash_duration: FLAP_SQUASH_DURATION,
+            flap_squash_scale: FLAP_SQUASH_SCALE,
+            flap_stretch_scale: FLAP_STRETCH_SCALE,
+            score_pop_duration: SCORE_POP_DURATION,
+            score_pop_scale: SCORE_POP_SCALE,
+            score_popup_duration: SCORE_POPUP_DURATION,
+            score_popup_rise: SCORE_POPUP_RISE,
+            flap_particle_count_min: FLAP_PARTICLE_COUNT_MIN,
+            flap_particle_count_max: FLAP_PARTICLE_COUNT_MAX,
+            flap_particle_lifetime: FLAP_PARTICLE_LIFETIME,
+            death_particle_count: DEATH_PARTICLE_COUNT,
+            death_particle_speed: DEATH_PARTICLE_SPEED,
+            death_particle_lifetime: DEATH_PARTICLE_LIFETIME,
+            vignette_intensity: VIGNETTE_INTENSITY,
+            vignette_near_miss: VIGNETTE_NEAR_MISS,
+            vignette_death: VIGNETTE_DEATH,
+            vignette_pulse_duration: VIGNETTE_PULSE_DURATION,
+            near_miss_distance: NEAR_MISS_DISTANCE,
+            hide_bird: false,
+            bird_trail: None,
+        }
+    }
+}
+
+/// Adaptive music settings.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct MusicConfig {
+    /// Volume of each layer once faded in (0 mutes the music)
+    pub volume: f32,
+    /// Score from which the percussion layer plays
+    pub percussion_score: u32,
+    /// Score from which the lead layer plays
+    pub lead_score: u32,
+    /// Seconds for a layer to fade fully in or out
+    pub fade_time: f32,
+}
+
+impl Default for MusicConfig {
+    fn default() -> Self {
+        Self {
+            volume: MUSIC_VOLUME,
+            percussion_score: MUSIC_PERCUSSION_SCORE,
+            lead_score: MUSIC_LEAD_SCORE,
+            fade_time: MUSIC_FADE_TIME,
+        }
+    }
+}
+
+/// Milestone banner settings.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct MilestoneConfig {
+    /// Scores announced with a banner and a fanfare
+    pub scores: Vec<u32>,
+    /// Seconds a banner takes to slide across the screen
+    pub banner_duration: f32,
+}
+
+impl Default for MilestoneConfig {
+    fn default() -> Self {
+        Self {
+            scores: MILESTONE_SCORES.to_vec(),
+            banner_duration: MILESTONE_BANNER_DURATION,
+        }
+    }
+}
+
+/// Fog settings, a difficulty modifier hiding the pipes until they're close.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct FogConfig {
+    /// Distance ahead of the bird the fog starts at (0.0 turns it off)
+    pub distance: f32,
+    /// Opacity of the fog once faded in (0.0 - 1.0)
+    pub opacity: f32,
+}
+
+impl Default for FogConfig {
+    fn default() -> Self {
+        Self {
+            distance: FOG_DISTANCE,
+            opacity: FOG_OPACITY,
+        }
+    }
+}
+
+/// Handle keeping the configuration asset loaded.
+#[derive(Resource)]
+pub struct GameConfigHandle(pub Handle<GameConfig>);
+
+/// Loads `*.config.ron` files into `GameConfig` assets.
+#[derive(Default, TypePath)]
+pub struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
+
+/// Difficulty preset scaling the loaded configuration.
+///
+/// Picked with `--difficulty` on the command line; it is applied on top of
+/// the configuration file each time it is (re)loaded.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Difficulty {
+    /// Wider gaps and slower pipes
+    Easy,
+    /// The configuration as written
+    #[default]
+    Normal,
+    /// Narrower gaps and faster pipes
+    Hard,
+}
+
+impl Difficulty {
+    /// Parses a difficulty name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "easy" => Some(Self::Easy),
+            "normal" => Some(Self::Normal),
+            "hard" => Some(Self::Hard),
+            _ => None,
+        }
+    }
+
+    /// Returns `config` with its gap sizes and scroll speed scaled for this
+    /// difficulty.
+    pub fn apply(self, mut config: GameConfig) -> GameConfig {
+        let (gap_scale, speed_scale) = match self {
+            Self::Easy => DIFFICULTY_EASY,
+            Self::Normal => return config,
+            Self::Hard => DIFFICULTY_HARD,
+        };
+        let pipes = &mut config.pipes;
+        pipes.gap_start_min *= gap_scale;
+        pipes.gap_start_max *= gap_scale;
+        pipes.gap_end *= gap_scale;
+        pipes.scroll_speed *= speed_scale;
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_config_matches_defaults() {
+        let shipped =
+            ron::from_str::<GameConfig>(include_str!("../assets/config/game.config.ron")).ok();
+        assert_eq!(shipped, Some(GameConfig::default()));
+    }
+
+    #[test]
+    fn written_config_reads_back() {
+        let config = GameConfig {
+            fog: FogConfig {
+                distance: 250.0,
+                ..default()
+            },
+            ..default()
+        };
+        let written = config.to_ron().unwrap();
+        assert_eq!(ron::from_str::<GameConfig>(&written).ok(), Some(config));
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let config = ron::from_str::<GameConfig>("(physics: (gravity: -1000.0))").ok();
+        let expected = GameConfig {
+            physics: PhysicsConfig {
+                gravity: -1000.0,
+                ..default()
+            },
+            ..default()
+        };
+        assert_eq!(config, Some(expected));
+    }
+
+    #[test]
+    fn difficulty_scales_gaps_and_speed() {
+        let normal = GameConfig::default();
+        assert_eq!(Difficulty::Normal.apply(normal.clone()), normal);
+
+        let easy = Difficulty::Easy.apply(normal.clone());
+        let hard = Difficulty::Hard.apply(normal.clone());
+        assert!(easy.pipes.gap_end > normal.pipes.gap_end);
+        assert!(easy.pipes.scroll_speed < normal.pipes.scroll_speed);
+        assert!(hard.pipes.gap_end < normal.pipes.gap_end);
+        assert!(hard.pipes.scroll_speed > normal.pipes.scroll_speed);
+    }
+
+    #[test]
+    fn boss_starts_every_interval() {
+        let boss = BossConfig::default();
+        assert!(!boss.starts_at(0));
+        assert!(!boss.starts_at(BOSS_INTERVAL - 1));
+        assert!(boss.starts_at(BOSS_INTERVAL));
+        assert!(boss.starts_at(BOSS_INTERVAL * 2));
+
+        let disabled = BossConfig {
+            interval: 0,
+            ..default()
+        };
+        assert!(!disabled.starts_at(BOSS_INTERVAL));
+    }
+
+    #[test]
+    fn gap_range_narrows_to_end_value() {
+        let pipes = PipeConfig::default();
+        assert_eq!(pipes.gap_range(0), (PIPE_GAP_START_MIN, PIPE_GAP_START_MAX));
+        assert_eq!(
+            pipes.gap_range(PIPE_GAP_SCALE_SCORE * 2),
+            (PIPE_GAP_END, PIPE_GAP_END)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Developer console.
+//!
+//! Backtick drops down a console for testing: typed commands change the
+//! score, state, game speed, or random seed, or spawn pipes on demand, so the
+//! difficulty curve can be checked without playing up to it. Commands are
+//! parsed here and run by `handle_console_input`.
+
+use bevy::prelude::*;
+
+use crate::states::GameState;
+
+/// Lines kept in the console's log.
+pub const CONSOLE_LOG_LINES: usize = 6;
+
+/// Help text printed by the `help` command.
+pub const CONSOLE_HELP: &str = "\
+set score <N> | state <menu|playing|gameover|settings>
+spawn pipe <GAP> | speed <X> | seed <N> | help";
+
+/// A console command.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConsoleCommand {
+    /// Print the available commands
+    Help,
+    /// Set the current score
+    SetScore(u32),
+    /// Switch to a game state
+    State(GameState),
+    /// Spawn a pipe pair with a gap of this height
+    SpawnPipe(f32),
+    /// Set the game speed (1.0 is normal)
+    Speed(f32),
+    /// Reseed the game's random numbers
+    Seed(u64),
+}
+
+impl ConsoleCommand {
+    /// Parses a command line, e.g. `set score 30`.
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<String> = line
+            .split_whitespace()
+            .map(str::to_ascii_lowercase)
+            .collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        match words[..] {
+            ["help"] => Ok(Self::Help),
+            ["set", "score", score] => score
+                .parse()
+                .map(Self::SetScore)
+                .map_err(|_| format!("invalid score \"{score}\"")),
+            ["state", name] => match name {
+                "menu" => Ok(Self::State(GameState::Menu)),
+                "playing" => Ok(Self::State(GameState::Playing)),
+                "gameover" => Ok(Self::State(GameState::GameOver)),
+                "settings" => Ok(Self::State(GameState::Settings)),
+                _ => Err(format!("unknown state \"{name}\"")),
+            },
+            ["spawn", "pipe", gap] => gap
+                .parse()
+                .ok()
+                .filter(|gap: &f32| *gap > 0.0)
+                .map(Self::SpawnPipe)
+                .ok_or_else(|| format!("invalid gap \"{gap}\"")),
+            ["speed", speed] => speed
+                .parse()
+                .ok()
+                .filter(|speed: &f32| *speed > 0.0)
+                .map(Self::Speed)
+                .ok_or_else(|| format!("invalid speed \"{speed}\"")),
+            ["seed", seed] => seed
+                .parse()
+                .map(Self::Seed)
+                .map_err(|_| format!("invalid seed \"{seed}\"")),
+            [] => Err("type \"help\" for the commands".to_string()),
+            _ => Err(format!("unknown command \"{}\"", line.trim())),
+        }
+    }
+}
+
+/// Resource holding the console's state.
+#[derive(Resource, Default)]
+pub struct DevConsole {
+    /// Whether the console is open
+    pub open: bool,
+    /// Command line being typed
+    pub input: String,
+    /// Commands run and their results, oldest first
+    pub log: Vec<String>,
+}
+
+impl DevConsole {
+    /// Adds a line to the log, dropping the oldest past `CONSOLE_LOG_LINES`.
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.log.push(line.into());
+        let excess = self.log.len().saturating_sub(CONSOLE_LOG_LINES);
+        self.log.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_command() {
+        assert_eq!(ConsoleCommand::parse("help"), Ok(ConsoleCommand::Help));
+        assert_eq!(
+            ConsoleCommand::parse("set score 30"),
+            Ok(ConsoleCommand::SetScore(30))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("state GameOver"),
+            Ok(ConsoleCommand::State(GameState::GameOver))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("  spawn pipe 120 "),
+            Ok(ConsoleCommand::SpawnPipe(120.0))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("speed 0.5"),
+            Ok(ConsoleCommand::Speed(0.5))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("seed 42"),
+            Ok(ConsoleCommand::Seed(42))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_commands() {
+        assert!(ConsoleCommand::parse("").is_err());
+        assert!(ConsoleCommand::parse("set score -3").is_err());
+        assert!(ConsoleCommand::parse("state paused").is_err());
+        assert!(ConsoleCommand::parse("spawn pipe 0").is_err());
+        assert!(ConsoleCommand::parse("speed fast").is_err());
+        assert!(ConsoleCommand::parse("fly").is_err());
+    }
+
+    #[test]
+    fn log_keeps_the_latest_lines() {
+        let mut console = DevConsole::default();
+        for line in 0..CONSOLE_LOG_LINES + 2 {
+            console.print(line.to_string());
+        }
+        assert_eq!(console.log.len(), CONSOLE_LOG_LINES);
+        assert_eq!(console.log[0], "2");
+    }
+}
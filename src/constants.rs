@@ -2,6 +2,8 @@
 //!
 //! This module contains all the tunable parameters for the game.
 
+use crate::settings::FpsCap;
+
 /// Game logical dimensions (fixed height, width is dynamic based on aspect ratio)
 pub const GAME_HEIGHT: f32 = 600.0;
 /// Default aspect ratio for initial window
@@ -15,17 +17,104 @@ pub const WINDOW_HEIGHT: f32 = GAME_HEIGHT;
 pub const GRAVITY: f32 = -800.0;
 pub const FLAP_STRENGTH: f32 = 350.0;
 
+/// One-switch mode flaps a bit faster than it takes a flap to fall back, so
+/// the bird climbs slowly unless the player skips flaps
+pub const AUTO_FLAP_RHYTHM: f32 = 0.95;
+
+/// How long a flap pressed before a run starts is remembered, in seconds,
+/// so a press just before the switch to `Playing` still flaps
+pub const FLAP_BUFFER_TIME: f32 = 0.1;
+
+/// Glide mode: holding the flap input scales gravity down by this much
+pub const GLIDE_GRAVITY_SCALE: f32 = 0.6;
+
+/// Results screen: the final score counts up from zero, ticking faster as it
+/// goes (longer runs take longer, up to a cap)
+pub const COUNT_UP_TIME_PER_POINT: f32 = 0.08; // Seconds
+pub const COUNT_UP_MAX_TIME: f32 = 1.5; // Seconds
+
+/// New record celebration: confetti raining from the top of the screen, a
+/// quicker take on the milestone fanfare, and the "Best" label swapping with
+/// a pop once the score has counted up
+pub const RECORD_CONFETTI_COUNT: u32 = 60;
+pub const RECORD_CONFETTI_SIZE: (f32, f32) = (5.0, 12.0); // Height and max width
+pub const RECORD_CONFETTI_SPEED: f32 = 240.0;
+pub const RECORD_CONFETTI_GRAVITY: f32 = 500.0;
+pub const RECORD_CONFETTI_LIFETIME: f32 = 2.5;
+pub const RECORD_CONFETTI_COLORS: [(f32, f32, f32); 5] = [
+    (0.95, 0.3, 0.35),
+    (1.0, 0.8, 0.2),
+    (0.3, 0.85, 0.45),
+    (0.3, 0.6, 1.0),
+    (0.75, 0.4, 0.95),
+];
+pub const RECORD_FANFARE_SPEED: f32 = 1.25;
+pub const RECORD_FLOURISH_TIME: f32 = 0.4; // Seconds
+pub const RECORD_FLOURISH_SCALE: f32 = 1.5;
+pub const RECORD_LABEL_COLOR: (f32, f32, f32) = (1.0, 0.85, 0.2);
+
+/// First-run tutorial: flaps asked for under slowed gravity (with the bird
+/// kept within a band around the middle), then one wide practice gap
+pub const TUTORIAL_FLAPS: u32 = 3;
+pub const TUTORIAL_GRAVITY_SCALE: f32 = 0.5;
+pub const TUTORIAL_BAND: f32 = 140.0; // Half height of the band
+pub const TUTORIAL_GAP_SIZE: f32 = 260.0;
+
+/// Flap cooldown (hard mode): seconds between flaps, and the wing recharge
+/// bar shown below the bird while it recharges
+pub const FLAP_COOLDOWN: f32 = 0.3;
+pub const WING_RECHARGE_SIZE: (f32, f32) = (28.0, 4.0);
+pub const WING_RECHARGE_OFFSET: f32 = 22.0; // Below the bird's center
+pub const WING_RECHARGE_COLOR: (f32, f32, f32) = (0.4, 0.85, 1.0);
+
 /// Bird dimensions
 pub const BIRD_SIZE: f32 = 30.0;
+pub const BIRD_SCALE: f32 = 1.0; // Scales the sprite and the hitbox
+
+/// Menu idle animation: bob height in pixels and speed in radians per
+/// second, and how often and how long the bird blinks, in seconds
+pub const IDLE_BOB_HEIGHT: f32 = 8.0;
+pub const IDLE_BOB_SPEED: f32 = 2.5;
+pub const IDLE_BLINK_INTERVAL: f32 = 3.5;
+pub const IDLE_BLINK_DURATION: f32 = 0.15;
+
+/// Size of the bird's eye, shown on the menu
+pub const BIRD_EYE_SIZE: f32 = 6.0;
+
+/// Companion: size, resting offset from the bird, and the spring pulling it
+/// there (stiffness per second squared, damping per second; slightly
+/// underdamped so it sways a little)
+pub const COMPANION_SIZE: f32 = 12.0;
+pub const COMPANION_OFFSET_X: f32 = -34.0;
+pub const COMPANION_OFFSET_Y: f32 = 16.0;
+pub const COMPANION_STIFFNESS: f32 = 60.0;
+pub const COMPANION_DAMPING: f32 = 12.0;
+pub const COMPANION_COLOR: (f32, f32, f32) = (1.0, 0.75, 0.85);
+
+/// Opacity of ghost birds
+pub const GHOST_ALPHA: f32 = 0.4;
+
+/// Opacity of other players' birds in online races
+pub const RACER_ALPHA: f32 = 0.6;
+
+/// Attract mode: seconds idle on the menu before the demo starts, and the
+/// demo's opacity
+pub const ATTRACT_DELAY: f32 = 10.0;
+pub const ATTRACT_ALPHA: f32 = 0.45;
 
 /// Bird tilt animation
 pub const MAX_TILT_UP: f32 = 0.5; // ~28 degrees up
 pub const MAX_TILT_DOWN: f32 = -1.2; // ~68 degrees down
 pub const TILT_SPEED: f32 = 5.0;
+/// Whether the hitbox turns with the bird's tilt
+pub const ROTATED_HITBOX: bool = false;
+/// Whether gravity pulls the bird up and flaps push it down
+pub const INVERTED_GRAVITY: bool = false;
 
-/// Pipe dimensions and spawning
+/// Pipe dimensions and spawning (a column every `PIPE_SPAWN_DISTANCE` world
+/// units scrolled, two seconds apart at the default scroll speed)
 pub const PIPE_WIDTH: f32 = 60.0;
-pub const PIPE_SPAWN_TIME: f32 = 2.0;
+pub const PIPE_SPAWN_DISTANCE: f32 = 300.0;
 
 /// Pipe gap difficulty scaling
 /// At score 0: gap ranges from PIPE_GAP_START_MIN to PIPE_GAP_START_MAX
@@ -35,12 +124,122 @@ pub const PIPE_GAP_START_MAX: f32 = 160.0; // Very easy at start
 pub const PIPE_GAP_END: f32 = 110.0; // Hard at max difficulty (min = max)
 pub const PIPE_GAP_SCALE_SCORE: u32 = 20; // Score at which max difficulty is reached
 
+/// Difficulty presets picked on the command line: (gap scale, scroll speed scale)
+pub const DIFFICULTY_EASY: (f32, f32) = (1.25, 0.85);
+pub const DIFFICULTY_HARD: (f32, f32) = (0.85, 1.15);
+
+/// Selection weight of a random pipe pair versus authored patterns
+pub const RANDOM_PAIR_WEIGHT: f32 = 1.0;
+
+/// Tunnels (runs of tight gaps with small height changes)
+pub const TUNNEL_WEIGHT: f32 = 0.15;
+pub const TUNNEL_MIN_SCORE: u32 = 10;
+pub const TUNNEL_LENGTH: u32 = 5;
+pub const TUNNEL_SPACING: f32 = 0.5;
+/// Fraction of the physically reachable step a tunnel actually uses
+pub const TUNNEL_STEP_MARGIN: f32 = 0.75;
+/// Fraction of the physically reachable climb or drop a random gap may be
+/// from the previous one
+pub const GAP_STEP_MARGIN: f32 = 0.75;
+
+/// Obstacles (blades, gates, floating blocks)
+pub const OBSTACLE_MIN_SCORE: u32 = 5; // No obstacles before this score
+pub const BLADE_CHANCE: f32 = 0.15; // Per random pipe pair
+pub const BLADE_SIZE: f32 = 32.0;
+pub const BLADE_SPIN_SPEED: f32 = 6.0; // Radians per second
+pub const GATE_CHANCE: f32 = 0.15; // Per random pipe pair
+pub const GATE_MIN_OPENING: f32 = 70.0; // Gap left when the gate is fully closed
+pub const GATE_SPEED: f32 = 2.0; // Radians per second of the open/close cycle
+pub const BLOCK_CHANCE: f32 = 0.2; // Per random pipe pair
+pub const BLOCK_SIZE: f32 = 40.0;
+pub const BLOCK_BOB_HEIGHT: f32 = 60.0;
+pub const BLOCK_BOB_SPEED: f32 = 2.0; // Radians per second
+
+/// Hazard events (flocks, falling crates, wind gusts)
+pub const HAZARD_MIN_SCORE: u32 = 12; // No hazards before this score
+pub const HAZARD_MIN_INTERVAL: f32 = 12.0; // Seconds between hazards
+pub const HAZARD_MAX_INTERVAL: f32 = 24.0;
+pub const HAZARD_WARNING_TIME: f32 = 1.0; // Seconds the warning shows before the hazard
+pub const FLOCK_HAZARD_WEIGHT: f32 = 1.0;
+pub const FLOCK_HAZARD_SIZE: u32 = 4; // Birds per flock
+pub const FLOCK_HAZARD_SPEED: f32 = 160.0; // On top of the scroll speed
+pub const FLOCK_HAZARD_BIRD_SIZE: f32 = 18.0;
+pub const CRATE_HAZARD_WEIGHT: f32 = 1.0;
+pub const CRATE_HAZARD_SIZE: f32 = 36.0;
+pub const CRATE_HAZARD_GRAVITY: f32 = 500.0;
+pub const WIND_HAZARD_WEIGHT: f32 = 1.0;
+pub const WIND_HAZARD_STRENGTH: f32 = 600.0; // Vertical push in pixels per second squared
+pub const WIND_HAZARD_DURATION: f32 = 1.5;
+/// Hazard warning icon and effects
+pub const HAZARD_WARNING_SIZE: f32 = 30.0;
+pub const HAZARD_WARNING_MARGIN: f32 = 30.0; // Distance from the screen edge
+pub const HAZARD_WARNING_COLOR: (f32, f32, f32) = (1.0, 0.8, 0.1);
+pub const HAZARD_WARNING_BLINK_RATE: f32 = 8.0;
+pub const HAZARD_WARNING_Z: f32 = 3.0;
+pub const FLOCK_HAZARD_SPACING: f32 = 28.0; // Between the birds of a flock
+pub const FLOCK_HAZARD_BOB: (f32, f32) = (10.0, 6.0); // Height and radians per second
+pub const CRATE_HAZARD_LEAD: (f32, f32) = (80.0, 220.0); // Drop distance ahead of the bird
+pub const WIND_STREAK_RATE: f32 = 30.0; // Streaks per second while the wind blows
+pub const WIND_STREAK_SIZE: (f32, f32) = (2.0, 40.0);
+pub const WIND_STREAK_SPEED: f32 = 700.0;
+
+/// Chaser enemy: appears past the minimum score, parked off the left edge.
+/// It closes in while the bird hovers within the band for longer than the
+/// grace time, and each pipe passed pushes it back. Closeness runs from 0
+/// (off-screen) to 1 (on the bird's column)
+pub const CHASER_MIN_SCORE: u32 = 30; // Appears above this score
+pub const CHASER_SIZE: f32 = 34.0;
+pub const CHASER_COLOR: (f32, f32, f32) = (0.75, 0.1, 0.15);
+pub const CHASER_HOVER_BAND: f32 = 50.0; // Vertical pixels counted as hovering
+pub const CHASER_HOVER_GRACE: f32 = 1.5; // Seconds of hovering before it closes in
+pub const CHASER_CLOSE_RATE: f32 = 0.2; // Closeness gained per second of hovering
+pub const CHASER_RETREAT: f32 = 0.3; // Closeness lost per pipe passed
+pub const CHASER_MAX_SPEED: f32 = 240.0; // Steering speed in pixels per second
+pub const CHASER_STEERING: f32 = 3.0; // How quickly it turns towards its target, per second
+
+/// Boss segment (a wide pipe wall with a moving hole)
+pub const BOSS_INTERVAL: u32 = 25; // Points between boss segments
+pub const BOSS_WALL_WIDTH: f32 = 240.0;
+pub const BOSS_HOLE_SIZE: f32 = 140.0;
+pub const BOSS_HOLE_TRAVEL: f32 = 120.0; // Distance from the middle to the top of the hole's path
+pub const BOSS_HOLE_SPEED: f32 = 1.5; // Radians per second
+
+/// Adaptive music (layers fade in as the score rises)
+pub const MUSIC_VOLUME: f32 = 0.5;
+pub const MUSIC_PERCUSSION_SCORE: u32 = 10;
+pub const MUSIC_LEAD_SCORE: u32 = 25;
+pub const MUSIC_FADE_TIME: f32 = 2.0; // Seconds for a layer to fade fully in or out
+
+/// Milestone banners (announced with a fanfare when a score is reached)
+pub const MILESTONE_SCORES: [u32; 4] = [10, 25, 50, 100];
+pub const MILESTONE_BANNER_DURATION: f32 = 2.0;
+pub const MILESTONE_BANNER_SIZE: (f32, f32) = (360.0, 70.0);
+pub const MILESTONE_BANNER_COLOR: (f32, f32, f32) = (0.85, 0.2, 0.25);
+pub const MILESTONE_BANNER_Y: f32 = 150.0; // Below the score, from the top edge
+pub const MILESTONE_FONT_SIZE: f32 = 40.0;
+/// Fraction of the banner's time spent sliding in (and again sliding out)
+pub const MILESTONE_SLIDE_FRACTION: f32 = 0.2;
+
 /// World scroll speed (how fast pipes/ground move)
 pub const WORLD_SCROLL_SPEED: f32 = 150.0;
 
 /// Ground dimensions
 pub const GROUND_HEIGHT: f32 = 50.0;
 
+/// Default ("Classic" theme) world colors
+pub const SKY_COLOR: (f32, f32, f32) = (0.5, 0.8, 1.0); // Light blue
+pub const GROUND_COLOR: (f32, f32, f32) = (0.6, 0.4, 0.2); // Brown
+pub const PIPE_COLOR: (f32, f32, f32) = (0.2, 0.7, 0.2); // Green
+/// Scores at which newly spawned pipes switch to the next tier color
+pub const PIPE_TIER_SCORES: [u32; 3] = [10, 25, 50];
+pub const PIPE_TIER_COLORS: [(f32, f32, f32); 3] = [
+    (0.2, 0.45, 0.85),  // Blue
+    (0.55, 0.25, 0.75), // Purple
+    (0.9, 0.7, 0.15),   // Gold
+];
+pub const BIRD_COLOR: (f32, f32, f32) = (1.0, 0.8, 0.0); // Yellow
+pub const OBSTACLE_COLOR: (f32, f32, f32) = (0.4, 0.4, 0.45); // Steel gray
+
 // ============================================================================
 // VISUAL EFFECTS CONSTANTS
 // ============================================================================
@@ -50,11 +249,50 @@ pub const SCREEN_SHAKE_DURATION: f32 = 0.3;
 pub const SCREEN_SHAKE_INTENSITY: f32 = 8.0;
 pub const SCREEN_SHAKE_FREQUENCY: f32 = 30.0;
 
+/// Camera zoom: a quick punch-in on score and a slower zoom toward the bird
+/// on death, as fractions of the view
+pub const SCORE_ZOOM: f32 = 0.02;
+pub const SCORE_ZOOM_DURATION: f32 = 0.15;
+pub const DEATH_ZOOM: f32 = 0.08;
+pub const DEATH_ZOOM_DURATION: f32 = 0.8;
+
+/// Gamepad rumble (the death rumble lasts as long as the screen shake)
+pub const SCORE_RUMBLE_DURATION: f32 = 0.08;
+pub const SCORE_RUMBLE_INTENSITY: f32 = 0.4; // Weak motor
+pub const DEATH_RUMBLE_INTENSITY: f32 = 1.0; // Strong motor
+
+/// State transitions: starting or restarting a run fades in from black, and
+/// game over dips partly to black so the crash stays visible
+pub const TRANSITION_DURATION: f32 = 0.35;
+pub const GAME_OVER_TRANSITION_ALPHA: f32 = 0.5;
+
 /// Screen flash effect
 pub const DEATH_FLASH_DURATION: f32 = 0.15;
 pub const DEATH_FLASH_COLOR: (f32, f32, f32) = (1.0, 0.3, 0.2); // Red-ish
 pub const DEATH_FLASH_ALPHA: f32 = 0.6;
 
+/// Arrow pointing to an off-screen gap
+pub const GAP_INDICATOR_SIZE: f32 = 20.0;
+pub const GAP_INDICATOR_MARGIN: f32 = 16.0;
+pub const GAP_INDICATOR_ALPHA: f32 = 0.85;
+
+/// Preview strip of the next gaps, shown on viewports narrower than
+/// `PREVIEW_MAX_VIEWPORT_WIDTH`: one track per pipe pair with a notch at its gap
+pub const PREVIEW_SLOT_COUNT: usize = 3;
+pub const PREVIEW_MAX_VIEWPORT_WIDTH: f32 = 360.0;
+pub const PREVIEW_TRACK_SIZE: (f32, f32) = (10.0, 36.0);
+pub const PREVIEW_TRACK_SPACING: f32 = 18.0;
+pub const PREVIEW_TOP_MARGIN: f32 = 8.0;
+pub const PREVIEW_MIN_NOTCH_HEIGHT: f32 = 3.0;
+pub const PREVIEW_ALPHA: f32 = 0.35;
+
+/// Widest world text can grow with the text size setting, as a fraction of
+/// the viewport width, before it wraps
+pub const WORLD_TEXT_MAX_WIDTH: f32 = 0.9;
+
+/// Tint applied to the pipe the bird crashed into
+pub const PIPE_HIT_COLOR: (f32, f32, f32) = (0.8, 0.25, 0.2); // Red-ish
+
 pub const SCORE_FLASH_DURATION: f32 = 0.1;
 pub const SCORE_FLASH_COLOR: (f32, f32, f32) = (1.0, 0.9, 0.3); // Gold
 pub const SCORE_FLASH_ALPHA: f32 = 0.3;
@@ -68,6 +306,11 @@ pub const FLAP_STRETCH_SCALE: f32 = 1.5; // Stretch vertically more
 pub const SCORE_POP_DURATION: f32 = 0.2;
 pub const SCORE_POP_SCALE: f32 = 1.4;
 
+/// Floating "+1" popup at the scored gap
+pub const SCORE_POPUP_DURATION: f32 = 0.6;
+pub const SCORE_POPUP_RISE: f32 = 40.0;
+pub const SCORE_POPUP_FONT_SIZE: f32 = 28.0;
+
 /// Particle effects - Flap (cloud puff effect)
 pub const FLAP_PARTICLE_COUNT_MIN: u32 = 3;
 pub const FLAP_PARTICLE_COUNT_MAX: u32 = 6;
@@ -88,9 +331,183 @@ pub const DEATH_PARTICLE_COLORS: [(f32, f32, f32); 3] = [
     (1.0, 0.4, 0.0), // Dark orange
 ];
 
+/// Vignette darkening the corners of the screen, pulsing darker on near
+/// misses (the bird passing within `NEAR_MISS_DISTANCE` pixels of a pipe or
+/// obstacle) and on death
+pub const VIGNETTE_INTENSITY: f32 = 0.25;
+pub const VIGNETTE_NEAR_MISS: f32 = 0.2;
+pub const VIGNETTE_DEATH: f32 = 0.45;
+pub const VIGNETTE_PULSE_DURATION: f32 = 0.5;
+pub const NEAR_MISS_DISTANCE: f32 = 10.0;
+pub const VIGNETTE_COLOR: (f32, f32, f32) = (0.0, 0.0, 0.05); // Near black
+/// Vignette sits above the world and its effects, below the screen flash
+pub const VIGNETTE_Z: f32 = 8.5;
+
+/// Playtime tracking: progress is saved every minute, and the optional
+/// reminder shows after every hour of a session
+pub const PLAYTIME_SAVE_INTERVAL: f32 = 60.0;
+pub const PLAYTIME_REMINDER_INTERVAL: f32 = 3600.0;
+
+/// Battery saver: frame-rate limit and particle count multiplier
+pub const BATTERY_SAVER_FPS_CAP: FpsCap = FpsCap::Thirty;
+pub const BATTERY_SAVER_PARTICLE_SCALE: f32 = 0.5;
+
+/// HUD text outline: the drop shadow's offset, the outline's width (in HUD
+/// pixels), and the color and opacity of both
+pub const TEXT_SHADOW_OFFSET: (f32, f32) = (3.0, 3.0);
+pub const TEXT_OUTLINE_WIDTH: f32 = 2.0;
+pub const TEXT_OUTLINE_COLOR: (f32, f32, f32) = (0.05, 0.05, 0.1); // Near black
+pub const TEXT_OUTLINE_ALPHA: f32 = 0.85;
+
+/// Bloom: glow strength, and how bright a color must be to glow (only the
+/// emissive colors below pass the threshold)
+pub const BLOOM_INTENSITY: f32 = 0.3;
+pub const BLOOM_THRESHOLD: f32 = 1.0;
+pub const BLOOM_THRESHOLD_SOFTNESS: f32 = 0.2;
+/// Emissive colors: how much brighter than their plain color the bird, the
+/// pickups (stars and letters) and the score flashes shine under bloom
+pub const BIRD_GLOW: f32 = 1.6;
+pub const PICKUP_GLOW: f32 = 2.0;
+pub const SCORE_FLASH_GLOW: f32 = 2.5;
+
+/// Rewind: how far back a crash can be undone, and how often snapshots are taken
+pub const REWIND_SECONDS: f32 = 3.0;
+pub const REWIND_SNAPSHOT_INTERVAL: f32 = 0.1;
+
+/// Revive (second chance after a crash)
+pub const REVIVE_COST: u32 = 50;
+pub const REVIVE_PROMPT_TIME: f32 = 4.0;
+pub const REVIVE_INVINCIBILITY: f32 = 2.0;
+
+/// Rainbow mode: how fast hues cycle (degrees per second), the saturation
+/// and lightness range of the shifted colors, and how many hues the death
+/// particles spread over
+pub const RAINBOW_HUE_SPEED: f32 = 60.0;
+pub const RAINBOW_MIN_SATURATION: f32 = 0.75;
+pub const RAINBOW_LIGHTNESS: (f32, f32) = (0.4, 0.7);
+pub const RAINBOW_PARTICLE_HUES: usize = 6;
+
+/// Scene lighting of dark themes (see `ThemeLighting`)
+pub const LIGHTING_DARKNESS_COLOR: (f32, f32, f32) = (0.0, 0.0, 0.05);
+pub const LIGHTING_DARKNESS: f32 = 0.75;
+pub const LIGHTING_BIRD_RADIUS: f32 = 140.0;
+pub const LIGHTING_GAP_RADIUS: f32 = 90.0;
+pub const LIGHTING_GAP_INTENSITY: f32 = 0.5;
+/// Lighting overlay sits above the world but below text
+pub const LIGHTING_Z: f32 = 1.8;
+
+/// Gravity-flip zones
+pub const GRAVITY_ZONE_CHANCE: f32 = 0.1;
+pub const GRAVITY_ZONE_MIN_SCORE: u32 = 8;
+pub const GRAVITY_ZONE_WIDTH: f32 = 140.0;
+pub const GRAVITY_ZONE_MARGIN: f32 = 40.0;
+pub const GRAVITY_ZONE_COLOR: (f32, f32, f32) = (0.6, 0.3, 1.0);
+pub const GRAVITY_ZONE_ALPHA: f32 = 0.25;
+
+/// Background clouds: seconds between spawns, width and drift speed ranges
+/// (heights follow the width), opacity range, and how many start on screen
+pub const CLOUD_Z: f32 = -0.9;
+pub const CLOUD_SPAWN_INTERVAL: (f32, f32) = (3.0, 7.0);
+pub const CLOUD_WIDTH: (f32, f32) = (60.0, 160.0);
+pub const CLOUD_SPEED: (f32, f32) = (12.0, 30.0);
+pub const CLOUD_ALPHA: (f32, f32) = (0.25, 0.6);
+pub const CLOUD_INITIAL_COUNT: u32 = 3;
+
+/// Background flocks: seconds between flocks, birds per flock, flight speed
+/// range, bird size and color, and the spacing of their V formation. Birds
+/// are pulled to their formation slot (stiffness and damping per second) and
+/// pushed apart when closer than the separation distance
+pub const FLOCK_Z: f32 = -0.8;
+pub const FLOCK_SPAWN_INTERVAL: (f32, f32) = (20.0, 45.0);
+pub const FLOCK_SIZE: (u32, u32) = (3, 7);
+pub const FLOCK_SPEED: (f32, f32) = (35.0, 60.0);
+pub const FLOCK_BIRD_SIZE: f32 = 7.0;
+pub const FLOCK_BIRD_COLOR: (f32, f32, f32) = (0.2, 0.2, 0.28);
+pub const FLOCK_BIRD_ALPHA: f32 = 0.55;
+pub const FLOCK_SPACING: f32 = 16.0;
+pub const FLOCK_COHESION: f32 = 4.0;
+pub const FLOCK_DAMPING: f32 = 3.0;
+pub const FLOCK_SEPARATION: f32 = 12.0;
+pub const FLOCK_REPULSION: f32 = 30.0;
+pub const FLOCK_BOB_HEIGHT: f32 = 3.0;
+pub const FLOCK_BOB_SPEED: f32 = 3.0;
+
+/// Seasonal decorations: snow falls in front of the pipes and bats fly
+/// behind them. Seconds between spawns, sizes, speeds and motion
+pub const SNOW_Z: f32 = 0.9;
+pub const SNOW_SPAWN_INTERVAL: f32 = 0.12;
+pub const SNOW_SIZE: (f32, f32) = (3.0, 7.0);
+pub const SNOW_FALL_SPEED: (f32, f32) = (30.0, 70.0);
+pub const SNOW_SWAY: f32 = 20.0;
+pub const BAT_Z: f32 = -0.6;
+pub const BAT_SPAWN_INTERVAL: (f32, f32) = (1.5, 4.0);
+pub const BAT_WIDTH: f32 = 26.0;
+pub const BAT_HEIGHT: f32 = 10.0;
+pub const BAT_SPEED: (f32, f32) = (60.0, 110.0);
+pub const BAT_FLAP_RATE: f32 = 12.0;
+pub const BAT_BOB: f32 = 25.0;
+pub const BAT_COLOR: (f32, f32, f32) = (0.1, 0.05, 0.15);
+
+/// Flicker mode: pipes start fading at this horizontal distance from the bird
+/// and are invisible from the second one
+pub const FLICKER_FADE_START: f32 = 260.0;
+pub const FLICKER_FADE_END: f32 = 110.0;
+
+/// Invincibility (after a revive or a star)
+pub const INVINCIBILITY_BLINK_RATE: f32 = 10.0;
+pub const STAR_CHANCE: f32 = 0.04;
+pub const STAR_SIZE: f32 = 24.0;
+pub const STAR_COLOR: (f32, f32, f32) = (1.0, 0.95, 0.3);
+pub const STAR_DURATION: f32 = 5.0;
+pub const STAR_WARNING_TIME: f32 = 1.5;
+pub const STAR_TINT: (f32, f32, f32) = (0.6, 1.0, 1.0);
+pub const STAR_TINT_RATE: f32 = 6.0;
+pub const STAR_SPIN_SPEED: f32 = 3.0;
+
+/// Letter bonus: letters float halfway between two pipes, bobbing up and
+/// down. Collecting the whole word pays out the bonus
+pub const LETTER_CHANCE: f32 = 0.12; // Per pipe pair, while no letter is on screen
+pub const LETTER_SIZE: f32 = 30.0;
+pub const LETTER_COLOR: (f32, f32, f32) = (1.0, 0.55, 0.9);
+pub const LETTER_BOB: (f32, f32) = (12.0, 3.0); // Height and radians per second
+pub const LETTER_BONUS_COINS: u32 = 100;
+pub const LETTER_ICON_SIZE: f32 = 22.0;
+pub const LETTER_ICON_MISSING_ALPHA: f32 = 0.25;
+
+/// Stress-test benchmark (`--bench`)
+pub const BENCH_DURATION: f32 = 30.0; // Real seconds
+pub const BENCH_WARMUP: f32 = 2.0; // Real seconds of startup left out of the report
+pub const BENCH_SPEED: f32 = 4.0; // Game time runs this many times faster
+pub const BENCH_PIPE_SPAWN_DISTANCE: f32 = 15.0;
+pub const BENCH_PARTICLE_SCALE: u32 = 4;
+
+/// Replay verification (`--verify`)
+pub const VERIFY_LOAD_TIMEOUT: f32 = 30.0; // Real seconds to wait for the assets
+
+/// Toast notifications (achievements, playtime reminders)
+pub const TOAST_DURATION: f32 = 2.5;
+pub const TOAST_FADE: f32 = 0.5;
+pub const TOAST_SPACING: f32 = 26.0;
+
+/// Fog: off by default as a difficulty modifier; themes can turn it on with
+/// their own color. It fades in over a gradient of strips before going solid
+pub const FOG_DISTANCE: f32 = 0.0;
+pub const FOG_OPACITY: f32 = 0.95;
+pub const FOG_THEME_DISTANCE: f32 = 280.0;
+pub const FOG_THEME_OPACITY: f32 = 0.6;
+pub const FOG_FADE_WIDTH: f32 = 120.0;
+pub const FOG_GRADIENT_STRIPS: u32 = 12;
+pub const FOG_Z: f32 = 1.7;
+
 /// Edge flash border width for score effect
 pub const SCORE_FLASH_BORDER_WIDTH: f32 = 40.0;
 /// Number of gradient strips for edge flash fade effect
 pub const SCORE_FLASH_GRADIENT_STRIPS: u32 = 8;
 /// Ratio of the edge that stays solid (0.0 to 1.0) before fading starts
 pub const SCORE_FLASH_SOLID_RATIO: f32 = 0.35;
+
+/// Mutators (picked before a run): the big bird's scale, the double speed
+/// mutator's scroll speed scale, and the trail left by the invisible bird
+pub const MUTATOR_BIG_BIRD_SCALE: f32 = 1.5;
+pub const MUTATOR_SPEED_SCALE: f32 = 2.0;
+pub const MUTATOR_TRAIL: &str = "Sparkles";
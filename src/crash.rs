@@ -0,0 +1,378 @@
+//! Error screen for unexpected panics.
+//!
+//! A panic in a system used to end the process, which on iOS (no console)
+//! left nothing to report. Now the panic hook records the message, where it
+//! happened and a backtrace, and writes them to `crash.log` in the save
+//! directory. The main schedule runs under `catch_unwind`: once a frame
+//! panics, the game stops updating and an error screen shows the message
+//! instead. C (or a click or tap) copies the details to the clipboard, and
+//! Escape quits.
+//!
+//! After a crash only the engine's schedules around the game (input,
+//! transforms, text layout) keep running, so the screen stays responsive
+//! whatever state the game was left in.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bevy::camera::visibility::RenderLayers;
+use bevy::ecs::schedule::{ExecutorKind, ScheduleLabel};
+use bevy::prelude::*;
+use bevy::text::{Justify, LineBreak, TextBounds};
+use bevy::window::PrimaryWindow;
+
+use crate::build_info::build_label;
+use crate::save::save_dir;
+
+/// Name of the crash log inside the save directory.
+const CRASH_LOG_FILE_NAME: &str = "crash.log";
+
+/// Render layer of the error screen, away from everything the game draws.
+const CRASH_LAYER: usize = 31;
+
+/// Order of the error screen camera, above the game's cameras.
+const CRASH_CAMERA_ORDER: isize = 100;
+
+/// Distance between the message and the window edges.
+const MARGIN: f32 = 24.0;
+
+/// The last panic seen by the hook, picked up when a frame fails.
+static LAST_PANIC: Mutex<Option<CrashReport>> = Mutex::new(None);
+
+/// Set once the error screen is up; later panics are then expected and
+/// stay quiet.
+static CRASHED: AtomicBool = AtomicBool::new(false);
+
+/// Plugin running the game under a panic guard and showing the error screen.
+pub struct CrashScreenPlugin;
+
+impl Plugin for CrashScreenPlugin {
+    fn build(&self, app: &mut App) {
+        install_panic_hook();
+
+        let mut guarded_main = Schedule::new(GuardedMain);
+        guarded_main.set_executor_kind(ExecutorKind::SingleThreaded);
+        app.add_schedule(guarded_main)
+            .add_systems(GuardedMain, run_guarded_main)
+            .init_schedule(CrashScreen)
+            .add_systems(
+                CrashScreen,
+                (layout_crash_screen, handle_crash_screen_input),
+            );
+        app.main_mut().update_schedule = Some(GuardedMain.intern());
+    }
+}
+
+/// Schedule run every frame in place of `Main`, catching its panics.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct GuardedMain;
+
+/// Schedule of the error screen's systems, run once the game has crashed.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct CrashScreen;
+
+/// Resource describing the panic that stopped the game.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct CrashReport {
+    /// Panic message
+    pub message: String,
+    /// Source location of the panic, if known
+    pub location: String,
+    /// Backtrace captured by the hook
+    pub backtrace: String,
+}
+
+impl CrashReport {
+    /// Returns the full report, as written to the crash log and copied to
+    /// the clipboard.
+    pub fn details(&self) -> String {
+        format!(
+            "Flappy Rust {}\n\npanicked at {}:\n{}\n\nbacktrace:\n{}\n",
+            build_label(),
+            self.location,
+            self.message,
+            self.backtrace
+        )
+    }
+}
+
+/// Marker for the error screen message.
+#[derive(Component)]
+struct CrashText;
+
+/// Installs a panic hook recording each panic and writing the crash log,
+/// before handing over to the default hook.
+fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if CRASHED.load(Ordering::Relaxed) {
+            return;
+        }
+        let report = report_from_hook(info);
+        if let Err(error) = write_crash_log(&report) {
+            eprintln!("Failed to write the crash log: {error}");
+        }
+        if let Ok(mut last_panic) = LAST_PANIC.lock() {
+            *last_panic = Some(report);
+        }
+        default_hook(info);
+    }));
+}
+
+/// Builds a report from the panic hook's information.
+fn report_from_hook(info: &PanicHookInfo) -> CrashReport {
+    CrashReport {
+        message: panic_message(info.payload()),
+        location: info
+            .location()
+            .map_or_else(|| "an unknown location".to_string(), ToString::to_string),
+        backtrace: Backtrace::force_capture().to_string(),
+    }
+}
+
+/// Extracts the message of a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Returns the path of the crash log.
+pub fn crash_log_path() -> PathBuf {
+    save_dir().join(CRASH_LOG_FILE_NAME)
+}
+
+/// Writes a report to the crash log, replacing the previous one.
+fn write_crash_log(report: &CrashReport) -> std::io::Result<()> {
+    let path = crash_log_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, report.details())
+}
+
+/// Runs `Main` until a frame panics, then the error screen.
+fn run_guarded_main(world: &mut World, mut crashed: Local<bool>) {
+    if !*crashed {
+        let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| world.run_schedule(Main)))
+        else {
+            return;
+        };
+        *crashed = true;
+        CRASHED.store(true, Ordering::Relaxed);
+
+        let report = LAST_PANIC
+            .lock()
+            .ok()
+            .and_then(|mut last_panic| last_panic.take())
+            .unwrap_or_else(|| CrashReport {
+                message: panic_message(payload.as_ref()),
+                location: "an unknown location".to_string(),
+                backtrace: String::new(),
+            });
+        error!("The game crashed: {}", report.message);
+        spawn_crash_screen(world, &report);
+        world.insert_resource(report);
+    }
+
+    // The game's schedules are skipped; whichever of these the panic took
+    // down (a panicking schedule isn't put back) is skipped too
+    let labels = [
+        First.intern(),
+        PreUpdate.intern(),
+        CrashScreen.intern(),
+        PostUpdate.intern(),
+        Last.intern(),
+    ];
+    for label in labels {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| world.try_run_schedule(label)));
+    }
+}
+
+/// Spawns the error screen: a camera drawing over the game with nothing but
+/// the message.
+fn spawn_crash_screen(world: &mut World, report: &CrashReport) {
+    let hints = if cfg!(target_os = "ios") {
+        "Tap to copy the details"
+    } else if cfg!(target_os = "android") {
+        ""
+    } else {
+        "Press C or click to copy the details, Escape to quit"
+    };
+    let text = format!(
+        "Something went wrong\n\n{}\n\nat {}\n\nA crash log was saved to {}\n\n{hints}",
+        report.message,
+        report.location,
+        crash_log_path().display()
+    );
+
+    world.spawn((
+        Camera2d,
+        Camera {
+            order: CRASH_CAMERA_ORDER,
+            clear_color: ClearColorConfig::Custom(Color::srgb(0.12, 0.04, 0.04)),
+            ..default()
+        },
+        RenderLayers::layer(CRASH_LAYER),
+    ));
+    world.spawn((
+        Text2d::new(text),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new(Justify::Center, LineBreak::WordBoundary),
+        TextBounds::default(),
+        RenderLayers::layer(CRASH_LAYER),
+        CrashText,
+    ));
+}
+
+/// Wraps the message to the window width.
+fn layout_crash_screen(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut text_query: Query<&mut TextBounds, With<CrashText>>,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    let width = (window.width() - 2.0 * MARGIN).max(MARGIN);
+    for mut bounds in text_query.iter_mut() {
+        if bounds.width != Some(width) {
+            *bounds = TextBounds::new_horizontal(width);
+        }
+    }
+}
+
+/// Copies the details on C, a click or a tap, and quits on Escape.
+fn handle_crash_screen_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    report: Res<CrashReport>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyC)
+        || mouse_input.just_pressed(MouseButton::Left)
+        || touches.any_just_pressed()
+    {
+        match clipboard::copy(&report.details()) {
+            Ok(()) => info!("Copied the crash details to the clipboard"),
+            Err(error) => warn!("Failed to copy the crash details: {error}"),
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        exit.write(AppExit::error());
+    }
+}
+
+#[cfg(target_os = "ios")]
+mod clipboard {
+    use std::ffi::{CString, c_char, c_void};
+
+    #[link(name = "objc", kind = "dylib")]
+    unsafe extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    type Id = *mut c_void;
+    type Sel = *mut c_void;
+
+    /// Sets the text of UIKit's general `UIPasteboard`.
+    pub fn copy(text: &str) -> Result<(), String> {
+        let text = CString::new(text.replace('\0', "")).map_err(|error| error.to_string())?;
+
+        // SAFETY: `objc_msgSend` is called through function pointer types
+        // matching each message's signature, on classes and selectors that
+        // exist in UIKit and Foundation since iOS 3; the string is
+        // autoreleased.
+        unsafe {
+            let send: unsafe extern "C" fn(Id, Sel) -> Id =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            let send_ptr: unsafe extern "C" fn(Id, Sel, *const c_char) -> Id =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            let send_id: unsafe extern "C" fn(Id, Sel, Id) =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+
+            let pasteboard_class = objc_getClass(c"UIPasteboard".as_ptr());
+            let string_class = objc_getClass(c"NSString".as_ptr());
+            if pasteboard_class.is_null() || string_class.is_null() {
+                return Err("UIPasteboard is unavailable".to_string());
+            }
+            let pasteboard = send(
+                pasteboard_class,
+                sel_registerName(c"generalPasteboard".as_ptr()),
+            );
+            let string = send_ptr(
+                string_class,
+                sel_registerName(c"stringWithUTF8String:".as_ptr()),
+                text.as_ptr(),
+            );
+            if pasteboard.is_null() || string.is_null() {
+                return Err("UIPasteboard is unavailable".to_string());
+            }
+            send_id(pasteboard, sel_registerName(c"setString:".as_ptr()), string);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "android")]
+mod clipboard {
+    /// Android has no clipboard access here; the crash log has the details.
+    pub fn copy(_text: &str) -> Result<(), String> {
+        Err("no clipboard on this platform".to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+mod clipboard {
+    /// Sets the text of the system clipboard.
+    pub fn copy(text: &str) -> Result<(), String> {
+        arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(text))
+            .map_err(|error| error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panic_messages_are_extracted() {
+        let payload = panic::catch_unwind(|| panic!("static message")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "static message");
+
+        let payload = panic::catch_unwind(|| panic!("formatted {}", 42)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "formatted 42");
+
+        let payload = panic::catch_unwind(|| panic::panic_any(7_u8)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "unknown panic");
+    }
+
+    #[test]
+    fn details_include_the_build_and_location() {
+        let report = CrashReport {
+            message: "index out of bounds".to_string(),
+            location: "src/systems/pipes.rs:10:5".to_string(),
+            backtrace: "0: main".to_string(),
+        };
+        let details = report.details();
+        assert!(details.contains(&build_label()));
+        assert!(details.contains("panicked at src/systems/pipes.rs:10:5:\nindex out of bounds"));
+        assert!(details.ends_with("0: main\n"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Opt-in crash reports.
+//!
+//! Every session leaves a marker file in the save directory while it runs,
+//! removed on a clean exit (or when a phone backgrounds the app, since the
+//! OS may then end it without warning). A marker found at launch means the
+//! previous session crashed or was killed.
+//!
+//! When `FLAPPY_CRASH_REPORTS` is set, such a launch bundles the previous
+//! session's log, the crash log if that session wrote one, the settings, and
+//! some system information into a report. The variable holds either a
+//! directory, where the report is saved as `crash-report-<timestamp>.txt`,
+//! or an `http://` endpoint the report is posted to as plain text.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+
+use crate::build_info::build_label;
+use crate::crash::crash_log_path;
+use crate::logging::last_session_log_path;
+use crate::net;
+use crate::save::{is_read_only, now_ms, save_dir};
+use crate::settings::Settings;
+
+/// Environment variable that enables crash reports and selects their
+/// destination.
+pub const CRASH_REPORTS_ENV_VAR: &str = "FLAPPY_CRASH_REPORTS";
+
+/// Name of the running-session marker inside the save directory.
+const SESSION_MARKER_FILE_NAME: &str = "session.running";
+
+/// Where crash reports are delivered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportSink {
+    /// Save each report as a file in a directory.
+    Directory(PathBuf),
+    /// Post each report to an HTTP endpoint.
+    Endpoint(String),
+}
+
+impl ReportSink {
+    /// Reads the destination from the environment, or `None` if not opted in
+    /// (or the destination can't be used).
+    pub fn from_env() -> Option<Self> {
+        net::target_from_env(CRASH_REPORTS_ENV_VAR, "Crash reports", Self::from_target)
+    }
+
+    /// Interprets a target string as an endpoint URL or a directory.
+    ///
+    /// Only `http://` endpoints can be posted to, so `https://` targets are
+    /// refused rather than losing every report.
+    pub fn from_target(target: &str) -> Result<Self, String> {
+        if target.starts_with("https://") {
+            Err("https is not supported, use an http:// endpoint or a directory".to_string())
+        } else if target.starts_with("http://") {
+            Ok(Self::Endpoint(target.to_string()))
+        } else {
+            Ok(Self::Directory(PathBuf::from(target)))
+        }
+    }
+
+    /// Delivers a report, logging the outcome instead of panicking.
+    fn deliver(&self, report: &str) {
+        let result = match self {
+            Self::Directory(dir) => {
+                let path = dir.join(format!("crash-report-{}.txt", now_ms()));
+                fs::create_dir_all(dir)
+                    .and_then(|()| fs::write(&path, report))
+                    .map(|()| path.display().to_string())
+            }
+            Self::Endpoint(url) => {
+                net::post(url, "text/plain; charset=utf-8", report.as_bytes()).map(|()| url.clone())
+            }
+        };
+
+        match result {
+            Ok(destination) => info!("Sent a crash report to {destination}"),
+            Err(error) => warn!("Failed to deliver the crash report to {self:?}: {error}"),
+        }
+    }
+}
+
+/// Resource holding the running-session marker, removed on a clean exit.
+#[derive(Resource)]
+pub struct SessionMarker {
+    /// Path of the marker file
+    path: PathBuf,
+    /// Whether the marker is currently on disk
+    present: bool,
+}
+
+impl SessionMarker {
+    /// Places the marker for this session, returning it along with the
+    /// previous session's marker time if that one never removed its own.
+    pub fn begin() -> (Self, Option<SystemTime>) {
+        let path = save_dir().join(SESSION_MARKER_FILE_NAME);
+        // A read-only process isn't a session of its own
+        if is_read_only() {
+            return (
+                Self {
+                    path,
+                    present: false,
+                },
+                None,
+            );
+        }
+        let previous = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let mut marker = Self {
+            path,
+            present: false,
+        };
+        marker.place();
+        (marker, previous)
+    }
+
+    /// Writes the marker file.
+    pub fn place(&mut self) {
+        if self.present || is_read_only() {
+            return;
+        }
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&self.path, build_label()));
+        match result {
+            Ok(()) => self.present = true,
+            Err(error) => warn!("Failed to write {}: {error}", self.path.display()),
+        }
+    }
+
+    /// Removes the marker file: the session may now end without a report.
+    pub fn clear(&mut self) {
+        if !self.present {
+            return;
+        }
+        if let Err(error) = fs::remove_file(&self.path) {
+            warn!("Failed to remove {}: {error}", self.path.display());
+        }
+        self.present = false;
+    }
+}
+
+/// Bundles a report on the previous session, which started at `started`.
+pub fn build_report(started: SystemTime) -> String {
+    let crash_log = fs::read_to_string(crash_log_path())
+        .ok()
+        .filter(|_| modified_since(&crash_log_path(), started));
+    let settings = ron::ser::to_string_pretty(&Settings::load(), ron::ser::PrettyConfig::default())
+        .unwrap_or_else(|error| format!("(unavailable: {error})"));
+    let session_log = fs::read_to_string(last_session_log_path())
+        .unwrap_or_else(|error| format!("(unavailable: {error})"));
+
+    format_report(&[
+        ("System", system_info()),
+        (
+            "Crash log",
+            crash_log.unwrap_or_else(|| "(none: the game was closed or killed)".to_string()),
+        ),
+        ("Settings", settings),
+        ("Session log", session_log),
+    ])
+}
+
+/// Sends a report from a background thread, so a slow endpoint doesn't hold
+/// up the launch.
+pub fn send_report(sink: ReportSink, report: String) {
+    thread::spawn(move || sink.deliver(&report));
+}
+
+/// Returns whether a file was modified at or after `time`.
+fn modified_since(path: &Path, time: SystemTime) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| modified >= time)
+}
+
+/// Describes the build and the machine the game runs on.
+fn system_info() -> String {
+    let cpus = thread::available_parallelism().map_or(0, |count| count.get());
+    format!(
+        "Flappy Rust {}\nOS: {} ({})\nArchitecture: {}\nCPUs: {cpus}",
+        build_label(),
+        std::env::consts::OS,
+        std::env::consts::FAMILY,
+        std::env::consts::ARCH
+    )
+}
+
+/// Lays out the report's sections under titled headers.
+fn format_report(sections: &[(&str, String)]) -> String {
+    let mut report = String::from("Flappy Rust crash report\n");
+    for (title, body) in sections {
+        report.push_str(&format!("\n== {title} ==\n{}\n", body.trim_end()));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sink_from_target_detects_endpoints() {
+        assert_eq!(
+            ReportSink::from_target("http://reports.example.com/flappy"),
+            Ok(ReportSink::Endpoint(
+                "http://reports.example.com/flappy".to_string()
+            ))
+        );
+        assert_eq!(
+            ReportSink::from_target("crash-reports"),
+            Ok(ReportSink::Directory(PathBuf::from("crash-reports")))
+        );
+        assert!(ReportSink::from_target("https://reports.example.com/flappy").is_err());
+    }
+
+    #[test]
+    fn report_lists_each_section() {
+        let report = format_report(&[
+            ("System", "OS: linux".to_string()),
+            ("Session log", "INFO flappy_rust: Bird died\n".to_string()),
+        ]);
+        assert_eq!(
+            report,
+            "Flappy Rust crash report\n\
+             \n== System ==\nOS: linux\n\
+             \n== Session log ==\nINFO flappy_rust: Bird died\n"
+        );
+    }
+}
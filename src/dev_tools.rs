@@ -0,0 +1,143 @@
+//! Debug panel with cheats (behind the `dev-tools` feature).
+//!
+//! F4 shows a panel in the top-right corner listing the cheats, which are
+//! toggled with the keys it shows while it is open: invincibility, frozen
+//! pipes, frame stepping (time advances one frame per press), and teleporting
+//! the bird into the next gap. The cheats live in the `DebugTools` resource,
+//! which the collision, pipe, and bird systems read when it exists. A run
+//! during which any cheat was on is marked as cheated, which keeps it out of
+//! the best scores, achievements, ghosts and leaderboard.
+
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::components::PipePair;
+use crate::replay::ReplayRecorder;
+use crate::resources::{DebugTools, GameViewport};
+use crate::settings::on_off;
+
+/// Distance between the panel and the screen edges.
+const MARGIN: f32 = 8.0;
+
+/// Plugin adding the debug panel and its cheats.
+pub struct DevToolsPlugin;
+
+impl Plugin for DevToolsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugTools>()
+            .add_systems(Startup, spawn_debug_panel)
+            .add_systems(PreUpdate, finish_frame_step)
+            .add_systems(
+                Update,
+                (handle_debug_keys, mark_cheated_runs, update_debug_panel).chain(),
+            );
+    }
+}
+
+/// Marker for the debug panel text.
+#[derive(Component)]
+struct DebugPanel;
+
+/// Spawns the (hidden) debug panel.
+fn spawn_debug_panel(mut commands: Commands) {
+    commands.spawn((
+        Text2d::default(),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.8, 0.4)),
+        Anchor::TOP_RIGHT,
+        Transform::from_xyz(0.0, 0.0, 9.0),
+        Visibility::Hidden,
+        DebugPanel,
+    ));
+}
+
+/// Pauses time again once a stepped frame has run.
+fn finish_frame_step(mut debug: ResMut<DebugTools>, mut time: ResMut<Time<Virtual>>) {
+    if debug.step_pending {
+        debug.step_pending = false;
+        time.pause();
+    }
+}
+
+/// Toggles the panel on F4 and, while it is open, the cheats on F5-F9.
+fn handle_debug_keys(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut debug: ResMut<DebugTools>,
+    mut time: ResMut<Time<Virtual>>,
+    mut panel_query: Query<&mut Visibility, With<DebugPanel>>,
+    pair_query: Query<(&Transform, &PipePair)>,
+) {
+    let Ok(mut visibility) = panel_query.single_mut() else {
+        return;
+    };
+    if keyboard_input.just_pressed(KeyCode::F4) {
+        *visibility = if *visibility == Visibility::Hidden {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F5) {
+        debug.invincible = !debug.invincible;
+    }
+    if keyboard_input.just_pressed(KeyCode::F6) {
+        debug.freeze_pipes = !debug.freeze_pipes;
+    }
+    if keyboard_input.just_pressed(KeyCode::F7) {
+        debug.frame_stepping = !debug.frame_stepping;
+        if debug.frame_stepping {
+            time.pause();
+        } else {
+            time.unpause();
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::F8) && debug.frame_stepping {
+        time.unpause();
+        debug.step_pending = true;
+    }
+    if keyboard_input.just_pressed(KeyCode::F9) {
+        // Into the next unscored gap, or the middle of the screen
+        let next_gap = pair_query
+            .iter()
+            .filter(|(_, pair)| !pair.scored)
+            .min_by(|(a, _), (b, _)| a.translation.x.total_cmp(&b.translation.x))
+            .map_or(0.0, |(_, pair)| pair.gap_y);
+        debug.teleport = Some(next_gap);
+    }
+}
+
+/// Marks the current run as cheated while any cheat is on.
+fn mark_cheated_runs(debug: Res<DebugTools>, mut recorder: ResMut<ReplayRecorder>) {
+    if debug.is_cheating() && !recorder.cheated {
+        info!("Cheat used: this run won't count");
+        recorder.cheated = true;
+    }
+}
+
+/// Lists the cheats and their state, in the top-right corner.
+fn update_debug_panel(
+    debug: Res<DebugTools>,
+    viewport: Res<GameViewport>,
+    mut panel_query: Query<(&mut Text2d, &mut Transform, &Visibility), With<DebugPanel>>,
+) {
+    for (mut text, mut transform, visibility) in panel_query.iter_mut() {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        transform.translation.x = viewport.half_width() - MARGIN;
+        transform.translation.y = viewport.half_height() - MARGIN;
+        text.0 = format!(
+            "F5 Invincible: {}\nF6 Freeze pipes: {}\nF7 Frame step: {}\nF8 Next frame\nF9 Teleport to gap",
+            on_off(debug.invincible),
+            on_off(debug.freeze_pipes),
+            on_off(debug.frame_stepping),
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! Difficulty curve plot (behind the `dev-tools` feature).
+//!
+//! F2 opens a screen plotting the pipe gap range, scroll speed and spawn
+//! interval against the score, from the current `GameConfig`, so changes to
+//! the difficulty curve can be checked without playing through it. The plot
+//! follows the configuration as it changes: hot reloads, tuning panel edits
+//! and the `--difficulty` preset all show up straight away.
+
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::config::{GameConfig, PipeConfig};
+use crate::resources::GameViewport;
+
+/// Fraction of the screen width taken by the charts.
+const PLOT_WIDTH: f32 = 0.7;
+/// Fraction of the screen height taken by each chart.
+const CHART_HEIGHT: f32 = 0.22;
+/// Vertical distance between two charts, as a fraction of the screen height.
+const CHART_SPACING: f32 = 0.29;
+/// Draw order of the plot, above the game and the HUD effects.
+const PLOT_Z: f32 = 20.0;
+
+const AXIS_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+const GAP_MIN_COLOR: Color = Color::srgb(1.0, 0.55, 0.2);
+const GAP_MAX_COLOR: Color = Color::srgb(0.4, 0.9, 0.4);
+const SPEED_COLOR: Color = Color::srgb(0.4, 0.7, 1.0);
+const SPAWN_COLOR: Color = Color::srgb(0.9, 0.5, 0.9);
+
+/// Plugin adding the difficulty curve plot.
+pub struct DifficultyPlotPlugin;
+
+impl Plugin for DifficultyPlotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_difficulty_plot,
+                refresh_difficulty_plot
+                    .run_if(resource_changed::<GameConfig>.or(resource_changed::<GameViewport>)),
+                draw_difficulty_plot.run_if(any_with_component::<DifficultyPlot>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Marker for the entities of the plot screen.
+#[derive(Component)]
+struct DifficultyPlot;
+
+/// Difficulty values at one score.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DifficultySample {
+    /// Score the values apply at
+    score: u32,
+    /// Smallest gap size, in pixels
+    gap_min: f32,
+    /// Largest gap size, in pixels
+    gap_max: f32,
+    /// Scroll speed, in pixels per second
+    scroll_speed: f32,
+    /// Distance between pipe spawns, in pixels
+    spawn_distance: f32,
+}
+
+/// A chart of one or more curves sharing a scale.
+struct Chart {
+    /// Name and unit shown above the chart
+    title: &'static str,
+    /// Values at each sampled score, with the color of their curve
+    series: Vec<(Vec<f32>, Color)>,
+}
+
+impl Chart {
+    /// Returns the top of the chart's scale, a little above its largest value.
+    fn scale_max(&self) -> f32 {
+        let max = self
+            .series
+            .iter()
+            .flat_map(|(values, _)| values.iter().copied())
+            .fold(0.0, f32::max);
+        if max > 0.0 { max * 1.1 } else { 1.0 }
+    }
+}
+
+/// Returns the last score plotted: half again the score of maximum
+/// difficulty, so the flat end of the curve is visible.
+fn plot_max_score(pipes: &PipeConfig) -> u32 {
+    (pipes.gap_scale_score + pipes.gap_scale_score / 2).max(10)
+}
+
+/// Samples the difficulty values at every score up to `plot_max_score`.
+fn sample_curve(pipes: &PipeConfig) -> Vec<DifficultySample> {
+    (0..=plot_max_score(pipes))
+        .map(|score| {
+            let (gap_min, gap_max) = pipes.gap_range(score);
+            DifficultySample {
+                score,
+                gap_min,
+                gap_max,
+                scroll_speed: pipes.scroll_speed,
+                spawn_distance: pipes.spawn_distance,
+            }
+        })
+        .collect()
+}
+
+/// Splits the samples into the three charts, top to bottom.
+fn charts(samples: &[DifficultySample]) -> [Chart; 3] {
+    let series = |value: fn(&DifficultySample) -> f32| samples.iter().map(value).collect();
+    [
+        Chart {
+            title: "Gap size (px): smallest and largest",
+            series: vec![
+                (series(|sample| sample.gap_min), GAP_MIN_COLOR),
+                (series(|sample| sample.gap_max), GAP_MAX_COLOR),
+            ],
+        },
+        Chart {
+            title: "Scroll speed (px/s)",
+            series: vec![(series(|sample| sample.scroll_speed), SPEED_COLOR)],
+        },
+        Chart {
+            title: "Pipe spacing (px)",
+            series: vec![(series(|sample| sample.spawn_distance), SPAWN_COLOR)],
+        },
+    ]
+}
+
+/// Returns the area of the chart at `index`, counted from the top.
+fn chart_rect(index: usize, viewport: &GameViewport) -> Rect {
+    let width = viewport.width * PLOT_WIDTH;
+    let height = viewport.height * CHART_HEIGHT;
+    let center = Vec2::new(
+        0.0,
+        viewport.height * (CHART_SPACING - CHART_SPACING * index as f32) - 10.0,
+    );
+    Rect::from_center_size(center, Vec2::new(width, height))
+}
+
+/// Opens or closes the plot screen on F2.
+fn toggle_difficulty_plot(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    config: Res<GameConfig>,
+    viewport: Res<GameViewport>,
+    plot_query: Query<Entity, With<DifficultyPlot>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F2) {
+        return;
+    }
+    if plot_query.is_empty() {
+        spawn_plot(&mut commands, &config.pipes, &viewport);
+    } else {
+        for entity in plot_query.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Rebuilds the open plot screen when the configuration or window changes.
+fn refresh_difficulty_plot(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    viewport: Res<GameViewport>,
+    plot_query: Query<Entity, With<DifficultyPlot>>,
+) {
+    if plot_query.is_empty() {
+        return;
+    }
+    for entity in plot_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    spawn_plot(&mut commands, &config.pipes, &viewport);
+}
+
+/// Spawns the backdrop and labels of the plot screen.
+fn spawn_plot(commands: &mut Commands, pipes: &PipeConfig, viewport: &GameViewport) {
+    commands.spawn((
+        Sprite::from_color(
+            Color::srgba(0.0, 0.0, 0.0, 0.85),
+            Vec2::new(viewport.width, viewport.height),
+        ),
+        Transform::from_xyz(0.0, 0.0, PLOT_Z),
+        DifficultyPlot,
+    ));
+
+    let mut label = |text: String, position: Vec2, anchor: Anchor| {
+        commands.spawn((
+            Text2d::new(text),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            anchor,
+            Transform::from_translation(position.extend(PLOT_Z + 1.0)),
+            DifficultyPlot,
+        ));
+    };
+
+    label(
+        "Difficulty curve (F2 to close)".to_string(),
+        Vec2::new(0.0, viewport.half_height() - 12.0),
+        Anchor::TOP_CENTER,
+    );
+    let samples = sample_curve(pipes);
+    for (index, chart) in charts(&samples).iter().enumerate() {
+        let rect = chart_rect(index, viewport);
+        label(
+            chart.title.to_string(),
+            Vec2::new(rect.min.x, rect.max.y + 4.0),
+            Anchor::BOTTOM_LEFT,
+        );
+        label(
+            format!("{:.1}", chart.scale_max()),
+            Vec2::new(rect.min.x - 6.0, rect.max.y),
+            Anchor::CENTER_RIGHT,
+        );
+        label(
+            "0".to_string(),
+            Vec2::new(rect.min.x - 6.0, rect.min.y),
+            Anchor::CENTER_RIGHT,
+        );
+    }
+
+    let bottom = chart_rect(2, viewport);
+    label(
+        "score 0".to_string(),
+        Vec2::new(bottom.min.x, bottom.min.y - 4.0),
+        Anchor::TOP_LEFT,
+    );
+    label(
+        format!("max difficulty at {}", pipes.gap_scale_score),
+        Vec2::new(
+            score_x(pipes.gap_scale_score, plot_max_score(pipes), bottom),
+            bottom.min.y - 4.0,
+        ),
+        Anchor::TOP_CENTER,
+    );
+    label(
+        format!("score {}", plot_max_score(pipes)),
+        Vec2::new(bottom.max.x, bottom.min.y - 4.0),
+        Anchor::TOP_RIGHT,
+    );
+}
+
+/// Returns the x position of `score` across a chart.
+fn score_x(score: u32, max_score: u32, rect: Rect) -> f32 {
+    rect.min.x + rect.width() * score as f32 / max_score.max(1) as f32
+}
+
+/// Draws the axes and curves of the open plot screen.
+fn draw_difficulty_plot(mut gizmos: Gizmos, config: Res<GameConfig>, viewport: Res<GameViewport>) {
+    let pipes = &config.pipes;
+    let max_score = plot_max_score(pipes);
+    let samples = sample_curve(pipes);
+    for (index, chart) in charts(&samples).iter().enumerate() {
+        let rect = chart_rect(index, &viewport);
+        gizmos.rect_2d(rect.center(), rect.size(), AXIS_COLOR);
+        let max_difficulty_x = score_x(pipes.gap_scale_score, max_score, rect);
+        gizmos.line_2d(
+            Vec2::new(max_difficulty_x, rect.min.y),
+            Vec2::new(max_difficulty_x, rect.max.y),
+            AXIS_COLOR.with_alpha(0.4),
+        );
+
+        let scale_max = chart.scale_max();
+        for (values, color) in &chart.series {
+            let points = samples.iter().zip(values).map(|(sample, value)| {
+                Vec2::new(
+                    score_x(sample.score, max_score, rect),
+                    rect.min.y + rect.height() * value / scale_max,
+                )
+            });
+            gizmos.linestrip_2d(points, *color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_is_sampled_past_max_difficulty() {
+        let pipes = PipeConfig::default();
+        let samples = sample_curve(&pipes);
+        assert_eq!(samples.len() as u32, plot_max_score(&pipes) + 1);
+        assert!(plot_max_score(&pipes) > pipes.gap_scale_score);
+
+        let first = samples[0];
+        assert_eq!(
+            (first.gap_min, first.gap_max),
+            (pipes.gap_start_min, pipes.gap_start_max)
+        );
+        let last = samples[samples.len() - 1];
+        assert_eq!((last.gap_min, last.gap_max), (pipes.gap_end, pipes.gap_end));
+        assert_eq!(last.scroll_speed, pipes.scroll_speed);
+    }
+}
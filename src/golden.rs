@@ -0,0 +1,201 @@
+//! Simulation snapshot tests.
+//!
+//! Each scenario plays the headless game for a fixed number of 60 Hz frames
+//! with a known seed and scripted flaps, through the same machinery as
+//! replay verification, then compares the bird, the pipe layout and the
+//! score against a snapshot stored in `tests/golden/`. A change to the
+//! physics, the spawner or the scoring shows up as a failing snapshot.
+//!
+//! When a change is intended, rewrite the snapshots with
+//! `FLAPPY_BLESS=1 cargo test golden` and commit them with it.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Bird, PipePair};
+use crate::replay::Replay;
+use crate::resources::{Invincibility, Score};
+use crate::states::GameState;
+use crate::verification_app;
+use crate::verify::{ReplayVerifier, VerifyError};
+
+/// Game time of each frame, in nanoseconds.
+const FRAME_NANOS: u32 = 16_666_667;
+
+/// Most updates a scenario may take, loading included.
+const MAX_UPDATES: usize = 5_000;
+
+/// Scenarios share the global logger and read-only flag, so they run one at
+/// a time.
+static SCENARIO_LOCK: Mutex<()> = Mutex::new(());
+
+/// State of the game at the end of a scenario, rounded to a tenth of a pixel
+/// so snapshots don't churn on float noise.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Snapshot {
+    /// Whether the bird crashed before the last frame
+    crashed: bool,
+    /// Frames played
+    frames: usize,
+    /// Final score
+    score: u32,
+    /// Height of the bird
+    bird_y: f32,
+    /// Vertical velocity of the bird
+    bird_velocity: f32,
+    /// Position and gap height of each pipe pair, left to right
+    pipes: Vec<(f32, f32)>,
+}
+
+/// A scripted run.
+struct Scenario {
+    /// Name of the snapshot file
+    name: &'static str,
+    /// Master seed of the run
+    seed: u64,
+    /// Frames to play, unless the bird crashes first
+    frames: usize,
+    /// Frames the bird flaps on
+    flap_frames: Vec<u32>,
+    /// Whether the bird flies through the pipes rather than crashing on them
+    invincible: bool,
+}
+
+impl Scenario {
+    /// Plays the scenario and returns the state it ends in.
+    fn play(&self) -> Snapshot {
+        let _guard = SCENARIO_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let seconds = FRAME_NANOS as f32 / 1e9;
+        let replay = Replay {
+            seed: self.seed,
+            frames: vec![FRAME_NANOS; self.frames],
+            flaps: self
+                .flap_frames
+                .iter()
+                .map(|frame| *frame as f32 * seconds)
+                .collect(),
+            flap_frames: self.flap_frames.clone(),
+            duration: self.frames as f32 * seconds,
+            ..default()
+        };
+        let verdict = Arc::new(Mutex::new(None));
+        let mut app = verification_app(&replay, Arc::clone(&verdict));
+        if self.invincible {
+            app.add_systems(PreUpdate, stay_invincible);
+        }
+        app.finish();
+        app.cleanup();
+
+        let verdict = (0..MAX_UPDATES)
+            .find_map(|_| {
+                app.update();
+                verdict.lock().ok().and_then(|mut slot| slot.take())
+            })
+            .unwrap_or_else(|| panic!("{}: the run never finished", self.name));
+        if let Err(VerifyError::Unavailable(reason)) = &verdict {
+            panic!("{}: {reason}", self.name);
+        }
+
+        let world = app.world_mut();
+        let frames = world.resource::<ReplayVerifier>().frame + 1;
+        let score = world.resource::<Score>().0;
+        let (bird_y, bird_velocity) = world
+            .query::<(&Bird, &Transform)>()
+            .iter(world)
+            .map(|(bird, transform)| (round(transform.translation.y), round(bird.velocity)))
+            .next()
+            .unwrap_or_else(|| panic!("{}: no bird", self.name));
+        let mut pipes: Vec<(f32, f32)> = world
+            .query::<(&PipePair, &Transform)>()
+            .iter(world)
+            .map(|(pair, transform)| (round(transform.translation.x), round(pair.gap_y)))
+            .collect();
+        pipes.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Snapshot {
+            crashed: verdict != Err(VerifyError::NoCrash),
+            frames: frames.min(self.frames),
+            score,
+            bird_y,
+            bird_velocity,
+            pipes,
+        }
+    }
+
+    /// Plays the scenario and compares it with its stored snapshot, or
+    /// stores it when blessing.
+    fn check(&self) {
+        let snapshot = self.play();
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/golden")
+            .join(format!("{}.ron", self.name));
+
+        if std::env::var_os("FLAPPY_BLESS").is_some() {
+            let contents = ron::ser::to_string_pretty(&snapshot, ron::ser::PrettyConfig::default())
+                .unwrap_or_else(|error| panic!("{}: {error}", self.name));
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            fs::write(&path, contents + "\n")
+                .unwrap_or_else(|error| panic!("{}: {error}", path.display()));
+            return;
+        }
+
+        let contents = fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!(
+                "{} is missing; create it with `FLAPPY_BLESS=1 cargo test golden`",
+                path.display()
+            )
+        });
+        let golden: Snapshot =
+            ron::from_str(&contents).unwrap_or_else(|error| panic!("{}: {error}", path.display()));
+        assert_eq!(
+            snapshot, golden,
+            "{} changed; if intended, rewrite it with `FLAPPY_BLESS=1 cargo test golden`",
+            self.name
+        );
+    }
+}
+
+/// Keeps the bird invincible (without shattering pipes) while playing.
+fn stay_invincible(state: Res<State<GameState>>, mut invincibility: ResMut<Invincibility>) {
+    if *state.get() == GameState::Playing {
+        invincibility.grant(1.0, false);
+    }
+}
+
+/// Rounds to a tenth.
+fn round(value: f32) -> f32 {
+    (value * 10.0).round() / 10.0
+}
+
+#[test]
+fn bird_falls_to_the_ground() {
+    Scenario {
+        name: "falls_to_the_ground",
+        seed: 7,
+        frames: 300,
+        flap_frames: Vec::new(),
+        invincible: false,
+    }
+    .check();
+}
+
+#[test]
+fn bird_hovers_through_the_pipes() {
+    Scenario {
+        name: "hovers_through_the_pipes",
+        seed: 42,
+        frames: 600,
+        flap_frames: (0..600).step_by(52).collect(),
+        invincible: true,
+    }
+    .check();
+}
@@ -0,0 +1,156 @@
+//! Haptic feedback on mobile devices.
+//!
+//! A small platform abstraction: `Haptics::play` triggers an impact of the
+//! given strength through UIKit's `UIImpactFeedbackGenerator` on iOS and the
+//! `Vibrator` service on Android, and does nothing elsewhere. Platform APIs
+//! must be called from the main thread, so `Haptics` is a non-send resource.
+
+/// Strength of a haptic impact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HapticStrength {
+    /// A light tap (flaps)
+    Light,
+    /// A medium tap (scoring)
+    Medium,
+    /// A heavy thud (crashing)
+    Heavy,
+}
+
+/// Non-send resource triggering haptic feedback on the current platform.
+#[derive(Default)]
+pub struct Haptics;
+
+impl Haptics {
+    /// Triggers an impact, if the platform supports haptics.
+    pub fn play(&self, strength: HapticStrength) {
+        platform::impact(strength);
+    }
+}
+
+#[cfg(target_os = "ios")]
+mod platform {
+    use std::ffi::{c_char, c_long, c_void};
+
+    use super::HapticStrength;
+
+    #[link(name = "objc", kind = "dylib")]
+    unsafe extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+    }
+
+    type Id = *mut c_void;
+    type Sel = *mut c_void;
+
+    /// Plays a `UIImpactFeedbackGenerator` impact.
+    pub fn impact(strength: HapticStrength) {
+        // `UIImpactFeedbackStyle`: light, medium, heavy
+        let style: c_long = match strength {
+            HapticStrength::Light => 0,
+            HapticStrength::Medium => 1,
+            HapticStrength::Heavy => 2,
+        };
+
+        // SAFETY: `objc_msgSend` is called through function pointer types
+        // matching each message's signature, on classes and selectors that
+        // exist in UIKit since iOS 10; the generator is released after use.
+        unsafe {
+            let send: unsafe extern "C" fn(Id, Sel) -> Id =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+            let send_style: unsafe extern "C" fn(Id, Sel, c_long) -> Id =
+                std::mem::transmute(objc_msgSend as unsafe extern "C" fn());
+
+            let class = objc_getClass(c"UIImpactFeedbackGenerator".as_ptr());
+            if class.is_null() {
+                return;
+            }
+            let generator = send(class, sel_registerName(c"alloc".as_ptr()));
+            let generator = send_style(
+                generator,
+                sel_registerName(c"initWithStyle:".as_ptr()),
+                style,
+            );
+            if generator.is_null() {
+                return;
+            }
+            send(generator, sel_registerName(c"impactOccurred".as_ptr()));
+            send(generator, sel_registerName(c"release".as_ptr()));
+        }
+    }
+}
+
+#[cfg(target_os = "android")]
+mod platform {
+    use bevy::log::warn;
+    use jni::JavaVM;
+    use jni::objects::{JObject, JValue};
+
+    use super::HapticStrength;
+
+    /// Vibrates through the `Vibrator` system service.
+    ///
+    /// Requires the `android.permission.VIBRATE` permission.
+    pub fn impact(strength: HapticStrength) {
+        // (duration in milliseconds, amplitude from 1 to 255)
+        let (millis, amplitude) = match strength {
+            HapticStrength::Light => (10, 60),
+            HapticStrength::Medium => (20, 140),
+            HapticStrength::Heavy => (40, 255),
+        };
+        if let Err(error) = vibrate(millis, amplitude) {
+            warn!("Haptic feedback failed: {error}");
+        }
+    }
+
+    fn vibrate(millis: i64, amplitude: i32) -> jni::errors::Result<()> {
+        let context = ndk_context::android_context();
+        // SAFETY: the VM and activity pointers come from the running app.
+        let (vm, activity) = unsafe {
+            (
+                JavaVM::from_raw(context.vm().cast())?,
+                JObject::from_raw(context.context().cast()),
+            )
+        };
+        let mut env = vm.attach_current_thread()?;
+
+        let service = env
+            .get_static_field(
+                "android/content/Context",
+                "VIBRATOR_SERVICE",
+                "Ljava/lang/String;",
+            )?
+            .l()?;
+        let vibrator = env
+            .call_method(
+                &activity,
+                "getSystemService",
+                "(Ljava/lang/String;)Ljava/lang/Object;",
+                &[JValue::Object(&service)],
+            )?
+            .l()?;
+        let effect = env
+            .call_static_method(
+                "android/os/VibrationEffect",
+                "createOneShot",
+                "(JI)Landroid/os/VibrationEffect;",
+                &[JValue::Long(millis), JValue::Int(amplitude)],
+            )?
+            .l()?;
+        env.call_method(
+            &vibrator,
+            "vibrate",
+            "(Landroid/os/VibrationEffect;)V",
+            &[JValue::Object(&effect)],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
+mod platform {
+    use super::HapticStrength;
+
+    /// Desktop and web have no haptics.
+    pub fn impact(_strength: HapticStrength) {}
+}
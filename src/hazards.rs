@@ -0,0 +1,129 @@
+//! Hazard events.
+//!
+//! Every so often during a run, once the score is high enough, a brief
+//! hazard interrupts the pipes: a flock of birds flying straight at the
+//! player, a crate dropping from the top of the screen, or a gust of wind
+//! pushing the bird up or down. A warning icon blinks where the hazard will
+//! come from for a second before it starts.
+//!
+//! Hazards are drawn from their own random stream, so seeded runs, replays
+//! and races meet the same hazards at the same times.
+
+use bevy::prelude::*;
+
+use crate::config::HazardConfig;
+use crate::rng::RngStream;
+
+/// A kind of hazard event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HazardKind {
+    /// Birds crossing the screen against the scroll
+    Flock,
+    /// A crate falling onto the bird's path
+    FallingCrate,
+    /// A gust of wind pushing the bird up or down
+    Wind,
+}
+
+impl HazardKind {
+    /// All hazards, in the order of their weights.
+    pub const ALL: [HazardKind; 3] = [
+        HazardKind::Flock,
+        HazardKind::FallingCrate,
+        HazardKind::Wind,
+    ];
+
+    /// Returns the weight of this hazard in the configuration.
+    fn weight(&self, config: &HazardConfig) -> f32 {
+        match self {
+            Self::Flock => config.flock_weight,
+            Self::FallingCrate => config.crate_weight,
+            Self::Wind => config.wind_weight,
+        }
+    }
+
+    /// Picks a hazard at random by weight, if any has a weight.
+    pub fn pick(config: &HazardConfig, rng: &mut RngStream) -> Option<HazardKind> {
+        let weights = Self::ALL.map(|kind| kind.weight(config));
+        rng.weighted_index(&weights).map(|index| Self::ALL[index])
+    }
+}
+
+/// Resource timing the next hazard of the run.
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct HazardSchedule {
+    /// Seconds until the next warning
+    pub next: f32,
+}
+
+impl HazardSchedule {
+    /// Picks the delay before the next warning.
+    pub fn restart(&mut self, config: &HazardConfig, rng: &mut RngStream) {
+        self.next = rng.range(
+            config.min_interval,
+            config.max_interval.max(config.min_interval),
+        );
+    }
+
+    /// Advances the timer, returning true when the next warning is due.
+    pub fn tick(&mut self, dt: f32) -> bool {
+        self.next -= dt;
+        self.next <= 0.0
+    }
+}
+
+/// Resource holding the wind gust currently blowing, if any.
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct WindGust {
+    /// Seconds the gust keeps blowing
+    pub remaining: f32,
+    /// 1.0 when blowing up, -1.0 when blowing down
+    pub direction: f32,
+}
+
+impl WindGust {
+    /// Returns true while the gust blows.
+    pub fn is_blowing(&self) -> bool {
+        self.remaining > 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hazards_are_picked_by_weight() {
+        let mut rng = RngStream::from_seed(7);
+        let only_wind = HazardConfig {
+            flock_weight: 0.0,
+            crate_weight: 0.0,
+            ..default()
+        };
+        for _ in 0..20 {
+            assert_eq!(
+                HazardKind::pick(&only_wind, &mut rng),
+                Some(HazardKind::Wind)
+            );
+        }
+
+        let none = HazardConfig {
+            wind_weight: 0.0,
+            ..only_wind
+        };
+        assert_eq!(HazardKind::pick(&none, &mut rng), None);
+    }
+
+    #[test]
+    fn schedule_waits_between_hazards() {
+        let config = HazardConfig::default();
+        let mut rng = RngStream::from_seed(7);
+        let mut schedule = HazardSchedule::default();
+        schedule.restart(&config, &mut rng);
+        assert!((config.min_interval..=config.max_interval).contains(&schedule.next));
+
+        let delay = schedule.next;
+        assert!(!schedule.tick(delay / 2.0));
+        assert!(schedule.tick(delay / 2.0 + 0.01));
+    }
+}
@@ -0,0 +1,88 @@
+//! Hot reloading of asset files.
+//!
+//! Bevy's built-in file watcher requires the `file_watcher` feature and its
+//! native dependencies. Instead, this polls the modification time of a few
+//! registered asset files and asks the `AssetServer` to reload them when they
+//! change on disk.
+//!
+//! Watching is limited to debug builds: released games don't have their
+//! assets edited under them, and shouldn't pay for the polling.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+
+/// How often watched files are checked for changes, in seconds.
+const POLL_INTERVAL: f32 = 0.5;
+
+/// Resource listing the asset files to reload when they change on disk.
+#[derive(Resource)]
+pub struct AssetWatcher {
+    /// Throttles file system checks
+    pub timer: Timer,
+    /// Watched files
+    files: Vec<WatchedFile>,
+}
+
+/// A single watched asset file.
+struct WatchedFile {
+    /// Path relative to the assets directory
+    asset_path: String,
+    /// Last seen modification time
+    modified: Option<SystemTime>,
+}
+
+impl Default for AssetWatcher {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(POLL_INTERVAL, TimerMode::Repeating),
+            files: Vec::new(),
+        }
+    }
+}
+
+impl AssetWatcher {
+    /// Starts watching an asset (path relative to the assets directory).
+    pub fn watch(&mut self, asset_path: &str) {
+        if self.files.iter().any(|file| file.asset_path == asset_path) {
+            return;
+        }
+        self.files.push(WatchedFile {
+            asset_path: asset_path.to_string(),
+            modified: modified_time(asset_path),
+        });
+    }
+
+    /// Returns the watched assets modified since the last call.
+    pub fn changed_assets(&mut self) -> Vec<String> {
+        let mut changed = Vec::new();
+        for file in &mut self.files {
+            let modified = modified_time(&file.asset_path);
+            if modified.is_some() && modified != file.modified {
+                file.modified = modified;
+                changed.push(file.asset_path.clone());
+            }
+        }
+        changed
+    }
+}
+
+/// Run condition enabling hot reloading in debug builds.
+pub fn hot_reload_enabled() -> bool {
+    cfg!(debug_assertions)
+}
+
+/// Returns the modification time of an asset file, if it exists on disk.
+fn modified_time(asset_path: &str) -> Option<SystemTime> {
+    fs::metadata(asset_root().join(asset_path))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Returns the directory assets are loaded from (matches `AssetPlugin`'s default).
+pub fn asset_root() -> PathBuf {
+    FileAssetReader::get_base_path().join("assets")
+}
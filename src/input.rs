@@ -0,0 +1,213 @@
+//! Pointer and menu input helpers.
+//!
+//! Converts mouse clicks and touches into world positions so that 2D UI
+//! elements drawn with `Text2d` and `Sprite` can be pressed, reads the
+//! rebindable game actions, and reads menu navigation from the keyboard and
+//! gamepads so every menu can be used without a mouse.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::bindings::{GameAction, InputBindings};
+use crate::components::MainCamera;
+
+/// System parameter reading clicks and touches in world coordinates.
+#[derive(SystemParam)]
+pub struct PointerInput<'w, 's> {
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    touches: Res<'w, Touches>,
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<MainCamera>>,
+}
+
+impl PointerInput<'_, '_> {
+    /// Returns the world positions of clicks and touches that started this frame.
+    pub fn just_pressed_positions(&self) -> Vec<Vec2> {
+        let cursor = self
+            .mouse
+            .just_pressed(MouseButton::Left)
+            .then(|| self.cursor_position())
+            .flatten();
+        let touches = self
+            .touches
+            .iter_just_pressed()
+            .map(|touch| touch.position());
+        self.to_world(cursor.into_iter().chain(touches))
+    }
+
+    /// Returns the world positions of clicks and touches that ended this frame.
+    pub fn just_released_positions(&self) -> Vec<Vec2> {
+        let cursor = self
+            .mouse
+            .just_released(MouseButton::Left)
+            .then(|| self.cursor_position())
+            .flatten();
+        let touches = self
+            .touches
+            .iter_just_released()
+            .map(|touch| touch.position());
+        self.to_world(cursor.into_iter().chain(touches))
+    }
+
+    /// Returns the world positions of the mouse cursor and of the fingers
+    /// touching the screen.
+    pub fn hover_positions(&self) -> Vec<Vec2> {
+        let touches = self.touches.iter().map(|touch| touch.position());
+        self.to_world(self.cursor_position().into_iter().chain(touches))
+    }
+
+    /// Returns true while the mouse button or a finger is held down.
+    pub fn is_pressed(&self) -> bool {
+        self.mouse.pressed(MouseButton::Left) || self.touches.iter().next().is_some()
+    }
+
+    /// Returns the cursor position in the primary window, if it is inside.
+    fn cursor_position(&self) -> Option<Vec2> {
+        self.windows.single().ok()?.cursor_position()
+    }
+
+    /// Converts window positions to world positions.
+    fn to_world(&self, positions: impl Iterator<Item = Vec2>) -> Vec<Vec2> {
+        let Ok((camera, camera_transform)) = self.cameras.single() else {
+            return Vec::new();
+        };
+        positions
+            .filter_map(|position| camera.viewport_to_world_2d(camera_transform, position).ok())
+            .collect()
+    }
+}
+
+/// System parameter reading the rebindable game actions from the keyboard
+/// and gamepads, and flaps from the devices enabled for flapping.
+#[derive(SystemParam)]
+pub struct ActionInput<'w, 's> {
+    bindings: Res<'w, InputBindings>,
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    touches: Res<'w, Touches>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl ActionInput<'_, '_> {
+    /// Returns whether the action's key or gamepad button was pressed this frame.
+    ///
+    /// The flap key and button only count while their device flaps.
+    pub fn just_pressed(&self, action: GameAction) -> bool {
+        let binding = self.bindings.get(action);
+        let sources = self.bindings.flap_sources;
+        let (keyboard, gamepad) = match action {
+            GameAction::Flap => (sources.keyboard, sources.gamepad),
+            _ => (true, true),
+        };
+        (keyboard && self.keyboard.just_pressed(binding.key.0))
+            || (gamepad
+                && self
+                    .gamepads
+                    .iter()
+                    .any(|gamepad| gamepad.just_pressed(binding.button.0)))
+    }
+
+    /// Returns whether any device enabled for flapping flapped this frame:
+    /// the flap key or button, a click, or a touch.
+    pub fn flap_just_pressed(&self) -> bool {
+        let sources = self.bindings.flap_sources;
+        self.just_pressed(GameAction::Flap)
+            || (sources.mouse && self.mouse.just_pressed(MouseButton::Left))
+            || (sources.touch && self.touches.any_just_pressed())
+    }
+
+    /// Returns whether any key, mouse button, touch or gamepad button was
+    /// pressed this frame, whatever the bindings.
+    pub fn any_just_pressed(&self) -> bool {
+        self.keyboard.get_just_pressed().next().is_some()
+            || self.mouse.get_just_pressed().next().is_some()
+            || self.touches.any_just_pressed()
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+    }
+
+    /// Returns whether any device enabled for flapping is held down.
+    pub fn flap_pressed(&self) -> bool {
+        let binding = self.bindings.get(GameAction::Flap);
+        let sources = self.bindings.flap_sources;
+        (sources.keyboard && self.keyboard.pressed(binding.key.0))
+            || (sources.gamepad
+                && self
+                    .gamepads
+                    .iter()
+                    .any(|gamepad| gamepad.pressed(binding.button.0)))
+            || (sources.mouse && self.mouse.pressed(MouseButton::Left))
+            || (sources.touch && self.touches.iter().next().is_some())
+    }
+}
+
+/// A menu navigation action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuAction {
+    /// Move the focus up
+    Up,
+    /// Move the focus down
+    Down,
+    /// Decrease the focused value
+    Left,
+    /// Increase the focused value
+    Right,
+    /// Activate the focused item
+    Confirm,
+    /// Leave the menu
+    Back,
+}
+
+impl MenuAction {
+    /// Returns the keys triggering this action.
+    fn keys(&self) -> &'static [KeyCode] {
+        match self {
+            Self::Up => &[KeyCode::ArrowUp],
+            Self::Down => &[KeyCode::ArrowDown],
+            Self::Left => &[KeyCode::ArrowLeft],
+            Self::Right => &[KeyCode::ArrowRight],
+            Self::Confirm => &[KeyCode::Enter, KeyCode::NumpadEnter],
+            Self::Back => &[KeyCode::Escape],
+        }
+    }
+
+    /// Returns the gamepad button triggering this action.
+    fn button(&self) -> GamepadButton {
+        match self {
+            Self::Up => GamepadButton::DPadUp,
+            Self::Down => GamepadButton::DPadDown,
+            Self::Left => GamepadButton::DPadLeft,
+            Self::Right => GamepadButton::DPadRight,
+            Self::Confirm => GamepadButton::South,
+            Self::Back => GamepadButton::East,
+        }
+    }
+}
+
+/// System parameter reading menu navigation from the keyboard and gamepads.
+#[derive(SystemParam)]
+pub struct MenuInput<'w, 's> {
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl MenuInput<'_, '_> {
+    /// Returns whether the action was triggered this frame.
+    pub fn just_pressed(&self, action: MenuAction) -> bool {
+        self.keyboard
+            .any_just_pressed(action.keys().iter().copied())
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(action.button()))
+    }
+
+    /// Returns how far to move the focus this frame: -1 for up, 1 for down.
+    pub fn vertical_step(&self) -> i32 {
+        i32::from(self.just_pressed(MenuAction::Down))
+            - i32::from(self.just_pressed(MenuAction::Up))
+    }
+}
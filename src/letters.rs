@@ -0,0 +1,68 @@
+//! Letter bonus.
+//!
+//! Now and then a letter floats between two pipes. Letters come in the order
+//! of the word FLAPPY, one at a time, and collecting the whole word within a
+//! single run pays out a big coin bonus and unlocks an achievement. Letters
+//! collected so far are shown in the HUD and are lost when the bird dies.
+//!
+//! Letters are placed from their own random stream, so seeded runs, replays
+//! and races offer the same letters at the same pipes.
+
+use bevy::prelude::*;
+
+/// The word spelled by the letters, in collection order.
+pub const BONUS_WORD: [char; 6] = ['F', 'L', 'A', 'P', 'P', 'Y'];
+
+/// Resource tracking the letters collected this run.
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct LetterBonus {
+    /// How many letters of the word have been collected
+    pub collected: usize,
+}
+
+impl LetterBonus {
+    /// Returns the next letter to collect, or `None` once the word is complete.
+    pub fn next(&self) -> Option<char> {
+        BONUS_WORD.get(self.collected).copied()
+    }
+
+    /// Collects `letter` if it is the next one, returning true when it
+    /// completes the word.
+    pub fn collect(&mut self, letter: char) -> bool {
+        if self.next() != Some(letter) {
+            return false;
+        }
+        self.collected += 1;
+        self.is_complete()
+    }
+
+    /// Returns true once every letter of the word has been collected.
+    pub fn is_complete(&self) -> bool {
+        self.collected >= BONUS_WORD.len()
+    }
+}
+
+/// Message triggered when the whole word is collected.
+#[derive(Message)]
+pub struct WordCompleted;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_are_collected_in_order() {
+        let mut bonus = LetterBonus::default();
+        assert_eq!(bonus.next(), Some('F'));
+        assert!(!bonus.collect('L'));
+        assert_eq!(bonus.collected, 0);
+
+        let completed: Vec<bool> = BONUS_WORD
+            .iter()
+            .map(|&letter| bonus.collect(letter))
+            .collect();
+        assert_eq!(completed, [false, false, false, false, false, true]);
+        assert_eq!(bonus.next(), None);
+        assert!(!bonus.collect('F'));
+    }
+}
@@ -2,29 +2,178 @@
 //!
 //! A simple Flappy Bird clone built with Bevy game engine.
 
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::audio::{AddAudioSource, Volume};
+use bevy::camera::visibility::VisibilitySystems;
+use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
 use bevy::prelude::*;
+use bevy::render::RenderPlugin;
+use bevy::render::settings::WgpuSettings;
+use bevy::shader::Shader;
+use bevy::sprite_render::Material2dPlugin;
+use bevy::ui::UiSystems;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
 
+mod achievements;
+mod bench;
+mod bindings;
+mod bot;
+mod build_info;
+mod challenge;
+mod cli;
+mod cloud;
 mod components;
+mod config;
+mod console;
 mod constants;
+mod crash;
+mod crash_report;
+#[cfg(feature = "dev-tools")]
+mod dev_tools;
+#[cfg(feature = "dev-tools")]
+mod difficulty_plot;
+#[cfg(test)]
+mod golden;
+mod haptics;
+mod hazards;
+mod hot_reload;
+mod input;
+mod letters;
+mod lighting;
+mod logging;
+mod music;
+mod mutators;
+mod net;
+mod patterns;
+mod portable;
+mod race;
+mod replay;
 mod resources;
+mod results;
+mod rewind;
+mod rng;
+mod save;
+#[cfg(feature = "scripting")]
+mod scripting;
+mod settings;
+mod sfx;
+mod sigv4;
+mod splits;
 mod states;
+#[cfg(feature = "steam")]
+mod steam;
 mod systems;
-mod utils;
+mod telemetry;
+mod theme;
+mod trails;
+#[cfg(feature = "tuning")]
+mod tuning;
+mod verify;
+mod vignette;
 
+use bench::Bench;
+use bindings::InputBindings;
+pub use cli::{CliArgs, USAGE};
+use cloud::CloudSync;
+use components::{
+    ChallengeButton, ControlsRowText, JukeboxRowText, MutatorRowText, MutatorsButton, PauseButton,
+    PlayButton, ReviveButton, RewindButton, SettingsButton, SettingsRowText, SettingsScrollButton,
+};
+use config::{GameConfig, GameConfigHandle, GameConfigLoader};
+use console::DevConsole;
 use constants::{WINDOW_HEIGHT, WINDOW_WIDTH};
+use crash::CrashScreenPlugin;
+use crash_report::SessionMarker;
+use haptics::Haptics;
+use hazards::{HazardSchedule, WindGust};
+use hot_reload::{AssetWatcher, hot_reload_enabled};
+use letters::{LetterBonus, WordCompleted};
+use lighting::LightingMaterial;
+use logging::log_plugin;
+use music::MusicLoop;
+use patterns::{PipePattern, PipePatternLoader};
+use race::RaceSession;
+pub use replay::Replay;
+use replay::{GhostLibrary, ReplayRecorder, RunRecordFile};
 use resources::{
-    DeathEvent, EdgeFlashState, FlapEvent, GameViewport, PipeSpawnTimer, Score, ScoreEvent,
-    ScreenFlashState, ScreenShake,
+    AchievementUnlocked, AttractMode, AutoFlap, ButtonClicked, CameraZoom, DeathEvent,
+    EdgeFlashState, FlapBuffer, FlapCooldown, FlapEvent, GameViewport, GraphicsSettings,
+    Invincibility, NearMiss, NearbyObstacles, PendingSeed, PipeSequence, PipeShattered,
+    PipeSpawnDistance, PlaySfx, Playtime, RaceStarted, ReplayRecorded, Revive, RunStats, Score,
+    ScoreEvent, ScreenFlashState, ScreenShake, Transition, VignettePulse,
 };
-use states::GameState;
+use results::{NewRecord, ResultsAnimation, results_counted};
+use rewind::{Rewind, is_rewinding};
+use rng::GameRng;
+use save::SaveData;
+use settings::{Settings, one_switch_enabled, rainbow_mode_enabled};
+use splits::{BestSplits, RunSplits};
+use states::{GameState, PauseState, PlayPhase, TutorialStep};
 use systems::{
-    bird_flap, bird_physics, bird_tilt, check_collisions, initial_viewport_setup, pipe_movement,
-    pipe_spawner, restart_game, setup, spawn_death_particles, spawn_flap_particles, start_game,
-    trigger_bird_squash, trigger_death_effects, trigger_score_effects, trigger_score_pop,
-    update_bird_squash, update_edge_flash, update_edge_flash_positions,
-    update_fill_screen_entities, update_fill_width_entities, update_particles, update_score,
-    update_score_pop, update_screen_flash, update_screen_shake, update_viewport,
+    accept_revive, animate_decorations, animate_idle_bird, animate_results, announce_milestones,
+    announce_race_winner, announce_rejected_scores, apply_bench_config, apply_camera_rig,
+    apply_cloud_sync, apply_emissive, apply_game_config, apply_graphics_settings, apply_rewind,
+    apply_theme, apply_trail_gravity, bank_coins, bird_auto_flap, bird_flap, bird_physics,
+    bird_tilt, blow_wind, bob_floating_blocks, buffer_flap_input, celebrate_new_record,
+    check_collisions, clear_hazards, clear_rewind_buffer, collect_letters, collect_stars,
+    decline_revive, despawn_attract_demo, despawn_chaser, despawn_controls_menu, despawn_ghosts,
+    despawn_jukebox_menu, despawn_mutators_menu, despawn_pause_menu, despawn_revive_prompt,
+    despawn_rewind_button, despawn_seed_entry, despawn_settings_menu, despawn_tutorial_text,
+    drift_clouds, drive_bench_input, drive_verification_input, drop_crates, emit_trail,
+    fade_pipes_near_bird, fall_confetti, finish_boss_segment, finish_run_recording, finish_splits,
+    finish_tutorial, float_letters, fly_flocks, fly_hazard_flocks, follow_bird, forward_message,
+    guide_tutorial_flaps, handle_console_input, hide_bird, hide_run_stats, init_challenge_clock,
+    init_cloud_sync, init_crash_reports, init_race_session, init_telemetry, initial_viewport_setup,
+    limit_frame_rate, load_fanfare, load_game_config, load_pipe_patterns, load_sound_effects,
+    load_themes, load_trails, log_deaths, log_scores, log_state_transitions, move_boss_wall,
+    move_gate_doors, move_ghosts, move_menu_focus, navigate_controls, navigate_jukebox,
+    navigate_mutators, navigate_pause_menu, navigate_settings, offer_rewind, open_mutators,
+    open_settings, pause_on_focus_loss, pipe_movement, pipe_spawner, play_haptics,
+    play_menu_sounds, play_sound_effects, poll_watched_assets, prepare_verification,
+    pulse_vignette, receive_race_packets, recolor_themed_entities, record_bench_frame,
+    record_deaths, record_flaps, record_high_score, record_run_started, record_scores,
+    record_snapshot, record_splits, release_camera_zoom, request_cloud_sync, reset_auto_flap,
+    reset_companion, reset_flap_cooldown, reset_hazards, reset_invincibility, reset_letters,
+    reset_pipe_sequence, reset_results_animation, reset_revive, reset_rewind, reset_run_stats,
+    reset_splits, restart_game, rewind_on_request, rumble_gamepads, run_attract_demo, scale_bird,
+    scale_hud_text, schedule_hazards, send_button_clicks, send_race_death, send_race_state, setup,
+    sharpen_world_text, shatter_pipes, shift_theme_hues, show_achievement_toasts, show_bird,
+    show_run_stats, skip_results_count_up, spawn_boss_wall, spawn_build_info, spawn_chaser,
+    spawn_cloud_layer, spawn_console, spawn_controls_menu, spawn_death_particles,
+    spawn_decoration_layer, spawn_flap_particles, spawn_flap_prompt, spawn_flock_layer, spawn_fog,
+    spawn_gap_indicator, spawn_jukebox_menu, spawn_letters, spawn_lighting_overlay,
+    spawn_mutators_menu, spawn_pause_menu, spawn_pipe_preview, spawn_practice_gap,
+    spawn_revive_prompt, spawn_score_popups, spawn_seed_entry, spawn_settings_menu,
+    spawn_shatter_particles, spawn_vignette_overlay, spin_blades, start_bench, start_boss_segment,
+    start_challenge, start_game, start_idle_animation, start_music, start_race_run,
+    start_results_animation, start_run_recording, start_transition, start_tutorial, steer_chaser,
+    step_verification, stop_idle_animation, store_playtime_on_exit, style_buttons,
+    sync_challenge_clock, sync_text_outlines, toggle_debug_overlay, track_clean_exit,
+    track_playtime, track_session_time, trigger_bird_squash, trigger_death_effects,
+    trigger_hazard_warnings, trigger_score_effects, trigger_score_pop, type_seed_code,
+    unlock_achievements, update_attract_mode, update_best_score_text, update_bird_squash,
+    update_build_info, update_buttons, update_camera_zoom, update_challenge_buttons,
+    update_companion_presence, update_console, update_controls_rows, update_debug_overlay,
+    update_edge_flash, update_edge_flash_positions, update_fill_screen_entities,
+    update_fill_width_entities, update_fog, update_gap_indicator, update_gravity_zones,
+    update_invincibility, update_jukebox_rows, update_letter_icons, update_lighting,
+    update_milestone_banners, update_music_layers, update_mutator_rows, update_particles,
+    update_pipe_preview, update_race_opponents, update_score, update_score_pop,
+    update_score_popups, update_score_text, update_screen_flash, update_screen_shake,
+    update_seed_entry, update_settings_rows, update_split_timer, update_toasts, update_transition,
+    update_ui_scale, update_viewport, update_vignette, update_wing_recharge, watch_loaded_assets,
+    wrap_world_text, write_run_recording,
 };
+use telemetry::Telemetry;
+use theme::{BaseTheme, Theme, ThemeLoader};
+use trails::{TrailEmitter, TrailStyle, TrailStyleLoader};
+pub use verify::VerifyError;
+use verify::{ReplayVerifier, check_trace};
+use vignette::VignetteMaterial;
 
 #[cfg(target_os = "ios")]
 #[unsafe(no_mangle)]
@@ -33,47 +182,438 @@ pub extern "C" fn main(_argc: i32, _argv: *const *const u8) -> i32 {
     0
 }
 
+/// Systems turning player input into flaps.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct InputSet;
+
+/// Systems moving the bird, pipes, and obstacles.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct PhysicsSet;
+
+/// Systems testing the moved bird against pipes, obstacles, and pickups.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct CollisionSet;
+
+/// Systems counting points and recording progress.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct ScoringSet;
+
+/// Systems reacting to the frame's events with visual effects.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct EffectsSet;
+
+/// Runs the game with default options.
 pub fn run() {
-    App::new()
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
-            primary_window: Some(Window {
-                title: "Flappy Rust".to_string(),
-                // On iOS, we want to ignore the resolution and let the OS handle it
-                // This ensures the window takes up the full screen
-                resolution: (WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32).into(),
-                mode: if cfg!(target_os = "ios") {
-                    bevy::window::WindowMode::BorderlessFullscreen(bevy::window::MonitorSelection::Primary)
-                } else {
-                    bevy::window::WindowMode::Windowed
-                },
-                resizable: true,
+    run_with_args(CliArgs::default());
+}
+
+/// Runs the game with options from the command line.
+pub fn run_with_args(args: CliArgs) {
+    build_app(&args).run();
+}
+
+/// Re-simulates a replay headlessly and checks that it crashes with its
+/// claimed score, returning the score.
+///
+/// The game runs with default settings, controls, and save data, and never
+/// writes to the save directory, so replays verify the same on any machine.
+pub fn verify_replay(replay: &Replay) -> Result<u32, VerifyError> {
+    check_trace(replay)?;
+    let verdict = Arc::default();
+    verification_app(replay, Arc::clone(&verdict)).run();
+
+    let verdict = verdict.lock().ok().and_then(|mut slot| slot.take());
+    verdict.unwrap_or_else(|| {
+        Err(VerifyError::Unavailable(
+            "the game stopped early".to_string(),
+        ))
+    })
+}
+
+/// Builds a headless app playing a replay on its own, reporting the outcome
+/// into `verdict` (see `verify_replay`).
+fn verification_app(replay: &Replay, verdict: Arc<Mutex<Option<Result<u32, VerifyError>>>>) -> App {
+    let args = CliArgs {
+        seed: Some(replay.seed),
+        headless: true,
+        difficulty: replay.difficulty,
+        mutators: replay.mutators.clone(),
+        mute: true,
+        verify: true,
+        ..default()
+    };
+    let mut app = build_app(&args);
+    drive_from_replay(&mut app, ReplayVerifier::new(replay.clone(), verdict));
+    app
+}
+
+/// Plays a recorded run back with the options from the command line,
+/// feeding its frame times and flaps through the game like `verify_replay`.
+///
+/// Playback uses the same defaults as verification and never writes to the
+/// save directory, so a run plays back the same on any machine. Once the
+/// trace ends, the game carries on as usual.
+pub fn play_replay(replay: &Replay, args: CliArgs) -> Result<(), VerifyError> {
+    check_trace(replay)?;
+    let args = CliArgs {
+        seed: Some(replay.seed),
+        difficulty: replay.difficulty,
+        mutators: replay.mutators.clone(),
+        ..args
+    };
+    let mut app = build_app(&args);
+    drive_from_replay(&mut app, ReplayVerifier::playback(replay.clone()));
+    app.run();
+    Ok(())
+}
+
+/// Makes the app play the verifier's replay with default settings, controls
+/// and save data, without writing to the save directory.
+fn drive_from_replay(app: &mut App, verifier: ReplayVerifier) {
+    save::set_read_only();
+    app.insert_resource(SaveData::default())
+        .insert_resource(Settings::default())
+        .insert_resource(InputBindings::default())
+        .insert_resource(GhostLibrary::default())
+        .insert_resource(verifier)
+        .add_systems(
+            PreUpdate,
+            drive_verification_input.after(bevy::input::InputSystems),
+        )
+        .add_systems(Last, (prepare_verification, step_verification).chain());
+}
+
+/// Builds the game's app from command-line options.
+fn build_app(args: &CliArgs) -> App {
+    let primary_window = (!args.headless).then(|| {
+        let (width, height) = args
+            .window_size
+            .unwrap_or((WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32));
+        Window {
+            title: "Flappy Rust".to_string(),
+            // On iOS, we want to ignore the resolution and let the OS handle it
+            // This ensures the window takes up the full screen
+            resolution: (width, height).into(),
+            mode: if cfg!(target_os = "ios") && args.window_size.is_none() {
+                bevy::window::WindowMode::BorderlessFullscreen(
+                    bevy::window::MonitorSelection::Primary,
+                )
+            } else {
+                bevy::window::WindowMode::Windowed
+            },
+            resizable: true,
+            ..default()
+        }
+    });
+    let window_plugin = WindowPlugin {
+        primary_window,
+        // Headless runs have no window to close, so they run until exited
+        exit_condition: if args.headless {
+            ExitCondition::DontExit
+        } else {
+            ExitCondition::OnAllClosed
+        },
+        ..default()
+    };
+    let log_plugin = log_plugin(args.log_filter.as_deref());
+
+    let mut app = App::new();
+    if args.headless {
+        // No window and no GPU: the simulation runs on a fixed-rate loop
+        let render_plugin = RenderPlugin {
+            render_creation: WgpuSettings {
+                backends: None,
                 ..default()
-            }),
+            }
+            .into(),
             ..default()
-        }))
+        };
+        // Benchmarks and verification run as fast as possible
+        let frame_wait = if args.bench || args.verify {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(1.0 / 60.0)
+        };
+        app.add_plugins(
+            DefaultPlugins
+                .set(window_plugin)
+                .set(log_plugin)
+                .set(render_plugin)
+                .disable::<WinitPlugin>(),
+        )
+        .add_plugins(ScheduleRunnerPlugin::run_loop(frame_wait));
+    } else {
+        // Panics show an error screen in the window instead of closing it
+        app.add_plugins(DefaultPlugins.set(window_plugin).set(log_plugin))
+            .add_plugins(CrashScreenPlugin);
+    }
+
+    // Startup resources from the command line
+    let mut ghosts = GhostLibrary::load();
+    if let Some(path) = &args.replay
+        && let Err(error) = ghosts.add_replay_file(path)
+    {
+        warn!("Couldn't load replay {}: {error}", path.display());
+    }
+    app.insert_resource(args.seed.map_or_else(GameRng::default, GameRng::from_seed))
+        .insert_resource(args.difficulty)
+        .insert_resource(args.mutators.clone())
+        .insert_resource(
+            args.mutators
+                .apply(args.difficulty.apply(GameConfig::default())),
+        )
+        .insert_resource(ghosts);
+    if args.mute {
+        app.insert_resource(GlobalVolume::new(Volume::SILENT));
+    }
+    if let Some(path) = &args.record {
+        app.insert_resource(RunRecordFile(path.clone()))
+            .add_systems(Update, write_run_recording.after(finish_run_recording));
+    }
+    if args.bench {
+        app.init_resource::<Bench>()
+            .add_systems(Startup, start_bench)
+            .add_systems(
+                PreUpdate,
+                drive_bench_input.after(bevy::input::InputSystems),
+            )
+            .add_systems(
+                Update,
+                (
+                    apply_bench_config.run_if(resource_changed::<GameConfig>),
+                    record_bench_frame,
+                ),
+            );
+    }
+
+    app.add_plugins(Material2dPlugin::<LightingMaterial>::default())
+        .add_plugins(Material2dPlugin::<VignetteMaterial>::default())
+        .add_plugins(FrameTimeDiagnosticsPlugin::default())
         .init_state::<GameState>()
+        .add_sub_state::<PlayPhase>()
+        .add_sub_state::<PauseState>()
+        .add_sub_state::<TutorialStep>()
         // Core resources
         .init_resource::<Score>()
-        .init_resource::<PipeSpawnTimer>()
+        .init_resource::<PipeSpawnDistance>()
+        .init_resource::<PipeSequence>()
+        .init_resource::<NearbyObstacles>()
+        .init_resource::<DevConsole>()
         .init_resource::<GameViewport>()
+        .init_resource::<PendingSeed>()
+        .init_resource::<FlapBuffer>()
+        // Speedrun splits of the current run and the best one
+        .init_resource::<RunSplits>()
+        .insert_resource(BestSplits::load())
+        .insert_resource(SaveData::load())
+        // Run recording and ghosts
+        .init_resource::<ReplayRecorder>()
+        // Tunable configuration (hot-reloaded from assets/config/game.config.ron in debug builds)
+        .init_asset::<GameConfig>()
+        .init_asset_loader::<GameConfigLoader>()
+        .init_resource::<AssetWatcher>()
+        // Player settings and the selected theme
+        .insert_resource(Settings::load())
+        .insert_resource(InputBindings::load())
+        .init_asset::<Theme>()
+        .init_asset_loader::<ThemeLoader>()
+        .init_resource::<Theme>()
+        .init_resource::<BaseTheme>()
+        // Authored pipe patterns
+        .init_asset::<PipePattern>()
+        .init_asset_loader::<PipePatternLoader>()
+        // Bird trails bought with coins
+        .init_asset::<TrailStyle>()
+        .init_asset_loader::<TrailStyleLoader>()
+        .init_resource::<TrailEmitter>()
+        // Synthesized music layers
+        .add_audio_source::<MusicLoop>()
         // Effect resources
         .init_resource::<ScreenShake>()
+        .init_resource::<CameraZoom>()
+        .init_resource::<VignettePulse>()
+        .init_resource::<Transition>()
+        .init_resource::<GraphicsSettings>()
         .init_resource::<ScreenFlashState>()
         .init_resource::<EdgeFlashState>()
+        .init_resource::<AttractMode>()
+        .init_resource::<AutoFlap>()
+        .init_resource::<FlapCooldown>()
+        .init_resource::<RunStats>()
+        .init_resource::<Rewind>()
+        .init_resource::<Revive>()
+        .init_resource::<Invincibility>()
+        .init_resource::<HazardSchedule>()
+        .init_resource::<WindGust>()
+        .init_resource::<LetterBonus>()
+        .init_resource::<ResultsAnimation>()
+        .init_resource::<Playtime>()
+        // Haptics call platform APIs, which must stay on the main thread
+        .init_non_send_resource::<Haptics>()
         // Events/Messages
         .add_message::<FlapEvent>()
         .add_message::<ScoreEvent>()
         .add_message::<DeathEvent>()
+        .add_observer(forward_message::<FlapEvent>)
+        .add_observer(forward_message::<ScoreEvent>)
+        .add_observer(forward_message::<DeathEvent>)
+        // Effects that start the same frame as their event
+        .add_observer(trigger_bird_squash)
+        .add_observer(trigger_score_pop)
+        .add_observer(trigger_death_effects)
+        .add_message::<NearMiss>()
+        .add_message::<PipeShattered>()
+        .add_message::<AchievementUnlocked>()
+        .add_message::<WordCompleted>()
+        .add_message::<NewRecord>()
+        .add_message::<ReplayRecorded>()
+        .add_message::<RaceStarted>()
+        .add_message::<PlaySfx>()
+        .add_message::<ButtonClicked<PlayButton>>()
+        .add_message::<ButtonClicked<MutatorsButton>>()
+        .add_message::<ButtonClicked<SettingsButton>>()
+        .add_message::<ButtonClicked<ChallengeButton>>()
+        .add_message::<ButtonClicked<SettingsRowText>>()
+        .add_message::<ButtonClicked<SettingsScrollButton>>()
+        .add_message::<ButtonClicked<JukeboxRowText>>()
+        .add_message::<ButtonClicked<MutatorRowText>>()
+        .add_message::<ButtonClicked<ControlsRowText>>()
+        .add_message::<ButtonClicked<PauseButton>>()
+        .add_message::<ButtonClicked<ReviveButton>>()
+        .add_message::<ButtonClicked<RewindButton>>()
+        // Frame order: input, then movement, then collisions, then scoring,
+        // and effects last, so each stage sees this frame's results
+        .configure_sets(
+            Update,
+            (InputSet, PhysicsSet, CollisionSet, ScoringSet, EffectsSet).chain(),
+        )
         // Startup systems
         .add_systems(Startup, (setup, initial_viewport_setup).chain())
+        .add_systems(
+            Startup,
+            (
+                init_telemetry,
+                init_cloud_sync,
+                init_challenge_clock,
+                init_race_session,
+                init_crash_reports,
+            ),
+        )
+        .add_systems(Startup, (load_game_config, load_themes, load_pipe_patterns, load_trails))
+        .add_systems(
+            Startup,
+            (
+                start_music,
+                load_fanfare,
+                load_sound_effects,
+                spawn_gap_indicator,
+                spawn_pipe_preview,
+                spawn_lighting_overlay,
+                spawn_vignette_overlay,
+                spawn_build_info,
+                spawn_console,
+                spawn_cloud_layer,
+                spawn_flock_layer,
+                spawn_decoration_layer,
+                spawn_fog,
+                announce_rejected_scores,
+            ),
+        )
+        // Build information and debug overlay
+        .add_systems(
+            Update,
+            (
+                update_build_info,
+                (toggle_debug_overlay, update_debug_overlay).chain(),
+            ),
+        )
+        // Keyboard and gamepad focus for the menu and pause screen
+        .add_systems(
+            Update,
+            move_menu_focus
+                .run_if(in_state(GameState::Menu).or(in_state(PauseState::Paused))),
+        )
+        // Buttons track the pointer before any screen reads their clicks
+        .add_systems(
+            PreUpdate,
+            (
+                update_buttons,
+                (
+                    send_button_clicks::<PlayButton>,
+                    send_button_clicks::<MutatorsButton>,
+                    send_button_clicks::<SettingsButton>,
+                    send_button_clicks::<ChallengeButton>,
+                    send_button_clicks::<SettingsRowText>,
+                    send_button_clicks::<SettingsScrollButton>,
+                    send_button_clicks::<JukeboxRowText>,
+                    send_button_clicks::<MutatorRowText>,
+                    send_button_clicks::<ControlsRowText>,
+                    send_button_clicks::<PauseButton>,
+                    send_button_clicks::<ReviveButton>,
+                    send_button_clicks::<RewindButton>,
+                ),
+            )
+                .chain()
+                .after(bevy::input::InputSystems),
+        )
+        .add_systems(PostUpdate, style_buttons)
+        // Sound effects channel
+        .add_systems(
+            Update,
+            (
+                play_menu_sounds.run_if(
+                    in_state(GameState::Menu)
+                        .or(in_state(GameState::Settings))
+                        .or(in_state(GameState::Jukebox))
+                        .or(in_state(GameState::Controls))
+                        .or(in_state(GameState::SeedEntry))
+                        .or(in_state(GameState::Mutators))
+                        .or(in_state(PauseState::Paused)),
+                ),
+                play_sound_effects,
+            )
+                .chain(),
+        )
+        // Developer console, reading the keyboard before anything else does
+        .add_systems(
+            PreUpdate,
+            handle_console_input.after(bevy::input::InputSystems),
+        )
+        .add_systems(Update, update_console)
+        .add_systems(
+            Update,
+            update_music_layers.run_if(not(in_state(GameState::Jukebox))),
+        )
+        .add_systems(
+            Update,
+            apply_game_config.run_if(resource_exists::<GameConfigHandle>),
+        )
+        // Hot reloading of edited asset files (debug builds only)
+        .add_systems(
+            Update,
+            (
+                poll_watched_assets,
+                watch_loaded_assets::<Theme>,
+                watch_loaded_assets::<PipePattern>,
+                watch_loaded_assets::<TrailStyle>,
+                watch_loaded_assets::<Image>,
+                watch_loaded_assets::<AudioSource>,
+                watch_loaded_assets::<Shader>,
+            )
+                .run_if(hot_reload_enabled),
+        )
         // Viewport update systems (always running)
         .add_systems(
             Update,
             (
                 update_viewport,
+                update_ui_scale.after(update_viewport),
                 update_fill_width_entities,
                 update_fill_screen_entities,
                 update_edge_flash_positions,
+                update_gap_indicator,
+                update_pipe_preview,
+                update_lighting,
             ),
         )
         // Update systems
@@ -81,35 +621,409 @@ pub fn run() {
             Update,
             (
                 // Menu state
-                start_game.run_if(in_state(GameState::Menu)),
-                // Playing state - core gameplay
+                (start_game, start_challenge, open_mutators, open_settings)
+                    .after(move_menu_focus)
+                    .run_if(in_state(GameState::Menu)),
+                // Menu state - challenge clock and countdowns
+                sync_challenge_clock,
+                update_challenge_buttons.run_if(in_state(GameState::Menu)),
+                // Menu state - bird idle animation
+                animate_idle_bird.run_if(in_state(GameState::Menu)),
+                // Menu state - attract-mode demo
+                (update_attract_mode, run_attract_demo)
+                    .chain()
+                    .run_if(in_state(GameState::Menu)),
+                // Settings screen
+                (navigate_settings, update_settings_rows)
+                    .chain()
+                    .run_if(in_state(GameState::Settings)),
+                // Sound test screen
+                (navigate_jukebox, update_jukebox_rows)
+                    .chain()
+                    .run_if(in_state(GameState::Jukebox)),
+                // Controls screen
+                (navigate_controls, update_controls_rows)
+                    .chain()
+                    .run_if(in_state(GameState::Controls)),
+                // Seed entry screen
+                (type_seed_code, update_seed_entry)
+                    .chain()
+                    .run_if(in_state(GameState::SeedEntry)),
+                // Mutator screen
+                (navigate_mutators, update_mutator_rows)
+                    .chain()
+                    .run_if(in_state(GameState::Mutators)),
+                // Flaps pressed just before a run starts
+                buffer_flap_input.run_if(not(in_state(GameState::Playing))),
+                // Playing state - core gameplay (frozen while paused)
                 (
-                    bird_flap,
-                    bird_physics,
-                    bird_tilt,
-                    pipe_movement,
-                    pipe_spawner,
-                    check_collisions,
-                    update_score,
+                    // Flap controllers (swapped via the one-switch setting)
+                    (
+                        bird_flap.run_if(not(one_switch_enabled)),
+                        bird_auto_flap.run_if(one_switch_enabled),
+                    )
+                        .in_set(InputSet),
+                    (
+                        update_gravity_zones.before(bird_physics),
+                        bird_physics,
+                        bird_tilt,
+                        pipe_movement,
+                        pipe_spawner.run_if(in_state(PlayPhase::Normal)),
+                        spin_blades,
+                        move_gate_doors,
+                        bob_floating_blocks,
+                    )
+                        .in_set(PhysicsSet),
+                    // Stars and shattering first, so the bird can't crash into
+                    // a pipe it shatters this frame
+                    (
+                        collect_stars,
+                        collect_letters,
+                        shatter_pipes,
+                        check_collisions,
+                        update_invincibility,
+                    )
+                        .chain()
+                        .in_set(CollisionSet),
+                    (update_score, update_score_text)
+                        .chain()
+                        .in_set(ScoringSet),
+                    // Hazard events: warnings first, then the hazards they announce
+                    (
+                        schedule_hazards.run_if(in_state(PlayPhase::Normal)),
+                        trigger_hazard_warnings,
+                        blow_wind.before(bird_physics),
+                        fly_hazard_flocks,
+                        drop_crates,
+                    )
+                        .chain()
+                        .in_set(PhysicsSet),
+                    // Letter bonus (placed after the pipe pairs it follows)
+                    (spawn_letters.after(pipe_spawner), float_letters).in_set(PhysicsSet),
+                    // Chaser enemy (registered for collisions after the pipes scroll)
+                    (spawn_chaser, steer_chaser.after(pipe_movement)).in_set(PhysicsSet),
+                    // First-run tutorial (scripted in place of the regular spawning)
+                    (
+                        guide_tutorial_flaps
+                            .after(bird_physics)
+                            .in_set(PhysicsSet)
+                            .run_if(in_state(TutorialStep::Flap)),
+                        finish_tutorial
+                            .after(update_score)
+                            .in_set(ScoringSet)
+                            .run_if(in_state(TutorialStep::PracticeGap)),
+                    ),
                 )
-                    .run_if(in_state(GameState::Playing)),
+                    .run_if(in_state(PauseState::Running)),
+                // Playing state - pausing and resuming
+                pause_on_focus_loss.run_if(in_state(PauseState::Running)),
+                navigate_pause_menu
+                    .after(move_menu_focus)
+                    .run_if(in_state(PauseState::Paused)),
+                // Playing state - revive prompt after a crash
+                (accept_revive, decline_revive)
+                    .chain()
+                    .run_if(in_state(PauseState::Reviving)),
+                // Boss segments (regular spawning is paused during them)
+                start_boss_segment.run_if(in_state(PlayPhase::Normal)),
+                (
+                    move_boss_wall.in_set(PhysicsSet),
+                    finish_boss_segment.in_set(ScoringSet),
+                )
+                    .run_if(in_state(PlayPhase::Boss)),
                 // Playing state - visual effects (respond to events)
                 (
                     spawn_flap_particles,
-                    trigger_bird_squash,
-                    trigger_score_pop,
+                    emit_trail.run_if(in_state(PauseState::Running)),
                     trigger_score_effects,
+                    spawn_score_popups,
+                    spawn_shatter_particles,
+                    announce_milestones,
                 )
+                    .in_set(EffectsSet)
                     .run_if(in_state(GameState::Playing)),
                 // Game over state
-                restart_game.run_if(in_state(GameState::GameOver)),
+                (restart_game.run_if(results_counted), rewind_on_request)
+                    .run_if(in_state(GameState::GameOver)),
                 // Death effects (run on game over transition)
-                (spawn_death_particles, trigger_death_effects)
+                spawn_death_particles
+                    .in_set(EffectsSet)
                     .run_if(in_state(GameState::GameOver)),
             ),
         )
         // Always-running effect systems
-        .add_systems(Update, (update_particles, update_bird_squash, update_score_pop))
-        .add_systems(Update, (update_screen_shake, update_screen_flash, update_edge_flash))
-        .run();
+        .add_systems(
+            Update,
+            (
+                (apply_trail_gravity, fall_confetti, update_particles).chain(),
+                drift_clouds,
+                fly_flocks,
+                animate_decorations,
+                fade_pipes_near_bird,
+                update_fog,
+                update_bird_squash,
+                scale_bird.after(update_bird_squash),
+                update_wing_recharge,
+                update_score_pop,
+                update_score_popups,
+                update_milestone_banners,
+            )
+                .in_set(EffectsSet),
+        )
+        // Cosmetic companion (frozen after a crash and while paused)
+        .add_systems(
+            Update,
+            (
+                update_companion_presence,
+                follow_bird
+                    .run_if(not(in_state(GameState::GameOver)))
+                    .run_if(not(in_state(PauseState::Paused))),
+            )
+                .chain()
+                .in_set(EffectsSet),
+        )
+        // Progress tracking (persisted to the save file)
+        .add_systems(
+            Update,
+            (
+                record_high_score,
+                bank_coins,
+                unlock_achievements,
+                update_best_score_text,
+                update_letter_icons.run_if(resource_changed::<LetterBonus>),
+                show_achievement_toasts,
+                update_toasts,
+                track_playtime,
+                store_playtime_on_exit,
+            )
+                .in_set(ScoringSet),
+        )
+        .add_systems(
+            Update,
+            (
+                (update_camera_zoom, update_screen_shake, apply_camera_rig).chain(),
+                (pulse_vignette, update_vignette).chain(),
+                update_screen_flash,
+                update_edge_flash,
+            )
+                .in_set(EffectsSet),
+        )
+        .add_systems(
+            Update,
+            (
+                start_transition.run_if(state_changed::<GameState>),
+                update_transition,
+            )
+                .chain(),
+        )
+        .add_systems(Update, (play_haptics, rumble_gamepads))
+        // Graphics settings and frame-rate limit
+        .add_systems(
+            Update,
+            apply_graphics_settings.run_if(resource_changed::<Settings>),
+        )
+        .add_systems(Last, limit_frame_rate)
+        // Emissive colors glow on top of this frame's plain colors
+        .add_systems(PostUpdate, apply_emissive)
+        // Crisp world text, including text spawned this frame, at the text size setting
+        .add_systems(
+            PostUpdate,
+            (sharpen_world_text, scale_hud_text).before(TransformSystems::Propagate),
+        )
+        // Text outlines follow their text after it is scaled, before layout
+        .add_systems(
+            PostUpdate,
+            sync_text_outlines
+                .after(scale_hud_text)
+                .before(UiSystems::Prepare)
+                .before(VisibilitySystems::VisibilityPropagate),
+        )
+        .add_systems(Update, wrap_world_text)
+        .add_systems(
+            OnEnter(GameState::Playing),
+            (
+                reset_pipe_sequence,
+                reset_auto_flap,
+                reset_flap_cooldown,
+                reset_run_stats,
+                reset_rewind,
+                reset_revive,
+                reset_invincibility,
+                reset_companion,
+                reset_splits,
+                reset_hazards,
+                reset_letters,
+                start_run_recording,
+            )
+                .run_if(not(is_rewinding)),
+        )
+        // One-time rewind (resuming skips the new-run resets above)
+        .add_systems(OnEnter(GameState::Playing), apply_rewind.run_if(is_rewinding))
+        .add_systems(OnEnter(GameState::Playing), despawn_chaser)
+        .add_systems(
+            Update,
+            record_snapshot
+                .run_if(in_state(PauseState::Running))
+                .run_if(in_state(PlayPhase::Normal)),
+        )
+        .add_systems(
+            OnEnter(GameState::Playing),
+            start_tutorial
+                .after(start_run_recording)
+                .run_if(not(is_rewinding)),
+        )
+        .add_systems(OnEnter(TutorialStep::Flap), spawn_flap_prompt)
+        .add_systems(OnExit(TutorialStep::Flap), despawn_tutorial_text)
+        .add_systems(OnEnter(TutorialStep::PracticeGap), spawn_practice_gap)
+        .add_systems(OnExit(PlayPhase::Tutorial), despawn_tutorial_text)
+        .add_systems(OnEnter(PlayPhase::Boss), clear_rewind_buffer)
+        .add_systems(OnExit(PlayPhase::Boss), clear_rewind_buffer)
+        .add_systems(OnEnter(GameState::GameOver), offer_rewind)
+        // Collected letters are lost on death, even if the run is rewound
+        .add_systems(OnEnter(GameState::GameOver), reset_letters)
+        .add_systems(OnExit(GameState::GameOver), despawn_rewind_button)
+        .add_systems(
+            OnEnter(GameState::Playing),
+            // Settle the gravity before the run's first flap
+            (hide_bird, update_gravity_zones.after(apply_rewind)),
+        )
+        .add_systems(
+            OnExit(GameState::Playing),
+            (despawn_ghosts, clear_hazards, show_bird),
+        )
+        .add_systems(OnEnter(GameState::GameOver), show_run_stats)
+        .add_systems(OnExit(GameState::GameOver), hide_run_stats)
+        // New record celebration on the results screen
+        .add_systems(
+            Update,
+            (
+                (start_results_animation, celebrate_new_record).after(record_high_score),
+                animate_results
+                    .after(start_results_animation)
+                    .after(ScoringSet)
+                    .before(restart_game)
+                    .before(rewind_on_request),
+                // After the restart, so the press skipping the count-up
+                // doesn't restart too
+                skip_results_count_up
+                    .after(restart_game)
+                    .run_if(in_state(GameState::GameOver)),
+            ),
+        )
+        .add_systems(
+            OnExit(GameState::GameOver),
+            (reset_results_animation, release_camera_zoom),
+        )
+        // Online races (only when configured)
+        .add_systems(
+            OnEnter(GameState::Playing),
+            start_race_run
+                .after(start_run_recording)
+                .run_if(resource_exists::<RaceSession>),
+        )
+        .add_systems(
+            Update,
+            (
+                receive_race_packets,
+                send_race_state.run_if(in_state(GameState::Playing)),
+                send_race_death,
+                update_race_opponents,
+                announce_race_winner,
+            )
+                .chain()
+                .run_if(resource_exists::<RaceSession>),
+        )
+        .add_systems(
+            Update,
+            (
+                record_flaps
+                    .after(InputSet)
+                    .run_if(in_state(PauseState::Running)),
+                move_ghosts.run_if(in_state(GameState::Playing)),
+                finish_run_recording.after(ScoringSet),
+            ),
+        )
+        // Speedrun timer and splits
+        .add_systems(
+            Update,
+            (
+                record_splits
+                    .after(ScoringSet)
+                    .run_if(in_state(GameState::Playing)),
+                finish_splits,
+                update_split_timer,
+            )
+                .chain(),
+        )
+        .add_systems(OnEnter(PlayPhase::Boss), spawn_boss_wall)
+        .add_systems(OnEnter(PauseState::Paused), spawn_pause_menu)
+        .add_systems(OnExit(PauseState::Paused), despawn_pause_menu)
+        .add_systems(OnEnter(PauseState::Reviving), spawn_revive_prompt)
+        .add_systems(OnExit(PauseState::Reviving), despawn_revive_prompt)
+        .add_systems(OnExit(GameState::Menu), despawn_attract_demo)
+        .add_systems(OnEnter(GameState::Menu), start_idle_animation)
+        .add_systems(OnExit(GameState::Menu), stop_idle_animation)
+        .add_systems(OnEnter(GameState::Settings), spawn_settings_menu)
+        .add_systems(OnExit(GameState::Settings), despawn_settings_menu)
+        .add_systems(OnEnter(GameState::Jukebox), spawn_jukebox_menu)
+        .add_systems(OnExit(GameState::Jukebox), despawn_jukebox_menu)
+        .add_systems(OnEnter(GameState::Controls), spawn_controls_menu)
+        .add_systems(OnExit(GameState::Controls), despawn_controls_menu)
+        .add_systems(OnEnter(GameState::SeedEntry), spawn_seed_entry)
+        .add_systems(OnExit(GameState::SeedEntry), despawn_seed_entry)
+        .add_systems(OnEnter(GameState::Mutators), spawn_mutators_menu)
+        .add_systems(OnExit(GameState::Mutators), despawn_mutators_menu)
+        // Theme (re)application
+        .add_systems(
+            Update,
+            (
+                apply_theme,
+                shift_theme_hues
+                    .run_if(rainbow_mode_enabled)
+                    .run_if(not(in_state(GameState::GameOver))),
+                recolor_themed_entities.run_if(resource_changed::<Theme>),
+            )
+                .chain(),
+        )
+        // Optional cloud save sync (only runs when enabled via FLAPPY_CLOUD_SAVE)
+        .add_systems(
+            Update,
+            (request_cloud_sync, apply_cloud_sync).run_if(resource_exists::<CloudSync>),
+        )
+        // Opt-in telemetry (only runs when enabled via FLAPPY_TELEMETRY)
+        .add_systems(
+            OnEnter(GameState::Playing),
+            record_run_started
+                .run_if(resource_exists::<Telemetry>)
+                .run_if(not(is_rewinding)),
+        )
+        .add_systems(
+            Update,
+            (record_scores, record_deaths, track_session_time)
+                .run_if(resource_exists::<Telemetry>),
+        )
+        // Gameplay logs
+        .add_systems(Update, (log_state_transitions, log_scores, log_deaths))
+        // Crash reports on the next launch unless the session exits cleanly
+        .add_systems(
+            Last,
+            track_clean_exit.run_if(resource_exists::<SessionMarker>),
+        );
+
+    #[cfg(feature = "steam")]
+    app.add_plugins(steam::SteamPlugin);
+
+    #[cfg(feature = "scripting")]
+    app.add_plugins(scripting::ScriptingPlugin);
+
+    #[cfg(feature = "dev-tools")]
+    app.add_plugins((
+        dev_tools::DevToolsPlugin,
+        difficulty_plot::DifficultyPlotPlugin,
+    ));
+
+    #[cfg(feature = "tuning")]
+    app.add_plugins(tuning::TuningPlugin);
+
+    app
 }
@@ -0,0 +1,70 @@
+//! Scene lighting for dark themes.
+//!
+//! Bevy has no 2D lights, so the lighting pass is a full-screen overlay drawn
+//! with `LightingMaterial`: it paints the theme's darkness everywhere except
+//! around a list of point lights (a glow on the bird and dim lights at pipe
+//! gaps). The overlay is hidden unless the current theme sets `lighting`.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderType};
+use bevy::shader::ShaderRef;
+use bevy::sprite_render::{AlphaMode2d, Material2d};
+
+/// Shader drawing the lighting overlay.
+const LIGHTING_SHADER_PATH: &str = "shaders/lighting.wgsl";
+
+/// Most lights the shader handles; must match `MAX_LIGHTS` in the shader.
+pub const MAX_LIGHTS: usize = 16;
+
+/// Lighting state uploaded to the shader.
+#[derive(ShaderType, Clone, Debug, Default)]
+pub struct LightingUniform {
+    /// Color (rgb) and opacity (a) of the unlit scene
+    pub darkness: LinearRgba,
+    /// Lights as (x, y, radius, intensity) in world coordinates
+    pub lights: [Vec4; MAX_LIGHTS],
+    /// Number of lights in use
+    pub count: u32,
+}
+
+impl LightingUniform {
+    /// Adds a light, ignoring it once `MAX_LIGHTS` are in use.
+    pub fn add_light(&mut self, position: Vec2, radius: f32, intensity: f32) {
+        if let Some(light) = self.lights.get_mut(self.count as usize) {
+            *light = Vec4::new(position.x, position.y, radius, intensity);
+            self.count += 1;
+        }
+    }
+}
+
+/// Material of the full-screen lighting overlay.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug, Default)]
+pub struct LightingMaterial {
+    #[uniform(0)]
+    pub lighting: LightingUniform,
+}
+
+impl Material2d for LightingMaterial {
+    fn fragment_shader() -> ShaderRef {
+        LIGHTING_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lights_beyond_the_limit_are_dropped() {
+        let mut uniform = LightingUniform::default();
+        for index in 0..MAX_LIGHTS + 4 {
+            uniform.add_light(Vec2::splat(index as f32), 10.0, 1.0);
+        }
+        assert_eq!(uniform.count as usize, MAX_LIGHTS);
+        assert_eq!(uniform.lights[MAX_LIGHTS - 1].x, (MAX_LIGHTS - 1) as f32);
+    }
+}
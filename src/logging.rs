@@ -0,0 +1,194 @@
+//! Log setup and the in-game log viewer.
+//!
+//! Logs go through Bevy's `tracing` subscriber. Verbosity follows `RUST_LOG`
+//! when it is set, otherwise the `--log` filter (e.g. `debug` or
+//! `flappy_rust=trace`) on top of the default `info` level. Besides the
+//! console, every event that passes the filter is kept in `RecentLogs`, whose
+//! last lines the debug overlay shows, and appended to `session.log` in the
+//! save directory. The previous session's log is kept as `last-session.log`
+//! for crash reports.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::fs::{self, File};
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use bevy::log::tracing::field::{Field, Visit};
+use bevy::log::tracing::{Event, Subscriber};
+use bevy::log::tracing_subscriber::Layer;
+use bevy::log::tracing_subscriber::layer::Context;
+use bevy::log::{BoxedLayer, DEFAULT_FILTER, LogPlugin};
+use bevy::prelude::*;
+
+use crate::save::{is_read_only, save_dir};
+
+/// Number of log lines kept for the viewer.
+const RECENT_LOG_CAPACITY: usize = 64;
+
+/// Name of the current session's log inside the save directory.
+const SESSION_LOG_FILE_NAME: &str = "session.log";
+
+/// Name of the previous session's log inside the save directory.
+const LAST_SESSION_LOG_FILE_NAME: &str = "last-session.log";
+
+/// Returns the path of the previous session's log.
+pub fn last_session_log_path() -> PathBuf {
+    save_dir().join(LAST_SESSION_LOG_FILE_NAME)
+}
+
+/// Returns the log plugin, with `filter` (from `--log`) added to the default
+/// directives.
+pub fn log_plugin(filter: Option<&str>) -> LogPlugin {
+    let mut plugin = LogPlugin {
+        custom_layer: capture_layer,
+        ..default()
+    };
+    if let Some(filter) = filter {
+        plugin.filter = format!("{DEFAULT_FILTER},{filter}");
+    }
+    plugin
+}
+
+/// Resource holding the latest log lines, shared with the capturing layer.
+#[derive(Resource, Clone, Default)]
+pub struct RecentLogs(Arc<Mutex<VecDeque<String>>>);
+
+impl RecentLogs {
+    /// Adds a line, dropping the oldest one when full.
+    fn push(&self, line: String) {
+        let Ok(mut lines) = self.0.lock() else {
+            return;
+        };
+        if lines.len() == RECENT_LOG_CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Returns the last `count` lines, oldest first.
+    pub fn last(&self, count: usize) -> Vec<String> {
+        let Ok(lines) = self.0.lock() else {
+            return Vec::new();
+        };
+        lines
+            .iter()
+            .skip(lines.len().saturating_sub(count))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Creates the layer feeding `RecentLogs` and the session log, and inserts
+/// the resource.
+fn capture_layer(app: &mut App) -> Option<BoxedLayer> {
+    let logs = RecentLogs::default();
+    app.insert_resource(logs.clone());
+    Some(Box::new(LogCapture {
+        logs,
+        file: open_session_log().map(Mutex::new),
+    }))
+}
+
+/// Keeps the previous session's log and starts a new one, unless the save
+/// directory is read-only.
+///
+/// Failures only cost the log file, so they are reported on stderr (the
+/// logger isn't set up yet).
+fn open_session_log() -> Option<File> {
+    if is_read_only() {
+        return None;
+    }
+    let dir = save_dir();
+    let path = dir.join(SESSION_LOG_FILE_NAME);
+    let result = fs::create_dir_all(&dir).and_then(|()| {
+        if path.exists() {
+            fs::rename(&path, last_session_log_path())?;
+        }
+        File::create(&path)
+    });
+    match result {
+        Ok(file) => Some(file),
+        Err(error) => {
+            eprintln!("Failed to open {}: {error}", path.display());
+            None
+        }
+    }
+}
+
+/// Tracing layer copying each event into `RecentLogs` and the session log.
+struct LogCapture {
+    logs: RecentLogs,
+    /// Unbuffered, so the log survives the game being killed
+    file: Option<Mutex<File>>,
+}
+
+impl<S: Subscriber> Layer<S> for LogCapture {
+    fn on_event(&self, event: &Event<'_>, _context: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut line = LogLine::default();
+        event.record(&mut line);
+        let line = format!(
+            "{} {}: {}{}",
+            metadata.level(),
+            metadata.target(),
+            line.message,
+            line.fields
+        );
+        if let Some(Ok(mut file)) = self.file.as_ref().map(Mutex::lock) {
+            let _ = writeln!(file, "{line}");
+        }
+        self.logs.push(line);
+    }
+}
+
+/// An event's message and its other fields, formatted as `key=value`.
+#[derive(Default)]
+struct LogLine {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LogLine {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_logs_keep_the_latest_lines() {
+        let logs = RecentLogs::default();
+        for index in 0..RECENT_LOG_CAPACITY + 2 {
+            logs.push(index.to_string());
+        }
+        assert_eq!(logs.last(2), vec!["64".to_string(), "65".to_string()]);
+        assert_eq!(logs.last(1000).len(), RECENT_LOG_CAPACITY);
+        assert_eq!(logs.last(1000)[0], "2");
+    }
+
+    #[test]
+    fn log_filter_extends_the_default() {
+        assert_eq!(log_plugin(None).filter, DEFAULT_FILTER);
+        assert_eq!(
+            log_plugin(Some("flappy_rust=debug")).filter,
+            format!("{DEFAULT_FILTER},flappy_rust=debug")
+        );
+    }
+}
@@ -1,5 +1,55 @@
-use flappy_rust::run;
+use std::path::Path;
+
+use flappy_rust::{CliArgs, Replay, USAGE, play_replay, run_with_args, verify_replay};
 
 fn main() {
-    run();
+    let args = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("error: {error}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{USAGE}");
+        return;
+    }
+    if args.verify
+        && let Some(path) = &args.replay
+    {
+        verify(path);
+        return;
+    }
+    if let Some(path) = args.play.clone() {
+        play(&path, args);
+        return;
+    }
+    run_with_args(args);
+}
+
+/// Reads a replay file, exiting with 2 if it can't be.
+fn read_replay(path: &Path) -> Replay {
+    Replay::from_file(path).unwrap_or_else(|error| {
+        eprintln!("error: couldn't read replay {}: {error}", path.display());
+        std::process::exit(2);
+    })
+}
+
+/// Plays a recorded run back, exiting with 2 if it can't be.
+fn play(path: &Path, args: CliArgs) {
+    if let Err(error) = play_replay(&read_replay(path), args) {
+        eprintln!("error: couldn't play {}: {error}", path.display());
+        std::process::exit(2);
+    }
+}
+
+/// Verifies a replay file, exiting with 1 if it is rejected.
+fn verify(path: &Path) {
+    match verify_replay(&read_replay(path)) {
+        Ok(score) => println!("Verified: {score} points"),
+        Err(error) => {
+            println!("Rejected: {error}");
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,408 @@
+//! Adaptive, synthesized music.
+//!
+//! The soundtrack is made of layers that loop together: a base loop (bass and
+//! pads) that always plays, plus percussion and lead layers that fade in as
+//! the score rises (see `MusicConfig`). Layers are synthesized at startup, so
+//! the game ships no audio files; every layer has the same length to stay in
+//! time with the others. The fanfare of milestone banners is synthesized the
+//! same way, as are the sound effects (see `sfx`), and every sound can be
+//! previewed on the sound test screen.
+
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::audio::{Decodable, Source};
+use bevy::prelude::*;
+
+use crate::config::MusicConfig;
+use crate::rng::RngStream;
+use crate::sfx::SoundEffect;
+
+/// Sample rate of the synthesized music.
+pub const SAMPLE_RATE: u32 = 22_050;
+
+/// Tempo in beats per minute.
+const TEMPO: f32 = 112.0;
+
+/// Beats per bar.
+const BEATS_PER_BAR: usize = 4;
+
+/// Root note frequency of each bar of the loop (Am, F, C, G).
+const BAR_ROOTS: [f32; 4] = [110.0, 87.31, 130.81, 98.0];
+
+/// Semitone offsets of the triad played over each root (minor for the first bar).
+const BAR_TRIADS: [[i32; 3]; 4] = [[0, 3, 7], [0, 4, 7], [0, 4, 7], [0, 4, 7]];
+
+/// A layer of the soundtrack.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MusicLayer {
+    /// Bass and pads, always playing
+    Base,
+    /// Drums
+    Percussion,
+    /// Melody arpeggio
+    Lead,
+}
+
+impl MusicLayer {
+    /// All layers, from the bottom up.
+    pub const ALL: [MusicLayer; 3] = [MusicLayer::Base, MusicLayer::Percussion, MusicLayer::Lead];
+
+    /// Returns the layer's display name.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Base => "Base loop",
+            Self::Percussion => "Percussion loop",
+            Self::Lead => "Lead loop",
+        }
+    }
+
+    /// Returns the volume this layer should reach at `score`.
+    pub fn target_volume(&self, score: u32, config: &MusicConfig) -> f32 {
+        let plays = match self {
+            Self::Base => true,
+            Self::Percussion => score >= config.percussion_score,
+            Self::Lead => score >= config.lead_score,
+        };
+        if plays { config.volume } else { 0.0 }
+    }
+
+    /// Synthesizes one loop of this layer.
+    pub fn synthesize(&self) -> MusicLoop {
+        let beat = 60.0 / TEMPO;
+        let bar = beat * BEATS_PER_BAR as f32;
+        let mut samples = vec![0.0; (bar * BAR_ROOTS.len() as f32 * SAMPLE_RATE as f32) as usize];
+
+        for (index, (root, triad)) in BAR_ROOTS.iter().zip(BAR_TRIADS).enumerate() {
+            let bar_start = index as f32 * bar;
+            match self {
+                Self::Base => {
+                    // Pad: the triad held for the whole bar
+                    for semitones in triad {
+                        let frequency = root * 2.0 * semitone(semitones);
+                        add_note(&mut samples, bar_start, bar, |t| {
+                            0.06 * (TAU * frequency * t).sin() * swell(t, bar)
+                        });
+                    }
+                    // Bass: the root on every beat
+                    for step in 0..BEATS_PER_BAR {
+                        add_note(&mut samples, bar_start + step as f32 * beat, beat, |t| {
+                            let tone = (TAU * root * t).sin() + 0.3 * (TAU * root * 2.0 * t).sin();
+                            0.25 * tone * (-t * 4.0).exp()
+                        });
+                    }
+                }
+                Self::Percussion => {
+                    let mut noise = RngStream::from_seed(index as u64);
+                    for step in 0..BEATS_PER_BAR * 2 {
+                        let start = bar_start + step as f32 * beat / 2.0;
+                        let on_beat = step % 2 == 0;
+                        if on_beat && (step / 2) % 2 == 0 {
+                            // Kick: a quickly falling sine
+                            add_note(&mut samples, start, 0.3, |t| {
+                                let phase =
+                                    TAU * (50.0 * t + 60.0 * (1.0 - (-t * 30.0).exp()) / 30.0);
+                                0.6 * phase.sin() * (-t * 12.0).exp()
+                            });
+                        } else if on_beat {
+                            // Snare: a burst of noise
+                            add_note(&mut samples, start, 0.2, |t| {
+                                0.3 * (noise.f32() * 2.0 - 1.0) * (-t * 20.0).exp()
+                            });
+                        }
+                        // Hi-hat on every eighth
+                        add_note(&mut samples, start, 0.05, |t| {
+                            0.08 * (noise.f32() * 2.0 - 1.0) * (-t * 80.0).exp()
+                        });
+                    }
+                }
+                Self::Lead => {
+                    // Arpeggio of the triad, two octaves up, on eighths
+                    for step in 0..BEATS_PER_BAR * 2 {
+                        let semitones = triad[step % triad.len()] + if step >= 6 { 12 } else { 0 };
+                        let frequency = root * 4.0 * semitone(semitones);
+                        add_note(
+                            &mut samples,
+                            bar_start + step as f32 * beat / 2.0,
+                            beat / 2.0,
+                            |t| 0.15 * triangle(frequency * t) * (-t * 6.0).exp(),
+                        );
+                    }
+                }
+            }
+        }
+
+        MusicLoop {
+            samples: samples.into(),
+        }
+    }
+}
+
+/// A sound listed on the sound test screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JukeboxTrack {
+    /// A music layer, played on its own
+    Music(MusicLayer),
+    /// The milestone fanfare
+    Fanfare,
+    /// A sound effect
+    Effect(SoundEffect),
+}
+
+impl JukeboxTrack {
+    /// Every music layer and sound effect, in display order.
+    pub const ALL: [JukeboxTrack; 9] = [
+        JukeboxTrack::Music(MusicLayer::Base),
+        JukeboxTrack::Music(MusicLayer::Percussion),
+        JukeboxTrack::Music(MusicLayer::Lead),
+        JukeboxTrack::Fanfare,
+        JukeboxTrack::Effect(SoundEffect::Click),
+        JukeboxTrack::Effect(SoundEffect::Hover),
+        JukeboxTrack::Effect(SoundEffect::Back),
+        JukeboxTrack::Effect(SoundEffect::Whoosh),
+        JukeboxTrack::Effect(SoundEffect::Tick),
+    ];
+
+    /// Returns the track's display name.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Music(layer) => layer.label(),
+            Self::Fanfare => "Milestone fanfare",
+            Self::Effect(effect) => effect.label(),
+        }
+    }
+
+    /// Returns true for music, which loops until stopped.
+    pub fn loops(&self) -> bool {
+        matches!(self, Self::Music(_))
+    }
+}
+
+/// A row of the sound test screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JukeboxRow {
+    /// Plays a track
+    Track(JukeboxTrack),
+    /// Stops the playing track
+    Stop,
+    /// Returns to the settings screen
+    Back,
+}
+
+impl JukeboxRow {
+    /// All rows, in display order.
+    pub const ALL: [JukeboxRow; 11] = [
+        JukeboxRow::Track(JukeboxTrack::ALL[0]),
+        JukeboxRow::Track(JukeboxTrack::ALL[1]),
+        JukeboxRow::Track(JukeboxTrack::ALL[2]),
+        JukeboxRow::Track(JukeboxTrack::ALL[3]),
+        JukeboxRow::Track(JukeboxTrack::ALL[4]),
+        JukeboxRow::Track(JukeboxTrack::ALL[5]),
+        JukeboxRow::Track(JukeboxTrack::ALL[6]),
+        JukeboxRow::Track(JukeboxTrack::ALL[7]),
+        JukeboxRow::Track(JukeboxTrack::ALL[8]),
+        JukeboxRow::Stop,
+        JukeboxRow::Back,
+    ];
+
+    /// Returns the text shown for this row, marking the playing track.
+    pub fn label(&self, playing: Option<JukeboxTrack>) -> String {
+        match self {
+            Self::Track(track) if playing == Some(*track) => {
+                format!("> {} (playing)", track.label())
+            }
+            Self::Track(track) => format!("Play: {}", track.label()),
+            Self::Stop => "Stop".to_string(),
+            Self::Back => "Back".to_string(),
+        }
+    }
+}
+
+/// Length of each rising note of the fanfare, in seconds.
+const FANFARE_STEP: f32 = 0.11;
+
+/// Pitch of the fanfare's rising notes, in semitones above C5.
+const FANFARE_NOTES: [i32; 4] = [0, 4, 7, 12];
+
+/// Synthesizes the short fanfare played with milestone banners.
+///
+/// A rising C major arpeggio ends on the full chord.
+pub fn synthesize_fanfare() -> MusicLoop {
+    const C5: f32 = 523.25;
+    let chord_length = 0.6;
+    let length = FANFARE_STEP * FANFARE_NOTES.len() as f32 + chord_length;
+    let mut samples = vec![0.0; (length * SAMPLE_RATE as f32) as usize];
+
+    for (step, semitones) in FANFARE_NOTES.iter().enumerate() {
+        let frequency = C5 * semitone(*semitones);
+        add_note(
+            &mut samples,
+            step as f32 * FANFARE_STEP,
+            FANFARE_STEP,
+            |t| 0.25 * triangle(frequency * t) * (-t * 8.0).exp(),
+        );
+    }
+    let chord_start = FANFARE_STEP * FANFARE_NOTES.len() as f32;
+    for semitones in FANFARE_NOTES {
+        let frequency = C5 * semitone(semitones);
+        add_note(&mut samples, chord_start, chord_length, |t| {
+            0.12 * triangle(frequency * t) * (-t * 4.0).exp()
+        });
+    }
+
+    MusicLoop {
+        samples: samples.into(),
+    }
+}
+
+/// Returns the frequency ratio of a number of semitones.
+fn semitone(semitones: i32) -> f32 {
+    2f32.powf(semitones as f32 / 12.0)
+}
+
+/// A triangle wave with a period of 1.
+pub fn triangle(phase: f32) -> f32 {
+    4.0 * (phase - (phase + 0.5).floor()).abs() - 1.0
+}
+
+/// An envelope rising and falling smoothly over `length` seconds.
+fn swell(t: f32, length: f32) -> f32 {
+    (std::f32::consts::PI * t / length).sin()
+}
+
+/// Mixes a note into the loop, wrapping past its end.
+///
+/// `wave` receives the time since the note started.
+pub fn add_note(samples: &mut [f32], start: f32, length: f32, mut wave: impl FnMut(f32) -> f32) {
+    let first = (start * SAMPLE_RATE as f32) as usize;
+    let count = (length * SAMPLE_RATE as f32) as usize;
+    for index in 0..count {
+        let t = index as f32 / SAMPLE_RATE as f32;
+        let position = (first + index) % samples.len();
+        samples[position] += wave(t);
+    }
+}
+
+/// A synthesized sound (a music layer loop or the fanfare), playable as audio.
+#[derive(Asset, TypePath, Clone)]
+pub struct MusicLoop {
+    /// Mono samples at `SAMPLE_RATE`
+    samples: Arc<[f32]>,
+}
+
+impl MusicLoop {
+    /// Wraps mono samples at `SAMPLE_RATE`.
+    pub fn from_samples(samples: Vec<f32>) -> Self {
+        Self {
+            samples: samples.into(),
+        }
+    }
+
+    /// Returns the loop length in seconds.
+    #[cfg(test)]
+    pub fn duration(&self) -> f32 {
+        self.samples.len() as f32 / SAMPLE_RATE as f32
+    }
+
+    /// Returns true if any sample is loud enough to hear.
+    #[cfg(test)]
+    pub fn is_audible(&self) -> bool {
+        self.samples.iter().any(|sample| sample.abs() > 0.01)
+    }
+}
+
+impl Decodable for MusicLoop {
+    type DecoderItem = f32;
+    type Decoder = MusicLoopDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        MusicLoopDecoder {
+            samples: self.samples.clone(),
+            position: 0,
+        }
+    }
+}
+
+/// Plays a `MusicLoop` once (looping is handled by the playback settings).
+pub struct MusicLoopDecoder {
+    /// The loop's samples
+    samples: Arc<[f32]>,
+    /// Index of the next sample
+    position: usize,
+}
+
+impl Iterator for MusicLoopDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position)?;
+        self.position += 1;
+        Some(sample.clamp(-1.0, 1.0))
+    }
+}
+
+impl Source for MusicLoopDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len() - self.position)
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.samples.len() as f32 / SAMPLE_RATE as f32,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers_fade_in_with_score() {
+        let config = MusicConfig::default();
+        let volumes =
+            |score| MusicLayer::ALL.map(|layer| layer.target_volume(score, &config) > 0.0);
+        assert_eq!(volumes(0), [true, false, false]);
+        assert_eq!(volumes(config.percussion_score), [true, true, false]);
+        assert_eq!(volumes(config.lead_score), [true, true, true]);
+    }
+
+    #[test]
+    fn jukebox_lists_every_track() {
+        for track in JukeboxTrack::ALL {
+            assert!(JukeboxRow::ALL.contains(&JukeboxRow::Track(track)));
+        }
+        for layer in MusicLayer::ALL {
+            assert!(JukeboxTrack::ALL.contains(&JukeboxTrack::Music(layer)));
+        }
+        for effect in SoundEffect::ALL {
+            assert!(JukeboxTrack::ALL.contains(&JukeboxTrack::Effect(effect)));
+        }
+    }
+
+    #[test]
+    fn fanfare_is_short_and_audible() {
+        let fanfare = synthesize_fanfare();
+        assert!(fanfare.duration() < 1.5);
+        assert!(fanfare.is_audible());
+    }
+
+    #[test]
+    fn layers_loop_together() {
+        let loops = MusicLayer::ALL.map(|layer| layer.synthesize());
+        for music in &loops {
+            assert_eq!(music.samples.len(), loops[0].samples.len());
+            assert!(music.is_audible());
+        }
+        assert!((loops[0].duration() - 16.0 * 60.0 / TEMPO).abs() < 0.01);
+    }
+}
@@ -0,0 +1,207 @@
+//! Per-run mutators.
+//!
+//! Mutators are picked on the mutator screen before a run (or with
+//! `--mutator` on the command line). Each one is a small change to the
+//! `GameConfig`, applied on top of the configuration file and the
+//! difficulty. Runs with mutators on are ranked apart from the regular
+//! ones: the save file keeps a best score for each set of mutators, under
+//! its tag (such as `big-bird+double-speed`).
+
+use std::collections::BTreeSet;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GameConfig;
+use crate::constants::{MUTATOR_BIG_BIRD_SCALE, MUTATOR_SPEED_SCALE, MUTATOR_TRAIL};
+use crate::settings::on_off;
+
+/// A change to the rules of a run.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Mutator {
+    /// A bird half again as big, hitbox included
+    BigBird,
+    /// Pipes scroll twice as fast (their spacing stays the same)
+    DoubleSpeed,
+    /// The bird is hidden, leaving only its trail to follow
+    InvisibleBird,
+    /// Gravity pulls the bird up and flaps push it down
+    InvertedControls,
+}
+
+impl Mutator {
+    /// Every mutator, in the order they are listed on the mutator screen.
+    pub const ALL: [Mutator; 4] = [
+        Mutator::BigBird,
+        Mutator::DoubleSpeed,
+        Mutator::InvisibleBird,
+        Mutator::InvertedControls,
+    ];
+
+    /// Returns the mutator's display name.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::BigBird => "Big bird",
+            Self::DoubleSpeed => "Double speed",
+            Self::InvisibleBird => "Invisible bird",
+            Self::InvertedControls => "Inverted controls",
+        }
+    }
+
+    /// Returns the name the mutator is stored and picked by on the command
+    /// line.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::BigBird => "big-bird",
+            Self::DoubleSpeed => "double-speed",
+            Self::InvisibleBird => "invisible-bird",
+            Self::InvertedControls => "inverted-controls",
+        }
+    }
+
+    /// Parses a mutator tag (case-insensitive).
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mutator| mutator.tag().eq_ignore_ascii_case(tag))
+    }
+
+    /// Applies the mutator's change to `config`.
+    fn apply(self, config: &mut GameConfig) {
+        match self {
+            Self::BigBird => config.physics.bird_scale *= MUTATOR_BIG_BIRD_SCALE,
+            Self::DoubleSpeed => config.pipes.scroll_speed *= MUTATOR_SPEED_SCALE,
+            Self::InvisibleBird => {
+                config.effects.hide_bird = true;
+                config.effects.bird_trail = Some(MUTATOR_TRAIL.to_string());
+            }
+            Self::InvertedControls => {
+                config.physics.inverted_gravity = !config.physics.inverted_gravity;
+            }
+        }
+    }
+}
+
+/// Resource holding the mutators of the next runs.
+///
+/// Like the difficulty, the mutators are applied on top of the
+/// configuration file each time it is (re)loaded, and recorded in replays so
+/// they play back the same.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Mutators(pub BTreeSet<Mutator>);
+
+impl Mutators {
+    /// Returns whether no mutator is on.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether a mutator is on.
+    pub fn contains(&self, mutator: Mutator) -> bool {
+        self.0.contains(&mutator)
+    }
+
+    /// Turns a mutator on or off.
+    pub fn toggle(&mut self, mutator: Mutator) {
+        if !self.0.remove(&mutator) {
+            self.0.insert(mutator);
+        }
+    }
+
+    /// Returns `config` with every mutator's change applied.
+    pub fn apply(&self, mut config: GameConfig) -> GameConfig {
+        for mutator in &self.0 {
+            mutator.apply(&mut config);
+        }
+        config
+    }
+
+    /// Returns the tag scores of this set of mutators are ranked under: the
+    /// mutators' tags joined with `+`, always in the same order.
+    pub fn tag(&self) -> String {
+        self.0
+            .iter()
+            .map(|mutator| mutator.tag())
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+/// A row of the mutator screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MutatorRow {
+    /// Turns a mutator on or off
+    Toggle(Mutator),
+    /// Starts a run with the mutators on
+    Start,
+    /// Returns to the menu
+    Back,
+}
+
+impl MutatorRow {
+    /// Every row, in the order they are shown.
+    pub const ALL: [MutatorRow; 6] = [
+        MutatorRow::Toggle(Mutator::ALL[0]),
+        MutatorRow::Toggle(Mutator::ALL[1]),
+        MutatorRow::Toggle(Mutator::ALL[2]),
+        MutatorRow::Toggle(Mutator::ALL[3]),
+        MutatorRow::Start,
+        MutatorRow::Back,
+    ];
+
+    /// Returns the row's text for the current mutators.
+    pub fn label(self, mutators: &Mutators) -> String {
+        match self {
+            Self::Toggle(mutator) => format!(
+                "{}: < {} >",
+                mutator.label(),
+                on_off(mutators.contains(mutator))
+            ),
+            Self::Start => "Start".to_string(),
+            Self::Back => "Back".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutators_change_the_config() {
+        let normal = GameConfig::default();
+        assert_eq!(Mutators::default().apply(normal.clone()), normal);
+
+        let mut mutators = Mutators::default();
+        for mutator in Mutator::ALL {
+            mutators.toggle(mutator);
+        }
+        let mutated = mutators.apply(normal.clone());
+        assert!(mutated.physics.bird_scale > normal.physics.bird_scale);
+        assert!(mutated.pipes.scroll_speed > normal.pipes.scroll_speed);
+        assert_eq!(mutated.pipes.spawn_distance, normal.pipes.spawn_distance);
+        assert!(mutated.effects.hide_bird);
+        assert!(mutated.effects.bird_trail.is_some());
+        assert!(mutated.physics.inverted_gravity);
+    }
+
+    #[test]
+    fn tags_are_ordered_and_read_back() {
+        let mut mutators = Mutators::default();
+        assert_eq!(mutators.tag(), "");
+        mutators.toggle(Mutator::InvertedControls);
+        mutators.toggle(Mutator::BigBird);
+        assert_eq!(mutators.tag(), "big-bird+inverted-controls");
+        mutators.toggle(Mutator::BigBird);
+        assert_eq!(mutators.tag(), "inverted-controls");
+
+        for mutator in Mutator::ALL {
+            assert_eq!(Mutator::from_tag(mutator.tag()), Some(mutator));
+        }
+        assert_eq!(
+            Mutator::from_tag("Double-Speed"),
+            Some(Mutator::DoubleSpeed)
+        );
+        assert_eq!(Mutator::from_tag("tiny-bird"), None);
+    }
+}
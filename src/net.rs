@@ -0,0 +1,379 @@
+//! Minimal HTTP client.
+//!
+//! A tiny blocking HTTP/1.1 client over `std::net`, used by optional online
+//! features. Only plain `http://` URLs are supported; point HTTPS services at
+//! a local TLS-terminating proxy.
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use bevy::log::warn;
+
+/// Timeout applied to connecting, reading, and writing.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed `http://` URL.
+#[derive(Debug, PartialEq, Eq)]
+struct HttpUrl {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+/// Parses an `http://host[:port][/path]` URL.
+fn parse_url(url: &str) -> io::Result<HttpUrl> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("only http:// URLs are supported: {url}"),
+        )
+    })?;
+
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => {
+            let port = port.parse().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("bad port in {url}"))
+            })?;
+            (host, port)
+        }
+        None => (authority, 80),
+    };
+
+    if host.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("missing host in {url}"),
+        ));
+    }
+
+    Ok(HttpUrl {
+        host: host.to_string(),
+        port,
+        path: path.to_string(),
+    })
+}
+
+impl HttpUrl {
+    /// Returns the value of the `Host` header, with the port unless it is
+    /// the default one.
+    fn host_header(&self) -> String {
+        if self.port == 80 {
+            self.host.clone()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// Returns the `Host` header value and the request path `request` sends for
+/// a URL, for signing requests.
+pub fn host_and_path(url: &str) -> io::Result<(String, String)> {
+    let url = parse_url(url)?;
+    Ok((url.host_header(), url.path))
+}
+
+/// An HTTP response.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    /// Status code
+    pub status: u16,
+    /// Header names and values, in order
+    pub headers: Vec<(String, String)>,
+    /// Body, with any chunked transfer encoding removed
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Returns true for 2xx status codes.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Returns the value of a header, matching its name case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Sends a request with extra headers and returns the response.
+pub fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> io::Result<Response> {
+    let url = parse_url(url)?;
+    let address = format!("{}:{}", url.host, url.port);
+
+    let mut stream = TcpStream::connect(&address)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let mut header = format!(
+        "{method} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        url.path,
+        url.host_header(),
+        body.len()
+    );
+    for (name, value) in headers {
+        header.push_str(&format!("{name}: {value}\r\n"));
+    }
+    header.push_str("\r\n");
+    stream.write_all(header.as_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    parse_response(&response)
+}
+
+/// Sends a `POST` request, failing unless the response is a success.
+pub fn post(url: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    let response = request("POST", url, &[("Content-Type", content_type)], body)?;
+    if response.is_success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("HTTP status {}", response.status)))
+    }
+}
+
+/// Reads the destination of an opt-in feature from the environment variable
+/// `var`, or `None` if it is unset or blank.
+///
+/// A destination `parse` refuses turns the feature off, with a warning
+/// naming it, rather than failing at every delivery.
+pub fn target_from_env<T>(
+    var: &str,
+    feature: &str,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Option<T> {
+    let value = std::env::var(var).ok()?;
+    let target = value.trim();
+    if target.is_empty() {
+        return None;
+    }
+    match parse(target) {
+        Ok(parsed) => Some(parsed),
+        Err(error) => {
+            warn!("{feature} disabled: ignoring {var}={target}: {error}");
+            None
+        }
+    }
+}
+
+/// Returns whether a URL points at this machine, such as a local
+/// TLS-terminating proxy, so what is sent to it never crosses the network
+/// in the clear.
+pub fn is_loopback(url: &str) -> bool {
+    parse_url(url).is_ok_and(|url| {
+        let host = url.host.trim_start_matches('[').trim_end_matches(']');
+        host.eq_ignore_ascii_case("localhost")
+            || host
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|address| address.is_loopback())
+    })
+}
+
+/// Returns the value of a `Basic` authorization header for `user:password`.
+pub fn basic_auth(credentials: &str) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::from("Basic ");
+    for chunk in credentials.as_bytes().chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(ALPHABET[(bits >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Splits a raw HTTP response into its status code, headers and body.
+fn parse_response(response: &[u8]) -> io::Result<Response> {
+    let malformed = || io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response");
+
+    let (head, body) = match response.windows(4).position(|window| window == b"\r\n\r\n") {
+        Some(index) => (&response[..index], &response[index + 4..]),
+        None => (response, &[][..]),
+    };
+    let head = String::from_utf8_lossy(head);
+
+    let status = head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(malformed)?;
+
+    let headers = head
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+    let mut response = Response {
+        status,
+        headers,
+        body: Vec::new(),
+    };
+
+    let chunked = response
+        .header("transfer-encoding")
+        .is_some_and(|value| value.eq_ignore_ascii_case("chunked"));
+    response.body = if chunked {
+        decode_chunked(body).ok_or_else(malformed)?
+    } else {
+        body.to_vec()
+    };
+
+    Ok(response)
+}
+
+/// Removes chunked transfer encoding from a response body.
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|window| window == b"\r\n")?;
+        let size_line = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size_text = size_line.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_text, 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_url_splits_host_port_and_path() {
+        let url = parse_url("http://localhost:8080/events").ok();
+        assert_eq!(
+            url,
+            Some(HttpUrl {
+                host: "localhost".to_string(),
+                port: 8080,
+                path: "/events".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_url_defaults_port_and_path() {
+        let url = parse_url("http://example.com").ok();
+        assert_eq!(
+            url,
+            Some(HttpUrl {
+                host: "example.com".to_string(),
+                port: 80,
+                path: "/".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn host_header_keeps_non_default_ports() {
+        let host = |url| parse_url(url).map(|url| url.host_header()).ok();
+        assert_eq!(
+            host("http://example.com/events"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(
+            host("http://example.com:80/"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(
+            host("http://example.com:8080/events"),
+            Some("example.com:8080".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_url_rejects_https() {
+        assert!(parse_url("https://example.com/").is_err());
+    }
+
+    #[test]
+    fn loopback_urls_are_detected() {
+        assert!(is_loopback("http://localhost:8080/save.ron"));
+        assert!(is_loopback("http://127.0.0.1/save.ron"));
+        assert!(is_loopback("http://[::1]:8080/save.ron"));
+        assert!(!is_loopback("http://nas.local/save.ron"));
+        assert!(!is_loopback("https://localhost/save.ron"));
+    }
+
+    #[test]
+    fn parse_response_reads_code() {
+        assert_eq!(
+            parse_response(b"HTTP/1.1 204 No Content\r\n\r\n")
+                .ok()
+                .map(|response| response.status),
+            Some(204)
+        );
+        assert!(parse_response(b"garbage").is_err());
+    }
+
+    #[test]
+    fn parse_response_reads_plain_and_chunked_bodies() {
+        let plain = parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello").ok();
+        assert_eq!(plain.map(|response| response.body), Some(b"hello".to_vec()));
+
+        let chunked = parse_response(
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nhel\r\n2\r\nlo\r\n0\r\n\r\n",
+        )
+        .ok();
+        assert_eq!(
+            chunked.map(|response| response.body),
+            Some(b"hello".to_vec())
+        );
+    }
+
+    #[test]
+    fn parse_response_reads_headers() {
+        let response =
+            parse_response(b"HTTP/1.1 200 OK\r\nDate: Sun, 06 Nov 1994 08:49:37 GMT\r\n\r\n").ok();
+        assert_eq!(
+            response
+                .as_ref()
+                .and_then(|response| response.header("date")),
+            Some("Sun, 06 Nov 1994 08:49:37 GMT")
+        );
+        assert_eq!(
+            response
+                .as_ref()
+                .and_then(|response| response.header("server")),
+            None
+        );
+    }
+
+    #[test]
+    fn basic_auth_encodes_base64() {
+        assert_eq!(basic_auth("user:pass"), "Basic dXNlcjpwYXNz");
+        assert_eq!(basic_auth("ab"), "Basic YWI=");
+        assert_eq!(basic_auth("a"), "Basic YQ==");
+    }
+}
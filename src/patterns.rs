@@ -0,0 +1,178 @@
+//! Authored pipe patterns.
+//!
+//! A `PipePattern` is a short sequence of pipe columns (staircases, zigzags,
+//! double gaps, ...) defined in a RON asset in `assets/patterns/`. The pipe
+//! spawner interleaves them with random pipe pairs, picking each pattern with
+//! a weight that grows with the score.
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext, LoadedFolder};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Asset folder patterns are loaded from.
+pub const PATTERNS_FOLDER: &str = "patterns";
+
+/// An authored sequence of pipe columns.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug, PartialEq)]
+pub struct PipePattern {
+    /// Name used in logs and for deterministic ordering
+    pub name: String,
+    /// Score required before the pattern can appear
+    #[serde(default)]
+    pub min_score: u32,
+    /// Selection weight at `min_score` (random pipe pairs use `pipes.random_pair_weight`)
+    #[serde(default)]
+    pub weight: f32,
+    /// Weight added per point scored above `min_score`
+    #[serde(default)]
+    pub weight_per_point: f32,
+    /// Upper bound for the weight
+    #[serde(default = "default_max_weight")]
+    pub max_weight: f32,
+    /// Columns spawned in order
+    pub columns: Vec<PipeColumn>,
+}
+
+fn default_max_weight() -> f32 {
+    f32::MAX
+}
+
+impl PipePattern {
+    /// Returns the selection weight of this pattern at a given score.
+    pub fn weight_at(&self, score: u32) -> f32 {
+        if score < self.min_score {
+            return 0.0;
+        }
+        let bonus = self.weight_per_point * (score - self.min_score) as f32;
+        (self.weight + bonus).clamp(0.0, self.max_weight)
+    }
+}
+
+/// A single column of pipes with one or more gaps.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PipeColumn {
+    /// Openings in the column
+    pub gaps: Vec<GapSpec>,
+    /// Distance to the next column, in multiples of `pipes.spawn_distance`
+    pub spacing: f32,
+}
+
+impl Default for PipeColumn {
+    /// A classic pipe pair: one gap at a random height with a random size.
+    fn default() -> Self {
+        Self {
+            gaps: vec![GapSpec::default()],
+            spacing: 1.0,
+        }
+    }
+}
+
+/// An opening in a pipe column.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct GapSpec {
+    /// Vertical position from -1.0 (lowest) to 1.0 (highest); random if unset
+    pub y: Option<f32>,
+    /// Gap height in pixels; follows the difficulty curve if unset
+    pub size: Option<f32>,
+}
+
+/// Handle keeping every pattern in the patterns folder loaded.
+#[derive(Resource)]
+pub struct PatternLibrary(#[allow(dead_code)] pub Handle<LoadedFolder>);
+
+/// Returns the solid segments (bottom, top) of a column spanning `bottom..top`
+/// with the given gaps (center, size), skipping empty segments.
+pub fn column_segments(gaps: &[(f32, f32)], bottom: f32, top: f32) -> Vec<(f32, f32)> {
+    let mut sorted = gaps.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut segments = Vec::new();
+    let mut cursor = bottom;
+    for (center, size) in sorted {
+        let gap_bottom = (center - size / 2.0).clamp(bottom, top);
+        if gap_bottom > cursor {
+            segments.push((cursor, gap_bottom));
+        }
+        cursor = cursor.max((center + size / 2.0).clamp(bottom, top));
+    }
+    if top > cursor {
+        segments.push((cursor, top));
+    }
+    segments
+}
+
+/// Loads `*.pattern.ron` files into `PipePattern` assets.
+#[derive(Default, TypePath)]
+pub struct PipePatternLoader;
+
+impl AssetLoader for PipePatternLoader {
+    type Asset = PipePattern;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["pattern.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_patterns_parse() {
+        for source in [
+            include_str!("../assets/patterns/staircase.pattern.ron"),
+            include_str!("../assets/patterns/zigzag.pattern.ron"),
+            include_str!("../assets/patterns/double_gap.pattern.ron"),
+        ] {
+            let pattern = ron::from_str::<PipePattern>(source);
+            assert!(pattern.is_ok_and(|pattern| !pattern.columns.is_empty()));
+        }
+    }
+
+    #[test]
+    fn weight_scales_with_score_and_is_capped() {
+        let pattern = PipePattern {
+            name: "test".to_string(),
+            min_score: 5,
+            weight: 0.2,
+            weight_per_point: 0.1,
+            max_weight: 1.0,
+            columns: vec![PipeColumn::default()],
+        };
+        assert_eq!(pattern.weight_at(4), 0.0);
+        assert_eq!(pattern.weight_at(5), 0.2);
+        assert!((pattern.weight_at(7) - 0.4).abs() < 1e-6);
+        assert_eq!(pattern.weight_at(100), 1.0);
+    }
+
+    #[test]
+    fn column_segments_surround_each_gap() {
+        let segments = column_segments(&[(50.0, 20.0), (-50.0, 20.0)], -100.0, 100.0);
+        assert_eq!(
+            segments,
+            vec![(-100.0, -60.0), (-40.0, 40.0), (60.0, 100.0)]
+        );
+    }
+
+    #[test]
+    fn column_segments_skip_empty_and_overlapping_parts() {
+        let segments = column_segments(&[(-95.0, 20.0), (0.0, 40.0), (10.0, 40.0)], -100.0, 100.0);
+        assert_eq!(segments, vec![(-85.0, -20.0), (30.0, 100.0)]);
+    }
+}
@@ -0,0 +1,183 @@
+//! Portable save files.
+//!
+//! The settings screen can export all of a player's progress and settings
+//! to `flappy-save.ron`, a single versioned file in the save directory, and
+//! import it back. The file is plain RON, so it can be copied between the
+//! desktop, web and mobile builds to carry progress over.
+//!
+//! Imports are validated before anything is replaced: the file must have
+//! every section, come from this version of the format or an older one, and
+//! hold values the game can use. The best scores travel with their
+//! signature, so a file edited by hand is refused rather than trusted.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::save::{SaveData, Versioned, save_dir};
+use crate::settings::Settings;
+
+/// Name of the portable save file inside the save directory.
+pub const SAVE_EXPORT_FILE_NAME: &str = "flappy-save.ron";
+
+/// Version of the portable save format, raised when a change would make
+/// older games misread newer files.
+const SAVE_EXPORT_VERSION: u32 = 1;
+
+/// Contents of a portable save file.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SaveExport {
+    /// Format version, checked on import
+    version: u32,
+    /// Player progress: best scores, achievements, playtime, coins and trails
+    save: SaveData,
+    /// Signature of the best scores in `save`
+    signature: u64,
+    /// Player settings
+    settings: Settings,
+}
+
+/// The format version of a portable save file, ignoring the rest.
+#[derive(Deserialize)]
+struct ExportHeader {
+    /// Format version, 0 if missing
+    #[serde(default)]
+    version: u32,
+}
+
+/// Progress and settings read from a portable save file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PortableSave {
+    /// Player progress
+    pub save: SaveData,
+    /// Player settings
+    pub settings: Settings,
+}
+
+impl PortableSave {
+    /// Writes the progress and settings to the portable save file, returning
+    /// its path.
+    pub fn export(&self) -> Result<PathBuf, String> {
+        let path = save_dir().join(SAVE_EXPORT_FILE_NAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        }
+        fs::write(&path, self.to_export_string()?).map_err(|error| error.to_string())?;
+        Ok(path)
+    }
+
+    /// Reads progress and settings from the portable save file.
+    pub fn import() -> Result<Self, String> {
+        let path = save_dir().join(SAVE_EXPORT_FILE_NAME);
+        let contents =
+            fs::read_to_string(&path).map_err(|_| format!("{SAVE_EXPORT_FILE_NAME} not found"))?;
+        Self::from_export_str(&contents)
+    }
+
+    /// Serializes the progress and settings in the portable format.
+    fn to_export_string(&self) -> Result<String, String> {
+        let export = SaveExport {
+            version: SAVE_EXPORT_VERSION,
+            save: self.save.clone(),
+            signature: self.save.sign().unwrap_or_default(),
+            settings: self.settings.clone(),
+        };
+        ron::ser::to_string_pretty(&export, ron::ser::PrettyConfig::default())
+            .map_err(|error| error.to_string())
+    }
+
+    /// Parses and validates progress and settings in the portable format.
+    ///
+    /// Files from a newer version are refused rather than half-read. The
+    /// imported settings count as changed now, so they win the next sync.
+    fn from_export_str(contents: &str) -> Result<Self, String> {
+        let header: ExportHeader =
+            ron::from_str(contents).map_err(|_| "not a save file".to_string())?;
+        match header.version {
+            0 => return Err("not a save file".to_string()),
+            version if version > SAVE_EXPORT_VERSION => {
+                return Err(format!("made by a newer version (format {version})"));
+            }
+            _ => {}
+        }
+        let export: SaveExport =
+            ron::from_str(contents).map_err(|error| format!("invalid save file: {error}"))?;
+        if export.save.sign() != Some(export.signature) {
+            return Err("the best scores were edited".to_string());
+        }
+        export.save.validate()?;
+        export.settings.validate()?;
+
+        let mut settings = export.settings;
+        settings.touch();
+        Ok(Self {
+            save: export.save,
+            settings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::achievements::Achievement;
+
+    fn portable_save() -> PortableSave {
+        let mut save = SaveData {
+            best_score: 42,
+            playtime: 3600.0,
+            coins: 120,
+            equipped_trail: Some("Bubbles".to_string()),
+            ..SaveData::default()
+        };
+        save.achievements.insert(Achievement::Score25);
+        save.trails.insert("Bubbles".to_string());
+        PortableSave {
+            save,
+            settings: Settings {
+                theme: "night".to_string(),
+                ..Settings::default()
+            },
+        }
+    }
+
+    #[test]
+    fn exported_saves_import_back() {
+        let portable = portable_save();
+        let imported = portable
+            .to_export_string()
+            .and_then(|contents| PortableSave::from_export_str(&contents))
+            .map(|mut imported| {
+                imported.settings.updated_at = portable.settings.updated_at;
+                imported
+            });
+        assert_eq!(imported, Ok(portable));
+    }
+
+    #[test]
+    fn import_rejects_invalid_files() {
+        assert!(PortableSave::from_export_str("not ron").is_err());
+        assert!(PortableSave::from_export_str("(save: ())").is_err());
+        assert!(PortableSave::from_export_str("(version: 99, save: ())").is_err());
+        // Every section is required, including the signature
+        assert!(PortableSave::from_export_str("(version: 1, save: (), settings: ())").is_err());
+
+        let Ok(contents) = portable_save().to_export_string() else {
+            panic!("export failed");
+        };
+        let edited = contents.replace("best_score: 42", "best_score: 999");
+        assert_eq!(
+            PortableSave::from_export_str(&edited),
+            Err("the best scores were edited".to_string())
+        );
+
+        let mut unowned = portable_save();
+        unowned.save.trails.clear();
+        let imported = unowned
+            .to_export_string()
+            .and_then(|contents| PortableSave::from_export_str(&contents));
+        assert!(imported.is_err());
+    }
+}
@@ -0,0 +1,583 @@
+//! Online real-time races.
+//!
+//! When `FLAPPY_RACE` is set, 2–4 players fly through the same seeded pipe
+//! sequence at the same time over UDP. One player hosts (`host:7777`) and the
+//! others join (`join:192.168.1.20:7777`). The host starts each run for
+//! everyone and relays position updates between the players; the last one
+//! alive wins.
+//!
+//! Packets are single-line text datagrams, e.g. `STATE 2 -31.5 120.0`.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use bevy::prelude::*;
+
+/// Environment variable that enables racing and holds the role.
+pub const RACE_ENV_VAR: &str = "FLAPPY_RACE";
+
+/// Most players in a race, the host included.
+pub const MAX_RACERS: usize = 4;
+
+/// Id of the hosting player.
+const HOST_ID: u8 = 0;
+
+/// Seconds without packets after which a player counts as gone.
+const PLAYER_TIMEOUT: f32 = 5.0;
+
+/// Seconds between join attempts (and keepalives once joined).
+const HELLO_INTERVAL: f32 = 1.0;
+
+/// Times each start and death is sent, so one lost datagram doesn't leave a
+/// guest waiting or the race undecided.
+const REPEATS: usize = 3;
+
+/// A race protocol message.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RacePacket {
+    /// A guest asks to join
+    Hello,
+    /// The host accepted a guest under this id
+    Welcome { id: u8 },
+    /// The host has no room left
+    Full,
+    /// The host started its `run`th run with this seed
+    Start { seed: u64, run: u32 },
+    /// A player's bird position and velocity
+    State { id: u8, y: f32, velocity: f32 },
+    /// A player crashed with this score
+    Dead { id: u8, score: u32 },
+}
+
+impl RacePacket {
+    /// Serializes the packet.
+    pub fn encode(&self) -> String {
+        match self {
+            Self::Hello => "HELLO".to_string(),
+            Self::Welcome { id } => format!("WELCOME {id}"),
+            Self::Full => "FULL".to_string(),
+            Self::Start { seed, run } => format!("START {seed} {run}"),
+            Self::State { id, y, velocity } => format!("STATE {id} {y} {velocity}"),
+            Self::Dead { id, score } => format!("DEAD {id} {score}"),
+        }
+    }
+
+    /// Parses a packet, returning `None` for anything malformed, including
+    /// ids past `MAX_RACERS` and non-finite positions.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let text = std::str::from_utf8(bytes).ok()?;
+        let mut words = text.split_whitespace();
+        let packet = match words.next()? {
+            "HELLO" => Self::Hello,
+            "WELCOME" => Self::Welcome {
+                id: parse_id(words.next()?)?,
+            },
+            "FULL" => Self::Full,
+            "START" => Self::Start {
+                seed: words.next()?.parse().ok()?,
+                run: words.next()?.parse().ok()?,
+            },
+            "STATE" => Self::State {
+                id: parse_id(words.next()?)?,
+                y: parse_finite(words.next()?)?,
+                velocity: parse_finite(words.next()?)?,
+            },
+            "DEAD" => Self::Dead {
+                id: parse_id(words.next()?)?,
+                score: words.next()?.parse().ok()?,
+            },
+            _ => return None,
+        };
+        words.next().is_none().then_some(packet)
+    }
+}
+
+/// Parses a player id, which must be below `MAX_RACERS`.
+fn parse_id(word: &str) -> Option<u8> {
+    word.parse().ok().filter(|id| usize::from(*id) < MAX_RACERS)
+}
+
+/// Parses a number, rejecting NaN and infinities.
+fn parse_finite(word: &str) -> Option<f32> {
+    word.parse().ok().filter(|value: &f32| value.is_finite())
+}
+
+/// Returns the 1-based player number shown for an id.
+pub fn player_number(id: u8) -> u16 {
+    u16::from(id) + 1
+}
+
+/// How this game takes part in the race.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RaceRole {
+    /// Hosts on this UDP port
+    Host { port: u16 },
+    /// Joins the host at this address
+    Join { address: String },
+}
+
+impl RaceRole {
+    /// Parses `host:PORT` or `join:ADDRESS:PORT`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (kind, rest) = value.trim().split_once(':')?;
+        match kind {
+            "host" => Some(Self::Host {
+                port: rest.parse().ok()?,
+            }),
+            "join" if rest.contains(':') => Some(Self::Join {
+                address: rest.to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Another player in the race.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Racer {
+    /// Last known bird height
+    pub y: f32,
+    /// Last known vertical velocity
+    pub velocity: f32,
+    /// Whether the player is still flying in the current run
+    pub alive: bool,
+    /// Seconds since the last packet from this player
+    pub silence: f32,
+}
+
+/// Something that happened in the race, for the game to react to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RaceEvent {
+    /// The host started a run with this seed
+    Started { seed: u64 },
+}
+
+/// Resource holding the race connection and the other players' state.
+#[derive(Resource)]
+pub struct RaceSession {
+    /// Non-blocking socket for all race traffic
+    socket: UdpSocket,
+    /// The host's address (guests only)
+    host: Option<SocketAddr>,
+    /// Guest addresses and ids (host only)
+    guests: Vec<(SocketAddr, u8)>,
+    /// This player's id, once known
+    pub id: Option<u8>,
+    /// Seed of the current race run (guests only)
+    pub seed: Option<u64>,
+    /// The other players, by id
+    pub racers: BTreeMap<u8, Racer>,
+    /// Whether this player is still flying in the current run
+    pub alive: bool,
+    /// Number of the current run: started so far (host), or last received
+    /// (guests), so repeated starts are only acted on once
+    run: u32,
+    /// Seconds until the next join attempt or keepalive (guests only)
+    hello_timer: f32,
+}
+
+impl RaceSession {
+    /// Creates the session from the environment, or `None` if not configured.
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var(RACE_ENV_VAR).ok()?;
+        let Some(role) = RaceRole::parse(&value) else {
+            warn!("Ignoring {RACE_ENV_VAR}={value}: expected host:PORT or join:ADDRESS:PORT");
+            return None;
+        };
+        match Self::new(&role) {
+            Ok(session) => Some(session),
+            Err(error) => {
+                warn!("Could not start the race: {error}");
+                None
+            }
+        }
+    }
+
+    /// Opens the socket for the given role.
+    pub fn new(role: &RaceRole) -> io::Result<Self> {
+        let (socket, host, id) = match role {
+            RaceRole::Host { port } => (UdpSocket::bind(("0.0.0.0", *port))?, None, Some(HOST_ID)),
+            RaceRole::Join { address } => {
+                let host = address.to_socket_addrs()?.next().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, format!("{address} not found"))
+                })?;
+                let local = if host.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                };
+                (UdpSocket::bind(local)?, Some(host), None)
+            }
+        };
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            host,
+            guests: Vec::new(),
+            id,
+            seed: None,
+            racers: BTreeMap::new(),
+            alive: false,
+            run: 0,
+            hello_timer: 0.0,
+        })
+    }
+
+    /// Returns true if this player hosts the race.
+    pub fn is_host(&self) -> bool {
+        self.host.is_none()
+    }
+
+    /// Returns the port the session listens on.
+    #[cfg(test)]
+    fn port(&self) -> u16 {
+        self.socket.local_addr().map_or(0, |address| address.port())
+    }
+
+    /// Sends a packet to the host, or to every guest when hosting.
+    fn broadcast(&self, packet: RacePacket, except: Option<SocketAddr>) {
+        let text = packet.encode();
+        let targets = self
+            .host
+            .into_iter()
+            .chain(self.guests.iter().map(|(address, _)| *address))
+            .filter(|address| Some(*address) != except);
+        for address in targets {
+            // Positions are resent every frame, and starts and deaths a few
+            // times, so lost datagrams are fine
+            let _ = self.socket.send_to(text.as_bytes(), address);
+        }
+    }
+
+    /// Starts a run for everyone with this seed (host only).
+    pub fn start(&mut self, seed: u64) {
+        self.reset_run();
+        self.run += 1;
+        let run = self.run;
+        for _ in 0..REPEATS {
+            self.broadcast(RacePacket::Start { seed, run }, None);
+        }
+    }
+
+    /// Marks everyone as flying again for a new run.
+    fn reset_run(&mut self) {
+        self.alive = true;
+        for racer in self.racers.values_mut() {
+            racer.alive = true;
+        }
+    }
+
+    /// Shares this player's bird position.
+    pub fn send_state(&self, y: f32, velocity: f32) {
+        if let Some(id) = self.id {
+            self.broadcast(RacePacket::State { id, y, velocity }, None);
+        }
+    }
+
+    /// Tells everyone this player crashed.
+    pub fn send_death(&mut self, score: u32) {
+        self.alive = false;
+        if let Some(id) = self.id {
+            for _ in 0..REPEATS {
+                self.broadcast(RacePacket::Dead { id, score }, None);
+            }
+        }
+    }
+
+    /// Notes that a player is still connected.
+    fn touch(&mut self, id: u8) {
+        self.racers
+            .entry(id)
+            .or_insert(Racer {
+                y: 0.0,
+                velocity: 0.0,
+                alive: false,
+                silence: 0.0,
+            })
+            .silence = 0.0;
+    }
+
+    /// Processes incoming packets and timeouts, returning what happened.
+    pub fn poll(&mut self, delta: f32) -> Vec<RaceEvent> {
+        if !self.is_host() {
+            self.hello_timer -= delta;
+            if self.hello_timer <= 0.0 {
+                self.hello_timer = HELLO_INTERVAL;
+                self.broadcast(RacePacket::Hello, None);
+            }
+        }
+
+        for racer in self.racers.values_mut() {
+            racer.silence += delta;
+        }
+        let timed_out: Vec<u8> = self
+            .racers
+            .iter()
+            .filter(|(_, racer)| racer.silence > PLAYER_TIMEOUT)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in timed_out {
+            info!("Player {} left the race", player_number(id));
+            self.racers.remove(&id);
+            self.guests.retain(|(_, guest)| *guest != id);
+        }
+
+        let mut events = Vec::new();
+        let mut buffer = [0u8; 256];
+        while let Ok((length, from)) = self.socket.recv_from(&mut buffer) {
+            if let Some(packet) = RacePacket::decode(&buffer[..length]) {
+                events.extend(self.handle(packet, from));
+            }
+        }
+        events
+    }
+
+    /// Applies one packet.
+    fn handle(&mut self, packet: RacePacket, from: SocketAddr) -> Option<RaceEvent> {
+        // Guests only listen to the host
+        if self.host.is_some_and(|host| host != from) {
+            return None;
+        }
+
+        match packet {
+            RacePacket::Hello if self.is_host() => self.welcome(from),
+            RacePacket::Welcome { id } => {
+                if self.id.is_none() {
+                    info!("Joined the race as player {}", player_number(id));
+                    self.id = Some(id);
+                }
+                self.touch(HOST_ID);
+            }
+            RacePacket::Full if self.id.is_none() => {
+                warn!("The race is full");
+                // Retry less eagerly in case a slot frees up
+                self.hello_timer = PLAYER_TIMEOUT;
+            }
+            RacePacket::Start { seed, run } if !self.is_host() && run != self.run => {
+                self.run = run;
+                self.seed = Some(seed);
+                self.reset_run();
+                return Some(RaceEvent::Started { seed });
+            }
+            RacePacket::State { id, y, velocity }
+                if Some(id) != self.id && self.sent_by(id, from) =>
+            {
+                self.relay(packet, from);
+                // Players sending positions are flying, even if they joined late
+                let racer = self.racers.entry(id).or_insert(Racer {
+                    y,
+                    velocity,
+                    alive: true,
+                    silence: 0.0,
+                });
+                racer.y = y;
+                racer.velocity = velocity;
+                racer.silence = 0.0;
+            }
+            RacePacket::Dead { id, score } if Some(id) != self.id && self.sent_by(id, from) => {
+                self.relay(packet, from);
+                if let Some(racer) = self.racers.get_mut(&id) {
+                    info!("Player {} crashed with {score} points", player_number(id));
+                    racer.alive = false;
+                    racer.silence = 0.0;
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Accepts a guest, or tells it the race is full (host only).
+    fn welcome(&mut self, from: SocketAddr) {
+        let id = match self.guests.iter().find(|(address, _)| *address == from) {
+            // A keepalive, or the welcome got lost: (re)send it
+            Some((_, id)) => *id,
+            None => {
+                let free = (HOST_ID + 1..MAX_RACERS as u8)
+                    .find(|id| self.guests.iter().all(|(_, guest)| guest != id));
+                let Some(id) = free else {
+                    let _ = self
+                        .socket
+                        .send_to(RacePacket::Full.encode().as_bytes(), from);
+                    return;
+                };
+                info!("Player {} joined the race from {from}", player_number(id));
+                self.guests.push((from, id));
+                id
+            }
+        };
+        self.touch(id);
+        let _ = self
+            .socket
+            .send_to(RacePacket::Welcome { id }.encode().as_bytes(), from);
+    }
+
+    /// Returns true if a packet about player `id` may come from `from`.
+    ///
+    /// The host only takes a guest's packets from the address it joined
+    /// with, so nobody can speak for another player or invent new ones.
+    /// Guests already ignore everyone but the host, which relays for others.
+    fn sent_by(&self, id: u8, from: SocketAddr) -> bool {
+        !self.is_host() || self.guests.contains(&(from, id))
+    }
+
+    /// Forwards a guest's packet to the other guests (host only).
+    fn relay(&self, packet: RacePacket, from: SocketAddr) {
+        if self.is_host() {
+            self.broadcast(packet, Some(from));
+        }
+    }
+
+    /// Returns who won the current run: `Some(None)` if this player did,
+    /// `Some(Some(id))` for another player, or `None` while undecided.
+    ///
+    /// The race is decided once someone crashed and one player is left.
+    pub fn winner(&self) -> Option<Option<u8>> {
+        let crashed = !self.alive || self.racers.values().any(|racer| !racer.alive);
+        if !crashed {
+            return None;
+        }
+        let mut flying = self
+            .racers
+            .iter()
+            .filter(|(_, racer)| racer.alive)
+            .map(|(id, _)| Some(*id))
+            .chain(self.alive.then_some(None));
+        let first = flying.next()?;
+        flying.next().is_none().then_some(first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packets_round_trip() {
+        let packets = [
+            RacePacket::Hello,
+            RacePacket::Welcome { id: 2 },
+            RacePacket::Full,
+            RacePacket::Start {
+                seed: u64::MAX,
+                run: 4,
+            },
+            RacePacket::State {
+                id: 1,
+                y: -31.5,
+                velocity: 120.25,
+            },
+            RacePacket::Dead { id: 3, score: 17 },
+        ];
+        for packet in packets {
+            assert_eq!(RacePacket::decode(packet.encode().as_bytes()), Some(packet));
+        }
+        assert_eq!(RacePacket::decode(b"START 42"), None);
+        assert_eq!(RacePacket::decode(b"STATE 1 2"), None);
+        assert_eq!(RacePacket::decode(b"DEAD 1 2 3"), None);
+        assert_eq!(RacePacket::decode(b"JUMP"), None);
+        assert_eq!(RacePacket::decode(b"STATE 255 0 0"), None);
+        assert_eq!(RacePacket::decode(b"STATE 1 NaN 0"), None);
+        assert_eq!(RacePacket::decode(b"STATE 1 0 inf"), None);
+        assert_eq!(RacePacket::decode(b"WELCOME 4"), None);
+    }
+
+    #[test]
+    fn roles_parse() {
+        assert_eq!(
+            RaceRole::parse("host:7777"),
+            Some(RaceRole::Host { port: 7777 })
+        );
+        assert_eq!(
+            RaceRole::parse("join:192.168.1.20:7777"),
+            Some(RaceRole::Join {
+                address: "192.168.1.20:7777".to_string()
+            })
+        );
+        assert_eq!(RaceRole::parse("join:nowhere"), None);
+        assert_eq!(RaceRole::parse("spectate:7777"), None);
+    }
+
+    #[test]
+    fn last_one_flying_wins() {
+        let mut session = RaceSession::new(&RaceRole::Host { port: 0 }).unwrap();
+        let racer = Racer {
+            y: 0.0,
+            velocity: 0.0,
+            alive: true,
+            silence: 0.0,
+        };
+        session.racers.insert(1, racer.clone());
+        session.racers.insert(2, racer);
+        session.alive = true;
+        assert_eq!(session.winner(), None);
+
+        session.racers.get_mut(&1).unwrap().alive = false;
+        session.alive = false;
+        assert_eq!(session.winner(), Some(Some(2)));
+
+        session.alive = true;
+        session.racers.get_mut(&2).unwrap().alive = false;
+        assert_eq!(session.winner(), Some(None));
+    }
+
+    #[test]
+    fn guests_join_and_receive_starts() {
+        let mut host = RaceSession::new(&RaceRole::Host { port: 0 }).unwrap();
+        let mut guest = RaceSession::new(&RaceRole::Join {
+            address: format!("127.0.0.1:{}", host.port()),
+        })
+        .unwrap();
+
+        let settle = |host: &mut RaceSession, guest: &mut RaceSession| {
+            let mut events = Vec::new();
+            for _ in 0..50 {
+                host.poll(0.0);
+                events.extend(guest.poll(0.0));
+                std::thread::sleep(std::time::Duration::from_millis(2));
+            }
+            events
+        };
+
+        settle(&mut host, &mut guest);
+        assert_eq!(guest.id, Some(1));
+
+        // Starts are repeated, but each run only starts once
+        host.start(42);
+        let events = settle(&mut host, &mut guest);
+        assert_eq!(events, vec![RaceEvent::Started { seed: 42 }]);
+        host.start(42);
+        let events = settle(&mut host, &mut guest);
+        assert_eq!(events, vec![RaceEvent::Started { seed: 42 }]);
+
+        guest.send_state(12.0, -3.0);
+        settle(&mut host, &mut guest);
+        assert_eq!(host.racers.get(&1).map(|racer| racer.y), Some(12.0));
+    }
+
+    #[test]
+    fn host_ignores_packets_from_strangers() {
+        let mut host = RaceSession::new(&RaceRole::Host { port: 0 }).unwrap();
+        let guest: SocketAddr = "127.0.0.1:9001".parse().unwrap();
+        let stranger: SocketAddr = "127.0.0.1:9002".parse().unwrap();
+        host.welcome(guest);
+
+        let state = |id| RacePacket::State {
+            id,
+            y: 5.0,
+            velocity: 0.0,
+        };
+        host.handle(state(2), stranger);
+        host.handle(state(1), stranger);
+        host.handle(state(2), guest);
+        host.handle(RacePacket::Dead { id: 1, score: 3 }, stranger);
+        assert_eq!(host.racers.keys().copied().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(host.racers[&1].y, 0.0);
+
+        host.handle(state(1), guest);
+        assert_eq!(host.racers[&1].y, 5.0);
+        assert_eq!(player_number(u8::MAX), 256);
+    }
+}
@@ -0,0 +1,275 @@
+//! Run replays and ghosts.
+//!
+//! A `Replay` is the seed a run was played with plus its input trace (the
+//! time of each flap). Replaying the flaps with the same physics re-flies the
+//! run, which is how ghosts race alongside the player. The trace also keeps
+//! the game time of every frame and the frames the bird flapped on, so the
+//! whole run can be re-simulated exactly to verify its score (see `verify`).
+//! The player's best run is kept in `best.replay.ron`; leaderboard
+//! integrations can add more ghosts to the `GhostLibrary`. With `--record`,
+//! every finished run is also written to a file that `--play` plays back,
+//! so a run that hit a bug can be attached to its report.
+
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Difficulty;
+use crate::mutators::Mutators;
+use crate::save::{load_ron, store_ron};
+
+/// Name of the best run's replay file inside the save directory.
+const BEST_REPLAY_FILE_NAME: &str = "best.replay.ron";
+
+/// Label of the player's own best run in the ghost picker.
+const BEST_REPLAY_LABEL: &str = "My best";
+
+/// A recorded run.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Replay {
+    /// Master seed of the run's `GameRng`
+    pub seed: u64,
+    /// Final score
+    pub score: u32,
+    /// Seconds from the start of the run to each flap
+    pub flaps: Vec<f32>,
+    /// Seconds from the start of the run to the crash
+    pub duration: f32,
+    /// Difficulty the run was played at
+    pub difficulty: Difficulty,
+    /// Mutators the run was played with
+    pub mutators: Mutators,
+    /// Game time of each frame of the run, in nanoseconds (paused frames excluded)
+    pub frames: Vec<u32>,
+    /// Index in `frames` of the frame of each flap
+    pub flap_frames: Vec<u32>,
+}
+
+impl Replay {
+    /// Serializes the replay for upload or a replay file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        ron::to_string(self).unwrap_or_default().into_bytes()
+    }
+
+    /// Returns the seed as the two halves attached to leaderboard entries,
+    /// low half first.
+    #[cfg_attr(not(feature = "steam"), allow(dead_code))]
+    pub fn score_details(&self) -> [i32; 2] {
+        [self.seed as u32 as i32, (self.seed >> 32) as u32 as i32]
+    }
+
+    /// Parses an uploaded replay or a replay file.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        ron::from_str(std::str::from_utf8(bytes).ok()?).ok()
+    }
+
+    /// Reads a replay file.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|error| error.to_string())?;
+        Self::from_bytes(&bytes).ok_or_else(|| "not a replay file".to_string())
+    }
+
+    /// Writes the replay to a file, replacing it.
+    pub fn write_to(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_bytes()).map_err(|error| error.to_string())
+    }
+}
+
+/// Resource recording the current run.
+#[derive(Resource, Default)]
+pub struct ReplayRecorder {
+    /// The run recorded so far
+    pub replay: Replay,
+    /// Elapsed app time when the run started
+    pub started_at: f32,
+    /// Whether the run was rewound or revived, which makes the replay unplayable
+    pub resumed: bool,
+    /// Whether a debug panel cheat was used during the run, which keeps it
+    /// off the records
+    pub cheated: bool,
+}
+
+/// Resource holding the file finished runs are written to (`--record`).
+#[derive(Resource)]
+pub struct RunRecordFile(pub PathBuf);
+
+/// A replay that can be raced as a ghost.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GhostEntry {
+    /// Name shown in the ghost picker
+    pub label: String,
+    /// The run to re-fly
+    pub replay: Replay,
+}
+
+/// Resource listing the available ghosts and the one selected to race.
+#[derive(Resource, Default)]
+pub struct GhostLibrary {
+    /// Available ghosts, the player's best first
+    pub entries: Vec<GhostEntry>,
+    /// Index of the ghost raced in the next runs
+    pub selected: Option<usize>,
+}
+
+impl GhostLibrary {
+    /// Creates the library with the player's best run, if one was saved.
+    pub fn load() -> Self {
+        let best: Replay = load_ron(BEST_REPLAY_FILE_NAME);
+        let mut library = Self::default();
+        if !best.flaps.is_empty() {
+            library.set_best(best);
+        }
+        library
+    }
+
+    /// Returns the selected ghost, if any.
+    pub fn selected(&self) -> Option<&GhostEntry> {
+        self.selected.and_then(|index| self.entries.get(index))
+    }
+
+    /// Returns the label of the selected ghost, or "Off".
+    pub fn selected_label(&self) -> &str {
+        self.selected().map_or("Off", |entry| entry.label.as_str())
+    }
+
+    /// Returns all picker options: "Off" followed by every ghost.
+    pub fn labels(&self) -> Vec<String> {
+        std::iter::once("Off".to_string())
+            .chain(self.entries.iter().map(|entry| entry.label.clone()))
+            .collect()
+    }
+
+    /// Selects a ghost by its picker label ("Off" clears the selection).
+    pub fn select(&mut self, label: &str) {
+        self.selected = self.entries.iter().position(|entry| entry.label == label);
+    }
+
+    /// Adds or replaces the ghost with the same label.
+    pub fn add(&mut self, entry: GhostEntry) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|other| other.label == entry.label)
+        {
+            Some(other) => *other = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Adds a replay file as a ghost named after the file, and selects it.
+    pub fn add_replay_file(&mut self, path: &Path) -> Result<(), String> {
+        let replay = Replay::from_file(path)?;
+        let label = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.trim_end_matches(".replay.ron").to_string())
+            .unwrap_or_else(|| "Replay".to_string());
+        self.add(GhostEntry {
+            label: label.clone(),
+            replay,
+        });
+        self.select(&label);
+        Ok(())
+    }
+
+    /// Keeps a finished run if it beats the saved best, returning true if so.
+    pub fn submit(&mut self, replay: &Replay) -> bool {
+        let best_score = self
+            .entries
+            .iter()
+            .find(|entry| entry.label.starts_with(BEST_REPLAY_LABEL))
+            .map(|entry| entry.replay.score);
+        if best_score.is_some_and(|best| replay.score <= best) || replay.score == 0 {
+            return false;
+        }
+
+        store_ron(BEST_REPLAY_FILE_NAME, replay);
+        self.set_best(replay.clone());
+        true
+    }
+
+    /// Puts the player's best run first, keeping the selection on it if it was.
+    fn set_best(&mut self, replay: Replay) {
+        let label = format!("{BEST_REPLAY_LABEL} ({})", replay.score);
+        let had_best = self
+            .entries
+            .first()
+            .is_some_and(|entry| entry.label.starts_with(BEST_REPLAY_LABEL));
+        let entry = GhostEntry { label, replay };
+        if had_best {
+            self.entries[0] = entry;
+        } else {
+            self.entries.insert(0, entry);
+            self.selected = self.selected.map(|index| index + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutators::Mutator;
+
+    fn replay(score: u32) -> Replay {
+        Replay {
+            seed: 7,
+            score,
+            flaps: vec![0.2, 0.9, 1.4],
+            duration: 3.0,
+            difficulty: Difficulty::Hard,
+            mutators: Mutators([Mutator::InvertedControls].into()),
+            frames: vec![16_666_667; 180],
+            flap_frames: vec![12, 54, 84],
+        }
+    }
+
+    #[test]
+    fn replay_round_trips_through_bytes() {
+        let original = replay(3);
+        assert_eq!(Replay::from_bytes(&original.to_bytes()), Some(original));
+        assert_eq!(Replay::from_bytes(b"not a replay"), None);
+    }
+
+    #[test]
+    fn score_details_split_the_seed() {
+        let replay = Replay {
+            seed: 0x0000_00ab_8000_0001,
+            ..default()
+        };
+        assert_eq!(replay.score_details(), [i32::MIN + 1, 0xab]);
+    }
+
+    #[test]
+    fn ghost_selection_uses_labels() {
+        let mut library = GhostLibrary::default();
+        library.add(GhostEntry {
+            label: "#1 (40)".to_string(),
+            replay: replay(40),
+        });
+        assert_eq!(library.labels(), vec!["Off", "#1 (40)"]);
+
+        library.select("#1 (40)");
+        assert_eq!(library.selected_label(), "#1 (40)");
+        library.select("Off");
+        assert_eq!(library.selected(), None);
+    }
+
+    #[test]
+    fn best_run_goes_first_and_keeps_selection() {
+        let mut library = GhostLibrary::default();
+        library.add(GhostEntry {
+            label: "#1 (40)".to_string(),
+            replay: replay(40),
+        });
+        library.select("#1 (40)");
+
+        library.set_best(replay(5));
+        assert_eq!(library.entries[0].label, "My best (5)");
+        assert_eq!(library.selected_label(), "#1 (40)");
+
+        library.set_best(replay(8));
+        assert_eq!(library.labels(), vec!["Off", "My best (8)", "#1 (40)"]);
+    }
+}
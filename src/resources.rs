@@ -2,9 +2,18 @@
 //!
 //! This module contains all the ECS resources used in the game.
 
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
 use bevy::prelude::*;
 
-use crate::constants::{DEFAULT_ASPECT_RATIO, GAME_HEIGHT, PIPE_SPAWN_TIME};
+use crate::achievements::Achievement;
+use crate::constants::{DEFAULT_ASPECT_RATIO, GAME_HEIGHT, PIPE_SPAWN_DISTANCE, REVIVE_COST};
+use crate::music::MusicLoop;
+use crate::patterns::PipeColumn;
+use crate::replay::Replay;
+use crate::sfx::SoundEffect;
+use crate::trails::TrailChoice;
 
 /// Resource to track the current game viewport dimensions.
 /// The height is fixed at GAME_HEIGHT, width adjusts based on window aspect ratio.
@@ -61,14 +70,156 @@ impl Score {
     }
 }
 
-/// Timer resource for spawning pipes at regular intervals.
+/// Resource holding the pipe columns still to spawn from the current pattern.
+#[derive(Resource, Default)]
+pub struct PipeSequence {
+    /// Columns in spawn order
+    pub pending: VecDeque<PipeColumn>,
+    /// Center of the last column's scored gap, which the next random gap
+    /// has to be reachable from
+    pub last_gap: Option<f32>,
+}
+
+/// Resource listing the scrolling entities that have reached the bird's column.
+///
+/// Rebuilt by `pipe_movement` every frame from the positions it has just
+/// moved to, so collision and scoring only look at the pipe pairs and
+/// obstacles around the bird (plus the few behind it) instead of every one on screen.
+#[derive(Resource, Default)]
+pub struct NearbyObstacles(pub Vec<Entity>);
+
+/// Resource adjusting randomly sized pipe gaps (installed by the rules script).
+///
+/// Called with the current score and the generated gap size; returns the size to use.
 #[derive(Resource)]
-pub struct PipeSpawnTimer(pub Timer);
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+pub struct GapModifier(pub Box<dyn Fn(u32, f32) -> f32 + Send + Sync>);
+
+/// Cheats toggled from the debug panel.
+///
+/// Only present with the `dev-tools` feature; systems that honor a cheat
+/// read it as an optional resource.
+#[derive(Resource, Default, Debug)]
+#[cfg_attr(not(feature = "dev-tools"), allow(dead_code))]
+pub struct DebugTools {
+    /// Whether pipes and obstacles pass through the bird
+    pub invincible: bool,
+    /// Whether pipes stop scrolling and spawning
+    pub freeze_pipes: bool,
+    /// Whether time only advances one frame at a time
+    pub frame_stepping: bool,
+    /// Whether a single frame is being stepped (time pauses again after it)
+    pub step_pending: bool,
+    /// Height to move the bird to on the next physics step
+    pub teleport: Option<f32>,
+}
+
+impl DebugTools {
+    /// Returns true while a cheat that helps the bird is on (or pending).
+    pub fn is_cheating(&self) -> bool {
+        self.invincible || self.freeze_pipes || self.frame_stepping || self.teleport.is_some()
+    }
+}
+
+/// Resource spawning pipes at regular distances: a column spawns each time
+/// the world has scrolled `spacing` since the last one, so the spacing stays
+/// the same at any scroll speed.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct PipeSpawnDistance {
+    /// World units scrolled since the last column
+    pub scrolled: f32,
+    /// World units between the last column and the next
+    pub spacing: f32,
+}
 
-impl Default for PipeSpawnTimer {
+impl Default for PipeSpawnDistance {
     fn default() -> Self {
-        Self(Timer::from_seconds(PIPE_SPAWN_TIME, TimerMode::Repeating))
+        Self {
+            scrolled: 0.0,
+            spacing: PIPE_SPAWN_DISTANCE,
+        }
+    }
+}
+
+impl PipeSpawnDistance {
+    /// Adds the distance scrolled this frame, returning whether the next
+    /// column is due.
+    ///
+    /// The overshoot carries over to the next column, so spacing doesn't
+    /// depend on the frame rate.
+    pub fn advance(&mut self, distance: f32) -> bool {
+        self.scrolled += distance;
+        if self.scrolled < self.spacing {
+            return false;
+        }
+        self.scrolled -= self.spacing;
+        true
     }
+
+    /// Returns the world units left to scroll before the next column.
+    pub fn remaining(&self) -> f32 {
+        (self.spacing - self.scrolled).max(0.0)
+    }
+}
+
+/// Resource tracking menu idle time for the attract-mode demo.
+#[derive(Resource, Default)]
+pub struct AttractMode {
+    /// Seconds since the last input on the menu
+    pub idle: f32,
+    /// Seconds until the demo's next pipe pair
+    pub spawn_timer: f32,
+}
+
+/// Resource tracking the highlighted row of the settings screen.
+#[derive(Resource, Default)]
+pub struct SettingsMenu {
+    /// Index into `SettingsRow::ALL`
+    pub selected: usize,
+    /// Index of the first row on screen
+    pub scroll: usize,
+    /// Outcome of the last export, import or purchase, shown below the rows
+    pub status: String,
+    /// Trail shown on the trail row
+    pub trail: TrailChoice,
+}
+
+/// Resource tracking the highlighted row of the controls screen.
+#[derive(Resource, Default)]
+pub struct ControlsMenu {
+    /// Index into `ControlsRow::ALL`
+    pub selected: usize,
+    /// Whether the selected row waits for a new key or button
+    pub listening: bool,
+    /// Outcome of the last rebind, shown below the rows
+    pub status: String,
+}
+
+/// Resource holding the code typed on the seed entry screen.
+#[derive(Resource, Default)]
+pub struct SeedEntryMenu {
+    /// Code typed so far
+    pub input: String,
+    /// Why the last code was refused, shown below it
+    pub status: String,
+}
+
+/// Resource holding the seed entered for the next run, if any.
+#[derive(Resource, Default)]
+pub struct PendingSeed(pub Option<u64>);
+
+/// Resource tracking the highlighted row of the sound test screen.
+#[derive(Resource, Default)]
+pub struct JukeboxMenu {
+    /// Index into `JukeboxRow::ALL`
+    pub selected: usize,
+}
+
+/// Resource tracking the highlighted row of the mutator screen.
+#[derive(Resource, Default)]
+pub struct MutatorsMenu {
+    /// Index into `MutatorRow::ALL`
+    pub selected: usize,
 }
 
 // ============================================================================
@@ -100,6 +251,122 @@ impl ScreenShake {
     }
 }
 
+/// Resource for the camera zoom effect: a quick punch-in when scoring, and
+/// a slower zoom toward the bird on death that holds until the game over
+/// screen is left.
+#[derive(Resource, Default)]
+pub struct CameraZoom {
+    /// Seconds since the zoom started
+    pub elapsed: f32,
+    /// Seconds the zoom takes (to come back out, or to reach its peak when
+    /// it holds)
+    pub duration: f32,
+    /// How far the camera zooms in at the peak, as a fraction of the view
+    pub amount: f32,
+    /// World point that stays in place on screen as the camera zooms
+    pub focus: Vec2,
+    /// Whether the zoom stays at its peak instead of coming back out
+    pub hold: bool,
+}
+
+impl CameraZoom {
+    /// Triggers a quick punch-in on the middle of the screen.
+    pub fn punch(&mut self, amount: f32, duration: f32) {
+        *self = Self {
+            elapsed: 0.0,
+            duration,
+            amount,
+            focus: Vec2::ZERO,
+            hold: false,
+        };
+    }
+
+    /// Zooms in toward a point and holds there.
+    pub fn zoom_to(&mut self, focus: Vec2, amount: f32, duration: f32) {
+        *self = Self {
+            elapsed: 0.0,
+            duration,
+            amount,
+            focus,
+            hold: true,
+        };
+    }
+
+    /// Ends the zoom; the camera is restored on the next update.
+    pub fn release(&mut self) {
+        self.hold = false;
+        self.elapsed = self.elapsed.max(self.duration);
+    }
+
+    /// Returns true while the zoom is running or holding.
+    pub fn is_active(&self) -> bool {
+        self.duration > 0.0 && (self.hold || self.elapsed < self.duration)
+    }
+
+    /// Returns how far the camera is zoomed in now, as a fraction of the view.
+    pub fn current(&self) -> f32 {
+        if !self.is_active() {
+            return 0.0;
+        }
+        let progress = (self.elapsed / self.duration).min(1.0);
+        if self.hold {
+            // Ease out into the held zoom
+            self.amount * (1.0 - (1.0 - progress).powi(2))
+        } else {
+            self.amount * (progress * std::f32::consts::PI).sin()
+        }
+    }
+
+    /// Returns the orthographic projection scale for the current zoom.
+    pub fn scale(&self) -> f32 {
+        1.0 / (1.0 + self.current())
+    }
+
+    /// Returns the camera offset keeping the focus point in place on screen.
+    pub fn offset(&self) -> Vec2 {
+        self.focus * (1.0 - self.scale())
+    }
+}
+
+/// Resource for the vignette pulse: the corners of the screen darken
+/// briefly on near misses and death, on top of the resting vignette.
+#[derive(Resource, Default)]
+pub struct VignettePulse {
+    /// Remaining duration of the pulse
+    pub duration: f32,
+    /// Total duration for fade calculation
+    pub total_duration: f32,
+    /// Extra vignette intensity at the start of the pulse
+    pub amount: f32,
+}
+
+impl VignettePulse {
+    /// Triggers a pulse, unless a stronger one is still fading.
+    pub fn trigger(&mut self, duration: f32, amount: f32) {
+        if amount >= self.current() {
+            self.duration = duration;
+            self.total_duration = duration;
+            self.amount = amount;
+        }
+    }
+
+    /// Gets the current extra intensity based on remaining duration (fade out).
+    pub fn current(&self) -> f32 {
+        if self.total_duration <= 0.0 || self.duration <= 0.0 {
+            return 0.0;
+        }
+        (self.duration / self.total_duration) * self.amount
+    }
+}
+
+/// Message triggered on each frame the bird passes close to a pipe or
+/// obstacle without touching it.
+#[derive(Message)]
+pub struct NearMiss {
+    /// Distance between the bird and the nearest pipe or obstacle
+    pub clearance: f32,
+}
+
 /// Resource for screen flash effect state.
 #[derive(Resource, Default)]
 pub struct ScreenFlashState {
@@ -136,6 +403,38 @@ impl ScreenFlashState {
     }
 }
 
+/// Resource animating the full-screen overlay played on state changes.
+///
+/// The overlay starts at `max_alpha` black and fades out, revealing the new
+/// state.
+#[derive(Resource, Default)]
+pub struct Transition {
+    /// Remaining duration of the fade
+    pub duration: f32,
+    /// Total duration for fade calculation
+    pub total_duration: f32,
+    /// Opacity at the start of the fade
+    pub max_alpha: f32,
+}
+
+impl Transition {
+    /// Starts a new fade from `alpha` black.
+    pub fn start(&mut self, duration: f32, alpha: f32) {
+        self.duration = duration;
+        self.total_duration = duration;
+        self.max_alpha = alpha;
+    }
+
+    /// Gets the current overlay alpha, easing out of black.
+    pub fn current_alpha(&self) -> f32 {
+        if self.total_duration <= 0.0 || self.duration <= 0.0 {
+            return 0.0;
+        }
+        let remaining = self.duration / self.total_duration;
+        remaining * remaining * self.max_alpha
+    }
+}
+
 /// Resource for edge flash effect state (score effect at screen edges).
 #[derive(Resource, Default)]
 pub struct EdgeFlashState {
@@ -172,20 +471,312 @@ impl EdgeFlashState {
     }
 }
 
-/// Message triggered when the player flaps.
-#[derive(Message)]
+/// Resource holding the graphics options derived from the settings.
+#[derive(Resource)]
+pub struct GraphicsSettings {
+    /// Minimum time per frame, if the frame rate is limited
+    pub frame_time: Option<Duration>,
+    /// Multiplier applied to particle counts
+    pub particle_scale: f32,
+    /// Multiplier applied to particle lifetimes
+    pub lifetime_scale: f32,
+    /// Whether the main camera renders in HDR with bloom
+    pub bloom: bool,
+    /// When the last frame ended, for the frame limiter
+    pub last_frame: Instant,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            frame_time: None,
+            particle_scale: 1.0,
+            lifetime_scale: 1.0,
+            bloom: false,
+            last_frame: Instant::now(),
+        }
+    }
+}
+
+impl GraphicsSettings {
+    /// Scales a particle count, keeping at least one particle.
+    pub fn particles(&self, count: u32) -> u32 {
+        ((count as f32 * self.particle_scale).round() as u32).max(1)
+    }
+
+    /// Scales a particle lifetime.
+    pub fn lifetime(&self, lifetime: f32) -> f32 {
+        lifetime * self.lifetime_scale
+    }
+}
+
+/// Resource holding the current run's statistics for the results screen.
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct RunStats {
+    /// Time spent flying, in seconds
+    pub duration: f32,
+    /// Number of flaps
+    pub flaps: u32,
+    /// Number of pipes passed
+    pub pipes: u32,
+    /// Smallest distance between the bird and a pipe or obstacle, in pixels
+    pub closest_miss: Option<f32>,
+}
+
+impl RunStats {
+    /// Returns the average number of flaps per pipe passed, if any were.
+    pub fn flaps_per_pipe(&self) -> Option<f32> {
+        (self.pipes > 0).then(|| self.flaps as f32 / self.pipes as f32)
+    }
+
+    /// Records the distance to the nearest pipe or obstacle this frame.
+    pub fn record_clearance(&mut self, clearance: f32) {
+        if self.closest_miss.is_none_or(|closest| clearance < closest) {
+            self.closest_miss = Some(clearance);
+        }
+    }
+
+    /// Returns the text shown on the results screen.
+    pub fn summary(&self) -> String {
+        let per_pipe = self
+            .flaps_per_pipe()
+            .map_or("-".to_string(), |average| format!("{average:.1}"));
+        let closest = self
+            .closest_miss
+            .map_or("-".to_string(), |distance| format!("{distance:.0} px"));
+        format!(
+            "Time: {:.1}s   Flaps: {}\nFlaps per pipe: {per_pipe}   Closest call: {closest}",
+            self.duration, self.flaps
+        )
+    }
+}
+
+/// Resource tracking the current session's playtime.
+///
+/// Lifetime playtime is persisted in `SaveData::playtime`.
+#[derive(Resource, Default)]
+pub struct Playtime {
+    /// Time since the game started, in seconds
+    pub session: f32,
+    /// Time added to the lifetime total since it was last saved
+    pub unsaved: f32,
+    /// Number of hourly reminders shown this session
+    pub reminders: u32,
+}
+
+/// Formats a duration in seconds as hours and minutes (e.g. "1h 05m", "12m").
+pub fn format_playtime(seconds: f64) -> String {
+    let minutes = (seconds / 60.0) as u64;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// Resource for the one-switch flap controller's rhythm.
+#[derive(Resource, Default)]
+pub struct AutoFlap {
+    /// Time until the next automatic flap
+    pub timer: f32,
+    /// Whether the player asked to skip the next flap
+    pub skip: bool,
+}
+
+/// Resource for the flap cooldown of hard mode.
+#[derive(Resource, Default)]
+pub struct FlapCooldown {
+    /// Seconds until the wings have recharged and the bird may flap again
+    pub remaining: f32,
+}
+
+impl FlapCooldown {
+    /// Advances the cooldown, returning whether the bird may flap.
+    pub fn tick(&mut self, dt: f32) -> bool {
+        self.remaining = (self.remaining - dt).max(0.0);
+        self.remaining == 0.0
+    }
+
+    /// Returns how far the wings have recharged, from 0 (just flapped) to 1.
+    pub fn charge(&self, duration: f32) -> f32 {
+        if duration <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.remaining / duration).clamp(0.0, 1.0)
+    }
+}
+
+/// Resource remembering a flap pressed outside a run, such as the press that
+/// starts it, so it becomes the run's first flap.
+#[derive(Resource, Default)]
+pub struct FlapBuffer {
+    /// Real time of the buffered press, in seconds
+    pub pressed_at: Option<f32>,
+}
+
+impl FlapBuffer {
+    /// Returns whether a press was buffered at most `window` seconds before
+    /// `now`, and forgets it either way.
+    pub fn take(&mut self, now: f32, window: f32) -> bool {
+        self.pressed_at
+            .take()
+            .is_some_and(|pressed_at| now - pressed_at <= window)
+    }
+}
+
+/// Event triggered when the player flaps, and forwarded as a message.
+#[derive(Event, Message, Clone)]
 pub struct FlapEvent {
     /// Position where the flap occurred
     pub position: Vec3,
 }
 
-/// Message triggered when the player scores.
+/// Message triggered when a run ends, carrying its replay.
 #[derive(Message)]
-pub struct ScoreEvent;
+#[cfg_attr(not(feature = "steam"), allow(dead_code))]
+pub struct ReplayRecorded {
+    /// The finished run
+    pub replay: Replay,
+}
 
-/// Message triggered when the player dies.
-#[derive(Message)]
+/// Event triggered when the player scores, and forwarded as a message.
+#[derive(Event, Message, Clone)]
+pub struct ScoreEvent {
+    /// The pipe pair that was scored
+    pub pipe: Entity,
+    /// World position of the scored gap center
+    pub gap_position: Vec2,
+    /// Height of the scored gap
+    pub gap_size: f32,
+    /// Score value after this point was added
+    pub score: u32,
+}
+
+/// What the bird collided with to end the run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    /// The bird fell onto the ground.
+    Ground,
+    /// The bird flew into the top of the screen.
+    Ceiling,
+    /// The bird hit a pipe.
+    Pipe {
+        /// The pipe entity that was hit
+        entity: Entity,
+    },
+    /// The bird hit a blade, gate, floating block, hazard, or the chaser.
+    Obstacle {
+        /// The obstacle entity that was hit
+        entity: Entity,
+    },
+    /// The player gave up from the pause screen.
+    GaveUp,
+}
+
+impl DeathCause {
+    /// Returns a short, stable name for the cause (for logs and stats).
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ground => "ground",
+            Self::Ceiling => "ceiling",
+            Self::Pipe { .. } => "pipe",
+            Self::Obstacle { .. } => "obstacle",
+            Self::GaveUp => "gave_up",
+        }
+    }
+}
+
+/// Event triggered when the player dies, and forwarded as a message.
+#[derive(Event, Message, Clone)]
 pub struct DeathEvent {
     /// Position where death occurred
     pub position: Vec3,
+    /// What caused the death
+    pub cause: DeathCause,
+}
+
+/// Resource holding the synthesized milestone fanfare.
+#[derive(Resource)]
+pub struct Fanfare(pub Handle<MusicLoop>);
+
+/// Resource holding the synthesized sound effects.
+#[derive(Resource)]
+pub struct SoundEffects(pub Vec<(SoundEffect, Handle<MusicLoop>)>);
+
+impl SoundEffects {
+    /// Returns the sound of an effect.
+    pub fn get(&self, effect: SoundEffect) -> Option<Handle<MusicLoop>> {
+        self.0
+            .iter()
+            .find(|(other, _)| *other == effect)
+            .map(|(_, handle)| handle.clone())
+    }
+}
+
+/// Message sent to play a sound effect on the effects channel.
+#[derive(Message)]
+pub struct PlaySfx(pub SoundEffect);
+
+/// Resource tracking the once-per-run revive.
+#[derive(Resource, Default)]
+pub struct Revive {
+    /// Whether this run's revive has been spent
+    pub used: bool,
+    /// Where and how the bird crashed while the revive is on offer
+    pub crash: Option<(Vec3, DeathCause)>,
+    /// Seconds left to accept the revive
+    pub prompt_time: f32,
+}
+
+impl Revive {
+    /// Returns true if the revive is unused and the player can afford it.
+    pub fn is_available(&self, coins: u32) -> bool {
+        !self.used && coins >= REVIVE_COST
+    }
+}
+
+/// Resource tracking the bird's invincibility (after a revive or a star).
+#[derive(Resource, Default)]
+pub struct Invincibility {
+    /// Seconds of invincibility left
+    pub remaining: f32,
+    /// Whether pipes the bird passes through shatter (star power)
+    pub shatters: bool,
+}
+
+impl Invincibility {
+    /// Makes the bird invincible for the given time.
+    pub fn grant(&mut self, duration: f32, shatters: bool) {
+        self.remaining = duration;
+        self.shatters = shatters;
+    }
+
+    /// Returns true while the bird passes through pipes and obstacles.
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+}
+
+/// Message triggered when the star-powered bird breaks through a pipe, or
+/// a falling crate breaks on the ground.
+#[derive(Message)]
+pub struct PipeShattered {
+    /// Point of the pipe the bird broke through, or where the crate landed
+    pub position: Vec3,
+}
+
+/// Message triggered when the race host starts a run for everyone.
+#[derive(Message)]
+pub struct RaceStarted;
+
+/// Message sent when a button is clicked, carrying a copy of its action
+/// component `T` (such as `PlayButton` or `PauseButton`).
+#[derive(Message)]
+pub struct ButtonClicked<T: Component + Clone>(pub T);
+
+/// Message triggered when an achievement is unlocked for the first time.
+#[derive(Message)]
+pub struct AchievementUnlocked {
+    /// The newly unlocked achievement
+    pub achievement: Achievement,
 }
@@ -0,0 +1,143 @@
+//! Results screen animation.
+//!
+//! When a run ends, the game over screen counts the final score up from zero
+//! with a tick for each point, speeding up as it goes; any input skips to
+//! the final score, and restarting waits until it has landed. When the run
+//! also beat the previous best, the screen celebrates: confetti rains from
+//! the top of the screen, a fanfare plays, and once the score lands the
+//! "Best" label swaps from the old record to the new one with a flourish.
+
+use bevy::prelude::*;
+
+use crate::constants::{
+    COUNT_UP_MAX_TIME, COUNT_UP_TIME_PER_POINT, RECORD_FLOURISH_SCALE, RECORD_FLOURISH_TIME,
+};
+
+/// Message triggered when a run beats the best score of its category.
+#[derive(Message, Clone, Copy, Debug, PartialEq)]
+pub struct NewRecord {
+    /// The new best score
+    pub score: u32,
+    /// The best score it replaced
+    pub previous: u32,
+}
+
+/// Resource tracking the results screen animation of the last run.
+#[derive(Resource, Default, Debug, PartialEq)]
+pub struct ResultsAnimation {
+    /// Whether the animation is showing (from the end of a run until the
+    /// game over screen is left)
+    pub active: bool,
+    /// Seconds since the animation started
+    pub elapsed: f32,
+    /// The final score of the run
+    pub score: u32,
+    /// The record being celebrated, if the run set one
+    pub record: Option<NewRecord>,
+}
+
+impl ResultsAnimation {
+    /// Starts the animation for a run that ended with `score`.
+    pub fn start(score: u32, record: Option<NewRecord>) -> Self {
+        Self {
+            active: true,
+            elapsed: 0.0,
+            score,
+            record,
+        }
+    }
+
+    /// Returns how long the score takes to count up.
+    pub fn count_up_time(&self) -> f32 {
+        (self.score as f32 * COUNT_UP_TIME_PER_POINT).min(COUNT_UP_MAX_TIME)
+    }
+
+    /// Returns true while the score is still counting up.
+    pub fn is_counting(&self) -> bool {
+        self.active && self.elapsed < self.count_up_time()
+    }
+
+    /// Jumps to the end of the count-up.
+    pub fn skip(&mut self) {
+        self.elapsed = self.elapsed.max(self.count_up_time());
+    }
+
+    /// Returns the score to show while it counts up.
+    pub fn displayed_score(&self) -> u32 {
+        let progress = self.elapsed / self.count_up_time();
+        if progress.is_nan() || progress >= 1.0 {
+            return self.score;
+        }
+        // Ease in, so the count (and its ticks) speeds up towards the score
+        let eased = progress.max(0.0).powi(2);
+        (self.score as f32 * eased).floor() as u32
+    }
+
+    /// Returns the scale of the "Best" label, popping up as it swaps to the
+    /// new record once the count-up lands, and easing back down.
+    pub fn flourish_scale(&self) -> f32 {
+        let progress = (self.elapsed - self.count_up_time()) / RECORD_FLOURISH_TIME;
+        if !(0.0..1.0).contains(&progress) {
+            return 1.0;
+        }
+        1.0 + (progress * std::f32::consts::PI).sin() * (RECORD_FLOURISH_SCALE - 1.0)
+    }
+}
+
+/// Run condition: true unless the score is still counting up on the results
+/// screen.
+pub fn results_counted(animation: Res<ResultsAnimation>) -> bool {
+    !animation.is_counting()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_at(elapsed: f32) -> ResultsAnimation {
+        ResultsAnimation {
+            elapsed,
+            ..ResultsAnimation::start(
+                40,
+                Some(NewRecord {
+                    score: 40,
+                    previous: 30,
+                }),
+            )
+        }
+    }
+
+    #[test]
+    fn score_counts_up_faster_and_faster() {
+        let animation = record_at(0.0);
+        let time = animation.count_up_time();
+        assert_eq!(time, COUNT_UP_MAX_TIME);
+        assert_eq!(animation.displayed_score(), 0);
+        assert!(animation.is_counting());
+
+        let first_half = record_at(time / 2.0).displayed_score();
+        assert!(first_half > 0 && first_half < 20);
+        assert_eq!(record_at(time).displayed_score(), 40);
+        assert!(!record_at(time).is_counting());
+
+        let mut skipped = record_at(0.1);
+        skipped.skip();
+        assert_eq!(skipped.displayed_score(), 40);
+
+        // Nothing to count for an empty run
+        assert_eq!(ResultsAnimation::start(0, None).displayed_score(), 0);
+        assert!(!ResultsAnimation::start(0, None).is_counting());
+    }
+
+    #[test]
+    fn best_label_pops_once_the_score_lands() {
+        let time = record_at(0.0).count_up_time();
+        assert_eq!(record_at(0.0).flourish_scale(), 1.0);
+        let peak = record_at(time + RECORD_FLOURISH_TIME / 2.0).flourish_scale();
+        assert!((peak - RECORD_FLOURISH_SCALE).abs() < 1e-4);
+        assert_eq!(
+            record_at(time + RECORD_FLOURISH_TIME * 2.0).flourish_scale(),
+            1.0
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! One-time rewind.
+//!
+//! While playing, the game keeps snapshots of the last few seconds of the
+//! run in a ring buffer: the bird, every pipe and obstacle, the score, and
+//! the pipe spawner and random streams. Once per run, a crash can be undone
+//! by restoring the oldest snapshot and resuming from there.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::constants::{REWIND_SECONDS, REWIND_SNAPSHOT_INTERVAL};
+use crate::patterns::PipeColumn;
+use crate::resources::PipeSpawnDistance;
+use crate::rng::GameRng;
+
+/// The state of a pipe or obstacle at the time of a snapshot.
+#[derive(Clone, Debug)]
+pub struct EntitySnapshot {
+    /// The pipe pair, pipe, or obstacle
+    pub entity: Entity,
+    /// Its transform
+    pub transform: Transform,
+    /// Its sprite color, if it has a sprite
+    pub color: Option<Color>,
+    /// Whether it was scored, for pipe pairs
+    pub scored: Option<bool>,
+    /// Its animation time, for gate doors and floating blocks
+    pub elapsed: Option<f32>,
+}
+
+/// The state of a run at one moment.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    /// The bird's transform
+    pub bird_transform: Transform,
+    /// The bird's vertical velocity
+    pub bird_velocity: f32,
+    /// Every pipe and obstacle
+    pub entities: Vec<EntitySnapshot>,
+    /// The score
+    pub score: u32,
+    /// Progress toward the next pipe column
+    pub spawn_distance: PipeSpawnDistance,
+    /// The pipe columns still to spawn
+    pub sequence: VecDeque<PipeColumn>,
+    /// The center of the last spawned gap
+    pub last_gap: Option<f32>,
+    /// The random streams
+    pub rng: GameRng,
+}
+
+/// Resource holding the rewind buffer of the current run.
+#[derive(Resource, Default)]
+pub struct Rewind {
+    /// Snapshots from oldest to newest
+    pub snapshots: VecDeque<Snapshot>,
+    /// Time since the last snapshot
+    pub since_snapshot: f32,
+    /// Whether this run's rewind has been used
+    pub used: bool,
+    /// Snapshot to restore when the run resumes
+    pub pending: Option<Snapshot>,
+}
+
+impl Rewind {
+    /// Maximum number of snapshots kept.
+    pub const CAPACITY: usize = (REWIND_SECONDS / REWIND_SNAPSHOT_INTERVAL) as usize;
+
+    /// Counts `delta` seconds, returning true when a snapshot is due.
+    pub fn tick(&mut self, delta: f32) -> bool {
+        self.since_snapshot += delta;
+        if self.since_snapshot < REWIND_SNAPSHOT_INTERVAL {
+            return false;
+        }
+        self.since_snapshot = 0.0;
+        true
+    }
+
+    /// Adds a snapshot, dropping the oldest once the buffer is full.
+    pub fn push(&mut self, snapshot: Snapshot) {
+        if self.snapshots.len() >= Self::CAPACITY {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Forgets all snapshots (the run changed in a way they can't restore).
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.since_snapshot = 0.0;
+    }
+
+    /// Returns true if the player may rewind now.
+    pub fn is_available(&self) -> bool {
+        !self.used && !self.snapshots.is_empty()
+    }
+
+    /// Uses this run's rewind, queueing the oldest snapshot for restoring.
+    pub fn start(&mut self) -> bool {
+        if !self.is_available() {
+            return false;
+        }
+        self.used = true;
+        self.pending = self.snapshots.pop_front();
+        self.clear();
+        self.pending.is_some()
+    }
+}
+
+/// Run condition: a rewind is about to be restored.
+pub fn is_rewinding(rewind: Res<Rewind>) -> bool {
+    rewind.pending.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(score: u32) -> Snapshot {
+        Snapshot {
+            bird_transform: Transform::default(),
+            bird_velocity: 0.0,
+            entities: Vec::new(),
+            score,
+            spawn_distance: PipeSpawnDistance::default(),
+            sequence: VecDeque::new(),
+            last_gap: None,
+            rng: GameRng::from_seed(0),
+        }
+    }
+
+    #[test]
+    fn buffer_keeps_the_last_seconds() {
+        let mut rewind = Rewind::default();
+        for score in 0..(Rewind::CAPACITY as u32 + 5) {
+            rewind.push(snapshot(score));
+        }
+        assert_eq!(rewind.snapshots.len(), Rewind::CAPACITY);
+        assert_eq!(
+            rewind.snapshots.front().map(|snapshot| snapshot.score),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn rewind_restores_the_oldest_snapshot_once() {
+        let mut rewind = Rewind::default();
+        assert!(!rewind.start());
+
+        rewind.push(snapshot(1));
+        rewind.push(snapshot(2));
+        assert!(rewind.start());
+        assert_eq!(
+            rewind.pending.as_ref().map(|snapshot| snapshot.score),
+            Some(1)
+        );
+
+        rewind.pending = None;
+        rewind.push(snapshot(3));
+        assert!(!rewind.start());
+    }
+}
@@ -0,0 +1,316 @@
+//! Deterministic random number generation.
+//!
+//! This module provides the `GameRng` resource: a master seed split into
+//! independent per-system streams, so e.g. spawning more particles never
+//! changes the pipe layout produced by the same seed.
+//!
+//! Seeds are shared as short codes in Crockford's base 32, so friends can
+//! fly through the same pipes. Regular runs draw 40-bit seeds, whose codes
+//! are eight characters long.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+
+/// Number of bits in the seeds drawn for regular runs.
+pub const SEED_CODE_BITS: u32 = 40;
+
+/// Digits of seed codes: Crockford's base 32, without I, L, O and U.
+const SEED_CODE_DIGITS: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Minimum number of digits in a seed code.
+const SEED_CODE_MIN_DIGITS: usize = 8;
+
+/// A single xorshift64* random stream.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RngStream {
+    state: u64,
+}
+
+impl RngStream {
+    /// Creates a stream from a seed (any value, including zero, is valid).
+    pub fn from_seed(seed: u64) -> Self {
+        // Scramble the seed so nearby seeds produce unrelated sequences,
+        // and avoid the all-zero state xorshift can never leave.
+        let state = splitmix64(seed);
+        Self {
+            state: if state == 0 {
+                0x9E37_79B9_7F4A_7C15
+            } else {
+                state
+            },
+        }
+    }
+
+    /// Returns the next raw 64-bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        // xorshift64* (Vigna, 2016)
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a value uniformly distributed in [0.0, 1.0).
+    ///
+    /// Uses the top 24 bits, the full precision of an `f32` mantissa.
+    pub fn f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns a value uniformly distributed in [min, max).
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + self.f32() * (max - min)
+    }
+
+    /// Returns an integer uniformly distributed in [min, max] (inclusive).
+    pub fn range_u32(&mut self, min: u32, max: u32) -> u32 {
+        if max <= min {
+            return min;
+        }
+        let span = u64::from(max - min) + 1;
+        min + (self.next_u64() % span) as u32
+    }
+
+    /// Returns a random angle in radians in [0, TAU).
+    pub fn angle(&mut self) -> f32 {
+        self.f32() * std::f32::consts::TAU
+    }
+
+    /// Returns a random index, each chosen proportionally to its weight.
+    ///
+    /// Negative weights count as zero; returns `None` if no weight is positive.
+    pub fn weighted_index(&mut self, weights: &[f32]) -> Option<usize> {
+        let total: f32 = weights.iter().map(|weight| weight.max(0.0)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut target = self.f32() * total;
+        for (index, weight) in weights.iter().enumerate() {
+            let weight = weight.max(0.0);
+            if target < weight {
+                return Some(index);
+            }
+            target -= weight;
+        }
+        // Rounding left the target past the end: use the last positive weight
+        weights.iter().rposition(|weight| *weight > 0.0)
+    }
+
+    /// Returns a random element of a slice, or `None` if it is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        let index = self.range_u32(0, items.len() as u32 - 1) as usize;
+        items.get(index)
+    }
+}
+
+/// Identifies an independent random stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RngStreamId {
+    /// Pipe gap sizes and positions
+    Pipes,
+    /// Particle spawning
+    Particles,
+    /// Cosmetic effects (screen shake jitter, etc.)
+    Effects,
+    /// Hazard events and their timing
+    Hazards,
+    /// Letter bonus placement
+    Letters,
+}
+
+/// Resource holding the game's seeded random streams.
+#[derive(Resource, Clone, Debug)]
+pub struct GameRng {
+    /// Stream for pipe generation
+    pub pipes: RngStream,
+    /// Stream for particle effects
+    pub particles: RngStream,
+    /// Stream for other cosmetic effects
+    pub effects: RngStream,
+    /// Stream for hazard events
+    pub hazards: RngStream,
+    /// Stream for letter bonus placement
+    pub letters: RngStream,
+}
+
+impl GameRng {
+    /// Creates all streams from a master seed.
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            pipes: Self::stream(seed, RngStreamId::Pipes),
+            particles: Self::stream(seed, RngStreamId::Particles),
+            effects: Self::stream(seed, RngStreamId::Effects),
+            hazards: Self::stream(seed, RngStreamId::Hazards),
+            letters: Self::stream(seed, RngStreamId::Letters),
+        }
+    }
+
+    /// Derives the initial state of one stream from the master seed.
+    fn stream(seed: u64, id: RngStreamId) -> RngStream {
+        RngStream::from_seed(seed ^ splitmix64(id as u64 + 1))
+    }
+}
+
+impl Default for GameRng {
+    /// Seeds from the current time.
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default();
+        Self::from_seed(seed)
+    }
+}
+
+/// Formats a seed as a shareable code, in groups of four digits
+/// (e.g. `3F9K-7QXA`).
+pub fn seed_code(seed: u64) -> String {
+    let mut digits = Vec::new();
+    let mut rest = seed;
+    while rest > 0 || digits.len() < SEED_CODE_MIN_DIGITS {
+        digits.push(SEED_CODE_DIGITS[(rest % 32) as usize]);
+        rest /= 32;
+    }
+    digits.reverse();
+    digits
+        .chunks(4)
+        .map(|group| String::from_utf8_lossy(group).into_owned())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Parses a seed code.
+///
+/// Letters may be lowercase, dashes and spaces are ignored, and the letters
+/// easily mistaken for digits (O, I and L) read as those digits.
+pub fn parse_seed_code(code: &str) -> Result<u64, String> {
+    let mut seed: Option<u64> = None;
+    for character in code.chars().filter(|c| !matches!(c, '-' | ' ')) {
+        let digit = match character.to_ascii_uppercase() {
+            'O' => '0',
+            'I' | 'L' => '1',
+            other => other,
+        };
+        let value = SEED_CODE_DIGITS
+            .iter()
+            .position(|known| char::from(*known) == digit)
+            .ok_or_else(|| format!("\"{character}\" is not in seed codes"))?;
+        seed = Some(
+            seed.unwrap_or_default()
+                .checked_mul(32)
+                .and_then(|seed| seed.checked_add(value as u64))
+                .ok_or_else(|| "the code is too long".to_string())?,
+        );
+    }
+    seed.ok_or_else(|| "type a code first".to_string())
+}
+
+/// SplitMix64 mixing function, used to expand seeds.
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f32_returns_value_in_range() {
+        let mut rng = RngStream::from_seed(1);
+        for _ in 0..1000 {
+            let value = rng.f32();
+            assert!((0.0..1.0).contains(&value));
+        }
+    }
+
+    #[test]
+    fn f32_has_more_than_ten_thousand_distinct_values() {
+        let mut rng = RngStream::from_seed(7);
+        let mut values: Vec<u32> = (0..50_000).map(|_| rng.f32().to_bits()).collect();
+        values.sort_unstable();
+        values.dedup();
+        assert!(values.len() > 10_000);
+    }
+
+    #[test]
+    fn seed_codes_round_trip() {
+        for seed in [0, 1, 42, (1 << SEED_CODE_BITS) - 1, u64::MAX] {
+            assert_eq!(parse_seed_code(&seed_code(seed)), Ok(seed));
+        }
+        assert_eq!(seed_code(0), "0000-0000");
+        assert_eq!(seed_code((1 << SEED_CODE_BITS) - 1), "ZZZZ-ZZZZ");
+        assert_eq!(seed_code(u64::MAX).len(), 16);
+    }
+
+    #[test]
+    fn seed_codes_parse_leniently() {
+        assert_eq!(parse_seed_code("0000-001a"), parse_seed_code("1A"));
+        assert_eq!(parse_seed_code("o0 Il"), Ok(33));
+        assert!(parse_seed_code("ABCU").is_err());
+        assert!(parse_seed_code(" - ").is_err());
+        assert!(parse_seed_code("ZZZZ-ZZZZ-ZZZZ-Z").is_err());
+    }
+
+    #[test]
+    fn same_seed_produces_same_sequence() {
+        let mut a = GameRng::from_seed(42);
+        let mut b = GameRng::from_seed(42);
+        for _ in 0..10 {
+            assert_eq!(a.pipes.next_u64(), b.pipes.next_u64());
+        }
+    }
+
+    #[test]
+    fn streams_are_independent() {
+        let mut a = GameRng::from_seed(42);
+        let mut b = GameRng::from_seed(42);
+
+        // Drawing particles on one generator must not affect its pipes
+        for _ in 0..100 {
+            a.particles.f32();
+        }
+        assert_eq!(a.pipes.next_u64(), b.pipes.next_u64());
+        assert_ne!(a.pipes.next_u64(), a.particles.next_u64());
+    }
+
+    #[test]
+    fn range_u32_is_inclusive_and_bounded() {
+        let mut rng = RngStream::from_seed(3);
+        let mut seen = [false; 4];
+        for _ in 0..1000 {
+            let value = rng.range_u32(3, 6);
+            assert!((3..=6).contains(&value));
+            if let Some(slot) = seen.get_mut((value - 3) as usize) {
+                *slot = true;
+            }
+        }
+        assert!(seen.iter().all(|&hit| hit));
+        assert_eq!(rng.range_u32(5, 5), 5);
+    }
+
+    #[test]
+    fn weighted_index_skips_zero_weights() {
+        let mut rng = RngStream::from_seed(11);
+        for _ in 0..1000 {
+            assert_ne!(rng.weighted_index(&[1.0, 0.0, 2.0]), Some(1));
+        }
+        assert_eq!(rng.weighted_index(&[0.0, -1.0]), None);
+        assert_eq!(rng.weighted_index(&[]), None);
+    }
+
+    #[test]
+    fn choose_handles_empty_and_non_empty_slices() {
+        let mut rng = RngStream::from_seed(9);
+        let empty: [u8; 0] = [];
+        assert_eq!(rng.choose(&empty), None);
+        assert!(rng.choose(&[1, 2, 3]).is_some_and(|v| (1..=3).contains(v)));
+    }
+}
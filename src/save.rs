@@ -0,0 +1,664 @@
+//! Persistent save data.
+//!
+//! This module stores player progress (best scores, including those of runs
+//! with mutators, unlocked achievements, lifetime playtime, coins and bought
+//! trails)
+//! as a RON file in the platform's data directory, and provides the helpers
+//! used for other persisted files such as settings.
+//!
+//! Progress, settings and bindings are versioned files: they are written as
+//! `(version: N, data: ...)`, and files in an older format are upgraded when
+//! loaded (see `Versioned`). Files that can't be read at all are backed up
+//! before falling back to defaults, so storing the defaults never destroys
+//! them.
+//!
+//! The best scores are signed with a keyed checksum stored next to the data.
+//! A save whose scores don't match their signature was edited by hand: its
+//! best scores are reset to zero and the player is told why. The key ships
+//! with the game, so this only deters casual edits; it is not cryptography.
+//! Every save must carry a valid signature: an unversioned save, from before
+//! signing or downgraded to dodge it, keeps its progress but not its best
+//! scores.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::achievements::Achievement;
+
+/// Environment variable overriding the directory used for save files.
+pub const SAVE_DIR_ENV_VAR: &str = "FLAPPY_SAVE_DIR";
+
+/// Name of the save file inside the save directory.
+const SAVE_FILE_NAME: &str = "save.ron";
+
+/// Extension appended to the name of unreadable files when backing them up.
+const BACKUP_EXTENSION: &str = "bak";
+
+/// Key mixed into the score signature, so it can't be recomputed from the
+/// scores alone.
+const SIGNATURE_KEY: &[u8] = b"flappy-rust/scores/8c1f37d2";
+
+/// A persisted file with a versioned format.
+///
+/// Adding a field with a default needs no new version, since missing fields
+/// fall back to their defaults. Renaming, retyping or removing a field does:
+/// raise `VERSION`, keep the previous layout as its own type, and teach
+/// `migrate` to read it, so older files are upgraded instead of reset.
+pub trait Versioned: Serialize + DeserializeOwned + Default {
+    /// Current format version
+    const VERSION: u32;
+
+    /// Reads a file written in the older format `version`.
+    ///
+    /// Version 0 is the unversioned format, holding the bare data;
+    /// `read_versioned_data` reads the data of any version. By default only
+    /// unversioned files, holding the same fields, can be read.
+    fn migrate(version: u32, contents: &str) -> Result<Self, String> {
+        match version {
+            0 => read_versioned_data(version, contents),
+            _ => Err(format!("unknown format {version}")),
+        }
+    }
+
+    /// Returns the signature to store alongside the data, if the format
+    /// signs it.
+    fn sign(&self) -> Option<u64> {
+        None
+    }
+
+    /// Checks data read in the current format against the stored signature,
+    /// returning what may be kept.
+    fn verify(self, _signature: Option<u64>) -> Self {
+        self
+    }
+}
+
+/// Contents of a versioned file.
+#[derive(Serialize, Deserialize)]
+struct VersionedFile<T> {
+    /// Format version of `data`
+    version: u32,
+    /// The stored value
+    data: T,
+    /// Signature of `data`, for formats that sign it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<u64>,
+}
+
+/// The version of a versioned file, ignoring its data.
+#[derive(Deserialize)]
+struct VersionHeader {
+    /// Format version, 0 for unversioned files
+    #[serde(default)]
+    version: u32,
+}
+
+/// Resource holding all persisted player progress.
+///
+/// Missing fields fall back to their defaults so older save files keep loading.
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct SaveData {
+    /// Highest score ever reached
+    pub best_score: u32,
+    /// Highest score reached in flicker mode, ranked apart from `best_score`
+    pub flicker_best_score: u32,
+    /// Highest score reached in glide mode, ranked apart from `best_score`
+    pub glide_best_score: u32,
+    /// Highest score reached with each set of mutators, by `Mutators::tag`
+    pub mutator_best_scores: BTreeMap<String, u32>,
+    /// Achievements unlocked so far
+    pub achievements: BTreeSet<Achievement>,
+    /// Total time spent in the game, in seconds
+    pub playtime: f64,
+    /// Coins earned from cleared pipes, spent on revives and trails
+    pub coins: u32,
+    /// When `coins` last changed, in milliseconds since the Unix epoch, so
+    /// the newer balance wins when copies are merged
+    pub coins_updated_at: u64,
+    /// Names of the bird trails bought with coins
+    pub trails: BTreeSet<String>,
+    /// Name of the trail following the bird, if any
+    pub equipped_trail: Option<String>,
+    /// Whether the first-run tutorial was completed
+    pub tutorial_done: bool,
+    /// Whether the best scores were reset on load because the file was edited
+    #[serde(skip)]
+    pub scores_rejected: bool,
+}
+
+impl Versioned for SaveData {
+    const VERSION: u32 = 2;
+
+    fn migrate(version: u32, contents: &str) -> Result<Self, String> {
+        match version {
+            // Unversioned saves hold the same fields, but no signature
+            0 => read_versioned_data::<Self>(version, contents).map(|save| save.verify(None)),
+            _ => Err(format!("unknown format {version}")),
+        }
+    }
+
+    fn sign(&self) -> Option<u64> {
+        Some(self.score_signature())
+    }
+
+    fn verify(self, signature: Option<u64>) -> Self {
+        if signature == self.sign() {
+            return self;
+        }
+        warn!("The best scores in the save file don't match their signature; resetting them");
+        Self {
+            best_score: 0,
+            flicker_best_score: 0,
+            glide_best_score: 0,
+            mutator_best_scores: BTreeMap::new(),
+            scores_rejected: true,
+            ..self
+        }
+    }
+}
+
+impl SaveData {
+    /// Loads the save file, falling back to defaults if it is missing or invalid.
+    pub fn load() -> Self {
+        load_versioned(SAVE_FILE_NAME)
+    }
+
+    /// Writes the save file, logging (not panicking) on failure.
+    pub fn store(&self) {
+        store_versioned(SAVE_FILE_NAME, self);
+    }
+
+    /// Checks progress read from another machine for values the game can't
+    /// use.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.playtime.is_finite() || self.playtime < 0.0 {
+            return Err(format!("invalid playtime {}", self.playtime));
+        }
+        if let Some(trail) = &self.equipped_trail
+            && !self.trails.contains(trail)
+        {
+            return Err(format!("equipped trail {trail} is not owned"));
+        }
+        Ok(())
+    }
+
+    /// Returns whether the player should get the tutorial: it was never
+    /// finished, and no pipe was ever passed (older saves have no record of
+    /// the tutorial).
+    pub fn needs_tutorial(&self) -> bool {
+        !self.tutorial_done && self.achievements.is_empty()
+    }
+
+    /// Returns the best score of a category.
+    pub fn best_in(&self, category: ScoreCategory) -> u32 {
+        match category {
+            ScoreCategory::Normal => self.best_score,
+            ScoreCategory::Flicker => self.flicker_best_score,
+            ScoreCategory::Glide => self.glide_best_score,
+        }
+    }
+
+    /// Returns the keyed checksum of the best scores (FNV-1a).
+    ///
+    /// The glide and mutator bests are only mixed in once set, so saves from
+    /// before glide mode and mutators keep their signature.
+    fn score_signature(&self) -> u64 {
+        let best_score = self.best_score.to_le_bytes();
+        let flicker_best_score = self.flicker_best_score.to_le_bytes();
+        let glide_best_score = self.glide_best_score.to_le_bytes();
+        let mutator_best_scores: Vec<u8> = self
+            .mutator_best_scores
+            .iter()
+            .flat_map(|(tag, best)| tag.bytes().chain(best.to_le_bytes()))
+            .collect();
+        let bytes = SIGNATURE_KEY
+            .iter()
+            .chain(&best_score)
+            .chain(&flicker_best_score)
+            .chain(if self.glide_best_score > 0 {
+                &glide_best_score[..]
+            } else {
+                &[]
+            })
+            .chain(&mutator_best_scores);
+        bytes.fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    /// Records a finished run's score in its category, returning true if it
+    /// is a new best.
+    pub fn submit_score(&mut self, score: u32, category: ScoreCategory) -> bool {
+        let best = match category {
+            ScoreCategory::Normal => &mut self.best_score,
+            ScoreCategory::Flicker => &mut self.flicker_best_score,
+            ScoreCategory::Glide => &mut self.glide_best_score,
+        };
+        if score > *best {
+            *best = score;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Adds coins to the balance.
+    pub fn earn_coins(&mut self, amount: u32) {
+        self.coins += amount;
+        self.coins_updated_at = now_ms();
+    }
+
+    /// Takes coins from the balance, returning false (and taking nothing)
+    /// if there aren't enough.
+    pub fn spend_coins(&mut self, amount: u32) -> bool {
+        if self.coins < amount {
+            return false;
+        }
+        self.coins -= amount;
+        self.coins_updated_at = now_ms();
+        true
+    }
+
+    /// Returns the best score of a set of mutators, by `Mutators::tag`.
+    pub fn mutator_best(&self, tag: &str) -> u32 {
+        self.mutator_best_scores
+            .get(tag)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Records the final score of a run played with mutators under their
+    /// tag, returning true if it is a new best for them.
+    pub fn submit_mutator_score(&mut self, score: u32, tag: &str) -> bool {
+        if score <= self.mutator_best(tag) {
+            return false;
+        }
+        self.mutator_best_scores.insert(tag.to_string(), score);
+        true
+    }
+}
+
+/// Category a run's score is ranked in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScoreCategory {
+    /// Regular runs
+    #[default]
+    Normal,
+    /// Runs in flicker mode, where pipes fade out near the bird
+    Flicker,
+    /// Runs in glide mode, where holding the flap input slows the fall
+    Glide,
+}
+
+/// Whether this process leaves the save directory untouched.
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Stops this process from writing to the save directory, so verifying a
+/// replay never changes the player's files.
+pub fn set_read_only() {
+    READ_ONLY.store(true, Ordering::Relaxed);
+}
+
+/// Returns whether writing to the save directory is turned off.
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Returns the directory save files are stored in.
+///
+/// Honors `FLAPPY_SAVE_DIR`, otherwise uses the platform data directory.
+pub fn save_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(SAVE_DIR_ENV_VAR) {
+        return PathBuf::from(dir);
+    }
+
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+
+    if cfg!(target_os = "ios") {
+        // App sandbox: only Documents is writable and backed up
+        home.unwrap_or_default().join("Documents")
+    } else if cfg!(target_os = "macos") {
+        home.unwrap_or_default()
+            .join("Library/Application Support/flappy-rust")
+    } else if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join("flappy-rust")
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| home.join(".local/share")))
+            .unwrap_or_default()
+            .join("flappy-rust")
+    }
+}
+
+/// Milliseconds since the Unix epoch.
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Loads a RON file from the save directory, falling back to defaults if it
+/// is missing or invalid.
+pub fn load_ron<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    let path = save_dir().join(file_name);
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return T::default();
+    };
+
+    ron::from_str(&contents).unwrap_or_else(|error| {
+        warn!("Ignoring unreadable file {}: {error}", path.display());
+        T::default()
+    })
+}
+
+/// Writes a value as RON into the save directory, logging (not panicking) on failure.
+pub fn store_ron<T: Serialize>(file_name: &str, value: &T) {
+    write_save_file(
+        file_name,
+        ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+            .map_err(|error| error.to_string()),
+    );
+}
+
+/// Loads a versioned RON file from the save directory, upgrading older
+/// formats and falling back to defaults if it is missing or invalid.
+///
+/// Invalid files, including files from a newer version of the game, are
+/// backed up first so they aren't lost when the defaults are stored.
+pub fn load_versioned<T: Versioned>(file_name: &str) -> T {
+    let path = save_dir().join(file_name);
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return T::default();
+    };
+
+    parse_versioned(&contents).unwrap_or_else(|error| {
+        warn!("Ignoring unreadable file {}: {error}", path.display());
+        back_up(&path);
+        T::default()
+    })
+}
+
+/// Writes a value as a versioned RON file into the save directory, logging
+/// (not panicking) on failure.
+pub fn store_versioned<T: Versioned>(file_name: &str, value: &T) {
+    write_save_file(file_name, to_versioned_string(value));
+}
+
+/// Parses a versioned file, upgrading older formats and refusing newer ones.
+pub fn parse_versioned<T: Versioned>(contents: &str) -> Result<T, String> {
+    let header: VersionHeader = ron::from_str(contents).map_err(|error| error.to_string())?;
+    match header.version {
+        version if version == T::VERSION => {
+            let file: VersionedFile<T> =
+                ron::from_str(contents).map_err(|error| error.to_string())?;
+            Ok(file.data.verify(file.signature))
+        }
+        version if version > T::VERSION => {
+            Err(format!("written by a newer version (format {version})"))
+        }
+        version => T::migrate(version, contents),
+    }
+}
+
+/// Serializes a value as a versioned file in the current format.
+pub fn to_versioned_string<T: Versioned>(value: &T) -> Result<String, String> {
+    let file = VersionedFile {
+        version: T::VERSION,
+        data: value,
+        signature: value.sign(),
+    };
+    ron::ser::to_string_pretty(&file, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+}
+
+/// Reads the data of a versioned file of format `version` as `D`, the type
+/// that format stored.
+pub fn read_versioned_data<D: DeserializeOwned>(version: u32, contents: &str) -> Result<D, String> {
+    let data = if version == 0 {
+        ron::from_str(contents)
+    } else {
+        ron::from_str::<VersionedFile<D>>(contents).map(|file| file.data)
+    };
+    data.map_err(|error| error.to_string())
+}
+
+/// Writes serialized contents into the save directory, logging (not
+/// panicking) on failure.
+fn write_save_file(file_name: &str, contents: Result<String, String>) {
+    if is_read_only() {
+        return;
+    }
+    let path = save_dir().join(file_name);
+    let result = contents.and_then(|contents| {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        }
+        fs::write(&path, contents).map_err(|error| error.to_string())
+    });
+
+    if let Err(error) = result {
+        warn!("Failed to write {}: {error}", path.display());
+    }
+}
+
+/// Copies a file next to itself with the backup extension.
+fn back_up(path: &Path) {
+    if is_read_only() {
+        return;
+    }
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".");
+    backup.push(BACKUP_EXTENSION);
+    match fs::copy(path, &backup) {
+        Ok(_) => info!("Backed up {} to {}", path.display(), backup.display()),
+        Err(error) => warn!("Failed to back up {}: {error}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bindings::{Binding, BoundKey, InputBindings};
+    use crate::settings::{Settings, VolumeLevel};
+
+    #[test]
+    fn submit_score_only_keeps_improvements() {
+        let mut save = SaveData::default();
+        assert!(save.submit_score(5, ScoreCategory::Normal));
+        assert!(!save.submit_score(3, ScoreCategory::Normal));
+        assert!(!save.submit_score(5, ScoreCategory::Normal));
+        assert_eq!(save.best_score, 5);
+    }
+
+    #[test]
+    fn flicker_scores_are_ranked_apart() {
+        let mut save = SaveData::default();
+        assert!(save.submit_score(5, ScoreCategory::Normal));
+        assert!(save.submit_score(3, ScoreCategory::Flicker));
+        assert_eq!(save.best_in(ScoreCategory::Normal), 5);
+        assert_eq!(save.best_in(ScoreCategory::Flicker), 3);
+    }
+
+    #[test]
+    fn mutator_scores_are_ranked_by_tag() {
+        let mut save = SaveData::default();
+        assert!(save.submit_mutator_score(4, "big-bird"));
+        assert!(save.submit_mutator_score(2, "big-bird+double-speed"));
+        assert!(!save.submit_mutator_score(3, "big-bird"));
+        assert_eq!(save.mutator_best("big-bird"), 4);
+        assert_eq!(save.mutator_best("double-speed"), 0);
+        assert_eq!(save.best_score, 0);
+
+        // Mutator bests are signed once set
+        let signed = save.score_signature();
+        assert_ne!(signed, SaveData::default().score_signature());
+        save.mutator_best_scores.insert("big-bird".to_string(), 40);
+        assert_ne!(save.score_signature(), signed);
+    }
+
+    #[test]
+    fn save_data_round_trips_through_ron() {
+        let mut save = SaveData {
+            best_score: 12,
+            ..default()
+        };
+        save.achievements.insert(Achievement::FirstPipe);
+
+        let text = ron::to_string(&save).ok();
+        let loaded = text.and_then(|text| ron::from_str::<SaveData>(&text).ok());
+        assert_eq!(loaded, Some(save));
+    }
+
+    /// Checks that `value` reads back from an unversioned file (with
+    /// `read_unversioned`) and from a file in the current format.
+    fn assert_round_trips<T>(value: &T, read_unversioned: impl Fn(&str) -> Result<T, String>)
+    where
+        T: Versioned + PartialEq + std::fmt::Debug,
+    {
+        // Version 0: unversioned
+        let unversioned = ron::to_string(value).ok();
+        let loaded = unversioned.and_then(|text| read_unversioned(&text).ok());
+        assert_eq!(loaded.as_ref(), Some(value));
+
+        // Current version
+        let current = to_versioned_string(value).ok();
+        assert!(
+            current
+                .as_ref()
+                .is_some_and(|text| text.contains(&format!("version: {}", T::VERSION)))
+        );
+        let loaded = current.and_then(|text| parse_versioned::<T>(&text).ok());
+        assert_eq!(loaded.as_ref(), Some(value));
+    }
+
+    #[test]
+    fn versioned_files_round_trip_in_every_format() {
+        let mut save = SaveData {
+            best_score: 12,
+            coins: 4,
+            ..default()
+        };
+        save.achievements.insert(Achievement::FirstPipe);
+        assert_round_trips(&save, |text| read_versioned_data(0, text));
+        assert!(to_versioned_string(&save).is_ok_and(|text| text.contains("signature")));
+
+        let settings = Settings {
+            theme: "night".to_string(),
+            music_volume: VolumeLevel::Low,
+            ..default()
+        };
+        assert_round_trips(&settings, parse_versioned::<Settings>);
+
+        let defaults = InputBindings::default();
+        let bindings = InputBindings {
+            rewind: Binding {
+                key: BoundKey(KeyCode::KeyQ),
+                ..defaults.rewind
+            },
+            ..defaults
+        };
+        assert_round_trips(&bindings, parse_versioned::<InputBindings>);
+    }
+
+    #[test]
+    fn edited_scores_are_rejected() {
+        let save = SaveData {
+            best_score: 12,
+            flicker_best_score: 4,
+            coins: 30,
+            ..default()
+        };
+        let edited = to_versioned_string(&save)
+            .ok()
+            .map(|text| text.replace("best_score: 12", "best_score: 9999"));
+        let loaded = edited.and_then(|text| parse_versioned::<SaveData>(&text).ok());
+        assert_eq!(
+            loaded,
+            Some(SaveData {
+                coins: 30,
+                scores_rejected: true,
+                ..default()
+            })
+        );
+    }
+
+    #[test]
+    fn downgraded_saves_are_rejected() {
+        let save = SaveData {
+            best_score: 12,
+            coins: 30,
+            ..default()
+        };
+        let forged = SaveData {
+            best_score: 9999,
+            ..save.clone()
+        };
+
+        // Unsigned version 1 saves only existed in development builds
+        let downgraded = to_versioned_string(&forged)
+            .ok()
+            .map(|text| text.replace("version: 2", "version: 1"));
+        assert!(downgraded.is_some_and(|text| parse_versioned::<SaveData>(&text).is_err()));
+
+        // Unversioned saves carry no signature, so their scores aren't trusted
+        let unversioned = ron::to_string(&forged).ok();
+        let loaded = unversioned.and_then(|text| parse_versioned::<SaveData>(&text).ok());
+        assert_eq!(
+            loaded,
+            Some(SaveData {
+                coins: 30,
+                scores_rejected: true,
+                ..default()
+            })
+        );
+    }
+
+    #[test]
+    fn glide_best_is_signed_once_set() {
+        let mut save = SaveData {
+            best_score: 12,
+            ..default()
+        };
+        let unsigned_glide = save.score_signature();
+        save.glide_best_score = 7;
+        assert_ne!(save.score_signature(), unsigned_glide);
+
+        // Saves from before glide mode keep verifying
+        save.glide_best_score = 0;
+        assert_eq!(save.score_signature(), unsigned_glide);
+    }
+
+    #[test]
+    fn newer_formats_are_refused() {
+        let error = parse_versioned::<SaveData>("(version: 99, data: (best_score: 3))").err();
+        assert_eq!(
+            error.as_deref(),
+            Some("written by a newer version (format 99)")
+        );
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let loaded = ron::from_str::<SaveData>("(best_score: 3)").ok();
+        assert_eq!(
+            loaded,
+            Some(SaveData {
+                best_score: 3,
+                ..default()
+            })
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Game rule scripting (behind the `scripting` feature).
+//!
+//! Loads a [Rhai](https://rhai.rs) script that can hook into the game without
+//! recompiling the crate. A script may define any of:
+//!
+//! - `on_score(score)` - called each time a point is scored
+//! - `on_death(score, cause)` - called when a run ends (`cause` is
+//!   `"ground"`, `"ceiling"`, `"pipe"` or `"obstacle"`)
+//! - `modify_gap(score, gap)` - returns the size to use for a randomly sized
+//!   pipe gap
+//!
+//! Hooks can change the rules through `set_gravity(value)`,
+//! `set_flap_strength(value)` and `set_scroll_speed(value)`. The script is
+//! read from `assets/scripts/rules.rhai`, or from the path in `FLAPPY_RULES`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bevy::asset::io::file::FileAssetReader;
+use bevy::prelude::*;
+use rhai::{AST, Dynamic, Engine, Scope};
+
+use crate::config::GameConfig;
+use crate::resources::{DeathEvent, GapModifier, Score, ScoreEvent};
+
+/// Environment variable overriding the rules script path.
+pub const RULES_ENV_VAR: &str = "FLAPPY_RULES";
+
+/// Default rules script, relative to the assets folder.
+const DEFAULT_RULES_PATH: &str = "scripts/rules.rhai";
+
+/// Upper bound on operations per hook call, so a runaway script can't hang a frame.
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// Plugin running the game rules script when one is present.
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        let path = rules_path();
+        match GameScript::load(&path) {
+            Ok(script) => {
+                info!("Loaded game rules from {}", path.display());
+                let script = Arc::new(script);
+                if script.has_hook("modify_gap", 2) {
+                    let gap_script = Arc::clone(&script);
+                    app.insert_resource(GapModifier(Box::new(move |score, gap| {
+                        gap_script.modify_gap(score, gap)
+                    })));
+                }
+                app.insert_resource(GameRules(script)).add_systems(
+                    Update,
+                    (run_score_hooks, run_death_hooks, apply_rule_changes).chain(),
+                );
+            }
+            Err(reason) => info!("Game rules scripting disabled: {reason}"),
+        }
+    }
+}
+
+/// A rule change requested by a script.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RuleChange {
+    Gravity(f32),
+    FlapStrength(f32),
+    ScrollSpeed(f32),
+}
+
+/// A compiled rules script and the engine that runs it.
+struct GameScript {
+    engine: Engine,
+    ast: AST,
+    /// Rule changes requested by the script, applied once per frame
+    changes: Arc<Mutex<Vec<RuleChange>>>,
+}
+
+impl GameScript {
+    /// Reads and compiles the script at `path`.
+    fn load(path: &Path) -> Result<Self, String> {
+        let source = fs::read_to_string(path)
+            .map_err(|error| format!("cannot read {} ({error})", path.display()))?;
+        Self::compile(&source)
+    }
+
+    /// Compiles a script and registers the game API.
+    fn compile(source: &str) -> Result<Self, String> {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.on_print(|text| info!("[rules] {text}"));
+        engine.on_debug(|text, _, _| debug!("[rules] {text}"));
+
+        for (name, change) in [
+            ("set_gravity", RuleChange::Gravity as fn(f32) -> RuleChange),
+            ("set_flap_strength", RuleChange::FlapStrength),
+            ("set_scroll_speed", RuleChange::ScrollSpeed),
+        ] {
+            let changes = Arc::clone(&changes);
+            engine.register_fn(name, move |value: f64| {
+                lock(&changes).push(change(value as f32));
+            });
+        }
+
+        let ast = engine.compile(source).map_err(|error| error.to_string())?;
+
+        Ok(Self {
+            engine,
+            ast,
+            changes,
+        })
+    }
+
+    /// Returns true if the script defines `name` with `arity` parameters.
+    fn has_hook(&self, name: &str, arity: usize) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|function| function.name == name && function.params.len() == arity)
+    }
+
+    /// Calls a hook if the script defines it, logging script errors.
+    fn call(&self, name: &str, args: impl rhai::FuncArgs, arity: usize) -> Option<Dynamic> {
+        if !self.has_hook(name, arity) {
+            return None;
+        }
+        self.engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, name, args)
+            .map_err(|error| warn!("Rules script error in {name}: {error}"))
+            .ok()
+    }
+
+    /// Runs `modify_gap`, keeping the original size if it fails.
+    fn modify_gap(&self, score: u32, gap: f32) -> f32 {
+        self.call("modify_gap", (i64::from(score), f64::from(gap)), 2)
+            .and_then(|value| {
+                value
+                    .as_float()
+                    .ok()
+                    .or_else(|| value.as_int().ok().map(|value| value as f64))
+            })
+            .map_or(gap, |value| value as f32)
+    }
+
+    /// Takes the rule changes requested since the last call.
+    fn take_changes(&self) -> Vec<RuleChange> {
+        std::mem::take(&mut *lock(&self.changes))
+    }
+}
+
+/// Locks the pending changes, recovering from a poisoned lock.
+fn lock(changes: &Mutex<Vec<RuleChange>>) -> std::sync::MutexGuard<'_, Vec<RuleChange>> {
+    changes.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the path of the rules script.
+fn rules_path() -> PathBuf {
+    std::env::var_os(RULES_ENV_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            FileAssetReader::get_base_path()
+                .join("assets")
+                .join(DEFAULT_RULES_PATH)
+        })
+}
+
+/// Resource holding the loaded rules script.
+#[derive(Resource)]
+struct GameRules(Arc<GameScript>);
+
+/// Calls `on_score` for each point scored.
+fn run_score_hooks(rules: Res<GameRules>, mut score_events: MessageReader<ScoreEvent>) {
+    for event in score_events.read() {
+        rules.0.call("on_score", (i64::from(event.score),), 1);
+    }
+}
+
+/// Calls `on_death` when a run ends.
+fn run_death_hooks(
+    rules: Res<GameRules>,
+    score: Res<Score>,
+    mut death_events: MessageReader<DeathEvent>,
+) {
+    for event in death_events.read() {
+        let cause = event.cause.label().to_string();
+        rules.0.call("on_death", (i64::from(score.0), cause), 2);
+    }
+}
+
+/// Applies rule changes requested by the script to the game configuration.
+fn apply_rule_changes(rules: Res<GameRules>, mut config: ResMut<GameConfig>) {
+    for change in rules.0.take_changes() {
+        match change {
+            RuleChange::Gravity(value) => config.physics.gravity = value,
+            RuleChange::FlapStrength(value) => config.physics.flap_strength = value,
+            RuleChange::ScrollSpeed(value) => config.pipes.scroll_speed = value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_rules_keep_default_gameplay() {
+        let script = GameScript::compile(include_str!("../assets/scripts/rules.rhai"));
+        assert!(script.is_ok_and(|script| script.modify_gap(3, 150.0) == 150.0));
+    }
+
+    #[test]
+    fn hooks_can_change_rules() {
+        let script = GameScript::compile(
+            "fn on_score(score) { if score >= 10 { set_gravity(-1000.0); } }
+             fn modify_gap(score, gap) { gap - score }",
+        );
+        let Ok(script) = script else {
+            panic!("script failed to compile");
+        };
+
+        script.call("on_score", (5_i64,), 1);
+        assert!(script.take_changes().is_empty());
+        script.call("on_score", (10_i64,), 1);
+        assert_eq!(script.take_changes(), vec![RuleChange::Gravity(-1000.0)]);
+        assert_eq!(script.modify_gap(10, 150.0), 140.0);
+    }
+
+    #[test]
+    fn missing_hooks_are_ignored() {
+        let script = GameScript::compile("let unused = 1;");
+        assert!(script.is_ok_and(|script| {
+            script
+                .call("on_death", (1_i64, "pipe".to_string()), 2)
+                .is_none()
+                && script.modify_gap(1, 120.0) == 120.0
+        }));
+    }
+}
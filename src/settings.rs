@@ -0,0 +1,821 @@
+//! Player settings.
+//!
+//! Settings are persisted separately from progress in `settings.ron` inside
+//! the save directory, so they can be reset or shared independently. The
+//! settings screen can also export them to `settings-export.ron`, a versioned
+//! file that can be copied to another machine and imported there.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::replay::GhostLibrary;
+use crate::save::{ScoreCategory, Versioned, load_versioned, now_ms, save_dir, store_versioned};
+use crate::theme::DEFAULT_THEME_NAME;
+use crate::trails::TrailChoice;
+
+/// Name of the settings file inside the save directory.
+const SETTINGS_FILE_NAME: &str = "settings.ron";
+
+/// Name of the exported settings file inside the save directory.
+pub const SETTINGS_EXPORT_FILE_NAME: &str = "settings-export.ron";
+
+/// Version of the exported settings format, raised when a change would make
+/// older games misread newer files.
+const SETTINGS_EXPORT_VERSION: u32 = 1;
+
+/// Contents of an exported settings file.
+#[derive(Serialize, Deserialize)]
+struct SettingsExport {
+    /// Format version, checked on import
+    version: u32,
+    /// The exported settings
+    settings: Settings,
+}
+
+/// Resource holding the player's settings.
+///
+/// Missing fields fall back to their defaults so older files keep loading.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// Name of the selected theme
+    pub theme: String,
+    /// Whether seasonal themes replace the selected one on their dates
+    pub seasonal_themes: bool,
+    /// Whether the pipes and effects cycle through the rainbow
+    pub rainbow_mode: bool,
+    /// Whether to play haptic feedback on mobile devices
+    pub haptics: bool,
+    /// Whether to tone down motion: no screen shake or gamepad rumble
+    pub reduced_motion: bool,
+    /// Whether the camera shakes on crashes
+    pub screen_shake: bool,
+    /// Whether the screen flashes on crashes
+    pub screen_flash: bool,
+    /// Whether the screen edges flash on scoring
+    pub edge_flash: bool,
+    /// Whether the bird squashes and stretches on flaps
+    pub squash_stretch: bool,
+    /// Whether the companion follows the bird, once unlocked
+    pub companion: bool,
+    /// Whether the bird flaps on its own and the input skips a flap, making
+    /// the game playable with a single switch
+    pub one_switch: bool,
+    /// Whether pipes fade out near the bird, a challenge ranked on its own
+    pub flicker_mode: bool,
+    /// Whether holding the flap input glides, a variant ranked on its own
+    pub glide_mode: bool,
+    /// Whether flaps need a short cooldown between them (hard mode)
+    pub flap_cooldown: bool,
+    /// Whether to show a gentle reminder after each hour of play
+    pub playtime_reminder: bool,
+    /// Whether to show the run timer and splits against the best run
+    pub speedrun_timer: bool,
+    /// Whether to wait for the display's refresh (vsync)
+    pub vsync: bool,
+    /// Frame-rate limit
+    pub fps_cap: FpsCap,
+    /// Particle counts and lifetimes
+    pub particle_quality: ParticleQuality,
+    /// Whether bright colors (the bird, pickups and score flashes) glow
+    pub bloom: bool,
+    /// Size of the HUD relative to the window
+    pub ui_scale: UiScaleOption,
+    /// Size of all HUD and menu text
+    pub text_scale: TextScale,
+    /// Shadow or outline behind the score and instruction text
+    pub text_outline: TextOutline,
+    /// Volume of the music and the fanfare
+    pub music_volume: VolumeLevel,
+    /// Volume of the sound effects channel (menu sounds and transitions)
+    pub sfx_volume: VolumeLevel,
+    /// Whether to save battery: caps the frame rate at 30 FPS, halves
+    /// particle counts and turns off bloom
+    pub battery_saver: bool,
+    /// When the player last changed a setting, in milliseconds since the Unix
+    /// epoch (the newer copy wins when syncing)
+    pub updated_at: u64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: DEFAULT_THEME_NAME.to_string(),
+            seasonal_themes: true,
+            rainbow_mode: false,
+            haptics: true,
+            reduced_motion: false,
+            screen_shake: true,
+            screen_flash: true,
+            edge_flash: true,
+            squash_stretch: true,
+            companion: true,
+            one_switch: false,
+            flicker_mode: false,
+            glide_mode: false,
+            flap_cooldown: false,
+            playtime_reminder: true,
+            speedrun_timer: false,
+            vsync: true,
+            fps_cap: FpsCap::default(),
+            battery_saver: false,
+            particle_quality: ParticleQuality::default(),
+            bloom: true,
+            ui_scale: UiScaleOption::default(),
+            text_scale: TextScale::default(),
+            text_outline: TextOutline::default(),
+            music_volume: VolumeLevel::default(),
+            sfx_volume: VolumeLevel::default(),
+            updated_at: 0,
+        }
+    }
+}
+
+impl Versioned for Settings {
+    const VERSION: u32 = 1;
+}
+
+impl Settings {
+    /// Loads the settings file, falling back to defaults if it is missing or invalid.
+    pub fn load() -> Self {
+        load_versioned(SETTINGS_FILE_NAME)
+    }
+
+    /// Returns the category runs are ranked in with these settings.
+    ///
+    /// Gliding makes any run easier, so glide runs are ranked as such even
+    /// in flicker mode.
+    pub fn score_category(&self) -> ScoreCategory {
+        if self.glide_mode {
+            ScoreCategory::Glide
+        } else if self.flicker_mode {
+            ScoreCategory::Flicker
+        } else {
+            ScoreCategory::Normal
+        }
+    }
+
+    /// Records that the player changed a setting just now.
+    pub fn touch(&mut self) {
+        self.updated_at = now_ms();
+    }
+
+    /// Writes the settings file, logging (not panicking) on failure.
+    pub fn store(&self) {
+        store_versioned(SETTINGS_FILE_NAME, self);
+    }
+
+    /// Checks settings read from another machine for values the game can't
+    /// use.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.theme.trim().is_empty() {
+            return Err("missing theme".to_string());
+        }
+        Ok(())
+    }
+
+    /// Writes the settings to the export file, returning its path.
+    pub fn export(&self) -> Result<PathBuf, String> {
+        let path = save_dir().join(SETTINGS_EXPORT_FILE_NAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        }
+        fs::write(&path, self.to_export_string()?).map_err(|error| error.to_string())?;
+        Ok(path)
+    }
+
+    /// Reads settings from the export file.
+    pub fn import() -> Result<Self, String> {
+        let path = save_dir().join(SETTINGS_EXPORT_FILE_NAME);
+        let contents = fs::read_to_string(&path)
+            .map_err(|_| format!("{SETTINGS_EXPORT_FILE_NAME} not found"))?;
+        Self::from_export_str(&contents)
+    }
+
+    /// Serializes the settings in the export format.
+    fn to_export_string(&self) -> Result<String, String> {
+        let export = SettingsExport {
+            version: SETTINGS_EXPORT_VERSION,
+            settings: self.clone(),
+        };
+        ron::ser::to_string_pretty(&export, ron::ser::PrettyConfig::default())
+            .map_err(|error| error.to_string())
+    }
+
+    /// Parses and validates settings in the export format.
+    ///
+    /// Files from a newer version are refused rather than half-read. The
+    /// imported settings count as changed now, so they win the next sync.
+    fn from_export_str(contents: &str) -> Result<Self, String> {
+        let export: SettingsExport =
+            ron::from_str(contents).map_err(|_| "not a settings file".to_string())?;
+        match export.version {
+            0 => return Err("not a settings file".to_string()),
+            version if version > SETTINGS_EXPORT_VERSION => {
+                return Err(format!("made by a newer version (format {version})"));
+            }
+            _ => {}
+        }
+        export.settings.validate()?;
+
+        let mut settings = export.settings;
+        settings.touch();
+        Ok(settings)
+    }
+}
+
+/// A row of the settings screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsRow {
+    /// Visual theme picker
+    Theme,
+    /// Seasonal themes toggle
+    SeasonalThemes,
+    /// Rainbow mode toggle
+    RainbowMode,
+    /// Ghost to race in the next runs
+    Ghost,
+    /// Bird trail shop and picker
+    Trail,
+    /// Haptic feedback toggle
+    Haptics,
+    /// Reduced motion toggle
+    ReducedMotion,
+    /// Screen shake toggle
+    ScreenShake,
+    /// Screen flash toggle
+    ScreenFlash,
+    /// Edge flash toggle
+    EdgeFlash,
+    /// Squash and stretch toggle
+    SquashStretch,
+    /// Companion toggle
+    Companion,
+    /// One-switch flapping toggle
+    OneSwitch,
+    /// Flicker challenge toggle
+    FlickerMode,
+    /// Glide variant toggle
+    GlideMode,
+    /// Flap cooldown toggle
+    FlapCooldown,
+    /// Playtime reminder toggle
+    PlaytimeReminder,
+    /// Speedrun timer toggle
+    SpeedrunTimer,
+    /// Vsync toggle
+    Vsync,
+    /// Frame-rate limit picker
+    FpsCap,
+    /// Battery saver toggle
+    BatterySaver,
+    /// Particle quality picker
+    ParticleQuality,
+    /// Bloom toggle
+    Bloom,
+    /// UI scale picker
+    UiScale,
+    /// Text size picker
+    TextScale,
+    /// Text outline picker
+    TextOutline,
+    /// Music volume picker
+    MusicVolume,
+    /// Sound effects volume picker
+    SfxVolume,
+    /// Writes the settings to the export file
+    ExportSettings,
+    /// Reads the settings from the export file
+    ImportSettings,
+    /// Writes progress and settings to the portable save file
+    ExportSave,
+    /// Replaces progress and settings with the portable save file's
+    ImportSave,
+    /// Writes the last run's splits and the best run's to the export file
+    ExportSplits,
+    /// Opens the controls screen
+    Controls,
+    /// Opens the seed entry screen
+    SeedCode,
+    /// Opens the sound test screen
+    SoundTest,
+    /// Return to the menu
+    Back,
+}
+
+impl SettingsRow {
+    /// All rows, in display order.
+    pub const ALL: [SettingsRow; 37] = [
+        SettingsRow::Theme,
+        SettingsRow::SeasonalThemes,
+        SettingsRow::RainbowMode,
+        SettingsRow::Ghost,
+        SettingsRow::Trail,
+        SettingsRow::Haptics,
+        SettingsRow::ReducedMotion,
+        SettingsRow::ScreenShake,
+        SettingsRow::ScreenFlash,
+        SettingsRow::EdgeFlash,
+        SettingsRow::SquashStretch,
+        SettingsRow::Companion,
+        SettingsRow::OneSwitch,
+        SettingsRow::FlickerMode,
+        SettingsRow::GlideMode,
+        SettingsRow::FlapCooldown,
+        SettingsRow::PlaytimeReminder,
+        SettingsRow::SpeedrunTimer,
+        SettingsRow::Vsync,
+        SettingsRow::FpsCap,
+        SettingsRow::BatterySaver,
+        SettingsRow::ParticleQuality,
+        SettingsRow::Bloom,
+        SettingsRow::UiScale,
+        SettingsRow::TextScale,
+        SettingsRow::TextOutline,
+        SettingsRow::MusicVolume,
+        SettingsRow::SfxVolume,
+        SettingsRow::ExportSettings,
+        SettingsRow::ImportSettings,
+        SettingsRow::ExportSave,
+        SettingsRow::ImportSave,
+        SettingsRow::ExportSplits,
+        SettingsRow::Controls,
+        SettingsRow::SeedCode,
+        SettingsRow::SoundTest,
+        SettingsRow::Back,
+    ];
+
+    /// Returns the text shown for this row.
+    pub fn label(&self, settings: &Settings, ghosts: &GhostLibrary, trail: &TrailChoice) -> String {
+        match self {
+            Self::Theme => format!("Theme: < {} >", settings.theme),
+            Self::SeasonalThemes => {
+                format!("Seasonal themes: < {} >", on_off(settings.seasonal_themes))
+            }
+            Self::RainbowMode => format!("Rainbow mode: < {} >", on_off(settings.rainbow_mode)),
+            Self::Ghost => format!("Ghost: < {} >", ghosts.selected_label()),
+            Self::Trail => trail.label(),
+            Self::Haptics => format!("Haptics: < {} >", on_off(settings.haptics)),
+            Self::ReducedMotion => {
+                format!("Reduced motion: < {} >", on_off(settings.reduced_motion))
+            }
+            Self::ScreenShake => format!("Screen shake: < {} >", on_off(settings.screen_shake)),
+            Self::ScreenFlash => format!("Screen flash: < {} >", on_off(settings.screen_flash)),
+            Self::EdgeFlash => format!("Edge flash: < {} >", on_off(settings.edge_flash)),
+            Self::SquashStretch => {
+                format!("Squash & stretch: < {} >", on_off(settings.squash_stretch))
+            }
+            Self::Companion => format!("Companion: < {} >", on_off(settings.companion)),
+            Self::OneSwitch => format!("One-switch: < {} >", on_off(settings.one_switch)),
+            Self::FlickerMode => format!("Flicker mode: < {} >", on_off(settings.flicker_mode)),
+            Self::GlideMode => format!("Glide mode: < {} >", on_off(settings.glide_mode)),
+            Self::FlapCooldown => {
+                format!("Flap cooldown: < {} >", on_off(settings.flap_cooldown))
+            }
+            Self::PlaytimeReminder => format!(
+                "Playtime reminder: < {} >",
+                on_off(settings.playtime_reminder)
+            ),
+            Self::SpeedrunTimer => {
+                format!("Speedrun timer: < {} >", on_off(settings.speedrun_timer))
+            }
+            Self::Vsync => format!("Vsync: < {} >", on_off(settings.vsync)),
+            Self::FpsCap => format!("FPS cap: < {} >", settings.fps_cap.label()),
+            Self::BatterySaver => format!("Battery saver: < {} >", on_off(settings.battery_saver)),
+            Self::ParticleQuality => {
+                format!("Particles: < {} >", settings.particle_quality.label())
+            }
+            Self::Bloom => format!("Bloom: < {} >", on_off(settings.bloom)),
+            Self::UiScale => format!("UI scale: < {} >", settings.ui_scale.label()),
+            Self::TextScale => format!("Text size: < {} >", settings.text_scale.label()),
+            Self::TextOutline => {
+                format!("Text outline: < {} >", settings.text_outline.label())
+            }
+            Self::MusicVolume => format!("Music volume: < {} >", settings.music_volume.label()),
+            Self::SfxVolume => format!("Effects volume: < {} >", settings.sfx_volume.label()),
+            Self::ExportSettings => "Export settings".to_string(),
+            Self::ImportSettings => "Import settings".to_string(),
+            Self::ExportSave => "Export save".to_string(),
+            Self::ImportSave => "Import save".to_string(),
+            Self::ExportSplits => "Export splits".to_string(),
+            Self::Controls => "Controls".to_string(),
+            Self::SeedCode => "Play a seed code".to_string(),
+            Self::SoundTest => "Sound test".to_string(),
+            Self::Back => "Back".to_string(),
+        }
+    }
+}
+
+/// A frame-rate limit.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FpsCap {
+    /// 30 frames per second
+    Thirty,
+    /// 60 frames per second
+    Sixty,
+    /// As fast as the display (or vsync) allows
+    #[default]
+    Uncapped,
+}
+
+impl FpsCap {
+    /// All limits, in display order.
+    pub const ALL: [FpsCap; 3] = [FpsCap::Thirty, FpsCap::Sixty, FpsCap::Uncapped];
+
+    /// Returns the text shown for this limit.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Thirty => "30",
+            Self::Sixty => "60",
+            Self::Uncapped => "Uncapped",
+        }
+    }
+
+    /// Returns the limit `step` places away, wrapping around.
+    pub fn cycle(&self, step: i32) -> FpsCap {
+        let index = Self::ALL.iter().position(|cap| cap == self).unwrap_or(0);
+        Self::ALL[(index as i32 + step).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+
+    /// Returns the minimum time per frame, if limited.
+    pub fn frame_time(&self) -> Option<Duration> {
+        match self {
+            Self::Thirty => Some(Duration::from_secs_f64(1.0 / 30.0)),
+            Self::Sixty => Some(Duration::from_secs_f64(1.0 / 60.0)),
+            Self::Uncapped => None,
+        }
+    }
+}
+
+/// How many particles effects spawn, and how long they last.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParticleQuality {
+    /// Few, short-lived particles for low-end devices
+    Low,
+    /// Fewer particles
+    Medium,
+    /// Every particle from the game config
+    #[default]
+    High,
+}
+
+impl ParticleQuality {
+    /// All qualities, in display order.
+    pub const ALL: [ParticleQuality; 3] = [
+        ParticleQuality::Low,
+        ParticleQuality::Medium,
+        ParticleQuality::High,
+    ];
+
+    /// Returns the text shown for this quality.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+        }
+    }
+
+    /// Returns the quality `step` places away, wrapping around.
+    pub fn cycle(&self, step: i32) -> ParticleQuality {
+        let index = Self::ALL
+            .iter()
+            .position(|quality| quality == self)
+            .unwrap_or(0);
+        Self::ALL[(index as i32 + step).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+
+    /// Returns the multipliers applied to particle counts and lifetimes.
+    pub fn scales(&self) -> (f32, f32) {
+        match self {
+            Self::Low => (0.4, 0.6),
+            Self::Medium => (0.7, 0.8),
+            Self::High => (1.0, 1.0),
+        }
+    }
+}
+
+/// Size of the HUD relative to the window.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UiScaleOption {
+    /// 85%
+    Small,
+    /// 100%
+    #[default]
+    Normal,
+    /// 125%
+    Large,
+    /// 150%
+    Largest,
+}
+
+impl UiScaleOption {
+    /// All scales, in display order.
+    pub const ALL: [UiScaleOption; 4] = [
+        UiScaleOption::Small,
+        UiScaleOption::Normal,
+        UiScaleOption::Large,
+        UiScaleOption::Largest,
+    ];
+
+    /// Returns the text shown for this scale.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Small => "85%",
+            Self::Normal => "100%",
+            Self::Large => "125%",
+            Self::Largest => "150%",
+        }
+    }
+
+    /// Returns the scale `step` places away, wrapping around.
+    pub fn cycle(&self, step: i32) -> UiScaleOption {
+        let index = Self::ALL
+            .iter()
+            .position(|scale| scale == self)
+            .unwrap_or(0);
+        Self::ALL[(index as i32 + step).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+
+    /// Returns the factor applied to the HUD's size.
+    pub fn factor(&self) -> f32 {
+        match self {
+            Self::Small => 0.85,
+            Self::Normal => 1.0,
+            Self::Large => 1.25,
+            Self::Largest => 1.5,
+        }
+    }
+}
+
+/// Size of the HUD and menu text, for players who need it larger.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextScale {
+    /// 100%
+    #[default]
+    Normal,
+    /// 125%
+    Large,
+    /// 150%
+    Larger,
+    /// 175%
+    Largest,
+    /// 200%
+    Double,
+}
+
+impl TextScale {
+    /// All sizes, in display order.
+    pub const ALL: [TextScale; 5] = [
+        TextScale::Normal,
+        TextScale::Large,
+        TextScale::Larger,
+        TextScale::Largest,
+        TextScale::Double,
+    ];
+
+    /// Returns the text shown for this size.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Normal => "100%",
+            Self::Large => "125%",
+            Self::Larger => "150%",
+            Self::Largest => "175%",
+            Self::Double => "200%",
+        }
+    }
+
+    /// Returns the size `step` places away, wrapping around.
+    pub fn cycle(&self, step: i32) -> TextScale {
+        let index = Self::ALL.iter().position(|size| size == self).unwrap_or(0);
+        Self::ALL[(index as i32 + step).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+
+    /// Returns the factor applied to font sizes.
+    pub fn factor(&self) -> f32 {
+        match self {
+            Self::Normal => 1.0,
+            Self::Large => 1.25,
+            Self::Larger => 1.5,
+            Self::Largest => 1.75,
+            Self::Double => 2.0,
+        }
+    }
+}
+
+/// What keeps the score and instruction text readable over bright skies and
+/// flashes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextOutline {
+    /// Plain text
+    Off,
+    /// A dark drop shadow
+    #[default]
+    Shadow,
+    /// A dark outline all around the glyphs
+    Outline,
+}
+
+impl TextOutline {
+    /// All styles, in display order.
+    pub const ALL: [TextOutline; 3] = [TextOutline::Off, TextOutline::Shadow, TextOutline::Outline];
+
+    /// Returns the text shown for this style.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Shadow => "Shadow",
+            Self::Outline => "Outline",
+        }
+    }
+
+    /// Returns the style `step` places away, wrapping around.
+    pub fn cycle(&self, step: i32) -> TextOutline {
+        let index = Self::ALL
+            .iter()
+            .position(|style| style == self)
+            .unwrap_or(0);
+        Self::ALL[(index as i32 + step).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+}
+
+/// Volume of an audio channel.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VolumeLevel {
+    /// Silent
+    Off,
+    /// 25%
+    Low,
+    /// 50%
+    Medium,
+    /// 75%
+    High,
+    /// 100%
+    #[default]
+    Full,
+}
+
+impl VolumeLevel {
+    /// All levels, in display order.
+    pub const ALL: [VolumeLevel; 5] = [
+        VolumeLevel::Off,
+        VolumeLevel::Low,
+        VolumeLevel::Medium,
+        VolumeLevel::High,
+        VolumeLevel::Full,
+    ];
+
+    /// Returns the text shown for this level.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Low => "25%",
+            Self::Medium => "50%",
+            Self::High => "75%",
+            Self::Full => "100%",
+        }
+    }
+
+    /// Returns the level `step` places away, wrapping around.
+    pub fn cycle(&self, step: i32) -> VolumeLevel {
+        let index = Self::ALL
+            .iter()
+            .position(|level| level == self)
+            .unwrap_or(0);
+        Self::ALL[(index as i32 + step).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+
+    /// Returns the factor applied to the channel's sounds.
+    pub fn factor(&self) -> f32 {
+        match self {
+            Self::Off => 0.0,
+            Self::Low => 0.25,
+            Self::Medium => 0.5,
+            Self::High => 0.75,
+            Self::Full => 1.0,
+        }
+    }
+}
+
+/// Run condition: the rainbow mode is on.
+pub fn rainbow_mode_enabled(settings: Res<Settings>) -> bool {
+    settings.rainbow_mode
+}
+
+/// Run condition: the one-switch flap controller is selected.
+pub fn one_switch_enabled(settings: Res<Settings>) -> bool {
+    settings.one_switch
+}
+
+/// Returns "On" or "Off" for a toggle.
+pub fn on_off(enabled: bool) -> &'static str {
+    if enabled { "On" } else { "Off" }
+}
+
+/// Returns the option `step` places away from `current`, wrapping around.
+///
+/// Falls back to the first option if `current` is not in the list.
+pub fn cycle<'a>(options: &'a [String], current: &str, step: i32) -> Option<&'a String> {
+    let index = options
+        .iter()
+        .position(|option| option == current)
+        .map_or(0, |index| {
+            (index as i32 + step).rem_euclid(options.len() as i32) as usize
+        });
+    options.get(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        assert_eq!(
+            ron::from_str::<Settings>("()").ok(),
+            Some(Settings::default())
+        );
+    }
+
+    #[test]
+    fn exported_settings_import_back() {
+        let settings = Settings {
+            theme: "night".to_string(),
+            vsync: false,
+            fps_cap: FpsCap::Sixty,
+            ..default()
+        };
+        let imported = settings
+            .to_export_string()
+            .and_then(|contents| Settings::from_export_str(&contents));
+        assert_eq!(
+            imported.map(|imported| Settings {
+                updated_at: settings.updated_at,
+                ..imported
+            }),
+            Ok(settings)
+        );
+    }
+
+    #[test]
+    fn import_rejects_invalid_files() {
+        assert!(Settings::from_export_str("not ron").is_err());
+        assert!(Settings::from_export_str("(settings: ())").is_err());
+        assert!(Settings::from_export_str("(version: 99, settings: ())").is_err());
+        assert!(Settings::from_export_str("(version: 1, settings: (theme: \"\"))").is_err());
+        assert!(Settings::from_export_str("(version: 1, settings: ())").is_ok());
+    }
+
+    #[test]
+    fn fps_cap_cycles_through_limits() {
+        assert_eq!(FpsCap::Uncapped.cycle(1), FpsCap::Thirty);
+        assert_eq!(FpsCap::Thirty.cycle(-1), FpsCap::Uncapped);
+        assert_eq!(
+            FpsCap::Sixty.frame_time(),
+            Some(Duration::from_secs_f64(1.0 / 60.0))
+        );
+    }
+
+    #[test]
+    fn ui_scale_cycles_through_sizes() {
+        assert_eq!(UiScaleOption::Largest.cycle(1), UiScaleOption::Small);
+        assert_eq!(UiScaleOption::Normal.cycle(-1), UiScaleOption::Small);
+        assert_eq!(UiScaleOption::default().factor(), 1.0);
+    }
+
+    #[test]
+    fn text_scale_cycles_through_sizes() {
+        assert_eq!(TextScale::Double.cycle(1), TextScale::Normal);
+        assert_eq!(TextScale::Normal.cycle(-1), TextScale::Double);
+        assert_eq!(TextScale::default().factor(), 1.0);
+        assert_eq!(TextScale::Double.factor(), 2.0);
+    }
+
+    #[test]
+    fn volume_cycles_through_levels() {
+        assert_eq!(VolumeLevel::Full.cycle(1), VolumeLevel::Off);
+        assert_eq!(VolumeLevel::Off.cycle(-1), VolumeLevel::Full);
+        assert_eq!(VolumeLevel::Off.factor(), 0.0);
+        assert_eq!(VolumeLevel::default().factor(), 1.0);
+    }
+
+    #[test]
+    fn text_outline_cycles_through_styles() {
+        assert_eq!(TextOutline::default().cycle(1), TextOutline::Outline);
+        assert_eq!(TextOutline::Outline.cycle(1), TextOutline::Off);
+        assert_eq!(TextOutline::Off.cycle(-1), TextOutline::Outline);
+    }
+
+    #[test]
+    fn cycle_wraps_in_both_directions() {
+        let options = ["A", "B", "C"].map(String::from);
+        assert_eq!(cycle(&options, "C", 1).map(String::as_str), Some("A"));
+        assert_eq!(cycle(&options, "A", -1).map(String::as_str), Some("C"));
+        assert_eq!(cycle(&options, "missing", 1).map(String::as_str), Some("A"));
+        assert_eq!(cycle(&[], "A", 1), None);
+    }
+}
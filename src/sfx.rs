@@ -0,0 +1,108 @@
+//! Synthesized sound effects.
+//!
+//! Menu sounds, the whoosh of state transitions and the ticks of the score
+//! counting up on the results screen are synthesized at startup like the
+//! music, so they need no audio files either. They all play through the
+//! effects channel: systems send a `PlaySfx` message and the sound is spawned
+//! at the effects volume from the settings.
+
+use std::f32::consts::TAU;
+
+use crate::music::{MusicLoop, SAMPLE_RATE, add_note, triangle};
+use crate::rng::RngStream;
+
+/// A sound effect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundEffect {
+    /// A button or menu item was activated
+    Click,
+    /// The pointer or the focus moved onto a menu item
+    Hover,
+    /// The player left a screen
+    Back,
+    /// The game moved to another state
+    Whoosh,
+    /// The final score counted up by one on the results screen
+    Tick,
+}
+
+impl SoundEffect {
+    /// All effects, in display order.
+    pub const ALL: [SoundEffect; 5] = [
+        SoundEffect::Click,
+        SoundEffect::Hover,
+        SoundEffect::Back,
+        SoundEffect::Whoosh,
+        SoundEffect::Tick,
+    ];
+
+    /// Returns the effect's display name.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Click => "Click",
+            Self::Hover => "Hover",
+            Self::Back => "Back",
+            Self::Whoosh => "Transition whoosh",
+            Self::Tick => "Score tick",
+        }
+    }
+
+    /// Synthesizes the effect.
+    pub fn synthesize(&self) -> MusicLoop {
+        let length = match self {
+            Self::Click => 0.06,
+            Self::Hover => 0.04,
+            Self::Back => 0.16,
+            Self::Whoosh => 0.45,
+            Self::Tick => 0.03,
+        };
+        let mut samples = vec![0.0; (length * SAMPLE_RATE as f32) as usize];
+
+        match self {
+            Self::Click => add_note(&mut samples, 0.0, length, |t| {
+                0.3 * triangle(1200.0 * t) * (-t * 70.0).exp()
+            }),
+            Self::Hover => add_note(&mut samples, 0.0, length, |t| {
+                0.1 * (TAU * 1800.0 * t).sin() * (-t * 90.0).exp()
+            }),
+            Self::Back => {
+                // Two falling notes
+                for (index, frequency) in [880.0, 660.0].into_iter().enumerate() {
+                    add_note(&mut samples, index as f32 * 0.07, 0.08, |t| {
+                        0.2 * triangle(frequency * t) * (-t * 40.0).exp()
+                    });
+                }
+            }
+            Self::Tick => add_note(&mut samples, 0.0, length, |t| {
+                0.15 * (TAU * 2400.0 * t).sin() * (-t * 150.0).exp()
+            }),
+            Self::Whoosh => {
+                // Noise through a low-pass filter opening and closing again
+                let mut noise = RngStream::from_seed(0);
+                let mut filtered = 0.0;
+                add_note(&mut samples, 0.0, length, |t| {
+                    let swell = (std::f32::consts::PI * t / length).sin();
+                    let cutoff = 0.02 + 0.2 * swell;
+                    filtered += (noise.f32() * 2.0 - 1.0 - filtered) * cutoff;
+                    0.6 * filtered * swell
+                });
+            }
+        }
+
+        MusicLoop::from_samples(samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_are_short_and_audible() {
+        for effect in SoundEffect::ALL {
+            let sound = effect.synthesize();
+            assert!(sound.duration() < 0.5, "{effect:?} is too long");
+            assert!(sound.is_audible(), "{effect:?} is silent");
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Speedrun splits.
+//!
+//! With the speedrun timer on, each run records its flight time at every
+//! pipe passed. The personal best (the run that passed the most pipes,
+//! fastest on a tie) is persisted in `splits.ron` inside the save directory,
+//! and the timer shows how far ahead or behind it each pipe was passed. The
+//! settings screen exports the last run and the best run side by side as a
+//! CSV file.
+
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::save::{Versioned, load_versioned, save_dir, store_versioned};
+
+/// Name of the personal best splits file inside the save directory.
+const SPLITS_FILE_NAME: &str = "splits.ron";
+
+/// Name of the exported splits file inside the save directory.
+pub const SPLITS_EXPORT_FILE_NAME: &str = "splits-export.csv";
+
+/// Resource holding the split times of the current (or last) run.
+#[derive(Resource, Default, Clone, Debug, PartialEq)]
+pub struct RunSplits {
+    /// Flight time when each pipe was passed, in seconds
+    pub times: Vec<f32>,
+}
+
+/// Resource holding the split times of the personal best run.
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct BestSplits {
+    /// Flight time when each pipe was passed, in seconds
+    pub times: Vec<f32>,
+}
+
+impl Versioned for BestSplits {
+    const VERSION: u32 = 1;
+}
+
+impl BestSplits {
+    /// Loads the splits file, falling back to no best run if it is missing or invalid.
+    pub fn load() -> Self {
+        load_versioned(SPLITS_FILE_NAME)
+    }
+
+    /// Writes the splits file, logging (not panicking) on failure.
+    pub fn store(&self) {
+        store_versioned(SPLITS_FILE_NAME, self);
+    }
+
+    /// Returns whether a run with these split times beats the best run: it
+    /// passed more pipes, or as many sooner.
+    pub fn is_beaten_by(&self, times: &[f32]) -> bool {
+        match (times.last(), self.times.last()) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(time), Some(best)) => {
+                times.len() > self.times.len() || (times.len() == self.times.len() && time < best)
+            }
+        }
+    }
+
+    /// Returns how much later than the best run a pipe was passed (negative
+    /// when ahead), if the best run passed it too.
+    pub fn delta(&self, pipe: usize, time: f32) -> Option<f32> {
+        self.times.get(pipe).map(|best| time - best)
+    }
+
+    /// Writes a run's splits and the best run's to the export file, returning
+    /// its path.
+    pub fn export(&self, run: &RunSplits) -> Result<PathBuf, String> {
+        let path = save_dir().join(SPLITS_EXPORT_FILE_NAME);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        }
+        fs::write(&path, self.to_csv(run)).map_err(|error| error.to_string())?;
+        Ok(path)
+    }
+
+    /// Formats a run's splits and the best run's as CSV, one pipe per line.
+    fn to_csv(&self, run: &RunSplits) -> String {
+        let mut csv = "pipe,time,best,delta\n".to_string();
+        for pipe in 0..run.times.len().max(self.times.len()) {
+            let time = run.times.get(pipe);
+            let best = self.times.get(pipe);
+            let delta = time.and_then(|time| self.delta(pipe, *time));
+            let cell =
+                |value: Option<f32>| value.map_or(String::new(), |value| format!("{value:.3}"));
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                pipe + 1,
+                cell(time.copied()),
+                cell(best.copied()),
+                cell(delta)
+            ));
+        }
+        csv
+    }
+}
+
+/// Formats a run time as seconds, with minutes past the first (`1:02.35`).
+pub fn format_split_time(seconds: f32) -> String {
+    let hundredths = (seconds.max(0.0) * 100.0).round() as u32;
+    let (minutes, hundredths) = (hundredths / 6000, hundredths % 6000);
+    if minutes > 0 {
+        format!("{minutes}:{:02}.{:02}", hundredths / 100, hundredths % 100)
+    } else {
+        format!("{}.{:02}", hundredths / 100, hundredths % 100)
+    }
+}
+
+/// Formats the difference to the best run, signed (`-0.52`, `+1.20`).
+pub fn format_split_delta(delta: f32) -> String {
+    let sign = if delta < 0.0 { '-' } else { '+' };
+    format!("{sign}{}", format_split_time(delta.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn more_pipes_or_faster_splits_beat_the_best() {
+        let best = BestSplits {
+            times: vec![1.0, 2.0, 3.0],
+        };
+        assert!(!best.is_beaten_by(&[]));
+        assert!(!best.is_beaten_by(&[0.5, 1.0]));
+        assert!(!best.is_beaten_by(&[1.0, 2.0, 3.5]));
+        assert!(best.is_beaten_by(&[1.0, 2.0, 2.5]));
+        assert!(best.is_beaten_by(&[1.0, 2.0, 3.0, 4.0]));
+        assert!(BestSplits::default().is_beaten_by(&[9.0]));
+    }
+
+    #[test]
+    fn split_times_format_like_a_stopwatch() {
+        assert_eq!(format_split_time(3.456), "3.46");
+        assert_eq!(format_split_time(62.35), "1:02.35");
+        assert_eq!(format_split_delta(-0.52), "-0.52");
+        assert_eq!(format_split_delta(1.2), "+1.20");
+    }
+
+    #[test]
+    fn exported_splits_line_up_by_pipe() {
+        let best = BestSplits {
+            times: vec![1.0, 2.0],
+        };
+        let run = RunSplits {
+            times: vec![0.75, 2.5, 4.0],
+        };
+        assert_eq!(
+            best.to_csv(&run),
+            "pipe,time,best,delta\n\
+             1,0.750,1.000,-0.250\n\
+             2,2.500,2.000,0.500\n\
+             3,4.000,,\n"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Game state definitions.
 //!
-//! This module contains the game state machine.
+//! This module contains the game state machine and the phases of a run.
 
 use bevy::prelude::*;
 
@@ -14,4 +14,51 @@ pub enum GameState {
     Playing,
     /// Game over state, waiting for restart.
     GameOver,
+    /// Settings screen, opened from the menu.
+    Settings,
+    /// Sound test screen, opened from the settings.
+    Jukebox,
+    /// Controls screen, opened from the settings.
+    Controls,
+    /// Seed code entry screen, opened from the settings.
+    SeedEntry,
+    /// Mutator selection screen, opened from the menu before a run.
+    Mutators,
+}
+
+/// Phase of a run, only present while `Playing`.
+#[derive(SubStates, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[source(GameState = GameState::Playing)]
+pub enum PlayPhase {
+    /// Regular pipe spawning.
+    #[default]
+    Normal,
+    /// Scripted boss segment; regular spawning is paused until it is cleared.
+    Boss,
+    /// First-run tutorial; regular spawning waits until it is finished.
+    Tutorial,
+}
+
+/// Step of the first-run tutorial, only present during `PlayPhase::Tutorial`.
+#[derive(SubStates, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[source(PlayPhase = PlayPhase::Tutorial)]
+pub enum TutorialStep {
+    /// Flapping a few times, with slowed gravity and the bird kept on screen.
+    #[default]
+    Flap,
+    /// Flying through a single wide gap, with normal physics.
+    PracticeGap,
+}
+
+/// Whether a run is paused, only present while `Playing`.
+#[derive(SubStates, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[source(GameState = GameState::Playing)]
+pub enum PauseState {
+    /// The run is in progress.
+    #[default]
+    Running,
+    /// The run is frozen until the player resumes it.
+    Paused,
+    /// The bird has crashed and the player is offered a revive.
+    Reviving,
 }
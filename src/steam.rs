@@ -0,0 +1,721 @@
+//! Steamworks integration (behind the `steam` feature).
+//!
+//! Mirrors unlocked achievements (including those saved before Steam was
+//! connected) to Steam and uploads each run's score to a
+//! Steam leaderboard. When a run improves the player's leaderboard entry, its
+//! replay is shared through Steam Remote Storage and attached to the entry,
+//! and the replays of the top entries are downloaded as ghosts to race
+//! against. The Steamworks runtime (`steam_api`) is loaded
+//! dynamically through its flat C API, so the game still starts — with the
+//! integration silently disabled — when Steam or the library is unavailable.
+
+use std::ffi::{CString, c_char, c_void};
+
+use bevy::prelude::*;
+use libloading::{Library, Symbol};
+
+use crate::replay::{GhostEntry, GhostLibrary, Replay};
+use crate::resources::{AchievementUnlocked, ReplayRecorded};
+use crate::save::SaveData;
+
+/// Name of the Steam leaderboard scores are uploaded to.
+const LEADERBOARD_NAME: &str = "High Scores";
+
+/// `k_ELeaderboardUploadScoreMethodKeepBest`
+const UPLOAD_METHOD_KEEP_BEST: i32 = 1;
+
+/// `k_ELeaderboardDataRequestGlobal`
+const DATA_REQUEST_GLOBAL: i32 = 0;
+
+/// Number of top leaderboard entries whose ghosts are downloaded.
+const GHOST_ENTRY_COUNT: i32 = 10;
+
+/// Remote Storage file the player's best replay is shared from.
+const REPLAY_FILE_NAME: &str = "ghost.replay.ron";
+
+/// `k_EResultOK`
+const RESULT_OK: i32 = 1;
+
+/// `k_UGCHandleInvalid`
+const INVALID_UGC_HANDLE: u64 = u64::MAX;
+
+/// `k_EUGCRead_Close`: read the file and release it.
+const UGC_READ_CLOSE: i32 = 2;
+
+/// Callback id of `LeaderboardFindResult_t` (`k_iSteamUserStatsCallbacks + 4`).
+const LEADERBOARD_FIND_RESULT_ID: i32 = 1104;
+
+/// Callback id of `LeaderboardScoresDownloaded_t` (`k_iSteamUserStatsCallbacks + 5`).
+const LEADERBOARD_SCORES_DOWNLOADED_ID: i32 = 1105;
+
+/// Callback id of `LeaderboardScoreUploaded_t` (`k_iSteamUserStatsCallbacks + 6`).
+const LEADERBOARD_SCORE_UPLOADED_ID: i32 = 1106;
+
+/// Callback id of `RemoteStorageFileShareResult_t` (`k_iSteamRemoteStorageCallbacks + 7`).
+const FILE_SHARE_RESULT_ID: i32 = 1307;
+
+/// Callback id of `RemoteStorageDownloadUGCResult_t` (`k_iSteamRemoteStorageCallbacks + 17`).
+const DOWNLOAD_UGC_RESULT_ID: i32 = 1317;
+
+/// `SteamAPICall_t` handle for an asynchronous Steam request.
+type ApiCall = u64;
+
+/// Shared library name of the Steamworks runtime per platform.
+#[cfg(target_os = "windows")]
+const STEAM_API_LIBRARY: &str = "steam_api64.dll";
+#[cfg(target_os = "macos")]
+const STEAM_API_LIBRARY: &str = "libsteam_api.dylib";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const STEAM_API_LIBRARY: &str = "libsteam_api.so";
+
+/// Versioned interface accessors, newest first, to support several SDK releases.
+const USER_STATS_ACCESSORS: [&[u8]; 2] = [
+    b"SteamAPI_SteamUserStats_v013\0",
+    b"SteamAPI_SteamUserStats_v012\0",
+];
+const UTILS_ACCESSORS: [&[u8]; 1] = [b"SteamAPI_SteamUtils_v010\0"];
+const REMOTE_STORAGE_ACCESSORS: [&[u8]; 2] = [
+    b"SteamAPI_SteamRemoteStorage_v016\0",
+    b"SteamAPI_SteamRemoteStorage_v014\0",
+];
+
+/// Result payload of `FindLeaderboard` (`LeaderboardFindResult_t`).
+///
+/// Steam packs callback structs to 8 bytes on Windows and 4 elsewhere.
+#[cfg_attr(target_os = "windows", repr(C, packed(8)))]
+#[cfg_attr(not(target_os = "windows"), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct LeaderboardFindResult {
+    leaderboard: u64,
+    found: u8,
+}
+
+/// Result payload of `UploadLeaderboardScore` (`LeaderboardScoreUploaded_t`).
+#[cfg_attr(target_os = "windows", repr(C, packed(8)))]
+#[cfg_attr(not(target_os = "windows"), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct LeaderboardScoreUploaded {
+    success: u8,
+    leaderboard: u64,
+    score: i32,
+    score_changed: u8,
+    global_rank_new: i32,
+    global_rank_previous: i32,
+}
+
+/// Result payload of `DownloadLeaderboardEntries` (`LeaderboardScoresDownloaded_t`).
+#[cfg_attr(target_os = "windows", repr(C, packed(8)))]
+#[cfg_attr(not(target_os = "windows"), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct LeaderboardScoresDownloaded {
+    leaderboard: u64,
+    entries: u64,
+    entry_count: i32,
+}
+
+/// A downloaded leaderboard row (`LeaderboardEntry_t`).
+#[cfg_attr(target_os = "windows", repr(C, packed(8)))]
+#[cfg_attr(not(target_os = "windows"), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct LeaderboardEntry {
+    user: u64,
+    global_rank: i32,
+    score: i32,
+    details: i32,
+    ugc: u64,
+}
+
+/// Result payload of `FileShare` (`RemoteStorageFileShareResult_t`).
+#[cfg_attr(target_os = "windows", repr(C, packed(8)))]
+#[cfg_attr(not(target_os = "windows"), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct FileShareResult {
+    result: i32,
+    file: u64,
+    file_name: [u8; 260],
+}
+
+/// Result payload of `UGCDownload` (`RemoteStorageDownloadUGCResult_t`).
+#[cfg_attr(target_os = "windows", repr(C, packed(8)))]
+#[cfg_attr(not(target_os = "windows"), repr(C, packed(4)))]
+#[derive(Clone, Copy)]
+struct DownloadUgcResult {
+    result: i32,
+    file: u64,
+    app_id: u32,
+    size: i32,
+    file_name: [u8; 260],
+    owner: u64,
+}
+
+/// State of an asynchronous Steam request.
+enum CallStatus<T> {
+    /// Still running
+    Pending,
+    /// Finished with an I/O failure
+    Failed,
+    /// Finished with this result
+    Done(T),
+}
+
+/// An asynchronous request whose result is still awaited.
+enum PendingCall {
+    /// A score upload, with the run's replay to share if it became the best
+    ScoreUpload(Replay),
+    /// Sharing the replay file, to attach to the leaderboard entry
+    ReplayShare { leaderboard: u64 },
+    /// Downloading the top leaderboard entries
+    EntriesDownload,
+    /// Downloading the replay of a leaderboard entry
+    GhostDownload { rank: i32, score: i32 },
+}
+
+/// Plugin wiring achievements and scores to Steam when it is available.
+pub struct SteamPlugin;
+
+impl Plugin for SteamPlugin {
+    fn build(&self, app: &mut App) {
+        match Steam::connect() {
+            Ok(steam) => {
+                info!("Connected to Steam");
+                app.insert_resource(steam)
+                    .add_systems(Startup, sync_saved_achievements)
+                    .add_systems(
+                        Update,
+                        (
+                            run_steam_callbacks,
+                            sync_achievements,
+                            poll_leaderboard,
+                            upload_scores,
+                            poll_steam_calls,
+                        )
+                            .chain(),
+                    );
+            }
+            Err(reason) => info!("Steam integration disabled: {reason}"),
+        }
+    }
+}
+
+/// Handle to a running Steam client.
+#[derive(Resource)]
+pub struct Steam {
+    /// The loaded `steam_api` library (kept alive for the function pointers)
+    library: Library,
+    /// `ISteamUserStats` interface pointer
+    user_stats: *mut c_void,
+    /// `ISteamUtils` interface pointer
+    utils: *mut c_void,
+    /// `ISteamRemoteStorage` interface pointer, if the SDK provides one
+    remote_storage: Option<*mut c_void>,
+    /// Pending `FindLeaderboard` request
+    find_leaderboard: Option<ApiCall>,
+    /// Resolved leaderboard handle
+    leaderboard: Option<u64>,
+    /// Runs waiting for the leaderboard handle to resolve
+    pending_scores: Vec<Replay>,
+    /// Other requests whose results are awaited
+    calls: Vec<(ApiCall, PendingCall)>,
+}
+
+// SAFETY: the Steamworks interfaces are documented as thread-safe, and the
+// pointers are only ever used through the owning resource.
+unsafe impl Send for Steam {}
+// SAFETY: see above; all calls go through `&mut self` or are read-only.
+unsafe impl Sync for Steam {}
+
+impl Steam {
+    /// Loads the Steam runtime and initializes the API.
+    fn connect() -> Result<Self, String> {
+        // SAFETY: loading the official Steamworks runtime, which has no
+        // initialization side effects beyond those of `SteamAPI_Init*`.
+        let library = unsafe { Library::new(STEAM_API_LIBRARY) }
+            .map_err(|error| format!("{STEAM_API_LIBRARY} not found ({error})"))?;
+
+        // SAFETY: symbol signatures match the Steamworks flat API headers.
+        unsafe {
+            let initialized = if let Ok(init_flat) =
+                library.get::<unsafe extern "C" fn(*mut c_char) -> i32>(b"SteamAPI_InitFlat\0")
+            {
+                // `SteamErrMsg` is a 1024 byte buffer; 0 means `k_ESteamAPIInitResult_OK`
+                let mut error_message = [0 as c_char; 1024];
+                init_flat(error_message.as_mut_ptr()) == 0
+            } else {
+                let init: Symbol<unsafe extern "C" fn() -> bool> = library
+                    .get(b"SteamAPI_Init\0")
+                    .map_err(|error| error.to_string())?;
+                init()
+            };
+            if !initialized {
+                return Err("Steam is not running".to_string());
+            }
+
+            let user_stats = interface(&library, &USER_STATS_ACCESSORS)?;
+            let utils = interface(&library, &UTILS_ACCESSORS)?;
+            // Ghosts are optional: scores still upload without Remote Storage
+            let remote_storage = interface(&library, &REMOTE_STORAGE_ACCESSORS).ok();
+
+            let mut steam = Self {
+                library,
+                user_stats,
+                utils,
+                remote_storage,
+                find_leaderboard: None,
+                leaderboard: None,
+                pending_scores: Vec::new(),
+                calls: Vec::new(),
+            };
+            steam.request_leaderboard();
+            Ok(steam)
+        }
+    }
+
+    /// Looks up a flat API function by its nul-terminated name.
+    fn function<T>(&self, name: &[u8]) -> Option<Symbol<'_, T>> {
+        // SAFETY: callers request symbols with signatures matching the SDK headers.
+        unsafe { self.library.get(name).ok() }
+    }
+
+    /// Starts the asynchronous leaderboard lookup.
+    fn request_leaderboard(&mut self) {
+        type FindLeaderboard = unsafe extern "C" fn(*mut c_void, *const c_char) -> ApiCall;
+        let Ok(name) = CString::new(LEADERBOARD_NAME) else {
+            return;
+        };
+        if let Some(find) =
+            self.function::<FindLeaderboard>(b"SteamAPI_ISteamUserStats_FindLeaderboard\0")
+        {
+            // SAFETY: valid interface pointer and nul-terminated name.
+            let call = unsafe { find(self.user_stats, name.as_ptr()) };
+            self.find_leaderboard = Some(call).filter(|call| *call != 0);
+        }
+    }
+
+    /// Sets achievements and commits them to Steam in one go.
+    ///
+    /// Setting an achievement that is already set does nothing, so this is
+    /// safe to call with achievements Steam already has.
+    fn unlock<'a>(&self, api_names: impl IntoIterator<Item = &'a str>) {
+        type SetAchievement = unsafe extern "C" fn(*mut c_void, *const c_char) -> bool;
+        type StoreStats = unsafe extern "C" fn(*mut c_void) -> bool;
+
+        let set = self.function::<SetAchievement>(b"SteamAPI_ISteamUserStats_SetAchievement\0");
+        let store = self.function::<StoreStats>(b"SteamAPI_ISteamUserStats_StoreStats\0");
+        let (Some(set), Some(store)) = (set, store) else {
+            return;
+        };
+        for api_name in api_names {
+            let Ok(name) = CString::new(api_name) else {
+                continue;
+            };
+            // SAFETY: valid interface pointer and nul-terminated name.
+            if !unsafe { set(self.user_stats, name.as_ptr()) } {
+                warn!("Steam rejected achievement {api_name}");
+            }
+        }
+        // SAFETY: valid interface pointer.
+        if !unsafe { store(self.user_stats) } {
+            warn!("Steam failed to store achievements");
+        }
+    }
+
+    /// Uploads a run's score, keeping only the player's best on the leaderboard.
+    fn upload(&mut self, leaderboard: u64, replay: Replay) {
+        type UploadScore =
+            unsafe extern "C" fn(*mut c_void, u64, i32, i32, *const i32, i32) -> ApiCall;
+        if let Some(upload) =
+            self.function::<UploadScore>(b"SteamAPI_ISteamUserStats_UploadLeaderboardScore\0")
+        {
+            let score = replay.score.min(i32::MAX as u32) as i32;
+            // The seed rides along so a verifier can match the entry to its replay
+            let details = replay.score_details();
+            // SAFETY: valid interface pointer; the details array outlives the
+            // call and holds the count passed.
+            let call = unsafe {
+                upload(
+                    self.user_stats,
+                    leaderboard,
+                    UPLOAD_METHOD_KEEP_BEST,
+                    score,
+                    details.as_ptr(),
+                    details.len() as i32,
+                )
+            };
+            self.track(call, PendingCall::ScoreUpload(replay));
+        }
+    }
+
+    /// Awaits the result of a request, ignoring requests that failed to start.
+    fn track(&mut self, call: ApiCall, pending: PendingCall) {
+        if call != 0 {
+            self.calls.push((call, pending));
+        }
+    }
+
+    /// Returns the state of an asynchronous request.
+    ///
+    /// `T` must be the plain-old-data callback struct registered under
+    /// `callback_id`.
+    fn call_result<T: Copy>(&self, call: ApiCall, callback_id: i32) -> CallStatus<T> {
+        type IsCompleted = unsafe extern "C" fn(*mut c_void, ApiCall, *mut bool) -> bool;
+        type GetResult =
+            unsafe extern "C" fn(*mut c_void, ApiCall, *mut c_void, i32, i32, *mut bool) -> bool;
+
+        let is_completed =
+            self.function::<IsCompleted>(b"SteamAPI_ISteamUtils_IsAPICallCompleted\0");
+        let get_result = self.function::<GetResult>(b"SteamAPI_ISteamUtils_GetAPICallResult\0");
+        let (Some(is_completed), Some(get_result)) = (is_completed, get_result) else {
+            return CallStatus::Failed;
+        };
+
+        let mut failed = false;
+        // SAFETY: callback structs are plain integers and byte arrays, for
+        // which all zeroes is a valid value.
+        let mut result: T = unsafe { std::mem::zeroed() };
+        // SAFETY: valid interface pointer; the result buffer matches the
+        // expected callback struct layout and size.
+        unsafe {
+            if !is_completed(self.utils, call, &mut failed) {
+                return CallStatus::Pending;
+            }
+            let received = get_result(
+                self.utils,
+                call,
+                (&mut result as *mut T).cast(),
+                size_of::<T>() as i32,
+                callback_id,
+                &mut failed,
+            );
+            if !received || failed {
+                return CallStatus::Failed;
+            }
+        }
+        CallStatus::Done(result)
+    }
+
+    /// Checks whether the leaderboard lookup finished, storing the handle if so.
+    fn poll_find_leaderboard(&mut self) {
+        let Some(call) = self.find_leaderboard else {
+            return;
+        };
+        let found =
+            match self.call_result::<LeaderboardFindResult>(call, LEADERBOARD_FIND_RESULT_ID) {
+                CallStatus::Pending => return,
+                CallStatus::Failed => None,
+                CallStatus::Done(result) => Some(result).filter(|result| result.found != 0),
+            };
+
+        self.find_leaderboard = None;
+        match found {
+            Some(result) => self.leaderboard = Some(result.leaderboard),
+            None => warn!("Steam leaderboard \"{LEADERBOARD_NAME}\" not found"),
+        }
+    }
+
+    /// Requests the top leaderboard entries, to download their ghosts.
+    fn request_ghosts(&mut self, leaderboard: u64) {
+        type DownloadEntries = unsafe extern "C" fn(*mut c_void, u64, i32, i32, i32) -> ApiCall;
+        if self.remote_storage.is_none() {
+            return;
+        }
+        if let Some(download) = self
+            .function::<DownloadEntries>(b"SteamAPI_ISteamUserStats_DownloadLeaderboardEntries\0")
+        {
+            // SAFETY: valid interface pointer; ranks are 1-based and inclusive.
+            let call = unsafe {
+                download(
+                    self.user_stats,
+                    leaderboard,
+                    DATA_REQUEST_GLOBAL,
+                    1,
+                    GHOST_ENTRY_COUNT,
+                )
+            };
+            self.track(call, PendingCall::EntriesDownload);
+        }
+    }
+
+    /// Writes a replay to Remote Storage and shares it, to attach it to the
+    /// player's leaderboard entry.
+    fn share_replay(&mut self, leaderboard: u64, replay: &Replay) {
+        type FileWrite =
+            unsafe extern "C" fn(*mut c_void, *const c_char, *const c_void, i32) -> bool;
+        type FileShare = unsafe extern "C" fn(*mut c_void, *const c_char) -> ApiCall;
+
+        let (Some(remote_storage), Ok(name)) =
+            (self.remote_storage, CString::new(REPLAY_FILE_NAME))
+        else {
+            return;
+        };
+        let write = self.function::<FileWrite>(b"SteamAPI_ISteamRemoteStorage_FileWrite\0");
+        let share = self.function::<FileShare>(b"SteamAPI_ISteamRemoteStorage_FileShare\0");
+        let (Some(write), Some(share)) = (write, share) else {
+            return;
+        };
+
+        let bytes = replay.to_bytes();
+        // SAFETY: valid interface pointer, nul-terminated name, and a buffer
+        // of the given length.
+        let call = unsafe {
+            if !write(
+                remote_storage,
+                name.as_ptr(),
+                bytes.as_ptr().cast(),
+                bytes.len() as i32,
+            ) {
+                warn!("Steam rejected the replay file");
+                return;
+            }
+            share(remote_storage, name.as_ptr())
+        };
+        self.track(call, PendingCall::ReplayShare { leaderboard });
+    }
+
+    /// Attaches a shared replay to the player's leaderboard entry.
+    fn attach_replay(&self, leaderboard: u64, file: u64) {
+        type AttachUgc = unsafe extern "C" fn(*mut c_void, u64, u64) -> ApiCall;
+        if let Some(attach) =
+            self.function::<AttachUgc>(b"SteamAPI_ISteamUserStats_AttachLeaderboardUGC\0")
+        {
+            // SAFETY: valid interface pointer and handles returned by Steam.
+            unsafe { attach(self.user_stats, leaderboard, file) };
+        }
+    }
+
+    /// Starts downloading the replay of each downloaded entry that has one.
+    fn download_ghosts(&mut self, downloaded: LeaderboardScoresDownloaded) {
+        type GetEntry = unsafe extern "C" fn(
+            *mut c_void,
+            u64,
+            i32,
+            *mut LeaderboardEntry,
+            *mut i32,
+            i32,
+        ) -> bool;
+        type UgcDownload = unsafe extern "C" fn(*mut c_void, u64, u32) -> ApiCall;
+
+        let Some(remote_storage) = self.remote_storage else {
+            return;
+        };
+        let get_entry =
+            self.function::<GetEntry>(b"SteamAPI_ISteamUserStats_GetDownloadedLeaderboardEntry\0");
+        let ugc_download =
+            self.function::<UgcDownload>(b"SteamAPI_ISteamRemoteStorage_UGCDownload\0");
+        let (Some(get_entry), Some(ugc_download)) = (get_entry, ugc_download) else {
+            return;
+        };
+
+        let mut downloads = Vec::new();
+        for index in 0..downloaded.entry_count {
+            // SAFETY: entries are plain integers, for which all zeroes is valid.
+            let mut entry: LeaderboardEntry = unsafe { std::mem::zeroed() };
+            // SAFETY: valid interface pointer and entries handle, and an
+            // entry buffer; no score details are requested.
+            unsafe {
+                if !get_entry(
+                    self.user_stats,
+                    downloaded.entries,
+                    index,
+                    &mut entry,
+                    std::ptr::null_mut(),
+                    0,
+                ) || entry.ugc == INVALID_UGC_HANDLE
+                {
+                    continue;
+                }
+                let call = ugc_download(remote_storage, entry.ugc, 0);
+                downloads.push((
+                    call,
+                    PendingCall::GhostDownload {
+                        rank: entry.global_rank,
+                        score: entry.score,
+                    },
+                ));
+            }
+        }
+
+        for (call, pending) in downloads {
+            self.track(call, pending);
+        }
+    }
+
+    /// Reads a downloaded replay.
+    fn read_replay(&self, downloaded: DownloadUgcResult) -> Option<Replay> {
+        type UgcRead = unsafe extern "C" fn(*mut c_void, u64, *mut c_void, i32, u32, i32) -> i32;
+
+        let remote_storage = self.remote_storage?;
+        let read = self.function::<UgcRead>(b"SteamAPI_ISteamRemoteStorage_UGCRead\0")?;
+        let mut bytes = vec![0u8; downloaded.size.max(0) as usize];
+        // SAFETY: valid interface pointer and a buffer of the given length.
+        let read = unsafe {
+            read(
+                remote_storage,
+                downloaded.file,
+                bytes.as_mut_ptr().cast(),
+                bytes.len() as i32,
+                0,
+                UGC_READ_CLOSE,
+            )
+        };
+        bytes.truncate(read.max(0) as usize);
+        Replay::from_bytes(&bytes)
+    }
+
+    /// Advances the requests whose results arrived.
+    fn poll_calls(&mut self, ghosts: &mut GhostLibrary) {
+        for (call, pending) in std::mem::take(&mut self.calls) {
+            match pending {
+                PendingCall::ScoreUpload(replay) => {
+                    match self.call_result::<LeaderboardScoreUploaded>(
+                        call,
+                        LEADERBOARD_SCORE_UPLOADED_ID,
+                    ) {
+                        CallStatus::Pending => {
+                            self.calls.push((call, PendingCall::ScoreUpload(replay)))
+                        }
+                        CallStatus::Done(uploaded)
+                            if uploaded.success != 0 && uploaded.score_changed != 0 =>
+                        {
+                            // The run is the player's new entry: share its replay
+                            self.share_replay(uploaded.leaderboard, &replay);
+                        }
+                        CallStatus::Done(_) => {}
+                        CallStatus::Failed => warn!("Steam score upload failed"),
+                    }
+                }
+                PendingCall::ReplayShare { leaderboard } => {
+                    match self.call_result::<FileShareResult>(call, FILE_SHARE_RESULT_ID) {
+                        CallStatus::Pending => self
+                            .calls
+                            .push((call, PendingCall::ReplayShare { leaderboard })),
+                        CallStatus::Done(shared) if shared.result == RESULT_OK => {
+                            self.attach_replay(leaderboard, shared.file);
+                        }
+                        _ => warn!("Steam replay sharing failed"),
+                    }
+                }
+                PendingCall::EntriesDownload => {
+                    match self.call_result::<LeaderboardScoresDownloaded>(
+                        call,
+                        LEADERBOARD_SCORES_DOWNLOADED_ID,
+                    ) {
+                        CallStatus::Pending => {
+                            self.calls.push((call, PendingCall::EntriesDownload))
+                        }
+                        CallStatus::Done(downloaded) => self.download_ghosts(downloaded),
+                        CallStatus::Failed => warn!("Steam leaderboard download failed"),
+                    }
+                }
+                PendingCall::GhostDownload { rank, score } => {
+                    match self.call_result::<DownloadUgcResult>(call, DOWNLOAD_UGC_RESULT_ID) {
+                        CallStatus::Pending => self
+                            .calls
+                            .push((call, PendingCall::GhostDownload { rank, score })),
+                        CallStatus::Done(downloaded) if downloaded.result == RESULT_OK => {
+                            match self.read_replay(downloaded) {
+                                Some(replay) => ghosts.add(GhostEntry {
+                                    label: format!("#{rank} ({score})"),
+                                    replay,
+                                }),
+                                None => warn!("Ghost of leaderboard rank {rank} is not a replay"),
+                            }
+                        }
+                        _ => warn!("Ghost download of leaderboard rank {rank} failed"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Steam {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.function::<unsafe extern "C" fn()>(b"SteamAPI_Shutdown\0") {
+            // SAFETY: the API was initialized in `connect`.
+            unsafe { shutdown() };
+        }
+    }
+}
+
+/// Resolves a versioned interface accessor, trying each known version in turn.
+///
+/// # Safety
+///
+/// The Steam API must have been initialized.
+unsafe fn interface(library: &Library, accessors: &[&[u8]]) -> Result<*mut c_void, String> {
+    for accessor in accessors {
+        // SAFETY: interface accessors take no arguments and return a pointer.
+        if let Ok(get) = unsafe { library.get::<unsafe extern "C" fn() -> *mut c_void>(accessor) } {
+            // SAFETY: the API is initialized (guaranteed by the caller).
+            let pointer = unsafe { get() };
+            if !pointer.is_null() {
+                return Ok(pointer);
+            }
+        }
+    }
+    Err("unsupported Steamworks SDK version".to_string())
+}
+
+/// Pumps Steam callbacks once per frame.
+fn run_steam_callbacks(steam: Res<Steam>) {
+    if let Some(run) = steam.function::<unsafe extern "C" fn()>(b"SteamAPI_RunCallbacks\0") {
+        // SAFETY: the API was initialized in `connect`.
+        unsafe { run() };
+    }
+}
+
+/// Mirrors the achievements already in the save to Steam once connected, so
+/// those unlocked before Steam was available aren't missing there.
+fn sync_saved_achievements(steam: Res<Steam>, save: Res<SaveData>) {
+    if !save.achievements.is_empty() {
+        steam.unlock(
+            save.achievements
+                .iter()
+                .map(|achievement| achievement.api_name()),
+        );
+    }
+}
+
+/// Mirrors newly unlocked achievements to Steam.
+fn sync_achievements(steam: Res<Steam>, mut unlocked: MessageReader<AchievementUnlocked>) {
+    if !unlocked.is_empty() {
+        steam.unlock(unlocked.read().map(|event| event.achievement.api_name()));
+    }
+}
+
+/// Resolves the leaderboard handle, flushes runs queued before it was ready,
+/// and starts downloading the top ghosts.
+fn poll_leaderboard(mut steam: ResMut<Steam>) {
+    if steam.leaderboard.is_some() || steam.find_leaderboard.is_none() {
+        return;
+    }
+
+    steam.poll_find_leaderboard();
+    if let Some(leaderboard) = steam.leaderboard {
+        for replay in std::mem::take(&mut steam.pending_scores) {
+            steam.upload(leaderboard, replay);
+        }
+        steam.request_ghosts(leaderboard);
+    }
+}
+
+/// Uploads the final score of each run along with its replay.
+fn upload_scores(mut steam: ResMut<Steam>, mut recorded: MessageReader<ReplayRecorded>) {
+    for event in recorded.read() {
+        match steam.leaderboard {
+            Some(leaderboard) => steam.upload(leaderboard, event.replay.clone()),
+            // Still looking the leaderboard up: upload once it resolves
+            None if steam.find_leaderboard.is_some() => {
+                steam.pending_scores.push(event.replay.clone());
+            }
+            None => {}
+        }
+    }
+}
+
+/// Advances pending leaderboard and replay requests, adding downloaded ghosts.
+fn poll_steam_calls(mut steam: ResMut<Steam>, mut ghosts: ResMut<GhostLibrary>) {
+    if !steam.calls.is_empty() {
+        steam.poll_calls(&mut ghosts);
+    }
+}
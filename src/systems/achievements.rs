@@ -0,0 +1,108 @@
+//! Achievement tracking systems.
+//!
+//! This module unlocks achievements in response to gameplay events.
+
+use bevy::prelude::*;
+
+use crate::achievements::Achievement;
+use crate::components::Toast;
+use crate::constants::{GAME_HEIGHT, TOAST_DURATION, TOAST_FADE, TOAST_SPACING};
+use crate::letters::WordCompleted;
+use crate::replay::ReplayRecorder;
+use crate::resources::{AchievementUnlocked, DeathEvent, ScoreEvent};
+use crate::save::SaveData;
+
+/// Unlocks score, death and letter bonus achievements and persists new
+/// unlocks.
+///
+/// Runs helped by a debug panel cheat unlock nothing.
+pub fn unlock_achievements(
+    mut score_events: MessageReader<ScoreEvent>,
+    mut death_events: MessageReader<DeathEvent>,
+    mut word_events: MessageReader<WordCompleted>,
+    mut unlocked_events: MessageWriter<AchievementUnlocked>,
+    recorder: Res<ReplayRecorder>,
+    mut save: ResMut<SaveData>,
+) {
+    if recorder.cheated {
+        score_events.clear();
+        death_events.clear();
+        word_events.clear();
+        return;
+    }
+    let reached = score_events
+        .read()
+        .flat_map(|event| Achievement::for_score(event.score))
+        .chain(
+            death_events
+                .read()
+                .filter_map(|event| Achievement::for_death(event.cause)),
+        )
+        .chain(word_events.read().map(|_| Achievement::Flappy))
+        .collect::<Vec<_>>();
+
+    let mut changed = false;
+    for achievement in reached {
+        if save.achievements.insert(achievement) {
+            info!("Achievement unlocked: {}", achievement.title());
+            unlocked_events.write(AchievementUnlocked { achievement });
+            changed = true;
+        }
+    }
+
+    if changed {
+        save.store();
+    }
+}
+
+/// Shows a toast for each newly unlocked achievement.
+pub fn show_achievement_toasts(
+    mut commands: Commands,
+    mut unlocked_events: MessageReader<AchievementUnlocked>,
+    toast_query: Query<(), With<Toast>>,
+) {
+    // Stack new toasts above any that are still visible
+    let first_slot = toast_query.iter().count();
+
+    for (slot, event) in (first_slot..).zip(unlocked_events.read()) {
+        spawn_toast(
+            &mut commands,
+            format!("Achievement unlocked: {}", event.achievement.title()),
+            slot,
+        );
+    }
+}
+
+/// Spawns a toast near the bottom of the screen, `slot` rows above the first.
+pub fn spawn_toast(commands: &mut Commands, text: String, slot: usize) {
+    let y = -GAME_HEIGHT / 2.0 + 90.0 + slot as f32 * TOAST_SPACING;
+    commands.spawn((
+        Text2d::new(text),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.9, 0.3)),
+        Transform::from_xyz(0.0, y, 3.0),
+        Toast { timer: 0.0 },
+    ));
+}
+
+/// Fades out and removes toasts.
+pub fn update_toasts(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Toast, &mut TextColor)>,
+) {
+    for (entity, mut toast, mut color) in query.iter_mut() {
+        toast.timer += time.delta_secs();
+
+        if toast.timer >= TOAST_DURATION {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let remaining = TOAST_DURATION - toast.timer;
+        color.0.set_alpha((remaining / TOAST_FADE).min(1.0));
+    }
+}
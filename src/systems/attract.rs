@@ -0,0 +1,207 @@
+//! Attract mode systems.
+//!
+//! After `ATTRACT_DELAY` seconds without input on the menu, a faded demo run
+//! flown by the autopilot plays behind the menu text, like an arcade attract
+//! loop. Any input stops it instantly. The demo uses its own entities, so it
+//! never touches the score, progress, or the pipe sequence.
+
+use bevy::prelude::*;
+
+use crate::bot;
+use crate::components::{AttractDemo, Bird, DemoBird, DemoPipe};
+use crate::config::GameConfig;
+use crate::constants::{ATTRACT_ALPHA, ATTRACT_DELAY, BIRD_SIZE, GROUND_HEIGHT, PIPE_WIDTH};
+use crate::resources::{AttractMode, GameViewport};
+use crate::rng::GameRng;
+use crate::systems::pipes::pipe_sprite;
+use crate::theme::{Theme, color};
+
+/// Demo entities sit behind the ground and the menu bird.
+const DEMO_PIPE_Z: f32 = -0.4;
+const DEMO_BIRD_Z: f32 = -0.3;
+
+/// Starts the demo after a while without input and stops it on any input.
+#[allow(clippy::too_many_arguments)]
+pub fn update_attract_mode(
+    mut commands: Commands,
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    mut attract: ResMut<AttractMode>,
+    theme: Res<Theme>,
+    demo_query: Query<Entity, With<AttractDemo>>,
+    mut bird_query: Query<&mut Visibility, With<Bird>>,
+) {
+    let input = keyboard_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+        || touches.any_just_pressed();
+
+    if input {
+        attract.idle = 0.0;
+        if !demo_query.is_empty() {
+            stop_demo(&mut commands, &demo_query, &mut bird_query);
+        }
+        return;
+    }
+
+    attract.idle += time.delta_secs();
+    if attract.idle < ATTRACT_DELAY || !demo_query.is_empty() {
+        return;
+    }
+
+    // The demo bird takes over from the menu bird
+    for mut visibility in bird_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+    attract.spawn_timer = 0.0;
+    commands.spawn((
+        Sprite {
+            color: color(theme.bird_color).with_alpha(ATTRACT_ALPHA),
+            custom_size: Some(Vec2::splat(BIRD_SIZE)),
+            ..default()
+        },
+        Transform::from_xyz(-50.0, 0.0, DEMO_BIRD_Z),
+        DemoBird::default(),
+        AttractDemo,
+    ));
+}
+
+/// Flies the demo: spawns and scrolls its pipes and lets the bot fly the bird.
+///
+/// A crash ends the demo; it starts over on the next frame.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn run_attract_demo(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut attract: ResMut<AttractMode>,
+    viewport: Res<GameViewport>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    mut rng: ResMut<GameRng>,
+    mut bird_query: Query<(&mut DemoBird, &mut Transform)>,
+    mut pipe_query: Query<
+        (Entity, &mut Transform, Option<&DemoPipe>),
+        (With<AttractDemo>, Without<DemoBird>),
+    >,
+    demo_query: Query<Entity, With<AttractDemo>>,
+    mut menu_bird_query: Query<&mut Visibility, With<Bird>>,
+) {
+    let Ok((mut bird, mut bird_transform)) = bird_query.single_mut() else {
+        return;
+    };
+    let delta = time.delta_secs();
+    let bottom = -viewport.half_height() + GROUND_HEIGHT;
+    let top = viewport.half_height();
+
+    attract.spawn_timer -= delta;
+    if attract.spawn_timer <= 0.0 {
+        attract.spawn_timer = config.pipes.spawn_interval();
+        spawn_demo_pipes(&mut commands, &viewport, &config, &theme, &mut rng);
+    }
+
+    // Aim for the gap of the nearest pair the bird hasn't cleared yet
+    let bird_x = bird_transform.translation.x;
+    let mut target_y = (bottom + top) / 2.0;
+    let mut nearest = f32::INFINITY;
+    for (entity, mut transform, pipe) in pipe_query.iter_mut() {
+        transform.translation.x -= config.pipes.scroll_speed * delta;
+        let x = transform.translation.x;
+        if x < -viewport.half_width() - PIPE_WIDTH {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if let Some(pipe) = pipe
+            && x + PIPE_WIDTH / 2.0 > bird_x - BIRD_SIZE / 2.0
+            && x < nearest
+        {
+            nearest = x;
+            target_y = pipe.gap_y;
+        }
+    }
+
+    let physics = &config.physics;
+    if bot::should_flap(
+        bird_transform.translation.y,
+        bird.velocity,
+        target_y,
+        physics,
+    ) {
+        bird.velocity = physics.flap_strength;
+    }
+    bird.velocity += physics.gravity * delta;
+    bird_transform.translation.y += bird.velocity * delta;
+    bird_transform.rotation = Quat::from_rotation_z(
+        (bird.velocity / physics.flap_strength * physics.max_tilt_up)
+            .clamp(physics.max_tilt_down, physics.max_tilt_up),
+    );
+
+    let y = bird_transform.translation.y;
+    if y - BIRD_SIZE / 2.0 < bottom || y + BIRD_SIZE / 2.0 > top {
+        stop_demo(&mut commands, &demo_query, &mut menu_bird_query);
+    }
+}
+
+/// Removes the demo when leaving the menu.
+pub fn despawn_attract_demo(
+    mut commands: Commands,
+    mut attract: ResMut<AttractMode>,
+    demo_query: Query<Entity, With<AttractDemo>>,
+    mut bird_query: Query<&mut Visibility, With<Bird>>,
+) {
+    attract.idle = 0.0;
+    stop_demo(&mut commands, &demo_query, &mut bird_query);
+}
+
+/// Despawns the demo and shows the menu bird again.
+fn stop_demo(
+    commands: &mut Commands,
+    demo_query: &Query<Entity, With<AttractDemo>>,
+    bird_query: &mut Query<&mut Visibility, With<Bird>>,
+) {
+    for entity in demo_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for mut visibility in bird_query.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Spawns a faded, easy pipe pair just off the right edge.
+fn spawn_demo_pipes(
+    commands: &mut Commands,
+    viewport: &GameViewport,
+    config: &GameConfig,
+    theme: &Theme,
+    rng: &mut GameRng,
+) {
+    let bottom = -viewport.half_height() + GROUND_HEIGHT;
+    let top = viewport.half_height();
+    let gap_size = config.pipes.gap_start_max;
+    let margin = gap_size / 2.0 + 40.0;
+    let gap_y = if top - margin > bottom + margin {
+        // The demo draws from the effects stream so the pipes stream is untouched
+        rng.effects.range(bottom + margin, top - margin)
+    } else {
+        (bottom + top) / 2.0
+    };
+
+    let x = viewport.half_width() + PIPE_WIDTH / 2.0;
+    let segments = [
+        (bottom, gap_y - gap_size / 2.0, true),
+        (gap_y + gap_size / 2.0, top, false),
+    ];
+    for (segment_bottom, segment_top, lower) in segments {
+        let height = segment_top - segment_bottom;
+        let mut sprite = pipe_sprite(Vec2::new(PIPE_WIDTH, height), theme, 0);
+        sprite.color.set_alpha(ATTRACT_ALPHA);
+        let mut pipe = commands.spawn((
+            sprite,
+            Transform::from_xyz(x, segment_bottom + height / 2.0, DEMO_PIPE_Z),
+            AttractDemo,
+        ));
+        if lower {
+            pipe.insert(DemoPipe { gap_y });
+        }
+    }
+}
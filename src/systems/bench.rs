@@ -0,0 +1,124 @@
+//! Benchmark systems.
+//!
+//! While the `Bench` resource exists, the autopilot plays through the real
+//! input path: it presses the flap key to start and restart runs and whenever the
+//! bird should flap towards the next gap. The bird stays invincible and
+//! shatters the pipes it hits, so runs last and keep spawning particles.
+//! After `Bench::duration` real seconds the frame-time report is printed and
+//! the app exits.
+
+use bevy::prelude::*;
+
+use crate::bench::Bench;
+use crate::bindings::InputBindings;
+use crate::bot;
+use crate::components::{Bird, Particle, Pipe, PipePair};
+use crate::config::GameConfig;
+use crate::constants::{BENCH_DURATION, BENCH_SPEED, BENCH_WARMUP, BIRD_SIZE};
+use crate::resources::Invincibility;
+use crate::settings::{ParticleQuality, Settings};
+use crate::states::{GameState, PauseState};
+
+/// Speeds up game time and forces the settings that spawn the most particles.
+///
+/// The settings are changed for this session only, never stored.
+pub fn start_bench(mut time: ResMut<Time<Virtual>>, mut settings: ResMut<Settings>) {
+    time.set_relative_speed(BENCH_SPEED);
+    settings.particle_quality = ParticleQuality::High;
+    settings.battery_saver = false;
+    settings.one_switch = false;
+    info!("Benchmarking at {BENCH_SPEED}x speed");
+}
+
+/// Applies the benchmark overrides each time the configuration is (re)loaded.
+pub fn apply_bench_config(mut config: ResMut<GameConfig>) {
+    // Bypass change detection so the overrides aren't applied twice
+    Bench::apply(config.bypass_change_detection());
+}
+
+/// Presses the flap key for the autopilot: to start and restart runs, and to flap
+/// towards the gap of the next pipe pair.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn drive_bench_input(
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    bindings: Res<InputBindings>,
+    state: Res<State<GameState>>,
+    pause: Option<Res<State<PauseState>>>,
+    config: Res<GameConfig>,
+    mut invincibility: ResMut<Invincibility>,
+    bird_query: Query<(&Bird, &Transform)>,
+    pair_query: Query<(&Transform, &PipePair), Without<Bird>>,
+) {
+    let flap_key = bindings.flap.key.0;
+    keyboard_input.release(flap_key);
+    keyboard_input.release(KeyCode::Escape);
+
+    let press = match state.get() {
+        GameState::Menu | GameState::GameOver => true,
+        GameState::Playing => match pause.as_deref().map(State::get) {
+            // Decline revives straight away
+            Some(PauseState::Reviving) => {
+                keyboard_input.press(KeyCode::Escape);
+                false
+            }
+            Some(PauseState::Paused) => false,
+            _ => {
+                if !invincibility.is_active() {
+                    invincibility.grant(BENCH_DURATION * BENCH_SPEED, true);
+                }
+                bird_query.iter().any(|(bird, transform)| {
+                    let bird_x = transform.translation.x;
+                    let target_y = pair_query
+                        .iter()
+                        .filter(|(pair_transform, pair)| {
+                            !pair.scored
+                                && pair_transform.translation.x + pair.width / 2.0
+                                    > bird_x - BIRD_SIZE / 2.0
+                        })
+                        .min_by(|(a, _), (b, _)| a.translation.x.total_cmp(&b.translation.x))
+                        .map_or(0.0, |(_, pair)| pair.gap_y);
+                    bot::should_flap(
+                        transform.translation.y,
+                        bird.velocity,
+                        target_y,
+                        &config.physics,
+                    )
+                })
+            }
+        },
+        _ => false,
+    };
+    if press {
+        keyboard_input.press(flap_key);
+    }
+}
+
+/// Records the frame time and, once the benchmark is over, prints the report
+/// and exits.
+pub fn record_bench_frame(
+    time: Res<Time<Real>>,
+    mut bench: ResMut<Bench>,
+    pipe_query: Query<Ref<Pipe>>,
+    particle_query: Query<(), With<Particle>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    let delta = time.delta_secs();
+    // Frames during the warm-up include startup work and asset loading
+    if time.elapsed_secs() - delta > BENCH_WARMUP {
+        bench.frame_times.push(delta * 1000.0);
+        bench.elapsed += delta;
+    }
+    bench.pipes_spawned += pipe_query.iter().filter(Ref::is_added).count();
+    bench.peak_pipes = bench.peak_pipes.max(pipe_query.iter().count());
+    bench.peak_particles = bench.peak_particles.max(particle_query.iter().count());
+
+    if bench.elapsed < bench.duration {
+        return;
+    }
+    println!("Benchmark: {}", bench.report());
+    println!(
+        "Benchmark: {} pipes spawned, peak {} pipes and {} particles",
+        bench.pipes_spawned, bench.peak_pipes, bench.peak_particles
+    );
+    exit.write(AppExit::Success);
+}
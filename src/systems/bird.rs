@@ -4,66 +4,310 @@
 
 use bevy::prelude::*;
 
-use crate::components::Bird;
-use crate::constants::{FLAP_STRENGTH, GRAVITY, MAX_TILT_DOWN, MAX_TILT_UP, TILT_SPEED};
-use crate::resources::FlapEvent;
+use crate::components::{Bird, BirdSquashStretch, WingRecharge};
+use crate::config::{GameConfig, PhysicsConfig};
+use crate::constants::{
+    AUTO_FLAP_RHYTHM, FLAP_BUFFER_TIME, FLAP_COOLDOWN, GLIDE_GRAVITY_SCALE, TUTORIAL_GRAVITY_SCALE,
+    WING_RECHARGE_OFFSET, WING_RECHARGE_SIZE,
+};
+use crate::input::ActionInput;
+use crate::resources::{AutoFlap, DebugTools, FlapBuffer, FlapCooldown, FlapEvent, RunStats};
+use crate::settings::Settings;
+use crate::states::{GameState, TutorialStep};
 
-/// Handles bird flapping when space is pressed.
+/// Remembers flaps pressed outside a run, so the press that starts one (or
+/// one landing a frame early) isn't lost.
+pub fn buffer_flap_input(
+    time: Res<Time<Real>>,
+    actions: ActionInput,
+    mut buffer: ResMut<FlapBuffer>,
+) {
+    if actions.flap_just_pressed() {
+        buffer.pressed_at = Some(time.elapsed_secs());
+    }
+}
+
+/// Handles bird flapping when a flap input is pressed.
 ///
 /// Sets the bird's vertical velocity to the flap strength, causing it to rise.
-/// Also sends a FlapEvent for visual effects.
+/// Also triggers a FlapEvent for visual effects. A press buffered just before
+/// the run started counts as its first flap. With the flap cooldown on,
+/// presses are ignored until the wings have recharged.
+#[allow(clippy::too_many_arguments)]
 pub fn bird_flap(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    touches: Res<Touches>,
+    time: Res<Time<Real>>,
+    game_time: Res<Time>,
+    actions: ActionInput,
+    mut buffer: ResMut<FlapBuffer>,
+    mut cooldown: ResMut<FlapCooldown>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    mut stats: ResMut<RunStats>,
     mut query: Query<(&mut Bird, &Transform)>,
-    mut flap_events: MessageWriter<FlapEvent>,
+    mut commands: Commands,
 ) {
-    let should_flap = keyboard_input.just_pressed(KeyCode::Space)
-        || mouse_input.just_pressed(MouseButton::Left)
-        || touches.any_just_pressed();
-
-    if should_flap {
-        for (mut bird, transform) in query.iter_mut() {
-            bird.velocity = FLAP_STRENGTH;
-            // Send flap event for visual effects
-            flap_events.write(FlapEvent {
-                position: transform.translation,
-            });
+    let buffered = buffer.take(time.elapsed_secs(), FLAP_BUFFER_TIME);
+    let should_flap = actions.flap_just_pressed() || buffered;
+    let recharged = cooldown.tick(game_time.delta_secs()) || !settings.flap_cooldown;
+
+    if should_flap && recharged {
+        flap(&config.physics, &mut stats, &mut query, &mut commands);
+        if settings.flap_cooldown {
+            cooldown.remaining = FLAP_COOLDOWN;
         }
     }
 }
 
+/// Recharges the wings at the start of each run.
+pub fn reset_flap_cooldown(mut cooldown: ResMut<FlapCooldown>) {
+    *cooldown = FlapCooldown::default();
+}
+
+/// Shows the wing recharge bar below the bird while the flap cooldown runs,
+/// filling up as the wings recharge.
+#[allow(clippy::type_complexity)]
+pub fn update_wing_recharge(
+    state: Res<State<GameState>>,
+    settings: Res<Settings>,
+    cooldown: Res<FlapCooldown>,
+    bird_query: Query<&Transform, With<Bird>>,
+    mut bar_query: Query<(&mut Transform, &mut Visibility), (With<WingRecharge>, Without<Bird>)>,
+) {
+    let recharging =
+        settings.flap_cooldown && cooldown.remaining > 0.0 && *state.get() == GameState::Playing;
+    let Ok(bird_transform) = bird_query.single() else {
+        return;
+    };
+    for (mut transform, mut visibility) in bar_query.iter_mut() {
+        visibility.set_if_neq(if recharging {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        let charge = cooldown.charge(FLAP_COOLDOWN);
+        // Fill from the left edge of the bar
+        transform.translation.x =
+            bird_transform.translation.x - WING_RECHARGE_SIZE.0 * (1.0 - charge) / 2.0;
+        transform.translation.y = bird_transform.translation.y - WING_RECHARGE_OFFSET;
+        transform.scale.x = charge;
+    }
+}
+
+/// One-switch flap controller, used instead of `bird_flap` when enabled in
+/// the settings.
+///
+/// The bird flaps on its own on a steady rhythm that slowly gains height,
+/// and the single input skips the next flap to let it sink.
+pub fn bird_auto_flap(
+    time: Res<Time>,
+    actions: ActionInput,
+    config: Res<GameConfig>,
+    mut auto_flap: ResMut<AutoFlap>,
+    mut stats: ResMut<RunStats>,
+    mut query: Query<(&mut Bird, &Transform)>,
+    mut commands: Commands,
+) {
+    if actions.flap_just_pressed() {
+        auto_flap.skip = true;
+    }
+
+    auto_flap.timer -= time.delta_secs();
+    if auto_flap.timer > 0.0 {
+        return;
+    }
+    auto_flap.timer += auto_flap_interval(&config.physics);
+
+    if auto_flap.skip {
+        auto_flap.skip = false;
+    } else {
+        flap(&config.physics, &mut stats, &mut query, &mut commands);
+    }
+}
+
+/// Restarts the one-switch rhythm with a flap at the start of each run.
+pub fn reset_auto_flap(mut auto_flap: ResMut<AutoFlap>) {
+    *auto_flap = AutoFlap::default();
+}
+
+/// Returns the time between one-switch flaps.
+///
+/// A flap takes `2 * flap_strength / -gravity` seconds to fall back to the
+/// height it started from; flapping slightly sooner makes the bird climb.
+fn auto_flap_interval(physics: &PhysicsConfig) -> f32 {
+    2.0 * physics.flap_strength / (-physics.gravity).max(f32::EPSILON) * AUTO_FLAP_RHYTHM
+}
+
+/// Makes the bird rise, counts the flap, and triggers a FlapEvent for visual
+/// effects.
+fn flap(
+    physics: &PhysicsConfig,
+    stats: &mut RunStats,
+    query: &mut Query<(&mut Bird, &Transform)>,
+    commands: &mut Commands,
+) {
+    for (mut bird, transform) in query.iter_mut() {
+        // Inside a gravity-flip zone, flapping pushes the bird down
+        bird.velocity = physics.flap_strength * bird.gravity_direction;
+        stats.flaps += 1;
+        commands.trigger(FlapEvent {
+            position: transform.translation,
+        });
+    }
+}
+
 /// Applies gravity and updates bird position.
 ///
-/// This system runs every frame to simulate physics on the bird.
-pub fn bird_physics(time: Res<Time>, mut query: Query<(&mut Bird, &mut Transform)>) {
+/// This system runs every frame to simulate physics on the bird, and times
+/// the run. In glide mode, holding the flap input after a flap weakens
+/// gravity until it is released (one-switch flapping never glides). A
+/// teleport requested from the debug panel lands here. Gravity is slowed
+/// while the tutorial asks for the first flaps.
+#[allow(clippy::too_many_arguments)]
+pub fn bird_physics(
+    time: Res<Time>,
+    actions: ActionInput,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    mut stats: ResMut<RunStats>,
+    mut query: Query<(&mut Bird, &mut Transform)>,
+    debug: Option<ResMut<DebugTools>>,
+    tutorial: Option<Res<State<TutorialStep>>>,
+) {
+    stats.duration += time.delta_secs();
+    let gliding = settings.glide_mode && !settings.one_switch && actions.flap_pressed();
+    let mut gravity = glide_gravity(config.physics.gravity, gliding);
+    if tutorial.is_some_and(|step| *step.get() == TutorialStep::Flap) {
+        gravity *= TUTORIAL_GRAVITY_SCALE;
+    }
+    let teleport = debug.and_then(|mut debug| debug.teleport.take());
     for (mut bird, mut transform) in query.iter_mut() {
-        bird.velocity += GRAVITY * time.delta_secs();
+        if let Some(y) = teleport {
+            transform.translation.y = y;
+            bird.velocity = 0.0;
+        }
+        bird.velocity += gravity * bird.gravity_direction * time.delta_secs();
         transform.translation.y += bird.velocity * time.delta_secs();
     }
 }
 
+/// Returns the gravity pulling the bird, weakened while it glides.
+fn glide_gravity(gravity: f32, gliding: bool) -> f32 {
+    if gliding {
+        gravity * GLIDE_GRAVITY_SCALE
+    } else {
+        gravity
+    }
+}
+
+/// Sizes the bird to the configured scale (hitbox included), unless the
+/// flap squash is animating it (see `update_bird_squash`).
+pub fn scale_bird(
+    config: Res<GameConfig>,
+    mut query: Query<&mut Transform, (With<Bird>, Without<BirdSquashStretch>)>,
+) {
+    let scale = Vec3::new(config.physics.bird_scale, config.physics.bird_scale, 1.0);
+    for mut transform in query.iter_mut() {
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+    }
+}
+
+/// Hides the bird for the run when the configuration asks for it, leaving
+/// only its trail to follow.
+pub fn hide_bird(config: Res<GameConfig>, mut query: Query<&mut Visibility, With<Bird>>) {
+    if !config.effects.hide_bird {
+        return;
+    }
+    for mut visibility in query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Shows the bird again when the run ends, so the crash can be seen.
+pub fn show_bird(mut query: Query<&mut Visibility, With<Bird>>) {
+    for mut visibility in query.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
 /// Tilts the bird based on its velocity.
 ///
 /// The bird tilts upward when rising (after a flap) and downward when falling,
 /// creating a natural-looking flight animation.
-pub fn bird_tilt(time: Res<Time>, mut query: Query<(&Bird, &mut Transform)>) {
+pub fn bird_tilt(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<(&Bird, &mut Transform)>,
+) {
+    let physics = &config.physics;
     for (bird, mut transform) in query.iter_mut() {
         // Calculate target tilt based on velocity
         let target_tilt = if bird.velocity > 0.0 {
             // Going up - tilt up
-            (bird.velocity / FLAP_STRENGTH) * MAX_TILT_UP
+            (bird.velocity / physics.flap_strength) * physics.max_tilt_up
         } else {
             // Falling - tilt down proportionally
-            (bird.velocity / 500.0).clamp(MAX_TILT_DOWN, 0.0)
+            (bird.velocity / 500.0).clamp(physics.max_tilt_down, 0.0)
         };
 
         // Smoothly interpolate to target tilt
         let current_rotation = transform.rotation.to_euler(EulerRot::XYZ).2;
-        let new_rotation =
-            current_rotation + (target_tilt - current_rotation) * TILT_SPEED * time.delta_secs();
+        let new_rotation = current_rotation
+            + (target_tilt - current_rotation) * physics.tilt_speed * time.delta_secs();
 
         transform.rotation = Quat::from_rotation_z(new_rotation);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_flap_climbs_slowly_and_skips_sink() {
+        let physics = PhysicsConfig::default();
+        let interval = auto_flap_interval(&physics);
+        // Height gained over one interval starting at `velocity`
+        let cycle = |velocity: f32| velocity * interval + physics.gravity * interval.powi(2) / 2.0;
+
+        let climb = cycle(physics.flap_strength);
+        assert!(climb > 0.0 && climb < 20.0, "climbed {climb}");
+        // Skipping a flap keeps the bird falling from where the last flap left it
+        let skipped = cycle(physics.flap_strength + physics.gravity * interval);
+        assert!(skipped < -climb, "skipping only dropped {skipped}");
+    }
+
+    #[test]
+    fn gliding_weakens_gravity() {
+        let gravity = PhysicsConfig::default().gravity;
+        assert_eq!(glide_gravity(gravity, false), gravity);
+        assert!(glide_gravity(gravity, true).abs() < gravity.abs());
+        assert!(glide_gravity(gravity, true).signum() == gravity.signum());
+    }
+
+    #[test]
+    fn cooldown_blocks_flaps_until_recharged() {
+        let mut cooldown = FlapCooldown::default();
+        assert!(cooldown.tick(0.016));
+        assert_eq!(cooldown.charge(FLAP_COOLDOWN), 1.0);
+
+        cooldown.remaining = FLAP_COOLDOWN;
+        assert!(!cooldown.tick(FLAP_COOLDOWN / 2.0));
+        assert!((cooldown.charge(FLAP_COOLDOWN) - 0.5).abs() < 1e-6);
+        assert!(cooldown.tick(FLAP_COOLDOWN));
+    }
+
+    #[test]
+    fn buffered_flaps_are_used_once_and_expire() {
+        let mut buffer = FlapBuffer {
+            pressed_at: Some(1.0),
+        };
+        assert!(buffer.take(1.05, FLAP_BUFFER_TIME));
+        assert!(!buffer.take(1.05, FLAP_BUFFER_TIME));
+
+        buffer.pressed_at = Some(1.0);
+        assert!(!buffer.take(1.5, FLAP_BUFFER_TIME));
+        assert_eq!(buffer.pressed_at, None);
+    }
+}
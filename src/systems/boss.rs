@@ -0,0 +1,117 @@
+//! Boss segment systems.
+//!
+//! Every `boss.interval` points the run switches to `PlayPhase::Boss`: regular
+//! spawning pauses and a wide pipe wall with a moving hole scrolls in. Once
+//! the wall is scored, the run goes back to `PlayPhase::Normal`.
+
+use bevy::prelude::*;
+
+use crate::components::{BossWall, GapSensor, Pipe, PipePair};
+use crate::config::GameConfig;
+use crate::constants::{GROUND_HEIGHT, PIPE_WIDTH};
+use crate::resources::{GameViewport, PipeSpawnDistance, ScoreEvent};
+use crate::states::PlayPhase;
+use crate::systems::pipes::pipe_sprite;
+use crate::theme::Theme;
+
+/// Boss walls sit behind the ground, which hides their lower end.
+const BOSS_WALL_Z: f32 = -0.1;
+
+/// Starts a boss segment when the score reaches the boss interval.
+pub fn start_boss_segment(
+    mut score_events: MessageReader<ScoreEvent>,
+    config: Res<GameConfig>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+) {
+    for event in score_events.read() {
+        if config.boss.starts_at(event.score) {
+            info!("Boss segment at score {}", event.score);
+            next_phase.set(PlayPhase::Boss);
+        }
+    }
+}
+
+/// Spawns the boss wall where the next pipe pair would have appeared.
+pub fn spawn_boss_wall(
+    mut commands: Commands,
+    viewport: Res<GameViewport>,
+    spawn_distance: Res<PipeSpawnDistance>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+) {
+    let boss = &config.boss;
+    let bottom = -viewport.half_height() + GROUND_HEIGHT;
+    let top = viewport.half_height();
+    let play_height = top - bottom;
+
+    // Keep the hole inside the play area for its whole path
+    let base_y = (bottom + top) / 2.0;
+    let travel = boss
+        .hole_travel
+        .min((play_height - boss.hole_size) / 2.0 - 20.0)
+        .max(0.0);
+
+    let spawn_edge = viewport.half_width() + PIPE_WIDTH / 2.0 + spawn_distance.remaining();
+    let x = spawn_edge + boss.wall_width / 2.0;
+
+    // Segments are as tall as the play area so they cover it wherever the hole is
+    let size = Vec2::new(boss.wall_width, play_height);
+    let offset = (boss.hole_size + play_height) / 2.0;
+    commands
+        .spawn((
+            Transform::from_xyz(x, base_y, BOSS_WALL_Z),
+            Visibility::default(),
+            PipePair {
+                gap_y: base_y,
+                width: boss.wall_width,
+                scored: false,
+            },
+            BossWall {
+                base_y,
+                travel,
+                speed: boss.hole_speed,
+                elapsed: 0.0,
+            },
+        ))
+        .with_children(|wall| {
+            for offset in [-offset, offset] {
+                wall.spawn((
+                    pipe_sprite(size, &theme, 0),
+                    Transform::from_xyz(0.0, offset, 0.0),
+                    Pipe,
+                ));
+            }
+            wall.spawn((
+                Transform::default(),
+                GapSensor {
+                    size: Vec2::new(boss.wall_width, boss.hole_size),
+                },
+            ));
+        });
+}
+
+/// Moves the hole of the boss wall up and down.
+pub fn move_boss_wall(
+    time: Res<Time>,
+    mut query: Query<(&mut BossWall, &mut Transform, &mut PipePair)>,
+) {
+    for (mut wall, mut transform, mut pair) in query.iter_mut() {
+        wall.elapsed += time.delta_secs();
+        let hole_y = wall.base_y + wall.travel * (wall.elapsed * wall.speed).sin();
+        transform.translation.y = hole_y;
+        pair.gap_y = hole_y;
+    }
+}
+
+/// Resumes regular spawning once the boss wall has been scored.
+pub fn finish_boss_segment(
+    walls: Query<&PipePair, With<BossWall>>,
+    mut spawn_distance: ResMut<PipeSpawnDistance>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+) {
+    if walls.iter().all(|pair| pair.scored) {
+        // Give the wall a full pipe spacing before the next pipe pair
+        spawn_distance.scrolled = 0.0;
+        next_phase.set(PlayPhase::Normal);
+    }
+}
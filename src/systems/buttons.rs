@@ -0,0 +1,229 @@
+//! Button systems.
+//!
+//! Pressable text on the menus is a `WorldButton`. It lights up while the
+//! pointer hovers it, darkens while pressed, and is clicked when a press that
+//! started on it is released over it, so dragging off a button cancels the
+//! click. Each click sends a `ButtonClicked` message carrying the button's
+//! action component, which the screens read instead of testing pointer
+//! positions themselves. Disabled buttons are dimmed and ignore the pointer.
+//! Hovering and clicking a button play the matching sound effects.
+
+use bevy::prelude::*;
+
+use crate::components::{ButtonState, Focusable, HitArea, WorldButton};
+use crate::input::PointerInput;
+use crate::resources::{ButtonClicked, PlaySfx};
+use crate::sfx::SoundEffect;
+use crate::systems::focus::FOCUS_COLOR;
+
+/// Color of a button held down.
+pub const PRESSED_COLOR: Color = Color::srgb(0.85, 0.6, 0.15);
+
+/// Opacity of a disabled button, relative to its idle color.
+const DISABLED_ALPHA: f32 = 0.35;
+
+/// Where the pointer is relative to a button this frame.
+#[derive(Clone, Copy, Default)]
+struct PointerOver {
+    /// The cursor or a finger is over the button
+    hovered: bool,
+    /// A click or touch started over the button
+    just_pressed: bool,
+    /// A click or touch ended over the button
+    just_released: bool,
+    /// The mouse button or a finger is held down anywhere
+    held: bool,
+}
+
+/// Tracks the pointer over every button and marks the ones clicked this frame.
+///
+/// Hidden buttons behave as if disabled.
+pub fn update_buttons(
+    pointer: PointerInput,
+    mut sfx: MessageWriter<PlaySfx>,
+    mut query: Query<(
+        &GlobalTransform,
+        &HitArea,
+        &InheritedVisibility,
+        &mut WorldButton,
+    )>,
+) {
+    let hovered = pointer.hover_positions();
+    let pressed = pointer.just_pressed_positions();
+    let released = pointer.just_released_positions();
+    let held = pointer.is_pressed();
+
+    for (transform, area, visibility, mut button) in query.iter_mut() {
+        let over = |positions: &[Vec2]| {
+            positions
+                .iter()
+                .any(|position| area.contains(transform, *position))
+        };
+        let next = advance(
+            *button,
+            visibility.get(),
+            PointerOver {
+                hovered: over(&hovered),
+                just_pressed: over(&pressed),
+                just_released: over(&released),
+                held,
+            },
+        );
+        if next.clicked {
+            sfx.write(PlaySfx(SoundEffect::Click));
+        } else if next.state == ButtonState::Hovered && button.state == ButtonState::Idle {
+            sfx.write(PlaySfx(SoundEffect::Hover));
+        }
+        button.set_if_neq(next);
+    }
+}
+
+/// Sends a `ButtonClicked` message for every clicked button carrying `T`.
+pub fn send_button_clicks<T: Component + Clone>(
+    query: Query<(&WorldButton, &T)>,
+    mut clicks: MessageWriter<ButtonClicked<T>>,
+) {
+    for (button, action) in query.iter() {
+        if button.clicked {
+            clicks.write(ButtonClicked(action.clone()));
+        }
+    }
+}
+
+/// Colors every button by its state; focused buttons look hovered.
+pub fn style_buttons(mut query: Query<(&WorldButton, Option<&Focusable>, &mut TextColor)>) {
+    for (button, focusable, mut color) in query.iter_mut() {
+        let focused = focusable.is_some_and(|focusable| focusable.focused);
+        let target = match button.state {
+            ButtonState::Disabled => button
+                .color
+                .with_alpha(button.color.alpha() * DISABLED_ALPHA),
+            ButtonState::Pressed => PRESSED_COLOR,
+            ButtonState::Hovered => FOCUS_COLOR,
+            ButtonState::Idle if focused => FOCUS_COLOR,
+            ButtonState::Idle => button.color,
+        };
+        color.set_if_neq(TextColor(target));
+    }
+}
+
+/// Returns the button after this frame's pointer input.
+fn advance(button: WorldButton, visible: bool, pointer: PointerOver) -> WorldButton {
+    if button.disabled || !visible {
+        return WorldButton {
+            state: ButtonState::Disabled,
+            armed: false,
+            clicked: false,
+            ..button
+        };
+    }
+
+    let clicked = button.armed && pointer.just_released;
+    let armed = (button.armed || pointer.just_pressed) && pointer.held;
+    let state = match (pointer.hovered, armed) {
+        (true, true) => ButtonState::Pressed,
+        (true, false) => ButtonState::Hovered,
+        (false, _) => ButtonState::Idle,
+    };
+    WorldButton {
+        state,
+        armed,
+        clicked,
+        ..button
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds a sequence of pointer frames to a fresh button.
+    fn run(frames: &[PointerOver]) -> Vec<WorldButton> {
+        let mut button = WorldButton::default();
+        frames
+            .iter()
+            .map(|pointer| {
+                button = advance(button, true, *pointer);
+                button
+            })
+            .collect()
+    }
+
+    #[test]
+    fn press_and_release_over_the_button_clicks() {
+        let frames = run(&[
+            PointerOver {
+                hovered: true,
+                ..default()
+            },
+            PointerOver {
+                hovered: true,
+                just_pressed: true,
+                held: true,
+                ..default()
+            },
+            PointerOver {
+                hovered: true,
+                just_released: true,
+                ..default()
+            },
+        ]);
+        let states: Vec<_> = frames.iter().map(|button| button.state).collect();
+        assert_eq!(
+            states,
+            [
+                ButtonState::Hovered,
+                ButtonState::Pressed,
+                ButtonState::Hovered
+            ]
+        );
+        assert_eq!(
+            frames
+                .iter()
+                .map(|button| button.clicked)
+                .collect::<Vec<_>>(),
+            [false, false, true]
+        );
+    }
+
+    #[test]
+    fn dragging_off_the_button_cancels_the_click() {
+        let frames = run(&[
+            PointerOver {
+                hovered: true,
+                just_pressed: true,
+                held: true,
+                ..default()
+            },
+            PointerOver {
+                held: true,
+                ..default()
+            },
+            PointerOver::default(),
+        ]);
+        assert_eq!(frames[1].state, ButtonState::Idle);
+        assert!(frames.iter().all(|button| !button.clicked));
+    }
+
+    #[test]
+    fn disabled_buttons_ignore_the_pointer() {
+        let button = WorldButton {
+            disabled: true,
+            ..default()
+        };
+        let pressed = advance(
+            button,
+            true,
+            PointerOver {
+                hovered: true,
+                just_pressed: true,
+                held: true,
+                ..default()
+            },
+        );
+        assert_eq!(pressed.state, ButtonState::Disabled);
+        assert!(!pressed.armed);
+        let hidden = advance(WorldButton::default(), false, PointerOver::default());
+        assert_eq!(hidden.state, ButtonState::Disabled);
+    }
+}
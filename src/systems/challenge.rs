@@ -0,0 +1,96 @@
+//! Daily and weekly challenge systems.
+//!
+//! This module syncs the challenge clock with the time server, shows how
+//! long each challenge has left on its menu button, and starts a run with
+//! the challenge's seed when a button is activated.
+
+use bevy::prelude::*;
+
+use crate::challenge::{ChallengeClock, TimeSource, format_countdown};
+use crate::components::{ChallengeButton, Focusable, InstructionText, MenuButton};
+use crate::input::{MenuAction, MenuInput};
+use crate::race::RaceSession;
+use crate::resources::{ButtonClicked, PendingSeed};
+use crate::states::GameState;
+
+/// Creates the challenge clock and starts syncing it with the time server,
+/// if one is configured.
+pub fn init_challenge_clock(mut commands: Commands) {
+    commands.insert_resource(ChallengeClock::from_env());
+}
+
+/// Switches to the time server's clock once it answers.
+pub fn sync_challenge_clock(mut clock: ResMut<ChallengeClock>) {
+    // Only write (and trigger change detection) when the reply arrives
+    if !clock.bypass_change_detection().poll() {
+        return;
+    }
+    clock.set_changed();
+    if clock.source() != TimeSource::System {
+        info!(
+            "Challenge clock synced over {:?} ({:+.1}s from the system clock)",
+            clock.source(),
+            clock.offset()
+        );
+    }
+}
+
+/// Shows the time left before each challenge rolls over on its button.
+pub fn update_challenge_buttons(
+    clock: Res<ChallengeClock>,
+    mut query: Query<(&ChallengeButton, &mut Text2d)>,
+) {
+    let now = clock.now();
+    for (ChallengeButton(kind), mut text) in query.iter_mut() {
+        let label = format!(
+            "{} ({})",
+            kind.label(),
+            format_countdown(kind.rolls_over_in(now))
+        );
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
+/// Starts a run with a challenge's seed when its button is clicked or
+/// confirmed. Races pick their own seed, so challenges can't start there.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn start_challenge(
+    menu_input: MenuInput,
+    mut clicks: MessageReader<ButtonClicked<ChallengeButton>>,
+    button_query: Query<(&ChallengeButton, &Focusable)>,
+    clock: Res<ChallengeClock>,
+    race: Option<Res<RaceSession>>,
+    mut pending_seed: ResMut<PendingSeed>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+) {
+    let confirmed = menu_input
+        .just_pressed(MenuAction::Confirm)
+        .then(|| {
+            button_query
+                .iter()
+                .find(|(_, focusable)| focusable.focused)
+                .map(|(ChallengeButton(kind), _)| *kind)
+        })
+        .flatten();
+    let clicked = clicks
+        .read()
+        .last()
+        .map(|ButtonClicked(ChallengeButton(kind))| *kind);
+    let Some(kind) = clicked.or(confirmed) else {
+        return;
+    };
+    if race.is_some() {
+        return;
+    }
+
+    let seed = kind.seed(clock.now());
+    info!("Starting the {} (seed {seed})", kind.label().to_lowercase());
+    pending_seed.0 = Some(seed);
+    next_state.set(GameState::Playing);
+    for mut visibility in menu_text_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
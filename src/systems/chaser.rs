@@ -0,0 +1,133 @@
+//! Chaser enemy systems.
+//!
+//! Above 30 points, a chaser waits just off the left edge of the screen. A
+//! bird that hovers at the same height for too long sees it close in from
+//! behind, and every pipe passed pushes it back, so it only catches players
+//! who stall. It steers towards a spot on the bird's height, between its
+//! parking spot and the bird, and registers itself in `NearbyObstacles` once
+//! level with the bird so `check_collisions` tests it like an obstacle.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, Chaser, CollisionShape};
+use crate::constants::{
+    BIRD_SIZE, CHASER_CLOSE_RATE, CHASER_COLOR, CHASER_HOVER_BAND, CHASER_HOVER_GRACE,
+    CHASER_MAX_SPEED, CHASER_MIN_SCORE, CHASER_RETREAT, CHASER_SIZE, CHASER_STEERING,
+};
+use crate::resources::{GameViewport, NearbyObstacles, Score, ScoreEvent};
+use crate::theme::color;
+
+/// The chaser flies just behind the bird.
+const CHASER_Z: f32 = 0.95;
+
+/// Spawns the chaser once the score is above its threshold.
+pub fn spawn_chaser(
+    mut commands: Commands,
+    score: Res<Score>,
+    viewport: Res<GameViewport>,
+    bird_query: Query<&Transform, With<Bird>>,
+    chaser_query: Query<(), With<Chaser>>,
+) {
+    if score.0 <= CHASER_MIN_SCORE || !chaser_query.is_empty() {
+        return;
+    }
+    for transform in bird_query.iter() {
+        let y = transform.translation.y;
+        commands.spawn((
+            Sprite::from_color(color(CHASER_COLOR), Vec2::splat(CHASER_SIZE)),
+            Transform::from_xyz(parked_x(&viewport), y, CHASER_Z),
+            Chaser {
+                hover_y: y,
+                ..default()
+            },
+            CollisionShape::Circle(CHASER_SIZE / 2.0),
+        ));
+    }
+}
+
+/// Removes the chaser when a run starts (or is rewound), so it comes back
+/// from off-screen.
+pub fn despawn_chaser(mut commands: Commands, query: Query<Entity, With<Chaser>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Closes the chaser in on a hovering bird, pushes it back for every pipe
+/// passed, and steers it towards its spot.
+pub fn steer_chaser(
+    time: Res<Time>,
+    viewport: Res<GameViewport>,
+    mut score_events: MessageReader<ScoreEvent>,
+    mut nearby: ResMut<NearbyObstacles>,
+    bird_query: Query<&Transform, With<Bird>>,
+    mut chaser_query: Query<(Entity, &mut Chaser, &mut Transform), Without<Bird>>,
+) {
+    let pipes_passed = score_events.read().count() as u32;
+    let Ok(bird) = bird_query.single() else {
+        return;
+    };
+    let bird_pos = bird.translation.truncate();
+    let dt = time.delta_secs();
+
+    for (entity, mut chaser, mut transform) in chaser_query.iter_mut() {
+        if (bird_pos.y - chaser.hover_y).abs() > CHASER_HOVER_BAND / 2.0 {
+            chaser.hover_y = bird_pos.y;
+            chaser.hover_time = 0.0;
+        } else {
+            chaser.hover_time += dt;
+        }
+        chaser.closeness = step_closeness(chaser.closeness, chaser.hover_time, pipes_passed, dt);
+
+        let target = Vec2::new(
+            parked_x(&viewport).lerp(bird_pos.x, chaser.closeness),
+            bird_pos.y,
+        );
+        let position = transform.translation.truncate();
+        let desired = ((target - position) * CHASER_STEERING).clamp_length_max(CHASER_MAX_SPEED);
+        let velocity = chaser.velocity;
+        chaser.velocity = velocity + (desired - velocity) * (CHASER_STEERING * dt).min(1.0);
+        transform.translation.x += chaser.velocity.x * dt;
+        transform.translation.y += chaser.velocity.y * dt;
+
+        if (transform.translation.x - bird_pos.x).abs() < (CHASER_SIZE + BIRD_SIZE) / 2.0 {
+            nearby.0.push(entity);
+        }
+    }
+}
+
+/// Returns where the chaser waits, just off the left edge of the screen.
+fn parked_x(viewport: &GameViewport) -> f32 {
+    -viewport.half_width() - CHASER_SIZE
+}
+
+/// Advances the chaser's closeness by `dt` seconds: it grows once the bird
+/// has hovered past the grace time, and drops for every pipe passed.
+fn step_closeness(closeness: f32, hover_time: f32, pipes_passed: u32, dt: f32) -> f32 {
+    let mut closeness = closeness - pipes_passed as f32 * CHASER_RETREAT;
+    if hover_time > CHASER_HOVER_GRACE {
+        closeness += CHASER_CLOSE_RATE * dt;
+    }
+    closeness.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chaser_closes_in_only_after_the_grace_time() {
+        assert_eq!(step_closeness(0.0, CHASER_HOVER_GRACE / 2.0, 0, 1.0), 0.0);
+        assert_eq!(
+            step_closeness(0.0, CHASER_HOVER_GRACE + 0.1, 0, 1.0),
+            CHASER_CLOSE_RATE
+        );
+        assert_eq!(step_closeness(0.9, CHASER_HOVER_GRACE + 0.1, 0, 10.0), 1.0);
+    }
+
+    #[test]
+    fn passing_pipes_pushes_the_chaser_back() {
+        assert_eq!(step_closeness(0.5, 0.0, 1, 0.0), 0.5 - CHASER_RETREAT);
+        assert_eq!(step_closeness(0.5, 0.0, 5, 0.0), 0.0);
+    }
+}
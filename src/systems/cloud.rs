@@ -0,0 +1,58 @@
+//! Cloud save sync systems.
+//!
+//! This module feeds local progress and settings to the optional `CloudSync`
+//! resource and applies merged copies coming back from the remote. All
+//! systems here only run when sync is configured.
+
+use bevy::prelude::*;
+
+use crate::cloud::{CloudSave, CloudSync};
+use crate::save::SaveData;
+use crate::settings::Settings;
+
+/// Enables cloud save sync if a remote is configured in the environment.
+pub fn init_cloud_sync(mut commands: Commands) {
+    if let Some(sync) = CloudSync::from_env() {
+        info!("Cloud save sync enabled");
+        commands.insert_resource(sync);
+    }
+}
+
+/// Syncs with the remote whenever progress or settings change (and at startup).
+pub fn request_cloud_sync(sync: Res<CloudSync>, save: Res<SaveData>, settings: Res<Settings>) {
+    if save.is_changed() || settings.is_changed() {
+        sync.request(CloudSave {
+            save: save.clone(),
+            settings: settings.clone(),
+        });
+    }
+}
+
+/// Applies merged copies from the remote and stores them locally.
+///
+/// The result is merged with the current local data again, so progress made
+/// while a sync was in flight is kept.
+pub fn apply_cloud_sync(
+    sync: Res<CloudSync>,
+    mut save: ResMut<SaveData>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some(remote) = sync.take_result() else {
+        return;
+    };
+
+    let local = CloudSave {
+        save: save.clone(),
+        settings: settings.clone(),
+    };
+    let merged = local.merge(&remote);
+
+    if save.set_if_neq(merged.save) {
+        info!("Updated progress from cloud save");
+        save.store();
+    }
+    if settings.set_if_neq(merged.settings) {
+        info!("Updated settings from cloud save");
+        settings.store();
+    }
+}
@@ -0,0 +1,101 @@
+//! Background cloud systems.
+//!
+//! Clouds of varied size and opacity drift slowly across the sky, on a
+//! background layer behind the pipes. They spawn past the right edge of the
+//! viewport and are removed past the left edge, so they follow window
+//! resizes. They are purely cosmetic and draw from the effects random
+//! stream, never the pipes one.
+
+use bevy::prelude::*;
+
+use crate::components::{Cloud, CloudLayer};
+use crate::constants::{
+    CLOUD_ALPHA, CLOUD_INITIAL_COUNT, CLOUD_SPAWN_INTERVAL, CLOUD_SPEED, CLOUD_WIDTH, CLOUD_Z,
+    GROUND_HEIGHT,
+};
+use crate::resources::GameViewport;
+use crate::rng::{GameRng, RngStream};
+
+/// Spawns the cloud layer with a few clouds already on screen.
+pub fn spawn_cloud_layer(
+    mut commands: Commands,
+    viewport: Res<GameViewport>,
+    mut rng: ResMut<GameRng>,
+) {
+    let layer = commands
+        .spawn((
+            Transform::from_xyz(0.0, 0.0, CLOUD_Z),
+            Visibility::default(),
+            CloudLayer {
+                spawn_timer: rng
+                    .effects
+                    .range(CLOUD_SPAWN_INTERVAL.0, CLOUD_SPAWN_INTERVAL.1),
+            },
+        ))
+        .id();
+
+    for _ in 0..CLOUD_INITIAL_COUNT {
+        let x = rng
+            .effects
+            .range(-viewport.half_width(), viewport.half_width());
+        spawn_cloud(&mut commands, layer, x, &viewport, &mut rng.effects);
+    }
+}
+
+/// Drifts the clouds, spawns new ones past the right edge, and despawns the
+/// ones past the left edge.
+pub fn drift_clouds(
+    mut commands: Commands,
+    time: Res<Time>,
+    viewport: Res<GameViewport>,
+    mut rng: ResMut<GameRng>,
+    mut layer_query: Query<(Entity, &mut CloudLayer)>,
+    mut cloud_query: Query<(Entity, &Cloud, &Sprite, &mut Transform)>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, cloud, sprite, mut transform) in cloud_query.iter_mut() {
+        transform.translation.x -= cloud.speed * delta;
+        let half_width = sprite.custom_size.map_or(0.0, |size| size.x) / 2.0;
+        if transform.translation.x < -viewport.half_width() - half_width {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for (layer, mut clouds) in layer_query.iter_mut() {
+        clouds.spawn_timer -= delta;
+        if clouds.spawn_timer > 0.0 {
+            continue;
+        }
+        clouds.spawn_timer = rng
+            .effects
+            .range(CLOUD_SPAWN_INTERVAL.0, CLOUD_SPAWN_INTERVAL.1);
+        let x = viewport.half_width() + CLOUD_WIDTH.1;
+        spawn_cloud(&mut commands, layer, x, &viewport, &mut rng.effects);
+    }
+}
+
+/// Spawns a cloud of random size, opacity, height, and speed on the layer.
+///
+/// Clouds stay in the upper part of the sky, clear of the ground.
+fn spawn_cloud(
+    commands: &mut Commands,
+    layer: Entity,
+    x: f32,
+    viewport: &GameViewport,
+    rng: &mut RngStream,
+) {
+    let width = rng.range(CLOUD_WIDTH.0, CLOUD_WIDTH.1);
+    let size = Vec2::new(width, width * rng.range(0.3, 0.5));
+    let lowest = -viewport.half_height() + GROUND_HEIGHT + viewport.height / 3.0;
+    let y = rng.range(lowest, viewport.half_height() - size.y);
+    let alpha = rng.range(CLOUD_ALPHA.0, CLOUD_ALPHA.1);
+
+    commands.entity(layer).with_child((
+        Sprite::from_color(Color::WHITE.with_alpha(alpha), size),
+        Transform::from_xyz(x, y, 0.0),
+        Cloud {
+            speed: rng.range(CLOUD_SPEED.0, CLOUD_SPEED.1),
+        },
+    ));
+}
@@ -1,113 +1,420 @@
 //! Collision detection systems.
 //!
-//! This module handles all collision-related logic.
+//! This module handles all collision-related logic. Only the pipes and
+//! obstacles (including the chaser enemy) listed in `NearbyObstacles` are
+//! tested, so the cost doesn't grow
+//! with the number of obstacles on screen. The tests run in their own tracing
+//! spans, which show up when profiling with the `trace` features.
+//!
+//! The bird's hitbox (`BirdHitbox`) is a `BIRD_SIZE` square. With `physics.rotated_hitbox`
+//! it turns with the bird's tilt, so a bird diving nose-first past a pipe
+//! corner collides where the sprite does. Every size is multiplied by the
+//! entity's `Transform::scale`, so squash and stretch (or any other scaling)
+//! changes the hitbox along with the sprite. Shattering, near misses and
+//! scoring all measure the same hitbox.
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
-use crate::components::{Bird, InstructionText, Pipe};
+use crate::components::{
+    Bird, Chaser, CollisionShape, InstructionText, Obstacle, Pipe, PipePair, Shattered,
+};
+use crate::config::{GameConfig, PhysicsConfig};
 use crate::constants::{BIRD_SIZE, GROUND_HEIGHT};
-use crate::resources::{DeathEvent, GameViewport};
-use crate::states::GameState;
+use crate::race::RaceSession;
+use crate::resources::{
+    DeathCause, DeathEvent, DebugTools, GameViewport, Invincibility, NearMiss, NearbyObstacles,
+    PipeShattered, Revive, RunStats,
+};
+use crate::save::SaveData;
+use crate::states::{GameState, PauseState};
+
+/// The pipes the bird can collide with, looked up through their pairs.
+#[derive(SystemParam)]
+#[allow(clippy::type_complexity)]
+pub struct SolidPipes<'w, 's> {
+    pairs: Query<'w, 's, (&'static Transform, &'static Children), With<PipePair>>,
+    pipes: Query<
+        'w,
+        's,
+        (Entity, &'static Transform, &'static Sprite),
+        (With<Pipe>, Without<Shattered>),
+    >,
+}
+
+/// Where the bird's closest shaves go: the run's stats, and near misses for
+/// the effects.
+#[derive(SystemParam)]
+pub struct ClearanceLog<'w> {
+    stats: ResMut<'w, RunStats>,
+    near_misses: MessageWriter<'w, NearMiss>,
+}
+
+impl ClearanceLog<'_> {
+    /// Records the bird's clearance this frame, reporting it as a near miss
+    /// when under `near_miss_distance`.
+    fn record(&mut self, clearance: f32, near_miss_distance: f32) {
+        self.stats.record_clearance(clearance);
+        if clearance < near_miss_distance {
+            self.near_misses.write(NearMiss { clearance });
+        }
+    }
+}
+
+/// The obstacles the bird can collide with, including the chaser enemy.
+type SolidObstacles<'w, 's> = Query<
+    'w,
+    's,
+    (Entity, &'static Transform, &'static CollisionShape),
+    Or<(With<Obstacle>, With<Chaser>)>,
+>;
+
+/// The bird's hitbox: a square stretched by its scale, and turned with its
+/// tilt when the configuration asks for it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct BirdHitbox {
+    /// World position of the center
+    center: Vec2,
+    /// Half the width and height, before rotation
+    half: Vec2,
+    /// Rotation in radians (0 keeps the square axis-aligned)
+    angle: f32,
+}
+
+impl BirdHitbox {
+    /// Returns the hitbox of a bird with the given transform.
+    pub(crate) fn new(transform: &Transform, physics: &PhysicsConfig) -> Self {
+        let angle = if physics.rotated_hitbox {
+            transform.rotation.to_euler(EulerRot::ZYX).0
+        } else {
+            0.0
+        };
+        Self {
+            center: transform.translation.truncate(),
+            half: scaled_size(Vec2::splat(BIRD_SIZE / 2.0), transform.scale),
+            angle,
+        }
+    }
+
+    /// Returns the directions of the hitbox's width and height.
+    fn axes(&self) -> [Vec2; 2] {
+        let (sin, cos) = self.angle.sin_cos();
+        [Vec2::new(cos, sin), Vec2::new(-sin, cos)]
+    }
+
+    /// Returns how far the hitbox reaches from its center along a unit axis.
+    fn extent(&self, axis: Vec2) -> f32 {
+        let [width, height] = self.axes();
+        self.half.x * width.dot(axis).abs() + self.half.y * height.dot(axis).abs()
+    }
+
+    /// Returns the world position of the hitbox's center.
+    pub(crate) fn center(&self) -> Vec2 {
+        self.center
+    }
+
+    /// Returns the width and height of the axis-aligned box around the
+    /// hitbox.
+    pub(crate) fn bounds(&self) -> Vec2 {
+        2.0 * Vec2::new(self.extent(Vec2::X), self.extent(Vec2::Y))
+    }
+
+    /// Returns the lowest point of the hitbox.
+    fn bottom(&self) -> f32 {
+        self.center.y - self.extent(Vec2::Y)
+    }
 
-/// Checks for collisions between bird and pipes/ground/ceiling.
+    /// Returns the highest point of the hitbox.
+    fn top(&self) -> f32 {
+        self.center.y + self.extent(Vec2::Y)
+    }
+
+    /// Tests the hitbox against an axis-aligned box, by looking for a
+    /// separating axis among the edges of both.
+    fn overlaps_box(&self, center: Vec2, size: Vec2) -> bool {
+        let offset = center - self.center;
+        let half_box = size / 2.0;
+        let [edge_a, edge_b] = self.axes();
+        [Vec2::X, Vec2::Y, edge_a, edge_b].into_iter().all(|axis| {
+            let box_extent = half_box.x * axis.x.abs() + half_box.y * axis.y.abs();
+            offset.dot(axis).abs() < self.extent(axis) + box_extent
+        })
+    }
+
+    /// Tests the hitbox against a circle, using the hitbox's closest point
+    /// to the circle's center.
+    fn overlaps_circle(&self, center: Vec2, radius: f32) -> bool {
+        // The circle's center in the hitbox's own frame
+        let local = Vec2::from_angle(-self.angle).rotate(center - self.center);
+        let closest = local.clamp(-self.half, self.half);
+        closest.distance_squared(local) < radius * radius
+    }
+}
+
+/// Returns a size multiplied by a transform's scale (mirroring ignored).
+fn scaled_size(size: Vec2, scale: Vec3) -> Vec2 {
+    size * scale.truncate().abs()
+}
+
+/// Returns the collision shape of an obstacle, multiplied by its scale.
+///
+/// Circles stay round, so they grow with the larger of the two factors.
+fn scaled_shape(shape: CollisionShape, scale: Vec3) -> CollisionShape {
+    match shape {
+        CollisionShape::Circle(radius) => {
+            CollisionShape::Circle(radius * scale.x.abs().max(scale.y.abs()))
+        }
+        CollisionShape::Box(size) => CollisionShape::Box(scaled_size(size, scale)),
+    }
+}
+
+impl SolidPipes<'_, '_> {
+    /// Returns the solid pipes of the nearby pairs, with their world position
+    /// and size (both scaled by the pair's and the pipe's transforms).
+    fn near<'a>(
+        &'a self,
+        nearby: &'a NearbyObstacles,
+    ) -> impl Iterator<Item = (Entity, Vec3, Vec2)> + 'a {
+        self.pairs
+            .iter_many(&nearby.0)
+            .flat_map(move |(pair, children)| {
+                self.pipes
+                    .iter_many(children)
+                    .map(move |(entity, transform, sprite)| {
+                        (
+                            entity,
+                            pair.transform_point(transform.translation),
+                            scaled_size(
+                                sprite.custom_size.unwrap_or(Vec2::ZERO),
+                                pair.scale * transform.scale,
+                            ),
+                        )
+                    })
+            })
+    }
+}
+
+/// Checks for collisions between bird and pipes/obstacles/ground/ceiling.
 ///
-/// Triggers game over state when a collision is detected.
+/// Triggers game over state when a collision is detected, and otherwise
+/// records how close the bird came to a pipe or obstacle, reporting near
+/// misses. A crash first offers the run's revive if the player can afford
+/// it, and an invincible bird (or one made invincible from the debug panel)
+/// passes through pipes and obstacles.
+#[allow(clippy::too_many_arguments)]
 pub fn check_collisions(
     bird_query: Query<&Transform, With<Bird>>,
-    pipe_query: Query<(&Transform, &Sprite), With<Pipe>>,
+    pipe_query: SolidPipes,
+    obstacle_query: SolidObstacles,
+    nearby: Res<NearbyObstacles>,
     mut next_state: ResMut<NextState<GameState>>,
-    mut instruction_query: Query<(&mut Visibility, &mut Text2d), With<InstructionText>>,
-    mut death_events: MessageWriter<DeathEvent>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+    mut instruction_query: Query<(&mut Visibility, &mut Text), With<InstructionText>>,
+    mut commands: Commands,
+    mut clearances: ClearanceLog,
+    mut revive: ResMut<Revive>,
+    invincibility: Res<Invincibility>,
+    save: Res<SaveData>,
+    race: Option<Res<RaceSession>>,
     viewport: Res<GameViewport>,
+    config: Res<GameConfig>,
+    debug: Option<Res<DebugTools>>,
 ) {
     let Ok(bird_transform) = bird_query.single() else {
         return;
     };
     let bird_pos = bird_transform.translation;
+    let hitbox = BirdHitbox::new(bird_transform, &config.physics);
+    let invincible = invincibility.is_active() || debug.is_some_and(|debug| debug.invincible);
+    let _span = info_span!("collision_tests", entities = nearby.0.len()).entered();
 
-    // Check ground collision
-    if check_ground_collision(bird_pos, &viewport) {
-        trigger_game_over(
-            &mut next_state,
-            &mut instruction_query,
-            &mut death_events,
-            bird_pos,
-        );
+    let cause = if check_ground_collision(&hitbox, &viewport) {
+        DeathCause::Ground
+    } else if check_ceiling_collision(&hitbox, &viewport) {
+        DeathCause::Ceiling
+    } else if let Some(entity) = check_pipe_collisions(&hitbox, &pipe_query, &nearby)
+        && !invincible
+    {
+        DeathCause::Pipe { entity }
+    } else if let Some(entity) = check_obstacle_collisions(&hitbox, &obstacle_query, &nearby)
+        && !invincible
+    {
+        DeathCause::Obstacle { entity }
+    } else {
+        if !invincible
+            && let Some(clearance) = info_span!("nearest_clearance")
+                .in_scope(|| nearest_clearance(&hitbox, &pipe_query, &obstacle_query, &nearby))
+        {
+            clearances.record(clearance, config.effects.near_miss_distance);
+        }
+        return;
+    };
+
+    if revive.is_available(save.coins) && race.is_none() {
+        revive.crash = Some((bird_pos, cause));
+        next_pause.set(PauseState::Reviving);
         return;
     }
 
-    // Check ceiling collision
-    if check_ceiling_collision(bird_pos, &viewport) {
-        trigger_game_over(
-            &mut next_state,
-            &mut instruction_query,
-            &mut death_events,
-            bird_pos,
-        );
+    trigger_game_over(
+        &mut next_state,
+        &mut instruction_query,
+        &mut commands,
+        bird_pos,
+        cause,
+    );
+}
+
+/// Shatters the pipes the star-powered bird flies through.
+///
+/// Shattered pipes are hidden and stop colliding, but still score.
+pub fn shatter_pipes(
+    mut commands: Commands,
+    invincibility: Res<Invincibility>,
+    bird_query: Query<&Transform, With<Bird>>,
+    pipe_query: SolidPipes,
+    nearby: Res<NearbyObstacles>,
+    config: Res<GameConfig>,
+    mut shatter_events: MessageWriter<PipeShattered>,
+) {
+    if !invincibility.is_active() || !invincibility.shatters {
         return;
     }
+    let Ok(bird_transform) = bird_query.single() else {
+        return;
+    };
+    let hitbox = BirdHitbox::new(bird_transform, &config.physics);
+    let _span = info_span!("shatter_tests", entities = nearby.0.len()).entered();
+
+    for (entity, pipe_pos, size) in pipe_query.near(&nearby) {
+        if !hitbox.overlaps_box(pipe_pos.truncate(), size) {
+            continue;
+        }
+        let half_size = size / 2.0;
 
-    // Check pipe collisions
-    if check_pipe_collisions(bird_pos, &pipe_query) {
-        trigger_game_over(
-            &mut next_state,
-            &mut instruction_query,
-            &mut death_events,
-            bird_pos,
+        commands
+            .entity(entity)
+            .insert((Shattered, Visibility::Hidden));
+        // Burst from the point of the pipe closest to the bird
+        let contact = hitbox.center.clamp(
+            pipe_pos.truncate() - half_size,
+            pipe_pos.truncate() + half_size,
         );
+        shatter_events.write(PipeShattered {
+            position: contact.extend(pipe_pos.z),
+        });
     }
 }
 
 /// Checks if the bird has hit the ground.
-fn check_ground_collision(bird_pos: Vec3, viewport: &GameViewport) -> bool {
+fn check_ground_collision(hitbox: &BirdHitbox, viewport: &GameViewport) -> bool {
     let ground_top = -viewport.half_height() + GROUND_HEIGHT;
-    bird_pos.y - BIRD_SIZE / 2.0 <= ground_top
+    hitbox.bottom() <= ground_top
 }
 
 /// Checks if the bird has hit the ceiling.
-fn check_ceiling_collision(bird_pos: Vec3, viewport: &GameViewport) -> bool {
-    bird_pos.y + BIRD_SIZE / 2.0 >= viewport.half_height()
+fn check_ceiling_collision(hitbox: &BirdHitbox, viewport: &GameViewport) -> bool {
+    hitbox.top() >= viewport.half_height()
 }
 
 /// Checks if the bird has collided with any pipe.
+///
+/// Returns the first pipe entity the bird overlaps, if any.
 fn check_pipe_collisions(
-    bird_pos: Vec3,
-    pipe_query: &Query<(&Transform, &Sprite), With<Pipe>>,
-) -> bool {
-    for (pipe_transform, sprite) in pipe_query.iter() {
-        let pipe_pos = pipe_transform.translation;
-        let pipe_size = sprite.custom_size.unwrap_or(Vec2::ZERO);
-
-        if check_aabb_collision(bird_pos, BIRD_SIZE, pipe_pos, pipe_size) {
-            return true;
+    hitbox: &BirdHitbox,
+    pipe_query: &SolidPipes,
+    nearby: &NearbyObstacles,
+) -> Option<Entity> {
+    for (entity, pipe_pos, pipe_size) in pipe_query.near(nearby) {
+        if hitbox.overlaps_box(pipe_pos.truncate(), pipe_size) {
+            return Some(entity);
         }
     }
-    false
+    None
 }
 
-/// Performs AABB (Axis-Aligned Bounding Box) collision detection.
-fn check_aabb_collision(pos_a: Vec3, size_a: f32, pos_b: Vec3, size_b: Vec2) -> bool {
-    let half_a = size_a / 2.0;
-    let half_b_w = size_b.x / 2.0;
-    let half_b_h = size_b.y / 2.0;
+/// Checks if the bird has collided with any obstacle.
+///
+/// Returns the first obstacle entity the bird overlaps, if any.
+fn check_obstacle_collisions(
+    hitbox: &BirdHitbox,
+    obstacle_query: &SolidObstacles,
+    nearby: &NearbyObstacles,
+) -> Option<Entity> {
+    for (entity, transform, shape) in obstacle_query.iter_many(&nearby.0) {
+        let position = transform.translation.truncate();
+        let hit = match scaled_shape(*shape, transform.scale) {
+            CollisionShape::Circle(radius) => hitbox.overlaps_circle(position, radius),
+            CollisionShape::Box(size) => hitbox.overlaps_box(position, size),
+        };
+        if hit {
+            return Some(entity);
+        }
+    }
+    None
+}
+
+/// Returns the distance between the bird and the nearest pipe or obstacle.
+///
+/// Only pipes and obstacles level with the bird horizontally count, so the
+/// result measures how narrowly the bird is squeezing past them.
+fn nearest_clearance(
+    hitbox: &BirdHitbox,
+    pipe_query: &SolidPipes,
+    obstacle_query: &SolidObstacles,
+    nearby: &NearbyObstacles,
+) -> Option<f32> {
+    let bounds = hitbox.bounds();
+    let level = |x: f32, half_width: f32| (hitbox.center.x - x).abs() < bounds.x / 2.0 + half_width;
 
-    pos_a.x + half_a > pos_b.x - half_b_w
-        && pos_a.x - half_a < pos_b.x + half_b_w
-        && pos_a.y + half_a > pos_b.y - half_b_h
-        && pos_a.y - half_a < pos_b.y + half_b_h
+    let pipes = pipe_query
+        .near(nearby)
+        .filter(|&(_, position, size)| level(position.x, size.x / 2.0))
+        .map(|(_, position, size)| {
+            aabb_clearance(hitbox.center, bounds, position.truncate(), size)
+        });
+    let obstacles = obstacle_query
+        .iter_many(&nearby.0)
+        .filter_map(|(_, transform, shape)| {
+            let position = transform.translation;
+            match scaled_shape(*shape, transform.scale) {
+                CollisionShape::Circle(radius) => level(position.x, radius)
+                    .then(|| circle_clearance(hitbox.center, bounds, position.truncate(), radius)),
+                CollisionShape::Box(size) => level(position.x, size.x / 2.0)
+                    .then(|| aabb_clearance(hitbox.center, bounds, position.truncate(), size)),
+            }
+        });
+    pipes.chain(obstacles).reduce(f32::min)
+}
+
+/// Returns the distance between two boxes (zero if they overlap).
+fn aabb_clearance(pos_a: Vec2, size_a: Vec2, pos_b: Vec2, size_b: Vec2) -> f32 {
+    let gap = (pos_a - pos_b).abs() - (size_a + size_b) / 2.0;
+    gap.max(Vec2::ZERO).length()
+}
+
+/// Returns the distance between a box and a circle (zero if they overlap).
+fn circle_clearance(pos_a: Vec2, size_a: Vec2, center: Vec2, radius: f32) -> f32 {
+    let half_a = size_a / 2.0;
+    let closest = center.clamp(pos_a - half_a, pos_a + half_a);
+    (closest.distance(center) - radius).max(0.0)
 }
 
 /// Triggers the game over state and updates the UI.
-fn trigger_game_over(
+pub fn trigger_game_over(
     next_state: &mut ResMut<NextState<GameState>>,
-    instruction_query: &mut Query<(&mut Visibility, &mut Text2d), With<InstructionText>>,
-    death_events: &mut MessageWriter<DeathEvent>,
+    instruction_query: &mut Query<(&mut Visibility, &mut Text), With<InstructionText>>,
+    commands: &mut Commands,
     bird_pos: Vec3,
+    cause: DeathCause,
 ) {
     next_state.set(GameState::GameOver);
 
-    // Send death event for visual effects
-    death_events.write(DeathEvent { position: bird_pos });
+    // Trigger death event for visual effects
+    commands.trigger(DeathEvent {
+        position: bird_pos,
+        cause,
+    });
 
     let game_over_text = if cfg!(target_os = "ios") {
         "Game Over!\nTap to restart"
@@ -120,3 +427,110 @@ fn trigger_game_over(
         text.0 = game_over_text.to_string();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An upright hitbox of `BIRD_SIZE` (30) at the origin.
+    fn upright() -> BirdHitbox {
+        BirdHitbox {
+            center: Vec2::ZERO,
+            half: Vec2::splat(BIRD_SIZE / 2.0),
+            angle: 0.0,
+        }
+    }
+
+    #[test]
+    fn aabb_collision_requires_overlap() {
+        let size = Vec2::new(40.0, 40.0);
+        assert!(upright().overlaps_box(Vec2::new(30.0, 0.0), size));
+        assert!(!upright().overlaps_box(Vec2::new(40.0, 0.0), size));
+    }
+
+    #[test]
+    fn circle_collision_uses_closest_point() {
+        // Touching a side of the square
+        assert!(upright().overlaps_circle(Vec2::new(25.0, 0.0), 11.0));
+        // Near a corner, but outside the circle
+        assert!(!upright().overlaps_circle(Vec2::new(22.0, 22.0), 9.0));
+        assert!(upright().overlaps_circle(Vec2::new(22.0, 22.0), 10.0));
+    }
+
+    #[test]
+    fn rotated_hitbox_follows_the_tilt() {
+        let tilted = BirdHitbox {
+            angle: std::f32::consts::FRAC_PI_4,
+            ..upright()
+        };
+        // A box off the square's corner: clear once the corner turns away...
+        let corner = Vec2::new(24.0, 24.0);
+        assert!(upright().overlaps_box(corner, Vec2::splat(20.0)));
+        assert!(!tilted.overlaps_box(corner, Vec2::splat(20.0)));
+        // ...while the turned corner now reaches a box straight ahead
+        let ahead = Vec2::new(28.0, 0.0);
+        assert!(!upright().overlaps_box(ahead, Vec2::splat(20.0)));
+        assert!(tilted.overlaps_box(ahead, Vec2::splat(20.0)));
+
+        assert!(!upright().overlaps_circle(ahead, 12.0));
+        assert!(tilted.overlaps_circle(ahead, 12.0));
+        assert!((tilted.bottom() + 15.0 * std::f32::consts::SQRT_2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn hitbox_follows_the_scale() {
+        // Squashed to 20 wide and stretched to 40 tall
+        let transform = Transform::from_scale(Vec3::new(2.0 / 3.0, 4.0 / 3.0, 1.0));
+        let hitbox = BirdHitbox::new(&transform, &PhysicsConfig::default());
+        assert!((hitbox.top() - 20.0).abs() < 1e-4);
+        assert!(hitbox.overlaps_box(Vec2::new(0.0, 28.0), Vec2::splat(20.0)));
+        assert!(!hitbox.overlaps_box(Vec2::new(22.0, 0.0), Vec2::splat(20.0)));
+
+        assert_eq!(
+            scaled_shape(CollisionShape::Circle(10.0), Vec3::new(1.5, -2.0, 1.0)),
+            CollisionShape::Circle(20.0)
+        );
+        assert_eq!(
+            scaled_shape(CollisionShape::Box(Vec2::new(10.0, 20.0)), Vec3::splat(0.5)),
+            CollisionShape::Box(Vec2::new(5.0, 10.0))
+        );
+    }
+
+    #[test]
+    fn hitbox_rotation_is_optional() {
+        let transform = Transform::from_rotation(Quat::from_rotation_z(-1.0));
+        let mut physics = PhysicsConfig::default();
+        assert_eq!(BirdHitbox::new(&transform, &physics).angle, 0.0);
+        physics.rotated_hitbox = true;
+        assert!((BirdHitbox::new(&transform, &physics).angle + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn clearance_measures_the_gap_between_shapes() {
+        let size = Vec2::new(40.0, 100.0);
+        // 10px above a pipe whose top is at y = 50
+        let bird = upright().bounds();
+        let clearance = aabb_clearance(Vec2::new(0.0, 75.0), bird, Vec2::ZERO, size);
+        assert!((clearance - 10.0).abs() < 1e-4);
+        assert_eq!(aabb_clearance(Vec2::ZERO, bird, Vec2::ZERO, size), 0.0);
+
+        let clearance = circle_clearance(Vec2::ZERO, bird, Vec2::new(30.0, 0.0), 10.0);
+        assert!((clearance - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn scaled_hitbox_follows_the_stretch() {
+        let transform = Transform::from_scale(Vec3::new(0.5, 1.5, 1.0));
+        let hitbox = BirdHitbox::new(&transform, &PhysicsConfig::default());
+        assert_eq!(hitbox.bounds(), Vec2::new(BIRD_SIZE * 0.5, BIRD_SIZE * 1.5));
+        // 10px above a pipe whose top is at y = 50 when upright, a bird
+        // stretched tall reaches 7.5px further down, so it clears it by 2.5px
+        let clearance = aabb_clearance(
+            Vec2::new(0.0, 75.0),
+            hitbox.bounds(),
+            Vec2::ZERO,
+            Vec2::new(40.0, 100.0),
+        );
+        assert!((clearance - (25.0 - BIRD_SIZE * 0.75)).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,139 @@
+//! Companion systems.
+//!
+//! Scoring 25 points unlocks a tiny companion that trails behind the bird on
+//! a spring, swaying as the bird flaps. It is purely cosmetic: it never
+//! collides with anything. It freezes where it was when the bird crashes and
+//! jumps back behind the bird when a new run starts. The companion setting
+//! hides it.
+
+use bevy::prelude::*;
+
+use crate::achievements::Achievement;
+use crate::components::{Bird, Companion};
+use crate::constants::{
+    COMPANION_COLOR, COMPANION_DAMPING, COMPANION_OFFSET_X, COMPANION_OFFSET_Y, COMPANION_SIZE,
+    COMPANION_STIFFNESS,
+};
+use crate::save::SaveData;
+use crate::settings::Settings;
+use crate::theme::color;
+
+/// Achievement that unlocks the companion.
+const COMPANION_UNLOCK: Achievement = Achievement::Score25;
+
+/// The companion flies just behind the bird.
+const COMPANION_Z: f32 = 0.9;
+
+/// Longest time step the spring takes at once, so a frame hitch can't make
+/// it overshoot wildly.
+const MAX_SPRING_STEP: f32 = 1.0 / 30.0;
+
+/// Spawns the companion once unlocked and enabled, and removes it when the
+/// player turns it off.
+pub fn update_companion_presence(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    save: Res<SaveData>,
+    bird_query: Query<&Transform, With<Bird>>,
+    companion_query: Query<Entity, With<Companion>>,
+) {
+    let wanted = settings.companion && save.achievements.contains(&COMPANION_UNLOCK);
+    if !wanted {
+        for entity in companion_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    if !companion_query.is_empty() {
+        return;
+    }
+
+    for transform in bird_query.iter() {
+        commands.spawn((
+            Sprite::from_color(color(COMPANION_COLOR), Vec2::splat(COMPANION_SIZE)),
+            Transform::from_translation(
+                rest_position(transform.translation.truncate()).extend(COMPANION_Z),
+            ),
+            Companion::default(),
+        ));
+    }
+}
+
+/// Pulls the companion toward its resting spot behind the bird.
+pub fn follow_bird(
+    time: Res<Time>,
+    bird_query: Query<&Transform, (With<Bird>, Without<Companion>)>,
+    mut companion_query: Query<(&mut Companion, &mut Transform)>,
+) {
+    let Ok(bird) = bird_query.single() else {
+        return;
+    };
+    let target = rest_position(bird.translation.truncate());
+    let dt = time.delta_secs().min(MAX_SPRING_STEP);
+
+    for (mut companion, mut transform) in companion_query.iter_mut() {
+        let (position, velocity) = spring_step(
+            transform.translation.truncate(),
+            companion.velocity,
+            target,
+            dt,
+        );
+        companion.velocity = velocity;
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+/// Puts the companion back behind the bird at the start of a run.
+pub fn reset_companion(
+    bird_query: Query<&Transform, (With<Bird>, Without<Companion>)>,
+    mut companion_query: Query<(&mut Companion, &mut Transform)>,
+) {
+    let Ok(bird) = bird_query.single() else {
+        return;
+    };
+    let target = rest_position(bird.translation.truncate());
+    for (mut companion, mut transform) in companion_query.iter_mut() {
+        companion.velocity = Vec2::ZERO;
+        transform.translation.x = target.x;
+        transform.translation.y = target.y;
+    }
+}
+
+/// Returns where the companion rests for a bird at `bird`.
+fn rest_position(bird: Vec2) -> Vec2 {
+    bird + Vec2::new(COMPANION_OFFSET_X, COMPANION_OFFSET_Y)
+}
+
+/// Advances a damped spring by `dt` seconds (semi-implicit Euler), returning
+/// the new position and velocity.
+fn spring_step(position: Vec2, velocity: Vec2, target: Vec2, dt: f32) -> (Vec2, Vec2) {
+    let acceleration = (target - position) * COMPANION_STIFFNESS - velocity * COMPANION_DAMPING;
+    let velocity = velocity + acceleration * dt;
+    (position + velocity * dt, velocity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spring_settles_on_the_target() {
+        let target = Vec2::new(-84.0, 116.0);
+        let (mut position, mut velocity) = (Vec2::ZERO, Vec2::ZERO);
+        for _ in 0..300 {
+            (position, velocity) = spring_step(position, velocity, target, 1.0 / 60.0);
+        }
+        assert!(position.distance(target) < 0.5);
+        assert!(velocity.length() < 1.0);
+    }
+
+    #[test]
+    fn spring_at_rest_stays_put() {
+        let target = Vec2::new(3.0, -7.0);
+        assert_eq!(
+            spring_step(target, Vec2::ZERO, target, 1.0 / 30.0),
+            (target, Vec2::ZERO)
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Game configuration systems.
+//!
+//! This module loads the `GameConfig` asset, applies it to the `GameConfig`
+//! resource, and reloads watched asset files when they change on disk.
+
+use bevy::prelude::*;
+
+#[cfg(feature = "tuning")]
+use crate::config::TUNED_CONFIG_PATH;
+use crate::config::{Difficulty, GAME_CONFIG_PATH, GameConfig, GameConfigHandle};
+use crate::hot_reload::AssetWatcher;
+#[cfg(feature = "tuning")]
+use crate::hot_reload::asset_root;
+use crate::mutators::Mutators;
+
+/// Starts loading the game configuration file and watches it for changes.
+///
+/// With the `tuning` feature, values saved from the tuning panel are loaded
+/// instead, if there are any.
+pub fn load_game_config(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut watcher: ResMut<AssetWatcher>,
+) {
+    let path = config_path();
+    commands.insert_resource(GameConfigHandle(asset_server.load(path)));
+    watcher.watch(path);
+}
+
+/// Returns the asset path of the configuration to load.
+fn config_path() -> &'static str {
+    #[cfg(feature = "tuning")]
+    {
+        if asset_root().join(TUNED_CONFIG_PATH).exists() {
+            return TUNED_CONFIG_PATH;
+        }
+    }
+    GAME_CONFIG_PATH
+}
+
+/// Copies the configuration asset into the `GameConfig` resource, scaled by
+/// the difficulty and changed by the mutators, whenever it finishes loading
+/// or is reloaded, or the mutators change.
+///
+/// Until the asset has loaded, the mutators apply to the defaults.
+pub fn apply_game_config(
+    mut asset_events: MessageReader<AssetEvent<GameConfig>>,
+    handle: Res<GameConfigHandle>,
+    configs: Res<Assets<GameConfig>>,
+    difficulty: Res<Difficulty>,
+    mutators: Res<Mutators>,
+    mut config: ResMut<GameConfig>,
+) {
+    let reloaded = asset_events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0));
+    if reloaded && let Some(loaded) = configs.get(&handle.0) {
+        *config = mutators.apply(difficulty.apply(loaded.clone()));
+        if let Some(path) = handle.0.path() {
+            info!("Applied game config from {path}");
+        }
+    } else if mutators.is_changed() && !mutators.is_added() {
+        let base = configs.get(&handle.0).cloned().unwrap_or_default();
+        *config = mutators.apply(difficulty.apply(base));
+        info!("Applied mutators [{}]", mutators.tag());
+    }
+}
+
+/// Registers every loaded asset of type `A` for hot reloading.
+pub fn watch_loaded_assets<A: Asset>(
+    mut asset_events: MessageReader<AssetEvent<A>>,
+    asset_server: Res<AssetServer>,
+    mut watcher: ResMut<AssetWatcher>,
+) {
+    for event in asset_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } = event
+            && let Some(path) = asset_server.get_path(*id)
+        {
+            watcher.watch(&path.to_string());
+        }
+    }
+}
+
+/// Reloads watched asset files that changed on disk.
+pub fn poll_watched_assets(
+    time: Res<Time>,
+    mut watcher: ResMut<AssetWatcher>,
+    asset_server: Res<AssetServer>,
+) {
+    if !watcher.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    for asset_path in watcher.changed_assets() {
+        info!("Reloading {asset_path}");
+        asset_server.reload(asset_path);
+    }
+}
@@ -0,0 +1,203 @@
+//! Developer console systems.
+//!
+//! Backtick toggles the console, which drops down from the top of the screen.
+//! While it is open, typed text goes to its command line instead of the game;
+//! Enter runs the command and Escape closes the console.
+
+use bevy::ecs::system::SystemParam;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::components::{ConsoleText, ConsoleUi};
+use crate::config::GameConfig;
+use crate::console::{CONSOLE_HELP, ConsoleCommand, DevConsole};
+use crate::patterns::{GapSpec, PipeColumn};
+use crate::resources::{GameViewport, Score};
+use crate::rng::GameRng;
+use crate::states::GameState;
+use crate::systems::pipes::spawn_pipe_column;
+use crate::theme::Theme;
+
+/// Height of the console panel.
+const CONSOLE_HEIGHT: f32 = 170.0;
+
+/// Distance between the console text and the panel edges.
+const MARGIN: f32 = 8.0;
+
+/// The console draws above everything else.
+const CONSOLE_Z: f32 = 9.5;
+
+/// Resources the console commands change.
+#[derive(SystemParam)]
+pub struct ConsoleTargets<'w, 's> {
+    commands: Commands<'w, 's>,
+    score: ResMut<'w, Score>,
+    next_state: ResMut<'w, NextState<GameState>>,
+    time: ResMut<'w, Time<Virtual>>,
+    rng: ResMut<'w, GameRng>,
+    viewport: Res<'w, GameViewport>,
+    config: Res<'w, GameConfig>,
+    theme: Res<'w, Theme>,
+}
+
+impl ConsoleTargets<'_, '_> {
+    /// Runs a command, returning the line to log.
+    fn run(&mut self, command: ConsoleCommand) -> String {
+        match command {
+            ConsoleCommand::Help => CONSOLE_HELP.to_string(),
+            ConsoleCommand::SetScore(score) => {
+                self.score.0 = score;
+                format!("score = {score}")
+            }
+            ConsoleCommand::State(state) => {
+                self.next_state.set(state);
+                format!("state = {state:?}")
+            }
+            ConsoleCommand::SpawnPipe(gap) => {
+                let column = PipeColumn {
+                    gaps: vec![GapSpec {
+                        y: None,
+                        size: Some(gap),
+                    }],
+                    spacing: 1.0,
+                };
+                // Drawn from the effects stream so the pipe sequence is untouched
+                spawn_pipe_column(
+                    &mut self.commands,
+                    &column,
+                    self.score.0,
+                    &self.viewport,
+                    &self.config,
+                    &self.theme,
+                    None,
+                    None,
+                    &mut self.rng.effects,
+                );
+                format!("spawned a pipe with a {gap} gap")
+            }
+            ConsoleCommand::Speed(speed) => {
+                self.time.set_relative_speed(speed);
+                format!("speed = {speed}")
+            }
+            ConsoleCommand::Seed(seed) => {
+                *self.rng = GameRng::from_seed(seed);
+                format!("seed = {seed}")
+            }
+        }
+    }
+}
+
+/// Spawns the (hidden) console panel.
+pub fn spawn_console(mut commands: Commands) {
+    commands
+        .spawn((
+            Sprite::from_color(Color::srgba(0.0, 0.0, 0.0, 0.8), Vec2::ONE),
+            Anchor::TOP_CENTER,
+            Transform::from_xyz(0.0, 0.0, CONSOLE_Z),
+            Visibility::Hidden,
+            ConsoleUi,
+        ))
+        .with_child((
+            Text2d::default(),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.6, 1.0, 0.6)),
+            Anchor::TOP_LEFT,
+            Transform::from_xyz(0.0, -MARGIN, 0.1),
+            ConsoleText,
+        ));
+}
+
+/// Toggles the console on backtick and, while it is open, edits and runs
+/// its command line.
+///
+/// Runs right after the input is read and clears it while the console is
+/// open, so typing doesn't flap the bird or navigate menus.
+pub fn handle_console_input(
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut key_events: MessageReader<KeyboardInput>,
+    mut console: ResMut<DevConsole>,
+    mut targets: ConsoleTargets,
+) {
+    let toggled = keyboard_input.just_pressed(KeyCode::Backquote);
+    if toggled {
+        console.open = !console.open;
+    }
+    if !console.open {
+        if toggled {
+            keyboard_input.reset_all();
+        }
+        key_events.clear();
+        return;
+    }
+
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Escape => console.open = false,
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                console.print(format!("> {line}"));
+                let result = match ConsoleCommand::parse(&line) {
+                    Ok(command) => targets.run(command),
+                    Err(error) => error,
+                };
+                for result_line in result.lines() {
+                    console.print(result_line);
+                }
+            }
+            _ => {
+                let text = event.text.as_deref().unwrap_or_default();
+                console
+                    .input
+                    .extend(text.chars().filter(|c| !c.is_control() && *c != '`'));
+            }
+        }
+    }
+    keyboard_input.reset_all();
+}
+
+/// Shows the console while it is open, across the top of the screen, with
+/// its log and command line.
+#[allow(clippy::type_complexity)]
+pub fn update_console(
+    console: Res<DevConsole>,
+    viewport: Res<GameViewport>,
+    mut panel_query: Query<(&mut Sprite, &mut Transform, &mut Visibility), With<ConsoleUi>>,
+    mut text_query: Query<(&mut Text2d, &mut Transform), (With<ConsoleText>, Without<ConsoleUi>)>,
+) {
+    let Ok((mut sprite, mut transform, mut visibility)) = panel_query.single_mut() else {
+        return;
+    };
+    if !console.open {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    }
+    visibility.set_if_neq(Visibility::Inherited);
+    sprite.custom_size = Some(Vec2::new(viewport.width, CONSOLE_HEIGHT));
+    transform.translation.y = viewport.half_height();
+
+    for (mut text, mut text_transform) in text_query.iter_mut() {
+        text_transform.translation.x = -viewport.half_width() + MARGIN;
+        let label = console_label(&console);
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
+/// Returns the console's log followed by its command line.
+fn console_label(console: &DevConsole) -> String {
+    let mut lines = console.log.clone();
+    lines.push(format!("> {}_", console.input));
+    lines.join("\n")
+}
@@ -0,0 +1,296 @@
+//! Controls screen systems.
+//!
+//! The controls screen, opened from the settings, lists the key and gamepad
+//! button of every game action. Activating a row listens for the next key
+//! (or gamepad button) and binds it, unless another action already uses it;
+//! Escape or the east button cancels. The first row switches between the
+//! control presets. The bindings are saved as soon as they change, and a row
+//! restores the defaults.
+
+use bevy::prelude::*;
+
+use crate::bindings::{
+    BoundButton, BoundInput, BoundKey, ControlPreset, ControlsRow, InputBindings, InputDevice,
+};
+use crate::components::{
+    ControlsRowText, ControlsStatusText, ControlsUi, FillScreen, Focusable, HitArea,
+    InstructionText, MenuButton, WorldButton,
+};
+use crate::input::{MenuAction, MenuInput};
+use crate::resources::{ButtonClicked, ControlsMenu, GameViewport};
+use crate::settings::Settings;
+use crate::states::GameState;
+use crate::systems::focus::{FOCUS_COLOR, step_focus};
+
+/// Vertical distance between controls rows.
+const ROW_SPACING: f32 = 34.0;
+
+/// Height of the first row on screen.
+const FIRST_ROW_Y: f32 = 115.0;
+
+/// Outcome of listening for a new binding this frame.
+enum Capture {
+    /// Nothing was pressed
+    Nothing,
+    /// The player cancelled
+    Cancelled,
+    /// A key or button that can't be bound was pressed
+    Unbindable(String),
+    /// A bindable key or button was pressed
+    Input(BoundInput),
+}
+
+/// Builds the controls screen and hides the menu text behind it.
+#[allow(clippy::type_complexity)]
+pub fn spawn_controls_menu(
+    mut commands: Commands,
+    viewport: Res<GameViewport>,
+    bindings: Res<InputBindings>,
+    settings: Res<Settings>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+) {
+    commands.insert_resource(ControlsMenu::default());
+
+    for mut visibility in menu_text_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+
+    // Dim the world behind the menu
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            custom_size: Some(Vec2::new(viewport.width * 2.0, viewport.height * 2.0)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        FillScreen,
+        ControlsUi,
+    ));
+
+    commands.spawn((
+        Text2d::new("Controls"),
+        TextFont {
+            font_size: 36.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, 175.0, 6.0),
+        ControlsUi,
+    ));
+
+    for (index, row) in ControlsRow::ALL.into_iter().enumerate() {
+        commands.spawn((
+            Text2d::new(row.label(&bindings, &settings, false)),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(0.0, FIRST_ROW_Y - index as f32 * ROW_SPACING, 6.0),
+            HitArea {
+                size: Vec2::new(viewport.width, ROW_SPACING),
+            },
+            WorldButton::default(),
+            Focusable::new(index as u32),
+            ControlsRowText(row),
+            ControlsUi,
+        ));
+    }
+
+    commands.spawn((
+        Text2d::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(FOCUS_COLOR),
+        Transform::from_xyz(
+            0.0,
+            FIRST_ROW_Y - ControlsRow::ALL.len() as f32 * ROW_SPACING,
+            6.0,
+        ),
+        ControlsStatusText,
+        ControlsUi,
+    ));
+}
+
+/// Removes the controls screen and restores the menu text.
+#[allow(clippy::type_complexity)]
+pub fn despawn_controls_menu(
+    mut commands: Commands,
+    ui_query: Query<Entity, With<ControlsUi>>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for mut visibility in menu_text_query.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Handles input on the controls screen.
+///
+/// Up/Down (or the d-pad) select a row, Space/Enter (or the south button)
+/// activate it, and Escape (or the east button) returns to the settings.
+/// Clicking or tapping a row activates it. While a row listens, the next key
+/// or gamepad button is bound to its action instead.
+#[allow(clippy::too_many_arguments)]
+pub fn navigate_controls(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    menu_input: MenuInput,
+    mut row_clicks: MessageReader<ButtonClicked<ControlsRowText>>,
+    mut bindings: ResMut<InputBindings>,
+    mut settings: ResMut<Settings>,
+    mut menu: ResMut<ControlsMenu>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let row_count = ControlsRow::ALL.len();
+
+    if menu.listening {
+        row_clicks.clear();
+        let Some(ControlsRow::Binding(action, device)) = ControlsRow::ALL.get(menu.selected) else {
+            menu.listening = false;
+            return;
+        };
+        match capture(*device, &keyboard_input, &gamepads) {
+            Capture::Nothing => {}
+            Capture::Cancelled => {
+                menu.listening = false;
+                menu.status.clear();
+            }
+            Capture::Unbindable(name) => {
+                menu.status = format!("{name} can't be bound, try another");
+            }
+            Capture::Input(input) => {
+                menu.listening = false;
+                menu.status = match bindings.rebind(*action, input) {
+                    Ok(()) => {
+                        bindings.store();
+                        format!("{} bound to {}", action.label(), input.label())
+                    }
+                    Err(other) => {
+                        format!("{} is already used by {}", input.label(), other.label())
+                    }
+                };
+            }
+        }
+        return;
+    }
+
+    if menu_input.just_pressed(MenuAction::Back) {
+        next_state.set(GameState::Settings);
+        return;
+    }
+    menu.selected = step_focus(menu.selected, menu_input.vertical_step(), row_count);
+
+    let mut activated =
+        keyboard_input.just_pressed(KeyCode::Space) || menu_input.just_pressed(MenuAction::Confirm);
+    for ButtonClicked(ControlsRowText(row)) in row_clicks.read() {
+        if let Some(index) = ControlsRow::ALL.iter().position(|other| other == row) {
+            menu.selected = index;
+            activated = true;
+        }
+    }
+    if !activated {
+        return;
+    }
+
+    match ControlsRow::ALL[menu.selected % row_count] {
+        ControlsRow::Preset => {
+            let preset = ControlPreset::next(ControlPreset::matching(&bindings, &settings));
+            menu.status = match preset.apply(&mut bindings, &mut settings) {
+                Ok(()) => format!("Controls set to {}", preset.label()),
+                Err(other) => format!(
+                    "{} set, but {} keeps the default flap input",
+                    preset.label(),
+                    other.label()
+                ),
+            };
+            bindings.store();
+            settings.touch();
+            settings.store();
+        }
+        ControlsRow::Binding(_, device) => {
+            menu.listening = true;
+            menu.status = match device {
+                InputDevice::Keyboard => "Press a key (Escape cancels)",
+                InputDevice::Gamepad => "Press a gamepad button (east button cancels)",
+            }
+            .to_string();
+        }
+        ControlsRow::Reset => {
+            *bindings = InputBindings::default();
+            bindings.store();
+            menu.status = "Controls reset to defaults".to_string();
+        }
+        ControlsRow::Back => next_state.set(GameState::Settings),
+    }
+}
+
+/// Refreshes row labels and the status line, and focuses the selected row.
+#[allow(clippy::type_complexity)]
+pub fn update_controls_rows(
+    menu: Res<ControlsMenu>,
+    bindings: Res<InputBindings>,
+    settings: Res<Settings>,
+    mut row_query: Query<(&ControlsRowText, &mut Text2d, &mut Focusable)>,
+    mut status_query: Query<&mut Text2d, (With<ControlsStatusText>, Without<ControlsRowText>)>,
+) {
+    for mut text in status_query.iter_mut() {
+        if text.0 != menu.status {
+            text.0.clone_from(&menu.status);
+        }
+    }
+
+    for (ControlsRowText(row), mut text, mut focusable) in row_query.iter_mut() {
+        let selected = ControlsRow::ALL.get(menu.selected) == Some(row);
+        let label = row.label(&bindings, &settings, selected && menu.listening);
+        if text.0 != label {
+            text.0 = label;
+        }
+        if focusable.focused != selected {
+            focusable.focused = selected;
+        }
+    }
+}
+
+/// Returns the key or gamepad button pressed this frame for a row of `device`.
+///
+/// Escape cancels either way; the east button cancels gamepad rows.
+fn capture(
+    device: InputDevice,
+    keyboard_input: &ButtonInput<KeyCode>,
+    gamepads: &Query<&Gamepad>,
+) -> Capture {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        return Capture::Cancelled;
+    }
+    match device {
+        InputDevice::Keyboard => {
+            let Some(key) = keyboard_input.get_just_pressed().next() else {
+                return Capture::Nothing;
+            };
+            BoundKey::new(*key).map_or_else(
+                || Capture::Unbindable(format!("{key:?}")),
+                |key| Capture::Input(BoundInput::Key(key)),
+            )
+        }
+        InputDevice::Gamepad => {
+            let Some(button) = gamepads
+                .iter()
+                .find_map(|gamepad| gamepad.get_just_pressed().next().copied())
+            else {
+                return Capture::Nothing;
+            };
+            if button == GamepadButton::East {
+                return Capture::Cancelled;
+            }
+            BoundButton::new(button).map_or_else(
+                || Capture::Unbindable(format!("{button:?}")),
+                |button| Capture::Input(BoundInput::Button(button)),
+            )
+        }
+    }
+}
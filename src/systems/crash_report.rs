@@ -0,0 +1,45 @@
+//! Crash report systems.
+//!
+//! This module places the running-session marker at startup, reports on the
+//! previous session if it ended abnormally (and reports are enabled), and
+//! removes the marker once the session may end without a report.
+
+use bevy::prelude::*;
+use bevy::window::AppLifecycle;
+
+use crate::crash_report::{ReportSink, SessionMarker, build_report, send_report};
+
+/// Marks this session as running, and sends a report on the previous one if
+/// it never finished cleanly and the player opted in via the environment.
+pub fn init_crash_reports(mut commands: Commands) {
+    let (marker, previous) = SessionMarker::begin();
+    commands.insert_resource(marker);
+
+    let Some(started) = previous else {
+        return;
+    };
+    warn!("The previous session didn't exit cleanly");
+    if let Some(sink) = ReportSink::from_env() {
+        send_report(sink, build_report(started));
+    }
+}
+
+/// Clears the marker on a clean exit or when the app is backgrounded (the OS
+/// may then end it at any time), and places it again on resume.
+pub fn track_clean_exit(
+    mut exit_events: MessageReader<AppExit>,
+    mut lifecycle_events: MessageReader<AppLifecycle>,
+    mut marker: ResMut<SessionMarker>,
+) {
+    for event in lifecycle_events.read() {
+        match event {
+            AppLifecycle::WillSuspend | AppLifecycle::Suspended => marker.clear(),
+            AppLifecycle::WillResume | AppLifecycle::Running => marker.place(),
+            AppLifecycle::Idle => {}
+        }
+    }
+    // Errors (such as quitting from the crash screen) keep the marker
+    if exit_events.read().any(AppExit::is_success) {
+        marker.clear();
+    }
+}
@@ -0,0 +1,133 @@
+//! Build information and debug overlay systems.
+//!
+//! The menu shows the game's version, git hash, and build profile in its
+//! bottom-right corner. F3 toggles a debug overlay in the top-left corner
+//! with the frame rate, the same build information, and the latest log lines.
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::build_info::build_label;
+use crate::components::{DebugOverlay, VersionText};
+use crate::constants::GROUND_HEIGHT;
+use crate::logging::RecentLogs;
+use crate::resources::GameViewport;
+use crate::states::GameState;
+
+/// Distance between the build information text and the screen edges.
+const MARGIN: f32 = 8.0;
+
+/// Number of log lines shown on the debug overlay.
+const OVERLAY_LOG_LINES: usize = 12;
+
+/// Spawns the menu's build information and the (hidden) debug overlay.
+pub fn spawn_build_info(mut commands: Commands, viewport: Res<GameViewport>) {
+    commands.spawn((
+        Text2d::new(build_label()),
+        TextFont {
+            font_size: 12.0,
+            ..default()
+        },
+        TextColor(Color::srgba(1.0, 1.0, 1.0, 0.6)),
+        Anchor::BOTTOM_RIGHT,
+        Transform::from_translation(version_position(&viewport).extend(2.0)),
+        VersionText,
+    ));
+
+    commands.spawn((
+        Text2d::default(),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.6, 1.0, 0.6)),
+        Anchor::TOP_LEFT,
+        Transform::from_translation(overlay_position(&viewport).extend(9.0)),
+        Visibility::Hidden,
+        DebugOverlay,
+    ));
+}
+
+/// Returns the position of the menu's build information, above the ground in
+/// the bottom-right corner.
+fn version_position(viewport: &GameViewport) -> Vec2 {
+    Vec2::new(
+        viewport.half_width() - MARGIN,
+        -viewport.half_height() + GROUND_HEIGHT + MARGIN,
+    )
+}
+
+/// Returns the position of the debug overlay, in the top-left corner.
+fn overlay_position(viewport: &GameViewport) -> Vec2 {
+    Vec2::new(
+        -viewport.half_width() + MARGIN,
+        viewport.half_height() - MARGIN,
+    )
+}
+
+/// Shows the build information on the menu only and keeps both texts in
+/// their corners when the window is resized.
+#[allow(clippy::type_complexity)]
+pub fn update_build_info(
+    state: Res<State<GameState>>,
+    viewport: Res<GameViewport>,
+    mut version_query: Query<(&mut Transform, &mut Visibility), With<VersionText>>,
+    mut overlay_query: Query<&mut Transform, (With<DebugOverlay>, Without<VersionText>)>,
+) {
+    for (mut transform, mut visibility) in version_query.iter_mut() {
+        visibility.set_if_neq(if *state.get() == GameState::Menu {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        if viewport.is_changed() {
+            transform.translation = version_position(&viewport).extend(2.0);
+        }
+    }
+    if viewport.is_changed() {
+        for mut transform in overlay_query.iter_mut() {
+            transform.translation = overlay_position(&viewport).extend(9.0);
+        }
+    }
+}
+
+/// Toggles the debug overlay when F3 is pressed.
+pub fn toggle_debug_overlay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut overlay_query: Query<&mut Visibility, With<DebugOverlay>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F3) {
+        return;
+    }
+    for mut visibility in overlay_query.iter_mut() {
+        *visibility = if *visibility == Visibility::Hidden {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Refreshes the frame rate and log lines shown on the debug overlay.
+pub fn update_debug_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    logs: Option<Res<RecentLogs>>,
+    mut overlay_query: Query<(&mut Text2d, &Visibility), With<DebugOverlay>>,
+) {
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or_default();
+    for (mut text, visibility) in overlay_query.iter_mut() {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        let mut lines = vec![format!("{fps:.0} FPS"), build_label()];
+        if let Some(logs) = &logs {
+            lines.push(String::new());
+            lines.extend(logs.last(OVERLAY_LOG_LINES));
+        }
+        text.0 = lines.join("\n");
+    }
+}
@@ -2,42 +2,52 @@
 //!
 //! This module handles all visual feedback effects:
 //! - Screen shake
+//! - Camera zoom
+//! - Camera rig composing the shake and zoom
 //! - Screen flash
 //! - Particle effects
 //! - Score pop animation
+//! - Floating score popups
 //! - Bird squash/stretch animation
+//!
+//! The score pop, squash/stretch, and death effects are observers on the
+//! score, flap, and death events, so they start the same frame the event is
+//! triggered.
 
+use bevy::camera::Projection;
 use bevy::prelude::*;
 
 use crate::components::{
-    Bird, BirdSquashStretch, DeathParticle, EdgeFlash, FlapParticle, MainCamera, Particle,
-    ScorePopAnimation, ScoreText, ScreenFlash,
+    Bird, BirdSquashStretch, CameraRig, DeathParticle, EdgeFlash, Emissive, FlapParticle,
+    MainCamera, Obstacle, Particle, Pipe, PipePair, ScorePopAnimation, ScorePopup, ScoreText,
+    ScreenFlash,
 };
+use crate::config::GameConfig;
 use crate::constants::{
-    BIRD_SIZE, DEATH_FLASH_ALPHA, DEATH_FLASH_COLOR, DEATH_FLASH_DURATION, DEATH_PARTICLE_COLORS,
-    DEATH_PARTICLE_COUNT, DEATH_PARTICLE_LIFETIME, DEATH_PARTICLE_SIZE_MAX,
-    DEATH_PARTICLE_SIZE_MIN, DEATH_PARTICLE_SPEED, FLAP_PARTICLE_COLOR, FLAP_PARTICLE_COUNT_MAX,
-    FLAP_PARTICLE_COUNT_MIN, FLAP_PARTICLE_LIFETIME, FLAP_PARTICLE_SIZE_MAX,
-    FLAP_PARTICLE_SIZE_MIN, FLAP_SQUASH_DURATION, FLAP_SQUASH_SCALE, FLAP_STRETCH_SCALE,
-    SCORE_FLASH_ALPHA, SCORE_FLASH_COLOR, SCORE_FLASH_DURATION, SCORE_POP_DURATION,
-    SCORE_POP_SCALE, SCREEN_SHAKE_DURATION, SCREEN_SHAKE_FREQUENCY, SCREEN_SHAKE_INTENSITY,
-    WORLD_SCROLL_SPEED,
+    BIRD_SIZE, DEATH_PARTICLE_COLORS, DEATH_PARTICLE_SIZE_MAX, DEATH_PARTICLE_SIZE_MIN,
+    FLAP_PARTICLE_SIZE_MAX, FLAP_PARTICLE_SIZE_MIN, SCORE_FLASH_GLOW, SCORE_POPUP_FONT_SIZE,
 };
 use crate::resources::{
-    DeathEvent, EdgeFlashState, FlapEvent, ScoreEvent, ScreenFlashState, ScreenShake,
+    CameraZoom, DeathCause, DeathEvent, EdgeFlashState, FlapEvent, GraphicsSettings, PipeShattered,
+    ScoreEvent, ScreenFlashState, ScreenShake,
 };
+use crate::rng::GameRng;
+use crate::settings::Settings;
 use crate::states::GameState;
-use crate::utils::rand_f32;
+use crate::theme::{Rgb, Theme, color};
 
 // ============================================================================
 // SCREEN SHAKE SYSTEM
 // ============================================================================
 
-/// Updates the screen shake effect and applies it to the camera.
+/// Updates the screen shake effect and applies it to the camera rig's shake
+/// layer.
 pub fn update_screen_shake(
     time: Res<Time>,
     mut shake: ResMut<ScreenShake>,
-    mut camera_query: Query<&mut Transform, With<MainCamera>>,
+    mut game_rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mut rig_query: Query<&mut CameraRig, With<MainCamera>>,
 ) {
     if !shake.is_active() {
         return;
@@ -48,24 +58,72 @@ pub fn update_screen_shake(
     shake.duration -= dt;
 
     // Calculate shake offset using sine waves for smooth motion
-    let decay = (shake.duration / SCREEN_SHAKE_DURATION).max(0.0);
-    let offset_x =
-        (shake.elapsed * SCREEN_SHAKE_FREQUENCY).sin() * shake.intensity * decay * rand_f32();
-    let offset_y =
-        (shake.elapsed * SCREEN_SHAKE_FREQUENCY * 1.3).cos() * shake.intensity * decay * rand_f32();
-
-    for mut transform in camera_query.iter_mut() {
-        if shake.duration > 0.0 {
-            transform.translation.x = offset_x;
-            transform.translation.y = offset_y;
+    let effects = &config.effects;
+    let decay = (shake.duration / effects.screen_shake_duration).max(0.0);
+    let rng = &mut game_rng.effects;
+    let frequency = effects.screen_shake_frequency;
+    let offset_x = (shake.elapsed * frequency).sin() * shake.intensity * decay * rng.f32();
+    let offset_y = (shake.elapsed * frequency * 1.3).cos() * shake.intensity * decay * rng.f32();
+
+    for mut rig in rig_query.iter_mut() {
+        rig.shake_offset = if shake.duration > 0.0 {
+            Vec2::new(offset_x, offset_y)
         } else {
-            // Reset camera position when shake ends
-            transform.translation.x = 0.0;
-            transform.translation.y = 0.0;
+            // Clear the layer when the shake ends
+            Vec2::ZERO
+        };
+    }
+}
+
+// ============================================================================
+// CAMERA ZOOM SYSTEM
+// ============================================================================
+
+/// Updates the camera zoom and applies it to the camera rig's zoom layer,
+/// clearing it once the zoom ends.
+pub fn update_camera_zoom(
+    time: Res<Time>,
+    mut zoom: ResMut<CameraZoom>,
+    mut rig_query: Query<&mut CameraRig, With<MainCamera>>,
+) {
+    if zoom.duration <= 0.0 {
+        return;
+    }
+    zoom.elapsed += time.delta_secs();
+    if !zoom.is_active() {
+        *zoom = CameraZoom::default();
+    }
+
+    for mut rig in rig_query.iter_mut() {
+        rig.zoom_offset = zoom.offset();
+        rig.scale = zoom.scale();
+    }
+}
+
+// ============================================================================
+// CAMERA RIG
+// ============================================================================
+
+/// Composes the camera rig's layers into the main camera's position and
+/// projection scale.
+pub fn apply_camera_rig(
+    mut camera_query: Query<(&CameraRig, &mut Transform, &mut Projection), Changed<CameraRig>>,
+) {
+    for (rig, mut transform, mut projection) in camera_query.iter_mut() {
+        let translation = rig.translation();
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+        if let Projection::Orthographic(ref mut ortho) = *projection {
+            ortho.scale = rig.scale;
         }
     }
 }
 
+/// Zooms the camera back out when leaving the game over screen.
+pub fn release_camera_zoom(mut zoom: ResMut<CameraZoom>) {
+    zoom.release();
+}
+
 // ============================================================================
 // SCREEN FLASH SYSTEM
 // ============================================================================
@@ -131,6 +189,7 @@ pub fn update_particles(
 ) {
     let dt = time.delta_secs();
     let is_playing = *game_state.get() == GameState::Playing;
+    let _span = info_span!("update_particles", particles = query.iter().len()).entered();
 
     for (entity, mut particle, mut transform) in query.iter_mut() {
         // Update lifetime
@@ -174,29 +233,40 @@ pub fn update_particles(
 
 /// Spawns flap particles when the player flaps - creates a small cloud puff effect.
 /// Particles spawn at the flap position and drift gently, fading away.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_flap_particles(
     mut commands: Commands,
     mut flap_events: MessageReader<FlapEvent>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut game_rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    graphics: Res<GraphicsSettings>,
+    theme: Res<Theme>,
 ) {
+    let rng = &mut game_rng.particles;
+    let effects = &config.effects;
+
     for event in flap_events.read() {
         // Spawn position is fixed at where the flap happened
         let flap_pos = event.position;
 
         // Randomize particle count
-        let particle_count = FLAP_PARTICLE_COUNT_MIN
-            + (rand_f32() * (FLAP_PARTICLE_COUNT_MAX - FLAP_PARTICLE_COUNT_MIN + 1) as f32) as u32;
+        let particle_count = graphics.particles(rng.range_u32(
+            effects.flap_particle_count_min,
+            effects.flap_particle_count_max,
+        ));
+        let _span = info_span!("spawn_flap_particles", count = particle_count).entered();
 
         // Random base direction for this flap's particles (all particles offset from this)
-        let base_angle = rand_f32() * std::f32::consts::TAU;
+        let base_angle = rng.angle();
 
         for i in 0..particle_count {
             // Spread circles evenly around the base angle with some randomness
             let angle = base_angle
                 + (i as f32 / particle_count as f32) * std::f32::consts::TAU
-                + (rand_f32() - 0.5) * 0.6; // Random jitter
-            let offset_distance = 5.0 + rand_f32() * 8.0;
+                + (rng.f32() - 0.5) * 0.6; // Random jitter
+            let offset_distance = 5.0 + rng.f32() * 8.0;
 
             let spawn_offset = Vec3::new(
                 angle.cos() * offset_distance,
@@ -205,20 +275,19 @@ pub fn spawn_flap_particles(
             );
 
             // Gentle drift velocity - small outward drift for cloud effect
-            let drift_speed = 10.0 + rand_f32() * 15.0;
+            let drift_speed = 10.0 + rng.f32() * 15.0;
             let velocity = Vec2::new(
-                angle.cos() * drift_speed + (rand_f32() - 0.5) * 8.0,
+                angle.cos() * drift_speed + (rng.f32() - 0.5) * 8.0,
                 angle.sin() * drift_speed - 3.0, // Slight downward drift
             );
 
             // World velocity - moves with the world (pipes), stops on game over
-            let world_velocity = Vec2::new(-WORLD_SCROLL_SPEED, 0.0);
+            let world_velocity = Vec2::new(-config.pipes.scroll_speed, 0.0);
 
             // Vary sizes
-            let size = FLAP_PARTICLE_SIZE_MIN
-                + rand_f32() * (FLAP_PARTICLE_SIZE_MAX - FLAP_PARTICLE_SIZE_MIN);
+            let size = rng.range(FLAP_PARTICLE_SIZE_MIN, FLAP_PARTICLE_SIZE_MAX);
 
-            let (r, g, b) = FLAP_PARTICLE_COLOR;
+            let (r, g, b) = theme.flap_particle_color;
 
             // Create a circle mesh
             let circle = Circle::new(size / 2.0);
@@ -233,8 +302,9 @@ pub fn spawn_flap_particles(
                 Particle {
                     velocity,
                     world_velocity,
-                    lifetime: FLAP_PARTICLE_LIFETIME * (0.7 + rand_f32() * 0.3),
-                    initial_lifetime: FLAP_PARTICLE_LIFETIME,
+                    lifetime: graphics.lifetime(effects.flap_particle_lifetime)
+                        * (0.7 + rng.f32() * 0.3),
+                    initial_lifetime: graphics.lifetime(effects.flap_particle_lifetime),
                 },
                 FlapParticle,
             ));
@@ -243,39 +313,105 @@ pub fn spawn_flap_particles(
 }
 
 /// Spawns death particles when the player dies.
-pub fn spawn_death_particles(mut commands: Commands, mut death_events: MessageReader<DeathEvent>) {
+pub fn spawn_death_particles(
+    mut commands: Commands,
+    mut death_events: MessageReader<DeathEvent>,
+    mut game_rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    graphics: Res<GraphicsSettings>,
+    theme: Res<Theme>,
+) {
+    let count = graphics.particles(config.effects.death_particle_count);
     for event in death_events.read() {
-        let base_pos = event.position;
-
-        for _ in 0..DEATH_PARTICLE_COUNT {
-            // Random angle in all directions
-            let angle = rand_f32() * std::f32::consts::TAU;
-            let speed = DEATH_PARTICLE_SPEED * (0.3 + rand_f32() * 0.7);
-
-            let velocity = Vec2::new(angle.cos() * speed, angle.sin() * speed * 1.5); // More upward momentum
-            let size = DEATH_PARTICLE_SIZE_MIN
-                + rand_f32() * (DEATH_PARTICLE_SIZE_MAX - DEATH_PARTICLE_SIZE_MIN);
+        spawn_particle_burst(
+            &mut commands,
+            &mut game_rng,
+            &config,
+            &graphics,
+            event.position,
+            count,
+            &theme.death_particle_colors,
+            // Bounce off the ceiling instead of flying through it
+            event.cause == DeathCause::Ceiling,
+        );
+    }
+}
 
-            // Random color from death particle colors
-            let color_idx = (rand_f32() * DEATH_PARTICLE_COLORS.len() as f32) as usize;
-            let (r, g, b) = DEATH_PARTICLE_COLORS[color_idx.min(DEATH_PARTICLE_COLORS.len() - 1)];
+/// Spawns death-style particles in the pipe color where a pipe shatters.
+pub fn spawn_shatter_particles(
+    mut commands: Commands,
+    mut shatter_events: MessageReader<PipeShattered>,
+    mut game_rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    graphics: Res<GraphicsSettings>,
+    theme: Res<Theme>,
+) {
+    let count = graphics.particles(config.effects.death_particle_count / 2);
+    for event in shatter_events.read() {
+        spawn_particle_burst(
+            &mut commands,
+            &mut game_rng,
+            &config,
+            &graphics,
+            event.position,
+            count,
+            &[theme.pipe_color],
+            false,
+        );
+    }
+}
 
-            commands.spawn((
-                Sprite {
-                    color: Color::srgb(r, g, b),
-                    custom_size: Some(Vec2::splat(size)),
-                    ..default()
-                },
-                Transform::from_translation(base_pos + Vec3::new(0.0, 0.0, 2.0)),
-                Particle {
-                    velocity,
-                    world_velocity: Vec2::ZERO, // Death particles don't move with world
-                    lifetime: DEATH_PARTICLE_LIFETIME * (0.6 + rand_f32() * 0.4),
-                    initial_lifetime: DEATH_PARTICLE_LIFETIME,
-                },
-                DeathParticle,
-            ));
+/// Spawns a burst of death-style particles flying out in all directions.
+///
+/// Each particle gets a random color from `colors`; `downward` sends them all
+/// down, away from the ceiling.
+#[allow(clippy::too_many_arguments)]
+fn spawn_particle_burst(
+    commands: &mut Commands,
+    game_rng: &mut GameRng,
+    config: &GameConfig,
+    graphics: &GraphicsSettings,
+    position: Vec3,
+    count: u32,
+    colors: &[Rgb],
+    downward: bool,
+) {
+    let _span = info_span!("spawn_particle_burst", count).entered();
+    let rng = &mut game_rng.particles;
+    let effects = &config.effects;
+
+    for _ in 0..count {
+        // Random angle in all directions
+        let angle = rng.angle();
+        let speed = effects.death_particle_speed * (0.3 + rng.f32() * 0.7);
+
+        let mut velocity = Vec2::new(angle.cos() * speed, angle.sin() * speed * 1.5); // More upward momentum
+        if downward {
+            velocity.y = -velocity.y.abs();
         }
+        let size = rng.range(DEATH_PARTICLE_SIZE_MIN, DEATH_PARTICLE_SIZE_MAX);
+
+        let rgb = rng
+            .choose(colors)
+            .copied()
+            .unwrap_or(DEATH_PARTICLE_COLORS[0]);
+
+        commands.spawn((
+            Sprite {
+                color: color(rgb),
+                custom_size: Some(Vec2::splat(size)),
+                ..default()
+            },
+            Transform::from_translation(position + Vec3::new(0.0, 0.0, 2.0)),
+            Particle {
+                velocity,
+                world_velocity: Vec2::ZERO, // Death particles don't move with world
+                lifetime: graphics.lifetime(effects.death_particle_lifetime)
+                    * (0.6 + rng.f32() * 0.4),
+                initial_lifetime: graphics.lifetime(effects.death_particle_lifetime),
+            },
+            DeathParticle,
+        ));
     }
 }
 
@@ -283,19 +419,18 @@ pub fn spawn_death_particles(mut commands: Commands, mut death_events: MessageRe
 // SCORE POP ANIMATION
 // ============================================================================
 
-/// Triggers score pop animation when score event occurs.
+/// Observer starting the score pop animation when a score event occurs.
 pub fn trigger_score_pop(
-    mut score_events: MessageReader<ScoreEvent>,
+    _score: On<ScoreEvent>,
     mut commands: Commands,
+    config: Res<GameConfig>,
     query: Query<Entity, With<ScoreText>>,
 ) {
-    for _ in score_events.read() {
-        for entity in query.iter() {
-            commands.entity(entity).insert(ScorePopAnimation {
-                timer: 0.0,
-                duration: SCORE_POP_DURATION,
-            });
-        }
+    for entity in query.iter() {
+        commands.entity(entity).insert(ScorePopAnimation {
+            timer: 0.0,
+            duration: config.effects.score_pop_duration,
+        });
     }
 }
 
@@ -303,22 +438,86 @@ pub fn trigger_score_pop(
 pub fn update_score_pop(
     time: Res<Time>,
     mut commands: Commands,
-    mut query: Query<(Entity, &mut ScorePopAnimation, &mut Transform), With<ScoreText>>,
+    config: Res<GameConfig>,
+    mut query: Query<(Entity, &mut ScorePopAnimation, &mut UiTransform), With<ScoreText>>,
 ) {
     for (entity, mut anim, mut transform) in query.iter_mut() {
         anim.timer += time.delta_secs();
 
         if anim.timer >= anim.duration {
             // Animation complete, reset scale and remove component
-            transform.scale = Vec3::ONE;
+            transform.scale = Vec2::ONE;
             commands.entity(entity).remove::<ScorePopAnimation>();
         } else {
             // Calculate bounce scale using sine wave
             let progress = anim.timer / anim.duration;
             // Quick scale up, then ease back down
-            let scale = 1.0 + (progress * std::f32::consts::PI).sin() * (SCORE_POP_SCALE - 1.0);
-            transform.scale = Vec3::splat(scale);
+            let scale = 1.0
+                + (progress * std::f32::consts::PI).sin() * (config.effects.score_pop_scale - 1.0);
+            transform.scale = Vec2::splat(scale);
+        }
+    }
+}
+
+// ============================================================================
+// SCORE POPUP
+// ============================================================================
+
+/// Spawns a floating "+1" at the gap that was just scored.
+///
+/// The popup is parented to the scored pipe pair so it scrolls with the
+/// world and is cleaned up together with the pair.
+pub fn spawn_score_popups(
+    mut score_events: MessageReader<ScoreEvent>,
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    pair_query: Query<&Transform, With<PipePair>>,
+) {
+    for event in score_events.read() {
+        let Ok(pair_transform) = pair_query.get(event.pipe) else {
+            continue;
+        };
+
+        // Offset from the pair center to the gap center, in the pair's local space
+        let local_y = event.gap_position.y - pair_transform.translation.y;
+
+        commands.entity(event.pipe).with_child((
+            Text2d::new("+1"),
+            TextFont {
+                font_size: SCORE_POPUP_FONT_SIZE,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(0.0, local_y, 2.0),
+            ScorePopup {
+                timer: 0.0,
+                duration: config.effects.score_popup_duration,
+            },
+            Emissive::new(SCORE_FLASH_GLOW),
+        ));
+    }
+}
+
+/// Floats score popups upward while fading them out.
+pub fn update_score_popups(
+    time: Res<Time>,
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut TextColor)>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut popup, mut transform, mut color) in query.iter_mut() {
+        popup.timer += dt;
+
+        if popup.timer >= popup.duration {
+            commands.entity(entity).despawn();
+            continue;
         }
+
+        let progress = popup.timer / popup.duration;
+        transform.translation.y += config.effects.score_popup_rise / popup.duration * dt;
+        color.0 = Color::srgba(1.0, 1.0, 1.0, 1.0 - progress * progress);
     }
 }
 
@@ -326,17 +525,20 @@ pub fn update_score_pop(
 // BIRD SQUASH/STRETCH ANIMATION
 // ============================================================================
 
-/// Triggers bird squash/stretch animation on flap.
+/// Observer starting the bird squash/stretch animation on flap, unless
+/// turned off in the settings.
 pub fn trigger_bird_squash(
-    mut flap_events: MessageReader<FlapEvent>,
+    _flap: On<FlapEvent>,
     mut commands: Commands,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
     query: Query<Entity, With<Bird>>,
 ) {
-    for _ in flap_events.read() {
+    if settings.squash_stretch {
         for entity in query.iter() {
             commands.entity(entity).insert(BirdSquashStretch {
                 timer: 0.0,
-                duration: FLAP_SQUASH_DURATION,
+                duration: config.effects.flap_squash_duration,
                 is_squash: true,
             });
         }
@@ -347,6 +549,7 @@ pub fn trigger_bird_squash(
 pub fn update_bird_squash(
     time: Res<Time>,
     mut commands: Commands,
+    config: Res<GameConfig>,
     mut query: Query<(Entity, &mut BirdSquashStretch, &mut Sprite, &mut Transform), With<Bird>>,
 ) {
     for (entity, mut anim, mut sprite, mut transform) in query.iter_mut() {
@@ -355,7 +558,7 @@ pub fn update_bird_squash(
         if anim.timer >= anim.duration {
             // Animation complete, reset size and scale
             sprite.custom_size = Some(Vec2::splat(BIRD_SIZE));
-            transform.scale = Vec3::ONE;
+            transform.scale = Vec3::new(config.physics.bird_scale, config.physics.bird_scale, 1.0);
             commands.entity(entity).remove::<BirdSquashStretch>();
         } else {
             let progress = anim.timer / anim.duration;
@@ -368,12 +571,13 @@ pub fn update_bird_squash(
             let deform_amount = 1.0 - elastic_ease;
 
             // Calculate squash (horizontal compress) and stretch (vertical expand)
-            let squash = 1.0 + (FLAP_SQUASH_SCALE - 1.0) * deform_amount;
-            let stretch = 1.0 + (FLAP_STRETCH_SCALE - 1.0) * deform_amount;
+            let squash = 1.0 + (config.effects.flap_squash_scale - 1.0) * deform_amount;
+            let stretch = 1.0 + (config.effects.flap_stretch_scale - 1.0) * deform_amount;
 
             // Apply the deformation via transform scale for smoother look
             // This gives a more organic curved appearance
-            transform.scale = Vec3::new(squash, stretch, 1.0);
+            let scale = config.physics.bird_scale;
+            transform.scale = Vec3::new(squash * scale, stretch * scale, 1.0);
         }
     }
 }
@@ -398,24 +602,72 @@ fn elastic_out(t: f32) -> f32 {
 // EFFECT TRIGGER SYSTEMS
 // ============================================================================
 
-/// Triggers death effects (shake, flash, particles).
+/// Observer triggering the death effects (shake, zoom toward the bird, flash,
+/// hit pipe or obstacle tint).
+///
+/// Shake and flash can each be turned off in the settings; reduced motion
+/// turns off the shake and the zoom.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn trigger_death_effects(
-    mut death_events: MessageReader<DeathEvent>,
+    death: On<DeathEvent>,
     mut shake: ResMut<ScreenShake>,
+    mut zoom: ResMut<CameraZoom>,
     mut flash: ResMut<ScreenFlashState>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
+    mut pipe_query: Query<&mut Sprite, Or<(With<Pipe>, With<Obstacle>)>>,
 ) {
-    for _ in death_events.read() {
-        shake.trigger(SCREEN_SHAKE_DURATION, SCREEN_SHAKE_INTENSITY);
-        flash.trigger(DEATH_FLASH_DURATION, DEATH_FLASH_COLOR, DEATH_FLASH_ALPHA);
+    let effects = &config.effects;
+    if settings.screen_shake && !settings.reduced_motion {
+        shake.trigger(
+            effects.screen_shake_duration,
+            effects.screen_shake_intensity,
+        );
+    }
+    if !settings.reduced_motion {
+        zoom.zoom_to(
+            death.position.truncate(),
+            effects.death_zoom,
+            effects.death_zoom_duration,
+        );
+    }
+    if settings.screen_flash {
+        flash.trigger(
+            effects.death_flash_duration,
+            theme.death_flash_color,
+            effects.death_flash_alpha,
+        );
+    }
+
+    // Highlight the pipe or obstacle the bird crashed into
+    if let DeathCause::Pipe { entity } | DeathCause::Obstacle { entity } = death.cause
+        && let Ok(mut sprite) = pipe_query.get_mut(entity)
+    {
+        sprite.color = color(theme.pipe_hit_color);
     }
 }
 
-/// Triggers score effects (edge flash).
+/// Triggers score effects (edge flash and zoom punch), unless turned off in
+/// the settings (the punch follows the reduced motion setting).
 pub fn trigger_score_effects(
     mut score_events: MessageReader<ScoreEvent>,
     mut flash: ResMut<EdgeFlashState>,
+    mut zoom: ResMut<CameraZoom>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    theme: Res<Theme>,
 ) {
-    for _ in score_events.read() {
-        flash.trigger(SCORE_FLASH_DURATION, SCORE_FLASH_COLOR, SCORE_FLASH_ALPHA);
+    let effects = &config.effects;
+    let scored = score_events.read().count() > 0;
+    if scored && settings.edge_flash {
+        flash.trigger(
+            effects.score_flash_duration,
+            theme.score_flash_color,
+            effects.score_flash_alpha,
+        );
+    }
+    if scored && !settings.reduced_motion {
+        zoom.punch(effects.score_zoom, effects.score_zoom_duration);
     }
 }
@@ -0,0 +1,14 @@
+//! Event forwarding.
+//!
+//! Flap, score, and death events are triggered with `Commands::trigger`, so
+//! the effects observing them (see `effects`) fire the same frame. Each one
+//! is also forwarded as a message for the systems that read them in the
+//! schedule, such as achievements, telemetry, and haptics.
+
+use bevy::ecs::message::Message;
+use bevy::prelude::*;
+
+/// Writes a triggered event as a message.
+pub fn forward_message<E: Event + Message + Clone>(event: On<E>, mut messages: MessageWriter<E>) {
+    messages.write(event.event().clone());
+}
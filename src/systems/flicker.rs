@@ -0,0 +1,64 @@
+//! Flicker mode systems.
+//!
+//! In flicker mode, a challenge turned on from the settings, pipes fade out
+//! as they get close to the bird, so the player has to remember where the
+//! gap was. Pipes show again once the run is over. Flicker runs have their
+//! own best score (see `ScoreCategory`).
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, Pipe, PipePair};
+use crate::constants::{FLICKER_FADE_END, FLICKER_FADE_START};
+use crate::settings::Settings;
+use crate::states::GameState;
+
+/// Fades the pipes near the bird while a flicker run is being played, and
+/// shows every pipe otherwise.
+pub fn fade_pipes_near_bird(
+    settings: Res<Settings>,
+    state: Res<State<GameState>>,
+    bird_query: Query<&Transform, With<Bird>>,
+    pair_query: Query<(&Transform, &Children), With<PipePair>>,
+    mut pipe_query: Query<&mut Sprite, With<Pipe>>,
+) {
+    let active = settings.flicker_mode && *state.get() == GameState::Playing;
+    let bird_x = bird_query
+        .iter()
+        .next()
+        .map_or(0.0, |transform| transform.translation.x);
+
+    for (transform, children) in pair_query.iter() {
+        let alpha = if active {
+            flicker_alpha((transform.translation.x - bird_x).abs())
+        } else {
+            1.0
+        };
+        let mut pipes = pipe_query.iter_many_mut(children);
+        while let Some(mut sprite) = pipes.fetch_next() {
+            if sprite.color.alpha() != alpha {
+                sprite.color.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+/// Returns the opacity of a pipe at the given horizontal distance from the
+/// bird in flicker mode.
+fn flicker_alpha(distance: f32) -> f32 {
+    ((distance - FLICKER_FADE_END) / (FLICKER_FADE_START - FLICKER_FADE_END)).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipes_fade_out_as_they_near_the_bird() {
+        assert_eq!(flicker_alpha(FLICKER_FADE_START + 50.0), 1.0);
+        assert_eq!(flicker_alpha(FLICKER_FADE_START), 1.0);
+        assert_eq!(flicker_alpha(FLICKER_FADE_END), 0.0);
+        assert_eq!(flicker_alpha(0.0), 0.0);
+        let halfway = flicker_alpha((FLICKER_FADE_START + FLICKER_FADE_END) / 2.0);
+        assert!((halfway - 0.5).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,187 @@
+//! Background flock systems.
+//!
+//! Every now and then a small flock of birds crosses the sky in a V
+//! formation, on a background layer just in front of the clouds. Each bird
+//! is pulled toward its slot in the formation and pushed away from flockmates
+//! that get too close, so the flock gathers into shape after spawning and
+//! sways a little as the birds bob. Like the clouds, flocks are purely
+//! cosmetic, never collide, and draw from the effects random stream.
+
+use bevy::prelude::*;
+
+use crate::components::{Flock, FlockBird, FlockLayer};
+use crate::constants::{
+    FLOCK_BIRD_ALPHA, FLOCK_BIRD_COLOR, FLOCK_BIRD_SIZE, FLOCK_BOB_HEIGHT, FLOCK_BOB_SPEED,
+    FLOCK_COHESION, FLOCK_DAMPING, FLOCK_REPULSION, FLOCK_SEPARATION, FLOCK_SIZE, FLOCK_SPACING,
+    FLOCK_SPAWN_INTERVAL, FLOCK_SPEED, FLOCK_Z, GROUND_HEIGHT,
+};
+use crate::resources::GameViewport;
+use crate::rng::{GameRng, RngStream};
+use crate::theme::color;
+
+/// Spawns the (initially empty) flock layer.
+pub fn spawn_flock_layer(mut commands: Commands, mut rng: ResMut<GameRng>) {
+    commands.spawn((
+        Transform::from_xyz(0.0, 0.0, FLOCK_Z),
+        Visibility::default(),
+        FlockLayer {
+            spawn_timer: rng
+                .effects
+                .range(FLOCK_SPAWN_INTERVAL.0, FLOCK_SPAWN_INTERVAL.1),
+        },
+    ));
+}
+
+/// Flies the flocks across the sky, spawns new ones past the right edge, and
+/// despawns the ones past the left edge.
+pub fn fly_flocks(
+    mut commands: Commands,
+    time: Res<Time>,
+    viewport: Res<GameViewport>,
+    mut rng: ResMut<GameRng>,
+    mut layer_query: Query<(Entity, &mut FlockLayer)>,
+    mut flock_query: Query<(Entity, &mut Flock, &mut Transform, &Children)>,
+    mut bird_query: Query<(&mut FlockBird, &mut Transform), Without<Flock>>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut flock, mut transform, children) in flock_query.iter_mut() {
+        flock.elapsed += delta;
+        transform.translation.x -= flock.speed * delta;
+        if transform.translation.x < -viewport.half_width() - flock.extent - FLOCK_BIRD_SIZE {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let positions: Vec<Vec2> = children
+            .iter()
+            .filter_map(|child| bird_query.get(child).ok())
+            .map(|(_, transform)| transform.translation.truncate())
+            .collect();
+        for child in children.iter() {
+            let Ok((mut bird, mut transform)) = bird_query.get_mut(child) else {
+                continue;
+            };
+            let position = transform.translation.truncate();
+            let bob = FLOCK_BOB_HEIGHT * (flock.elapsed * FLOCK_BOB_SPEED + bird.phase).sin();
+            let target = bird.slot + Vec2::new(0.0, bob);
+            let acceleration = (target - position) * FLOCK_COHESION - bird.velocity * FLOCK_DAMPING
+                + separation(position, &positions);
+            bird.velocity += acceleration * delta;
+            transform.translation += (bird.velocity * delta).extend(0.0);
+        }
+    }
+
+    for (layer, mut flocks) in layer_query.iter_mut() {
+        flocks.spawn_timer -= delta;
+        if flocks.spawn_timer > 0.0 {
+            continue;
+        }
+        flocks.spawn_timer = rng
+            .effects
+            .range(FLOCK_SPAWN_INTERVAL.0, FLOCK_SPAWN_INTERVAL.1);
+        spawn_flock(&mut commands, layer, &viewport, &mut rng.effects);
+    }
+}
+
+/// Spawns a flock of random size, height, and speed past the right edge.
+///
+/// Birds start scattered around their slots and gather into formation as
+/// they fly in. Flocks stay in the upper part of the sky, like the clouds.
+fn spawn_flock(
+    commands: &mut Commands,
+    layer: Entity,
+    viewport: &GameViewport,
+    rng: &mut RngStream,
+) {
+    let count = rng.range_u32(FLOCK_SIZE.0, FLOCK_SIZE.1);
+    let slots: Vec<Vec2> = (0..count).map(formation_slot).collect();
+    let extent = slots.iter().map(|slot| slot.x).fold(0.0, f32::max);
+    let lowest = -viewport.half_height() + GROUND_HEIGHT + viewport.height / 2.0;
+    let y = rng.range(lowest, viewport.half_height() - extent);
+    let bird_color = color(FLOCK_BIRD_COLOR).with_alpha(FLOCK_BIRD_ALPHA);
+
+    let flock = commands
+        .spawn((
+            Transform::from_xyz(viewport.half_width() + FLOCK_BIRD_SIZE, y, 0.0),
+            Visibility::default(),
+            Flock {
+                speed: rng.range(FLOCK_SPEED.0, FLOCK_SPEED.1),
+                elapsed: 0.0,
+                extent,
+            },
+            ChildOf(layer),
+        ))
+        .id();
+    for slot in slots {
+        let scatter = Vec2::new(
+            rng.range(-FLOCK_SPACING, FLOCK_SPACING),
+            rng.range(-FLOCK_SPACING, FLOCK_SPACING),
+        );
+        commands.entity(flock).with_child((
+            Sprite::from_color(
+                bird_color,
+                Vec2::new(FLOCK_BIRD_SIZE, FLOCK_BIRD_SIZE / 2.0),
+            ),
+            Transform::from_translation((slot + scatter).extend(0.0)),
+            FlockBird {
+                slot,
+                velocity: Vec2::ZERO,
+                phase: rng.angle(),
+            },
+        ));
+    }
+}
+
+/// Returns the place of the `index`th bird in a V formation flying left: the
+/// leader in front, then pairs trailing behind it on either side.
+fn formation_slot(index: u32) -> Vec2 {
+    let row = index.div_ceil(2) as f32;
+    let side = if index % 2 == 1 { 1.0 } else { -1.0 };
+    Vec2::new(row * FLOCK_SPACING, side * row * FLOCK_SPACING * 0.6)
+}
+
+/// Returns the push away from flockmates closer than `FLOCK_SEPARATION`,
+/// growing as they get closer.
+fn separation(position: Vec2, flockmates: &[Vec2]) -> Vec2 {
+    flockmates
+        .iter()
+        .map(|other| position - *other)
+        .filter(|offset| *offset != Vec2::ZERO && offset.length() < FLOCK_SEPARATION)
+        .map(|offset| offset.normalize() * (FLOCK_SEPARATION - offset.length()) * FLOCK_REPULSION)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formation_is_a_v_behind_the_leader() {
+        assert_eq!(formation_slot(0), Vec2::ZERO);
+        let (left, right) = (formation_slot(1), formation_slot(2));
+        assert_eq!(left.x, FLOCK_SPACING);
+        assert_eq!(left.x, right.x);
+        assert_eq!(left.y, -right.y);
+        assert!(formation_slot(3).x > left.x);
+    }
+
+    #[test]
+    fn formation_slots_are_spaced_apart() {
+        let slots: Vec<Vec2> = (0..FLOCK_SIZE.1).map(formation_slot).collect();
+        for slot in &slots {
+            assert_eq!(separation(*slot, &slots), Vec2::ZERO);
+        }
+    }
+
+    #[test]
+    fn close_flockmates_push_apart() {
+        let push = separation(Vec2::ZERO, &[Vec2::new(FLOCK_SEPARATION / 2.0, 0.0)]);
+        assert!(push.x < 0.0);
+        assert_eq!(push.y, 0.0);
+        assert_eq!(
+            separation(Vec2::ZERO, &[Vec2::new(FLOCK_SEPARATION * 2.0, 0.0)]),
+            Vec2::ZERO
+        );
+    }
+}
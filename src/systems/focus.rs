@@ -0,0 +1,59 @@
+//! Menu focus systems.
+//!
+//! The items of the main menu and the pause screen are `Focusable`. Up/Down
+//! on the keyboard or a gamepad's d-pad move the focus between the visible
+//! items, wrapping around at either end, and the focused item is
+//! highlighted like a hovered button; each screen activates its focused item
+//! on Confirm. The settings and sound test screens scroll, so they keep a
+//! selection of their own and mirror it onto their rows' focus.
+
+use bevy::prelude::*;
+
+use crate::components::Focusable;
+use crate::input::MenuInput;
+
+/// Color of the focused menu item.
+pub const FOCUS_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+
+/// Moves the focus between the visible menu items.
+pub fn move_menu_focus(input: MenuInput, mut query: Query<(&mut Focusable, &Visibility)>) {
+    let mut items: Vec<_> = query
+        .iter_mut()
+        .filter(|(_, visibility)| **visibility != Visibility::Hidden)
+        .collect();
+    items.sort_by_key(|(focusable, _)| focusable.order);
+    let current = items
+        .iter()
+        .position(|(focusable, _)| focusable.focused)
+        .unwrap_or(0);
+    let next = step_focus(current, input.vertical_step(), items.len());
+
+    for (index, (mut focusable, _)) in items.into_iter().enumerate() {
+        let focused = index == next;
+        if focusable.focused != focused {
+            focusable.focused = focused;
+        }
+    }
+}
+
+/// Returns the index `step` places away from `index` in a list of `count`
+/// items, wrapping around.
+pub fn step_focus(index: usize, step: i32, count: usize) -> usize {
+    if count == 0 {
+        return 0;
+    }
+    (index as i32 + step).rem_euclid(count as i32) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus_wraps_around() {
+        assert_eq!(step_focus(0, -1, 3), 2);
+        assert_eq!(step_focus(2, 1, 3), 0);
+        assert_eq!(step_focus(1, 0, 3), 1);
+        assert_eq!(step_focus(0, 1, 0), 0);
+    }
+}
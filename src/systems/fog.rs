@@ -0,0 +1,149 @@
+//! Fog systems.
+//!
+//! Fog hides the right of the screen from a distance ahead of the bird,
+//! fading in over a gradient of strips (like the edge flashes) before going
+//! solid. The configuration turns it on as a difficulty modifier and themes
+//! can add it as atmosphere; when both do, the nearer and thicker one wins.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, FogStrip};
+use crate::config::{FogConfig, GameConfig};
+use crate::constants::{FOG_FADE_WIDTH, FOG_GRADIENT_STRIPS, FOG_Z};
+use crate::resources::GameViewport;
+use crate::theme::{Rgb, Theme, color};
+
+/// Where the fog starts ahead of the bird, and how it looks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Fog {
+    distance: f32,
+    color: Rgb,
+    opacity: f32,
+}
+
+/// Spawns the fog strips, hidden until fog is turned on.
+pub fn spawn_fog(mut commands: Commands) {
+    // The gradient strips, then the solid body
+    for index in 0..=FOG_GRADIENT_STRIPS as usize {
+        commands.spawn((
+            Sprite::from_color(Color::NONE, Vec2::ONE),
+            Transform::from_xyz(0.0, 0.0, FOG_Z),
+            Visibility::Hidden,
+            FogStrip { index },
+        ));
+    }
+}
+
+/// Lays the fog strips out ahead of the bird, or hides them without fog.
+pub fn update_fog(
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    viewport: Res<GameViewport>,
+    bird_query: Query<&Transform, (With<Bird>, Without<FogStrip>)>,
+    mut strip_query: Query<(&FogStrip, &mut Sprite, &mut Transform, &mut Visibility)>,
+) {
+    let fog = resolve_fog(&config.fog, &theme);
+    let bird_x = bird_query
+        .iter()
+        .next()
+        .map_or(0.0, |transform| transform.translation.x);
+    let strip_width = FOG_FADE_WIDTH / FOG_GRADIENT_STRIPS as f32;
+    // Reach past the edges so screen shake doesn't reveal them
+    let right = viewport.half_width() * 2.0;
+
+    for (strip, mut sprite, mut transform, mut visibility) in strip_query.iter_mut() {
+        let Some(fog) = fog else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let left = bird_x + fog.distance + strip.index as f32 * strip_width;
+        let (width, alpha) = if strip.index < FOG_GRADIENT_STRIPS as usize {
+            (strip_width, fog.opacity * fade(strip.index))
+        } else {
+            ((right - left).max(0.0), fog.opacity)
+        };
+        sprite.color = color(fog.color).with_alpha(alpha);
+        sprite.custom_size = Some(Vec2::new(width, viewport.height * 2.0));
+        transform.translation.x = left + width / 2.0;
+    }
+}
+
+/// Combines the configured fog and the theme's fog, if any.
+fn resolve_fog(config: &FogConfig, theme: &Theme) -> Option<Fog> {
+    let configured = (config.distance > 0.0).then_some(Fog {
+        distance: config.distance,
+        color: theme.fog.map_or(theme.sky_color, |fog| fog.color),
+        opacity: config.opacity,
+    });
+    let themed = theme.fog.map(|fog| Fog {
+        distance: fog.distance,
+        color: fog.color,
+        opacity: fog.opacity,
+    });
+    match (configured, themed) {
+        (Some(configured), Some(themed)) => Some(Fog {
+            distance: configured.distance.min(themed.distance),
+            opacity: configured.opacity.max(themed.opacity),
+            ..themed
+        }),
+        (configured, themed) => configured.or(themed),
+    }
+}
+
+/// Returns the opacity multiplier of a gradient strip, easing in away from
+/// the bird.
+fn fade(index: usize) -> f32 {
+    let t = (index + 1) as f32 / (FOG_GRADIENT_STRIPS + 1) as f32;
+    t * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::ThemeFog;
+
+    #[test]
+    fn fog_is_off_by_default() {
+        assert_eq!(resolve_fog(&FogConfig::default(), &Theme::default()), None);
+    }
+
+    #[test]
+    fn nearer_and_thicker_fog_wins() {
+        let config = FogConfig {
+            distance: 200.0,
+            opacity: 0.9,
+        };
+        let theme = Theme {
+            fog: Some(ThemeFog {
+                color: (0.1, 0.1, 0.2),
+                distance: 300.0,
+                opacity: 0.5,
+            }),
+            ..default()
+        };
+        let fog = resolve_fog(&config, &theme);
+        assert_eq!(
+            fog,
+            Some(Fog {
+                distance: 200.0,
+                color: (0.1, 0.1, 0.2),
+                opacity: 0.9,
+            })
+        );
+
+        let configured = resolve_fog(&config, &Theme::default());
+        assert_eq!(
+            configured.map(|fog| fog.color),
+            Some(Theme::default().sky_color)
+        );
+    }
+
+    #[test]
+    fn gradient_fades_in_away_from_the_bird() {
+        assert!(fade(0) > 0.0);
+        assert!(fade(0) < fade(1));
+        assert!(fade(FOG_GRADIENT_STRIPS as usize - 1) < 1.0);
+    }
+}
@@ -4,27 +4,51 @@
 
 use bevy::prelude::*;
 
-use crate::components::{Bird, InstructionText, Pipe, ScoreText};
-use crate::resources::Score;
+use crate::bindings::GameAction;
+use crate::components::{
+    Bird, Focusable, GravityZone, HitArea, InstructionText, LetterPickup, MenuButton, Obstacle,
+    PipePair, PlayButton, ScoreText, StarPickup, WorldButton,
+};
+use crate::input::{ActionInput, MenuAction, MenuInput, PointerInput};
+use crate::race::RaceSession;
+use crate::resources::{ButtonClicked, RaceStarted, Score};
 use crate::states::GameState;
 
 /// Handles starting the game from the menu.
 ///
-/// Waits for the player to press SPACE, click, or tap to begin playing.
+/// Waits for the player to press the flap key or button, click, or tap to begin playing, or
+/// to click or confirm the play button. Clicks and taps on other buttons
+/// don't start the game. Race guests start when the host does instead.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
 pub fn start_game(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    touches: Res<Touches>,
+    actions: ActionInput,
+    pointer: PointerInput,
+    menu_input: MenuInput,
+    mut play_clicks: MessageReader<ButtonClicked<PlayButton>>,
+    button_query: Query<(&GlobalTransform, &HitArea), With<WorldButton>>,
+    play_query: Query<&Focusable, With<PlayButton>>,
+    race: Option<Res<RaceSession>>,
+    mut race_started: MessageReader<RaceStarted>,
     mut next_state: ResMut<NextState<GameState>>,
-    mut instruction_query: Query<&mut Visibility, With<InstructionText>>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
 ) {
-    let should_start = keyboard_input.just_pressed(KeyCode::Space)
-        || mouse_input.just_pressed(MouseButton::Left)
-        || touches.any_just_pressed();
+    let pressed_background = pointer
+        .just_pressed_positions()
+        .into_iter()
+        .any(|position| {
+            !button_query
+                .iter()
+                .any(|(transform, area)| area.contains(transform, position))
+        });
+    let play_pressed = play_clicks.read().count() > 0
+        || (menu_input.just_pressed(MenuAction::Confirm)
+            && play_query.iter().any(|focusable| focusable.focused));
+    let pressed = actions.just_pressed(GameAction::Flap) || pressed_background || play_pressed;
+    let should_start = (pressed && is_local_start(race)) || race_started.read().count() > 0;
 
     if should_start {
         next_state.set(GameState::Playing);
-        for mut visibility in instruction_query.iter_mut() {
+        for mut visibility in menu_text_query.iter_mut() {
             *visibility = Visibility::Hidden;
         }
     }
@@ -32,26 +56,46 @@ pub fn start_game(
 
 /// Handles restarting the game after game over.
 ///
-/// Resets all game state including bird position, pipes, and score.
+/// Resets all game state including bird position, pipes, obstacles, and score.
+/// Clicks and taps on buttons, such as the rewind button, don't restart. Race guests restart
+/// when the host does instead.
 #[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn restart_game(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    touches: Res<Touches>,
+    actions: ActionInput,
+    pointer: PointerInput,
+    button_query: Query<(&GlobalTransform, &HitArea), With<WorldButton>>,
+    race: Option<Res<RaceSession>>,
+    mut race_started: MessageReader<RaceStarted>,
     mut next_state: ResMut<NextState<GameState>>,
     mut bird_query: Query<(&mut Bird, &mut Transform)>,
-    pipe_query: Query<Entity, With<Pipe>>,
+    pipe_query: Query<
+        Entity,
+        Or<(
+            With<PipePair>,
+            With<Obstacle>,
+            With<StarPickup>,
+            With<LetterPickup>,
+            With<GravityZone>,
+        )>,
+    >,
     mut commands: Commands,
     mut score: ResMut<Score>,
-    mut text_query: Query<&mut Text2d, With<ScoreText>>,
+    mut text_query: Query<&mut Text, With<ScoreText>>,
     mut instruction_query: Query<
-        (&mut Visibility, &mut Text2d),
+        (&mut Visibility, &mut Text),
         (With<InstructionText>, Without<ScoreText>),
     >,
 ) {
-    let should_restart = keyboard_input.just_pressed(KeyCode::Space)
-        || mouse_input.just_pressed(MouseButton::Left)
-        || touches.any_just_pressed();
+    let pressed_background = pointer
+        .just_pressed_positions()
+        .into_iter()
+        .any(|position| {
+            !button_query
+                .iter()
+                .any(|(transform, area)| area.contains(transform, position))
+        });
+    let pressed = actions.just_pressed(GameAction::Flap) || pressed_background;
+    let should_restart = (pressed && is_local_start(race)) || race_started.read().count() > 0;
 
     if should_restart {
         reset_bird(&mut bird_query);
@@ -62,24 +106,43 @@ pub fn restart_game(
     }
 }
 
+/// Returns true if the player may start runs themselves (not a race guest).
+fn is_local_start(race: Option<Res<RaceSession>>) -> bool {
+    race.is_none_or(|race| race.is_host())
+}
+
 /// Resets the bird to its starting position and state.
 fn reset_bird(bird_query: &mut Query<(&mut Bird, &mut Transform)>) {
     for (mut bird, mut transform) in bird_query.iter_mut() {
         bird.velocity = 0.0;
+        bird.gravity_direction = 1.0;
         transform.translation = Vec3::new(-50.0, 0.0, 1.0);
         transform.rotation = Quat::IDENTITY;
     }
 }
 
-/// Removes all pipe entities from the world.
-fn despawn_all_pipes(commands: &mut Commands, pipe_query: &Query<Entity, With<Pipe>>) {
+/// Removes all pipe, obstacle, star, letter, and zone entities from the world.
+#[allow(clippy::type_complexity)]
+fn despawn_all_pipes(
+    commands: &mut Commands,
+    pipe_query: &Query<
+        Entity,
+        Or<(
+            With<PipePair>,
+            With<Obstacle>,
+            With<StarPickup>,
+            With<LetterPickup>,
+            With<GravityZone>,
+        )>,
+    >,
+) {
     for entity in pipe_query.iter() {
         commands.entity(entity).despawn();
     }
 }
 
 /// Resets the score to zero and updates the display.
-fn reset_score(score: &mut ResMut<Score>, text_query: &mut Query<&mut Text2d, With<ScoreText>>) {
+fn reset_score(score: &mut ResMut<Score>, text_query: &mut Query<&mut Text, With<ScoreText>>) {
     score.reset();
     for mut text in text_query.iter_mut() {
         text.0 = "0".to_string();
@@ -90,7 +153,7 @@ fn reset_score(score: &mut ResMut<Score>, text_query: &mut Query<&mut Text2d, Wi
 #[allow(clippy::type_complexity)]
 fn hide_instructions(
     instruction_query: &mut Query<
-        (&mut Visibility, &mut Text2d),
+        (&mut Visibility, &mut Text),
         (With<InstructionText>, Without<ScoreText>),
     >,
 ) {
@@ -0,0 +1,172 @@
+//! Graphics settings systems.
+//!
+//! This module applies the vsync, frame-rate limit, particle quality, bloom,
+//! and battery saver settings to the window, cameras and effects at runtime,
+//! limits the frame rate, and makes emissive colors glow under bloom.
+
+use std::thread;
+use std::time::Instant;
+
+use bevy::post_process::bloom::{Bloom, BloomCompositeMode, BloomPrefilter};
+use bevy::prelude::*;
+use bevy::render::view::Hdr;
+use bevy::window::{PresentMode, PrimaryWindow};
+
+use crate::components::{Emissive, HudCamera, MainCamera};
+use crate::constants::{
+    BATTERY_SAVER_FPS_CAP, BATTERY_SAVER_PARTICLE_SCALE, BLOOM_INTENSITY, BLOOM_THRESHOLD,
+    BLOOM_THRESHOLD_SOFTNESS,
+};
+use crate::resources::GraphicsSettings;
+use crate::settings::Settings;
+
+/// Reconfigures the window, cameras and effects when the settings change.
+///
+/// Bloom renders the main camera in HDR; the HUD camera drawn over it
+/// switches to HDR with it, so both render into the same texture.
+#[allow(clippy::type_complexity)]
+pub fn apply_graphics_settings(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut graphics: ResMut<GraphicsSettings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    cameras: Query<(Entity, Has<MainCamera>), Or<(With<MainCamera>, With<HudCamera>)>>,
+) {
+    let present_mode = if settings.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    for mut window in windows.iter_mut() {
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+    }
+
+    let fps_cap = if settings.battery_saver {
+        BATTERY_SAVER_FPS_CAP
+    } else {
+        settings.fps_cap
+    };
+    graphics.frame_time = fps_cap.frame_time();
+    let (particle_scale, lifetime_scale) = settings.particle_quality.scales();
+    graphics.lifetime_scale = lifetime_scale;
+    graphics.particle_scale = if settings.battery_saver {
+        particle_scale * BATTERY_SAVER_PARTICLE_SCALE
+    } else {
+        particle_scale
+    };
+
+    let bloom = settings.bloom && !settings.battery_saver;
+    if graphics.bloom != bloom {
+        graphics.bloom = bloom;
+        for (entity, is_main) in cameras.iter() {
+            let mut camera = commands.entity(entity);
+            match (bloom, is_main) {
+                (true, true) => camera.insert(bloom_settings()),
+                (true, false) => camera.insert(Hdr),
+                (false, _) => camera.remove::<(Bloom, Hdr)>(),
+            };
+        }
+    }
+}
+
+/// Bloom that only picks up colors brighter than white, so the emissive
+/// colors glow and the rest of the scene looks the same as without it.
+fn bloom_settings() -> Bloom {
+    Bloom {
+        intensity: BLOOM_INTENSITY,
+        prefilter: BloomPrefilter {
+            threshold: BLOOM_THRESHOLD,
+            threshold_softness: BLOOM_THRESHOLD_SOFTNESS,
+        },
+        composite_mode: BloomCompositeMode::Additive,
+        ..Bloom::NATURAL
+    }
+}
+
+/// Brightens emissive sprites and text past white while bloom is on, and
+/// restores their plain colors when it is turned off.
+pub fn apply_emissive(
+    graphics: Res<GraphicsSettings>,
+    mut sprites: Query<(&mut Sprite, &mut Emissive)>,
+    mut texts: Query<(&mut TextColor, &mut Emissive), Without<Sprite>>,
+) {
+    for (mut sprite, mut emissive) in sprites.iter_mut() {
+        if let Some(glow) = emissive_color(&mut emissive, sprite.color, graphics.bloom) {
+            sprite.color = glow;
+        }
+    }
+    for (mut text_color, mut emissive) in texts.iter_mut() {
+        if let Some(glow) = emissive_color(&mut emissive, text_color.0, graphics.bloom) {
+            text_color.0 = glow;
+        }
+    }
+}
+
+/// Returns the color an emissive entity showing `current` should show
+/// instead, if any, taking `current` as its new plain color unless it is the
+/// glow written last time.
+fn emissive_color(emissive: &mut Emissive, current: Color, bloom: bool) -> Option<Color> {
+    if emissive.applied != Some(current) {
+        emissive.base = current;
+    }
+    let target = if bloom {
+        let plain = emissive.base.to_linear();
+        Color::LinearRgba(LinearRgba {
+            red: plain.red * emissive.strength,
+            green: plain.green * emissive.strength,
+            blue: plain.blue * emissive.strength,
+            alpha: plain.alpha,
+        })
+    } else {
+        emissive.base
+    };
+    emissive.applied = Some(target);
+    (target != current).then_some(target)
+}
+
+/// Sleeps at the end of the frame until the frame-rate limit allows the next one.
+pub fn limit_frame_rate(mut graphics: ResMut<GraphicsSettings>) {
+    if let Some(frame_time) = graphics.frame_time {
+        let elapsed = graphics.last_frame.elapsed();
+        if elapsed < frame_time {
+            thread::sleep(frame_time - elapsed);
+        }
+    }
+    graphics.last_frame = Instant::now();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emissive_colors_glow_past_white_under_bloom() {
+        let mut emissive = Emissive::new(2.0);
+        let plain = Color::srgba(1.0, 0.5, 0.0, 0.8);
+
+        let glow = emissive_color(&mut emissive, plain, true).expect("no glow");
+        let linear = glow.to_linear();
+        assert_eq!(linear.red, 2.0);
+        assert_eq!(linear.alpha, 0.8);
+
+        // The glow written last frame is kept, not brightened again
+        assert_eq!(emissive_color(&mut emissive, glow, true), None);
+        assert_eq!(emissive.base, plain);
+    }
+
+    #[test]
+    fn emissive_colors_follow_the_game_and_turn_plain_without_bloom() {
+        let mut emissive = Emissive::new(2.0);
+        let glow = emissive_color(&mut emissive, Color::WHITE, true).expect("no glow");
+
+        // A new plain color set by the game becomes the base
+        let blue = Color::srgb(0.0, 0.0, 1.0);
+        let glow = emissive_color(&mut emissive, blue, true).unwrap_or(glow);
+        assert_eq!(emissive.base, blue);
+
+        assert_eq!(emissive_color(&mut emissive, glow, false), Some(blue));
+        assert_eq!(emissive_color(&mut emissive, blue, false), None);
+    }
+}
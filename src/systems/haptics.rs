@@ -0,0 +1,85 @@
+//! Haptic feedback systems.
+//!
+//! This module turns gameplay events into haptic impacts on mobile devices
+//! (light on flaps, medium on scoring, heavy on crashing) and into rumble on
+//! connected gamepads.
+
+use std::time::Duration;
+
+use bevy::input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest};
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::constants::{DEATH_RUMBLE_INTENSITY, SCORE_RUMBLE_DURATION, SCORE_RUMBLE_INTENSITY};
+use crate::haptics::{HapticStrength, Haptics};
+use crate::resources::{DeathEvent, FlapEvent, ScoreEvent};
+use crate::settings::Settings;
+
+/// Plays the strongest impact called for by this frame's events.
+pub fn play_haptics(
+    haptics: NonSend<Haptics>,
+    settings: Res<Settings>,
+    mut flap_events: MessageReader<FlapEvent>,
+    mut score_events: MessageReader<ScoreEvent>,
+    mut death_events: MessageReader<DeathEvent>,
+) {
+    // Read every reader so events don't pile up while haptics are off
+    let flapped = flap_events.read().count() > 0;
+    let scored = score_events.read().count() > 0;
+    let died = death_events.read().count() > 0;
+
+    let strength = if died {
+        HapticStrength::Heavy
+    } else if scored {
+        HapticStrength::Medium
+    } else if flapped {
+        HapticStrength::Light
+    } else {
+        return;
+    };
+
+    if settings.haptics {
+        haptics.play(strength);
+    }
+}
+
+/// Rumbles connected gamepads: a short weak pulse on scoring, and a strong
+/// pulse lasting as long as the screen shake on crashing.
+///
+/// Reduced motion turns rumble off, like the screen shake.
+pub fn rumble_gamepads(
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    mut score_events: MessageReader<ScoreEvent>,
+    mut death_events: MessageReader<DeathEvent>,
+    mut rumble_requests: MessageWriter<GamepadRumbleRequest>,
+) {
+    let scored = score_events.read().count() > 0;
+    let died = death_events.read().count() > 0;
+    if settings.reduced_motion {
+        return;
+    }
+
+    let (duration, intensity) = if died {
+        (
+            config.effects.screen_shake_duration,
+            GamepadRumbleIntensity::strong_motor(DEATH_RUMBLE_INTENSITY),
+        )
+    } else if scored {
+        (
+            SCORE_RUMBLE_DURATION,
+            GamepadRumbleIntensity::weak_motor(SCORE_RUMBLE_INTENSITY),
+        )
+    } else {
+        return;
+    };
+
+    for gamepad in gamepads.iter() {
+        rumble_requests.write(GamepadRumbleRequest::Add {
+            duration: Duration::from_secs_f32(duration),
+            intensity,
+            gamepad,
+        });
+    }
+}
@@ -0,0 +1,265 @@
+//! Hazard event systems.
+//!
+//! This module times the hazards of a run, blinks a warning icon where each
+//! one will come from, and then starts it: flocks fly in from the right,
+//! crates drop from the top and break on the ground, and wind gusts push the
+//! bird up or down. Flocks and crates are obstacles, hit-tested by
+//! `check_collisions` through their `CollisionShape` and cleared with the
+//! pipes.
+
+use bevy::prelude::*;
+
+use crate::components::{
+    Bird, CollisionShape, FallingCrate, HazardBird, HazardWarning, Obstacle, Particle,
+};
+use crate::config::GameConfig;
+use crate::constants::{
+    CRATE_HAZARD_LEAD, FLOCK_HAZARD_BOB, FLOCK_HAZARD_SPACING, GROUND_HEIGHT,
+    HAZARD_WARNING_BLINK_RATE, HAZARD_WARNING_COLOR, HAZARD_WARNING_MARGIN, HAZARD_WARNING_SIZE,
+    HAZARD_WARNING_Z, WIND_STREAK_RATE, WIND_STREAK_SIZE, WIND_STREAK_SPEED,
+};
+use crate::hazards::{HazardKind, HazardSchedule, WindGust};
+use crate::resources::{GameViewport, GraphicsSettings, PipeShattered, Score};
+use crate::rng::GameRng;
+use crate::theme::{Theme, color};
+
+/// Picks when the first hazard of a new run comes, and calms the wind.
+pub fn reset_hazards(
+    mut schedule: ResMut<HazardSchedule>,
+    mut wind: ResMut<WindGust>,
+    mut game_rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+) {
+    schedule.restart(&config.hazards, &mut game_rng.hazards);
+    *wind = WindGust::default();
+}
+
+/// Removes pending warnings and calms the wind when a run ends.
+pub fn clear_hazards(
+    mut commands: Commands,
+    mut wind: ResMut<WindGust>,
+    warning_query: Query<Entity, With<HazardWarning>>,
+) {
+    *wind = WindGust::default();
+    for entity in warning_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Shows a warning where the next hazard will come from once it is due.
+///
+/// Flocks are announced on the right edge at the height they fly at, crates
+/// at the top above where they drop, and wind at the edge it blows from.
+#[allow(clippy::too_many_arguments)]
+pub fn schedule_hazards(
+    mut commands: Commands,
+    time: Res<Time>,
+    score: Res<Score>,
+    config: Res<GameConfig>,
+    viewport: Res<GameViewport>,
+    mut schedule: ResMut<HazardSchedule>,
+    mut game_rng: ResMut<GameRng>,
+    bird_query: Query<&Transform, With<Bird>>,
+) {
+    let hazards = &config.hazards;
+    if score.0 < hazards.min_score || !schedule.tick(time.delta_secs()) {
+        return;
+    }
+    let rng = &mut game_rng.hazards;
+    schedule.restart(hazards, rng);
+    let Some(kind) = HazardKind::pick(hazards, rng) else {
+        return;
+    };
+    let column = bird_query
+        .single()
+        .map_or(0.0, |transform| transform.translation.x);
+
+    let top = viewport.half_height() - HAZARD_WARNING_MARGIN;
+    let bottom = -viewport.half_height() + GROUND_HEIGHT + HAZARD_WARNING_MARGIN;
+    let position = match kind {
+        HazardKind::Flock => Vec2::new(
+            viewport.half_width() - HAZARD_WARNING_MARGIN,
+            rng.range(bottom, top),
+        ),
+        HazardKind::FallingCrate => Vec2::new(
+            (column + rng.range(CRATE_HAZARD_LEAD.0, CRATE_HAZARD_LEAD.1))
+                .min(viewport.half_width() - HAZARD_WARNING_MARGIN),
+            top,
+        ),
+        HazardKind::Wind => Vec2::new(0.0, if rng.f32() < 0.5 { top } else { bottom }),
+    };
+    info!("Hazard incoming: {kind:?}");
+
+    commands
+        .spawn((
+            Transform::from_translation(position.extend(HAZARD_WARNING_Z)),
+            Visibility::default(),
+            HazardWarning {
+                kind,
+                remaining: hazards.warning_time,
+            },
+        ))
+        .with_children(|warning| {
+            warning.spawn((
+                Sprite::from_color(
+                    color(HAZARD_WARNING_COLOR),
+                    Vec2::splat(HAZARD_WARNING_SIZE),
+                ),
+                Transform::from_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+            ));
+            warning.spawn((
+                Text2d::new("!"),
+                TextFont {
+                    font_size: HAZARD_WARNING_SIZE,
+                    ..default()
+                },
+                TextColor(Color::BLACK),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            ));
+        });
+}
+
+/// Blinks the warnings and starts each hazard when its warning runs out.
+#[allow(clippy::too_many_arguments)]
+pub fn trigger_hazard_warnings(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    viewport: Res<GameViewport>,
+    theme: Res<Theme>,
+    mut wind: ResMut<WindGust>,
+    mut warning_query: Query<(Entity, &mut HazardWarning, &Transform, &mut Visibility)>,
+) {
+    let hazards = &config.hazards;
+    for (entity, mut warning, transform, mut visibility) in warning_query.iter_mut() {
+        warning.remaining -= time.delta_secs();
+        if warning.remaining > 0.0 {
+            let shown = ((warning.remaining * HAZARD_WARNING_BLINK_RATE) as u32).is_multiple_of(2);
+            visibility.set_if_neq(if shown {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            });
+            continue;
+        }
+        commands.entity(entity).despawn();
+
+        let position = transform.translation.truncate();
+        match warning.kind {
+            HazardKind::Flock => {
+                // A short line of birds entering from the right edge
+                let size = hazards.flock_bird_size;
+                for index in 0..hazards.flock_size {
+                    let x = viewport.half_width() + size + index as f32 * FLOCK_HAZARD_SPACING;
+                    commands.spawn((
+                        Sprite::from_color(color(theme.obstacle_color), Vec2::splat(size)),
+                        Transform::from_xyz(x, position.y, 0.5),
+                        Obstacle,
+                        HazardBird {
+                            speed: hazards.flock_speed,
+                            base_y: position.y,
+                            elapsed: index as f32,
+                        },
+                        CollisionShape::Circle(size / 2.0),
+                    ));
+                }
+            }
+            HazardKind::FallingCrate => {
+                let size = Vec2::splat(hazards.crate_size);
+                commands.spawn((
+                    Sprite::from_color(color(theme.obstacle_color), size),
+                    Transform::from_xyz(position.x, viewport.half_height() + size.y, 0.5),
+                    Obstacle,
+                    FallingCrate { velocity: 0.0 },
+                    CollisionShape::Box(size),
+                ));
+            }
+            HazardKind::Wind => {
+                // Announced at the top when blowing down, at the bottom when blowing up
+                *wind = WindGust {
+                    remaining: hazards.wind_duration,
+                    direction: if position.y > 0.0 { -1.0 } else { 1.0 },
+                };
+            }
+        }
+    }
+}
+
+/// Flies hazard birds against the scroll, bobbing as they flap.
+pub fn fly_hazard_flocks(time: Res<Time>, mut query: Query<(&mut HazardBird, &mut Transform)>) {
+    let (bob_height, bob_speed) = FLOCK_HAZARD_BOB;
+    for (mut bird, mut transform) in query.iter_mut() {
+        bird.elapsed += time.delta_secs();
+        transform.translation.x -= bird.speed * time.delta_secs();
+        transform.translation.y = bird.base_y + bob_height * (bird.elapsed * bob_speed).sin();
+    }
+}
+
+/// Drops falling crates, breaking them apart when they reach the ground.
+pub fn drop_crates(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    viewport: Res<GameViewport>,
+    mut query: Query<(Entity, &mut FallingCrate, &mut Transform)>,
+    mut shatter_events: MessageWriter<PipeShattered>,
+) {
+    let dt = time.delta_secs();
+    let ground_top = -viewport.half_height() + GROUND_HEIGHT;
+    for (entity, mut falling, mut transform) in query.iter_mut() {
+        falling.velocity += config.hazards.crate_gravity * dt;
+        transform.translation.y -= falling.velocity * dt;
+
+        if transform.translation.y - config.hazards.crate_size / 2.0 <= ground_top {
+            commands.entity(entity).despawn();
+            shatter_events.write(PipeShattered {
+                position: transform.translation.with_y(ground_top),
+            });
+        }
+    }
+}
+
+/// Pushes the bird with the wind while a gust blows, with streaks showing
+/// which way it blows.
+#[allow(clippy::too_many_arguments)]
+pub fn blow_wind(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    viewport: Res<GameViewport>,
+    graphics: Res<GraphicsSettings>,
+    mut wind: ResMut<WindGust>,
+    mut game_rng: ResMut<GameRng>,
+    mut bird_query: Query<&mut Bird>,
+) {
+    if !wind.is_blowing() {
+        return;
+    }
+    let dt = time.delta_secs().min(wind.remaining);
+    wind.remaining -= dt;
+    for mut bird in bird_query.iter_mut() {
+        bird.velocity += wind.direction * config.hazards.wind_strength * dt;
+    }
+
+    // Streaks are cosmetic, so they draw from the particle stream
+    let rng = &mut game_rng.particles;
+    if rng.f32() >= WIND_STREAK_RATE * graphics.particle_scale * dt {
+        return;
+    }
+    let x = rng.range(-viewport.half_width(), viewport.half_width());
+    let y = -wind.direction * viewport.half_height();
+    let lifetime = graphics.lifetime(viewport.height / WIND_STREAK_SPEED);
+    commands.spawn((
+        Sprite::from_color(
+            Color::srgba(1.0, 1.0, 1.0, 0.4),
+            Vec2::new(WIND_STREAK_SIZE.0, WIND_STREAK_SIZE.1),
+        ),
+        Transform::from_xyz(x, y, HAZARD_WARNING_Z - 0.1).with_scale(Vec3::ZERO),
+        Particle {
+            velocity: Vec2::new(0.0, wind.direction * WIND_STREAK_SPEED),
+            world_velocity: Vec2::new(-config.pipes.scroll_speed, 0.0),
+            lifetime,
+            initial_lifetime: lifetime,
+        },
+    ));
+}
@@ -0,0 +1,104 @@
+//! Menu idle animation systems.
+//!
+//! On the menu the bird bobs gently and blinks now and then, so the title
+//! screen feels alive. The bob is skipped with reduced motion. The eye only
+//! exists while the animation runs; blinking swaps it to its closed frame.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, BirdEye, IdleAnimation};
+use crate::constants::{
+    BIRD_EYE_SIZE, BIRD_SIZE, IDLE_BLINK_DURATION, IDLE_BLINK_INTERVAL, IDLE_BOB_HEIGHT,
+    IDLE_BOB_SPEED,
+};
+use crate::settings::Settings;
+
+/// Color of the bird's eye.
+const EYE_COLOR: Color = Color::srgb(0.1, 0.1, 0.1);
+
+/// Starts the idle animation and gives the bird its eye.
+pub fn start_idle_animation(
+    mut commands: Commands,
+    bird_query: Query<(Entity, &Transform), With<Bird>>,
+) {
+    for (entity, transform) in bird_query.iter() {
+        commands
+            .entity(entity)
+            .insert(IdleAnimation {
+                base_y: transform.translation.y,
+                elapsed: 0.0,
+            })
+            .with_child((
+                Sprite::from_color(EYE_COLOR, Vec2::splat(BIRD_EYE_SIZE)),
+                Transform::from_xyz(BIRD_SIZE * 0.2, BIRD_SIZE * 0.15, 0.1),
+                BirdEye,
+            ));
+    }
+}
+
+/// Stops the idle animation, puts the bird back at rest, and removes its eye.
+pub fn stop_idle_animation(
+    mut commands: Commands,
+    mut bird_query: Query<(Entity, &IdleAnimation, &mut Transform), With<Bird>>,
+    eye_query: Query<Entity, With<BirdEye>>,
+) {
+    for (entity, idle, mut transform) in bird_query.iter_mut() {
+        transform.translation.y = idle.base_y;
+        commands.entity(entity).remove::<IdleAnimation>();
+    }
+    for entity in eye_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Bobs the bird and blinks its eye.
+pub fn animate_idle_bird(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut bird_query: Query<(&mut IdleAnimation, &mut Transform), With<Bird>>,
+    mut eye_query: Query<&mut Sprite, With<BirdEye>>,
+) {
+    let mut blinking = false;
+    for (mut idle, mut transform) in bird_query.iter_mut() {
+        idle.elapsed += time.delta_secs();
+        let bob = if settings.reduced_motion {
+            0.0
+        } else {
+            IDLE_BOB_HEIGHT * (idle.elapsed * IDLE_BOB_SPEED).sin()
+        };
+        transform.translation.y = idle.base_y + bob;
+        blinking = is_blinking(idle.elapsed);
+    }
+
+    let eye_size = if blinking {
+        Vec2::new(BIRD_EYE_SIZE, BIRD_EYE_SIZE / 4.0)
+    } else {
+        Vec2::splat(BIRD_EYE_SIZE)
+    };
+    for mut sprite in eye_query.iter_mut() {
+        sprite.custom_size = Some(eye_size);
+    }
+}
+
+/// Returns whether the eye is closed after `elapsed` seconds of idling.
+///
+/// The bird blinks at the end of every `IDLE_BLINK_INTERVAL`.
+fn is_blinking(elapsed: f32) -> bool {
+    IDLE_BLINK_INTERVAL - elapsed.rem_euclid(IDLE_BLINK_INTERVAL) < IDLE_BLINK_DURATION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blinks_briefly_once_per_interval() {
+        assert!(!is_blinking(0.0));
+        assert!(!is_blinking(IDLE_BLINK_INTERVAL / 2.0));
+        assert!(is_blinking(IDLE_BLINK_INTERVAL - IDLE_BLINK_DURATION / 2.0));
+        assert!(!is_blinking(IDLE_BLINK_INTERVAL + IDLE_BLINK_DURATION));
+        assert!(is_blinking(
+            2.0 * IDLE_BLINK_INTERVAL - IDLE_BLINK_DURATION / 2.0
+        ));
+    }
+}
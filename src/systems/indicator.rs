@@ -0,0 +1,128 @@
+//! Off-screen gap indicator systems.
+//!
+//! On short or ultrawide windows the next gap can sit above or below what the
+//! camera shows. An arrow at the screen edge then points to the gap center.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::components::{GapIndicator, MainCamera, PipePair};
+use crate::constants::{
+    GAP_INDICATOR_ALPHA, GAP_INDICATOR_MARGIN, GAP_INDICATOR_SIZE, GROUND_HEIGHT,
+};
+use crate::resources::GameViewport;
+use crate::states::GameState;
+
+/// The arrow draws above the world, below the UI text.
+const GAP_INDICATOR_Z: f32 = 1.5;
+
+/// Spawns the (hidden) gap indicator arrow.
+pub fn spawn_gap_indicator(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let half = GAP_INDICATOR_SIZE / 2.0;
+    let arrow = Triangle2d::new(
+        Vec2::new(0.0, half),
+        Vec2::new(-half, -half),
+        Vec2::new(half, -half),
+    );
+    commands.spawn((
+        Mesh2d(meshes.add(arrow)),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(
+            Color::WHITE.with_alpha(GAP_INDICATOR_ALPHA),
+        ))),
+        Transform::from_xyz(0.0, 0.0, GAP_INDICATOR_Z),
+        Visibility::Hidden,
+        GapIndicator,
+    ));
+}
+
+/// Points the arrow to the next unscored gap while it is off-screen.
+#[allow(clippy::type_complexity)]
+pub fn update_gap_indicator(
+    state: Res<State<GameState>>,
+    viewport: Res<GameViewport>,
+    pair_query: Query<(&Transform, &PipePair)>,
+    camera_query: Query<&Transform, (With<MainCamera>, Without<GapIndicator>)>,
+    mut indicator_query: Query<
+        (&mut Transform, &mut Visibility),
+        (With<GapIndicator>, Without<PipePair>, Without<MainCamera>),
+    >,
+) {
+    let Ok((mut transform, mut visibility)) = indicator_query.single_mut() else {
+        return;
+    };
+    let camera_y = camera_query
+        .single()
+        .map_or(0.0, |camera| camera.translation.y);
+
+    let next_gap = pair_query
+        .iter()
+        .filter(|(_, pair)| !pair.scored)
+        .map(|(transform, pair)| Vec2::new(transform.translation.x, pair.gap_y))
+        .min_by(|a, b| a.x.total_cmp(&b.x));
+
+    let placement = next_gap
+        .filter(|_| *state.get() == GameState::Playing)
+        .and_then(|gap| indicator_placement(gap, camera_y, &viewport));
+
+    match placement {
+        Some((position, points_up)) => {
+            transform.translation = position.extend(GAP_INDICATOR_Z);
+            transform.rotation = Quat::from_rotation_z(if points_up { 0.0 } else { PI });
+            *visibility = Visibility::Inherited;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+/// Returns where to draw the arrow for a gap, and whether it points up, or
+/// `None` if the gap center is visible.
+///
+/// The visible area is the camera's view above the ground.
+fn indicator_placement(gap: Vec2, camera_y: f32, viewport: &GameViewport) -> Option<(Vec2, bool)> {
+    let top = camera_y + viewport.half_height();
+    let bottom = camera_y - viewport.half_height() + GROUND_HEIGHT;
+    let inset = GAP_INDICATOR_MARGIN + GAP_INDICATOR_SIZE / 2.0;
+    let max_x = (viewport.half_width() - inset).max(0.0);
+    let x = gap.x.clamp(-max_x, max_x);
+
+    if gap.y > top {
+        Some((Vec2::new(x, top - inset), true))
+    } else if gap.y < bottom {
+        Some((Vec2::new(x, bottom + inset), false))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrow_points_to_hidden_gaps_only() {
+        let viewport = GameViewport::default();
+        let top = viewport.half_height();
+
+        assert_eq!(
+            indicator_placement(Vec2::new(100.0, 0.0), 0.0, &viewport),
+            None
+        );
+
+        let (position, points_up) =
+            indicator_placement(Vec2::new(100.0, top + 50.0), 0.0, &viewport).unwrap();
+        assert!(points_up);
+        assert_eq!(position.x, 100.0);
+        assert!(position.y < top);
+
+        // Behind the ground counts as hidden, and x stays on screen
+        let (position, points_up) =
+            indicator_placement(Vec2::new(10_000.0, -top + 10.0), 0.0, &viewport).unwrap();
+        assert!(!points_up);
+        assert!(position.x < viewport.half_width());
+    }
+}
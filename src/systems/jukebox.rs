@@ -0,0 +1,210 @@
+//! Sound test screen systems.
+//!
+//! The sound test screen, opened from the settings, lists every music layer
+//! and sound effect with a play button so the soundtrack can be previewed and
+//! the audio checked. The game music is silenced while it is open, and only
+//! one track plays at a time.
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::components::{
+    FillScreen, Focusable, HitArea, InstructionText, JukeboxRowText, JukeboxSound, JukeboxUi,
+    MenuButton, Music, WorldButton,
+};
+use crate::config::GameConfig;
+use crate::input::{MenuAction, MenuInput};
+use crate::music::{JukeboxRow, JukeboxTrack, MusicLoop};
+use crate::resources::{ButtonClicked, Fanfare, GameViewport, JukeboxMenu, SoundEffects};
+use crate::settings::Settings;
+use crate::states::GameState;
+use crate::systems::focus::step_focus;
+
+/// Vertical distance between sound test rows.
+const ROW_SPACING: f32 = 36.0;
+
+/// Height of the first row on screen.
+const FIRST_ROW_Y: f32 = 105.0;
+
+/// Builds the sound test screen, hides the menu text behind it, and silences
+/// the game music.
+#[allow(clippy::type_complexity)]
+pub fn spawn_jukebox_menu(
+    mut commands: Commands,
+    viewport: Res<GameViewport>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+    mut music_query: Query<&mut AudioSink, With<Music>>,
+) {
+    commands.insert_resource(JukeboxMenu::default());
+
+    for mut visibility in menu_text_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+    // The layers fade back in on their own once the screen closes
+    for mut sink in music_query.iter_mut() {
+        sink.set_volume(Volume::Linear(0.0));
+    }
+
+    // Dim the world behind the menu
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            custom_size: Some(Vec2::new(viewport.width * 2.0, viewport.height * 2.0)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        FillScreen,
+        JukeboxUi,
+    ));
+
+    commands.spawn((
+        Text2d::new("Sound test"),
+        TextFont {
+            font_size: 36.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, 175.0, 6.0),
+        JukeboxUi,
+    ));
+
+    for (index, row) in JukeboxRow::ALL.into_iter().enumerate() {
+        commands.spawn((
+            Text2d::new(row.label(None)),
+            TextFont {
+                font_size: 22.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(0.0, FIRST_ROW_Y - index as f32 * ROW_SPACING, 6.0),
+            HitArea {
+                size: Vec2::new(viewport.width, ROW_SPACING),
+            },
+            WorldButton::default(),
+            Focusable::new(index as u32),
+            JukeboxRowText(row),
+            JukeboxUi,
+        ));
+    }
+}
+
+/// Removes the sound test screen, stops the previewed track, and restores the
+/// menu text.
+#[allow(clippy::type_complexity)]
+pub fn despawn_jukebox_menu(
+    mut commands: Commands,
+    ui_query: Query<Entity, Or<(With<JukeboxUi>, With<JukeboxSound>)>>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for mut visibility in menu_text_query.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Handles input on the sound test screen.
+///
+/// Up/Down (or the d-pad) select a row, Space/Enter (or the south button)
+/// activate it, and Escape (or the east button) returns to the settings.
+/// Clicking or tapping a row activates it. Playing a track stops the one
+/// already playing.
+#[allow(clippy::too_many_arguments)]
+pub fn navigate_jukebox(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    menu_input: MenuInput,
+    mut row_clicks: MessageReader<ButtonClicked<JukeboxRowText>>,
+    music_query: Query<(&Music, &AudioPlayer<MusicLoop>)>,
+    sound_query: Query<Entity, With<JukeboxSound>>,
+    fanfare: Option<Res<Fanfare>>,
+    effects: Option<Res<SoundEffects>>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    mut menu: ResMut<JukeboxMenu>,
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let row_count = JukeboxRow::ALL.len();
+
+    if menu_input.just_pressed(MenuAction::Back) {
+        next_state.set(GameState::Settings);
+        return;
+    }
+    menu.selected = step_focus(menu.selected, menu_input.vertical_step(), row_count);
+
+    let mut activated =
+        keyboard_input.just_pressed(KeyCode::Space) || menu_input.just_pressed(MenuAction::Confirm);
+    for ButtonClicked(JukeboxRowText(row)) in row_clicks.read() {
+        if let Some(index) = JukeboxRow::ALL.iter().position(|other| other == row) {
+            menu.selected = index;
+            activated = true;
+        }
+    }
+    if !activated {
+        return;
+    }
+
+    let Some(row) = JukeboxRow::ALL.get(menu.selected) else {
+        return;
+    };
+    match row {
+        JukeboxRow::Track(track) => {
+            let handle = match track {
+                JukeboxTrack::Music(layer) => music_query
+                    .iter()
+                    .find(|(music, _)| music.layer == *layer)
+                    .map(|(_, player)| player.0.clone()),
+                JukeboxTrack::Fanfare => fanfare.map(|fanfare| fanfare.0.clone()),
+                JukeboxTrack::Effect(effect) => effects.and_then(|effects| effects.get(*effect)),
+            };
+            let Some(handle) = handle else {
+                warn!("Sound test: {} is not loaded", track.label());
+                return;
+            };
+            for entity in sound_query.iter() {
+                commands.entity(entity).despawn();
+            }
+            let playback = if track.loops() {
+                PlaybackSettings::LOOP
+            } else {
+                PlaybackSettings::DESPAWN
+            };
+            let volume = match track {
+                JukeboxTrack::Effect(_) => settings.sfx_volume.factor(),
+                _ => config.music.volume * settings.music_volume.factor(),
+            };
+            commands.spawn((
+                AudioPlayer(handle),
+                playback.with_volume(Volume::Linear(volume)),
+                JukeboxSound(*track),
+            ));
+        }
+        JukeboxRow::Stop => {
+            for entity in sound_query.iter() {
+                commands.entity(entity).despawn();
+            }
+        }
+        JukeboxRow::Back => next_state.set(GameState::Settings),
+    }
+}
+
+/// Focuses the selected row and marks the track that is playing.
+pub fn update_jukebox_rows(
+    menu: Res<JukeboxMenu>,
+    sound_query: Query<&JukeboxSound>,
+    mut row_query: Query<(&JukeboxRowText, &mut Text2d, &mut Focusable)>,
+) {
+    let playing = sound_query.iter().next().map(|JukeboxSound(track)| *track);
+    for (JukeboxRowText(row), mut text, mut focusable) in row_query.iter_mut() {
+        let label = row.label(playing);
+        if text.0 != label {
+            text.0 = label;
+        }
+
+        let selected = JukeboxRow::ALL.get(menu.selected) == Some(row);
+        if focusable.focused != selected {
+            focusable.focused = selected;
+        }
+    }
+}
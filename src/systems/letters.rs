@@ -0,0 +1,140 @@
+//! Letter bonus systems.
+//!
+//! This module floats the next letter of the bonus word between two pipes
+//! now and then, collects it when the bird touches it, and pays out the
+//! bonus once the word is complete. Letters scroll with the pipes and are
+//! cleared with them; the HUD icons light up as letters are collected.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, BossWall, Emissive, LetterIcon, LetterPickup, PipePair, Toast};
+use crate::config::GameConfig;
+use crate::constants::{
+    BIRD_SIZE, LETTER_BOB, LETTER_BONUS_COINS, LETTER_CHANCE, LETTER_COLOR,
+    LETTER_ICON_MISSING_ALPHA, LETTER_SIZE, PICKUP_GLOW,
+};
+use crate::letters::{BONUS_WORD, LetterBonus, WordCompleted};
+use crate::rng::GameRng;
+use crate::save::SaveData;
+use crate::systems::achievements::spawn_toast;
+use crate::theme::color;
+
+/// Forgets the collected letters when a run starts or the bird dies.
+pub fn reset_letters(mut bonus: ResMut<LetterBonus>) {
+    *bonus = LetterBonus::default();
+}
+
+/// Sometimes floats the next letter after a newly spawned pipe pair,
+/// halfway to the next one.
+///
+/// Only one letter is on screen at a time, none once the word is complete,
+/// and none by the boss wall.
+#[allow(clippy::type_complexity)]
+pub fn spawn_letters(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    bonus: Res<LetterBonus>,
+    mut game_rng: ResMut<GameRng>,
+    pair_query: Query<(&Transform, &PipePair), (Added<PipePair>, Without<BossWall>)>,
+    letter_query: Query<(), With<LetterPickup>>,
+) {
+    let Some(letter) = bonus.next() else {
+        return;
+    };
+    if !letter_query.is_empty() {
+        return;
+    }
+    let rng = &mut game_rng.letters;
+    for (transform, pair) in pair_query.iter() {
+        if rng.f32() >= LETTER_CHANCE {
+            continue;
+        }
+        let x = transform.translation.x + config.pipes.spawn_distance / 2.0;
+        commands.spawn((
+            Text2d::new(letter.to_string()),
+            TextFont {
+                font_size: LETTER_SIZE,
+                ..default()
+            },
+            TextColor(color(LETTER_COLOR)),
+            Transform::from_xyz(x, pair.gap_y, 0.6),
+            LetterPickup {
+                letter,
+                base_y: pair.gap_y,
+                elapsed: 0.0,
+            },
+            Emissive::new(PICKUP_GLOW),
+        ));
+        return;
+    }
+}
+
+/// Bobs the letters up and down.
+pub fn float_letters(time: Res<Time>, mut query: Query<(&mut LetterPickup, &mut Transform)>) {
+    let (bob_height, bob_speed) = LETTER_BOB;
+    for (mut letter, mut transform) in query.iter_mut() {
+        letter.elapsed += time.delta_secs();
+        transform.translation.y = letter.base_y + bob_height * (letter.elapsed * bob_speed).sin();
+    }
+}
+
+/// Collects the letters the bird touches, paying out the bonus when they
+/// complete the word.
+#[allow(clippy::too_many_arguments)]
+pub fn collect_letters(
+    mut commands: Commands,
+    bird_query: Query<&Transform, With<Bird>>,
+    letter_query: Query<(Entity, &LetterPickup, &Transform)>,
+    toast_query: Query<(), With<Toast>>,
+    mut bonus: ResMut<LetterBonus>,
+    mut save: ResMut<SaveData>,
+    mut word_events: MessageWriter<WordCompleted>,
+) {
+    let Ok(bird_transform) = bird_query.single() else {
+        return;
+    };
+    let bird_pos = bird_transform.translation.truncate();
+
+    for (entity, pickup, transform) in letter_query.iter() {
+        if bird_pos.distance(transform.translation.truncate()) >= (BIRD_SIZE + LETTER_SIZE) / 2.0 {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        if !bonus.collect(pickup.letter) {
+            continue;
+        }
+
+        let word = String::from_iter(BONUS_WORD);
+        info!("Letter bonus: {word} collected");
+        save.earn_coins(LETTER_BONUS_COINS);
+        save.store();
+        word_events.write(WordCompleted);
+        spawn_toast(
+            &mut commands,
+            format!("{word}! +{LETTER_BONUS_COINS} coins"),
+            toast_query.iter().count(),
+        );
+    }
+}
+
+/// Shows the collected letters in the HUD, dimming the missing ones.
+///
+/// The icons stay hidden until the first letter of a run is collected.
+pub fn update_letter_icons(
+    bonus: Res<LetterBonus>,
+    mut icon_query: Query<(&LetterIcon, &mut TextColor, &mut Visibility)>,
+) {
+    for (icon, mut text_color, mut visibility) in icon_query.iter_mut() {
+        let alpha = if icon.0 < bonus.collected {
+            1.0
+        } else {
+            LETTER_ICON_MISSING_ALPHA
+        };
+        text_color.0 = color(LETTER_COLOR).with_alpha(alpha);
+        *visibility = if bonus.collected > 0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
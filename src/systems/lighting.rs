@@ -0,0 +1,81 @@
+//! Scene lighting systems.
+//!
+//! This module keeps the lighting overlay (see `lighting.rs`) in sync with
+//! the theme, the bird, and the pipe gaps.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, LightingOverlay, PipePair};
+use crate::constants::LIGHTING_Z;
+use crate::lighting::{LightingMaterial, LightingUniform};
+use crate::resources::GameViewport;
+use crate::theme::{Theme, color};
+
+/// Spawns the (initially hidden) lighting overlay.
+pub fn spawn_lighting_overlay(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<LightingMaterial>>,
+) {
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
+        MeshMaterial2d(materials.add(LightingMaterial::default())),
+        Transform::from_xyz(0.0, 0.0, LIGHTING_Z),
+        Visibility::Hidden,
+        LightingOverlay,
+    ));
+}
+
+/// Shows the overlay for themes with lighting and moves the lights along
+/// with the bird and the pipe gaps.
+#[allow(clippy::type_complexity)]
+pub fn update_lighting(
+    theme: Res<Theme>,
+    viewport: Res<GameViewport>,
+    bird_query: Query<&Transform, (With<Bird>, Without<LightingOverlay>)>,
+    gap_query: Query<(&Transform, &PipePair), Without<LightingOverlay>>,
+    mut overlay_query: Query<
+        (
+            &MeshMaterial2d<LightingMaterial>,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        With<LightingOverlay>,
+    >,
+    mut materials: ResMut<Assets<LightingMaterial>>,
+) {
+    for (material, mut transform, mut visibility) in overlay_query.iter_mut() {
+        let Some(lighting) = theme.lighting else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        // Oversized so screen shake never uncovers the edges
+        transform.scale = Vec3::new(viewport.width * 2.0, viewport.height * 2.0, 1.0);
+
+        let mut uniform = LightingUniform {
+            darkness: color(lighting.darkness_color)
+                .with_alpha(lighting.darkness)
+                .to_linear(),
+            ..default()
+        };
+        for bird_transform in bird_query.iter() {
+            uniform.add_light(
+                bird_transform.translation.truncate(),
+                lighting.bird_light_radius,
+                1.0,
+            );
+        }
+        for (pair_transform, pair) in gap_query.iter() {
+            uniform.add_light(
+                Vec2::new(pair_transform.translation.x, pair.gap_y),
+                lighting.gap_light_radius,
+                lighting.gap_light_intensity,
+            );
+        }
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.lighting = uniform;
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Gameplay logging systems.
+//!
+//! State transitions, scores, and deaths are logged with structured fields
+//! (`score=12 cause="pipe"`), so `--log` or `RUST_LOG` can raise or lower
+//! how much of a session ends up in the console and the log viewer.
+
+use bevy::prelude::*;
+
+use crate::resources::{DeathEvent, Score, ScoreEvent};
+use crate::states::{GameState, PauseState, PlayPhase};
+
+/// Logs every change of the game state, run phase, and pause state.
+pub fn log_state_transitions(
+    mut game_transitions: MessageReader<StateTransitionEvent<GameState>>,
+    mut phase_transitions: MessageReader<StateTransitionEvent<PlayPhase>>,
+    mut pause_transitions: MessageReader<StateTransitionEvent<PauseState>>,
+) {
+    for transition in game_transitions.read() {
+        info!(from = ?transition.exited, to = ?transition.entered, "Game state changed");
+    }
+    for transition in phase_transitions.read() {
+        debug!(from = ?transition.exited, to = ?transition.entered, "Run phase changed");
+    }
+    for transition in pause_transitions.read() {
+        debug!(from = ?transition.exited, to = ?transition.entered, "Pause state changed");
+    }
+}
+
+/// Logs every point scored.
+pub fn log_scores(mut score_events: MessageReader<ScoreEvent>) {
+    for event in score_events.read() {
+        debug!(score = event.score, gap_size = event.gap_size, "Scored");
+    }
+}
+
+/// Logs the end of each run with its final score and death cause.
+pub fn log_deaths(score: Res<Score>, mut death_events: MessageReader<DeathEvent>) {
+    for event in death_events.read() {
+        info!(
+            score = score.0,
+            cause = event.cause.label(),
+            x = event.position.x,
+            y = event.position.y,
+            "Bird died"
+        );
+    }
+}
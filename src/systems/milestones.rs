@@ -0,0 +1,126 @@
+//! Milestone banner systems.
+//!
+//! Reaching one of the configured milestone scores slides a large banner
+//! across the top of the screen and plays a short fanfare.
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::components::MilestoneBanner;
+use crate::config::GameConfig;
+use crate::constants::{
+    MILESTONE_BANNER_COLOR, MILESTONE_BANNER_SIZE, MILESTONE_BANNER_Y, MILESTONE_FONT_SIZE,
+    MILESTONE_SLIDE_FRACTION,
+};
+use crate::music::{MusicLoop, synthesize_fanfare};
+use crate::resources::{Fanfare, GameViewport, ScoreEvent};
+use crate::settings::Settings;
+use crate::theme::color;
+
+/// Synthesizes the milestone fanfare.
+pub fn load_fanfare(mut commands: Commands, mut loops: ResMut<Assets<MusicLoop>>) {
+    commands.insert_resource(Fanfare(loops.add(synthesize_fanfare())));
+}
+
+/// Announces milestone scores with a banner and the fanfare.
+pub fn announce_milestones(
+    mut commands: Commands,
+    mut score_events: MessageReader<ScoreEvent>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    fanfare: Res<Fanfare>,
+    viewport: Res<GameViewport>,
+) {
+    for event in score_events.read() {
+        if !config.milestones.scores.contains(&event.score) {
+            continue;
+        }
+
+        let (width, height) = MILESTONE_BANNER_SIZE;
+        commands
+            .spawn((
+                Sprite::from_color(color(MILESTONE_BANNER_COLOR), Vec2::new(width, height)),
+                Transform::from_xyz(
+                    banner_x(0.0, &viewport),
+                    viewport.half_height() - MILESTONE_BANNER_Y,
+                    8.0,
+                ),
+                MilestoneBanner {
+                    elapsed: 0.0,
+                    duration: config.milestones.banner_duration,
+                },
+            ))
+            .with_child((
+                Text2d::new(format!("{} POINTS!", event.score)),
+                TextFont {
+                    font_size: MILESTONE_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            ));
+
+        commands.spawn((
+            AudioPlayer(fanfare.0.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(
+                config.music.volume * settings.music_volume.factor(),
+            )),
+        ));
+    }
+}
+
+/// Slides milestone banners across the screen and removes them at the end.
+pub fn update_milestone_banners(
+    mut commands: Commands,
+    time: Res<Time>,
+    viewport: Res<GameViewport>,
+    mut query: Query<(Entity, &mut MilestoneBanner, &mut Transform)>,
+) {
+    for (entity, mut banner, mut transform) in query.iter_mut() {
+        banner.elapsed += time.delta_secs();
+        let progress = banner.elapsed / banner.duration.max(f32::EPSILON);
+        if progress >= 1.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.x = banner_x(progress, &viewport);
+        transform.translation.y = viewport.half_height() - MILESTONE_BANNER_Y;
+    }
+}
+
+/// Returns the banner's x position at `progress` (0.0 - 1.0) through its time.
+fn banner_x(progress: f32, viewport: &GameViewport) -> f32 {
+    let travel = viewport.half_width() + MILESTONE_BANNER_SIZE.0 / 2.0;
+    banner_offset(progress) * travel
+}
+
+/// Returns the banner's offset from the center at `progress`, from 1.0 (off
+/// the right edge) to -1.0 (off the left edge).
+///
+/// The banner eases in from the right, holds in the middle, and eases out to
+/// the left.
+fn banner_offset(progress: f32) -> f32 {
+    let slide = MILESTONE_SLIDE_FRACTION;
+    let ease = |t: f32| t * t * (3.0 - 2.0 * t);
+    if progress < slide {
+        1.0 - ease(progress / slide)
+    } else if progress > 1.0 - slide {
+        -ease((progress - (1.0 - slide)) / slide)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn banner_slides_in_holds_and_slides_out() {
+        assert_eq!(banner_offset(0.0), 1.0);
+        assert_eq!(banner_offset(0.5), 0.0);
+        assert!((banner_offset(1.0) + 1.0).abs() < 1e-6);
+        assert!(banner_offset(MILESTONE_SLIDE_FRACTION / 2.0) > 0.0);
+        assert!(banner_offset(1.0 - MILESTONE_SLIDE_FRACTION / 2.0) < 0.0);
+    }
+}
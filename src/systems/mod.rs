@@ -2,28 +2,185 @@
 //!
 //! This module re-exports all game systems organized by functionality.
 
+pub mod achievements;
+pub mod attract;
+pub mod bench;
 pub mod bird;
+pub mod boss;
+pub mod buttons;
+pub mod challenge;
+pub mod chaser;
+pub mod cloud;
+pub mod clouds;
 pub mod collision;
+pub mod companion;
+pub mod config;
+pub mod console;
+pub mod controls;
+pub mod crash_report;
+pub mod debug;
 pub mod effects;
+pub mod events;
+pub mod flicker;
+pub mod flocks;
+pub mod focus;
+pub mod fog;
 pub mod game;
+pub mod graphics;
+pub mod haptics;
+pub mod hazards;
+pub mod idle;
+pub mod indicator;
+pub mod jukebox;
+pub mod letters;
+pub mod lighting;
+pub mod logging;
+pub mod milestones;
+pub mod music;
+pub mod mutators;
+pub mod obstacles;
+pub mod outline;
+pub mod pause;
 pub mod pipes;
+pub mod playtime;
+pub mod powerups;
+pub mod preview;
+pub mod race;
+pub mod replay;
+pub mod results;
+pub mod revive;
+pub mod rewind;
 pub mod score;
+pub mod seasonal;
+pub mod seed_entry;
+pub mod settings;
 pub mod setup;
+pub mod sfx;
+pub mod splits;
+pub mod telemetry;
+pub mod theme;
+pub mod trails;
+pub mod transition;
+pub mod tutorial;
+pub mod verify;
 pub mod viewport;
+pub mod vignette;
+pub mod zones;
 
 // Re-export commonly used systems for convenient access
-pub use bird::{bird_flap, bird_physics, bird_tilt};
-pub use collision::check_collisions;
+pub use achievements::{show_achievement_toasts, unlock_achievements, update_toasts};
+pub use attract::{despawn_attract_demo, run_attract_demo, update_attract_mode};
+pub use bench::{apply_bench_config, drive_bench_input, record_bench_frame, start_bench};
+pub use bird::{
+    bird_auto_flap, bird_flap, bird_physics, bird_tilt, buffer_flap_input, hide_bird,
+    reset_auto_flap, reset_flap_cooldown, scale_bird, show_bird, update_wing_recharge,
+};
+pub use boss::{finish_boss_segment, move_boss_wall, spawn_boss_wall, start_boss_segment};
+pub use buttons::{send_button_clicks, style_buttons, update_buttons};
+pub use challenge::{
+    init_challenge_clock, start_challenge, sync_challenge_clock, update_challenge_buttons,
+};
+pub use chaser::{despawn_chaser, spawn_chaser, steer_chaser};
+pub use cloud::{apply_cloud_sync, init_cloud_sync, request_cloud_sync};
+pub use clouds::{drift_clouds, spawn_cloud_layer};
+pub use collision::{check_collisions, shatter_pipes};
+pub use companion::{follow_bird, reset_companion, update_companion_presence};
+pub use config::{apply_game_config, load_game_config, poll_watched_assets, watch_loaded_assets};
+pub use console::{handle_console_input, spawn_console, update_console};
+pub use controls::{
+    despawn_controls_menu, navigate_controls, spawn_controls_menu, update_controls_rows,
+};
+pub use crash_report::{init_crash_reports, track_clean_exit};
+pub use debug::{spawn_build_info, toggle_debug_overlay, update_build_info, update_debug_overlay};
 pub use effects::{
-    spawn_death_particles, spawn_flap_particles, trigger_bird_squash, trigger_death_effects,
-    trigger_score_effects, trigger_score_pop, update_bird_squash, update_edge_flash,
-    update_particles, update_score_pop, update_screen_flash, update_screen_shake,
+    apply_camera_rig, release_camera_zoom, spawn_death_particles, spawn_flap_particles,
+    spawn_score_popups, spawn_shatter_particles, trigger_bird_squash, trigger_death_effects,
+    trigger_score_effects, trigger_score_pop, update_bird_squash, update_camera_zoom,
+    update_edge_flash, update_particles, update_score_pop, update_score_popups,
+    update_screen_flash, update_screen_shake,
 };
+pub use events::forward_message;
+pub use flicker::fade_pipes_near_bird;
+pub use flocks::{fly_flocks, spawn_flock_layer};
+pub use focus::move_menu_focus;
+pub use fog::{spawn_fog, update_fog};
 pub use game::{restart_game, start_game};
-pub use pipes::{pipe_movement, pipe_spawner};
-pub use score::update_score;
+pub use graphics::{apply_emissive, apply_graphics_settings, limit_frame_rate};
+pub use haptics::{play_haptics, rumble_gamepads};
+pub use hazards::{
+    blow_wind, clear_hazards, drop_crates, fly_hazard_flocks, reset_hazards, schedule_hazards,
+    trigger_hazard_warnings,
+};
+pub use idle::{animate_idle_bird, start_idle_animation, stop_idle_animation};
+pub use indicator::{spawn_gap_indicator, update_gap_indicator};
+pub use jukebox::{
+    despawn_jukebox_menu, navigate_jukebox, spawn_jukebox_menu, update_jukebox_rows,
+};
+pub use letters::{
+    collect_letters, float_letters, reset_letters, spawn_letters, update_letter_icons,
+};
+pub use lighting::{spawn_lighting_overlay, update_lighting};
+pub use logging::{log_deaths, log_scores, log_state_transitions};
+pub use milestones::{announce_milestones, load_fanfare, update_milestone_banners};
+pub use music::{start_music, update_music_layers};
+pub use mutators::{
+    despawn_mutators_menu, navigate_mutators, open_mutators, spawn_mutators_menu,
+    update_mutator_rows,
+};
+pub use obstacles::{bob_floating_blocks, move_gate_doors, spin_blades};
+pub use outline::sync_text_outlines;
+pub use pause::{despawn_pause_menu, navigate_pause_menu, pause_on_focus_loss, spawn_pause_menu};
+pub use pipes::{load_pipe_patterns, pipe_movement, pipe_spawner, reset_pipe_sequence};
+pub use playtime::{store_playtime_on_exit, track_playtime};
+pub use powerups::{collect_stars, reset_invincibility, update_invincibility};
+pub use preview::{spawn_pipe_preview, update_pipe_preview};
+pub use race::{
+    announce_race_winner, init_race_session, receive_race_packets, send_race_death,
+    send_race_state, start_race_run, update_race_opponents,
+};
+pub use replay::{
+    despawn_ghosts, finish_run_recording, move_ghosts, record_flaps, start_run_recording,
+    write_run_recording,
+};
+pub use results::{
+    animate_results, celebrate_new_record, fall_confetti, reset_results_animation,
+    skip_results_count_up, start_results_animation,
+};
+pub use revive::{
+    accept_revive, decline_revive, despawn_revive_prompt, reset_revive, spawn_revive_prompt,
+};
+pub use rewind::{
+    apply_rewind, clear_rewind_buffer, despawn_rewind_button, offer_rewind, record_snapshot,
+    reset_rewind, rewind_on_request,
+};
+pub use score::{
+    announce_rejected_scores, bank_coins, hide_run_stats, record_high_score, reset_run_stats,
+    show_run_stats, update_best_score_text, update_score, update_score_text,
+};
+pub use seasonal::{animate_decorations, spawn_decoration_layer};
+pub use seed_entry::{despawn_seed_entry, spawn_seed_entry, type_seed_code, update_seed_entry};
+pub use settings::{
+    despawn_settings_menu, navigate_settings, open_settings, spawn_settings_menu,
+    update_settings_rows,
+};
 pub use setup::setup;
+pub use sfx::{load_sound_effects, play_menu_sounds, play_sound_effects};
+pub use splits::{finish_splits, record_splits, reset_splits, update_split_timer};
+pub use telemetry::{
+    init_telemetry, record_deaths, record_run_started, record_scores, track_session_time,
+};
+pub use theme::{apply_theme, load_themes, recolor_themed_entities, shift_theme_hues};
+pub use trails::{apply_trail_gravity, emit_trail, load_trails};
+pub use transition::{start_transition, update_transition};
+pub use tutorial::{
+    despawn_tutorial_text, finish_tutorial, guide_tutorial_flaps, spawn_flap_prompt,
+    spawn_practice_gap, start_tutorial,
+};
+pub use verify::{drive_verification_input, prepare_verification, step_verification};
 pub use viewport::{
-    initial_viewport_setup, update_edge_flash_positions, update_fill_screen_entities,
-    update_fill_width_entities, update_viewport,
+    initial_viewport_setup, scale_hud_text, sharpen_world_text, update_edge_flash_positions,
+    update_fill_screen_entities, update_fill_width_entities, update_ui_scale, update_viewport,
+    wrap_world_text,
 };
+pub use vignette::{pulse_vignette, spawn_vignette_overlay, update_vignette};
+pub use zones::update_gravity_zones;
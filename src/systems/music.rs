@@ -0,0 +1,45 @@
+//! Adaptive music systems.
+//!
+//! This module starts every music layer together at startup and fades each
+//! one in or out depending on the current score.
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::components::Music;
+use crate::config::GameConfig;
+use crate::music::{MusicLayer, MusicLoop};
+use crate::resources::Score;
+use crate::settings::Settings;
+
+/// Synthesizes the music layers and starts them all, silent until faded in.
+pub fn start_music(mut commands: Commands, mut loops: ResMut<Assets<MusicLoop>>) {
+    for layer in MusicLayer::ALL {
+        commands.spawn((
+            AudioPlayer(loops.add(layer.synthesize())),
+            PlaybackSettings::LOOP.with_volume(Volume::Linear(0.0)),
+            Music { layer },
+        ));
+    }
+}
+
+/// Fades music layers towards the volume the current score and the music
+/// volume setting call for.
+pub fn update_music_layers(
+    time: Res<Time>,
+    score: Res<Score>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    mut query: Query<(&Music, &mut AudioSink)>,
+) {
+    let music = &config.music;
+    let step = time.delta_secs() / music.fade_time.max(f32::EPSILON);
+    for (Music { layer }, mut sink) in query.iter_mut() {
+        let current = sink.volume().to_linear();
+        let target = layer.target_volume(score.0, music) * settings.music_volume.factor();
+        if current != target {
+            let volume = current + (target - current).clamp(-step, step);
+            sink.set_volume(Volume::Linear(volume));
+        }
+    }
+}
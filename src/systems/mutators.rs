@@ -0,0 +1,190 @@
+//! Mutator screen systems.
+//!
+//! The mutator screen, opened from the menu, lists every mutator with its
+//! on/off state and starts a run with the ones picked. The mutators stay on
+//! for the following runs until they are turned off here again. Races are
+//! flown without mutators, so the screen doesn't open during one.
+
+use bevy::prelude::*;
+
+use crate::components::{
+    FillScreen, Focusable, HitArea, InstructionText, MenuButton, MutatorRowText, MutatorsButton,
+    MutatorsUi, WorldButton,
+};
+use crate::input::{MenuAction, MenuInput};
+use crate::mutators::{MutatorRow, Mutators};
+use crate::race::RaceSession;
+use crate::resources::{ButtonClicked, GameViewport, MutatorsMenu};
+use crate::states::GameState;
+use crate::systems::focus::step_focus;
+
+/// Vertical distance between mutator rows.
+const ROW_SPACING: f32 = 36.0;
+
+/// Height of the first row on screen.
+const FIRST_ROW_Y: f32 = 105.0;
+
+/// Opens the mutator screen when its menu button is clicked or confirmed.
+pub fn open_mutators(
+    menu_input: MenuInput,
+    mut clicks: MessageReader<ButtonClicked<MutatorsButton>>,
+    button_query: Query<&Focusable, With<MutatorsButton>>,
+    race: Option<Res<RaceSession>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let button_clicked = clicks.read().count() > 0;
+    let button_confirmed = menu_input.just_pressed(MenuAction::Confirm)
+        && button_query.iter().any(|focusable| focusable.focused);
+
+    if (button_clicked || button_confirmed) && race.is_none() {
+        next_state.set(GameState::Mutators);
+    }
+}
+
+/// Builds the mutator screen and hides the menu text behind it.
+///
+/// The menu text stays hidden when the screen closes into a run; going back
+/// to the menu shows it again (see `navigate_mutators`).
+#[allow(clippy::type_complexity)]
+pub fn spawn_mutators_menu(
+    mut commands: Commands,
+    viewport: Res<GameViewport>,
+    mutators: Res<Mutators>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+) {
+    commands.insert_resource(MutatorsMenu::default());
+
+    for mut visibility in menu_text_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+
+    // Dim the world behind the menu
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            custom_size: Some(Vec2::new(viewport.width * 2.0, viewport.height * 2.0)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        FillScreen,
+        MutatorsUi,
+    ));
+
+    commands.spawn((
+        Text2d::new("Mutators"),
+        TextFont {
+            font_size: 36.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, 175.0, 6.0),
+        MutatorsUi,
+    ));
+
+    commands.spawn((
+        Text2d::new("Runs with mutators are ranked on their own"),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgba(1.0, 1.0, 1.0, 0.7)),
+        Transform::from_xyz(0.0, 140.0, 6.0),
+        MutatorsUi,
+    ));
+
+    for (index, row) in MutatorRow::ALL.into_iter().enumerate() {
+        commands.spawn((
+            Text2d::new(row.label(&mutators)),
+            TextFont {
+                font_size: 22.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(0.0, FIRST_ROW_Y - index as f32 * ROW_SPACING, 6.0),
+            HitArea {
+                size: Vec2::new(viewport.width, ROW_SPACING),
+            },
+            WorldButton::default(),
+            Focusable::new(index as u32),
+            MutatorRowText(row),
+            MutatorsUi,
+        ));
+    }
+}
+
+/// Removes the mutator screen.
+pub fn despawn_mutators_menu(mut commands: Commands, ui_query: Query<Entity, With<MutatorsUi>>) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Handles input on the mutator screen.
+///
+/// Up/Down (or the d-pad) select a row, Left/Right or Space/Enter (or the
+/// south button) turn the selected mutator on or off or activate the row,
+/// and Escape (or the east button) returns to the menu. Clicking or tapping
+/// a row activates it.
+#[allow(clippy::type_complexity)]
+pub fn navigate_mutators(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    menu_input: MenuInput,
+    mut row_clicks: MessageReader<ButtonClicked<MutatorRowText>>,
+    mut menu: ResMut<MutatorsMenu>,
+    mut mutators: ResMut<Mutators>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+) {
+    let row_count = MutatorRow::ALL.len();
+
+    let mut back = menu_input.just_pressed(MenuAction::Back);
+    menu.selected = step_focus(menu.selected, menu_input.vertical_step(), row_count);
+
+    let mut activated = keyboard_input.just_pressed(KeyCode::Space)
+        || menu_input.just_pressed(MenuAction::Confirm)
+        || menu_input.just_pressed(MenuAction::Left)
+        || menu_input.just_pressed(MenuAction::Right);
+    for ButtonClicked(MutatorRowText(row)) in row_clicks.read() {
+        if let Some(index) = MutatorRow::ALL.iter().position(|other| other == row) {
+            menu.selected = index;
+            activated = true;
+        }
+    }
+
+    if activated && !back {
+        match MutatorRow::ALL[menu.selected % row_count] {
+            MutatorRow::Toggle(mutator) => mutators.toggle(mutator),
+            MutatorRow::Start => {
+                info!("Starting a run with mutators [{}]", mutators.tag());
+                next_state.set(GameState::Playing);
+            }
+            MutatorRow::Back => back = true,
+        }
+    }
+
+    if back {
+        next_state.set(GameState::Menu);
+        for mut visibility in menu_text_query.iter_mut() {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+/// Refreshes the row labels and focuses the selected row.
+pub fn update_mutator_rows(
+    menu: Res<MutatorsMenu>,
+    mutators: Res<Mutators>,
+    mut row_query: Query<(&MutatorRowText, &mut Text2d, &mut Focusable)>,
+) {
+    for (MutatorRowText(row), mut text, mut focusable) in row_query.iter_mut() {
+        let label = row.label(&mutators);
+        if text.0 != label {
+            text.0 = label;
+        }
+
+        let selected = MutatorRow::ALL.get(menu.selected) == Some(row);
+        if focusable.focused != selected {
+            focusable.focused = selected;
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Obstacle systems.
+//!
+//! This module spawns blades, gates, and floating blocks next to random pipe
+//! pairs and animates them. Obstacles scroll with the pipes (see
+//! `pipe_movement`) and are hit-tested by `check_collisions` through their
+//! `CollisionShape`.
+
+use bevy::prelude::*;
+
+use crate::components::{CollisionShape, FloatingBlock, GateDoor, Obstacle, SpinningBlade};
+use crate::config::GameConfig;
+use crate::constants::PIPE_WIDTH;
+use crate::rng::RngStream;
+use crate::theme::{Theme, color};
+
+/// Gate doors sit behind the pipes so they are hidden while retracted.
+const GATE_DOOR_Z: f32 = -0.5;
+
+/// Rolls for an obstacle next to a random pipe pair and spawns it.
+///
+/// `x` is the pipe pair's position, `gap` its (center, size), and `bottom` /
+/// `top` the limits of the play area.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_gap_obstacle(
+    commands: &mut Commands,
+    x: f32,
+    (center, size): (f32, f32),
+    (bottom, top): (f32, f32),
+    score: u32,
+    config: &GameConfig,
+    theme: &Theme,
+    rng: &mut RngStream,
+) {
+    let obstacles = &config.obstacles;
+    if score < obstacles.min_score {
+        return;
+    }
+
+    let roll = rng.f32();
+    let color = color(theme.obstacle_color);
+
+    if roll < obstacles.blade_chance {
+        // Leave room to pass on one side of the blade
+        let side = if rng.f32() < 0.5 { 1.0 } else { -1.0 };
+        let offset = side * size / 4.0;
+        commands.spawn((
+            Sprite::from_color(
+                color,
+                Vec2::new(obstacles.blade_size, obstacles.blade_size / 4.0),
+            ),
+            Transform::from_xyz(x, center + offset, 0.5),
+            Obstacle,
+            SpinningBlade {
+                speed: obstacles.blade_spin_speed,
+            },
+            // The blade hits anything inside the circle it sweeps
+            CollisionShape::Circle(obstacles.blade_size / 2.0),
+        ));
+    } else if roll < obstacles.blade_chance + obstacles.gate_chance {
+        let door_height = (size - obstacles.gate_min_opening) / 2.0;
+        if door_height <= 0.0 {
+            return;
+        }
+        let door_size = Vec2::new(PIPE_WIDTH - 10.0, door_height);
+        for direction in [-1.0, 1.0] {
+            let gap_edge = center + direction * size / 2.0;
+            let open_y = gap_edge + direction * door_height / 2.0;
+            commands.spawn((
+                Sprite::from_color(color, door_size),
+                Transform::from_xyz(x, open_y, GATE_DOOR_Z),
+                Obstacle,
+                GateDoor {
+                    open_y,
+                    closed_y: gap_edge - direction * door_height / 2.0,
+                    speed: obstacles.gate_speed,
+                    elapsed: 0.0,
+                },
+                CollisionShape::Box(door_size),
+            ));
+        }
+    } else if roll < obstacles.blade_chance + obstacles.gate_chance + obstacles.block_chance {
+        // Halfway to the next pipe pair
+        let block_x = x + config.pipes.spawn_distance / 2.0;
+        let margin = obstacles.block_size / 2.0 + obstacles.block_bob_height;
+        let base_y = if top - margin > bottom + margin {
+            rng.range(bottom + margin, top - margin)
+        } else {
+            (bottom + top) / 2.0
+        };
+        let block_size = Vec2::splat(obstacles.block_size);
+        commands.spawn((
+            Sprite::from_color(color, block_size),
+            Transform::from_xyz(block_x, base_y, 0.5),
+            Obstacle,
+            FloatingBlock {
+                base_y,
+                amplitude: obstacles.block_bob_height,
+                speed: obstacles.block_bob_speed,
+                elapsed: 0.0,
+            },
+            CollisionShape::Box(block_size),
+        ));
+    }
+}
+
+/// Rotates spinning blades.
+pub fn spin_blades(time: Res<Time>, mut query: Query<(&SpinningBlade, &mut Transform)>) {
+    for (blade, mut transform) in query.iter_mut() {
+        transform.rotate_z(blade.speed * time.delta_secs());
+    }
+}
+
+/// Slides gate doors between their open and closed positions.
+///
+/// Doors start open and ease in and out of each end of the cycle.
+pub fn move_gate_doors(time: Res<Time>, mut query: Query<(&mut GateDoor, &mut Transform)>) {
+    for (mut door, mut transform) in query.iter_mut() {
+        door.elapsed += time.delta_secs();
+        let closed = (1.0 - (door.elapsed * door.speed).cos()) / 2.0;
+        transform.translation.y = door.open_y.lerp(door.closed_y, closed);
+    }
+}
+
+/// Bobs floating blocks up and down around their base height.
+pub fn bob_floating_blocks(
+    time: Res<Time>,
+    mut query: Query<(&mut FloatingBlock, &mut Transform)>,
+) {
+    for (mut block, mut transform) in query.iter_mut() {
+        block.elapsed += time.delta_secs();
+        transform.translation.y =
+            block.base_y + block.amplitude * (block.elapsed * block.speed).sin();
+    }
+}
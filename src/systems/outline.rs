@@ -0,0 +1,95 @@
+//! Text outline systems.
+//!
+//! White HUD text is hard to read over a light sky or a flash, so the score
+//! and instruction text can be drawn with a dark drop shadow (Bevy's text
+//! shadow) or a dark outline. The outline is made of copies of the text
+//! offset in eight directions and drawn behind it, kept in step with it here.
+
+use bevy::prelude::*;
+
+use crate::components::{OutlineGlyph, OutlinedText};
+use crate::constants::{TEXT_OUTLINE_ALPHA, TEXT_OUTLINE_COLOR, TEXT_SHADOW_OFFSET};
+use crate::settings::{Settings, TextOutline};
+use crate::theme::color;
+
+/// Shows the shadow or the outline the settings ask for, and copies the
+/// outlined text's contents, font, layout, opacity, visibility and pop
+/// animation to its outline.
+#[allow(clippy::type_complexity)]
+pub fn sync_text_outlines(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    sources: Query<
+        (
+            Entity,
+            &Text,
+            &TextFont,
+            &TextLayout,
+            &TextColor,
+            &Visibility,
+            &UiTransform,
+            Has<TextShadow>,
+        ),
+        With<OutlinedText>,
+    >,
+    mut glyphs: Query<
+        (
+            &OutlineGlyph,
+            &mut Text,
+            &mut TextFont,
+            &mut TextLayout,
+            &mut TextColor,
+            &mut Visibility,
+            &mut UiTransform,
+        ),
+        Without<OutlinedText>,
+    >,
+) {
+    let shadow = settings.text_outline == TextOutline::Shadow;
+    for (entity, .., has_shadow) in sources.iter() {
+        if shadow && !has_shadow {
+            commands.entity(entity).insert(TextShadow {
+                offset: Vec2::new(TEXT_SHADOW_OFFSET.0, TEXT_SHADOW_OFFSET.1),
+                color: color(TEXT_OUTLINE_COLOR).with_alpha(TEXT_OUTLINE_ALPHA),
+            });
+        } else if !shadow && has_shadow {
+            commands.entity(entity).remove::<TextShadow>();
+        }
+    }
+
+    let outline = settings.text_outline == TextOutline::Outline;
+    for (glyph, mut text, mut font, mut layout, mut text_color, mut visibility, mut transform) in
+        glyphs.iter_mut()
+    {
+        let Ok((
+            _,
+            source_text,
+            source_font,
+            source_layout,
+            source_color,
+            source_visibility,
+            source_transform,
+            _,
+        )) = sources.get(glyph.source)
+        else {
+            continue;
+        };
+        if !outline {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(*source_visibility);
+        if text.0 != source_text.0 {
+            text.0.clone_from(&source_text.0);
+        }
+        if *font != *source_font {
+            *font = source_font.clone();
+        }
+        if layout.justify != source_layout.justify || layout.linebreak != source_layout.linebreak {
+            *layout = *source_layout;
+        }
+        let alpha = TEXT_OUTLINE_ALPHA * source_color.0.alpha();
+        text_color.set_if_neq(TextColor(color(TEXT_OUTLINE_COLOR).with_alpha(alpha)));
+        transform.set_if_neq(*source_transform);
+    }
+}
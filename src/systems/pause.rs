@@ -0,0 +1,182 @@
+//! Pause systems.
+//!
+//! A run pauses on its own when the window loses focus or the mobile app is
+//! sent to the background, so the bird doesn't crash while nobody watches.
+//! Pausing freezes virtual time; resuming requires explicit input. The pause
+//! screen also offers to give up the run. Races are never paused, since the
+//! other players keep flying.
+
+use bevy::prelude::*;
+use bevy::text::{Justify, LineBreak};
+use bevy::window::{AppLifecycle, WindowFocused};
+
+use crate::bindings::GameAction;
+use crate::components::{
+    Bird, FillScreen, Focusable, HitArea, InstructionText, PauseButton, PauseUi, WorldButton,
+};
+use crate::input::{ActionInput, MenuAction, MenuInput, PointerInput};
+use crate::race::RaceSession;
+use crate::replay::ReplayRecorder;
+use crate::resources::{ButtonClicked, DeathCause, GameViewport};
+use crate::rng::seed_code;
+use crate::states::{GameState, PauseState};
+use crate::systems::collision::trigger_game_over;
+
+/// Vertical distance between the pause screen's buttons.
+const PAUSE_BUTTON_SPACING: f32 = 40.0;
+
+/// Pauses the run when the window loses focus or the app is backgrounded.
+pub fn pause_on_focus_loss(
+    mut focus_events: MessageReader<WindowFocused>,
+    mut lifecycle_events: MessageReader<AppLifecycle>,
+    race: Option<Res<RaceSession>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    let lost_focus = focus_events.read().any(|event| !event.focused);
+    let backgrounded = lifecycle_events
+        .read()
+        .any(|event| matches!(event, AppLifecycle::WillSuspend | AppLifecycle::Suspended));
+
+    if (lost_focus || backgrounded) && race.is_none() {
+        next_state.set(PauseState::Paused);
+    }
+}
+
+/// Handles input on the pause screen.
+///
+/// The flap key or button, Escape, or the east gamepad button resume the run; confirming
+/// activates the focused button. Clicking or tapping a button activates it,
+/// and clicking or tapping anywhere else resumes the run.
+#[allow(clippy::too_many_arguments)]
+pub fn navigate_pause_menu(
+    actions: ActionInput,
+    menu_input: MenuInput,
+    pointer: PointerInput,
+    mut clicks: MessageReader<ButtonClicked<PauseButton>>,
+    button_query: Query<(&GlobalTransform, &HitArea, &Focusable, &PauseButton)>,
+    bird_query: Query<&Transform, With<Bird>>,
+    mut next_pause: ResMut<NextState<PauseState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut instruction_query: Query<(&mut Visibility, &mut Text), With<InstructionText>>,
+    mut commands: Commands,
+) {
+    let mut pressed = clicks.read().last().map(|ButtonClicked(button)| *button);
+    let pressed_background = pointer
+        .just_pressed_positions()
+        .into_iter()
+        .any(|position| {
+            !button_query
+                .iter()
+                .any(|(transform, area, _, _)| area.contains(transform, position))
+        });
+    if pressed_background {
+        pressed = Some(PauseButton::Resume);
+    }
+    if menu_input.just_pressed(MenuAction::Confirm) {
+        pressed = button_query
+            .iter()
+            .find(|(_, _, focusable, _)| focusable.focused)
+            .map(|(_, _, _, button)| *button);
+    }
+    if actions.just_pressed(GameAction::Flap) || menu_input.just_pressed(MenuAction::Back) {
+        pressed = Some(PauseButton::Resume);
+    }
+
+    match pressed {
+        Some(PauseButton::Resume) => next_pause.set(PauseState::Running),
+        Some(PauseButton::GiveUp) => {
+            let position = bird_query
+                .iter()
+                .next()
+                .map_or(Vec3::ZERO, |transform| transform.translation);
+            trigger_game_over(
+                &mut next_state,
+                &mut instruction_query,
+                &mut commands,
+                position,
+                DeathCause::GaveUp,
+            );
+        }
+        None => {}
+    }
+}
+
+/// Freezes time and shows the pause screen, with the run's seed code.
+pub fn spawn_pause_menu(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    viewport: Res<GameViewport>,
+    recorder: Res<ReplayRecorder>,
+) {
+    time.pause();
+
+    // Dim the frozen run behind the text
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.0, 0.0, 0.0, 0.5),
+            custom_size: Some(Vec2::new(viewport.width * 2.0, viewport.height * 2.0)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        FillScreen,
+        PauseUi,
+    ));
+
+    commands.spawn((
+        Text2d::new("Paused"),
+        TextFont {
+            font_size: 36.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, 60.0, 6.0),
+        PauseUi,
+    ));
+
+    commands.spawn((
+        Text2d::new(format!("Seed code: {}", seed_code(recorder.replay.seed))),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::srgba(1.0, 1.0, 1.0, 0.7)),
+        Transform::from_xyz(0.0, 100.0, 6.0),
+        PauseUi,
+    ));
+
+    let buttons = [
+        (PauseButton::Resume, "Resume"),
+        (PauseButton::GiveUp, "Give up"),
+    ];
+    for (order, (button, label)) in (0..).zip(buttons) {
+        commands.spawn((
+            Text2d::new(label),
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new(Justify::Center, LineBreak::NoWrap),
+            Transform::from_xyz(0.0, -(order as f32) * PAUSE_BUTTON_SPACING, 6.0),
+            HitArea {
+                size: Vec2::new(160.0, PAUSE_BUTTON_SPACING),
+            },
+            WorldButton::default(),
+            Focusable::new(order),
+            button,
+            PauseUi,
+        ));
+    }
+}
+
+/// Removes the pause screen and lets time run again.
+pub fn despawn_pause_menu(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    ui_query: Query<Entity, With<PauseUi>>,
+) {
+    time.unpause();
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -4,102 +4,570 @@
 
 use bevy::prelude::*;
 
-use crate::components::{Pipe, Scored};
+use crate::components::{
+    Bird, GapSensor, GravityZone, LetterPickup, Obstacle, Pipe, PipePair, PipeTier, StarPickup,
+};
+use crate::config::{GameConfig, PhysicsConfig, PipeConfig};
 use crate::constants::{
-    GROUND_HEIGHT, PIPE_GAP_END, PIPE_GAP_SCALE_SCORE, PIPE_GAP_START_MAX, PIPE_GAP_START_MIN,
-    PIPE_WIDTH, WORLD_SCROLL_SPEED,
+    BIRD_SIZE, GAME_HEIGHT, GAP_STEP_MARGIN, GRAVITY_ZONE_ALPHA, GRAVITY_ZONE_CHANCE,
+    GRAVITY_ZONE_COLOR, GRAVITY_ZONE_MARGIN, GRAVITY_ZONE_MIN_SCORE, GRAVITY_ZONE_WIDTH,
+    GROUND_HEIGHT, PIPE_TIER_SCORES, PIPE_WIDTH, STAR_CHANCE, TUNNEL_STEP_MARGIN,
+};
+use crate::patterns::{
+    GapSpec, PATTERNS_FOLDER, PatternLibrary, PipeColumn, PipePattern, column_segments,
+};
+use crate::resources::{
+    DebugTools, GameViewport, GapModifier, NearbyObstacles, PipeSequence, PipeSpawnDistance, Score,
 };
-use crate::resources::{GameViewport, PipeSpawnTimer, Score};
-use crate::states::GameState;
-use crate::utils::rand_f32;
+use crate::rng::{GameRng, RngStream};
+use crate::systems::obstacles::spawn_gap_obstacle;
+use crate::systems::powerups::spawn_star;
+use crate::theme::{Theme, color};
 
-/// Spawns pipes at regular intervals.
+/// Spawns pipe columns at regular distances scrolled.
 ///
-/// Creates a pair of pipes (top and bottom) with a random gap position
-/// and random gap size for variety in gameplay.
+/// Columns come from the current pattern's queue; when it is empty, either a
+/// weighted-random authored pattern, a generated tunnel, or a random pipe pair
+/// is queued next. Random gaps stay within reach of the previous column's gap.
+/// Nothing spawns while pipes are frozen from the debug panel.
+#[allow(clippy::too_many_arguments)]
 pub fn pipe_spawner(
     mut commands: Commands,
     time: Res<Time>,
-    mut timer: ResMut<PipeSpawnTimer>,
-    state: Res<State<GameState>>,
+    mut spawn_distance: ResMut<PipeSpawnDistance>,
+    mut sequence: ResMut<PipeSequence>,
     score: Res<Score>,
     viewport: Res<GameViewport>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    patterns: Res<Assets<PipePattern>>,
+    gap_modifier: Option<Res<GapModifier>>,
+    mut rng: ResMut<GameRng>,
+    debug: Option<Res<DebugTools>>,
 ) {
-    if *state.get() != GameState::Playing {
+    if debug.is_some_and(|debug| debug.freeze_pipes) {
         return;
     }
+    if config.is_changed() {
+        spawn_distance.spacing = config.pipes.spawn_distance;
+    }
+
+    // The same distance `pipe_movement` scrolls the pipes this frame
+    if !spawn_distance.advance(config.pipes.scroll_speed * time.delta_secs()) {
+        return;
+    }
+
+    if sequence.pending.is_empty() {
+        let _span = info_span!("choose_columns").entered();
+        let next = choose_columns(&patterns, score.0, &config, &mut rng.pipes);
+        sequence.pending.extend(next);
+    }
+
+    if let Some(column) = sequence.pending.pop_front() {
+        let _span = info_span!("spawn_pipe_column", gaps = column.gaps.len()).entered();
+        // The spacing just scrolled is the time the bird has to get from the
+        // previous gap to this one
+        let interval = spawn_distance.spacing / config.pipes.scroll_speed.max(f32::EPSILON);
+        let reach = sequence.last_gap.map(|previous| {
+            let (mut climb, mut drop) = reachable_gap_steps(&config.physics, interval);
+            // Inverted gravity swaps the bird's easy and hard directions
+            if config.physics.inverted_gravity {
+                std::mem::swap(&mut climb, &mut drop);
+            }
+            (previous - drop, previous + climb)
+        });
+        let gap = spawn_pipe_column(
+            &mut commands,
+            &column,
+            score.0,
+            &viewport,
+            &config,
+            &theme,
+            gap_modifier.as_deref(),
+            reach,
+            &mut rng.pipes,
+        );
+        sequence.last_gap = Some(gap);
+
+        // The column's spacing decides when the next one spawns
+        let spacing = config.pipes.spawn_distance * column.spacing.max(0.1);
+        spawn_distance.spacing = spacing;
+        debug!(
+            gaps = column.gaps.len(),
+            score = score.0,
+            next_in = spacing,
+            "Spawned pipe column"
+        );
+    }
+}
+
+/// Picks the next columns to spawn: an authored pattern, a tunnel, or a
+/// random pipe pair.
+///
+/// Patterns are sorted by name so the same seed always picks the same ones.
+fn choose_columns(
+    patterns: &Assets<PipePattern>,
+    score: u32,
+    config: &GameConfig,
+    rng: &mut RngStream,
+) -> Vec<PipeColumn> {
+    let pipes = &config.pipes;
+    let mut available: Vec<&PipePattern> = patterns
+        .iter()
+        .map(|(_, pattern)| pattern)
+        .filter(|pattern| pattern.weight_at(score) > 0.0 && !pattern.columns.is_empty())
+        .collect();
+    available.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let tunnel_weight = if score >= pipes.tunnel_min_score && pipes.tunnel_length > 0 {
+        pipes.tunnel_weight
+    } else {
+        0.0
+    };
+    let weights: Vec<f32> = [pipes.random_pair_weight, tunnel_weight]
+        .into_iter()
+        .chain(available.iter().map(|pattern| pattern.weight_at(score)))
+        .collect();
+
+    match rng.weighted_index(&weights) {
+        Some(1) => {
+            debug!("Spawning a tunnel");
+            tunnel_columns(score, pipes, &config.physics, rng)
+        }
+        Some(index) if index > 1 => {
+            let pattern = available[index - 2];
+            debug!("Spawning pipe pattern {}", pattern.name);
+            pattern.columns.clone()
+        }
+        _ => vec![PipeColumn::default()],
+    }
+}
 
-    timer.0.tick(time.delta());
+/// Generates a tunnel: closely spaced columns with the tightest gap of the
+/// current difficulty, drifting up and down by reachable steps.
+fn tunnel_columns(
+    score: u32,
+    pipes: &PipeConfig,
+    physics: &PhysicsConfig,
+    rng: &mut RngStream,
+) -> Vec<PipeColumn> {
+    let (size, _) = pipes.gap_range(score);
+    // Same mapping from `GapSpec::y` to pixels as `spawn_pipe_column`
+    let range = ((GAME_HEIGHT - GROUND_HEIGHT - size - 100.0) / 2.0).max(1.0);
+    let step = max_tunnel_step(physics, pipes.spawn_interval() * pipes.tunnel_spacing);
 
-    if timer.0.just_finished() {
-        spawn_pipe_pair(&mut commands, score.0, &viewport);
+    let mut center = rng.range(-range, range) / 2.0;
+    (0..pipes.tunnel_length)
+        .map(|index| {
+            if index > 0 {
+                center = (center + rng.range(-step, step)).clamp(-range, range);
+            }
+            let last = index + 1 == pipes.tunnel_length;
+            PipeColumn {
+                gaps: vec![GapSpec {
+                    y: Some(center / range),
+                    size: Some(size),
+                }],
+                spacing: if last { 1.0 } else { pipes.tunnel_spacing },
+            }
+        })
+        .collect()
+}
+
+/// Returns the largest height change between tunnel columns `interval`
+/// seconds apart that the bird can always follow.
+///
+/// A single flap climbs at most `flap_strength² / (2 * -gravity)`, and
+/// without flapping the bird falls `-gravity * interval² / 2` between the
+/// columns; steps stay within a margin of the smaller of the two.
+fn max_tunnel_step(physics: &PhysicsConfig, interval: f32) -> f32 {
+    let gravity = (-physics.gravity).max(f32::EPSILON);
+    let climb = physics.flap_strength.powi(2) / (2.0 * gravity);
+    let fall = gravity * interval.powi(2) / 2.0;
+    climb.min(fall) * TUNNEL_STEP_MARGIN
+}
+
+/// Returns how far (up, down) a gap can move from the previous one when the
+/// columns are `interval` seconds apart, keeping `GAP_STEP_MARGIN` of slack.
+///
+/// Flapping each time the bird stops rising climbs `flap_strength / 2` per
+/// second (and at least one flap's `flap_strength² / (2 * -gravity)`), while
+/// without flapping the bird drops at most `-gravity * interval² / 2`.
+fn reachable_gap_steps(physics: &PhysicsConfig, interval: f32) -> (f32, f32) {
+    let gravity = (-physics.gravity).max(f32::EPSILON);
+    let flap_height = physics.flap_strength.powi(2) / (2.0 * gravity);
+    let climb = flap_height.max(physics.flap_strength * interval / 2.0);
+    let drop = gravity * interval.powi(2) / 2.0;
+    (climb * GAP_STEP_MARGIN, drop * GAP_STEP_MARGIN)
+}
+
+/// Picks a random gap center at most `range` from the middle of the play
+/// area and, given `reach`, between its lowest and highest centers.
+fn random_gap_center(range: f32, reach: Option<(f32, f32)>, rng: &mut RngStream) -> f32 {
+    let range = range.max(0.0);
+    match reach {
+        Some((low, high)) => rng.range(low.clamp(-range, range), high.clamp(-range, range)),
+        None => (rng.f32() * 2.0 - 1.0) * range,
     }
 }
 
-/// Spawns a pair of pipes (top and bottom) with score-based difficulty.
-fn spawn_pipe_pair(commands: &mut Commands, current_score: u32, viewport: &GameViewport) {
-    // Calculate difficulty progress (0.0 at score 0, 1.0 at PIPE_GAP_SCALE_SCORE)
-    let difficulty = (current_score as f32 / PIPE_GAP_SCALE_SCORE as f32).min(1.0);
+/// Starts loading every pattern in the patterns folder.
+pub fn load_pipe_patterns(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PatternLibrary(asset_server.load_folder(PATTERNS_FOLDER)));
+}
 
-    // Interpolate min/max gap based on difficulty
-    // At difficulty 0: use START values, at difficulty 1: both become END value
-    let gap_min = PIPE_GAP_START_MIN + (PIPE_GAP_END - PIPE_GAP_START_MIN) * difficulty;
-    let gap_max = PIPE_GAP_START_MAX + (PIPE_GAP_END - PIPE_GAP_START_MAX) * difficulty;
+/// Clears any half-finished pattern when a new run starts.
+pub fn reset_pipe_sequence(
+    mut sequence: ResMut<PipeSequence>,
+    mut spawn_distance: ResMut<PipeSpawnDistance>,
+    config: Res<GameConfig>,
+) {
+    sequence.pending.clear();
+    sequence.last_gap = None;
+    spawn_distance.spacing = config.pipes.spawn_distance;
+}
 
-    // Random gap size between current min and max
-    let pipe_gap = gap_min + rand_f32() * (gap_max - gap_min);
+/// Spawns a column of pipes around its gaps, with score-based difficulty.
+///
+/// The pipes, and a `GapSensor` in each gap, are children of a single
+/// `PipePair` entity.
+/// Gaps without an authored size or position get a random one, like a
+/// classic pipe pair. Random sizes go through the `GapModifier`, if any, and
+/// random pairs may get an obstacle, a gravity-flip zone behind them, and,
+/// rarely, a star. Pipes are tinted by the score tier they spawn in.
+/// Random gap centers stay within `reach` (lowest, highest), if given.
+///
+/// Returns the center of the scored gap.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_pipe_column(
+    commands: &mut Commands,
+    column: &PipeColumn,
+    current_score: u32,
+    viewport: &GameViewport,
+    config: &GameConfig,
+    theme: &Theme,
+    gap_modifier: Option<&GapModifier>,
+    reach: Option<(f32, f32)>,
+    rng: &mut RngStream,
+) -> f32 {
+    // Gap range narrows as the score approaches max difficulty
+    let (gap_min, gap_max) = config.pipes.gap_range(current_score);
 
-    // Random gap position (vertical center of the gap)
-    let gap_y = (rand_f32() - 0.5) * (viewport.height - GROUND_HEIGHT - pipe_gap - 100.0);
+    let gaps: Vec<(f32, f32)> = column
+        .gaps
+        .iter()
+        .map(|spec| {
+            let size = spec.size.unwrap_or_else(|| {
+                let size = rng.range(gap_min, gap_max);
+                gap_modifier.map_or(size, |modifier| (modifier.0)(current_score, size))
+            });
+            // Vertical center of the gap, keeping clear of the ground and top
+            let range = (viewport.height - GROUND_HEIGHT - size - 100.0) / 2.0;
+            let center = match spec.y {
+                Some(position) => position * range,
+                None => random_gap_center(range, reach, rng),
+            };
+            (center, size)
+        })
+        .collect();
 
     // Spawn pipe fully off-screen (center + half width outside visible area)
     let spawn_x = viewport.half_width() + PIPE_WIDTH;
+    let bottom = -viewport.half_height() + GROUND_HEIGHT;
+    let top = viewport.half_height();
 
-    // Top pipe (green rectangle)
-    let top_pipe_height = viewport.half_height() - gap_y - pipe_gap / 2.0;
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.2, 0.7, 0.2),
-            custom_size: Some(Vec2::new(PIPE_WIDTH, top_pipe_height)),
-            ..default()
-        },
-        Transform::from_xyz(spawn_x, viewport.half_height() - top_pipe_height / 2.0, 0.0),
-        Pipe,
-    ));
+    let tier = pipe_tier(current_score);
 
-    // Bottom pipe (green rectangle)
-    let bottom_pipe_height = viewport.half_height() + gap_y - pipe_gap / 2.0 - GROUND_HEIGHT;
-    commands.spawn((
-        Sprite {
-            color: Color::srgb(0.2, 0.7, 0.2),
-            custom_size: Some(Vec2::new(PIPE_WIDTH, bottom_pipe_height)),
-            ..default()
-        },
-        Transform::from_xyz(
+    // The lowest gap is the one reported in score events
+    let scored_gap = gaps
+        .iter()
+        .map(|(center, _)| *center)
+        .min_by(f32::total_cmp)
+        .unwrap_or_default();
+
+    let segments = column_segments(&gaps, bottom, top);
+    commands
+        .spawn((
+            Transform::from_xyz(spawn_x, 0.0, 0.0),
+            Visibility::default(),
+            PipePair {
+                gap_y: scored_gap,
+                width: PIPE_WIDTH,
+                scored: false,
+            },
+        ))
+        .with_children(|pair| {
+            for (segment_bottom, segment_top) in segments {
+                let height = segment_top - segment_bottom;
+                pair.spawn((
+                    pipe_sprite(Vec2::new(PIPE_WIDTH, height), theme, tier),
+                    Transform::from_xyz(0.0, segment_bottom + height / 2.0, 0.0),
+                    Pipe,
+                    PipeTier(tier),
+                ));
+            }
+            for &(center, size) in &gaps {
+                pair.spawn((
+                    Transform::from_xyz(0.0, center, 0.0),
+                    GapSensor {
+                        size: Vec2::new(PIPE_WIDTH, size),
+                    },
+                ));
+            }
+        });
+
+    if let [gap] = gaps[..]
+        && *column == PipeColumn::default()
+    {
+        spawn_gap_obstacle(
+            commands,
             spawn_x,
-            -viewport.half_height() + GROUND_HEIGHT + bottom_pipe_height / 2.0,
-            0.0,
+            gap,
+            (bottom, top),
+            current_score,
+            config,
+            theme,
+            rng,
+        );
+        if current_score >= GRAVITY_ZONE_MIN_SCORE && rng.f32() < GRAVITY_ZONE_CHANCE {
+            let zone_x =
+                spawn_x + PIPE_WIDTH / 2.0 + GRAVITY_ZONE_MARGIN + GRAVITY_ZONE_WIDTH / 2.0;
+            spawn_gravity_zone(commands, zone_x, (bottom, top));
+        }
+        if rng.f32() < STAR_CHANCE {
+            spawn_star(commands, Vec2::new(spawn_x, gap.0));
+        }
+    }
+
+    scored_gap
+}
+
+/// Spawns a gravity-flip zone spanning the play area at the given x.
+fn spawn_gravity_zone(commands: &mut Commands, x: f32, (bottom, top): (f32, f32)) {
+    let height = top - bottom;
+    commands.spawn((
+        Sprite::from_color(
+            color(GRAVITY_ZONE_COLOR).with_alpha(GRAVITY_ZONE_ALPHA),
+            Vec2::new(GRAVITY_ZONE_WIDTH, height),
         ),
-        Pipe,
-        Scored(false),
+        // Behind the pipes and obstacles
+        Transform::from_xyz(x, bottom + height / 2.0, -0.8),
+        GravityZone,
     ));
 }
 
-/// Moves pipes from right to left and despawns them when off-screen.
+/// Returns the score tier (0 before the first of `PIPE_TIER_SCORES`).
+pub fn pipe_tier(score: u32) -> usize {
+    PIPE_TIER_SCORES
+        .iter()
+        .take_while(|&&threshold| score >= threshold)
+        .count()
+}
+
+/// Creates a themed pipe sprite of the given size and score tier.
+pub fn pipe_sprite(size: Vec2, theme: &Theme, tier: usize) -> Sprite {
+    let mut sprite = Sprite {
+        custom_size: Some(size),
+        ..default()
+    };
+    apply_pipe_theme(&mut sprite, theme, tier);
+    sprite
+}
+
+/// Applies a theme's pipe color (for the score tier) and texture to a pipe
+/// sprite.
 ///
-/// This creates the scrolling effect of the game world.
+/// Textures are tiled vertically so they don't stretch with the pipe height.
+pub fn apply_pipe_theme(sprite: &mut Sprite, theme: &Theme, tier: usize) {
+    sprite.color = color(theme.pipe_color_at(tier));
+    match &theme.pipe_image {
+        Some(image) => {
+            sprite.image = image.clone();
+            sprite.image_mode = SpriteImageMode::Tiled {
+                tile_x: false,
+                tile_y: true,
+                stretch_value: 1.0,
+            };
+        }
+        None => {
+            sprite.image = Handle::default();
+            sprite.image_mode = SpriteImageMode::Auto;
+        }
+    }
+}
+
+/// Moves pipe pairs, obstacles, stars, letters, and zones from right to left and despawns them when off-screen.
+///
+/// This creates the scrolling effect of the game world. Pipes stand still
+/// while frozen from the debug panel.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn pipe_movement(
     mut commands: Commands,
     time: Res<Time>,
-    mut query: Query<(Entity, &mut Transform), With<Pipe>>,
+    mut query: Query<
+        (Entity, &mut Transform, Option<&Sprite>, Option<&PipePair>),
+        (
+            Or<(
+                With<PipePair>,
+                With<Obstacle>,
+                With<StarPickup>,
+                With<LetterPickup>,
+                With<GravityZone>,
+            )>,
+            Without<Bird>,
+        ),
+    >,
+    bird_query: Query<&Transform, With<Bird>>,
+    mut nearby: ResMut<NearbyObstacles>,
     viewport: Res<GameViewport>,
+    config: Res<GameConfig>,
+    debug: Option<Res<DebugTools>>,
 ) {
-    for (entity, mut transform) in query.iter_mut() {
-        transform.translation.x -= WORLD_SCROLL_SPEED * time.delta_secs();
+    let _span = info_span!("scroll_pipes", entities = query.iter().len()).entered();
+    let scroll = if debug.is_some_and(|debug| debug.freeze_pipes) {
+        0.0
+    } else {
+        config.pipes.scroll_speed * time.delta_secs()
+    };
+    let column = bird_query
+        .single()
+        .ok()
+        .map(|transform| transform.translation.x);
+    nearby.0.clear();
+
+    for (entity, mut transform, sprite, pair) in query.iter_mut() {
+        transform.translation.x -= scroll;
 
         // Despawn pipes when fully off-screen (center - half width past left edge)
-        if transform.translation.x < -viewport.half_width() - PIPE_WIDTH / 2.0 {
+        let width = match (pair, sprite) {
+            (Some(pair), _) => pair.width,
+            (None, Some(sprite)) => sprite.custom_size.map_or(PIPE_WIDTH, |size| size.x),
+            (None, None) => PIPE_WIDTH,
+        };
+        let half_width = width / 2.0;
+        if transform.translation.x < -viewport.half_width() - half_width {
             commands.entity(entity).despawn();
+        } else if column
+            .is_some_and(|column| has_reached_column(transform.translation.x, half_width, column))
+        {
+            nearby.0.push(entity);
         }
     }
 }
+
+/// Returns whether an entity's left edge has reached the bird's column.
+///
+/// Entities behind the bird stay included until they scroll off-screen, so a
+/// pipe that skips past the bird in one long frame still scores.
+fn has_reached_column(x: f32, half_width: f32, column: f32) -> bool {
+    x - half_width <= column + BIRD_SIZE / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::PIPE_SPAWN_DISTANCE;
+
+    #[test]
+    fn tunnel_steps_stay_reachable() {
+        let pipes = PipeConfig::default();
+        let physics = PhysicsConfig::default();
+        let mut rng = RngStream::from_seed(7);
+        let columns = tunnel_columns(pipes.tunnel_min_score, &pipes, &physics, &mut rng);
+        assert_eq!(columns.len(), pipes.tunnel_length as usize);
+
+        let (size, _) = pipes.gap_range(pipes.tunnel_min_score);
+        let range = (GAME_HEIGHT - GROUND_HEIGHT - size - 100.0) / 2.0;
+        let step = max_tunnel_step(&physics, pipes.spawn_interval() * pipes.tunnel_spacing);
+        let heights: Vec<f32> = columns
+            .iter()
+            .map(|column| column.gaps[0].y.unwrap() * range)
+            .collect();
+        for pair in heights.windows(2) {
+            assert!((pair[1] - pair[0]).abs() <= step + 1e-3);
+        }
+        assert_eq!(columns.last().map(|column| column.spacing), Some(1.0));
+    }
+
+    #[test]
+    fn random_gaps_stay_within_reach() {
+        let physics = PhysicsConfig::default();
+        let pipes = PipeConfig::default();
+        let (climb, drop) = reachable_gap_steps(&physics, pipes.spawn_interval());
+        assert!(climb > 0.0 && drop > 0.0);
+
+        let mut rng = RngStream::from_seed(3);
+        let range = 200.0;
+        let mut previous = 0.0;
+        for _ in 0..1000 {
+            let reach = Some((previous - drop, previous + climb));
+            let center = random_gap_center(range, reach, &mut rng);
+            assert!(center >= previous - drop - 1e-3 && center <= previous + climb + 1e-3);
+            assert!(center.abs() <= range);
+            previous = center;
+        }
+    }
+
+    #[test]
+    fn shorter_spacing_narrows_the_reachable_gaps() {
+        let physics = PhysicsConfig::default();
+        let (near_climb, near_drop) = reachable_gap_steps(&physics, 0.5);
+        let (far_climb, far_drop) = reachable_gap_steps(&physics, 2.0);
+        assert!(near_climb <= far_climb);
+        assert!(near_drop < far_drop);
+    }
+
+    #[test]
+    fn pipes_keep_their_spacing_at_any_scroll_speed() {
+        // Where each column spawns, in world units scrolled from the start
+        let spawn_points = |speed: f32| {
+            let mut spawn_distance = PipeSpawnDistance::default();
+            let mut scrolled = 0.0;
+            let mut points = Vec::new();
+            for _ in 0..590 {
+                let step = speed / 60.0;
+                scrolled += step;
+                if spawn_distance.advance(step) {
+                    points.push(scrolled - spawn_distance.scrolled);
+                }
+            }
+            points
+        };
+
+        let normal = spawn_points(150.0);
+        let fast = spawn_points(300.0);
+        assert_eq!(normal.len(), 4);
+        assert_eq!(fast.len(), 9);
+        for (normal, fast) in normal.iter().zip(&fast) {
+            assert!((normal - fast).abs() < 0.1);
+        }
+        assert!((fast[1] - fast[0] - PIPE_SPAWN_DISTANCE).abs() < 0.1);
+    }
+
+    #[test]
+    fn pipe_tier_counts_reached_thresholds() {
+        assert_eq!(pipe_tier(0), 0);
+        assert_eq!(pipe_tier(PIPE_TIER_SCORES[0] - 1), 0);
+        assert_eq!(pipe_tier(PIPE_TIER_SCORES[0]), 1);
+        assert_eq!(pipe_tier(PIPE_TIER_SCORES[2] + 100), 3);
+    }
+
+    #[test]
+    fn nearby_entities_start_at_the_bird() {
+        let half_width = PIPE_WIDTH / 2.0;
+        let touching = -50.0 + BIRD_SIZE / 2.0 + half_width;
+        assert!(has_reached_column(touching, half_width, -50.0));
+        assert!(!has_reached_column(touching + 1.0, half_width, -50.0));
+        // Entities behind the bird stay nearby
+        assert!(has_reached_column(-200.0, half_width, -50.0));
+    }
+
+    #[test]
+    fn tunnel_step_is_below_one_flap() {
+        let physics = PhysicsConfig::default();
+        let climb = physics.flap_strength.powi(2) / (2.0 * -physics.gravity);
+        assert!(max_tunnel_step(&physics, 1.0) < climb);
+        // Short intervals leave less time to fall
+        assert!(max_tunnel_step(&physics, 0.1) < max_tunnel_step(&physics, 1.0));
+    }
+}
@@ -0,0 +1,57 @@
+//! Playtime tracking systems.
+//!
+//! This module adds up session and lifetime playtime, saves the lifetime
+//! total regularly, and shows the optional hourly reminder toast.
+
+use bevy::prelude::*;
+
+use crate::components::Toast;
+use crate::constants::{PLAYTIME_REMINDER_INTERVAL, PLAYTIME_SAVE_INTERVAL};
+use crate::resources::Playtime;
+use crate::save::SaveData;
+use crate::settings::Settings;
+use crate::systems::achievements::spawn_toast;
+
+/// Adds the frame time to the session and lifetime playtime.
+pub fn track_playtime(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut playtime: ResMut<Playtime>,
+    mut save: ResMut<SaveData>,
+    toast_query: Query<(), With<Toast>>,
+) {
+    let delta = time.delta_secs();
+    playtime.session += delta;
+    playtime.unsaved += delta;
+    save.playtime += delta as f64;
+
+    if playtime.unsaved >= PLAYTIME_SAVE_INTERVAL {
+        playtime.unsaved = 0.0;
+        save.store();
+    }
+
+    let hours = (playtime.session / PLAYTIME_REMINDER_INTERVAL) as u32;
+    if hours > playtime.reminders {
+        playtime.reminders = hours;
+        if settings.playtime_reminder {
+            let text = if hours == 1 {
+                "You've been playing for an hour - time for a break?".to_string()
+            } else {
+                format!("You've been playing for {hours} hours - time for a break?")
+            };
+            spawn_toast(&mut commands, text, toast_query.iter().count());
+        }
+    }
+}
+
+/// Saves the playtime not yet written when the game closes.
+pub fn store_playtime_on_exit(
+    mut exit_events: MessageReader<AppExit>,
+    playtime: Res<Playtime>,
+    save: Res<SaveData>,
+) {
+    if exit_events.read().count() > 0 && playtime.unsaved > 0.0 {
+        save.store();
+    }
+}
@@ -0,0 +1,93 @@
+//! Power-up systems.
+//!
+//! Stars occasionally sit in the gap of a random pipe pair. Collecting one
+//! makes the bird invincible for a few seconds, during which it shatters the
+//! pipes it flies through (see `shatter_pipes`). The same invincibility,
+//! without the shattering, protects the bird after a revive.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, Emissive, SpinningBlade, StarPickup};
+use crate::constants::{
+    BIRD_SIZE, INVINCIBILITY_BLINK_RATE, PICKUP_GLOW, STAR_COLOR, STAR_DURATION, STAR_SIZE,
+    STAR_SPIN_SPEED, STAR_TINT, STAR_TINT_RATE, STAR_WARNING_TIME,
+};
+use crate::resources::Invincibility;
+use crate::theme::{Theme, color};
+
+/// Spawns a star pickup at the given position.
+///
+/// Stars scroll with the pipes (see `pipe_movement`).
+pub fn spawn_star(commands: &mut Commands, position: Vec2) {
+    commands.spawn((
+        Sprite::from_color(color(STAR_COLOR), Vec2::splat(STAR_SIZE)),
+        Transform::from_translation(position.extend(0.6))
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+        SpinningBlade {
+            speed: STAR_SPIN_SPEED,
+        },
+        StarPickup,
+        Emissive::new(PICKUP_GLOW),
+    ));
+}
+
+/// Ends any invincibility when a new run starts.
+pub fn reset_invincibility(
+    mut invincibility: ResMut<Invincibility>,
+    mut bird_query: Query<&mut Sprite, With<Bird>>,
+    theme: Res<Theme>,
+) {
+    *invincibility = Invincibility::default();
+    for mut sprite in bird_query.iter_mut() {
+        sprite.color = color(theme.bird_color);
+    }
+}
+
+/// Makes the bird invincible when it touches a star.
+pub fn collect_stars(
+    mut commands: Commands,
+    bird_query: Query<&Transform, With<Bird>>,
+    star_query: Query<(Entity, &Transform), With<StarPickup>>,
+    mut invincibility: ResMut<Invincibility>,
+) {
+    let Ok(bird_transform) = bird_query.single() else {
+        return;
+    };
+    let bird_pos = bird_transform.translation.truncate();
+
+    for (entity, transform) in star_query.iter() {
+        if bird_pos.distance(transform.translation.truncate()) < (BIRD_SIZE + STAR_SIZE) / 2.0 {
+            commands.entity(entity).despawn();
+            invincibility.grant(STAR_DURATION, true);
+        }
+    }
+}
+
+/// Wears off the bird's invincibility.
+///
+/// A star-powered bird flashes between its color and the star tint; the bird
+/// blinks after a revive and when a star is about to run out.
+pub fn update_invincibility(
+    time: Res<Time>,
+    mut invincibility: ResMut<Invincibility>,
+    mut bird_query: Query<&mut Sprite, With<Bird>>,
+    theme: Res<Theme>,
+) {
+    if !invincibility.is_active() {
+        return;
+    }
+    invincibility.remaining = (invincibility.remaining - time.delta_secs()).max(0.0);
+    let remaining = invincibility.remaining;
+
+    let tinted = invincibility.is_active()
+        && invincibility.shatters
+        && ((remaining * STAR_TINT_RATE) as u32).is_multiple_of(2);
+    let blinking = invincibility.is_active()
+        && (!invincibility.shatters || remaining < STAR_WARNING_TIME)
+        && !((remaining * INVINCIBILITY_BLINK_RATE) as u32).is_multiple_of(2);
+
+    let tint = if tinted { STAR_TINT } else { theme.bird_color };
+    for mut sprite in bird_query.iter_mut() {
+        sprite.color = color(tint).with_alpha(if blinking { 0.3 } else { 1.0 });
+    }
+}
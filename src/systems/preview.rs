@@ -0,0 +1,148 @@
+//! Pipe preview strip systems.
+//!
+//! On very narrow windows the pipes come into view just before the bird
+//! reaches them. A thin strip at the top of the screen then previews the
+//! next pipe pairs, including the ones spawned past the right edge: each
+//! pair gets a small track with a notch at the height and size of its gap.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, GapSensor, PipePair, PipePreview, PreviewNotch};
+use crate::constants::{
+    BIRD_SIZE, GROUND_HEIGHT, PREVIEW_ALPHA, PREVIEW_MAX_VIEWPORT_WIDTH, PREVIEW_MIN_NOTCH_HEIGHT,
+    PREVIEW_SLOT_COUNT, PREVIEW_TOP_MARGIN, PREVIEW_TRACK_SIZE, PREVIEW_TRACK_SPACING,
+};
+use crate::resources::GameViewport;
+use crate::states::GameState;
+
+/// The strip draws above the world, below the UI text.
+const PREVIEW_Z: f32 = 1.6;
+
+/// Spawns the (hidden) preview strip with a track and a notch per slot.
+pub fn spawn_pipe_preview(mut commands: Commands) {
+    let track_size = Vec2::new(PREVIEW_TRACK_SIZE.0, PREVIEW_TRACK_SIZE.1);
+    let first_x = -((PREVIEW_SLOT_COUNT - 1) as f32) * PREVIEW_TRACK_SPACING / 2.0;
+
+    commands
+        .spawn((
+            Transform::from_xyz(0.0, 0.0, PREVIEW_Z),
+            Visibility::Hidden,
+            PipePreview,
+        ))
+        .with_children(|strip| {
+            for slot in 0..PREVIEW_SLOT_COUNT {
+                let x = first_x + slot as f32 * PREVIEW_TRACK_SPACING;
+                strip.spawn((
+                    Sprite::from_color(Color::BLACK.with_alpha(PREVIEW_ALPHA), track_size),
+                    Transform::from_xyz(x, 0.0, 0.0),
+                ));
+                strip.spawn((
+                    Sprite::from_color(Color::WHITE, Vec2::new(track_size.x, 1.0)),
+                    Transform::from_xyz(x, 0.0, 0.1),
+                    Visibility::Hidden,
+                    PreviewNotch { slot },
+                ));
+            }
+        });
+}
+
+/// Shows the strip on narrow viewports while playing, and moves each notch
+/// to the gap of the upcoming pair it stands for.
+#[allow(clippy::type_complexity)]
+pub fn update_pipe_preview(
+    state: Res<State<GameState>>,
+    viewport: Res<GameViewport>,
+    bird_query: Query<&Transform, (With<Bird>, Without<PipePreview>, Without<PreviewNotch>)>,
+    pair_query: Query<
+        (&Transform, &Children, &PipePair),
+        (Without<PipePreview>, Without<PreviewNotch>),
+    >,
+    sensor_query: Query<(&Transform, &GapSensor), (Without<PipePreview>, Without<PreviewNotch>)>,
+    mut strip_query: Query<
+        (&mut Transform, &mut Visibility),
+        (With<PipePreview>, Without<PreviewNotch>),
+    >,
+    mut notch_query: Query<(&PreviewNotch, &mut Sprite, &mut Transform, &mut Visibility)>,
+) {
+    let Ok((mut strip_transform, mut strip_visibility)) = strip_query.single_mut() else {
+        return;
+    };
+    let shown = *state.get() == GameState::Playing && viewport.width < PREVIEW_MAX_VIEWPORT_WIDTH;
+    if !shown {
+        strip_visibility.set_if_neq(Visibility::Hidden);
+        return;
+    }
+    strip_visibility.set_if_neq(Visibility::Inherited);
+    strip_transform.translation.y =
+        viewport.half_height() - PREVIEW_TOP_MARGIN - PREVIEW_TRACK_SIZE.1 / 2.0;
+
+    let bird_x = bird_query
+        .iter()
+        .next()
+        .map_or(0.0, |transform| transform.translation.x);
+    let mut upcoming: Vec<(f32, f32, f32)> = pair_query
+        .iter()
+        .filter(|(transform, _, pair)| {
+            !pair.scored && transform.translation.x + pair.width / 2.0 > bird_x - BIRD_SIZE / 2.0
+        })
+        .map(|(transform, children, pair)| {
+            // The sensor nearest the pair's gap gives its size
+            let gap_offset = pair.gap_y - transform.translation.y;
+            let gap_size = sensor_query
+                .iter_many(children)
+                .min_by(|(a, _), (b, _)| {
+                    let a = (a.translation.y - gap_offset).abs();
+                    let b = (b.translation.y - gap_offset).abs();
+                    a.total_cmp(&b)
+                })
+                .map_or(0.0, |(_, sensor)| sensor.size.y);
+            (transform.translation.x, pair.gap_y, gap_size)
+        })
+        .collect();
+    upcoming.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let bottom = -viewport.half_height() + GROUND_HEIGHT;
+    let top = viewport.half_height();
+    for (notch, mut sprite, mut transform, mut visibility) in notch_query.iter_mut() {
+        let Some(&(_, gap_y, gap_size)) = upcoming.get(notch.slot) else {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        };
+        visibility.set_if_neq(Visibility::Inherited);
+        let (y, height) = notch_placement(gap_y, gap_size, bottom, top);
+        transform.translation.y = y;
+        sprite.custom_size = Some(Vec2::new(PREVIEW_TRACK_SIZE.0, height));
+    }
+}
+
+/// Returns the center and height of a notch on a track for a gap between
+/// the world's `bottom` and `top`.
+fn notch_placement(gap_y: f32, gap_size: f32, bottom: f32, top: f32) -> (f32, f32) {
+    let track_height = PREVIEW_TRACK_SIZE.1;
+    let scale = track_height / (top - bottom).max(1.0);
+    let height = (gap_size * scale).clamp(PREVIEW_MIN_NOTCH_HEIGHT, track_height);
+    let center = (bottom + top) / 2.0;
+    let limit = (track_height - height) / 2.0;
+    let y = ((gap_y - center) * scale).clamp(-limit, limit);
+    (y, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notches_scale_gaps_onto_the_track() {
+        let track_height = PREVIEW_TRACK_SIZE.1;
+
+        // A centered gap covering half the world covers half the track
+        let (y, height) = notch_placement(0.0, 100.0, -100.0, 100.0);
+        assert_eq!(y, 0.0);
+        assert_eq!(height, track_height / 2.0);
+
+        // Gaps near the edges stay on the track, and tiny ones stay visible
+        let (y, height) = notch_placement(100.0, 1.0, -100.0, 100.0);
+        assert_eq!(height, PREVIEW_MIN_NOTCH_HEIGHT);
+        assert_eq!(y, (track_height - height) / 2.0);
+    }
+}
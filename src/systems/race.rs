@@ -0,0 +1,155 @@
+//! Online race systems.
+//!
+//! This module connects the optional `RaceSession` to the game: it starts
+//! guests' runs with the host's seed, shares the bird's position and crash,
+//! draws the other players, and announces the winner. All systems here only
+//! run when racing is configured.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, RaceBanner, RaceOpponent};
+use crate::config::GameConfig;
+use crate::constants::{BIRD_SIZE, RACER_ALPHA};
+use crate::race::{RaceEvent, RaceSession, player_number};
+use crate::replay::ReplayRecorder;
+use crate::resources::{DeathEvent, RaceStarted, Score};
+use crate::theme::{Theme, color};
+
+/// Opponent birds sit behind the player's bird.
+const RACE_OPPONENT_Z: f32 = 0.8;
+
+/// Opens the race connection if one is configured in the environment.
+pub fn init_race_session(mut commands: Commands) {
+    if let Some(session) = RaceSession::from_env() {
+        if session.is_host() {
+            info!("Hosting a race");
+        }
+        commands.insert_resource(session);
+    }
+}
+
+/// Processes race packets, starting guests' runs when the host starts one.
+pub fn receive_race_packets(
+    time: Res<Time>,
+    mut session: ResMut<RaceSession>,
+    mut started_events: MessageWriter<RaceStarted>,
+) {
+    for event in session.poll(time.delta_secs()) {
+        match event {
+            RaceEvent::Started { seed } => {
+                info!("Race started with seed {seed}");
+                started_events.write(RaceStarted);
+            }
+        }
+    }
+}
+
+/// Starts the run for everyone when the host starts playing.
+pub fn start_race_run(
+    mut commands: Commands,
+    mut session: ResMut<RaceSession>,
+    recorder: Res<ReplayRecorder>,
+    banner_query: Query<Entity, With<RaceBanner>>,
+) {
+    if session.is_host() {
+        session.start(recorder.replay.seed);
+    }
+    for entity in banner_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Shares the bird's position every frame.
+pub fn send_race_state(session: Res<RaceSession>, bird_query: Query<(&Bird, &Transform)>) {
+    for (bird, transform) in bird_query.iter() {
+        session.send_state(transform.translation.y, bird.velocity);
+    }
+}
+
+/// Tells the other players when this player crashes.
+pub fn send_race_death(
+    mut session: ResMut<RaceSession>,
+    score: Res<Score>,
+    mut death_events: MessageReader<DeathEvent>,
+) {
+    for _ in death_events.read() {
+        session.send_death(score.0);
+    }
+}
+
+/// Spawns, moves, and removes the birds of the other players.
+///
+/// Positions are extrapolated from the last update so opponents fly smoothly
+/// between packets.
+pub fn update_race_opponents(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    session: Res<RaceSession>,
+    mut query: Query<(Entity, &RaceOpponent, &mut Transform)>,
+) {
+    let physics = &config.physics;
+    let tilt = |velocity: f32| {
+        Quat::from_rotation_z(
+            (velocity / physics.flap_strength * physics.max_tilt_up)
+                .clamp(physics.max_tilt_down, physics.max_tilt_up),
+        )
+    };
+
+    for (entity, opponent, mut transform) in query.iter_mut() {
+        match session.racers.get(&opponent.id) {
+            Some(racer) if racer.alive => {
+                let velocity = racer.velocity + physics.gravity * racer.silence;
+                transform.translation.y =
+                    racer.y + (racer.velocity + velocity) / 2.0 * racer.silence;
+                transform.rotation = tilt(velocity);
+            }
+            _ => commands.entity(entity).despawn(),
+        }
+    }
+
+    for (id, racer) in session.racers.iter() {
+        let shown = query.iter().any(|(_, opponent, _)| opponent.id == *id);
+        if racer.alive && !shown {
+            commands.spawn((
+                Sprite {
+                    color: color(theme.bird_color).with_alpha(RACER_ALPHA),
+                    custom_size: Some(Vec2::splat(BIRD_SIZE)),
+                    ..default()
+                },
+                Transform::from_xyz(-50.0, racer.y, RACE_OPPONENT_Z)
+                    .with_rotation(tilt(racer.velocity)),
+                RaceOpponent { id: *id },
+            ));
+        }
+    }
+}
+
+/// Announces the winner once a single player is left flying.
+pub fn announce_race_winner(
+    mut commands: Commands,
+    session: Res<RaceSession>,
+    banner_query: Query<(), With<RaceBanner>>,
+) {
+    if !banner_query.is_empty() {
+        return;
+    }
+    let Some(winner) = session.winner() else {
+        return;
+    };
+
+    let text = match winner {
+        None => "You win the race!".to_string(),
+        Some(id) => format!("Player {} wins the race!", player_number(id)),
+    };
+    commands.spawn((
+        Text2d::new(text),
+        TextFont {
+            font_size: 28.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.9, 0.3)),
+        Transform::from_xyz(0.0, 60.0, 3.0),
+        RaceBanner,
+    ));
+}
@@ -0,0 +1,204 @@
+//! Replay recording and ghost systems.
+//!
+//! This module records each run's seed and input trace, keeps the best run
+//! as a ghost, writes finished runs to the `--record` file, and flies the
+//! selected ghost alongside the player.
+
+use bevy::prelude::*;
+
+use crate::components::Ghost;
+use crate::config::{Difficulty, GameConfig};
+use crate::constants::{BIRD_SIZE, GHOST_ALPHA};
+use crate::mutators::Mutators;
+use crate::race::RaceSession;
+use crate::replay::{GhostLibrary, Replay, ReplayRecorder, RunRecordFile};
+use crate::resources::{DeathEvent, FlapEvent, PendingSeed, ReplayRecorded, Score};
+use crate::rng::{GameRng, SEED_CODE_BITS};
+use crate::save::ScoreCategory;
+use crate::settings::Settings;
+use crate::theme::{Theme, color};
+
+/// Starts recording a run, reseeding the game and spawning the selected ghost.
+///
+/// Runs without a race, an entered seed code or a ghost draw a seed short
+/// enough to share as a code.
+#[allow(clippy::too_many_arguments)]
+pub fn start_run_recording(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut pending_seed: ResMut<PendingSeed>,
+    mut recorder: ResMut<ReplayRecorder>,
+    ghosts: Res<GhostLibrary>,
+    theme: Res<Theme>,
+    difficulty: Res<Difficulty>,
+    mutators: Res<Mutators>,
+    race: Option<Res<RaceSession>>,
+) {
+    // Racing other players, a shared code or a ghost reuses their seed so
+    // everyone flies through the same pipes
+    let seed = race
+        .and_then(|race| race.seed)
+        .or_else(|| pending_seed.0.take())
+        .or_else(|| ghosts.selected().map(|ghost| ghost.replay.seed))
+        .unwrap_or_else(|| rng.effects.next_u64() >> (64 - SEED_CODE_BITS));
+    *rng = GameRng::from_seed(seed);
+    *recorder = ReplayRecorder {
+        replay: Replay {
+            seed,
+            difficulty: *difficulty,
+            mutators: mutators.clone(),
+            ..default()
+        },
+        started_at: time.elapsed_secs(),
+        resumed: false,
+        cheated: false,
+    };
+
+    if let Some(ghost) = ghosts.selected() {
+        commands.spawn((
+            Sprite {
+                color: color(theme.bird_color).with_alpha(GHOST_ALPHA),
+                custom_size: Some(Vec2::splat(BIRD_SIZE)),
+                ..default()
+            },
+            // Same start as the bird, just behind it
+            Transform::from_xyz(-50.0, 0.0, 0.9),
+            Ghost {
+                flaps: ghost.replay.flaps.clone(),
+                next_flap: 0,
+                duration: ghost.replay.duration,
+                started_at: time.elapsed_secs(),
+                velocity: 0.0,
+            },
+        ));
+    }
+}
+
+/// Records the game time of each frame of the run and the flaps in it.
+pub fn record_flaps(
+    time: Res<Time>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut flap_events: MessageReader<FlapEvent>,
+) {
+    let frame = recorder.replay.frames.len() as u32;
+    // Verification refuses frames that don't advance the clock
+    let nanos = (time.delta().as_nanos() as u32).max(1);
+    recorder.replay.frames.push(nanos);
+    for _ in flap_events.read() {
+        let at = time.elapsed_secs() - recorder.started_at;
+        recorder.replay.flaps.push(at);
+        recorder.replay.flap_frames.push(frame);
+    }
+}
+
+/// Finishes the recording when the run ends and keeps it if it is a new best.
+///
+/// Rewound runs can't be replayed, so they are neither kept nor shared, and
+/// neither are runs helped by a debug panel cheat.
+/// Flicker runs, runs with mutators and runs under a `--difficulty` preset
+/// are ranked on their own, so they don't replace the best ghost or reach the
+/// leaderboard either.
+#[allow(clippy::too_many_arguments)]
+pub fn finish_run_recording(
+    time: Res<Time>,
+    score: Res<Score>,
+    settings: Res<Settings>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut ghosts: ResMut<GhostLibrary>,
+    mut death_events: MessageReader<DeathEvent>,
+    mut recorded_events: MessageWriter<ReplayRecorded>,
+) {
+    for _ in death_events.read() {
+        recorder.replay.score = score.0;
+        recorder.replay.duration = time.elapsed_secs() - recorder.started_at;
+        if recorder.resumed
+            || recorder.cheated
+            || settings.score_category() != ScoreCategory::Normal
+            || !recorder.replay.mutators.is_empty()
+            || recorder.replay.difficulty != Difficulty::Normal
+        {
+            continue;
+        }
+
+        if ghosts.submit(&recorder.replay) {
+            info!("Saved best run replay ({} points)", score.0);
+        }
+        recorded_events.write(ReplayRecorded {
+            replay: recorder.replay.clone(),
+        });
+    }
+}
+
+/// Writes each finished run to the `--record` file, replacing the previous
+/// one.
+///
+/// Rewound, revived and tutorial runs can't be played back, so they are
+/// skipped.
+pub fn write_run_recording(
+    file: Res<RunRecordFile>,
+    recorder: Res<ReplayRecorder>,
+    mut death_events: MessageReader<DeathEvent>,
+) {
+    if death_events.read().count() == 0 {
+        return;
+    }
+    if recorder.resumed {
+        warn!("Not recording the run: it was rewound, revived or a tutorial");
+        return;
+    }
+    if recorder.cheated {
+        warn!("Not recording the run: a debug panel cheat was used");
+        return;
+    }
+    match recorder.replay.write_to(&file.0) {
+        Ok(()) => info!(
+            "Recorded the run ({} points) to {}",
+            recorder.replay.score,
+            file.0.display()
+        ),
+        Err(error) => warn!("Couldn't record the run to {}: {error}", file.0.display()),
+    }
+}
+
+/// Re-flies ghosts by replaying their flaps with the current physics.
+///
+/// A ghost disappears at the moment its run crashed.
+pub fn move_ghosts(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    mut query: Query<(Entity, &mut Ghost, &mut Transform)>,
+) {
+    let physics = &config.physics;
+    for (entity, mut ghost, mut transform) in query.iter_mut() {
+        let elapsed = time.elapsed_secs() - ghost.started_at;
+        if elapsed >= ghost.duration {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        while ghost
+            .flaps
+            .get(ghost.next_flap)
+            .is_some_and(|flap| *flap <= elapsed)
+        {
+            ghost.velocity = physics.flap_strength;
+            ghost.next_flap += 1;
+        }
+
+        ghost.velocity += physics.gravity * time.delta_secs();
+        transform.translation.y += ghost.velocity * time.delta_secs();
+
+        let tilt = (ghost.velocity / physics.flap_strength * physics.max_tilt_up)
+            .clamp(physics.max_tilt_down, physics.max_tilt_up);
+        transform.rotation = Quat::from_rotation_z(tilt);
+    }
+}
+
+/// Removes ghosts when a run ends.
+pub fn despawn_ghosts(mut commands: Commands, query: Query<Entity, With<Ghost>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,212 @@
+//! Results screen systems.
+//!
+//! This module starts the results animation when a run ends, counts the
+//! score up with ticks (skipped by any input), celebrates new records with
+//! confetti, a fanfare and the "Best" label swap, and puts the HUD back when
+//! the game over screen is left.
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::components::{BestScoreText, Confetti, Particle, ScoreText};
+use crate::config::GameConfig;
+use crate::constants::{
+    RECORD_CONFETTI_COLORS, RECORD_CONFETTI_COUNT, RECORD_CONFETTI_GRAVITY,
+    RECORD_CONFETTI_LIFETIME, RECORD_CONFETTI_SIZE, RECORD_CONFETTI_SPEED, RECORD_FANFARE_SPEED,
+    RECORD_LABEL_COLOR,
+};
+use crate::input::ActionInput;
+use crate::mutators::Mutators;
+use crate::race::RaceSession;
+use crate::resources::{DeathEvent, Fanfare, GameViewport, GraphicsSettings, PlaySfx, Score};
+use crate::results::{NewRecord, ResultsAnimation};
+use crate::rng::GameRng;
+use crate::save::SaveData;
+use crate::settings::Settings;
+use crate::sfx::SoundEffect;
+use crate::systems::score::{best_label, best_score_label};
+use crate::theme::color;
+
+/// Starts counting the score up when a run ends.
+///
+/// Race guests restart when the host does, so in races the score lands
+/// straight away rather than holding up the restart.
+pub fn start_results_animation(
+    mut death_events: MessageReader<DeathEvent>,
+    mut records: MessageReader<NewRecord>,
+    score: Res<Score>,
+    race: Option<Res<RaceSession>>,
+    mut animation: ResMut<ResultsAnimation>,
+) {
+    if death_events.read().count() == 0 {
+        return;
+    }
+    *animation = ResultsAnimation::start(score.0, records.read().last().copied());
+    if race.is_some() {
+        animation.skip();
+    }
+}
+
+/// Starts the celebration when a run sets a new record: confetti across the
+/// top of the screen and a quicker take on the milestone fanfare.
+#[allow(clippy::too_many_arguments)]
+pub fn celebrate_new_record(
+    mut commands: Commands,
+    mut records: MessageReader<NewRecord>,
+    mut game_rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    settings: Res<Settings>,
+    graphics: Res<GraphicsSettings>,
+    viewport: Res<GameViewport>,
+    fanfare: Res<Fanfare>,
+) {
+    let Some(record) = records.read().last() else {
+        return;
+    };
+    info!("New record: {} (was {})", record.score, record.previous);
+
+    let rng = &mut game_rng.particles;
+    let (size_min, size_max) = RECORD_CONFETTI_SIZE;
+    for _ in 0..graphics.particles(RECORD_CONFETTI_COUNT) {
+        let x = rng.range(-viewport.half_width(), viewport.half_width());
+        let y = viewport.half_height() + rng.range(0.0, 40.0);
+        let velocity = Vec2::new(
+            rng.range(-0.5, 0.5) * RECORD_CONFETTI_SPEED,
+            rng.range(0.0, 1.0) * RECORD_CONFETTI_SPEED,
+        );
+        let rgb = rng
+            .choose(&RECORD_CONFETTI_COLORS)
+            .copied()
+            .unwrap_or(RECORD_CONFETTI_COLORS[0]);
+        let lifetime = graphics.lifetime(RECORD_CONFETTI_LIFETIME);
+        commands.spawn((
+            Sprite {
+                color: color(rgb),
+                custom_size: Some(Vec2::new(rng.range(size_min, size_max), size_min)),
+                ..default()
+            },
+            Transform::from_xyz(x, y, 9.0).with_rotation(Quat::from_rotation_z(rng.angle())),
+            Particle {
+                velocity,
+                world_velocity: Vec2::ZERO,
+                lifetime: lifetime * (0.7 + rng.f32() * 0.3),
+                initial_lifetime: lifetime,
+            },
+            Confetti {
+                gravity: RECORD_CONFETTI_GRAVITY,
+            },
+        ));
+    }
+
+    commands.spawn((
+        AudioPlayer(fanfare.0.clone()),
+        PlaybackSettings::DESPAWN
+            .with_speed(RECORD_FANFARE_SPEED)
+            .with_volume(Volume::Linear(
+                config.music.volume * settings.music_volume.factor(),
+            )),
+    ));
+}
+
+/// Pulls the confetti down, cancelling the particles' air resistance.
+pub fn fall_confetti(time: Res<Time>, mut query: Query<(&Confetti, &mut Particle)>) {
+    let dt = time.delta_secs();
+    for (confetti, mut particle) in query.iter_mut() {
+        particle.velocity.y -= confetti.gravity * dt;
+    }
+}
+
+/// Counts the score up with a tick for each point and, when the run set a
+/// new record, swaps the "Best" label once the score lands.
+#[allow(clippy::type_complexity)]
+pub fn animate_results(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mutators: Res<Mutators>,
+    mut animation: ResMut<ResultsAnimation>,
+    mut sfx: MessageWriter<PlaySfx>,
+    mut score_query: Query<&mut Text, With<ScoreText>>,
+    mut best_query: Query<
+        (&mut Text, &mut TextColor, &mut UiTransform),
+        (With<BestScoreText>, Without<ScoreText>),
+    >,
+) {
+    if !animation.active {
+        return;
+    }
+    animation.elapsed += time.delta_secs();
+
+    let displayed = animation.displayed_score();
+    let label = displayed.to_string();
+    for mut text in score_query.iter_mut() {
+        if text.0 != label {
+            text.0.clone_from(&label);
+            if displayed > 0 {
+                sfx.write(PlaySfx(SoundEffect::Tick));
+            }
+        }
+    }
+
+    let Some(record) = animation.record else {
+        return;
+    };
+    let category = settings.score_category();
+    let swapped = !animation.is_counting();
+    let scale = if settings.reduced_motion {
+        1.0
+    } else {
+        animation.flourish_scale()
+    };
+    for (mut text, mut text_color, mut transform) in best_query.iter_mut() {
+        let (best, rgb) = if swapped {
+            (record.score, RECORD_LABEL_COLOR)
+        } else {
+            (record.previous, (1.0, 1.0, 1.0))
+        };
+        let label = best_label(category, &mutators, best);
+        if text.0 != label {
+            text.0 = label;
+        }
+        text_color.0 = color(rgb);
+        transform.scale = Vec2::splat(scale);
+    }
+}
+
+/// Skips the count-up to the final score on any input.
+pub fn skip_results_count_up(actions: ActionInput, mut animation: ResMut<ResultsAnimation>) {
+    if animation.is_counting() && actions.any_just_pressed() {
+        animation.skip();
+    }
+}
+
+/// Ends the results animation when leaving the game over screen, putting
+/// the score and the "Best" label back.
+#[allow(clippy::type_complexity)]
+pub fn reset_results_animation(
+    mut animation: ResMut<ResultsAnimation>,
+    score: Res<Score>,
+    save: Res<SaveData>,
+    settings: Res<Settings>,
+    mutators: Res<Mutators>,
+    mut score_query: Query<&mut Text, With<ScoreText>>,
+    mut best_query: Query<
+        (&mut Text, &mut TextColor, &mut UiTransform),
+        (With<BestScoreText>, Without<ScoreText>),
+    >,
+) {
+    if !animation.active {
+        return;
+    }
+    animation.active = false;
+    for mut text in score_query.iter_mut() {
+        text.0 = score.0.to_string();
+    }
+    if animation.record.take().is_none() {
+        return;
+    }
+    for (mut text, mut text_color, mut transform) in best_query.iter_mut() {
+        text.0 = best_score_label(&save, settings.score_category(), &mutators);
+        text_color.0 = Color::WHITE;
+        transform.scale = Vec2::ONE;
+    }
+}
@@ -0,0 +1,176 @@
+//! Revive systems.
+//!
+//! When the bird crashes and the player has enough coins, the run stops in
+//! the `Reviving` pause state and offers a second chance. Accepting spends the
+//! coins, clears the pipes around the bird, and resumes the run with a short
+//! invincibility; declining (or letting the prompt time out) ends the run as
+//! usual. Each run can be revived once, and never in races.
+
+use bevy::prelude::*;
+use bevy::text::{Justify, LineBreak};
+
+use crate::bindings::GameAction;
+use crate::components::{
+    Bird, FillScreen, HitArea, InstructionText, Obstacle, PipePair, ReviveButton, ReviveUi,
+    WorldButton,
+};
+use crate::constants::{
+    BIRD_SIZE, PIPE_WIDTH, REVIVE_COST, REVIVE_INVINCIBILITY, REVIVE_PROMPT_TIME,
+};
+use crate::input::ActionInput;
+use crate::replay::ReplayRecorder;
+use crate::resources::{ButtonClicked, GameViewport, Invincibility, Revive};
+use crate::save::SaveData;
+use crate::states::{GameState, PauseState};
+use crate::systems::collision::trigger_game_over;
+
+/// Gives a new run a fresh, unused revive.
+pub fn reset_revive(mut revive: ResMut<Revive>) {
+    *revive = Revive::default();
+}
+
+/// Shows the revive prompt over the frozen run.
+pub fn spawn_revive_prompt(
+    mut commands: Commands,
+    mut revive: ResMut<Revive>,
+    save: Res<SaveData>,
+    viewport: Res<GameViewport>,
+) {
+    revive.prompt_time = REVIVE_PROMPT_TIME;
+
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.0, 0.0, 0.0, 0.5),
+            custom_size: Some(Vec2::new(viewport.width * 2.0, viewport.height * 2.0)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        FillScreen,
+        ReviveUi,
+    ));
+
+    commands.spawn((
+        Text2d::new(format!(
+            "Crashed!\nRevive for {REVIVE_COST} coins? (you have {})",
+            save.coins
+        )),
+        TextFont {
+            font_size: 28.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new(Justify::Center, LineBreak::NoWrap),
+        Transform::from_xyz(0.0, 40.0, 6.0),
+        ReviveUi,
+    ));
+
+    commands.spawn((
+        Text2d::new(revive_label(revive.prompt_time)),
+        TextFont {
+            font_size: 22.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.85, 0.3)),
+        Transform::from_xyz(0.0, -40.0, 6.0),
+        HitArea {
+            size: Vec2::new(380.0, 40.0),
+        },
+        WorldButton::new(Color::srgb(1.0, 0.85, 0.3)),
+        ReviveUi,
+        ReviveButton,
+    ));
+}
+
+/// Removes the revive prompt.
+pub fn despawn_revive_prompt(mut commands: Commands, ui_query: Query<Entity, With<ReviveUi>>) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Revives the bird when the revive key or button is pressed or the revive button is clicked.
+///
+/// Pipes and obstacles in the bird's column are removed and the bird restarts
+/// from the middle of the screen.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn accept_revive(
+    actions: ActionInput,
+    mut clicks: MessageReader<ButtonClicked<ReviveButton>>,
+    mut revive: ResMut<Revive>,
+    mut invincibility: ResMut<Invincibility>,
+    mut save: ResMut<SaveData>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut bird_query: Query<(&mut Bird, &mut Transform)>,
+    pipe_query: Query<(Entity, &Transform), (Or<(With<PipePair>, With<Obstacle>)>, Without<Bird>)>,
+    mut commands: Commands,
+    mut next_pause: ResMut<NextState<PauseState>>,
+) {
+    let button_clicked = clicks.read().count() > 0;
+    if !actions.just_pressed(GameAction::Revive) && !button_clicked {
+        return;
+    }
+    if !revive.is_available(save.coins) {
+        return;
+    }
+
+    save.spend_coins(REVIVE_COST);
+    save.store();
+    revive.used = true;
+    revive.crash = None;
+    invincibility.grant(REVIVE_INVINCIBILITY, false);
+    recorder.resumed = true;
+
+    for (mut bird, mut transform) in bird_query.iter_mut() {
+        let column = transform.translation.x;
+        for (entity, pipe_transform) in pipe_query.iter() {
+            if (pipe_transform.translation.x - column).abs() < PIPE_WIDTH / 2.0 + BIRD_SIZE {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        bird.velocity = 0.0;
+        transform.translation.y = 0.0;
+        transform.rotation = Quat::IDENTITY;
+    }
+
+    next_pause.set(PauseState::Running);
+}
+
+/// Counts the prompt down and ends the run on Escape or when time runs out.
+pub fn decline_revive(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut revive: ResMut<Revive>,
+    mut button_query: Query<&mut Text2d, With<ReviveButton>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut instruction_query: Query<(&mut Visibility, &mut Text), With<InstructionText>>,
+    mut commands: Commands,
+) {
+    revive.prompt_time -= time.delta_secs();
+    for mut text in button_query.iter_mut() {
+        text.0 = revive_label(revive.prompt_time);
+    }
+
+    if revive.prompt_time > 0.0 && !keyboard_input.just_pressed(KeyCode::Escape) {
+        return;
+    }
+    if let Some((position, cause)) = revive.crash.take() {
+        trigger_game_over(
+            &mut next_state,
+            &mut instruction_query,
+            &mut commands,
+            position,
+            cause,
+        );
+    }
+}
+
+/// Returns the revive button's label with the seconds left to accept.
+fn revive_label(time_left: f32) -> String {
+    let action = if cfg!(target_os = "ios") {
+        "Tap here to revive"
+    } else {
+        "Press ENTER or click here to revive"
+    };
+    format!("{action} ({})", time_left.max(0.0).ceil() as u32)
+}
@@ -0,0 +1,240 @@
+//! Rewind systems.
+//!
+//! This module records the rewind buffer while playing, offers the rewind on
+//! the game over screen, and restores the run when the player takes it.
+//! Rewinding is unavailable in races, and snapshots are not taken during
+//! boss segments.
+
+use bevy::prelude::*;
+
+use crate::bindings::GameAction;
+use crate::components::{
+    Bird, FloatingBlock, GateDoor, GravityZone, HitArea, InstructionText, LetterPickup, Obstacle,
+    Pipe, PipePair, RewindButton, ScoreText, StarPickup, WorldButton,
+};
+use crate::input::ActionInput;
+use crate::race::RaceSession;
+use crate::replay::ReplayRecorder;
+use crate::resources::{ButtonClicked, PipeSequence, PipeSpawnDistance, Score};
+use crate::rewind::{EntitySnapshot, Rewind, Snapshot};
+use crate::rng::GameRng;
+use crate::states::GameState;
+
+/// Query over every pipe pair, pipe, obstacle, star, letter, and zone with the state
+/// a snapshot keeps.
+type SnapshotQuery<'w, 's, T> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        T,
+        Option<&'static mut Sprite>,
+        Option<&'static mut PipePair>,
+        Option<&'static mut GateDoor>,
+        Option<&'static mut FloatingBlock>,
+        Has<Pipe>,
+    ),
+    (
+        Or<(
+            With<PipePair>,
+            With<Pipe>,
+            With<Obstacle>,
+            With<StarPickup>,
+            With<LetterPickup>,
+            With<GravityZone>,
+        )>,
+        Without<Bird>,
+    ),
+>;
+
+/// Gives a new run a fresh, unused rewind.
+pub fn reset_rewind(mut rewind: ResMut<Rewind>) {
+    *rewind = Rewind::default();
+}
+
+/// Forgets the snapshots when a boss segment starts or ends.
+pub fn clear_rewind_buffer(mut rewind: ResMut<Rewind>) {
+    rewind.clear();
+}
+
+/// Takes a snapshot of the run at regular intervals.
+#[allow(clippy::too_many_arguments)]
+pub fn record_snapshot(
+    time: Res<Time>,
+    mut rewind: ResMut<Rewind>,
+    bird_query: Query<(&Bird, &Transform)>,
+    entity_query: SnapshotQuery<&Transform>,
+    score: Res<Score>,
+    spawn_distance: Res<PipeSpawnDistance>,
+    sequence: Res<PipeSequence>,
+    rng: Res<GameRng>,
+) {
+    if !rewind.tick(time.delta_secs()) {
+        return;
+    }
+    let Ok((bird, bird_transform)) = bird_query.single() else {
+        return;
+    };
+
+    let entities = entity_query
+        .iter()
+        .map(
+            |(entity, transform, sprite, pair, door, block, _)| EntitySnapshot {
+                entity,
+                transform: *transform,
+                color: sprite.map(|sprite| sprite.color),
+                scored: pair.map(|pair| pair.scored),
+                elapsed: door
+                    .map(|door| door.elapsed)
+                    .or(block.map(|block| block.elapsed)),
+            },
+        )
+        .collect();
+
+    rewind.push(Snapshot {
+        bird_transform: *bird_transform,
+        bird_velocity: bird.velocity,
+        entities,
+        score: score.0,
+        spawn_distance: spawn_distance.clone(),
+        sequence: sequence.pending.clone(),
+        last_gap: sequence.last_gap,
+        rng: rng.clone(),
+    });
+}
+
+/// Shows the rewind button on the game over screen if this run's rewind is
+/// still available.
+pub fn offer_rewind(
+    mut commands: Commands,
+    mut rewind: ResMut<Rewind>,
+    race: Option<Res<RaceSession>>,
+) {
+    // The last rewind (if any) has been fully restored by now
+    rewind.pending = None;
+    if !rewind.is_available() || race.is_some() {
+        return;
+    }
+
+    let label = if cfg!(target_os = "ios") {
+        "Tap here to rewind"
+    } else {
+        "Press R or click here to rewind"
+    };
+    commands.spawn((
+        Text2d::new(label),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.6, 0.9, 1.0)),
+        Transform::from_xyz(0.0, -150.0, 2.0),
+        HitArea {
+            size: Vec2::new(340.0, 36.0),
+        },
+        WorldButton::new(Color::srgb(0.6, 0.9, 1.0)),
+        RewindButton,
+    ));
+}
+
+/// Removes the rewind button when leaving the game over screen.
+pub fn despawn_rewind_button(
+    mut commands: Commands,
+    button_query: Query<Entity, With<RewindButton>>,
+) {
+    for entity in button_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Rewinds when the rewind key or button is pressed or the rewind button is clicked.
+pub fn rewind_on_request(
+    actions: ActionInput,
+    mut clicks: MessageReader<ButtonClicked<RewindButton>>,
+    button_query: Query<(), With<RewindButton>>,
+    mut rewind: ResMut<Rewind>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let button_clicked = clicks.read().count() > 0;
+    if button_query.is_empty() {
+        return;
+    }
+
+    if (actions.just_pressed(GameAction::Rewind) || button_clicked) && rewind.start() {
+        recorder.resumed = true;
+        next_state.set(GameState::Playing);
+    }
+}
+
+/// Restores the rewound snapshot as the run resumes.
+///
+/// Pipe pairs, obstacles, stars, letters, and zones spawned after the snapshot are
+/// removed.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn apply_rewind(
+    mut commands: Commands,
+    rewind: Res<Rewind>,
+    mut bird_query: Query<(&mut Bird, &mut Transform)>,
+    mut entity_query: SnapshotQuery<&mut Transform>,
+    mut score: ResMut<Score>,
+    mut spawn_distance: ResMut<PipeSpawnDistance>,
+    mut sequence: ResMut<PipeSequence>,
+    mut rng: ResMut<GameRng>,
+    mut text_query: Query<
+        (&mut Text, &mut Visibility, Has<ScoreText>),
+        Or<(With<ScoreText>, With<InstructionText>)>,
+    >,
+) {
+    let Some(snapshot) = &rewind.pending else {
+        return;
+    };
+
+    for (mut bird, mut transform) in bird_query.iter_mut() {
+        bird.velocity = snapshot.bird_velocity;
+        *transform = snapshot.bird_transform;
+    }
+
+    for (entity, mut transform, sprite, pair, door, block, is_pipe) in entity_query.iter_mut() {
+        let Some(saved) = snapshot
+            .entities
+            .iter()
+            .find(|saved| saved.entity == entity)
+        else {
+            // Pipes are despawned along with their pair
+            if !is_pipe {
+                commands.entity(entity).despawn();
+            }
+            continue;
+        };
+        *transform = saved.transform;
+        if let (Some(mut sprite), Some(color)) = (sprite, saved.color) {
+            sprite.color = color;
+        }
+        if let (Some(mut pair), Some(was_scored)) = (pair, saved.scored) {
+            pair.scored = was_scored;
+        }
+        if let Some(elapsed) = saved.elapsed {
+            if let Some(mut door) = door {
+                door.elapsed = elapsed;
+            }
+            if let Some(mut block) = block {
+                block.elapsed = elapsed;
+            }
+        }
+    }
+
+    score.0 = snapshot.score;
+    *spawn_distance = snapshot.spawn_distance.clone();
+    sequence.pending = snapshot.sequence.clone();
+    sequence.last_gap = snapshot.last_gap;
+    *rng = snapshot.rng.clone();
+
+    for (mut text, mut visibility, is_score) in text_query.iter_mut() {
+        if is_score {
+            text.0 = score.0.to_string();
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
@@ -4,35 +4,228 @@
 
 use bevy::prelude::*;
 
-use crate::components::{Bird, Pipe, ScoreText, Scored};
-use crate::resources::{Score, ScoreEvent};
+use crate::components::{BestScoreText, Bird, GapSensor, PipePair, RunStatsText, ScoreText};
+use crate::config::{Difficulty, GameConfig};
+use crate::mutators::Mutators;
+use crate::replay::ReplayRecorder;
+use crate::resources::{
+    DeathEvent, Invincibility, NearbyObstacles, Playtime, RunStats, Score, ScoreEvent,
+    format_playtime,
+};
+use crate::results::NewRecord;
+use crate::rng::seed_code;
+use crate::save::{SaveData, ScoreCategory};
+use crate::settings::Settings;
+use crate::systems::achievements::spawn_toast;
+use crate::systems::collision::BirdHitbox;
 
-/// Updates the score when bird passes pipes.
+/// Updates the score when a bird flies through the gap of a pipe pair.
 ///
-/// Only bottom pipes have the `Scored` component to avoid double-counting.
+/// Each pair scores once, when a bird first overlaps one of its gap sensors.
+/// An invincible bird flies through the pipes too, so any pass through the
+/// column counts. Only the pairs in `NearbyObstacles` are checked, against
+/// the same scaled (and possibly tilted) hitbox the collisions use.
+#[allow(clippy::too_many_arguments)]
 pub fn update_score(
     bird_query: Query<&Transform, With<Bird>>,
-    mut pipe_query: Query<(&Transform, &mut Scored), With<Pipe>>,
+    mut pair_query: Query<(&Transform, &Children, &mut PipePair)>,
+    sensor_query: Query<(&Transform, &GapSensor)>,
+    nearby: Res<NearbyObstacles>,
+    invincibility: Res<Invincibility>,
+    config: Res<GameConfig>,
     mut score: ResMut<Score>,
-    mut text_query: Query<&mut Text2d, With<ScoreText>>,
-    mut score_events: MessageWriter<ScoreEvent>,
+    mut stats: ResMut<RunStats>,
+    mut commands: Commands,
 ) {
-    let Ok(bird_transform) = bird_query.single() else {
-        return;
-    };
-    let bird_x = bird_transform.translation.x;
+    let invincible = invincibility.is_active();
+
+    for &entity in &nearby.0 {
+        let Ok((pair_transform, children, mut pair)) = pair_query.get_mut(entity) else {
+            continue;
+        };
+        if pair.scored {
+            continue;
+        }
+        let crossed = sensor_query
+            .iter_many(children)
+            .map(|(transform, sensor)| {
+                let center = (pair_transform.translation + transform.translation).truncate();
+                (center, sensor)
+            })
+            .find(|(center, sensor)| {
+                bird_query.iter().any(|bird| {
+                    let hitbox = BirdHitbox::new(bird, &config.physics);
+                    sensor.overlaps(*center, hitbox.center(), hitbox.bounds(), invincible)
+                })
+            });
+        let Some((center, sensor)) = crossed else {
+            continue;
+        };
+
+        pair.scored = true;
+        score.increment();
+        stats.pipes += 1;
+
+        // Trigger score event for display and visual effects
+        commands.trigger(ScoreEvent {
+            pipe: entity,
+            gap_position: center,
+            gap_size: sensor.size.y,
+            score: score.0,
+        });
+    }
+}
+
+/// Updates the score display when a point is scored.
+pub fn update_score_text(
+    mut score_events: MessageReader<ScoreEvent>,
+    mut text_query: Query<&mut Text, With<ScoreText>>,
+) {
+    for event in score_events.read() {
+        for mut text in text_query.iter_mut() {
+            text.0 = event.score.to_string();
+        }
+    }
+}
 
-    for (pipe_transform, mut scored) in pipe_query.iter_mut() {
-        if !scored.0 && pipe_transform.translation.x < bird_x {
-            scored.0 = true;
-            score.increment();
+/// Clears the run statistics at the start of each run.
+pub fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+/// Shows the run statistics and playtime on the game over screen.
+pub fn show_run_stats(
+    stats: Res<RunStats>,
+    playtime: Res<Playtime>,
+    save: Res<SaveData>,
+    recorder: Res<ReplayRecorder>,
+    mut text_query: Query<(&mut Text, &mut Visibility), With<RunStatsText>>,
+) {
+    let seed = format!("Seed code: {}", seed_code(recorder.replay.seed));
+    let playtime = format!(
+        "Session: {}   Total: {}   Coins: {}",
+        format_playtime(playtime.session as f64),
+        format_playtime(save.playtime),
+        save.coins
+    );
+    for (mut text, mut visibility) in text_query.iter_mut() {
+        text.0 = format!("{}\n{playtime}\n{seed}", stats.summary());
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Hides the run statistics when leaving the game over screen.
+pub fn hide_run_stats(mut text_query: Query<&mut Visibility, With<RunStatsText>>) {
+    for mut visibility in text_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+/// Records the final score of a run as the new best of its category (or of
+/// its mutators, if any are on) if it beats the old one, announcing the new
+/// record.
+///
+/// Runs under a `--difficulty` preset aren't comparable, and runs helped by
+/// a debug panel cheat don't count, so they set no records.
+#[allow(clippy::too_many_arguments)]
+pub fn record_high_score(
+    mut death_events: MessageReader<DeathEvent>,
+    score: Res<Score>,
+    settings: Res<Settings>,
+    mutators: Res<Mutators>,
+    difficulty: Res<Difficulty>,
+    recorder: Res<ReplayRecorder>,
+    mut save: ResMut<SaveData>,
+    mut records: MessageWriter<NewRecord>,
+) {
+    for _ in death_events.read() {
+        if *difficulty != Difficulty::Normal || recorder.cheated {
+            continue;
+        }
+        let previous = best_score(&save, settings.score_category(), &mutators);
+        let new_best = if mutators.is_empty() {
+            save.submit_score(score.0, settings.score_category())
+        } else {
+            save.submit_mutator_score(score.0, &mutators.tag())
+        };
+        if new_best {
+            save.store();
+            records.write(NewRecord {
+                score: score.0,
+                previous,
+            });
+        }
+    }
+}
 
-            // Send score event for visual effects
-            score_events.write(ScoreEvent);
+/// Tells the player when the best scores were reset because the save file
+/// was edited.
+pub fn announce_rejected_scores(mut commands: Commands, save: Res<SaveData>) {
+    if save.scores_rejected {
+        spawn_toast(
+            &mut commands,
+            "Best scores reset: the save file was edited".to_string(),
+            0,
+        );
+    }
+}
 
-            for mut text in text_query.iter_mut() {
-                text.0 = score.0.to_string();
-            }
+/// Banks one coin per point when a run ends.
+pub fn bank_coins(
+    mut death_events: MessageReader<DeathEvent>,
+    score: Res<Score>,
+    mut save: ResMut<SaveData>,
+) {
+    for _ in death_events.read() {
+        if score.0 > 0 {
+            save.earn_coins(score.0);
+            save.store();
         }
     }
 }
+
+/// Updates the best score display when the saved best, the score category
+/// or the mutators change.
+pub fn update_best_score_text(
+    save: Res<SaveData>,
+    settings: Res<Settings>,
+    mutators: Res<Mutators>,
+    mut text_query: Query<&mut Text, With<BestScoreText>>,
+) {
+    if !save.is_changed() && !settings.is_changed() && !mutators.is_changed() {
+        return;
+    }
+
+    for mut text in text_query.iter_mut() {
+        text.0 = best_score_label(&save, settings.score_category(), &mutators);
+    }
+}
+
+/// Returns the best score of a score category, or of the mutators if any
+/// are on.
+fn best_score(save: &SaveData, category: ScoreCategory, mutators: &Mutators) -> u32 {
+    if mutators.is_empty() {
+        save.best_in(category)
+    } else {
+        save.mutator_best(&mutators.tag())
+    }
+}
+
+/// Formats the best score display text for a score category, or for the
+/// mutators if any are on.
+pub fn best_score_label(save: &SaveData, category: ScoreCategory, mutators: &Mutators) -> String {
+    best_label(category, mutators, best_score(save, category, mutators))
+}
+
+/// Formats the best score display text for a score category (or the
+/// mutators) and best score.
+pub fn best_label(category: ScoreCategory, mutators: &Mutators, best_score: u32) -> String {
+    if !mutators.is_empty() {
+        return format!("Mutators best: {best_score}");
+    }
+    match category {
+        ScoreCategory::Normal => format!("Best: {best_score}"),
+        ScoreCategory::Flicker => format!("Flicker best: {best_score}"),
+        ScoreCategory::Glide => format!("Glide best: {best_score}"),
+    }
+}
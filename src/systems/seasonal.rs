@@ -0,0 +1,135 @@
+//! Seasonal decoration systems.
+//!
+//! Themes can decorate the sky: snowflakes sway down in front of the pipes,
+//! and bats flap across it behind them. Decorations of another kind are
+//! removed when the theme changes, and none spawn with reduced motion. They
+//! are purely cosmetic and draw from the effects random stream, never the
+//! pipes one.
+
+use bevy::prelude::*;
+
+use crate::components::{DecorationLayer, SeasonalDecoration};
+use crate::constants::{
+    BAT_BOB, BAT_COLOR, BAT_FLAP_RATE, BAT_HEIGHT, BAT_SPAWN_INTERVAL, BAT_SPEED, BAT_WIDTH, BAT_Z,
+    GROUND_HEIGHT, SNOW_FALL_SPEED, SNOW_SIZE, SNOW_SPAWN_INTERVAL, SNOW_SWAY, SNOW_Z,
+};
+use crate::resources::GameViewport;
+use crate::rng::{GameRng, RngStream};
+use crate::settings::Settings;
+use crate::theme::{Decoration, Theme, color};
+
+/// Spawns the empty layer seasonal decorations are parented to.
+pub fn spawn_decoration_layer(mut commands: Commands) {
+    commands.spawn((
+        Transform::default(),
+        Visibility::default(),
+        DecorationLayer::default(),
+    ));
+}
+
+/// Moves the decorations, spawns new ones for the current theme, and
+/// despawns the ones that left the screen or belong to another theme.
+#[allow(clippy::too_many_arguments)]
+pub fn animate_decorations(
+    mut commands: Commands,
+    time: Res<Time>,
+    theme: Res<Theme>,
+    settings: Res<Settings>,
+    viewport: Res<GameViewport>,
+    mut rng: ResMut<GameRng>,
+    mut layer_query: Query<(Entity, &mut DecorationLayer)>,
+    mut decoration_query: Query<(Entity, &mut SeasonalDecoration, &mut Transform)>,
+) {
+    let delta = time.delta_secs();
+
+    for (entity, mut decoration, mut transform) in decoration_query.iter_mut() {
+        if theme.decoration != Some(decoration.kind) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        decoration.elapsed += delta;
+        let elapsed = decoration.elapsed;
+        transform.translation += decoration.velocity.extend(0.0) * delta;
+        match decoration.kind {
+            Decoration::Snow => {
+                transform.translation.x += elapsed.sin() * SNOW_SWAY * delta;
+            }
+            Decoration::Bats => {
+                transform.translation.y += (elapsed * 2.0).cos() * BAT_BOB * delta;
+                // Beat the wings by squashing the body
+                transform.scale.y = 0.4 + 0.6 * (elapsed * BAT_FLAP_RATE).sin().abs();
+            }
+        }
+
+        let position = transform.translation;
+        if position.y < -viewport.half_height() - SNOW_SIZE.1
+            || position.x < -viewport.half_width() - BAT_WIDTH
+        {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    let Some(kind) = theme.decoration else {
+        return;
+    };
+    if settings.reduced_motion {
+        return;
+    }
+    for (layer, mut decorations) in layer_query.iter_mut() {
+        decorations.spawn_timer -= delta;
+        if decorations.spawn_timer > 0.0 {
+            continue;
+        }
+        decorations.spawn_timer = match kind {
+            Decoration::Snow => SNOW_SPAWN_INTERVAL,
+            Decoration::Bats => rng
+                .effects
+                .range(BAT_SPAWN_INTERVAL.0, BAT_SPAWN_INTERVAL.1),
+        };
+        spawn_decoration(&mut commands, layer, kind, &viewport, &mut rng.effects);
+    }
+}
+
+/// Spawns a decoration of random size, position, and speed on the layer.
+///
+/// Snowflakes start above the screen anywhere across it; bats start past the
+/// right edge, in the upper part of the sky.
+fn spawn_decoration(
+    commands: &mut Commands,
+    layer: Entity,
+    kind: Decoration,
+    viewport: &GameViewport,
+    rng: &mut RngStream,
+) {
+    let elapsed = rng.angle();
+    let (sprite, position, velocity) = match kind {
+        Decoration::Snow => {
+            let size = rng.range(SNOW_SIZE.0, SNOW_SIZE.1);
+            let x = rng.range(-viewport.half_width(), viewport.half_width());
+            (
+                Sprite::from_color(Color::WHITE.with_alpha(0.85), Vec2::splat(size)),
+                Vec3::new(x, viewport.half_height() + size, SNOW_Z),
+                Vec2::new(0.0, -rng.range(SNOW_FALL_SPEED.0, SNOW_FALL_SPEED.1)),
+            )
+        }
+        Decoration::Bats => {
+            let lowest = -viewport.half_height() + GROUND_HEIGHT + viewport.height / 3.0;
+            let y = rng.range(lowest, viewport.half_height() - BAT_HEIGHT);
+            (
+                Sprite::from_color(color(BAT_COLOR), Vec2::new(BAT_WIDTH, BAT_HEIGHT)),
+                Vec3::new(viewport.half_width() + BAT_WIDTH, y, BAT_Z),
+                Vec2::new(-rng.range(BAT_SPEED.0, BAT_SPEED.1), 0.0),
+            )
+        }
+    };
+
+    commands.entity(layer).with_child((
+        sprite,
+        Transform::from_translation(position),
+        SeasonalDecoration {
+            kind,
+            velocity,
+            elapsed,
+        },
+    ));
+}
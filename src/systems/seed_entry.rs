@@ -0,0 +1,171 @@
+//! Seed entry screen systems.
+//!
+//! The seed entry screen, opened from the settings, takes a seed code shared
+//! by another player. Enter starts a run with that seed, so both fly through
+//! the same pipes; Escape (or the east gamepad button) returns to the
+//! settings. Codes that can't be read are refused with the reason below them.
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+
+use crate::components::{
+    FillScreen, InstructionText, MenuButton, SeedEntryUi, SeedInputText, SeedStatusText,
+};
+use crate::input::{MenuAction, MenuInput};
+use crate::resources::{GameViewport, PendingSeed, SeedEntryMenu};
+use crate::rng::parse_seed_code;
+use crate::states::GameState;
+use crate::systems::focus::FOCUS_COLOR;
+
+/// Longest code that can be typed, dashes included.
+const MAX_INPUT_LENGTH: usize = 20;
+
+/// Builds the seed entry screen and hides the menu text behind it.
+///
+/// The menu text stays hidden when the screen closes: both the settings and
+/// the run it starts hide it too.
+#[allow(clippy::type_complexity)]
+pub fn spawn_seed_entry(
+    mut commands: Commands,
+    viewport: Res<GameViewport>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+) {
+    commands.insert_resource(SeedEntryMenu::default());
+
+    for mut visibility in menu_text_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+
+    // Dim the world behind the screen
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            custom_size: Some(Vec2::new(viewport.width * 2.0, viewport.height * 2.0)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        FillScreen,
+        SeedEntryUi,
+    ));
+
+    commands.spawn((
+        Text2d::new("Play a seed code"),
+        TextFont {
+            font_size: 36.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, 120.0, 6.0),
+        SeedEntryUi,
+    ));
+
+    commands.spawn((
+        Text2d::new("Type a code shared by a friend\nEnter: play   Esc: back"),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgba(1.0, 1.0, 1.0, 0.7)),
+        Transform::from_xyz(0.0, 60.0, 6.0),
+        SeedEntryUi,
+    ));
+
+    commands.spawn((
+        Text2d::new("_"),
+        TextFont {
+            font_size: 32.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, 0.0, 6.0),
+        SeedInputText,
+        SeedEntryUi,
+    ));
+
+    commands.spawn((
+        Text2d::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(FOCUS_COLOR),
+        Transform::from_xyz(0.0, -45.0, 6.0),
+        SeedStatusText,
+        SeedEntryUi,
+    ));
+}
+
+/// Removes the seed entry screen.
+pub fn despawn_seed_entry(mut commands: Commands, ui_query: Query<Entity, With<SeedEntryUi>>) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Edits the code and starts a run with it on confirm.
+pub fn type_seed_code(
+    mut key_events: MessageReader<KeyboardInput>,
+    menu_input: MenuInput,
+    mut menu: ResMut<SeedEntryMenu>,
+    mut pending_seed: ResMut<PendingSeed>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if menu_input.just_pressed(MenuAction::Back) {
+        key_events.clear();
+        next_state.set(GameState::Settings);
+        return;
+    }
+
+    for event in key_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Backspace => {
+                menu.input.pop();
+            }
+            Key::Character(_) => {
+                let text = event.text.as_deref().unwrap_or_default();
+                let typed = text
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+                    .map(|c| c.to_ascii_uppercase());
+                for character in typed {
+                    if menu.input.len() < MAX_INPUT_LENGTH {
+                        menu.input.push(character);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if menu_input.just_pressed(MenuAction::Confirm) {
+        match parse_seed_code(&menu.input) {
+            Ok(seed) => {
+                pending_seed.0 = Some(seed);
+                next_state.set(GameState::Playing);
+            }
+            Err(error) => menu.status = format!("Can't play this code: {error}"),
+        }
+    }
+}
+
+/// Shows the code typed so far and the status line.
+#[allow(clippy::type_complexity)]
+pub fn update_seed_entry(
+    menu: Res<SeedEntryMenu>,
+    mut input_query: Query<&mut Text2d, (With<SeedInputText>, Without<SeedStatusText>)>,
+    mut status_query: Query<&mut Text2d, (With<SeedStatusText>, Without<SeedInputText>)>,
+) {
+    if !menu.is_changed() {
+        return;
+    }
+    for mut text in input_query.iter_mut() {
+        text.0 = format!("{}_", menu.input);
+    }
+    for mut text in status_query.iter_mut() {
+        text.0.clone_from(&menu.status);
+    }
+}
@@ -0,0 +1,597 @@
+//! Settings screen systems.
+//!
+//! This module opens the settings screen from the menu, builds its rows,
+//! and handles keyboard, mouse, and touch navigation. Only a page of rows
+//! fits on screen; the list scrolls to follow the selection, and "More"
+//! buttons scroll it by a page for mouse and touch, dimmed at either end of
+//! the list. Larger text sizes space the rows further apart and fit fewer on
+//! a page. A status line below the rows reports the outcome of exporting or
+//! importing the settings or the save, or of buying a trail.
+
+use bevy::prelude::*;
+
+use crate::bindings::GameAction;
+use crate::components::{
+    FillScreen, Focusable, HitArea, InstructionText, MenuButton, SettingsButton, SettingsRowText,
+    SettingsScrollButton, SettingsStatusText, SettingsUi, WorldButton,
+};
+use crate::input::{ActionInput, MenuAction, MenuInput};
+use crate::portable::{PortableSave, SAVE_EXPORT_FILE_NAME};
+use crate::replay::GhostLibrary;
+use crate::resources::{ButtonClicked, GameViewport, SettingsMenu};
+use crate::save::SaveData;
+use crate::settings::{SETTINGS_EXPORT_FILE_NAME, Settings, SettingsRow, cycle};
+use crate::splits::{BestSplits, RunSplits, SPLITS_EXPORT_FILE_NAME};
+use crate::states::GameState;
+use crate::systems::focus::{FOCUS_COLOR, step_focus};
+use crate::theme::{Theme, theme_names};
+use crate::trails::{TrailChoice, TrailStyle, trail_names};
+
+/// Vertical distance between settings rows at the normal text size.
+const ROW_SPACING: f32 = 36.0;
+
+/// Height of the first row on screen.
+const FIRST_ROW_Y: f32 = 105.0;
+
+/// Number of rows on screen at once at the normal text size.
+const VISIBLE_ROWS: usize = 8;
+
+/// Opens the settings screen when the settings key or button is pressed, the settings button is
+/// pressed, or the focused settings button is confirmed.
+pub fn open_settings(
+    actions: ActionInput,
+    menu_input: MenuInput,
+    mut clicks: MessageReader<ButtonClicked<SettingsButton>>,
+    button_query: Query<&Focusable, With<SettingsButton>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let button_clicked = clicks.read().count() > 0;
+    let button_confirmed = menu_input.just_pressed(MenuAction::Confirm)
+        && button_query.iter().any(|focusable| focusable.focused);
+
+    if actions.just_pressed(GameAction::OpenSettings) || button_clicked || button_confirmed {
+        next_state.set(GameState::Settings);
+    }
+}
+
+/// Builds the settings screen and hides the menu text behind it.
+#[allow(clippy::type_complexity)]
+pub fn spawn_settings_menu(
+    mut commands: Commands,
+    viewport: Res<GameViewport>,
+    settings: Res<Settings>,
+    ghosts: Res<GhostLibrary>,
+    save: Res<SaveData>,
+    trails: Res<Assets<TrailStyle>>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+) {
+    let trail = TrailChoice::new(save.equipped_trail.as_deref(), &save, &trails);
+    commands.insert_resource(SettingsMenu {
+        trail: trail.clone(),
+        ..default()
+    });
+
+    for mut visibility in menu_text_query.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+
+    // Dim the world behind the menu
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            custom_size: Some(Vec2::new(viewport.width * 2.0, viewport.height * 2.0)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        FillScreen,
+        SettingsUi,
+    ));
+
+    commands.spawn((
+        Text2d::new("Settings"),
+        TextFont {
+            font_size: 36.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, 175.0, 6.0),
+        SettingsUi,
+    ));
+
+    let (spacing, visible_rows) = row_layout(&settings);
+    for (index, row) in SettingsRow::ALL.into_iter().enumerate() {
+        commands.spawn((
+            Text2d::new(row.label(&settings, &ghosts, &trail)),
+            TextFont {
+                font_size: 22.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(0.0, row_y(index, 0, spacing), 6.0),
+            if index < visible_rows {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            },
+            HitArea {
+                size: Vec2::new(viewport.width, spacing),
+            },
+            WorldButton::default(),
+            Focusable::new(index as u32),
+            SettingsRowText(row),
+            SettingsUi,
+        ));
+    }
+
+    let scroll_buttons = [
+        ("More ^", FIRST_ROW_Y + ROW_SPACING, -1),
+        ("More v", row_y(VISIBLE_ROWS, 0, ROW_SPACING), 1),
+    ];
+    for (label, y, direction) in scroll_buttons {
+        commands.spawn((
+            Text2d::new(label),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgba(1.0, 1.0, 1.0, 0.7)),
+            Transform::from_xyz(0.0, y, 6.0),
+            HitArea {
+                size: Vec2::new(140.0, ROW_SPACING),
+            },
+            WorldButton::new(Color::srgba(1.0, 1.0, 1.0, 0.7)),
+            SettingsScrollButton(direction),
+            SettingsUi,
+        ));
+    }
+
+    commands.spawn((
+        Text2d::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(FOCUS_COLOR),
+        Transform::from_xyz(0.0, row_y(VISIBLE_ROWS + 1, 0, ROW_SPACING), 6.0),
+        SettingsStatusText,
+        SettingsUi,
+    ));
+}
+
+/// Returns the spacing of the rows and how many fit on screen at the text
+/// size setting. The list keeps its height, so larger text shows fewer rows.
+fn row_layout(settings: &Settings) -> (f32, usize) {
+    let factor = settings.text_scale.factor();
+    let visible_rows = (VISIBLE_ROWS as f32 / factor).floor() as usize;
+    (ROW_SPACING * factor, visible_rows.max(1))
+}
+
+/// Returns the height of the row at `index` when the list is scrolled to
+/// `scroll`, with rows `spacing` apart.
+fn row_y(index: usize, scroll: usize, spacing: f32) -> f32 {
+    FIRST_ROW_Y - (index as f32 - scroll as f32) * spacing
+}
+
+/// Returns the scroll position keeping `selected` among the `visible_rows`
+/// on screen, moving as little as possible from `scroll`.
+fn scroll_to(selected: usize, scroll: usize, visible_rows: usize) -> usize {
+    if selected < scroll {
+        selected
+    } else if selected >= scroll + visible_rows {
+        selected + 1 - visible_rows
+    } else {
+        scroll
+    }
+}
+
+/// Removes the settings screen and restores the menu text.
+#[allow(clippy::type_complexity)]
+pub fn despawn_settings_menu(
+    mut commands: Commands,
+    ui_query: Query<Entity, With<SettingsUi>>,
+    mut menu_text_query: Query<&mut Visibility, Or<(With<InstructionText>, With<MenuButton>)>>,
+) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for mut visibility in menu_text_query.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Handles input on the settings screen.
+///
+/// Up/Down (or the d-pad) select a row, Left/Right change its value,
+/// Space/Enter (or the south button) activate it, and Escape (or the east
+/// button) returns to the menu. Clicking or tapping a row activates it,
+/// and clicking or tapping a "More" button scrolls by a page.
+#[allow(clippy::too_many_arguments)]
+pub fn navigate_settings(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    menu_input: MenuInput,
+    mut row_clicks: MessageReader<ButtonClicked<SettingsRowText>>,
+    mut scroll_clicks: MessageReader<ButtonClicked<SettingsScrollButton>>,
+    mut menu: ResMut<SettingsMenu>,
+    mut settings: ResMut<Settings>,
+    mut ghosts: ResMut<GhostLibrary>,
+    mut save: ResMut<SaveData>,
+    themes: Res<Assets<Theme>>,
+    trails: Res<Assets<TrailStyle>>,
+    run_splits: Res<RunSplits>,
+    best_splits: Res<BestSplits>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let row_count = SettingsRow::ALL.len();
+    let (_, visible_rows) = row_layout(&settings);
+
+    if menu_input.just_pressed(MenuAction::Back) {
+        next_state.set(GameState::Menu);
+        return;
+    }
+    menu.selected = step_focus(menu.selected, menu_input.vertical_step(), row_count);
+
+    let mut activated =
+        menu_input.just_pressed(MenuAction::Confirm) || keyboard_input.just_pressed(KeyCode::Space);
+    let mut step = if menu_input.just_pressed(MenuAction::Left) {
+        -1
+    } else if menu_input.just_pressed(MenuAction::Right) || activated {
+        1
+    } else {
+        0
+    };
+
+    for ButtonClicked(SettingsScrollButton(direction)) in scroll_clicks.read() {
+        let last_page = row_count.saturating_sub(visible_rows);
+        menu.scroll = (menu.scroll as i32 + direction * visible_rows as i32)
+            .clamp(0, last_page as i32) as usize;
+        menu.selected = menu
+            .selected
+            .clamp(menu.scroll, menu.scroll + visible_rows - 1);
+    }
+    for ButtonClicked(SettingsRowText(row)) in row_clicks.read() {
+        if let Some(index) = SettingsRow::ALL.iter().position(|other| other == row) {
+            menu.selected = index;
+            step = 1;
+            activated = true;
+        }
+    }
+    menu.scroll = scroll_to(menu.selected, menu.scroll, visible_rows);
+
+    if step == 0 {
+        return;
+    }
+
+    match SettingsRow::ALL[menu.selected % row_count] {
+        SettingsRow::Theme => {
+            let names = theme_names(&themes);
+            if let Some(name) = cycle(&names, &settings.theme, step) {
+                settings.theme = name.clone();
+                settings.touch();
+                settings.store();
+            }
+        }
+        SettingsRow::SeasonalThemes => {
+            settings.seasonal_themes = !settings.seasonal_themes;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::RainbowMode => {
+            settings.rainbow_mode = !settings.rainbow_mode;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::Ghost => {
+            let labels = ghosts.labels();
+            if let Some(label) = cycle(&labels, ghosts.selected_label(), step) {
+                ghosts.select(label);
+            }
+        }
+        SettingsRow::Trail => {
+            if activated
+                && let (Some(name), Some(cost)) = (menu.trail.name.clone(), menu.trail.cost)
+            {
+                // Activating a locked trail buys it
+                if !save.spend_coins(cost) {
+                    menu.status = format!("{name} costs {cost} coins (you have {})", save.coins);
+                    return;
+                }
+                save.trails.insert(name.clone());
+                save.equipped_trail = Some(name.clone());
+                save.store();
+                info!("Bought the {name} trail for {cost} coins");
+                menu.status = format!("Bought {name}!");
+                menu.trail = TrailChoice::new(Some(&name), &save, &trails);
+                return;
+            }
+
+            // Browse "None" and every trail, equipping the owned ones
+            let options: Vec<String> = std::iter::once(String::new())
+                .chain(trail_names(&trails))
+                .collect();
+            let current = menu.trail.name.clone().unwrap_or_default();
+            if let Some(next) = cycle(&options, &current, step) {
+                let name = Some(next.as_str()).filter(|name| !name.is_empty());
+                menu.trail = TrailChoice::new(name, &save, &trails);
+                if menu.trail.cost.is_none() && save.equipped_trail.as_deref() != name {
+                    save.equipped_trail = name.map(str::to_string);
+                    save.store();
+                }
+            }
+        }
+        SettingsRow::Haptics => {
+            settings.haptics = !settings.haptics;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::ReducedMotion => {
+            settings.reduced_motion = !settings.reduced_motion;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::ScreenShake => {
+            settings.screen_shake = !settings.screen_shake;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::ScreenFlash => {
+            settings.screen_flash = !settings.screen_flash;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::EdgeFlash => {
+            settings.edge_flash = !settings.edge_flash;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::SquashStretch => {
+            settings.squash_stretch = !settings.squash_stretch;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::Companion => {
+            settings.companion = !settings.companion;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::OneSwitch => {
+            settings.one_switch = !settings.one_switch;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::FlickerMode => {
+            settings.flicker_mode = !settings.flicker_mode;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::GlideMode => {
+            settings.glide_mode = !settings.glide_mode;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::FlapCooldown => {
+            settings.flap_cooldown = !settings.flap_cooldown;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::PlaytimeReminder => {
+            settings.playtime_reminder = !settings.playtime_reminder;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::SpeedrunTimer => {
+            settings.speedrun_timer = !settings.speedrun_timer;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::Vsync => {
+            settings.vsync = !settings.vsync;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::FpsCap => {
+            settings.fps_cap = settings.fps_cap.cycle(step);
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::BatterySaver => {
+            settings.battery_saver = !settings.battery_saver;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::ParticleQuality => {
+            settings.particle_quality = settings.particle_quality.cycle(step);
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::Bloom => {
+            settings.bloom = !settings.bloom;
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::UiScale => {
+            settings.ui_scale = settings.ui_scale.cycle(step);
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::TextScale => {
+            settings.text_scale = settings.text_scale.cycle(step);
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::TextOutline => {
+            settings.text_outline = settings.text_outline.cycle(step);
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::MusicVolume => {
+            settings.music_volume = settings.music_volume.cycle(step);
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::SfxVolume => {
+            settings.sfx_volume = settings.sfx_volume.cycle(step);
+            settings.touch();
+            settings.store();
+        }
+        SettingsRow::ExportSettings => {
+            menu.status = match settings.export() {
+                Ok(path) => {
+                    info!("Exported settings to {}", path.display());
+                    format!("Exported to {SETTINGS_EXPORT_FILE_NAME}")
+                }
+                Err(error) => format!("Export failed: {error}"),
+            };
+        }
+        SettingsRow::ImportSettings => {
+            menu.status = match Settings::import() {
+                Ok(mut imported) => {
+                    let known_theme = keep_known_theme(&mut imported, &settings, &themes);
+                    *settings = imported;
+                    settings.store();
+                    if known_theme {
+                        "Settings imported".to_string()
+                    } else {
+                        "Settings imported (theme not installed, kept yours)".to_string()
+                    }
+                }
+                Err(error) => format!("Import failed: {error}"),
+            };
+        }
+        SettingsRow::ExportSave => {
+            let portable = PortableSave {
+                save: save.clone(),
+                settings: settings.clone(),
+            };
+            menu.status = match portable.export() {
+                Ok(path) => {
+                    info!("Exported the save to {}", path.display());
+                    format!("Exported to {SAVE_EXPORT_FILE_NAME}")
+                }
+                Err(error) => format!("Export failed: {error}"),
+            };
+        }
+        SettingsRow::ImportSave => {
+            menu.status = match PortableSave::import() {
+                Ok(PortableSave {
+                    save: imported_save,
+                    settings: mut imported_settings,
+                }) => {
+                    let known_theme = keep_known_theme(&mut imported_settings, &settings, &themes);
+                    *save = imported_save;
+                    save.store();
+                    *settings = imported_settings;
+                    settings.store();
+                    menu.trail = TrailChoice::new(save.equipped_trail.as_deref(), &save, &trails);
+                    info!("Imported the save from {SAVE_EXPORT_FILE_NAME}");
+                    if known_theme {
+                        "Save imported".to_string()
+                    } else {
+                        "Save imported (theme not installed, kept yours)".to_string()
+                    }
+                }
+                Err(error) => format!("Import failed: {error}"),
+            };
+        }
+        SettingsRow::ExportSplits => {
+            menu.status = if run_splits.times.is_empty() && best_splits.times.is_empty() {
+                "No splits yet: pass a pipe first".to_string()
+            } else {
+                match best_splits.export(&run_splits) {
+                    Ok(path) => {
+                        info!("Exported splits to {}", path.display());
+                        format!("Exported to {SPLITS_EXPORT_FILE_NAME}")
+                    }
+                    Err(error) => format!("Export failed: {error}"),
+                }
+            };
+        }
+        SettingsRow::Controls => next_state.set(GameState::Controls),
+        SettingsRow::SeedCode => next_state.set(GameState::SeedEntry),
+        SettingsRow::SoundTest => next_state.set(GameState::Jukebox),
+        SettingsRow::Back => next_state.set(GameState::Menu),
+    }
+}
+
+/// Keeps the current theme in imported settings whose theme isn't
+/// installed, returning whether the imported theme was kept.
+fn keep_known_theme(imported: &mut Settings, current: &Settings, themes: &Assets<Theme>) -> bool {
+    let known_theme = theme_names(themes).contains(&imported.theme);
+    if !known_theme {
+        imported.theme = current.theme.clone();
+    }
+    known_theme
+}
+
+/// Refreshes row labels and the status line, scrolls and spaces the rows,
+/// focuses the selected row, and disables the "More" buttons at either end of
+/// the list.
+#[allow(clippy::type_complexity)]
+pub fn update_settings_rows(
+    mut menu: ResMut<SettingsMenu>,
+    settings: Res<Settings>,
+    ghosts: Res<GhostLibrary>,
+    mut row_query: Query<(
+        &SettingsRowText,
+        &mut Text2d,
+        &mut Focusable,
+        &mut Transform,
+        &mut Visibility,
+        &mut HitArea,
+    )>,
+    mut scroll_query: Query<(&SettingsScrollButton, &mut WorldButton)>,
+    mut status_query: Query<&mut Text2d, (With<SettingsStatusText>, Without<SettingsRowText>)>,
+) {
+    for mut text in status_query.iter_mut() {
+        if text.0 != menu.status {
+            text.0.clone_from(&menu.status);
+        }
+    }
+
+    // The text size may have just changed, fitting fewer rows on screen
+    let row_count = SettingsRow::ALL.len();
+    let (spacing, visible_rows) = row_layout(&settings);
+    let scroll = scroll_to(menu.selected, menu.scroll, visible_rows);
+    if menu.scroll != scroll {
+        menu.scroll = scroll;
+    }
+
+    for (SettingsScrollButton(direction), mut button) in scroll_query.iter_mut() {
+        let more = if *direction < 0 {
+            menu.scroll > 0
+        } else {
+            menu.scroll + visible_rows < row_count
+        };
+        if button.disabled == more {
+            button.disabled = !more;
+        }
+    }
+
+    for (SettingsRowText(row), mut text, mut focusable, mut transform, mut visibility, mut area) in
+        row_query.iter_mut()
+    {
+        let index = SettingsRow::ALL
+            .iter()
+            .position(|other| other == row)
+            .unwrap_or_default();
+        let on_screen = (menu.scroll..menu.scroll + visible_rows).contains(&index);
+        visibility.set_if_neq(if on_screen {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+        transform.translation.y = row_y(index, menu.scroll, spacing);
+        if area.size.y != spacing {
+            area.size.y = spacing;
+        }
+
+        let label = row.label(&settings, &ghosts, &menu.trail);
+        if text.0 != label {
+            text.0 = label;
+        }
+
+        let selected = SettingsRow::ALL.get(menu.selected) == Some(row);
+        if focusable.focused != selected {
+            focusable.focused = selected;
+        }
+    }
+}
@@ -1,35 +1,63 @@
 //! Setup system for initializing the game world.
 //!
 //! This module contains the startup system that creates all initial entities.
+//! The HUD (score, best score, instructions, run statistics) is made of
+//! `bevy_ui` nodes anchored to the screen, drawn by a camera of its own.
 
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::camera::visibility::RenderLayers;
 use bevy::camera::{OrthographicProjection, Projection, ScalingMode};
 use bevy::prelude::*;
 use bevy::text::{Justify, LineBreak};
 
+use crate::challenge::ChallengeKind;
 use crate::components::{
-    Bird, EdgeFlash, EdgeType, FillScreen, Ground, InstructionText, MainCamera, ScoreText,
-    ScreenFlash, Sky,
+    BestScoreText, Bird, CameraRig, ChallengeButton, EdgeFlash, EdgeType, Emissive, FillScreen,
+    Focusable, Ground, HitArea, HudCamera, InstructionText, LetterIcon, MainCamera, MenuButton,
+    MutatorsButton, OutlineGlyph, OutlinedText, PlayButton, RunStatsText, ScoreText, ScreenFlash,
+    SettingsButton, Sky, SplitTimerText, TransitionOverlay, WingRecharge, WorldButton,
 };
 use crate::constants::{
-    BIRD_SIZE, GAME_HEIGHT, GROUND_HEIGHT, SCORE_FLASH_BORDER_WIDTH, SCORE_FLASH_GRADIENT_STRIPS,
-    SCORE_FLASH_SOLID_RATIO,
+    BIRD_GLOW, BIRD_SIZE, GAME_HEIGHT, GROUND_HEIGHT, LETTER_COLOR, LETTER_ICON_MISSING_ALPHA,
+    LETTER_ICON_SIZE, SCORE_FLASH_BORDER_WIDTH, SCORE_FLASH_GLOW, SCORE_FLASH_GRADIENT_STRIPS,
+    SCORE_FLASH_SOLID_RATIO, TEXT_OUTLINE_ALPHA, TEXT_OUTLINE_COLOR, TEXT_OUTLINE_WIDTH,
+    WING_RECHARGE_COLOR, WING_RECHARGE_OFFSET, WING_RECHARGE_SIZE,
 };
+use crate::letters::BONUS_WORD;
+use crate::mutators::Mutators;
 use crate::resources::GameViewport;
+use crate::save::SaveData;
+use crate::settings::Settings;
+use crate::systems::score::best_score_label;
+use crate::theme::{Theme, color};
+
+/// Distance between the HUD and the screen edges, in game units.
+const HUD_MARGIN: f32 = 12.0;
 
 /// Sets up the initial game entities.
 ///
 /// Creates the camera, bird, ground, sky background, and UI elements.
-pub fn setup(mut commands: Commands, viewport: Res<GameViewport>) {
+pub fn setup(
+    mut commands: Commands,
+    viewport: Res<GameViewport>,
+    save: Res<SaveData>,
+    settings: Res<Settings>,
+    mutators: Res<Mutators>,
+    theme: Res<Theme>,
+) {
     spawn_camera(&mut commands, &viewport);
-    spawn_bird(&mut commands);
-    spawn_ground(&mut commands, &viewport);
-    spawn_sky(&mut commands, &viewport);
-    spawn_ui(&mut commands);
+    spawn_bird(&mut commands, &theme);
+    spawn_ground(&mut commands, &viewport, &theme);
+    spawn_sky(&mut commands, &viewport, &theme);
+    spawn_ui(&mut commands, &save, &settings, &mutators);
     spawn_screen_flash(&mut commands, &viewport);
+    spawn_transition_overlay(&mut commands, &viewport);
     spawn_edge_flashes(&mut commands, &viewport);
 }
 
-/// Spawns the 2D camera with MainCamera marker and proper projection.
+/// Spawns the 2D camera with MainCamera marker and proper projection, and
+/// the HUD camera drawn over it.
 fn spawn_camera(commands: &mut Commands, viewport: &GameViewport) {
     commands.spawn((
         Camera2d,
@@ -40,27 +68,53 @@ fn spawn_camera(commands: &mut Commands, viewport: &GameViewport) {
             ..OrthographicProjection::default_2d()
         }),
         MainCamera,
+        CameraRig::default(),
+    ));
+
+    // Draws only the UI, so the camera rig moving and zooming the main camera
+    // leaves the HUD in place
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: 1,
+            clear_color: ClearColorConfig::None,
+            ..default()
+        },
+        RenderLayers::none(),
+        IsDefaultUiCamera,
+        HudCamera,
     ));
 }
 
-/// Spawns the bird entity (yellow square).
-fn spawn_bird(commands: &mut Commands) {
+/// Spawns the bird entity (square in the theme's bird color).
+fn spawn_bird(commands: &mut Commands, theme: &Theme) {
     commands.spawn((
         Sprite {
-            color: Color::srgb(1.0, 0.8, 0.0),
+            color: color(theme.bird_color),
             custom_size: Some(Vec2::splat(BIRD_SIZE)),
             ..default()
         },
         Transform::from_xyz(-50.0, 0.0, 1.0),
         Bird::default(),
+        Emissive::new(BIRD_GLOW),
+    ));
+    // Wing recharge bar, shown below the bird during a flap cooldown
+    commands.spawn((
+        Sprite::from_color(
+            color(WING_RECHARGE_COLOR),
+            Vec2::new(WING_RECHARGE_SIZE.0, WING_RECHARGE_SIZE.1),
+        ),
+        Transform::from_xyz(-50.0, -WING_RECHARGE_OFFSET, 1.1),
+        Visibility::Hidden,
+        WingRecharge,
     ));
 }
 
-/// Spawns the ground entity (brown rectangle).
-fn spawn_ground(commands: &mut Commands, viewport: &GameViewport) {
+/// Spawns the ground entity (rectangle in the theme's ground color).
+fn spawn_ground(commands: &mut Commands, viewport: &GameViewport, theme: &Theme) {
     commands.spawn((
         Sprite {
-            color: Color::srgb(0.6, 0.4, 0.2),
+            color: color(theme.ground_color),
             // Make ground wider than viewport to handle any aspect ratio
             custom_size: Some(Vec2::new(viewport.width * 2.0, GROUND_HEIGHT)),
             ..default()
@@ -70,11 +124,11 @@ fn spawn_ground(commands: &mut Commands, viewport: &GameViewport) {
     ));
 }
 
-/// Spawns the sky background (light blue rectangle).
-fn spawn_sky(commands: &mut Commands, viewport: &GameViewport) {
+/// Spawns the sky background (rectangle in the theme's sky color).
+fn spawn_sky(commands: &mut Commands, viewport: &GameViewport, theme: &Theme) {
     commands.spawn((
         Sprite {
-            color: Color::srgb(0.5, 0.8, 1.0),
+            color: color(theme.sky_color),
             // Make sky larger than viewport to handle any aspect ratio
             custom_size: Some(Vec2::new(viewport.width * 2.0, viewport.height)),
             ..default()
@@ -84,40 +138,251 @@ fn spawn_sky(commands: &mut Commands, viewport: &GameViewport) {
     ));
 }
 
-/// Spawns UI elements (score and instruction text).
-fn spawn_ui(commands: &mut Commands) {
-    // Score text
+/// Spawns UI elements (score, best score, instruction text, and menu buttons).
+///
+/// HUD sizes and offsets are in game units: the UI scale follows the window
+/// height, so they match the world at any window size.
+fn spawn_ui(commands: &mut Commands, save: &SaveData, settings: &Settings, mutators: &Mutators) {
+    // Score text, along the top edge
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(45.0),
+            width: Val::Percent(100.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            let score = parent
+                .spawn((
+                    Text::new("0"),
+                    TextFont {
+                        font_size: 60.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    TextLayout::new(Justify::Center, LineBreak::NoWrap),
+                    Node {
+                        width: Val::Percent(100.0),
+                        ..default()
+                    },
+                    ScoreText,
+                    OutlinedText,
+                ))
+                .id();
+            spawn_outline_glyphs(parent, score);
+        });
+
+    // Best score text, in the top right corner
+    commands.spawn((
+        Text::new(best_score_label(save, settings.score_category(), mutators)),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new(Justify::Right, LineBreak::NoWrap),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(HUD_MARGIN),
+            right: Val::Px(HUD_MARGIN),
+            ..default()
+        },
+        BestScoreText,
+    ));
+
+    // Speedrun timer (when enabled), in the top left corner
     commands.spawn((
-        Text2d::new("0"),
+        Text::default(),
         TextFont {
-            font_size: 60.0,
+            font_size: 20.0,
             ..default()
         },
         TextColor(Color::WHITE),
-        Transform::from_xyz(0.0, GAME_HEIGHT / 2.0 - 80.0, 2.0),
-        ScoreText,
+        TextLayout::new(Justify::Left, LineBreak::NoWrap),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(HUD_MARGIN),
+            left: Val::Px(HUD_MARGIN),
+            ..default()
+        },
+        Visibility::Hidden,
+        SplitTimerText,
     ));
 
-    // Instruction text
+    // Letter bonus icons (once a letter is collected), below the score
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(115.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            column_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|row| {
+            for (index, letter) in BONUS_WORD.into_iter().enumerate() {
+                row.spawn((
+                    Text::new(letter.to_string()),
+                    TextFont {
+                        font_size: LETTER_ICON_SIZE,
+                        ..default()
+                    },
+                    TextColor(color(LETTER_COLOR).with_alpha(LETTER_ICON_MISSING_ALPHA)),
+                    Visibility::Hidden,
+                    LetterIcon(index),
+                ));
+            }
+        });
+
+    // Instruction text, in the middle of the screen
     let instruction_text = if cfg!(target_os = "ios") {
         "Tap to start"
     } else {
         "Click or press SPACE to start"
     };
 
+    commands.spawn(centered_panel(0.0)).with_children(|panel| {
+        panel.spawn(Node::default()).with_children(|parent| {
+            let instructions = parent
+                .spawn((
+                    Text::new(instruction_text),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                    TextLayout::new(Justify::Center, LineBreak::NoWrap),
+                    InstructionText,
+                    OutlinedText,
+                ))
+                .id();
+            spawn_outline_glyphs(parent, instructions);
+        });
+    });
+
+    // Run statistics (game over only), below the instructions
+    commands.spawn(centered_panel(80.0)).with_child((
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new(Justify::Center, LineBreak::NoWrap),
+        Visibility::Hidden,
+        RunStatsText,
+    ));
+
+    // Play, mutator, challenge and settings buttons (menu only)
     commands.spawn((
-        Text2d::new(instruction_text),
+        Text2d::new("Play"),
         TextFont {
             font_size: 20.0,
             ..default()
         },
         TextColor(Color::WHITE),
-        TextLayout::new(Justify::Center, LineBreak::NoWrap),
-        Transform::from_xyz(0.0, 0.0, 2.0),
-        InstructionText,
+        Transform::from_xyz(0.0, -GAME_HEIGHT / 2.0 + GROUND_HEIGHT + 200.0, 2.0),
+        HitArea {
+            size: Vec2::new(140.0, 36.0),
+        },
+        WorldButton::default(),
+        Focusable::new(0),
+        MenuButton,
+        PlayButton,
+    ));
+    commands.spawn((
+        Text2d::new("Mutators"),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, -GAME_HEIGHT / 2.0 + GROUND_HEIGHT + 160.0, 2.0),
+        HitArea {
+            size: Vec2::new(140.0, 36.0),
+        },
+        WorldButton::default(),
+        Focusable::new(1),
+        MenuButton,
+        MutatorsButton,
+    ));
+    for (index, kind) in ChallengeKind::ALL.into_iter().enumerate() {
+        // Labelled with their countdown once the menu updates
+        commands.spawn((
+            Text2d::new(kind.label()),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(
+                0.0,
+                -GAME_HEIGHT / 2.0 + GROUND_HEIGHT + 120.0 - index as f32 * 40.0,
+                2.0,
+            ),
+            HitArea {
+                size: Vec2::new(300.0, 36.0),
+            },
+            WorldButton::default(),
+            Focusable::new(index as u32 + 2),
+            MenuButton,
+            ChallengeButton(kind),
+        ));
+    }
+    commands.spawn((
+        Text2d::new("Settings"),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, -GAME_HEIGHT / 2.0 + GROUND_HEIGHT + 40.0, 2.0),
+        HitArea {
+            size: Vec2::new(140.0, 36.0),
+        },
+        WorldButton::default(),
+        Focusable::new(ChallengeKind::ALL.len() as u32 + 2),
+        MenuButton,
+        SettingsButton,
     ));
 }
 
+/// Returns a full-screen node centering its content `offset` units below
+/// the middle of the screen.
+fn centered_panel(offset: f32) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        padding: UiRect::top(Val::Px(offset * 2.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+/// Spawns the copies of `source` drawing its outline, behind it under the
+/// same parent, which sizes itself to `source` (see `sync_text_outlines`).
+fn spawn_outline_glyphs(parent: &mut ChildSpawnerCommands, source: Entity) {
+    for direction in 0..8 {
+        let offset = Vec2::from_angle(direction as f32 * FRAC_PI_4) * TEXT_OUTLINE_WIDTH;
+        parent.spawn((
+            Text::default(),
+            TextColor(color(TEXT_OUTLINE_COLOR).with_alpha(TEXT_OUTLINE_ALPHA)),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(offset.x),
+                top: Val::Px(offset.y),
+                width: Val::Percent(100.0),
+                ..default()
+            },
+            ZIndex(-1),
+            Visibility::Hidden,
+            OutlineGlyph { source },
+        ));
+    }
+}
+
 /// Spawns the screen flash overlay for visual effects.
 fn spawn_screen_flash(commands: &mut Commands, viewport: &GameViewport) {
     commands.spawn((
@@ -132,6 +397,20 @@ fn spawn_screen_flash(commands: &mut Commands, viewport: &GameViewport) {
     ));
 }
 
+/// Spawns the (transparent) state transition overlay above everything else.
+fn spawn_transition_overlay(commands: &mut Commands, viewport: &GameViewport) {
+    commands.spawn((
+        Sprite {
+            color: Color::srgba(0.0, 0.0, 0.0, 0.0),
+            custom_size: Some(Vec2::new(viewport.width * 2.0, viewport.height * 2.0)),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 20.0),
+        TransitionOverlay,
+        FillScreen,
+    ));
+}
+
 /// Spawns edge flash overlays for score effect (4 edges with gradient fade).
 fn spawn_edge_flashes(commands: &mut Commands, viewport: &GameViewport) {
     let total_border = SCORE_FLASH_BORDER_WIDTH;
@@ -167,6 +446,7 @@ fn spawn_edge_flashes(commands: &mut Commands, viewport: &GameViewport) {
                 edge: EdgeType::Top,
                 strip_index: i,
             },
+            Emissive::new(SCORE_FLASH_GLOW),
         ));
 
         // Bottom edge strips
@@ -182,6 +462,7 @@ fn spawn_edge_flashes(commands: &mut Commands, viewport: &GameViewport) {
                 edge: EdgeType::Bottom,
                 strip_index: i,
             },
+            Emissive::new(SCORE_FLASH_GLOW),
         ));
 
         // Left edge strips
@@ -197,6 +478,7 @@ fn spawn_edge_flashes(commands: &mut Commands, viewport: &GameViewport) {
                 edge: EdgeType::Left,
                 strip_index: i,
             },
+            Emissive::new(SCORE_FLASH_GLOW),
         ));
 
         // Right edge strips
@@ -212,6 +494,7 @@ fn spawn_edge_flashes(commands: &mut Commands, viewport: &GameViewport) {
                 edge: EdgeType::Right,
                 strip_index: i,
             },
+            Emissive::new(SCORE_FLASH_GLOW),
         ));
     }
 }
@@ -0,0 +1,64 @@
+//! Sound effect systems.
+//!
+//! This module synthesizes the sound effects at startup and plays every
+//! `PlaySfx` message at the effects volume from the settings. Keyboard and
+//! gamepad navigation of the menus is voiced here as well: moving the focus
+//! ticks, confirming clicks, and going back plays the back sound. Buttons
+//! voice their own hovers and clicks (see `buttons`).
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::input::{MenuAction, MenuInput};
+use crate::music::MusicLoop;
+use crate::resources::{PlaySfx, SoundEffects};
+use crate::settings::Settings;
+use crate::sfx::SoundEffect;
+
+/// Synthesizes every sound effect.
+pub fn load_sound_effects(mut commands: Commands, mut loops: ResMut<Assets<MusicLoop>>) {
+    let sounds = SoundEffect::ALL
+        .into_iter()
+        .map(|effect| (effect, loops.add(effect.synthesize())))
+        .collect();
+    commands.insert_resource(SoundEffects(sounds));
+}
+
+/// Plays the requested sound effects, unless the effects channel is muted.
+pub fn play_sound_effects(
+    mut commands: Commands,
+    mut requests: MessageReader<PlaySfx>,
+    effects: Option<Res<SoundEffects>>,
+    settings: Res<Settings>,
+) {
+    let volume = settings.sfx_volume.factor();
+    for PlaySfx(effect) in requests.read() {
+        if volume <= 0.0 {
+            continue;
+        }
+        let Some(sound) = effects.as_ref().and_then(|effects| effects.get(*effect)) else {
+            continue;
+        };
+        commands.spawn((
+            AudioPlayer(sound),
+            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(volume)),
+        ));
+    }
+}
+
+/// Voices keyboard and gamepad navigation of the menus.
+pub fn play_menu_sounds(menu_input: MenuInput, mut sfx: MessageWriter<PlaySfx>) {
+    let effect = if menu_input.just_pressed(MenuAction::Back) {
+        SoundEffect::Back
+    } else if menu_input.just_pressed(MenuAction::Confirm)
+        || menu_input.just_pressed(MenuAction::Left)
+        || menu_input.just_pressed(MenuAction::Right)
+    {
+        SoundEffect::Click
+    } else if menu_input.vertical_step() != 0 {
+        SoundEffect::Hover
+    } else {
+        return;
+    };
+    sfx.write(PlaySfx(effect));
+}
@@ -0,0 +1,110 @@
+//! Speedrun timer systems.
+//!
+//! This module records the flight time of every pipe passed, keeps the run
+//! with the best splits, and shows the timer with the last split against the
+//! best run while the speedrun timer is enabled in the settings.
+
+use bevy::prelude::*;
+
+use crate::components::SplitTimerText;
+use crate::config::Difficulty;
+use crate::replay::ReplayRecorder;
+use crate::resources::{DeathEvent, RunStats, ScoreEvent};
+use crate::save::ScoreCategory;
+use crate::settings::Settings;
+use crate::splits::{BestSplits, RunSplits, format_split_delta, format_split_time};
+use crate::states::GameState;
+
+/// Color of the timer while ahead of the best run.
+const AHEAD_COLOR: Color = Color::srgb(0.4, 0.95, 0.45);
+
+/// Color of the timer while behind the best run.
+const BEHIND_COLOR: Color = Color::srgb(1.0, 0.45, 0.4);
+
+/// Clears the splits at the start of a new run.
+pub fn reset_splits(mut splits: ResMut<RunSplits>) {
+    splits.times.clear();
+}
+
+/// Records the flight time when each pipe is passed.
+pub fn record_splits(
+    stats: Res<RunStats>,
+    mut splits: ResMut<RunSplits>,
+    mut score_events: MessageReader<ScoreEvent>,
+) {
+    for _ in score_events.read() {
+        splits.times.push(stats.duration);
+    }
+}
+
+/// Keeps the splits of a finished run if they beat the best run.
+///
+/// Like replays, rewound, revived and cheated runs don't count, and flicker runs, runs
+/// with mutators and runs under a `--difficulty` preset are ranked on their
+/// own.
+pub fn finish_splits(
+    recorder: Res<ReplayRecorder>,
+    settings: Res<Settings>,
+    splits: Res<RunSplits>,
+    mut best: ResMut<BestSplits>,
+    mut death_events: MessageReader<DeathEvent>,
+) {
+    for _ in death_events.read() {
+        if recorder.resumed
+            || recorder.cheated
+            || settings.score_category() != ScoreCategory::Normal
+            || !recorder.replay.mutators.is_empty()
+            || recorder.replay.difficulty != Difficulty::Normal
+        {
+            continue;
+        }
+        if best.is_beaten_by(&splits.times) {
+            info!("New best splits ({} pipes)", splits.times.len());
+            best.times.clone_from(&splits.times);
+            best.store();
+        }
+    }
+}
+
+/// Shows the run time and the last split against the best run while
+/// playing and on the game over screen.
+pub fn update_split_timer(
+    settings: Res<Settings>,
+    state: Res<State<GameState>>,
+    stats: Res<RunStats>,
+    splits: Res<RunSplits>,
+    best: Res<BestSplits>,
+    mut query: Query<(&mut Text, &mut TextColor, &mut Visibility), With<SplitTimerText>>,
+) {
+    let shown =
+        settings.speedrun_timer && matches!(state.get(), GameState::Playing | GameState::GameOver);
+
+    for (mut text, mut color, mut visibility) in query.iter_mut() {
+        if !shown {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+
+        let delta = splits
+            .times
+            .last()
+            .and_then(|time| best.delta(splits.times.len() - 1, *time));
+        let label = match delta {
+            Some(delta) => format!(
+                "{}\n{}",
+                format_split_time(stats.duration),
+                format_split_delta(delta)
+            ),
+            None => format_split_time(stats.duration),
+        };
+        if text.0 != label {
+            text.0 = label;
+        }
+        color.set_if_neq(TextColor(match delta {
+            Some(delta) if delta < 0.0 => AHEAD_COLOR,
+            Some(delta) if delta > 0.0 => BEHIND_COLOR,
+            _ => Color::WHITE,
+        }));
+    }
+}
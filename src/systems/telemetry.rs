@@ -0,0 +1,62 @@
+//! Telemetry recording systems.
+//!
+//! This module forwards gameplay events to the opt-in `Telemetry` resource.
+//! All systems here only run when telemetry is enabled.
+
+use bevy::prelude::*;
+
+use crate::resources::{DeathEvent, Score, ScoreEvent};
+use crate::telemetry::{Telemetry, TelemetryEvent};
+
+/// Enables telemetry if the player opted in via the environment.
+pub fn init_telemetry(mut commands: Commands) {
+    if let Some(telemetry) = Telemetry::from_env() {
+        info!("Telemetry enabled");
+        commands.insert_resource(telemetry);
+    }
+}
+
+/// Records the start of a new run.
+pub fn record_run_started(time: Res<Time>, mut telemetry: ResMut<Telemetry>) {
+    telemetry.run += 1;
+    telemetry.run_started_at = time.elapsed_secs();
+    telemetry.record(&TelemetryEvent::RunStarted { run: telemetry.run });
+}
+
+/// Records every point scored.
+pub fn record_scores(
+    time: Res<Time>,
+    telemetry: Res<Telemetry>,
+    mut score_events: MessageReader<ScoreEvent>,
+) {
+    for event in score_events.read() {
+        telemetry.record(&TelemetryEvent::Scored {
+            run: telemetry.run,
+            score: event.score,
+            gap_size: event.gap_size,
+            run_secs: time.elapsed_secs() - telemetry.run_started_at,
+        });
+    }
+}
+
+/// Records the end of a run with its final score and death cause.
+pub fn record_deaths(
+    time: Res<Time>,
+    telemetry: Res<Telemetry>,
+    score: Res<Score>,
+    mut death_events: MessageReader<DeathEvent>,
+) {
+    for event in death_events.read() {
+        telemetry.record(&TelemetryEvent::Died {
+            run: telemetry.run,
+            score: score.0,
+            cause: event.cause.label(),
+            run_secs: time.elapsed_secs() - telemetry.run_started_at,
+        });
+    }
+}
+
+/// Keeps the session clock current so the final session length is accurate.
+pub fn track_session_time(time: Res<Time>, mut telemetry: ResMut<Telemetry>) {
+    telemetry.last_seen_at = time.elapsed_secs();
+}
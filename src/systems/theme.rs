@@ -0,0 +1,95 @@
+//! Theme systems.
+//!
+//! This module loads the theme files, keeps the `Theme` resource in sync with
+//! the selected theme, shifts its hues in rainbow mode, and recolors the
+//! world when it changes.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, Ground, Obstacle, Pipe, PipeTier, Sky};
+use crate::constants::RAINBOW_HUE_SPEED;
+use crate::settings::Settings;
+use crate::systems::pipes::apply_pipe_theme;
+use crate::theme::{BaseTheme, THEMES_FOLDER, Theme, ThemeLibrary, color, seasonal_theme, today};
+
+/// Starts loading every theme in the themes folder.
+pub fn load_themes(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ThemeLibrary(asset_server.load_folder(THEMES_FOLDER)));
+}
+
+/// Copies the selected theme into the `Theme` resource, or the seasonal
+/// theme active today unless seasonal themes are turned off.
+///
+/// Runs when the settings change or a theme file is (re)loaded.
+pub fn apply_theme(
+    settings: Res<Settings>,
+    mut theme_events: MessageReader<AssetEvent<Theme>>,
+    themes: Res<Assets<Theme>>,
+    mut base: ResMut<BaseTheme>,
+    mut theme: ResMut<Theme>,
+) {
+    let themes_changed = theme_events.read().count() > 0;
+    if !themes_changed && !settings.is_changed() {
+        return;
+    }
+
+    let seasonal = settings
+        .seasonal_themes
+        .then(|| seasonal_theme(&themes, today()))
+        .flatten();
+    let selected = seasonal
+        .or_else(|| {
+            themes
+                .iter()
+                .map(|(_, theme)| theme)
+                .find(|theme| theme.name == settings.theme)
+        })
+        .cloned()
+        .unwrap_or_default();
+    base.0.clone_from(&selected);
+    theme.set_if_neq(selected);
+}
+
+/// Cycles the hues of the pipes and effects while the rainbow mode is on.
+///
+/// The game over screen holds the colors, so the crashed pipe keeps its tint.
+pub fn shift_theme_hues(time: Res<Time>, base: Res<BaseTheme>, mut theme: ResMut<Theme>) {
+    *theme = base.0.rainbow(time.elapsed_secs() * RAINBOW_HUE_SPEED);
+}
+
+/// Recolors the sky, ground, bird, pipes, and obstacles with the current theme.
+#[allow(clippy::type_complexity)]
+pub fn recolor_themed_entities(
+    theme: Res<Theme>,
+    mut query: Query<
+        (
+            &mut Sprite,
+            Has<Sky>,
+            Has<Ground>,
+            Has<Pipe>,
+            Has<Obstacle>,
+            Option<&PipeTier>,
+        ),
+        Or<(
+            With<Sky>,
+            With<Ground>,
+            With<Bird>,
+            With<Pipe>,
+            With<Obstacle>,
+        )>,
+    >,
+) {
+    for (mut sprite, is_sky, is_ground, is_pipe, is_obstacle, tier) in query.iter_mut() {
+        if is_obstacle {
+            sprite.color = color(theme.obstacle_color);
+        } else if is_pipe {
+            apply_pipe_theme(&mut sprite, &theme, tier.map_or(0, |tier| tier.0));
+        } else if is_sky {
+            sprite.color = color(theme.sky_color);
+        } else if is_ground {
+            sprite.color = color(theme.ground_color);
+        } else {
+            sprite.color = color(theme.bird_color);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Bird trail systems.
+//!
+//! This module loads the trail styles and, while a run is under way, spawns
+//! the equipped trail's particles behind the bird. The particles then move
+//! with the other particles in `update_particles`, plus their style's gravity.
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, Particle, TrailParticle};
+use crate::config::GameConfig;
+use crate::resources::GraphicsSettings;
+use crate::rng::GameRng;
+use crate::save::SaveData;
+use crate::theme::color;
+use crate::trails::{TRAILS_FOLDER, TrailEmitter, TrailLibrary, TrailStyle, find_trail};
+
+/// Starts loading every trail in the trails folder.
+pub fn load_trails(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TrailLibrary(asset_server.load_folder(TRAILS_FOLDER)));
+}
+
+/// Spawns the equipped trail's particles behind the bird, or the
+/// configuration's trail when none is equipped.
+#[allow(clippy::too_many_arguments)]
+pub fn emit_trail(
+    mut commands: Commands,
+    time: Res<Time>,
+    save: Res<SaveData>,
+    styles: Res<Assets<TrailStyle>>,
+    config: Res<GameConfig>,
+    graphics: Res<GraphicsSettings>,
+    mut emitter: ResMut<TrailEmitter>,
+    mut game_rng: ResMut<GameRng>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    bird_query: Query<&Transform, With<Bird>>,
+) {
+    let Some(style) = save
+        .equipped_trail
+        .as_deref()
+        .or(config.effects.bird_trail.as_deref())
+        .and_then(|name| find_trail(&styles, name))
+    else {
+        return;
+    };
+    let count = emitter.take(style.rate * graphics.particle_scale, time.delta_secs());
+    if count == 0 {
+        return;
+    }
+
+    let rng = &mut game_rng.particles;
+    let lifetime = graphics.lifetime(style.lifetime);
+    for bird_transform in bird_query.iter() {
+        for _ in 0..count {
+            let Some(&(r, g, b)) = rng.choose(&style.colors) else {
+                return;
+            };
+            let size = rng.range(style.size.0, style.size.1);
+            let velocity = style.velocity(rng.f32(), rng.f32());
+            // Spread the particles over the frame's flight, behind the bird
+            let offset = Vec3::new(-rng.f32() * 6.0, (rng.f32() - 0.5) * 6.0, -0.1);
+
+            commands.spawn((
+                Mesh2d(meshes.add(Circle::new(size / 2.0))),
+                MeshMaterial2d(materials.add(ColorMaterial::from_color(
+                    color((r, g, b)).with_alpha(style.alpha),
+                ))),
+                Transform::from_translation(bird_transform.translation + offset)
+                    .with_scale(Vec3::ZERO),
+                Particle {
+                    velocity,
+                    world_velocity: Vec2::new(-config.pipes.scroll_speed, 0.0),
+                    lifetime: lifetime * (0.8 + rng.f32() * 0.2),
+                    initial_lifetime: lifetime,
+                },
+                TrailParticle {
+                    gravity: style.gravity,
+                },
+            ));
+        }
+    }
+}
+
+/// Pulls trail particles down (or floats them up) by their style's gravity.
+pub fn apply_trail_gravity(time: Res<Time>, mut query: Query<(&TrailParticle, &mut Particle)>) {
+    let dt = time.delta_secs();
+    for (trail, mut particle) in query.iter_mut() {
+        particle.velocity.y -= trail.gravity * dt;
+    }
+}
@@ -0,0 +1,44 @@
+//! State transition systems.
+//!
+//! Starting a run, restarting, and crashing play a short fade on a
+//! full-screen overlay with a whoosh, so state changes don't cut abruptly.
+
+use bevy::prelude::*;
+
+use crate::components::TransitionOverlay;
+use crate::constants::{GAME_OVER_TRANSITION_ALPHA, TRANSITION_DURATION};
+use crate::resources::{PlaySfx, Transition};
+use crate::sfx::SoundEffect;
+use crate::states::GameState;
+
+/// Starts a fade when entering `Playing` (from the menu or a restart) or
+/// `GameOver`.
+pub fn start_transition(
+    state: Res<State<GameState>>,
+    mut transition: ResMut<Transition>,
+    mut sfx: MessageWriter<PlaySfx>,
+) {
+    let alpha = match state.get() {
+        GameState::Playing => 1.0,
+        GameState::GameOver => GAME_OVER_TRANSITION_ALPHA,
+        _ => return,
+    };
+    transition.start(TRANSITION_DURATION, alpha);
+    sfx.write(PlaySfx(SoundEffect::Whoosh));
+}
+
+/// Animates the transition overlay.
+pub fn update_transition(
+    time: Res<Time>,
+    mut transition: ResMut<Transition>,
+    mut overlay_query: Query<&mut Sprite, With<TransitionOverlay>>,
+) {
+    transition.duration = (transition.duration - time.delta_secs()).max(0.0);
+    let alpha = transition.current_alpha();
+
+    for mut sprite in overlay_query.iter_mut() {
+        if sprite.color.alpha() != alpha {
+            sprite.color.set_alpha(alpha);
+        }
+    }
+}
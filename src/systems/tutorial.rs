@@ -0,0 +1,157 @@
+//! First-run tutorial systems.
+//!
+//! On a fresh save, runs start with a short guided sequence instead of the
+//! regular pipes: the player first flaps a few times under slowed gravity,
+//! with the bird kept away from the ground and the ceiling, then flies
+//! through a single wide practice gap. Passing it finishes the tutorial and
+//! hands off to the normal game; dying repeats it on the next run.
+
+use bevy::prelude::*;
+
+use crate::bench::Bench;
+use crate::components::{Bird, TutorialText};
+use crate::config::GameConfig;
+use crate::constants::{TUTORIAL_BAND, TUTORIAL_FLAPS, TUTORIAL_GAP_SIZE};
+use crate::patterns::{GapSpec, PipeColumn};
+use crate::race::RaceSession;
+use crate::replay::ReplayRecorder;
+use crate::resources::{GameViewport, RunStats, Score, ScoreEvent};
+use crate::rng::GameRng;
+use crate::save::{SaveData, is_read_only};
+use crate::states::{PlayPhase, TutorialStep};
+use crate::systems::pipes::spawn_pipe_column;
+use crate::theme::Theme;
+
+/// Starts the tutorial if the player never finished it.
+///
+/// Races, benchmarks and read-only runs always skip it. Tutorial runs are
+/// not kept as replays, as their pipes are scripted.
+pub fn start_tutorial(
+    save: Res<SaveData>,
+    race: Option<Res<RaceSession>>,
+    bench: Option<Res<Bench>>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+) {
+    if !save.needs_tutorial() || race.is_some() || bench.is_some() || is_read_only() {
+        return;
+    }
+    info!("Starting the tutorial");
+    recorder.resumed = true;
+    next_phase.set(PlayPhase::Tutorial);
+}
+
+/// Shows the flap prompt.
+pub fn spawn_flap_prompt(mut commands: Commands) {
+    let prompt = if cfg!(target_os = "ios") {
+        "Tap to flap"
+    } else {
+        "Click or press SPACE to flap"
+    };
+    spawn_tutorial_text(&mut commands, prompt);
+}
+
+/// Keeps the bird within the tutorial band, and moves on to the practice gap
+/// after enough flaps.
+pub fn guide_tutorial_flaps(
+    stats: Res<RunStats>,
+    mut query: Query<(&mut Bird, &mut Transform)>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    for (mut bird, mut transform) in query.iter_mut() {
+        if let Some(y) = clamp_to_band(transform.translation.y) {
+            transform.translation.y = y;
+            bird.velocity = 0.0;
+        }
+    }
+    if stats.flaps >= TUTORIAL_FLAPS {
+        next_step.set(TutorialStep::PracticeGap);
+    }
+}
+
+/// Shows the practice prompt and spawns the wide practice gap, in the middle
+/// of the screen.
+pub fn spawn_practice_gap(
+    mut commands: Commands,
+    score: Res<Score>,
+    viewport: Res<GameViewport>,
+    config: Res<GameConfig>,
+    theme: Res<Theme>,
+    mut rng: ResMut<GameRng>,
+) {
+    spawn_tutorial_text(&mut commands, "Fly through the gap");
+    let column = PipeColumn {
+        gaps: vec![GapSpec {
+            y: Some(0.0),
+            size: Some(TUTORIAL_GAP_SIZE),
+        }],
+        spacing: 1.0,
+    };
+    spawn_pipe_column(
+        &mut commands,
+        &column,
+        score.0,
+        &viewport,
+        &config,
+        &theme,
+        None,
+        None,
+        &mut rng.pipes,
+    );
+}
+
+/// Finishes the tutorial once the practice gap is passed, handing off to the
+/// normal game.
+pub fn finish_tutorial(
+    mut score_events: MessageReader<ScoreEvent>,
+    mut save: ResMut<SaveData>,
+    mut next_phase: ResMut<NextState<PlayPhase>>,
+) {
+    if score_events.read().count() == 0 {
+        return;
+    }
+    info!("Tutorial finished");
+    save.tutorial_done = true;
+    save.store();
+    next_phase.set(PlayPhase::Normal);
+}
+
+/// Removes the tutorial prompt.
+pub fn despawn_tutorial_text(mut commands: Commands, query: Query<Entity, With<TutorialText>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Spawns a tutorial prompt above the tutorial band.
+fn spawn_tutorial_text(commands: &mut Commands, prompt: &str) {
+    commands.spawn((
+        Text2d::new(prompt),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Transform::from_xyz(0.0, TUTORIAL_BAND + 60.0, 5.0),
+        TutorialText,
+    ));
+}
+
+/// Returns the height to move the bird back to if it left the tutorial band.
+fn clamp_to_band(y: f32) -> Option<f32> {
+    let clamped = y.clamp(-TUTORIAL_BAND, TUTORIAL_BAND);
+    (clamped != y).then_some(clamped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bird_is_kept_within_the_band() {
+        assert_eq!(clamp_to_band(0.0), None);
+        assert_eq!(clamp_to_band(TUTORIAL_BAND), None);
+        assert_eq!(clamp_to_band(TUTORIAL_BAND + 10.0), Some(TUTORIAL_BAND));
+        assert_eq!(clamp_to_band(-500.0), Some(-TUTORIAL_BAND));
+    }
+}
@@ -0,0 +1,144 @@
+//! Replay verification systems.
+//!
+//! While the `ReplayVerifier` resource exists, the game plays a recorded run
+//! on its own. Once the configuration and pipe patterns have loaded, it
+//! starts a run with the replay's seed, advances the game clock by each
+//! recorded frame time, and presses the flap key on the recorded frames. When
+//! the bird crashes, or the trace runs out first, the outcome is recorded and
+//! the app exits; when playing a run back, the game goes on at its own pace
+//! instead.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::bindings::InputBindings;
+use crate::config::GameConfigHandle;
+use crate::constants::VERIFY_LOAD_TIMEOUT;
+use crate::patterns::PatternLibrary;
+use crate::resources::{DeathEvent, PendingSeed, Score};
+use crate::states::{GameState, PauseState};
+use crate::verify::{ReplayVerifier, VerifyError};
+
+/// Starts the run once the assets it depends on are loaded, giving up if
+/// they never are.
+pub fn prepare_verification(
+    time: Res<Time<Real>>,
+    asset_server: Res<AssetServer>,
+    patterns: Option<Res<PatternLibrary>>,
+    config: Option<Res<GameConfigHandle>>,
+    mut verifier: ResMut<ReplayVerifier>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    if verifier.ready {
+        return;
+    }
+    let loaded = patterns
+        .is_some_and(|patterns| asset_server.is_loaded_with_dependencies(&patterns.0))
+        && config.is_some_and(|config| asset_server.is_loaded_with_dependencies(&config.0));
+
+    if loaded {
+        // The run starts next frame, with the first recorded frame time
+        verifier.ready = true;
+        *strategy = TimeUpdateStrategy::ManualDuration(frame_duration(&verifier, 0));
+    } else if time.elapsed_secs() > VERIFY_LOAD_TIMEOUT {
+        verifier.conclude(Err(VerifyError::Unavailable(
+            "the game assets didn't load".to_string(),
+        )));
+        exit.write(AppExit::error());
+    }
+}
+
+/// Starts the run and presses the flap key on the recorded frames.
+pub fn drive_verification_input(
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    bindings: Res<InputBindings>,
+    mut verifier: ResMut<ReplayVerifier>,
+    mut pending_seed: ResMut<PendingSeed>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let flap_key = bindings.flap.key.0;
+    keyboard_input.release(flap_key);
+    if !verifier.ready || verifier.finished {
+        return;
+    }
+
+    if !verifier.started {
+        verifier.started = true;
+        pending_seed.0 = Some(verifier.replay.seed);
+        next_state.set(GameState::Playing);
+    }
+    if verifier.take_flap() {
+        keyboard_input.press(flap_key);
+    }
+}
+
+/// Judges the run when the bird crashes, otherwise moves on to the next
+/// recorded frame.
+#[allow(clippy::too_many_arguments)]
+pub fn step_verification(
+    state: Res<State<GameState>>,
+    pause: Option<Res<State<PauseState>>>,
+    score: Res<Score>,
+    mut death_events: MessageReader<DeathEvent>,
+    mut verifier: ResMut<ReplayVerifier>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    if !verifier.started || verifier.finished {
+        return;
+    }
+
+    let verdict = if death_events.read().next().is_some() {
+        verifier.judge(score.0)
+    } else {
+        // Like the recording, only frames of the running game count
+        if *state.get() == GameState::Playing
+            && pause.is_some_and(|pause| *pause.get() == PauseState::Running)
+        {
+            verifier.frame += 1;
+        }
+        if verifier.frame < verifier.replay.frames.len() {
+            *strategy =
+                TimeUpdateStrategy::ManualDuration(frame_duration(&verifier, verifier.frame));
+            return;
+        }
+        Err(VerifyError::NoCrash)
+    };
+
+    verifier.finished = true;
+    if verifier.playback {
+        match &verdict {
+            Ok(score) => info!("Played back the run: {score} points"),
+            Err(error) => warn!("The playback went differently from the recording: {error}"),
+        }
+        *strategy = TimeUpdateStrategy::Automatic;
+        return;
+    }
+
+    match &verdict {
+        Ok(score) => info!("Verified the replay: {score} points"),
+        Err(error) => info!("Rejected the replay: {error}"),
+    }
+    exit.write(if verdict.is_ok() {
+        AppExit::Success
+    } else {
+        AppExit::error()
+    });
+    verifier.conclude(verdict);
+}
+
+/// Returns the recorded game time of a frame.
+fn frame_duration(verifier: &ReplayVerifier, frame: usize) -> Duration {
+    Duration::from_nanos(
+        verifier
+            .replay
+            .frames
+            .get(frame)
+            .copied()
+            .unwrap_or_default()
+            .into(),
+    )
+}
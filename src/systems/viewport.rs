@@ -1,14 +1,28 @@
 //! Viewport and camera systems for responsive scaling.
 //!
 //! This module handles dynamic viewport sizing to support different screen sizes.
+//! The UI scale follows the window height (and the player's UI scale setting),
+//! so HUD nodes laid out in game units keep their size relative to the world.
+//! World text is rendered at the window's resolution rather than stretched by
+//! the camera, so it stays crisp on high-resolution and HiDPI displays. The
+//! text size setting enlarges world and HUD text alike, and text it makes
+//! too wide for the screen wraps onto more lines.
 
+use bevy::camera::visibility::RenderLayers;
 use bevy::camera::{Projection, ScalingMode};
 use bevy::prelude::*;
-use bevy::window::{PrimaryWindow, WindowResized};
+use bevy::text::{Justify, LineBreak, TextBounds, TextLayoutInfo};
+use bevy::window::{PrimaryWindow, WindowResized, WindowScaleFactorChanged};
 
-use crate::components::{EdgeFlash, EdgeType, FillScreen, Ground, MainCamera, Sky};
-use crate::constants::{GROUND_HEIGHT, SCORE_FLASH_BORDER_WIDTH, SCORE_FLASH_GRADIENT_STRIPS};
+use crate::components::{
+    EdgeFlash, EdgeType, FillScreen, Ground, HudTextSize, MainCamera, OutlineGlyph, Sky,
+    WorldTextSize, WrappedWorldText,
+};
+use crate::constants::{
+    GROUND_HEIGHT, SCORE_FLASH_BORDER_WIDTH, SCORE_FLASH_GRADIENT_STRIPS, WORLD_TEXT_MAX_WIDTH,
+};
 use crate::resources::GameViewport;
+use crate::settings::Settings;
 
 /// System to update viewport and camera projection on window resize.
 pub fn update_viewport(
@@ -17,6 +31,12 @@ pub fn update_viewport(
     mut camera_query: Query<&mut Projection, With<MainCamera>>,
 ) {
     for event in resize_events.read() {
+        let _span = info_span!(
+            "resize_viewport",
+            width = event.width,
+            height = event.height
+        )
+        .entered();
         viewport.update_from_window(event.width, event.height);
 
         // Update camera projection to match new viewport
@@ -52,6 +72,166 @@ pub fn initial_viewport_setup(
     }
 }
 
+/// Scales the UI with the window height and the UI scale setting.
+///
+/// Runs on resizes, on scale factor changes (moving the window to a display
+/// with a different density changes its logical size), and when the setting
+/// changes.
+pub fn update_ui_scale(
+    mut resize_events: MessageReader<WindowResized>,
+    mut scale_factor_events: MessageReader<WindowScaleFactorChanged>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    settings: Res<Settings>,
+    viewport: Res<GameViewport>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let window_changed = resize_events.read().count() + scale_factor_events.read().count() > 0;
+    if !window_changed && !settings.is_changed() && !viewport.is_changed() {
+        return;
+    }
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    if window.height() > 0.0 {
+        let scale = window.height() / viewport.height * settings.ui_scale.factor();
+        if ui_scale.0 != scale {
+            ui_scale.0 = scale;
+        }
+    }
+}
+
+/// Renders world text at the size it appears on screen.
+///
+/// The camera stretches the world to the window, so text laid out at its
+/// nominal size would be magnified and blurry on large windows. Instead the
+/// font grows by the camera's magnification and the text is scaled back
+/// down by the same amount. The text size setting then scales the font. Text
+/// on other render layers (the crash screen) isn't seen through the main
+/// camera and is left alone.
+#[allow(clippy::type_complexity)]
+pub fn sharpen_world_text(
+    mut commands: Commands,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    viewport: Res<GameViewport>,
+    settings: Res<Settings>,
+    mut text_query: Query<
+        (
+            Entity,
+            &mut TextFont,
+            &mut Transform,
+            Option<&WorldTextSize>,
+        ),
+        (With<Text2d>, Without<RenderLayers>),
+    >,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    if window.height() <= 0.0 {
+        return;
+    }
+    let magnification = window.height() / viewport.height;
+
+    for (entity, mut font, mut transform, size) in text_query.iter_mut() {
+        let base = match size {
+            Some(size) => size.0,
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(WorldTextSize(font.font_size));
+                font.font_size
+            }
+        };
+        let font_size = base * magnification * settings.text_scale.factor();
+        if font.font_size != font_size {
+            font.font_size = font_size;
+        }
+        let scale = Vec3::new(1.0 / magnification, 1.0 / magnification, 1.0);
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+    }
+}
+
+/// Wraps world text that grew wider than the screen onto more lines.
+///
+/// Runs on the previous frame's layout: once a text is too wide, it gets
+/// bounds of the screen width (which follow later resizes) and is centered,
+/// like the menus it belongs to.
+#[allow(clippy::type_complexity)]
+pub fn wrap_world_text(
+    mut commands: Commands,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    viewport: Res<GameViewport>,
+    mut text_query: Query<
+        (
+            Entity,
+            &TextLayoutInfo,
+            &mut TextLayout,
+            &mut TextBounds,
+            Has<WrappedWorldText>,
+        ),
+        (With<Text2d>, Without<RenderLayers>),
+    >,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+    if window.height() <= 0.0 {
+        return;
+    }
+    // Fonts are magnified to the window (see `sharpen_world_text`)
+    let magnification = window.height() / viewport.height;
+    let max_width = viewport.width * WORLD_TEXT_MAX_WIDTH * magnification;
+
+    for (entity, info, mut layout, mut bounds, wrapped) in text_query.iter_mut() {
+        if wrapped {
+            if bounds.width != Some(max_width) {
+                bounds.width = Some(max_width);
+            }
+            continue;
+        }
+        // The layout is in physical pixels
+        if info.size.x / window.scale_factor() > max_width {
+            commands.entity(entity).insert(WrappedWorldText);
+            bounds.width = Some(max_width);
+            *layout = TextLayout::new(Justify::Center, LineBreak::WordBoundary);
+        }
+    }
+}
+
+/// Scales HUD text by the text size setting.
+///
+/// Enlarged text wraps at word boundaries instead of running off the screen.
+/// Outline copies take their size from the text they outline instead.
+#[allow(clippy::type_complexity)]
+pub fn scale_hud_text(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut text_query: Query<
+        (Entity, &mut TextFont, &mut TextLayout, Option<&HudTextSize>),
+        (With<Text>, Without<OutlineGlyph>),
+    >,
+) {
+    let factor = settings.text_scale.factor();
+    for (entity, mut font, mut layout, size) in text_query.iter_mut() {
+        let base = match size {
+            Some(size) => size.0,
+            None => {
+                commands.entity(entity).insert(HudTextSize(font.font_size));
+                if layout.linebreak == LineBreak::NoWrap {
+                    layout.linebreak = LineBreak::WordBoundary;
+                }
+                font.font_size
+            }
+        };
+        let font_size = base * factor;
+        if font.font_size != font_size {
+            font.font_size = font_size;
+        }
+    }
+}
+
 /// System to update entities that should fill the screen width.
 pub fn update_fill_width_entities(
     viewport: Res<GameViewport>,
@@ -84,7 +264,7 @@ pub fn update_fill_screen_entities(
         sprite.custom_size = Some(Vec2::new(viewport.width * 2.0, viewport.height));
     }
 
-    // Update screen flash overlay
+    // Update full-screen overlays (screen flash, transitions)
     for mut sprite in flash_query.iter_mut() {
         sprite.custom_size = Some(Vec2::new(viewport.width * 2.0, viewport.height * 2.0));
     }
@@ -0,0 +1,99 @@
+//! Vignette systems.
+//!
+//! This module keeps the vignette overlay (see `vignette.rs`) framing the
+//! view and pulses it darker on near misses and death.
+
+use bevy::prelude::*;
+
+use crate::components::{CameraRig, MainCamera, VignetteOverlay};
+use crate::config::GameConfig;
+use crate::constants::{VIGNETTE_COLOR, VIGNETTE_Z};
+use crate::resources::{DeathEvent, GameViewport, NearMiss, VignettePulse};
+use crate::theme::color;
+use crate::vignette::{VignetteMaterial, VignetteUniform, vignette_intensity};
+
+/// Spawns the (initially hidden) vignette overlay.
+pub fn spawn_vignette_overlay(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<VignetteMaterial>>,
+) {
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
+        MeshMaterial2d(materials.add(VignetteMaterial::default())),
+        Transform::from_xyz(0.0, 0.0, VIGNETTE_Z),
+        Visibility::Hidden,
+        VignetteOverlay,
+    ));
+}
+
+/// Pulses the vignette darker on near misses, the more so the closer the
+/// shave, and on death.
+pub fn pulse_vignette(
+    mut near_misses: MessageReader<NearMiss>,
+    mut death_events: MessageReader<DeathEvent>,
+    config: Res<GameConfig>,
+    mut pulse: ResMut<VignettePulse>,
+) {
+    let effects = &config.effects;
+    // Closer shaves darken more
+    let closest = near_misses
+        .read()
+        .map(|miss| miss.clearance)
+        .reduce(f32::min);
+    if let Some(clearance) = closest {
+        let closeness = 1.0 - (clearance / effects.near_miss_distance).clamp(0.0, 1.0);
+        pulse.trigger(
+            effects.vignette_pulse_duration,
+            effects.vignette_near_miss * closeness,
+        );
+    }
+    if death_events.read().count() > 0 {
+        pulse.trigger(effects.vignette_pulse_duration, effects.vignette_death);
+    }
+}
+
+/// Fades the pulse and moves the overlay along with the camera rig, so it
+/// covers exactly the view.
+#[allow(clippy::type_complexity)]
+pub fn update_vignette(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    viewport: Res<GameViewport>,
+    mut pulse: ResMut<VignettePulse>,
+    rig_query: Query<&CameraRig, With<MainCamera>>,
+    mut overlay_query: Query<
+        (
+            &MeshMaterial2d<VignetteMaterial>,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        With<VignetteOverlay>,
+    >,
+    mut materials: ResMut<Assets<VignetteMaterial>>,
+) {
+    if pulse.duration > 0.0 {
+        pulse.duration -= time.delta_secs();
+    }
+    let intensity = vignette_intensity(config.effects.vignette_intensity, pulse.current());
+    let rig = rig_query.iter().next().copied().unwrap_or_default();
+
+    for (material, mut transform, mut visibility) in overlay_query.iter_mut() {
+        if intensity <= 0.0 {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+        let position = rig.translation();
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        transform.scale = Vec3::new(viewport.width * rig.scale, viewport.height * rig.scale, 1.0);
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.vignette = VignetteUniform {
+                color: color(VIGNETTE_COLOR).with_alpha(intensity).to_linear(),
+                ..default()
+            };
+        }
+    }
+}
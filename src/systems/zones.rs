@@ -0,0 +1,33 @@
+//! Gravity-flip zone systems.
+//!
+//! Zones occasionally follow a random pipe pair (see `spawn_pipe_column`) and
+//! scroll with the pipes. While the bird is inside one, gravity pulls it up
+//! and flapping pushes it down (or the other way around with inverted
+//! gravity).
+
+use bevy::prelude::*;
+
+use crate::components::{Bird, GravityZone};
+use crate::config::GameConfig;
+
+/// Flips the bird's gravity while it is inside a gravity-flip zone.
+pub fn update_gravity_zones(
+    config: Res<GameConfig>,
+    mut bird_query: Query<(&mut Bird, &Transform)>,
+    zone_query: Query<(&Transform, &Sprite), With<GravityZone>>,
+) {
+    for (mut bird, bird_transform) in bird_query.iter_mut() {
+        let bird_pos = bird_transform.translation.truncate();
+        let inside = zone_query.iter().any(|(transform, sprite)| {
+            let half_size = sprite.custom_size.unwrap_or(Vec2::ZERO) / 2.0;
+            let offset = (bird_pos - transform.translation.truncate()).abs();
+            offset.x <= half_size.x && offset.y <= half_size.y
+        });
+
+        bird.gravity_direction = if inside != config.physics.inverted_gravity {
+            -1.0
+        } else {
+            1.0
+        };
+    }
+}
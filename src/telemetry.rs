@@ -0,0 +1,249 @@
+//! Opt-in gameplay telemetry.
+//!
+//! Records structured gameplay events (run started, score, death cause,
+//! session length) as JSON lines. Telemetry is disabled unless the
+//! `FLAPPY_TELEMETRY` environment variable is set, either to a file path
+//! (events are appended as JSONL) or to an `http://` endpoint (each event is
+//! posted as a JSON body).
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::net;
+use crate::save::now_ms;
+
+/// Environment variable that enables telemetry and selects its destination.
+pub const TELEMETRY_ENV_VAR: &str = "FLAPPY_TELEMETRY";
+
+/// A structured gameplay event.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    /// A new run began.
+    RunStarted {
+        /// Run number within the session (starting at 1)
+        run: u32,
+    },
+    /// The player scored a point.
+    Scored {
+        /// Run number within the session
+        run: u32,
+        /// Score after this point
+        score: u32,
+        /// Height of the gap flown through, in pixels
+        gap_size: f32,
+        /// Seconds since the run started
+        run_secs: f32,
+    },
+    /// The bird died, ending the run.
+    Died {
+        /// Run number within the session
+        run: u32,
+        /// Final score of the run
+        score: u32,
+        /// What the bird hit
+        cause: &'static str,
+        /// Total run length in seconds
+        run_secs: f32,
+    },
+    /// The game is shutting down.
+    SessionEnded {
+        /// Number of runs played this session
+        runs: u32,
+        /// Total session length in seconds
+        session_secs: f32,
+    },
+}
+
+/// A telemetry event stamped with session and wall-clock time.
+#[derive(Serialize)]
+struct TelemetryRecord<'a> {
+    /// Identifier shared by all events of one game session
+    session: u64,
+    /// Milliseconds since the Unix epoch
+    timestamp_ms: u64,
+    #[serde(flatten)]
+    event: &'a TelemetryEvent,
+}
+
+/// Where telemetry records are delivered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelemetrySink {
+    /// Append JSON lines to a local file.
+    File(PathBuf),
+    /// Post each record to an HTTP endpoint.
+    Endpoint(String),
+}
+
+impl TelemetrySink {
+    /// Interprets a target string as an endpoint URL or a file path.
+    ///
+    /// Only `http://` endpoints can be posted to, so `https://` targets are
+    /// refused rather than dropping every event.
+    pub fn from_target(target: &str) -> Result<Self, String> {
+        if target.starts_with("https://") {
+            Err("https is not supported, use an http:// endpoint or a file path".to_string())
+        } else if target.starts_with("http://") {
+            Ok(Self::Endpoint(target.to_string()))
+        } else {
+            Ok(Self::File(PathBuf::from(target)))
+        }
+    }
+
+    /// Delivers one serialized record, logging failures instead of panicking.
+    fn deliver(&self, line: &str) {
+        let result = match self {
+            Self::File(path) => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{line}")),
+            // Error statuses count as failures, not deliveries
+            Self::Endpoint(url) => net::post(url, "application/json", line.as_bytes()),
+        };
+
+        if let Err(error) = result {
+            warn!("Failed to deliver telemetry to {self:?}: {error}");
+        }
+    }
+}
+
+/// Resource that records telemetry events for the current session.
+///
+/// Records are serialized on the game thread and delivered by a background
+/// thread so file and network I/O never stall a frame.
+#[derive(Resource)]
+pub struct Telemetry {
+    /// Identifier for this session
+    session: u64,
+    /// Channel to the delivery thread
+    sender: Option<Sender<String>>,
+    /// Delivery thread handle, joined on drop to flush pending records
+    worker: Option<JoinHandle<()>>,
+    /// Number of runs started this session
+    pub run: u32,
+    /// Elapsed app time when the current run started
+    pub run_started_at: f32,
+    /// Elapsed app time of the most recent update
+    pub last_seen_at: f32,
+}
+
+impl Telemetry {
+    /// Creates telemetry from the environment, or `None` if not opted in.
+    pub fn from_env() -> Option<Self> {
+        net::target_from_env(TELEMETRY_ENV_VAR, "Telemetry", TelemetrySink::from_target)
+            .map(Self::new)
+    }
+
+    /// Creates telemetry delivering to the given sink.
+    pub fn new(sink: TelemetrySink) -> Self {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let worker = thread::spawn(move || {
+            for line in receiver {
+                sink.deliver(&line);
+            }
+        });
+
+        Self {
+            session: now_ms(),
+            sender: Some(sender),
+            worker: Some(worker),
+            run: 0,
+            run_started_at: 0.0,
+            last_seen_at: 0.0,
+        }
+    }
+
+    /// Records a single event.
+    pub fn record(&self, event: &TelemetryEvent) {
+        let record = TelemetryRecord {
+            session: self.session,
+            timestamp_ms: now_ms(),
+            event,
+        };
+
+        match serde_json::to_string(&record) {
+            Ok(line) => {
+                if let Some(sender) = &self.sender {
+                    // The worker only stops once the sender is dropped
+                    let _ = sender.send(line);
+                }
+            }
+            Err(error) => warn!("Failed to serialize telemetry event: {error}"),
+        }
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        self.record(&TelemetryEvent::SessionEnded {
+            runs: self.run,
+            session_secs: self.last_seen_at,
+        });
+
+        // Close the channel and wait for pending records to be delivered
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sink_from_target_detects_endpoints() {
+        assert_eq!(
+            TelemetrySink::from_target("http://localhost:9000/ingest"),
+            Ok(TelemetrySink::Endpoint(
+                "http://localhost:9000/ingest".to_string()
+            ))
+        );
+        assert_eq!(
+            TelemetrySink::from_target("telemetry.jsonl"),
+            Ok(TelemetrySink::File(PathBuf::from("telemetry.jsonl")))
+        );
+    }
+
+    #[test]
+    fn sink_from_target_refuses_https() {
+        assert!(TelemetrySink::from_target("https://telemetry.example.com/ingest").is_err());
+    }
+
+    #[test]
+    fn record_serializes_as_flat_json() {
+        let event = TelemetryEvent::Died {
+            run: 2,
+            score: 7,
+            cause: "pipe",
+            run_secs: 12.5,
+        };
+        let record = TelemetryRecord {
+            session: 42,
+            timestamp_ms: 1000,
+            event: &event,
+        };
+
+        let json = serde_json::to_value(&record).ok();
+        assert_eq!(
+            json,
+            Some(serde_json::json!({
+                "session": 42,
+                "timestamp_ms": 1000,
+                "event": "died",
+                "run": 2,
+                "score": 7,
+                "cause": "pipe",
+                "run_secs": 12.5,
+            }))
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! Visual themes.
+//!
+//! A `Theme` describes the colors (and optional pipe texture) of the world
+//! and its effects. Themes are RON assets in `assets/themes/`; the one named
+//! in `Settings` is copied into the `Theme` resource that systems read. The
+//! defaults are the built-in "Classic" look from `constants.rs`. Dark themes
+//! can also turn on scene lighting (see `lighting.rs`) and fog (see
+//! `fog.rs`).
+//!
+//! Seasonal themes carry the dates they're active on and, unless turned off
+//! in the settings, replace the selected theme on those days. They can also
+//! add decorations to the sky (see `seasonal.rs`).
+//!
+//! The rainbow mode setting shifts the hues of the pipes and effects over
+//! time: the selected theme is kept in `BaseTheme`, and a shifted copy of it
+//! replaces the `Theme` resource every frame.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext, LoadedFolder};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::constants::{
+    BIRD_COLOR, DEATH_FLASH_COLOR, DEATH_PARTICLE_COLORS, FLAP_PARTICLE_COLOR, FOG_THEME_DISTANCE,
+    FOG_THEME_OPACITY, GROUND_COLOR, LIGHTING_BIRD_RADIUS, LIGHTING_DARKNESS,
+    LIGHTING_DARKNESS_COLOR, LIGHTING_GAP_INTENSITY, LIGHTING_GAP_RADIUS, OBSTACLE_COLOR,
+    PIPE_COLOR, PIPE_HIT_COLOR, PIPE_TIER_COLORS, RAINBOW_LIGHTNESS, RAINBOW_MIN_SATURATION,
+    RAINBOW_PARTICLE_HUES, SCORE_FLASH_COLOR, SKY_COLOR,
+};
+
+/// Asset folder themes are loaded from.
+pub const THEMES_FOLDER: &str = "themes";
+
+/// Name of the built-in theme, used when no other theme is selected or found.
+pub const DEFAULT_THEME_NAME: &str = "Classic";
+
+/// An RGB color with components in 0.0..=1.0.
+pub type Rgb = (f32, f32, f32);
+
+/// Colors and textures used to draw the game.
+///
+/// Missing fields fall back to the "Classic" theme.
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Theme {
+    /// Display name, shown in the theme picker
+    pub name: String,
+    /// Sky background
+    pub sky_color: Rgb,
+    /// Ground strip
+    pub ground_color: Rgb,
+    /// Pipe tint (multiplied with the texture, if any)
+    pub pipe_color: Rgb,
+    /// Pipe tints from each score tier on (see `PIPE_TIER_SCORES`); the last
+    /// one is kept for any further tiers
+    pub pipe_tier_colors: Vec<Rgb>,
+    /// Optional pipe texture, relative to the assets folder (tiled vertically)
+    pub pipe_texture: Option<String>,
+    /// Loaded `pipe_texture`
+    #[serde(skip)]
+    #[dependency]
+    pub pipe_image: Option<Handle<Image>>,
+    /// Bird body
+    pub bird_color: Rgb,
+    /// Blades, gates and floating blocks
+    pub obstacle_color: Rgb,
+    /// Flap cloud puffs
+    pub flap_particle_color: Rgb,
+    /// Death burst particles (one is picked at random per particle)
+    pub death_particle_colors: Vec<Rgb>,
+    /// Full-screen flash on death
+    pub death_flash_color: Rgb,
+    /// Edge flash on score
+    pub score_flash_color: Rgb,
+    /// Tint of the pipe the bird crashed into
+    pub pipe_hit_color: Rgb,
+    /// Darkens the scene around a glow on the bird and at pipe gaps, if set
+    pub lighting: Option<ThemeLighting>,
+    /// Fog hiding the screen ahead of the bird, if set
+    pub fog: Option<ThemeFog>,
+    /// Dates the theme replaces the selected one on, if it is seasonal
+    pub season: Option<ThemeSeason>,
+    /// Decorations drifting across the sky, if any
+    pub decoration: Option<Decoration>,
+}
+
+/// Scene lighting of a dark theme.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct ThemeLighting {
+    /// Color of the unlit scene
+    pub darkness_color: Rgb,
+    /// Opacity of the darkness away from any light (0.0 - 1.0)
+    pub darkness: f32,
+    /// Radius of the glow around the bird
+    pub bird_light_radius: f32,
+    /// Radius of the lights at pipe gaps
+    pub gap_light_radius: f32,
+    /// Brightness of the lights at pipe gaps (the bird's glow is 1.0)
+    pub gap_light_intensity: f32,
+}
+
+impl Default for ThemeLighting {
+    fn default() -> Self {
+        Self {
+            darkness_color: LIGHTING_DARKNESS_COLOR,
+            darkness: LIGHTING_DARKNESS,
+            bird_light_radius: LIGHTING_BIRD_RADIUS,
+            gap_light_radius: LIGHTING_GAP_RADIUS,
+            gap_light_intensity: LIGHTING_GAP_INTENSITY,
+        }
+    }
+}
+
+/// Atmospheric fog of a theme.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct ThemeFog {
+    /// Color of the fog
+    pub color: Rgb,
+    /// Distance ahead of the bird the fog starts at
+    pub distance: f32,
+    /// Opacity of the fog once faded in (0.0 - 1.0)
+    pub opacity: f32,
+}
+
+impl Default for ThemeFog {
+    fn default() -> Self {
+        Self {
+            color: SKY_COLOR,
+            distance: FOG_THEME_DISTANCE,
+            opacity: FOG_THEME_OPACITY,
+        }
+    }
+}
+
+/// Dates a seasonal theme is active on, as (month, day) pairs, both
+/// included. A season can wrap around the new year.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThemeSeason {
+    /// First day of the season
+    pub start: (u32, u32),
+    /// Last day of the season
+    pub end: (u32, u32),
+}
+
+impl ThemeSeason {
+    /// Returns whether the given (month, day) falls within the season.
+    pub fn contains(&self, date: (u32, u32)) -> bool {
+        if self.start <= self.end {
+            self.start <= date && date <= self.end
+        } else {
+            date >= self.start || date <= self.end
+        }
+    }
+}
+
+/// Decorations a theme adds to the sky.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decoration {
+    /// Snowflakes falling in front of the pipes
+    Snow,
+    /// Bats flapping across the sky behind the pipes
+    Bats,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_THEME_NAME.to_string(),
+            sky_color: SKY_COLOR,
+            ground_color: GROUND_COLOR,
+            pipe_color: PIPE_COLOR,
+            pipe_tier_colors: PIPE_TIER_COLORS.to_vec(),
+            pipe_texture: None,
+            pipe_image: None,
+            bird_color: BIRD_COLOR,
+            obstacle_color: OBSTACLE_COLOR,
+            flap_particle_color: FLAP_PARTICLE_COLOR,
+            death_particle_colors: DEATH_PARTICLE_COLORS.to_vec(),
+            death_flash_color: DEATH_FLASH_COLOR,
+            score_flash_color: SCORE_FLASH_COLOR,
+            pipe_hit_color: PIPE_HIT_COLOR,
+            lighting: None,
+            fog: None,
+            season: None,
+            decoration: None,
+        }
+    }
+}
+
+impl Theme {
+    /// Returns the pipe tint of a score tier (0 is the base `pipe_color`).
+    pub fn pipe_color_at(&self, tier: usize) -> Rgb {
+        match tier.checked_sub(1) {
+            Some(index) => self
+                .pipe_tier_colors
+                .get(index)
+                .or(self.pipe_tier_colors.last())
+                .copied()
+                .unwrap_or(self.pipe_color),
+            None => self.pipe_color,
+        }
+    }
+
+    /// Returns the theme with the hues of its pipes and effects shifted by
+    /// `hue_shift` degrees, for the rainbow mode.
+    ///
+    /// Dull colors are saturated so they visibly change hue, and the death
+    /// particles spread over the whole rainbow.
+    pub fn rainbow(&self, hue_shift: f32) -> Theme {
+        let death_particle_colors = (0..RAINBOW_PARTICLE_HUES)
+            .map(|index| {
+                let hue = index as f32 * 360.0 / RAINBOW_PARTICLE_HUES as f32;
+                rainbow_color((1.0, 0.0, 0.0), hue_shift + hue)
+            })
+            .collect();
+        Theme {
+            pipe_color: rainbow_color(self.pipe_color, hue_shift),
+            pipe_tier_colors: self
+                .pipe_tier_colors
+                .iter()
+                .map(|tier_color| rainbow_color(*tier_color, hue_shift))
+                .collect(),
+            flap_particle_color: rainbow_color(self.flap_particle_color, hue_shift),
+            death_particle_colors,
+            score_flash_color: rainbow_color(self.score_flash_color, hue_shift),
+            ..self.clone()
+        }
+    }
+}
+
+/// Resource holding the selected theme as loaded, before the rainbow mode
+/// shifts its hues.
+#[derive(Resource, Default)]
+pub struct BaseTheme(pub Theme);
+
+/// Returns a color with its hue rotated by `hue_shift` degrees, saturated
+/// and kept in a bright lightness range.
+fn rainbow_color(rgb: Rgb, hue_shift: f32) -> Rgb {
+    let hsla = Hsla::from(color(rgb));
+    let shifted = Hsla::new(
+        (hsla.hue + hue_shift).rem_euclid(360.0),
+        hsla.saturation.max(RAINBOW_MIN_SATURATION),
+        hsla.lightness
+            .clamp(RAINBOW_LIGHTNESS.0, RAINBOW_LIGHTNESS.1),
+        1.0,
+    );
+    let srgba = Srgba::from(shifted);
+    (srgba.red, srgba.green, srgba.blue)
+}
+
+/// Returns today's (month, day) in UTC.
+pub fn today() -> (u32, u32) {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    month_day((seconds / 86_400) as i64)
+}
+
+/// Converts days since the Unix epoch to a (month, day) of the Gregorian
+/// calendar.
+fn month_day(days: i64) -> (u32, u32) {
+    // Shift to a calendar starting in March so leap days fall at the end
+    let days = days + 719_468;
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    (month as u32, day as u32)
+}
+
+/// Returns the seasonal theme active on the given (month, day), if any.
+pub fn seasonal_theme(themes: &Assets<Theme>, date: (u32, u32)) -> Option<&Theme> {
+    themes
+        .iter()
+        .map(|(_, theme)| theme)
+        .find(|theme| theme.season.is_some_and(|season| season.contains(date)))
+}
+
+/// Converts a theme color to a Bevy color.
+pub fn color((r, g, b): Rgb) -> Color {
+    Color::srgb(r, g, b)
+}
+
+/// Handle keeping every theme in the themes folder loaded.
+#[derive(Resource)]
+pub struct ThemeLibrary(#[allow(dead_code)] pub Handle<LoadedFolder>);
+
+/// Returns the names of all available themes (built-in plus loaded), sorted.
+pub fn theme_names(themes: &Assets<Theme>) -> Vec<String> {
+    let mut names: Vec<String> = themes
+        .iter()
+        .map(|(_, theme)| theme.name.clone())
+        .chain(std::iter::once(DEFAULT_THEME_NAME.to_string()))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Loads `*.theme.ron` files into `Theme` assets.
+#[derive(Default, TypePath)]
+pub struct ThemeLoader;
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut theme: Theme = ron::de::from_bytes(&bytes)?;
+        theme.pipe_image = theme
+            .pipe_texture
+            .clone()
+            .map(|path| load_context.load(path));
+        Ok(theme)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_theme_matches_defaults() {
+        let classic =
+            ron::from_str::<Theme>(include_str!("../assets/themes/classic.theme.ron")).ok();
+        assert_eq!(classic, Some(Theme::default()));
+    }
+
+    #[test]
+    fn shipped_themes_parse() {
+        for source in [
+            include_str!("../assets/themes/night.theme.ron"),
+            include_str!("../assets/themes/sunset.theme.ron"),
+            include_str!("../assets/themes/halloween.theme.ron"),
+            include_str!("../assets/themes/winter.theme.ron"),
+        ] {
+            let theme = ron::from_str::<Theme>(source);
+            assert!(theme.is_ok_and(|theme| theme.name != DEFAULT_THEME_NAME));
+        }
+    }
+
+    #[test]
+    fn rainbow_shifts_pipes_and_effects_only() {
+        let theme = Theme::default();
+        let shifted = theme.rainbow(120.0);
+        assert_ne!(shifted.pipe_color, theme.pipe_color);
+        assert_ne!(shifted.score_flash_color, theme.score_flash_color);
+        assert_eq!(shifted.death_particle_colors.len(), RAINBOW_PARTICLE_HUES);
+        assert_eq!(shifted.sky_color, theme.sky_color);
+        assert_eq!(shifted.bird_color, theme.bird_color);
+
+        // A full turn comes back to the same colors
+        let turned = theme.rainbow(480.0);
+        let close =
+            |a: Rgb, b: Rgb| (a.0 - b.0).abs() + (a.1 - b.1).abs() + (a.2 - b.2).abs() < 1e-4;
+        assert!(close(turned.pipe_color, shifted.pipe_color));
+    }
+
+    #[test]
+    fn pipe_tiers_fall_back_to_the_last_color() {
+        let theme = Theme {
+            pipe_tier_colors: vec![(0.0, 0.0, 1.0), (1.0, 0.0, 1.0)],
+            ..default()
+        };
+        assert_eq!(theme.pipe_color_at(0), theme.pipe_color);
+        assert_eq!(theme.pipe_color_at(1), (0.0, 0.0, 1.0));
+        assert_eq!(theme.pipe_color_at(5), (1.0, 0.0, 1.0));
+
+        let untiered = Theme {
+            pipe_tier_colors: Vec::new(),
+            ..default()
+        };
+        assert_eq!(untiered.pipe_color_at(2), untiered.pipe_color);
+    }
+
+    #[test]
+    fn seasons_include_both_ends_and_wrap_around_the_year() {
+        let october = ThemeSeason {
+            start: (10, 20),
+            end: (10, 31),
+        };
+        assert!(october.contains((10, 20)));
+        assert!(october.contains((10, 31)));
+        assert!(!october.contains((10, 19)));
+        assert!(!october.contains((11, 1)));
+
+        let holidays = ThemeSeason {
+            start: (12, 20),
+            end: (1, 6),
+        };
+        assert!(holidays.contains((12, 31)));
+        assert!(holidays.contains((1, 1)));
+        assert!(!holidays.contains((1, 7)));
+        assert!(!holidays.contains((6, 1)));
+    }
+
+    #[test]
+    fn days_convert_to_calendar_dates() {
+        assert_eq!(month_day(0), (1, 1));
+        // 2024-02-29 and 2025-10-31
+        assert_eq!(month_day(19_782), (2, 29));
+        assert_eq!(month_day(20_392), (10, 31));
+        // 1969-12-31
+        assert_eq!(month_day(-1), (12, 31));
+    }
+
+    #[test]
+    fn theme_names_always_include_default() {
+        let themes = Assets::<Theme>::default();
+        assert_eq!(theme_names(&themes), vec![DEFAULT_THEME_NAME.to_string()]);
+    }
+}
@@ -0,0 +1,214 @@
+//! Bird trails.
+//!
+//! A `TrailStyle` describes a particle emitter that follows the bird while it
+//! flies: how many particles it spawns per second, how long they live, how big
+//! and fast they are, which way they drift and fall, and their colors. Styles
+//! are RON assets in `assets/trails/`, so new trails need no code.
+//!
+//! Trails are bought with coins on the settings screen. Owned trails and the
+//! equipped one are kept in the save file, next to the coins that paid for
+//! them.
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext, LoadedFolder};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::save::SaveData;
+use crate::theme::Rgb;
+
+/// Asset folder trail styles are loaded from.
+pub const TRAILS_FOLDER: &str = "trails";
+
+/// A particle emitter following the bird.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug, PartialEq)]
+pub struct TrailStyle {
+    /// Name shown in the settings and stored in the save file
+    pub name: String,
+    /// Price in coins
+    pub cost: u32,
+    /// Particles spawned per second
+    pub rate: f32,
+    /// Particle lifetime in seconds
+    pub lifetime: f32,
+    /// Particle diameter range in pixels
+    pub size: (f32, f32),
+    /// Initial drift speed range in pixels per second
+    pub speed: (f32, f32),
+    /// Drift direction in degrees (0 = forward, 90 = up)
+    #[serde(default = "default_direction")]
+    pub direction: f32,
+    /// Random spread around `direction`, in degrees
+    #[serde(default)]
+    pub spread: f32,
+    /// Downward acceleration in pixels per second squared (negative floats up)
+    #[serde(default)]
+    pub gravity: f32,
+    /// Particle opacity
+    #[serde(default = "default_alpha")]
+    pub alpha: f32,
+    /// Colors picked at random for each particle
+    pub colors: Vec<Rgb>,
+}
+
+fn default_direction() -> f32 {
+    180.0
+}
+
+fn default_alpha() -> f32 {
+    0.8
+}
+
+impl TrailStyle {
+    /// Returns the initial drift velocity of a particle, from random
+    /// fractions `speed` and `spread` in `0.0..1.0`.
+    pub fn velocity(&self, speed: f32, spread: f32) -> Vec2 {
+        let speed = self.speed.0 + (self.speed.1 - self.speed.0) * speed;
+        let angle = (self.direction + (spread - 0.5) * self.spread).to_radians();
+        Vec2::from_angle(angle) * speed
+    }
+}
+
+/// Returns the trail style with the given name, if it is loaded.
+pub fn find_trail<'a>(styles: &'a Assets<TrailStyle>, name: &str) -> Option<&'a TrailStyle> {
+    styles
+        .iter()
+        .map(|(_, style)| style)
+        .find(|style| style.name == name)
+}
+
+/// Returns the names of all loaded trails, cheapest first.
+pub fn trail_names(styles: &Assets<TrailStyle>) -> Vec<String> {
+    let mut trails: Vec<(u32, String)> = styles
+        .iter()
+        .map(|(_, style)| (style.cost, style.name.clone()))
+        .collect();
+    trails.sort();
+    trails.dedup();
+    trails.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Trail shown on the trail row of the settings screen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TrailChoice {
+    /// Name of the trail, `None` for no trail
+    pub name: Option<String>,
+    /// Price, if the trail isn't owned yet
+    pub cost: Option<u32>,
+}
+
+impl TrailChoice {
+    /// Describes a trail for a player with the given save.
+    pub fn new(name: Option<&str>, save: &SaveData, styles: &Assets<TrailStyle>) -> Self {
+        let cost = name
+            .filter(|name| !save.trails.contains(*name))
+            .and_then(|name| find_trail(styles, name))
+            .map(|style| style.cost);
+        Self {
+            name: name.map(str::to_string),
+            cost,
+        }
+    }
+
+    /// Returns the label of the settings row.
+    pub fn label(&self) -> String {
+        match (&self.name, self.cost) {
+            (None, _) => "Trail: < None >".to_string(),
+            (Some(name), None) => format!("Trail: < {name} >"),
+            (Some(name), Some(cost)) => format!("Trail: < {name} (buy: {cost} coins) >"),
+        }
+    }
+}
+
+/// Resource spreading a trail's particles evenly over frames.
+#[derive(Resource, Default)]
+pub struct TrailEmitter {
+    /// Fraction of a particle carried over from earlier frames
+    pub pending: f32,
+}
+
+impl TrailEmitter {
+    /// Returns how many particles to spawn this frame at `rate` per second.
+    pub fn take(&mut self, rate: f32, dt: f32) -> u32 {
+        self.pending += rate.max(0.0) * dt;
+        let count = self.pending.floor();
+        self.pending -= count;
+        count as u32
+    }
+}
+
+/// Handle keeping every trail in the trails folder loaded.
+#[derive(Resource)]
+pub struct TrailLibrary(#[allow(dead_code)] pub Handle<LoadedFolder>);
+
+/// Loads `*.trail.ron` files into `TrailStyle` assets.
+#[derive(Default, TypePath)]
+pub struct TrailStyleLoader;
+
+impl AssetLoader for TrailStyleLoader {
+    type Asset = TrailStyle;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["trail.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_trails_parse() {
+        for source in [
+            include_str!("../assets/trails/sparkles.trail.ron"),
+            include_str!("../assets/trails/bubbles.trail.ron"),
+            include_str!("../assets/trails/flames.trail.ron"),
+        ] {
+            let style = ron::from_str::<TrailStyle>(source);
+            assert!(style.is_ok_and(|style| !style.colors.is_empty() && style.rate > 0.0));
+        }
+    }
+
+    #[test]
+    fn emitter_carries_partial_particles_over() {
+        let mut emitter = TrailEmitter::default();
+        let counts: Vec<u32> = (0..4).map(|_| emitter.take(10.0, 0.25)).collect();
+        assert_eq!(counts, [2, 3, 2, 3]);
+        assert_eq!(emitter.take(-5.0, 1.0), 0);
+    }
+
+    #[test]
+    fn unowned_trails_show_their_price() {
+        let mut styles = Assets::<TrailStyle>::default();
+        let _ = styles.add(
+            ron::from_str::<TrailStyle>(include_str!("../assets/trails/bubbles.trail.ron"))
+                .unwrap(),
+        );
+        let mut save = SaveData::default();
+
+        let choice = TrailChoice::new(Some("Bubbles"), &save, &styles);
+        assert!(choice.cost.is_some());
+        assert!(choice.label().contains("coins"));
+
+        save.trails.insert("Bubbles".to_string());
+        let choice = TrailChoice::new(Some("Bubbles"), &save, &styles);
+        assert_eq!(choice.label(), "Trail: < Bubbles >");
+        assert_eq!(
+            TrailChoice::new(None, &save, &styles).label(),
+            "Trail: < None >"
+        );
+    }
+}
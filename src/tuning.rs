@@ -0,0 +1,382 @@
+//! Live tuning panel for the game configuration (behind the `tuning` feature).
+//!
+//! F10 shows a panel in the top-left corner listing every numeric and on/off
+//! `GameConfig` field. While it is open, Up/Down select a field and
+//! Left/Right nudge it (ten times as far with Shift) or toggle it; the change
+//! applies to the running game straight away. F12 writes the values to
+//! `assets/config/tuned.config.ron`, which is loaded instead of the shipped
+//! configuration from then on, so a balanced setup survives a restart without
+//! a recompile. The shipped file and its comments are never overwritten:
+//! copy the values worth keeping over by hand, and delete the tuned file to go
+//! back. The arrow keys are consumed while the panel is open.
+
+use std::fs;
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::config::{Difficulty, GameConfig, TUNED_CONFIG_PATH};
+use crate::hot_reload::asset_root;
+use crate::resources::GameViewport;
+
+/// Distance between the panel and the screen edges.
+const MARGIN: f32 = 8.0;
+
+/// Number of fields listed at once.
+const VISIBLE_ROWS: usize = 12;
+
+/// Plugin adding the tuning panel.
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TuningPanel>()
+            .add_systems(Startup, spawn_tuning_panel)
+            .add_systems(
+                PreUpdate,
+                handle_tuning_keys.after(bevy::input::InputSystems),
+            )
+            .add_systems(Update, update_tuning_panel);
+    }
+}
+
+/// State of the tuning panel.
+#[derive(Resource, Default)]
+struct TuningPanel {
+    /// Whether the panel is shown
+    open: bool,
+    /// Index of the selected field in `TUNABLES`
+    selected: usize,
+    /// Result of the last save, shown under the fields
+    status: String,
+}
+
+/// Marker for the tuning panel text.
+#[derive(Component)]
+struct TuningText;
+
+/// A configuration field borrowed for editing.
+enum TunableField<'a> {
+    Float(&'a mut f32),
+    Count(&'a mut u32),
+    Flag(&'a mut bool),
+}
+
+/// A configuration field listed on the panel.
+struct Tunable {
+    /// Path of the field in the configuration file
+    label: &'static str,
+    /// Change applied by one press of Left/Right
+    step: f32,
+    /// Borrows the field from the configuration
+    field: fn(&mut GameConfig) -> TunableField<'_>,
+}
+
+impl Tunable {
+    /// Returns the field's current value, formatted for the panel.
+    fn value(&self, config: &mut GameConfig) -> String {
+        match (self.field)(config) {
+            TunableField::Float(value) => format!("{value:.2}"),
+            TunableField::Count(value) => value.to_string(),
+            TunableField::Flag(value) => if *value { "on" } else { "off" }.to_string(),
+        }
+    }
+
+    /// Moves the field by `steps` steps; counts stop at zero and flags
+    /// toggle.
+    fn adjust(&self, config: &mut GameConfig, steps: i32) {
+        match (self.field)(config) {
+            TunableField::Float(value) => *value += self.step * steps as f32,
+            TunableField::Count(value) => {
+                *value = value.saturating_add_signed(self.step as i32 * steps);
+            }
+            TunableField::Flag(value) => *value = !*value,
+        }
+    }
+}
+
+macro_rules! tunable {
+    ($section:ident . $name:ident, Float, $step:expr) => {
+        Tunable {
+            label: concat!(stringify!($section), ".", stringify!($name)),
+            step: $step,
+            field: |config| TunableField::Float(&mut config.$section.$name),
+        }
+    };
+    ($section:ident . $name:ident, Count, $step:expr) => {
+        Tunable {
+            label: concat!(stringify!($section), ".", stringify!($name)),
+            step: $step,
+            field: |config| TunableField::Count(&mut config.$section.$name),
+        }
+    };
+    ($section:ident . $name:ident, Flag) => {
+        Tunable {
+            label: concat!(stringify!($section), ".", stringify!($name)),
+            step: 1.0,
+            field: |config| TunableField::Flag(&mut config.$section.$name),
+        }
+    };
+}
+
+/// Every numeric and on/off configuration field, in file order.
+///
+/// The milestone score list isn't a single number and has to be edited in
+/// the file.
+const TUNABLES: &[Tunable] = &[
+    tunable!(physics.gravity, Float, 10.0),
+    tunable!(physics.flap_strength, Float, 5.0),
+    tunable!(physics.max_tilt_up, Float, 0.05),
+    tunable!(physics.max_tilt_down, Float, 0.05),
+    tunable!(physics.tilt_speed, Float, 0.5),
+    tunable!(physics.rotated_hitbox, Flag),
+    tunable!(physics.bird_scale, Float, 0.1),
+    tunable!(physics.inverted_gravity, Flag),
+    tunable!(pipes.spawn_distance, Float, 10.0),
+    tunable!(pipes.gap_start_min, Float, 5.0),
+    tunable!(pipes.gap_start_max, Float, 5.0),
+    tunable!(pipes.gap_end, Float, 5.0),
+    tunable!(pipes.gap_scale_score, Count, 1.0),
+    tunable!(pipes.scroll_speed, Float, 5.0),
+    tunable!(pipes.random_pair_weight, Float, 0.1),
+    tunable!(pipes.tunnel_weight, Float, 0.05),
+    tunable!(pipes.tunnel_min_score, Count, 1.0),
+    tunable!(pipes.tunnel_length, Count, 1.0),
+    tunable!(pipes.tunnel_spacing, Float, 0.05),
+    tunable!(obstacles.min_score, Count, 1.0),
+    tunable!(obstacles.blade_chance, Float, 0.05),
+    tunable!(obstacles.blade_size, Float, 2.0),
+    tunable!(obstacles.blade_spin_speed, Float, 0.5),
+    tunable!(obstacles.gate_chance, Float, 0.05),
+    tunable!(obstacles.gate_min_opening, Float, 5.0),
+    tunable!(obstacles.gate_speed, Float, 0.1),
+    tunable!(obstacles.block_chance, Float, 0.05),
+    tunable!(obstacles.block_size, Float, 2.0),
+    tunable!(obstacles.block_bob_height, Float, 5.0),
+    tunable!(obstacles.block_bob_speed, Float, 0.1),
+    tunable!(hazards.min_score, Count, 1.0),
+    tunable!(hazards.min_interval, Float, 1.0),
+    tunable!(hazards.max_interval, Float, 1.0),
+    tunable!(hazards.warning_time, Float, 0.1),
+    tunable!(hazards.flock_weight, Float, 0.1),
+    tunable!(hazards.flock_size, Count, 1.0),
+    tunable!(hazards.flock_speed, Float, 10.0),
+    tunable!(hazards.flock_bird_size, Float, 1.0),
+    tunable!(hazards.crate_weight, Float, 0.1),
+    tunable!(hazards.crate_size, Float, 2.0),
+    tunable!(hazards.crate_gravity, Float, 25.0),
+    tunable!(hazards.wind_weight, Float, 0.1),
+    tunable!(hazards.wind_strength, Float, 25.0),
+    tunable!(hazards.wind_duration, Float, 0.1),
+    tunable!(boss.interval, Count, 1.0),
+    tunable!(boss.wall_width, Float, 10.0),
+    tunable!(boss.hole_size, Float, 5.0),
+    tunable!(boss.hole_travel, Float, 5.0),
+    tunable!(boss.hole_speed, Float, 0.1),
+    tunable!(effects.screen_shake_duration, Float, 0.05),
+    tunable!(effects.screen_shake_intensity, Float, 1.0),
+    tunable!(effects.screen_shake_frequency, Float, 1.0),
+    tunable!(effects.score_zoom, Float, 0.005),
+    tunable!(effects.score_zoom_duration, Float, 0.05),
+    tunable!(effects.death_zoom, Float, 0.01),
+    tunable!(effects.death_zoom_duration, Float, 0.1),
+    tunable!(effects.death_flash_duration, Float, 0.05),
+    tunable!(effects.death_flash_alpha, Float, 0.05),
+    tunable!(effects.score_flash_duration, Float, 0.05),
+    tunable!(effects.score_flash_alpha, Float, 0.05),
+    tunable!(effects.flap_squash_duration, Float, 0.05),
+    tunable!(effects.flap_squash_scale, Float, 0.05),
+    tunable!(effects.flap_stretch_scale, Float, 0.05),
+    tunable!(effects.score_pop_duration, Float, 0.05),
+    tunable!(effects.score_pop_scale, Float, 0.05),
+    tunable!(effects.score_popup_duration, Float, 0.05),
+    tunable!(effects.score_popup_rise, Float, 2.0),
+    tunable!(effects.flap_particle_count_min, Count, 1.0),
+    tunable!(effects.flap_particle_count_max, Count, 1.0),
+    tunable!(effects.flap_particle_lifetime, Float, 0.1),
+    tunable!(effects.death_particle_count, Count, 1.0),
+    tunable!(effects.death_particle_speed, Float, 10.0),
+    tunable!(effects.death_particle_lifetime, Float, 0.05),
+    tunable!(effects.vignette_intensity, Float, 0.05),
+    tunable!(effects.vignette_near_miss, Float, 0.05),
+    tunable!(effects.vignette_death, Float, 0.05),
+    tunable!(effects.vignette_pulse_duration, Float, 0.05),
+    tunable!(effects.near_miss_distance, Float, 1.0),
+    tunable!(effects.hide_bird, Flag),
+    tunable!(music.volume, Float, 0.05),
+    tunable!(music.percussion_score, Count, 1.0),
+    tunable!(music.lead_score, Count, 1.0),
+    tunable!(music.fade_time, Float, 0.1),
+    tunable!(milestones.banner_duration, Float, 0.1),
+    tunable!(fog.distance, Float, 10.0),
+    tunable!(fog.opacity, Float, 0.05),
+];
+
+/// Returns the indices of the rows to list so that `selected` stays in view.
+fn visible_rows(selected: usize, count: usize) -> Range<usize> {
+    let start = selected
+        .saturating_sub(VISIBLE_ROWS / 2)
+        .min(count.saturating_sub(VISIBLE_ROWS));
+    start..(start + VISIBLE_ROWS).min(count)
+}
+
+/// Writes the configuration to the tuned configuration file.
+///
+/// Refused under a difficulty preset, whose scaling would be written too.
+fn save_config(config: &GameConfig, difficulty: Difficulty) -> Result<(), String> {
+    if difficulty != Difficulty::Normal {
+        return Err("can't save while a --difficulty preset is applied".to_string());
+    }
+    let text = config.to_ron()?;
+    fs::write(asset_root().join(TUNED_CONFIG_PATH), text).map_err(|error| error.to_string())
+}
+
+/// Spawns the (hidden) tuning panel.
+fn spawn_tuning_panel(mut commands: Commands) {
+    commands.spawn((
+        Text2d::default(),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.6, 0.9, 1.0)),
+        Anchor::TOP_LEFT,
+        Transform::from_xyz(0.0, 0.0, 9.0),
+        Visibility::Hidden,
+        TuningText,
+    ));
+}
+
+/// Toggles the panel on F10 and, while it is open, edits and saves the
+/// configuration.
+fn handle_tuning_keys(
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut panel: ResMut<TuningPanel>,
+    mut config: ResMut<GameConfig>,
+    difficulty: Res<Difficulty>,
+) {
+    if keyboard_input.just_pressed(KeyCode::F10) {
+        panel.open = !panel.open;
+    }
+    if !panel.open {
+        return;
+    }
+
+    let count = TUNABLES.len();
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        panel.selected = (panel.selected + count - 1) % count;
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        panel.selected = (panel.selected + 1) % count;
+    }
+
+    let scale = if keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        10
+    } else {
+        1
+    };
+    let steps = if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        scale
+    } else if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        -scale
+    } else {
+        0
+    };
+    if steps != 0 {
+        TUNABLES[panel.selected].adjust(&mut config, steps);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F12) {
+        panel.status = match save_config(&config, *difficulty) {
+            Ok(()) => {
+                info!("Saved game config to {TUNED_CONFIG_PATH}");
+                format!("Saved to {TUNED_CONFIG_PATH}")
+            }
+            Err(error) => {
+                warn!("Failed to save game config: {error}");
+                format!("Not saved: {error}")
+            }
+        };
+    }
+
+    // The menus and settings shouldn't also react to the arrows
+    for key in [
+        KeyCode::ArrowUp,
+        KeyCode::ArrowDown,
+        KeyCode::ArrowLeft,
+        KeyCode::ArrowRight,
+    ] {
+        keyboard_input.clear_just_pressed(key);
+    }
+}
+
+/// Lists the fields around the selected one, in the top-left corner.
+fn update_tuning_panel(
+    panel: Res<TuningPanel>,
+    mut config: ResMut<GameConfig>,
+    viewport: Res<GameViewport>,
+    mut text_query: Query<(&mut Text2d, &mut Transform, &mut Visibility), With<TuningText>>,
+) {
+    for (mut text, mut transform, mut visibility) in text_query.iter_mut() {
+        *visibility = if panel.open {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if !panel.open {
+            continue;
+        }
+        transform.translation.x = -viewport.half_width() + MARGIN;
+        transform.translation.y = viewport.half_height() - MARGIN;
+
+        // Reading through `&mut` mustn't count as a change
+        let config = config.bypass_change_detection();
+        let mut lines = vec![format!(
+            "Tuning {}/{}  Up/Down select, Left/Right adjust, F12 save",
+            panel.selected + 1,
+            TUNABLES.len()
+        )];
+        for index in visible_rows(panel.selected, TUNABLES.len()) {
+            let tunable = &TUNABLES[index];
+            let cursor = if index == panel.selected { ">" } else { " " };
+            lines.push(format!(
+                "{cursor} {}: {}",
+                tunable.label,
+                tunable.value(config)
+            ));
+        }
+        if !panel.status.is_empty() {
+            lines.push(panel.status.clone());
+        }
+        text.0 = lines.join("\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjusting_moves_by_steps() {
+        let mut config = GameConfig::default();
+        let gravity = config.physics.gravity;
+        TUNABLES[0].adjust(&mut config, -2);
+        assert_eq!(config.physics.gravity, gravity - 20.0);
+
+        let boss = TUNABLES
+            .iter()
+            .find(|tunable| tunable.label == "boss.interval")
+            .unwrap();
+        boss.adjust(&mut config, -1000);
+        assert_eq!(config.boss.interval, 0);
+    }
+
+    #[test]
+    fn visible_rows_follow_the_selection() {
+        assert_eq!(visible_rows(0, 40), 0..VISIBLE_ROWS);
+        assert_eq!(visible_rows(20, 40), 14..26);
+        assert_eq!(visible_rows(39, 40), 28..40);
+        assert_eq!(visible_rows(3, 5), 0..5);
+    }
+}
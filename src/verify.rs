@@ -0,0 +1,300 @@
+//! Headless replay verification.
+//!
+//! Leaderboard submissions carry the run's replay: its seed, difficulty and
+//! input trace (the game time of every frame and the frames the bird flapped
+//! on). Verifying a replay plays it again in a headless game, feeding the
+//! recorded frame times and flap presses through the real systems, and checks
+//! that the bird crashes on the last frame with the claimed score. Forging a
+//! score then means flying the run for real.
+//!
+//! The verifying process never writes to the save directory.
+//!
+//! The same machinery plays recorded runs back in the window (`--play`),
+//! carrying on with the game once the trace ends instead of exiting.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::*;
+
+use crate::replay::Replay;
+
+/// Longest frame a trace may hold, in nanoseconds: the game clock never
+/// advances more than a quarter of a second per frame.
+const MAX_FRAME_NANOS: u32 = 250_000_000;
+
+/// Most frames a trace may hold: an hour at 240 frames per second.
+const MAX_TRACE_FRAMES: usize = 3600 * 240;
+
+/// Longest run a trace may hold, in nanoseconds: an hour.
+const MAX_TRACE_NANOS: u64 = 3600 * 1_000_000_000;
+
+/// Why a replay failed verification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The replay can't be re-simulated as recorded
+    Malformed(String),
+    /// The game couldn't be started to re-simulate it
+    Unavailable(String),
+    /// The re-simulated bird crashed before the end of the trace
+    CrashedEarly { frame: usize, frames: usize },
+    /// The re-simulated bird was still flying at the end of the trace
+    NoCrash,
+    /// The re-simulated run scored differently
+    ScoreMismatch { claimed: u32, simulated: u32 },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed(reason) => write!(f, "malformed replay: {reason}"),
+            Self::Unavailable(reason) => write!(f, "couldn't re-simulate the run: {reason}"),
+            Self::CrashedEarly { frame, frames } => {
+                write!(f, "the bird crashed on frame {} of {frames}", frame + 1)
+            }
+            Self::NoCrash => write!(f, "the bird was still flying when the trace ended"),
+            Self::ScoreMismatch { claimed, simulated } => {
+                write!(f, "claimed {claimed} points but the run scored {simulated}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Checks that a replay's trace can be re-simulated, before starting a game
+/// to do so.
+///
+/// Traces are capped in frames and in simulated time, and every frame must
+/// advance the clock, so a forged trace can't keep the verifier busy for
+/// longer than a real run would.
+pub fn check_trace(replay: &Replay) -> Result<(), VerifyError> {
+    let malformed = |reason: &str| Err(VerifyError::Malformed(reason.to_string()));
+
+    if replay.frames.is_empty() {
+        return malformed("no frames in the input trace");
+    }
+    if replay.frames.len() > MAX_TRACE_FRAMES {
+        return malformed("the input trace has too many frames");
+    }
+    if replay.frames.contains(&0) {
+        return malformed("a frame doesn't advance the clock");
+    }
+    if replay.frames.iter().any(|nanos| *nanos > MAX_FRAME_NANOS) {
+        return malformed("a frame is longer than the game allows");
+    }
+    if replay
+        .frames
+        .iter()
+        .map(|nanos| u64::from(*nanos))
+        .sum::<u64>()
+        > MAX_TRACE_NANOS
+    {
+        return malformed("the run is longer than the game allows");
+    }
+    if replay.flap_frames.len() != replay.flaps.len() {
+        return malformed("flap times and flap frames don't match");
+    }
+    if replay.flap_frames.windows(2).any(|pair| pair[0] > pair[1]) {
+        return malformed("flap frames are out of order");
+    }
+    if replay
+        .flap_frames
+        .last()
+        .is_some_and(|frame| *frame as usize >= replay.frames.len())
+    {
+        return malformed("a flap is past the last frame");
+    }
+    Ok(())
+}
+
+/// Resource driving the re-simulation of a replay.
+#[derive(Resource)]
+pub struct ReplayVerifier {
+    /// The run being verified
+    pub replay: Replay,
+    /// Whether the assets are loaded and the next frame starts the run
+    pub ready: bool,
+    /// Whether the run has started
+    pub started: bool,
+    /// Index of the run's current frame in the trace
+    pub frame: usize,
+    /// Index of the next flap in the trace
+    pub next_flap: usize,
+    /// Whether the run is played back to watch rather than verified, so the
+    /// game carries on once it ends
+    pub playback: bool,
+    /// Whether the run has ended
+    pub finished: bool,
+    /// Outcome, shared with the caller once the app exits
+    pub verdict: Arc<Mutex<Option<Result<u32, VerifyError>>>>,
+}
+
+impl ReplayVerifier {
+    /// Creates a verifier reporting its outcome into `verdict`.
+    pub fn new(replay: Replay, verdict: Arc<Mutex<Option<Result<u32, VerifyError>>>>) -> Self {
+        Self {
+            replay,
+            ready: false,
+            started: false,
+            frame: 0,
+            next_flap: 0,
+            playback: false,
+            finished: false,
+            verdict,
+        }
+    }
+
+    /// Creates a verifier playing a run back to watch (`--play`).
+    pub fn playback(replay: Replay) -> Self {
+        Self {
+            playback: true,
+            ..Self::new(replay, Arc::default())
+        }
+    }
+
+    /// Returns whether the bird flaps on the current frame, moving past the flap.
+    pub fn take_flap(&mut self) -> bool {
+        let flaps = self
+            .replay
+            .flap_frames
+            .get(self.next_flap..)
+            .unwrap_or_default()
+            .iter()
+            .take_while(|frame| **frame as usize == self.frame)
+            .count();
+        self.next_flap += flaps;
+        flaps > 0
+    }
+
+    /// Judges the run once the bird crashed on the current frame with `score`.
+    pub fn judge(&self, score: u32) -> Result<u32, VerifyError> {
+        let frames = self.replay.frames.len();
+        if self.frame + 1 < frames {
+            return Err(VerifyError::CrashedEarly {
+                frame: self.frame,
+                frames,
+            });
+        }
+        if score != self.replay.score {
+            return Err(VerifyError::ScoreMismatch {
+                claimed: self.replay.score,
+                simulated: score,
+            });
+        }
+        Ok(score)
+    }
+
+    /// Records the outcome for the caller.
+    pub fn conclude(&self, verdict: Result<u32, VerifyError>) {
+        if let Ok(mut slot) = self.verdict.lock() {
+            slot.get_or_insert(verdict);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay() -> Replay {
+        Replay {
+            seed: 42,
+            score: 2,
+            flaps: vec![0.0, 0.5, 0.5],
+            duration: 1.0,
+            frames: vec![100_000_000; 10],
+            flap_frames: vec![0, 5, 5],
+            ..default()
+        }
+    }
+
+    #[test]
+    fn traces_are_checked_before_simulating() {
+        assert_eq!(check_trace(&replay()), Ok(()));
+
+        let broken = [
+            Replay {
+                frames: Vec::new(),
+                ..replay()
+            },
+            Replay {
+                frames: vec![MAX_FRAME_NANOS + 1; 10],
+                ..replay()
+            },
+            Replay {
+                frames: vec![0; 10],
+                ..replay()
+            },
+            Replay {
+                frames: vec![1; MAX_TRACE_FRAMES + 1],
+                ..replay()
+            },
+            Replay {
+                frames: vec![
+                    MAX_FRAME_NANOS;
+                    (MAX_TRACE_NANOS / u64::from(MAX_FRAME_NANOS)) as usize + 1
+                ],
+                ..replay()
+            },
+            Replay {
+                flap_frames: vec![0, 5],
+                ..replay()
+            },
+            Replay {
+                flap_frames: vec![5, 0, 5],
+                ..replay()
+            },
+            Replay {
+                flap_frames: vec![0, 5, 10],
+                ..replay()
+            },
+        ];
+        for replay in broken {
+            assert!(matches!(
+                check_trace(&replay),
+                Err(VerifyError::Malformed(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn flaps_are_pressed_on_their_frames() {
+        let mut verifier = ReplayVerifier::new(replay(), Arc::default());
+        let pressed: Vec<bool> = (0..10)
+            .map(|frame| {
+                verifier.frame = frame;
+                verifier.take_flap()
+            })
+            .collect();
+        assert_eq!(
+            pressed,
+            [
+                true, false, false, false, false, true, false, false, false, false
+            ]
+        );
+        assert_eq!(verifier.next_flap, 3);
+    }
+
+    #[test]
+    fn runs_must_crash_on_the_last_frame_with_the_claimed_score() {
+        let mut verifier = ReplayVerifier::new(replay(), Arc::default());
+        verifier.frame = 9;
+        assert_eq!(verifier.judge(2), Ok(2));
+        assert_eq!(
+            verifier.judge(3),
+            Err(VerifyError::ScoreMismatch {
+                claimed: 2,
+                simulated: 3
+            })
+        );
+        verifier.frame = 4;
+        assert_eq!(
+            verifier.judge(2),
+            Err(VerifyError::CrashedEarly {
+                frame: 4,
+                frames: 10
+            })
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Vignette overlay.
+//!
+//! The vignette darkens the corners of the screen, drawn with
+//! `VignetteMaterial` on a quad that follows the camera rig so it always
+//! frames the view, even while the camera shakes or zooms. It rests at the
+//! configured intensity and pulses darker for a moment on near misses and
+//! death; an intensity of zero hides it.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{AsBindGroup, ShaderType};
+use bevy::shader::ShaderRef;
+use bevy::sprite_render::{AlphaMode2d, Material2d};
+
+/// Shader drawing the vignette.
+const VIGNETTE_SHADER_PATH: &str = "shaders/vignette.wgsl";
+
+/// Distance from the center of the screen where the darkening starts, and
+/// where it is full, with 1.0 at the middle of each edge.
+const VIGNETTE_INNER: f32 = 0.55;
+const VIGNETTE_OUTER: f32 = 1.45;
+
+/// Vignette state uploaded to the shader.
+#[derive(ShaderType, Clone, Debug)]
+pub struct VignetteUniform {
+    /// Color (rgb) and intensity (a) of the corners
+    pub color: LinearRgba,
+    /// Distance from the center where the darkening starts
+    pub inner: f32,
+    /// Distance from the center where the darkening is full
+    pub outer: f32,
+}
+
+impl Default for VignetteUniform {
+    fn default() -> Self {
+        Self {
+            color: LinearRgba::NONE,
+            inner: VIGNETTE_INNER,
+            outer: VIGNETTE_OUTER,
+        }
+    }
+}
+
+/// Material of the vignette overlay.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug, Default)]
+pub struct VignetteMaterial {
+    #[uniform(0)]
+    pub vignette: VignetteUniform,
+}
+
+impl Material2d for VignetteMaterial {
+    fn fragment_shader() -> ShaderRef {
+        VIGNETTE_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        AlphaMode2d::Blend
+    }
+}
+
+/// Returns the vignette intensity from its resting intensity and the current
+/// pulse, capped at fully dark corners.
+pub fn vignette_intensity(resting: f32, pulse: f32) -> f32 {
+    (resting + pulse).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pulses_darken_the_resting_vignette() {
+        assert_eq!(vignette_intensity(0.25, 0.0), 0.25);
+        assert_eq!(vignette_intensity(0.25, 0.2), 0.45);
+        assert_eq!(vignette_intensity(0.8, 0.45), 1.0);
+        assert_eq!(vignette_intensity(0.0, 0.0), 0.0);
+    }
+}